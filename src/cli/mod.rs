@@ -0,0 +1,1771 @@
+// src/cli/mod.rs
+
+mod shell;
+
+use crate::db::backup::{self, Backups};
+use crate::db::compaction::{self, CompactionMode};
+use crate::db::connection::{self, SqlitePool};
+use crate::db::maintenance;
+use crate::db::storage::{self, SqliteStorage, ShellStorage};
+use crate::db::workspace::{self, Workspaces};
+use crate::models::alert::AlertSettings;
+use crate::models::commissioning;
+use crate::models::compliance::ComplianceControl;
+use crate::models::csv_mapping::{CsvMapping, MergePolicy};
+use crate::models::data_source::{DataSource, RunStatus};
+use crate::models::import_run::ImportReport;
+use crate::models::matrix::MatrixColumns;
+use crate::models::risk::RiskBand;
+use crate::models::role::Role;
+use crate::models::ticket::{TicketSettings, TicketSystem};
+use crate::models::trash::DeletedItem;
+use crate::repositories::access;
+use crate::repositories::alias_repo::AliasRepository;
+use crate::repositories::commissioning_repo::CommissioningRepository;
+use crate::repositories::compliance_repo::ComplianceRepository;
+use crate::repositories::data_source_repo::DataSourceRepository;
+use crate::models::vulnerability::{LockedField, TriageStatus};
+use crate::reports::{changelog, compliance, diff, inventory, matrix, risk_acceptance, share, Layout};
+use crate::repositories::import_run_repo::ImportRunRepository;
+use crate::repositories::interchange_repo::InterchangeRepository;
+use crate::repositories::robot_repo::RobotRepository;
+use crate::repositories::settings_repo::SettingsRepository;
+use crate::repositories::snapshot_repo::SnapshotRepository;
+use crate::repositories::software_repo::SoftwareRepository;
+use crate::repositories::statistics_repo::StatisticsRepository;
+use crate::repositories::sync_delta_repo::SyncDeltaRepository;
+use crate::repositories::trash_repo::TrashRepository;
+use crate::repositories::vulnerability_repo::{QuickFilter, SortOrder, VulnerabilityFilter, VulnerabilityRepository};
+use crate::utils::alerts;
+use crate::utils::data_sources;
+use crate::utils::deep_link;
+use crate::utils::http;
+use crate::utils::ticketing;
+use crate::utils::csv_importer::{import_vulnerabilities_from_csv, import_vulnerabilities_from_xlsx};
+use crate::utils::import_archive::ImportArchive;
+use crate::utils::epss::import_epss_scores;
+use crate::utils::ghsa::{import_ghsa_advisories, DEFAULT_ECOSYSTEMS};
+use crate::utils::kev::import_kev_catalog;
+use crate::utils::logger;
+use crate::utils::nvd_api::NvdApiClient;
+use crate::utils::nvd_feed::import_nvd_feeds;
+use crate::utils::offline_bundle;
+use crate::utils::rvd_import::import_rvd_advisories;
+use crate::utils::progress::ProgressReporter;
+use crate::utils::robot_import::import_robots;
+use crate::utils::time::{self, DisplayTimeZone};
+use crate::utils::update_check;
+use crate::utils::watch_folder;
+use anyhow::{anyhow, Context, Result};
+use chrono::{Local, NaiveDate, NaiveTime, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use log::{error, info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Robot Vulnerability Database. Runs the GUI when no command is given.
+#[derive(Debug, Parser)]
+#[command(name = "rvd", version, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+	#[command(subcommand)]
+	pub command: Option<Command>,
+	/// Open the GUI on this vulnerability, given as a CVE ID or an rvd://cve/ link
+	#[arg(long, value_name = "CVE", conflicts_with = "link")]
+	pub open: Option<String>,
+	/// An rvd://cve/CVE-YYYY-NNNN link, as passed on when such a link is clicked
+	#[arg(value_name = "LINK")]
+	pub link: Option<String>,
+	/// Workspace to open instead of the last one used
+	#[arg(long, global = true, value_name = "NAME")]
+	pub workspace: Option<String>,
+	/// Directory holding RVD's `database` directory, instead of the current directory
+	#[arg(long, global = true, value_name = "DIR")]
+	pub data_dir: Option<PathBuf>,
+}
+
+impl Cli {
+	/// Runs in the data directory given on the command line, if any. Must come before
+	/// anything reads the data directory, since its paths are relative.
+	pub fn enter_data_dir(&self) -> Result<()> {
+		if let Some(dir) = &self.data_dir {
+			std::env::set_current_dir(dir).with_context(|| format!("Failed to enter the data directory {:?}", dir))?;
+		}
+		Ok(())
+	}
+
+	/// The workspace given on the command line, else the one used last
+	pub fn workspace(&self) -> String {
+		self.workspace.clone().unwrap_or_else(|| Workspaces::default().current())
+	}
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+	/// Interactive prompt to search, show and triage vulnerabilities and list robots,
+	/// with tab completion, for terminals where the GUI cannot run
+	Shell {
+		/// Work on a shared server database (postgres://...) instead of the workspace;
+		/// defaults to RVD_DATABASE_URL. Only the shell works on a server database.
+		#[arg(long, value_name = "URL")]
+		database_url: Option<String>,
+	},
+	/// Copy the workspace's vulnerabilities, triage state and robots to a shared
+	/// PostgreSQL server database, overwriting the entries already there. The copy is
+	/// one-way: triage done in the shell on the server does not come back to the workspace.
+	#[cfg(feature = "postgres")]
+	PushDatabase {
+		/// postgres://user@host/database; defaults to RVD_DATABASE_URL
+		#[arg(long, value_name = "URL")]
+		database_url: Option<String>,
+	},
+	/// Print all dashboard aggregates as a JSON document
+	Stats {
+		/// Write to this file instead of stdout
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+	},
+	/// Import a vulnerability CSV; columns follow the MITRE CVE list unless a saved preset is given
+	ImportCsv {
+		path: PathBuf,
+		/// Name of a mapping preset saved with save-csv-preset
+		#[arg(long)]
+		preset: Option<String>,
+		/// Merge policy for CVEs already recorded, instead of the preset's
+		#[arg(long = "merge", value_parser = parse_merge_policy)]
+		merge_policy: Option<MergePolicy>,
+	},
+	/// Import a vulnerability list from an Excel workbook (.xlsx), mapping columns as import-csv does
+	ImportXlsx {
+		path: PathBuf,
+		/// Sheet to read; the first sheet when omitted
+		#[arg(long)]
+		sheet: Option<String>,
+		/// Name of a mapping preset saved with save-csv-preset
+		#[arg(long)]
+		preset: Option<String>,
+		/// Merge policy for CVEs already recorded, instead of the preset's
+		#[arg(long = "merge", value_parser = parse_merge_policy)]
+		merge_policy: Option<MergePolicy>,
+	},
+	/// Save a CSV or spreadsheet column mapping as a named preset for recurring imports from one source system
+	SaveCsvPreset {
+		name: String,
+		#[command(flatten)]
+		mapping: MappingArgs,
+	},
+	/// List the saved CSV mapping presets
+	CsvPresets,
+	/// Import NVD JSON 2.0 feeds or saved API pages (.json or .json.gz, files or directories)
+	ImportNvd {
+		#[arg(required = true)]
+		paths: Vec<PathBuf>,
+	},
+	/// Import Alias Robotics RVD advisories (.yml, .yaml or .json, files or directories)
+	ImportRvd {
+		#[arg(required = true)]
+		paths: Vec<PathBuf>,
+	},
+	/// Import GitHub Security Advisories for the packages installed on robots. The
+	/// GitHub token is read from GITHUB_TOKEN.
+	ImportGhsa {
+		/// Package ecosystem to cover; repeat for several. Defaults to pip, rust, go and npm.
+		#[arg(long = "ecosystem", value_parser = ["actions", "composer", "erlang", "go", "maven", "npm", "nuget", "pip", "pub", "rubygems", "rust", "swift"])]
+		ecosystems: Vec<String>,
+	},
+	/// Record another feed's ID (GHSA, OSV, RVD...) for a vulnerability. An entry stored
+	/// under that ID is merged into it, keeping the richest description and metrics.
+	Alias {
+		/// CVE ID, or any ID the vulnerability is known by
+		id: String,
+		alias: String,
+		/// Feed the alias comes from, e.g. OSV
+		#[arg(long, default_value = "Manual")]
+		source: String,
+	},
+	/// Merge entries stored under an alias of another entry, such as advisories
+	/// imported before their CVE was assigned
+	Dedupe,
+	/// Change the description, severity, impact or mitigation of a vulnerability by hand. The
+	/// changed fields are locked so imports and NVD refreshes keep the edit; the editor is
+	/// recorded as RVD_USER, else the login name.
+	Edit {
+		cve: String,
+		#[arg(long)]
+		description: Option<String>,
+		#[arg(long)]
+		severity: Option<String>,
+		#[arg(long)]
+		impact: Option<String>,
+		#[arg(long)]
+		mitigation: Option<String>,
+	},
+	/// Let automatic updates change a field edited by hand again
+	Unlock {
+		cve: String,
+		#[arg(value_parser = parse_locked_field)]
+		field: LockedField,
+	},
+	/// Flag CVEs listed in the CISA Known Exploited Vulnerabilities catalog
+	/// (known_exploited_vulnerabilities.json)
+	ImportKev {
+		path: PathBuf,
+	},
+	/// Store FIRST EPSS exploitation probabilities (epss_scores-YYYY-MM-DD.csv[.gz])
+	/// and rescore the fleet
+	ImportEpss {
+		path: PathBuf,
+	},
+	/// List robots by risk score, riskiest first, after rescoring them, with the fleet
+	/// risk index. Run daily to build up the index history and the snapshots compared by
+	/// diff-report.
+	RiskScores,
+	/// Show or change the items of the security commissioning checklist every robot goes
+	/// through, e.g. "Default passwords changed". Open items raise a robot's risk score.
+	ChecklistItems {
+		#[arg(long)]
+		add: Vec<String>,
+		#[arg(long)]
+		remove: Vec<String>,
+	},
+	/// Show a robot's commissioning checklist, given by robot name, or tick items off
+	/// (--done) and open them again (--undo)
+	Checklist {
+		robot: String,
+		#[arg(long)]
+		done: Vec<String>,
+		#[arg(long)]
+		undo: Vec<String>,
+	},
+	/// List robots whose software inventory was not refreshed within the maximum age,
+	/// oldest first; their vulnerability matches may be out of date
+	StaleInventories,
+	/// Record that a robot's installed software, given by robot name, was checked and is
+	/// still current
+	ConfirmInventory {
+		robot: String,
+	},
+	/// Show or change after how many days without an import, edit or confirmation a
+	/// robot's inventory is flagged as stale and alerted about
+	InventoryMaxAge {
+		days: Option<u32>,
+	},
+	/// Import a robot inventory from CSV or JSON (name, manufacturer, model, software,
+	/// optionally specifications, operational_note and criticality). Robots already
+	/// recorded under the same name and manufacturer are updated.
+	ImportRobots {
+		path: PathBuf,
+	},
+	/// Export robots, software, correlations and assessments in the RVD interchange format
+	ExportFleet {
+		/// Write to this file instead of stdout
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+	},
+	/// Merge an RVD interchange document exported from another instance
+	ImportFleet {
+		path: PathBuf,
+	},
+	/// Export every vulnerability with its triage and the sync state of the data sources
+	/// into a compressed bundle signed with the key in RVD_BUNDLE_KEY, to carry to
+	/// deployments without internet access
+	ExportBundle {
+		output: PathBuf,
+	},
+	/// Verify an offline bundle with the key in RVD_BUNDLE_KEY and merge it, keeping
+	/// whichever copy of each vulnerability and triage was modified last
+	ImportBundle {
+		path: PathBuf,
+	},
+	/// Report accepted risks and false positives with justification, approver and expiry
+	RiskReport {
+		/// csv for spreadsheets, html to print or save as PDF from a browser, share for a
+		/// read-only page to send to people without RVD
+		#[arg(long, value_enum, default_value_t = ReportFormat::Csv)]
+		format: ReportFormat,
+		/// Write to this file instead of stdout
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+	},
+	/// List the compliance controls (IEC 62443-3-3, ISO/IEC 27001 Annex A...) vulnerabilities
+	/// can be mapped to, or add one
+	ComplianceControls {
+		#[arg(long, requires_all = ["code", "title"])]
+		standard: Option<String>,
+		/// e.g. "SR 5.1"
+		#[arg(long, requires = "standard")]
+		code: Option<String>,
+		#[arg(long, requires = "standard")]
+		title: Option<String>,
+	},
+	/// Show the compliance controls a vulnerability, given by CVE ID, is mapped to, or map
+	/// it to controls (--link) and remove mappings (--unlink). Controls are given by code,
+	/// e.g. "SR 5.1", or by standard and code where codes are ambiguous.
+	MapControls {
+		cve: String,
+		#[arg(long)]
+		link: Vec<String>,
+		#[arg(long)]
+		unlink: Vec<String>,
+	},
+	/// Report per compliance control the findings on the fleet mapped to it and how many
+	/// are still open
+	ComplianceReport {
+		/// csv for spreadsheets, html to print or save as PDF from a browser, share for a
+		/// read-only page to send to people without RVD
+		#[arg(long, value_enum, default_value_t = ReportFormat::Csv)]
+		format: ReportFormat,
+		/// Write to this file instead of stdout
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+	},
+	/// List the software installed on each robot with its license and open vulnerabilities
+	InventoryReport {
+		/// csv for spreadsheets, html to print or save as PDF from a browser, share for a
+		/// read-only page to send to people without RVD
+		#[arg(long, value_enum, default_value_t = ReportFormat::Csv)]
+		format: ReportFormat,
+		/// Write to this file instead of stdout
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+	},
+	/// Export CVEs against the deployed software versions or robots, each cell marking
+	/// the CVE as affected, fixed or unknown there
+	AffectedMatrix {
+		/// Column per software version installed on a robot, or per robot
+		#[arg(long, value_enum, default_value_t = MatrixBy::Versions)]
+		by: MatrixBy,
+		/// xlsx writes an Excel workbook and needs --output
+		#[arg(long, value_enum, default_value_t = MatrixFormat::Csv)]
+		format: MatrixFormat,
+		/// Write to this file instead of stdout
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+	},
+	/// Compare two daily fleet snapshots: CVEs opened and closed in between and the risk
+	/// change of each robot. A snapshot is saved each day risk scores are refreshed.
+	DiffReport {
+		/// Date of the earlier snapshot (YYYY-MM-DD); the last one on or before it is used
+		#[arg(long)]
+		from: NaiveDate,
+		/// Date of the later snapshot; the current state when omitted
+		#[arg(long)]
+		to: Option<NaiveDate>,
+		/// csv for spreadsheets, html to print or save as PDF from a browser, share for a
+		/// read-only page to send to people without RVD
+		#[arg(long, value_enum, default_value_t = ReportFormat::Csv)]
+		format: ReportFormat,
+		/// Write to this file instead of stdout
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+	},
+	/// List the days with a saved fleet snapshot
+	Snapshots,
+	/// Changelog of the data source syncs, newest first: the vulnerabilities each added,
+	/// the severities it changed and the robots it newly exposed
+	Changelog {
+		/// Only syncs started on or after this date (YYYY-MM-DD)
+		#[arg(long)]
+		since: Option<NaiveDate>,
+		/// csv for spreadsheets, html to print or save as PDF from a browser, share for a
+		/// read-only page to send to people without RVD
+		#[arg(long, value_enum, default_value_t = ReportFormat::Csv)]
+		format: ReportFormat,
+		/// Write to this file instead of stdout
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+	},
+	/// Write the vulnerabilities matching a filter as a self-contained, read-only HTML
+	/// page to send to people without RVD
+	ShareVulnerabilities {
+		/// Matched against CVE IDs, aliases, descriptions and references
+		#[arg(long, default_value = "")]
+		search: String,
+		#[arg(long, value_parser = ["critical", "high", "medium", "low"])]
+		severity: Option<String>,
+		/// Triage status, e.g. open or accepted-risk
+		#[arg(long, value_parser = parse_triage_status)]
+		status: Option<TriageStatus>,
+		/// Only vulnerabilities CISA lists as known exploited
+		#[arg(long)]
+		known_exploited: bool,
+		/// Write to this file instead of stdout
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+	},
+	/// Email alerts when robots become exposed or a tracked CVE changes severity.
+	/// The SMTP password is read from RVD_SMTP_PASSWORD.
+	ConfigureAlerts {
+		#[arg(long)]
+		smtp_host: String,
+		#[arg(long, default_value_t = 587)]
+		smtp_port: u16,
+		#[arg(long)]
+		smtp_username: Option<String>,
+		/// Sender address, e.g. "RVD <rvd@example.com>"
+		#[arg(long)]
+		from: String,
+		/// Recipient address; repeat for several
+		#[arg(long = "to", required = true)]
+		recipients: Vec<String>,
+		/// Send one digest a day instead of an email per alert
+		#[arg(long)]
+		digest: bool,
+	},
+	/// Stop queueing and sending email alerts
+	DisableAlerts,
+	/// Raise remediation tickets in Jira or a generic REST tracker and sync their status
+	/// back into the triage status every hour while the GUI runs. The API token is read
+	/// from RVD_TICKET_TOKEN.
+	ConfigureTickets {
+		/// jira or generic
+		#[arg(long, value_parser = parse_ticket_system)]
+		system: TicketSystem,
+		/// Base URL of the tracker, e.g. https://example.atlassian.net
+		#[arg(long)]
+		url: String,
+		/// Project key tickets are raised in, e.g. SEC
+		#[arg(long)]
+		project: String,
+		#[arg(long, default_value = "Bug")]
+		issue_type: String,
+		/// Account the token belongs to; without one the token is sent as a bearer token
+		#[arg(long)]
+		username: Option<String>,
+		/// Tracker status mapped to a triage status, e.g. "Ready for QA=in-progress";
+		/// repeat for several
+		#[arg(long = "map", value_parser = parse_status_mapping)]
+		status_map: Vec<(String, TriageStatus)>,
+	},
+	/// Stop raising and syncing remediation tickets; raised tickets stay linked
+	DisableTickets,
+	/// Raise a remediation ticket for a vulnerability
+	CreateTicket {
+		/// CVE ID or alias
+		cve: String,
+	},
+	/// Sync the status of the raised tickets into the triage status now
+	SyncTickets,
+	/// Send queued alerts now, including a digest that is not due yet
+	SendAlerts,
+	/// Show or change what the GUI does on startup when the database is worth compacting
+	/// (prompt, auto or off)
+	Compaction {
+		#[arg(value_parser = parse_compaction_mode)]
+		mode: Option<CompactionMode>,
+	},
+	/// Reclaim free pages and truncate the write-ahead log now
+	Compact,
+	/// Write a compressed snapshot of the workspace database to database/backups/<workspace>
+	Backup,
+	/// List the backups of the workspace, newest first
+	Backups,
+	/// Replace the workspace database with a backup (.db.gz or .db). The current state is
+	/// backed up first; close the GUI before restoring.
+	Restore {
+		path: PathBuf,
+	},
+	/// Show or change the automatic daily backup taken while the GUI runs and how many
+	/// backups are kept
+	BackupSchedule {
+		#[arg(long, conflicts_with = "disable")]
+		enable: bool,
+		#[arg(long)]
+		disable: bool,
+		/// Number of backups to keep; the oldest are deleted after each backup. The copies
+		/// taken before a restore are not counted and are never deleted automatically.
+		#[arg(long)]
+		keep: Option<usize>,
+	},
+	/// Check the integrity of the workspace database, refresh its query statistics and
+	/// release free pages; fails when the integrity check finds problems
+	Maintenance,
+	/// Show or change the weekly maintenance run while the GUI is open, with the outcome
+	/// of the last run
+	MaintenanceSchedule {
+		#[arg(long, conflicts_with = "disable")]
+		enable: bool,
+		#[arg(long)]
+		disable: bool,
+	},
+	/// List the deleted robots and vulnerabilities that can still be restored. They are
+	/// purged 30 days after deletion.
+	RecentlyDeleted,
+	/// Restore a deleted robot or vulnerability, given by robot name or CVE ID
+	Undelete {
+		name: String,
+	},
+	/// List the vulnerability list and robot inventory imports, newest first, with what each changed
+	ImportRuns,
+	/// List the rows an import run given by its run ID skipped, with the reason for each
+	SkippedRows {
+		run_id: i64,
+	},
+	/// Revert an import given by its run ID: delete the vulnerabilities or robots it added
+	/// and set those it overwrote back to their previous values
+	RevertImport {
+		run_id: i64,
+	},
+	/// Show or change how many days copies of imported files are kept before they are
+	/// compressed into the import archive
+	ImportRetention {
+		days: Option<u32>,
+	},
+	/// Compress kept import files older than the retention period now
+	ArchiveImports,
+	/// Show or change the folder whose dropped CSV, JSON and YAML files are imported
+	/// automatically; the GUI watches it from its next start
+	WatchFolder {
+		path: Option<PathBuf>,
+		/// Stop watching a folder
+		#[arg(long, conflicts_with = "path")]
+		off: bool,
+	},
+	/// Import the files dropped into the watch folder until stopped with Ctrl+C
+	Watch {
+		/// Import the files already in the folder, then exit
+		#[arg(long)]
+		once: bool,
+	},
+	/// List the data sources the GUI syncs in the background, with their schedules and
+	/// last runs
+	DataSources,
+	/// Show or change a data source given by name; a running GUI picks up the change
+	/// within a minute
+	DataSource {
+		name: String,
+		#[arg(long, conflicts_with = "disable")]
+		enable: bool,
+		#[arg(long)]
+		disable: bool,
+		/// Minutes between syncs
+		#[arg(long)]
+		interval: Option<u32>,
+		/// Where the KEV catalog, EPSS scores, OSV API or NVD feed is fetched from
+		#[arg(long)]
+		url: Option<String>,
+	},
+	/// Add an NVD JSON feed (.json or .json.gz) at a URL as a data source, e.g. a mirror
+	/// on the internal network
+	AddDataSource {
+		name: String,
+		url: String,
+		/// Minutes between syncs
+		#[arg(long, default_value_t = 24 * 60)]
+		interval: u32,
+	},
+	/// Remove a data source added with add-data-source
+	RemoveDataSource {
+		name: String,
+	},
+	/// Run a data source given by name now, or every enabled one. NVD answers fetched
+	/// within RVD_NVD_CACHE_TTL_HOURS (24 by default) are reused from the cache. An NVD
+	/// API key in NVD_API_KEY raises the request quota from 5 to 50 per 30 seconds.
+	SyncSources {
+		name: Option<String>,
+		/// Fetch every CVE from the NVD again instead of using cached answers
+		#[arg(long)]
+		no_cache: bool,
+	},
+	/// Whether the NVD answered during the last enrichment run
+	NvdStatus,
+	/// Show or change the robotics terms (ROS, robot models...) searched daily in the NVD
+	/// to import new CVEs mentioning them, even without a software match
+	DiscoveryTerms {
+		#[arg(long)]
+		add: Vec<String>,
+		#[arg(long)]
+		remove: Vec<String>,
+	},
+	/// Search the NVD for the discovery terms now
+	DiscoverKeywords,
+	/// Show or change the log filter, e.g. "info,rvd_core::utils::nvd_api=debug".
+	/// RUST_LOG overrides it; RVD_LOG_FORMAT=json switches to JSON lines.
+	LogFilter {
+		directives: Option<String>,
+	},
+	/// Show or change the check for new RVD releases the GUI runs on startup and shows in
+	/// its About dialog; off by default. Checks right away when on.
+	UpdateCheck {
+		#[arg(long, conflicts_with = "disable")]
+		enable: bool,
+		#[arg(long)]
+		disable: bool,
+		/// Release endpoint answering like the GitHub latest release API, e.g. an
+		/// internal mirror
+		#[arg(long)]
+		endpoint: Option<String>,
+	},
+	/// Show or change how requests to the NVD, advisory databases and trackers leave the
+	/// network: an HTTP(S) proxy and a PEM bundle of extra root certificates, e.g. of a
+	/// TLS-inspecting proxy. The proxy password is read from RVD_PROXY_PASSWORD.
+	Network {
+		/// Proxy URL, e.g. http://proxy.plant.local:3128; an empty value removes it
+		#[arg(long)]
+		proxy: Option<String>,
+		/// Account to authenticate to the proxy with; an empty value removes it
+		#[arg(long)]
+		proxy_user: Option<String>,
+		/// PEM file of root certificates trusted on top of the system's; an empty value
+		/// removes it
+		#[arg(long)]
+		ca_bundle: Option<String>,
+	},
+	/// Make clicked rvd://cve/ links open RVD: registers this executable as the desktop's
+	/// handler for them, for the current user (Linux and Windows). Links open RVD in the
+	/// directory this is run from, so run it where RVD keeps its `database` directory
+	RegisterLinks,
+	/// List the workspaces, marking the one opened by default
+	Workspaces,
+	/// Open this workspace by default from now on, creating it if needed
+	UseWorkspace {
+		name: String,
+	},
+	/// Show or change the role of this installation (admin or viewer); only an admin may change it.
+	/// The role guards against accidental edits and is not access control: there is no
+	/// authentication behind it, and anyone who can run RVD can change it with --recover.
+	Role {
+		#[arg(value_parser = parse_role)]
+		role: Option<Role>,
+		/// Change the role even when the current role is viewer, e.g. to set an
+		/// installation back to admin
+		#[arg(long, requires = "role")]
+		recover: bool,
+	},
+	/// Show or change the time zone timestamps are displayed in (local, utc or an offset like +02:00)
+	TimeZone {
+		#[arg(value_parser = parse_time_zone, allow_hyphen_values = true)]
+		zone: Option<DisplayTimeZone>,
+	},
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+	Csv,
+	Html,
+	Share,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum MatrixBy {
+	Versions,
+	Robots,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum MatrixFormat {
+	Csv,
+	Xlsx,
+}
+
+/// CSV column headers holding each vulnerability field
+#[derive(Debug, Args)]
+pub struct MappingArgs {
+	/// Source system the CSV is exported from
+	#[arg(long)]
+	source: String,
+	#[arg(long = "cve-column")]
+	cve_id: String,
+	#[arg(long = "severity-column")]
+	severity: String,
+	#[arg(long = "description-column")]
+	description: String,
+	#[arg(long = "references-column")]
+	references: Option<String>,
+	#[arg(long = "published-column")]
+	published_date: Option<String>,
+	#[arg(long = "impact-column")]
+	impact: Option<String>,
+	#[arg(long = "mitigation-column")]
+	mitigation: Option<String>,
+	/// How rows for CVEs already recorded are merged into them: overwrite,
+	/// newest-non-empty or keep-existing
+	#[arg(long = "merge", value_parser = parse_merge_policy, default_value = "newest-non-empty")]
+	merge_policy: MergePolicy,
+}
+
+impl From<MappingArgs> for CsvMapping {
+	fn from(args: MappingArgs) -> Self {
+		Self {
+			source: args.source,
+			cve_id: args.cve_id,
+			severity: args.severity,
+			description: args.description,
+			references: args.references,
+			published_date: args.published_date,
+			impact: args.impact,
+			mitigation: args.mitigation,
+			merge_policy: args.merge_policy,
+		}
+	}
+}
+
+fn parse_merge_policy(value: &str) -> Result<MergePolicy, String> {
+	MergePolicy::from_name(value)
+		.ok_or_else(|| format!("unknown merge policy '{}', expected overwrite, newest-non-empty or keep-existing", value))
+}
+
+fn parse_role(value: &str) -> Result<Role, String> {
+	Role::from_db(value).ok_or_else(|| format!("unknown role '{}', expected admin or viewer", value))
+}
+
+fn parse_triage_status(value: &str) -> Result<TriageStatus, String> {
+	shell::parse_status(value).map_err(|e| e.to_string())
+}
+
+fn parse_locked_field(value: &str) -> Result<LockedField, String> {
+	LockedField::from_db(&value.to_lowercase())
+		.ok_or_else(|| format!("unknown field '{}', expected description, severity, impact or mitigation", value))
+}
+
+fn parse_time_zone(value: &str) -> Result<DisplayTimeZone, String> {
+	DisplayTimeZone::from_setting(value)
+		.ok_or_else(|| format!("unknown time zone '{}', expected local, utc or an offset like +02:00", value))
+}
+
+fn parse_ticket_system(value: &str) -> Result<TicketSystem, String> {
+	match value.to_lowercase().as_str() {
+		"jira" => Ok(TicketSystem::Jira),
+		"generic" => Ok(TicketSystem::Generic),
+		_ => Err(format!("unknown ticketing system '{}', expected jira or generic", value)),
+	}
+}
+
+fn parse_status_mapping(value: &str) -> Result<(String, TriageStatus), String> {
+	let (ticket_status, triage) = value
+		.rsplit_once('=')
+		.ok_or_else(|| format!("expected TICKET STATUS=TRIAGE STATUS, got '{}'", value))?;
+	let triage = parse_triage_status(triage)?;
+	if triage.is_risk_decision() {
+		return Err(format!("{} needs a justification and approver, so tickets cannot set it", triage));
+	}
+	Ok((ticket_status.trim().to_string(), triage))
+}
+
+fn parse_compaction_mode(value: &str) -> Result<CompactionMode, String> {
+	CompactionMode::from_setting(value)
+		.ok_or_else(|| format!("unknown compaction mode '{}', expected prompt, auto or off", value))
+}
+
+/// Run a headless command against the database of a workspace
+pub async fn run(command: Command, workspace: &str) -> Result<()> {
+	let pool = Arc::new(
+		connection::establish_pool(workspace)
+			.with_context(|| format!("Failed to open workspace '{}'", workspace))?,
+	);
+
+	let settings = SettingsRepository::new(pool.clone());
+	access::set_current_role(settings.get_role().await?);
+	time::set_display_time_zone(settings.get_time_zone().await?);
+	http::set_network_settings(settings.get_network_settings().await?);
+	if let Some(directives) = settings.get_log_filter().await? {
+		if let Err(e) = logger::apply_configured_filter(&directives) {
+			warn!("Ignoring the configured log filter: {:#}", e);
+		}
+	}
+
+	match command {
+		Command::RegisterLinks => {
+			let location = deep_link::register_handler()?;
+			println!("rvd:// links now open RVD (registered in {})", location);
+			Ok(())
+		}
+		Command::Workspaces => {
+			let workspaces = Workspaces::default();
+			let current = workspaces.current();
+			for name in workspaces.list()? {
+				let marker = if name == current { "*" } else { " " };
+				println!("{} {}", marker, name);
+			}
+			Ok(())
+		}
+		Command::UseWorkspace { name } => {
+			workspace::validate_name(&name)?;
+			connection::establish_pool(&name)
+				.with_context(|| format!("Failed to open workspace '{}'", name))?;
+			Workspaces::default().set_current(&name)?;
+			println!("Workspace set to {}", name);
+			Ok(())
+		}
+		Command::Role { role: Some(role), recover } => {
+			if !recover {
+				access::require_write_access()
+					.map_err(|e| anyhow!("{}; pass --recover to change the role anyway", e))?;
+			}
+			settings.set_role(role).await?;
+			println!("Role set to {}", role);
+			Ok(())
+		}
+		Command::Role { role: None, .. } => {
+			println!("{}", access::current_role());
+			Ok(())
+		}
+		Command::TimeZone { zone: Some(zone) } => {
+			settings.set_time_zone(zone).await?;
+			println!("Time zone set to {}", zone);
+			Ok(())
+		}
+		Command::TimeZone { zone: None } => {
+			println!("{}", time::display_time_zone());
+			Ok(())
+		}
+		Command::ConfigureAlerts { smtp_host, smtp_port, smtp_username, from, recipients, digest } => {
+			settings.set_alert_settings(&AlertSettings {
+				smtp_host,
+				smtp_port,
+				smtp_username,
+				from,
+				recipients,
+				digest,
+			}).await?;
+			println!("Email alerts enabled");
+			Ok(())
+		}
+		Command::DisableAlerts => {
+			settings.clear_alert_settings().await?;
+			println!("Email alerts disabled");
+			Ok(())
+		}
+		Command::ConfigureTickets { system, url, project, issue_type, username, status_map } => {
+			settings.set_ticket_settings(&TicketSettings {
+				system,
+				base_url: url.trim().to_string(),
+				project: project.trim().to_string(),
+				issue_type,
+				username,
+				status_map: status_map.into_iter().collect(),
+			}).await?;
+			println!("Remediation tickets are raised in {} ({})", url.trim(), system);
+			Ok(())
+		}
+		Command::DisableTickets => {
+			settings.clear_ticket_settings().await?;
+			println!("Remediation tickets disabled");
+			Ok(())
+		}
+		Command::CreateTicket { cve } => {
+			let id = find_vulnerability_id(&VulnerabilityRepository::new(pool.clone()), &cve).await?;
+			let ticket = ticketing::create_ticket(pool, id).await?;
+			println!("Raised {}{}", ticket.key, ticket.url.map(|url| format!(": {}", url)).unwrap_or_default());
+			Ok(())
+		}
+		Command::SyncTickets => {
+			let changed = ticketing::sync(pool).await?;
+			println!("Synced tickets; {} triage statuses changed", changed);
+			Ok(())
+		}
+		Command::SendAlerts => {
+			let sent = alerts::dispatch(pool, true).await?;
+			println!("Sent {} alerts", sent);
+			Ok(())
+		}
+		Command::Compaction { mode: Some(mode) } => {
+			settings.set_compaction_mode(mode).await?;
+			println!("Compaction on startup set to {}", mode);
+			Ok(())
+		}
+		Command::Compaction { mode: None } => {
+			println!("{}", settings.get_compaction_mode().await?);
+			Ok(())
+		}
+		Command::ImportRetention { days: Some(days) } => {
+			settings.set_import_retention_days(days).await?;
+			println!("Imported files are archived after {} days", days);
+			Ok(())
+		}
+		Command::ImportRetention { days: None } => {
+			println!("{}", settings.get_import_retention_days().await?);
+			Ok(())
+		}
+		Command::DataSources => {
+			for source in DataSourceRepository::new(pool).get_sources().await? {
+				print_data_source(&source);
+			}
+			Ok(())
+		}
+		Command::DataSource { name, enable, disable, interval, url } => {
+			let repo = DataSourceRepository::new(pool);
+			let mut source = find_data_source(&repo, &name).await?;
+			if enable || disable || interval.is_some() || url.is_some() {
+				repo.update_source(
+					source.source_id,
+					if enable || disable { enable } else { source.enabled },
+					interval.unwrap_or(source.interval_minutes),
+					url.or(source.url),
+				).await?;
+				source = find_data_source(&repo, &name).await?;
+			}
+			print_data_source(&source);
+			Ok(())
+		}
+		Command::AddDataSource { name, url, interval } => {
+			DataSourceRepository::new(pool).add_feed(&name, &url, interval).await?;
+			println!("Added {}, synced every {} minutes", name.trim(), interval);
+			Ok(())
+		}
+		Command::RemoveDataSource { name } => {
+			let repo = DataSourceRepository::new(pool);
+			let source = find_data_source(&repo, &name).await?;
+			repo.remove_source(source.source_id).await?;
+			println!("Removed {}", source.name);
+			Ok(())
+		}
+		Command::SyncSources { name: Some(name), no_cache } => {
+			let source = find_data_source(&DataSourceRepository::new(pool.clone()), &name).await?;
+			let nvd_client = nvd_client(pool.clone(), no_cache)?;
+			let message = data_sources::sync_source(pool.clone(), &nvd_client, &source, cancel_on_ctrl_c()).await?;
+			println!("{}: {}", source.name, message);
+			send_alerts(pool).await;
+			Ok(())
+		}
+		Command::SyncSources { name: None, no_cache } => {
+			let nvd_client = nvd_client(pool.clone(), no_cache)?;
+			let ran = data_sources::sync_sources(pool.clone(), &nvd_client, None, cancel_on_ctrl_c()).await?;
+			for source in DataSourceRepository::new(pool.clone()).get_sources().await?.iter().filter(|s| s.enabled) {
+				print_data_source(source);
+			}
+			println!("Synced {} data sources", ran);
+			send_alerts(pool).await;
+			Ok(())
+		}
+		Command::ArchiveImports => {
+			let days = settings.get_import_retention_days().await?;
+			let summary = ImportArchive::for_workspace(workspace).archive_older_than(days, Utc::now())?;
+			println!(
+				"Archived {} import files ({} bytes compressed to {})",
+				summary.files, summary.bytes, summary.compressed_bytes
+			);
+			Ok(())
+		}
+		Command::WatchFolder { off: true, .. } => {
+			settings.clear_watch_folder().await?;
+			println!("No folder is watched");
+			Ok(())
+		}
+		Command::WatchFolder { path: Some(path), .. } => {
+			std::fs::create_dir_all(&path).with_context(|| format!("Failed to create {:?}", path))?;
+			let path = path.canonicalize().with_context(|| format!("Failed to resolve {:?}", path))?;
+			settings.set_watch_folder(&path).await?;
+			println!("Files dropped into {} are imported", path.display());
+			Ok(())
+		}
+		Command::WatchFolder { path: None, .. } => {
+			match settings.get_watch_folder().await? {
+				Some(path) => println!("{}", path.display()),
+				None => println!("No folder is watched"),
+			}
+			Ok(())
+		}
+		Command::Watch { once } => {
+			let dir = settings.get_watch_folder().await?
+				.context("No watch folder is set; set one with watch-folder <path>")?;
+			if once {
+				let ingested = watch_folder::ingest_pending(pool, &dir, cancel_on_ctrl_c()).await?;
+				for file in &ingested {
+					match &file.outcome {
+						Ok(summary) => println!("{}: {}", file.path.display(), summary),
+						Err(e) => println!("{}: failed: {}", file.path.display(), e),
+					}
+				}
+				println!("Processed {} files", ingested.len());
+				return Ok(());
+			}
+			tokio::select! {
+				result = watch_folder::watch(pool, dir, ProgressReporter::disabled()) => result,
+				_ = tokio::signal::ctrl_c() => Ok(()),
+			}
+		}
+		Command::NvdStatus => {
+			let health = settings.get_nvd_health().await?;
+			let last_success = health.last_success.map(time::format_local).unwrap_or_else(|| "never".to_string());
+			match health.failing_since {
+				Some(since) if health.is_outage() => println!(
+					"NVD unreachable since {} ({} failed requests), last successful contact: {}",
+					time::format_local(since), health.consecutive_failures, last_success
+				),
+				_ => println!("NVD reachable, last successful contact: {}", last_success),
+			}
+			Ok(())
+		}
+		Command::DiscoveryTerms { add, remove } => {
+			let mut discovery = settings.get_keyword_discovery().await?;
+			if !add.is_empty() || !remove.is_empty() {
+				access::require_write_access()?;
+				for term in &add {
+					discovery.add_term(term);
+				}
+				for term in &remove {
+					if !discovery.remove_term(term) {
+						warn!("{} is not a discovery term", term);
+					}
+				}
+				settings.set_keyword_discovery(&discovery).await?;
+			}
+			for term in &discovery.terms {
+				println!("{}", term);
+			}
+			if let Some(until) = discovery.searched_until {
+				println!("Searched CVEs published up to {}", until);
+			}
+			Ok(())
+		}
+		Command::DiscoverKeywords => {
+			let summary = NvdApiClient::new(pool.clone())?
+				.discover_keyword_matches(true, cancel_on_ctrl_c())
+				.await?;
+			println!("{}", summary);
+			for cve_id in &summary.inserted {
+				println!("  {}", cve_id);
+			}
+			send_alerts(pool).await;
+			Ok(())
+		}
+		Command::Compact => {
+			let conn = pool.get().context("Failed to get database connection")?;
+			println!("Before: {}", compaction::storage_stats(&conn)?);
+			println!("After: {}", compaction::compact(&conn, &ProgressReporter::disabled())?);
+			Ok(())
+		}
+		Command::Backup => {
+			let path = backup::back_up(pool, workspace).await?;
+			println!("Backed up to {}", path.display());
+			Ok(())
+		}
+		Command::Backups => {
+			for path in Backups::for_workspace(workspace).list()? {
+				let bytes = std::fs::metadata(&path)?.len();
+				println!("{} ({:.1} MB)", path.display(), bytes as f64 / (1024.0 * 1024.0));
+			}
+			Ok(())
+		}
+		Command::Restore { path } => {
+			access::require_write_access()?;
+			let mut conn = pool.get().context("Failed to get database connection")?;
+			let current = Backups::for_workspace(workspace).create(&conn, Utc::now(), Some("pre-restore"))?;
+			println!("Backed up the current state to {}", current.display());
+			backup::restore(&mut conn, &path)?;
+			println!("Restored {}", path.display());
+			Ok(())
+		}
+		Command::BackupSchedule { enable, disable, keep } => {
+			let mut policy = settings.get_backup_policy().await?;
+			if enable || disable || keep.is_some() {
+				if let Some(keep) = keep {
+					anyhow::ensure!(keep > 0, "At least one backup must be kept");
+					policy.keep = keep;
+				}
+				policy.daily = (policy.daily || enable) && !disable;
+				settings.set_backup_policy(&policy).await?;
+			}
+			println!(
+				"Daily backup {}, keeping {} backups",
+				if policy.daily { "on" } else { "off" },
+				policy.keep
+			);
+			if let Some(last) = policy.last_backup {
+				println!("Last backup: {}", time::format_local(last));
+			}
+			Ok(())
+		}
+		Command::Maintenance => {
+			let report = maintenance::run_maintenance(pool, ProgressReporter::disabled()).await?;
+			println!("{}", report);
+			anyhow::ensure!(report.is_intact(), "The database failed its integrity check");
+			Ok(())
+		}
+		Command::MaintenanceSchedule { enable, disable } => {
+			let mut policy = settings.get_maintenance_policy().await?;
+			if enable || disable {
+				policy.weekly = enable;
+				settings.set_maintenance_policy(&policy).await?;
+			}
+			println!("Weekly maintenance {}", if policy.weekly { "on" } else { "off" });
+			if let Some(report) = &policy.last_report {
+				println!("Last run {}: {}", time::format_local(report.finished_at), report);
+			}
+			Ok(())
+		}
+		Command::RecentlyDeleted => {
+			for item in TrashRepository::new(pool).get_deleted().await? {
+				println!(
+					"{:<14} {:<30} deleted {}, purged after {}",
+					item.kind,
+					item.label,
+					time::format_local(item.deleted_at),
+					time::format_local(item.purged_after())
+				);
+			}
+			Ok(())
+		}
+		Command::Undelete { name } => {
+			let trash = TrashRepository::new(pool);
+			let matches: Vec<DeletedItem> = trash
+				.get_deleted()
+				.await?
+				.into_iter()
+				.filter(|item| item.label.eq_ignore_ascii_case(name.trim()))
+				.collect();
+			match matches.as_slice() {
+				[] => anyhow::bail!("{} is not in Recently deleted", name),
+				[item] => {
+					trash.restore(item.kind, item.id).await?;
+					println!("Restored {} {}", item.kind, item.label);
+					Ok(())
+				}
+				_ => anyhow::bail!("Several deleted entries are named {}; restore the right one in the GUI", name),
+			}
+		}
+		Command::ImportRuns => {
+			for run in ImportRunRepository::new(pool).get_runs().await? {
+				println!(
+					"{:>5} {:<10} {} by {:<12} {} added, {} overwritten, {} skipped  {}",
+					run.run_id,
+					run.status,
+					time::format_local(run.started_at),
+					run.actor,
+					run.created,
+					run.updated,
+					run.skipped,
+					run.source
+				);
+			}
+			Ok(())
+		}
+		Command::SkippedRows { run_id } => {
+			for row in ImportRunRepository::new(pool).get_skipped_rows(run_id).await? {
+				println!("{}", row);
+			}
+			Ok(())
+		}
+		Command::RevertImport { run_id } => {
+			let summary = ImportRunRepository::new(pool).revert(run_id).await?;
+			println!("Reverted import run {}: {}", run_id, summary);
+			Ok(())
+		}
+		Command::LogFilter { directives: Some(directives) } => {
+			logger::parse_filter(&directives)?;
+			settings.set_log_filter(&directives).await?;
+			println!("Log filter set to {}", directives.trim());
+			Ok(())
+		}
+		Command::LogFilter { directives: None } => {
+			println!("{}", settings.get_log_filter().await?.unwrap_or_else(|| "info".to_string()));
+			Ok(())
+		}
+		Command::Network { proxy, proxy_user, ca_bundle } => {
+			let mut network = settings.get_network_settings().await?;
+			if proxy.is_some() || proxy_user.is_some() || ca_bundle.is_some() {
+				access::require_write_access()?;
+				let non_empty = |value: String| Some(value.trim().to_string()).filter(|value| !value.is_empty());
+				if let Some(proxy) = proxy {
+					network.proxy_url = non_empty(proxy);
+				}
+				if let Some(user) = proxy_user {
+					network.proxy_username = non_empty(user);
+				}
+				if let Some(path) = ca_bundle {
+					// Stored absolute, as the GUI may be started from another directory
+					network.ca_bundle = non_empty(path)
+						.map(|path| std::fs::canonicalize(&path).with_context(|| format!("Failed to read the CA bundle {}", path)))
+						.transpose()?;
+				}
+				http::check(&network)?;
+				settings.set_network_settings(&network).await?;
+			}
+			match (&network.proxy_url, &network.proxy_username) {
+				(Some(url), Some(user)) => println!(
+					"Proxy: {} as {}{}",
+					url,
+					user,
+					if std::env::var_os(http::PROXY_PASSWORD_ENV).is_some() { "" } else { " (RVD_PROXY_PASSWORD is not set)" },
+				),
+				(Some(url), None) => println!("Proxy: {}", url),
+				(None, _) => println!("Proxy: none, HTTP_PROXY and HTTPS_PROXY apply"),
+			}
+			match &network.ca_bundle {
+				Some(path) => println!("Extra root certificates: {}", path.display()),
+				None => println!("Extra root certificates: none"),
+			}
+			Ok(())
+		}
+		Command::UpdateCheck { enable, disable, endpoint } => {
+			let mut check = settings.get_update_check().await?;
+			if enable || disable || endpoint.is_some() {
+				access::require_write_access()?;
+				if let Some(endpoint) = endpoint {
+					check.endpoint = endpoint.trim().to_string();
+				}
+				check.enabled = (check.enabled || enable) && !disable;
+				settings.set_update_check(&check).await?;
+			}
+			println!("Update check {}, asking {}", if check.enabled { "on" } else { "off" }, check.endpoint);
+			if check.enabled {
+				match update_check::check(&check).await? {
+					Some(update) => println!(
+						"{} is available, running {}{}",
+						update.title,
+						update_check::CURRENT_VERSION,
+						update.url.map(|url| format!(": {}", url)).unwrap_or_default()
+					),
+					None => println!("RVD {} is the latest release", update_check::CURRENT_VERSION),
+				}
+			}
+			Ok(())
+		}
+		Command::Shell { database_url } => {
+			let url = database_url.or_else(|| std::env::var(storage::DATABASE_URL_ENV).ok());
+			let storage: Arc<dyn ShellStorage> = match url {
+				Some(url) => storage::open(&url)?,
+				None => Arc::new(SqliteStorage::new(pool)),
+			};
+			shell::run(storage).await
+		}
+		#[cfg(feature = "postgres")]
+		Command::PushDatabase { database_url } => {
+			let url = database_url
+				.or_else(|| std::env::var(storage::DATABASE_URL_ENV).ok())
+				.with_context(|| format!("No server database given; pass --database-url or set {}", storage::DATABASE_URL_ENV))?;
+			let vulnerabilities = VulnerabilityRepository::new(pool.clone()).get_all_vulnerabilities().await?;
+			let robots = RobotRepository::new(pool).get_all_robots().await?;
+			let summary = crate::db::postgres::PostgresStorage::connect(&url)?
+				.push(vulnerabilities, robots)
+				.await?;
+			println!("Pushed {} vulnerabilities and {} robots", summary.vulnerabilities, summary.robots);
+			Ok(())
+		}
+		Command::Stats { output } => export_statistics(pool, output).await,
+		Command::ImportCsv { path, preset, merge_policy } => {
+			let mapping = import_mapping(&settings, preset, merge_policy).await?;
+			let report = import_vulnerabilities_from_csv(
+				path.to_string_lossy().into_owned(),
+				pool,
+				mapping,
+				cancel_on_ctrl_c(),
+			).await?;
+			print_import_report(&report);
+			keep_import(workspace, &settings, &path).await;
+			Ok(())
+		}
+		Command::ImportXlsx { path, sheet, preset, merge_policy } => {
+			let mapping = import_mapping(&settings, preset, merge_policy).await?;
+			let report = import_vulnerabilities_from_xlsx(
+				path.to_string_lossy().into_owned(),
+				sheet,
+				pool,
+				mapping,
+				cancel_on_ctrl_c(),
+			).await?;
+			print_import_report(&report);
+			keep_import(workspace, &settings, &path).await;
+			Ok(())
+		}
+		Command::SaveCsvPreset { name, mapping } => {
+			settings.save_csv_preset(&name, &mapping.into()).await?;
+			println!("Saved CSV preset {}", name);
+			Ok(())
+		}
+		Command::CsvPresets => {
+			for (name, mapping) in settings.list_csv_presets().await? {
+				println!(
+					"{} ({}): {}; {}",
+					name,
+					mapping.source,
+					mapping.headers().join(", "),
+					mapping.merge_policy.as_str()
+				);
+			}
+			Ok(())
+		}
+		Command::ImportNvd { paths } => {
+			let summary = import_nvd_feeds(paths, pool.clone(), cancel_on_ctrl_c()).await?;
+			println!(
+				"Imported {} records from {} feed files ({} new vulnerabilities)",
+				summary.records, summary.files, summary.inserted
+			);
+			send_alerts(pool).await;
+			Ok(())
+		}
+		Command::ImportRvd { paths } => {
+			let summary = import_rvd_advisories(paths, pool.clone()).await?;
+			println!("Imported {}", summary);
+			send_alerts(pool).await;
+			Ok(())
+		}
+		Command::ImportGhsa { mut ecosystems } => {
+			if ecosystems.is_empty() {
+				ecosystems = DEFAULT_ECOSYSTEMS.map(String::from).to_vec();
+			}
+			let summary = import_ghsa_advisories(pool.clone(), ecosystems, cancel_on_ctrl_c()).await?;
+			println!("Imported {}", summary);
+			send_alerts(pool).await;
+			Ok(())
+		}
+		Command::Alias { id, alias, source } => {
+			let merged = AliasRepository::new(pool).link(id.clone(), alias.clone(), source).await?;
+			if merged {
+				println!("Merged {} into {}", alias, id);
+			} else {
+				println!("Recorded {} as an alias of {}", alias, id);
+			}
+			Ok(())
+		}
+		Command::Dedupe => {
+			let merged = AliasRepository::new(pool).collapse().await?;
+			println!("Merged {} duplicate entries", merged);
+			Ok(())
+		}
+		Command::Edit { cve, description, severity, impact, mitigation } => {
+			let edits: Vec<_> = [
+				(LockedField::Description, description),
+				(LockedField::Severity, severity),
+				(LockedField::Impact, impact),
+				(LockedField::Mitigation, mitigation),
+			]
+				.into_iter()
+				.filter_map(|(field, value)| value.map(|value| (field, Some(value))))
+				.collect();
+			anyhow::ensure!(!edits.is_empty(), "Nothing to edit; pass --description, --severity, --impact or --mitigation");
+			let repo = VulnerabilityRepository::new(pool);
+			let id = find_vulnerability_id(&repo, &cve).await?;
+			let fields: Vec<String> = edits.iter().map(|(field, _)| field.to_string()).collect();
+			repo.edit_fields(id, edits, access::current_user()).await?;
+			println!("Updated and locked the {} of {}", fields.join(", "), cve);
+			Ok(())
+		}
+		Command::Unlock { cve, field } => {
+			let repo = VulnerabilityRepository::new(pool);
+			let id = find_vulnerability_id(&repo, &cve).await?;
+			if repo.unlock_field(id, field).await? {
+				println!("Unlocked the {} of {}", field, cve);
+			} else {
+				println!("The {} of {} was not locked", field, cve);
+			}
+			Ok(())
+		}
+		Command::ImportKev { path } => {
+			let summary = import_kev_catalog(path.clone(), pool).await?;
+			println!(
+				"{} of {} known exploited vulnerabilities are tracked",
+				summary.matched, summary.listed
+			);
+			keep_import(workspace, &settings, &path).await;
+			Ok(())
+		}
+		Command::ImportEpss { path } => {
+			let summary = import_epss_scores(path.clone(), pool).await?;
+			println!(
+				"{} of {} scored vulnerabilities are tracked; {} robots rescored",
+				summary.matched, summary.listed, summary.robots_rescored
+			);
+			keep_import(workspace, &settings, &path).await;
+			Ok(())
+		}
+		Command::ImportRobots { path } => {
+			let summary = import_robots(path.clone(), pool.clone()).await?;
+			println!("{}", summary);
+			for error in &summary.errors {
+				println!("  {}", error);
+			}
+			keep_import(workspace, &settings, &path).await;
+			send_alerts(pool).await;
+			Ok(())
+		}
+		Command::RiskScores => {
+			let robots = RobotRepository::new(pool.clone());
+			if access::current_role().can_edit() {
+				robots.refresh_risk_scores().await?;
+			}
+			if let Some(fleet_risk) = StatisticsRepository::new(pool).get_statistics().await?.fleet_risk {
+				let trend = match (fleet_risk.change(), fleet_risk.previous_on.as_deref()) {
+					(Some(change), Some(previous_on)) => format!(" {} {:+.1} since {}", fleet_risk.trend_arrow(), change, previous_on),
+					_ => String::new(),
+				};
+				println!("Fleet risk index: {:.1}{}\n", fleet_risk.index, trend);
+			}
+			for robot in robots.get_robots_by_risk().await? {
+				let score = robot.risk_score.unwrap_or_default();
+				println!("{:>5.1} {:<8} {}", score, RiskBand::of(score).as_str(), robot.name);
+			}
+			Ok(())
+		}
+		Command::ChecklistItems { add, remove } => {
+			let checklist = CommissioningRepository::new(pool);
+			for label in &add {
+				checklist.add_item(label).await?;
+			}
+			for label in &remove {
+				if !checklist.remove_item(label).await? {
+					warn!("{} is not on the commissioning checklist", label);
+				}
+			}
+			for item in checklist.get_items().await? {
+				println!("{}", item.label);
+			}
+			Ok(())
+		}
+		Command::Checklist { robot, done, undo } => {
+			let robot_id = find_robot_id(&RobotRepository::new(pool.clone()), &robot).await?;
+			let checklist = CommissioningRepository::new(pool);
+			let entries = checklist.get_checklist(robot_id).await?;
+			for (labels, tick) in [(&done, true), (&undo, false)] {
+				for label in labels {
+					let entry = entries
+						.iter()
+						.find(|entry| entry.item.label.eq_ignore_ascii_case(label.trim()))
+						.with_context(|| format!("{} is not on the commissioning checklist", label))?;
+					checklist.set_done(robot_id, entry.item.item_id, tick).await?;
+				}
+			}
+			let entries = checklist.get_checklist(robot_id).await?;
+			for entry in &entries {
+				match &entry.completed {
+					Some((actor, at)) => println!("[x] {} ({}, {})", entry.item.label, actor, time::format_local(*at)),
+					None => println!("[ ] {}", entry.item.label),
+				}
+			}
+			println!("{}", commissioning::progress(&entries));
+			Ok(())
+		}
+		Command::StaleInventories => {
+			let max_age_days = settings.get_inventory_max_age_days().await?;
+			let now = Utc::now();
+			let mut stale: Vec<_> = RobotRepository::new(pool)
+				.get_all_robots()
+				.await?
+				.into_iter()
+				.filter(|robot| robot.inventory_is_stale(max_age_days, now))
+				.collect();
+			stale.sort_by_key(|robot| robot.inventory_refreshed_at);
+			for robot in &stale {
+				let refreshed = robot.inventory_refreshed_at.map_or_else(|| "never".to_string(), time::format_local);
+				let source = robot.inventory_source.map(|source| format!(" ({})", source)).unwrap_or_default();
+				println!("{:<16} {}{}", refreshed, robot.name, source);
+			}
+			println!("{} robots with an inventory older than {} days", stale.len(), max_age_days);
+			Ok(())
+		}
+		Command::ConfirmInventory { robot } => {
+			let robots = RobotRepository::new(pool);
+			robots.confirm_inventory(find_robot_id(&robots, &robot).await?).await?;
+			println!("Inventory of {} confirmed as current", robot.trim());
+			Ok(())
+		}
+		Command::InventoryMaxAge { days: Some(days) } => {
+			settings.set_inventory_max_age_days(days).await?;
+			println!("Inventories are flagged as stale after {} days", days);
+			Ok(())
+		}
+		Command::InventoryMaxAge { days: None } => {
+			println!("{}", settings.get_inventory_max_age_days().await?);
+			Ok(())
+		}
+		Command::ExportFleet { output } => {
+			let document = InterchangeRepository::new(pool).export(cancel_on_ctrl_c()).await?;
+			let json = serde_json::to_string_pretty(&document)
+				.context("Failed to serialize interchange document")?;
+			write_output(output, json)
+		}
+		Command::ImportFleet { path } => {
+			let json = std::fs::read_to_string(&path)
+				.with_context(|| format!("Failed to read {:?}", path))?;
+			let document = serde_json::from_str(&json)
+				.with_context(|| format!("{:?} is not a valid interchange document", path))?;
+			let summary = InterchangeRepository::new(pool.clone()).import(document).await?;
+			println!(
+				"Imported {} robots ({} new), {} software versions, {} correlations ({} more matched), {} assessments, {} notes",
+				summary.robots_created + summary.robots_updated,
+				summary.robots_created,
+				summary.software_versions,
+				summary.correlations,
+				summary.derived_correlations,
+				summary.assessments,
+				summary.notes
+			);
+			for product in summary.new_products.iter().filter(|p| !p.suggestions.is_empty()) {
+				let similar: Vec<String> = product.suggestions
+					.iter()
+					.map(|s| format!("{} by {} ({:.0}%)", s.product_name, s.vendor, s.similarity * 100.0))
+					.collect();
+				println!(
+					"New product {} by {} resembles existing: {}",
+					product.product_name, product.vendor, similar.join(", ")
+				);
+			}
+			keep_import(workspace, &settings, &path).await;
+			send_alerts(pool).await;
+			Ok(())
+		}
+		Command::ExportBundle { output } => {
+			let exported = offline_bundle::export_bundle(pool, &output, cancel_on_ctrl_c()).await?;
+			println!("Exported {} vulnerabilities to {}", exported, output.display());
+			Ok(())
+		}
+		Command::ImportBundle { path } => {
+			let summary = offline_bundle::import_bundle(pool.clone(), &path).await?;
+			println!("Imported {}", summary);
+			keep_import(workspace, &settings, &path).await;
+			send_alerts(pool).await;
+			Ok(())
+		}
+		Command::RiskReport { format, output } => {
+			let decisions = VulnerabilityRepository::new(pool).get_risk_decisions().await?;
+			let today = Local::now().date_naive();
+			let report = match format {
+				ReportFormat::Csv => risk_acceptance::report_csv(&decisions, today)?,
+				ReportFormat::Html => risk_acceptance::report_html(&decisions, today, Layout::Print),
+				ReportFormat::Share => risk_acceptance::report_html(&decisions, today, Layout::Share),
+			};
+			write_output(output, report)
+		}
+		Command::ComplianceControls { standard, code, title } => {
+			let compliance = ComplianceRepository::new(pool);
+			if let (Some(standard), Some(code), Some(title)) = (standard, code, title) {
+				compliance.add_control(&standard, &code, &title).await?;
+			}
+			for control in compliance.get_controls().await? {
+				println!("{}", control);
+			}
+			Ok(())
+		}
+		Command::MapControls { cve, link, unlink } => {
+			let vulnerability_id = find_vulnerability_id(&VulnerabilityRepository::new(pool.clone()), &cve).await?;
+			let compliance = ComplianceRepository::new(pool);
+			let controls = compliance.get_controls().await?;
+			for (names, linked) in [(&link, true), (&unlink, false)] {
+				for name in names {
+					let control_id = find_control_id(&controls, name)?;
+					compliance.set_linked(vulnerability_id, control_id, linked).await?;
+				}
+			}
+			for control in compliance.get_linked_controls(vulnerability_id).await? {
+				println!("{}", control);
+			}
+			Ok(())
+		}
+		Command::ComplianceReport { format, output } => {
+			let coverage = ComplianceRepository::new(pool).get_coverage().await?;
+			let report = match format {
+				ReportFormat::Csv => compliance::report_csv(&coverage)?,
+				ReportFormat::Html => compliance::report_html(&coverage, Layout::Print),
+				ReportFormat::Share => compliance::report_html(&coverage, Layout::Share),
+			};
+			write_output(output, report)
+		}
+		Command::InventoryReport { format, output } => {
+			let entries = SoftwareRepository::new(pool).get_inventory().await?;
+			let report = match format {
+				ReportFormat::Csv => inventory::report_csv(&entries)?,
+				ReportFormat::Html => inventory::report_html(&entries, Layout::Print),
+				ReportFormat::Share => inventory::report_html(&entries, Layout::Share),
+			};
+			write_output(output, report)
+		}
+		Command::AffectedMatrix { by, format, output } => {
+			let columns = match by {
+				MatrixBy::Versions => MatrixColumns::Versions,
+				MatrixBy::Robots => MatrixColumns::Robots,
+			};
+			let matrix = SoftwareRepository::new(pool).get_affected_matrix(columns).await?;
+			match format {
+				MatrixFormat::Csv => write_output(output, matrix::report_csv(&matrix)?),
+				MatrixFormat::Xlsx => {
+					let path = output.context("An XLSX workbook needs a file; pass --output")?;
+					std::fs::write(&path, matrix::report_xlsx(&matrix)?)
+						.with_context(|| format!("Failed to write {:?}", path))?;
+					info!("Written to {:?}", path);
+					Ok(())
+				}
+			}
+		}
+		Command::DiffReport { from, to, format, output } => {
+			let diff = SnapshotRepository::new(pool).diff(from, to).await?;
+			let report = match format {
+				ReportFormat::Csv => diff::report_csv(&diff)?,
+				ReportFormat::Html => diff::report_html(&diff, Layout::Print),
+				ReportFormat::Share => diff::report_html(&diff, Layout::Share),
+			};
+			write_output(output, report)
+		}
+		Command::Changelog { since, format, output } => {
+			let since = since.map(|date| date.and_time(NaiveTime::MIN).and_utc());
+			let deltas = SyncDeltaRepository::new(pool).get_deltas(since).await?;
+			let report = match format {
+				ReportFormat::Csv => changelog::report_csv(&deltas)?,
+				ReportFormat::Html => changelog::report_html(&deltas, Layout::Print),
+				ReportFormat::Share => changelog::report_html(&deltas, Layout::Share),
+			};
+			write_output(output, report)
+		}
+		Command::Snapshots => {
+			for date in SnapshotRepository::new(pool).get_snapshot_dates().await? {
+				println!("{}", date);
+			}
+			Ok(())
+		}
+		Command::ShareVulnerabilities { search, severity, status, known_exploited, output } => {
+			let filter = VulnerabilityFilter {
+				search,
+				severity,
+				status,
+				quick: known_exploited.then_some(QuickFilter::KnownExploited).into_iter().collect(),
+				..Default::default()
+			};
+			let summary = filter.summary();
+			let vulnerabilities = VulnerabilityRepository::new(pool)
+				.get_matching_vulnerabilities(filter, SortOrder::default())
+				.await?;
+			write_output(output, share::vulnerability_list_html(&vulnerabilities, &summary))
+		}
+	}
+}
+
+async fn export_statistics(pool: Arc<SqlitePool>, output: Option<PathBuf>) -> Result<()> {
+	let report = StatisticsRepository::new(pool).get_statistics().await?;
+	let json = serde_json::to_string_pretty(&report)
+		.context("Failed to serialize statistics")?;
+	write_output(output, json)
+}
+
+/// Keeps a copy of an imported file and archives old copies. The import itself has
+/// already succeeded, so failures are only logged.
+/// Vulnerability ID of a CVE ID or alias
+async fn find_vulnerability_id(repo: &VulnerabilityRepository, cve: &str) -> Result<i64> {
+	repo.get_vulnerability_by_cve(cve)
+		.await?
+		.and_then(|vuln| vuln.vulnerability_id)
+		.with_context(|| format!("{} is not in the database", cve))
+}
+
+/// NVD client for a sync, ignoring cached NVD answers if asked to
+fn nvd_client(pool: Arc<SqlitePool>, no_cache: bool) -> Result<NvdApiClient> {
+	let client = NvdApiClient::new(pool)?;
+	Ok(if no_cache { client.bypassing_cache() } else { client })
+}
+
+async fn find_data_source(repo: &DataSourceRepository, name: &str) -> Result<DataSource> {
+	repo.get_source(name).await?.with_context(|| format!("No data source named {}", name.trim()))
+}
+
+/// A data source with its schedule, followed by the outcome of its last run
+fn print_data_source(source: &DataSource) {
+	println!(
+		"{:<20} {:<18} {:<9} every {} minutes{}",
+		source.name,
+		source.kind,
+		if source.enabled { "enabled" } else { "disabled" },
+		source.interval_minutes,
+		source.url.as_deref().map(|url| format!(" from {}", url)).unwrap_or_default(),
+	);
+	if let Some(at) = source.last_run_at {
+		let status = match source.last_status {
+			Some(RunStatus::Succeeded) => "succeeded",
+			Some(RunStatus::Failed) => "failed",
+			None => "running",
+		};
+		println!("  last run {} {}: {}", time::format_local(at), status, source.last_message.as_deref().unwrap_or_default());
+	}
+}
+
+/// Control ID of a control given by code, e.g. "SR 5.1", or by standard and code
+fn find_control_id(controls: &[ComplianceControl], name: &str) -> Result<i64> {
+	let name = name.trim();
+	let matches: Vec<&ComplianceControl> = controls
+		.iter()
+		.filter(|control| {
+			control.code.eq_ignore_ascii_case(name)
+				|| format!("{} {}", control.standard, control.code).eq_ignore_ascii_case(name)
+		})
+		.collect();
+	match matches.as_slice() {
+		[control] => Ok(control.control_id),
+		[] => anyhow::bail!("{} is not a compliance control, see compliance-controls", name),
+		_ => anyhow::bail!("{} is a control of several standards, give the standard too", name),
+	}
+}
+
+/// Robot ID of the robot with the given name, ignoring case
+async fn find_robot_id(repo: &RobotRepository, name: &str) -> Result<i64> {
+	repo.get_all_robots()
+		.await?
+		.into_iter()
+		.find(|candidate| candidate.name.eq_ignore_ascii_case(name.trim()))
+		.and_then(|candidate| candidate.robot_id)
+		.map(i64::from)
+		.with_context(|| format!("No robot named {}", name))
+}
+
+/// Mapping of a vulnerability list import: the named preset or the MITRE layout, with
+/// the merge policy given on the command line, if any
+async fn import_mapping(
+	settings: &SettingsRepository,
+	preset: Option<String>,
+	merge_policy: Option<MergePolicy>,
+) -> Result<CsvMapping> {
+	let mut mapping = match preset {
+		Some(name) => settings.get_csv_preset(&name).await?
+			.with_context(|| format!("No CSV preset named '{}'", name))?,
+		None => CsvMapping::default(),
+	};
+	if let Some(merge_policy) = merge_policy {
+		mapping.merge_policy = merge_policy;
+	}
+	Ok(mapping)
+}
+
+/// The outcome of a vulnerability list import, with every skipped row it kept
+fn print_import_report(report: &ImportReport) {
+	println!("{}", report);
+	for row in &report.skipped_rows {
+		println!("  {}", row);
+	}
+	if report.skipped > report.skipped_rows.len() {
+		println!("  and {} more skipped rows", report.skipped - report.skipped_rows.len());
+	}
+}
+
+async fn keep_import(workspace: &str, settings: &SettingsRepository, path: &Path) {
+	let archive = ImportArchive::for_workspace(workspace);
+	let now = Utc::now();
+	let result = async {
+		archive.retain(path, now)?;
+		archive.archive_older_than(settings.get_import_retention_days().await?, now)
+	};
+	if let Err(e) = result.await {
+		warn!("Failed to keep a copy of {:?}: {:#}", path, e);
+	}
+}
+
+/// Send alerts an import queued; a mail failure does not fail the import itself
+async fn send_alerts(pool: Arc<SqlitePool>) {
+	if let Err(e) = alerts::dispatch(pool, false).await {
+		warn!("Failed to send email alerts: {}", e);
+	}
+}
+
+/// Reporter for a long-running command that Ctrl+C stops at its next batch boundary
+fn cancel_on_ctrl_c() -> ProgressReporter {
+	let progress = ProgressReporter::disabled();
+	let cancel = progress.cancellation_token();
+	tokio::spawn(async move {
+		match tokio::signal::ctrl_c().await {
+			Ok(()) => {
+				warn!("Cancelling, waiting for the current batch to finish...");
+				cancel.cancel();
+			}
+			Err(e) => error!("Failed to listen for ctrl-c signal: {}", e),
+		}
+	});
+	progress
+}
+
+/// Write a command's document to a file, or to stdout when no file was given
+fn write_output(output: Option<PathBuf>, content: String) -> Result<()> {
+	match output {
+		Some(path) => {
+			std::fs::write(&path, content)
+				.with_context(|| format!("Failed to write {:?}", path))?;
+			info!("Written to {:?}", path);
+		}
+		None => println!("{}", content),
+	}
+	Ok(())
+}
@@ -0,0 +1,277 @@
+// src/cli/shell.rs
+
+//! Interactive prompt for terminals where the GUI cannot run, e.g. over SSH.
+//! Tab completes commands, CVE IDs from the open database and triage statuses.
+
+use crate::db::storage::ShellStorage;
+use crate::models::vulnerability::{TriageStatus, Vulnerability};
+use crate::models::weakness::WeaknessClass;
+use crate::utils::time;
+use anyhow::{anyhow, bail, Context as _, Result};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::sync::Arc;
+use tokio::task;
+
+const PROMPT: &str = "rvd> ";
+const SEARCH_LIMIT: usize = 20;
+const COMPLETION_LIMIT: usize = 50;
+
+/// Name, usage and description of each command
+const COMMANDS: &[(&str, &str, &str)] = &[
+	("search", "search <text>", "CVEs matching an ID, description or reference"),
+	("show", "show <CVE>", "details of a vulnerability"),
+	("robots", "robots", "the robot inventory"),
+	("status", "status <CVE> <status> [assignee]", "set the triage status, e.g. in-progress"),
+	("help", "help", "this list"),
+	("exit", "exit", "leave the shell (or Ctrl+D)"),
+];
+
+/// Tab completion against the open database
+struct ShellHelper {
+	storage: Arc<dyn ShellStorage>,
+}
+
+impl ShellHelper {
+	/// Start of the word being completed and its candidates
+	fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+		let line = &line[..pos];
+		let start = line.rfind(' ').map_or(0, |idx| idx + 1);
+		let word = &line[start..];
+		let previous: Vec<&str> = line[..start].split_whitespace().collect();
+
+		let candidates = match previous.as_slice() {
+			[] => COMMANDS.iter().map(|(name, _, _)| name.to_string()).filter(|name| name.starts_with(word)).collect(),
+			["show"] | ["status"] => self.storage.cve_ids_with_prefix(word, COMPLETION_LIMIT).unwrap_or_default(),
+			["status", _] => TriageStatus::ALL
+				.iter()
+				.map(status_slug)
+				.filter(|slug| slug.starts_with(&word.to_ascii_lowercase()))
+				.collect(),
+			_ => Vec::new(),
+		};
+		(start, candidates)
+	}
+}
+
+impl Completer for ShellHelper {
+	type Candidate = String;
+
+	fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+		Ok(self.candidates(line, pos))
+	}
+}
+
+impl Hinter for ShellHelper {
+	type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// Status as typed in the shell, e.g. `accepted-risk`
+fn status_slug(status: &TriageStatus) -> String {
+	status.as_str().to_ascii_lowercase().replace(' ', "-")
+}
+
+pub(super) fn parse_status(value: &str) -> Result<TriageStatus> {
+	let value = value.to_ascii_lowercase().replace('_', "-");
+	match TriageStatus::ALL.iter().find(|status| status_slug(status) == value) {
+		Some(status) => Ok(*status),
+		None => bail!(
+			"Unknown status '{}', expected one of {}",
+			value,
+			TriageStatus::ALL.iter().map(status_slug).collect::<Vec<_>>().join(", ")
+		),
+	}
+}
+
+pub async fn run(storage: Arc<dyn ShellStorage>) -> Result<()> {
+	let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::new().context("Failed to start the shell")?;
+	editor.set_helper(Some(ShellHelper { storage: storage.clone() }));
+	println!("Type help for the commands, Tab to complete.");
+
+	loop {
+		// Reading a line blocks, and completion queries the database, so both run
+		// off the async workers
+		let (returned, line) = task::spawn_blocking(move || {
+			let line = editor.readline(PROMPT);
+			(editor, line)
+		})
+			.await
+			.context("Failed to read the shell input")?;
+		editor = returned;
+
+		let line = match line {
+			Ok(line) => line,
+			Err(ReadlineError::Interrupted) => continue,
+			Err(ReadlineError::Eof) => return Ok(()),
+			Err(e) => return Err(e).context("Failed to read the shell input"),
+		};
+		let line = line.trim();
+		if line.is_empty() {
+			continue;
+		}
+		let _ = editor.add_history_entry(line);
+
+		let words: Vec<&str> = line.split_whitespace().collect();
+		let result = match words.as_slice() {
+			["exit"] | ["quit"] => return Ok(()),
+			["help"] => {
+				for (_, usage, description) in COMMANDS {
+					println!("  {:<34} {}", usage, description);
+				}
+				Ok(())
+			}
+			["search", ..] => search(storage.as_ref(), line["search".len()..].trim()).await,
+			["show", cve_id] => show(storage.as_ref(), cve_id).await,
+			["robots"] => list_robots(storage.as_ref()).await,
+			["status", cve_id, status, assignee @ ..] => set_status(storage.as_ref(), cve_id, status, assignee.join(" ")).await,
+			[command, ..] => match COMMANDS.iter().find(|(name, _, _)| name == command) {
+				Some((_, usage, _)) => Err(anyhow!("Usage: {}", usage)),
+				None => Err(anyhow!("Unknown command '{}', type help for the list", command)),
+			},
+			[] => Ok(()),
+		};
+		if let Err(e) = result {
+			println!("Error: {:#}", e);
+		}
+	}
+}
+
+async fn search(storage: &dyn ShellStorage, text: &str) -> Result<()> {
+	let (vulnerabilities, more) = storage.search_vulnerabilities(text, SEARCH_LIMIT).await?;
+	if vulnerabilities.is_empty() {
+		println!("No matching vulnerabilities");
+	}
+	for vuln in &vulnerabilities {
+		let description = vuln.description.as_deref().unwrap_or_default();
+		println!(
+			"{:<16} {:<8} {:<14} {}",
+			vuln.cve_id,
+			vuln.severity,
+			vuln.status.as_str(),
+			description.chars().take(70).collect::<String>()
+		);
+	}
+	if more {
+		println!("First {} matches shown; narrow the search for more", SEARCH_LIMIT);
+	}
+	Ok(())
+}
+
+async fn find(storage: &dyn ShellStorage, cve_id: &str) -> Result<Vulnerability> {
+	storage
+		.get_vulnerability_by_cve(&cve_id.to_ascii_uppercase())
+		.await?
+		.with_context(|| format!("{} is not in the database", cve_id))
+}
+
+async fn show(storage: &dyn ShellStorage, cve_id: &str) -> Result<()> {
+	let vuln = find(storage, cve_id).await?;
+	println!("{}", vuln.cve_id);
+	match vuln.cvss_score {
+		Some(score) => println!("  Severity:   {} ({} {:.1})", vuln.severity, vuln.cvss_name(), score),
+		None => println!("  Severity:   {} (CVSS ~{:.1}, estimated)", vuln.severity, vuln.effective_cvss()),
+	}
+	println!("  Status:     {}", vuln.status);
+	if let Some(assignee) = &vuln.assigned_to {
+		println!("  Assignee:   {}", assignee);
+	}
+	if let Some(published) = vuln.published_date {
+		println!("  Published:  {}", published);
+	}
+	println!("  Sources:    {}", vuln.sources().join(", "));
+	for alias in &vuln.aliases {
+		println!("  Alias:      {} ({})", alias.id, alias.source);
+	}
+	if let Some(added) = vuln.kev_date_added {
+		println!("  Known exploited since {}", added);
+	}
+	for cwe_id in &vuln.cwe_ids {
+		println!("  Weakness:   {} ({})", cwe_id, WeaknessClass::of(cwe_id));
+	}
+	for lock in &vuln.locks {
+		match lock.locked_at {
+			Some(locked_at) => println!("  Locked:     {} edited by {} on {}", lock.field, lock.locked_by, time::format_local(locked_at)),
+			None => println!("  Locked:     {} edited by {}", lock.field, lock.locked_by),
+		}
+	}
+	for provenance in &vuln.provenance {
+		match provenance.updated_at {
+			Some(updated_at) => println!("  Source:     {} from {} on {}", provenance.field, provenance.source, time::format_local(updated_at)),
+			None => println!("  Source:     {} from {}", provenance.field, provenance.source),
+		}
+	}
+	for (label, value) in [("Description", &vuln.description), ("Impact", &vuln.impact), ("Mitigation", &vuln.mitigation)] {
+		if let Some(value) = value {
+			println!("  {}:\n    {}", label, value);
+		}
+	}
+	Ok(())
+}
+
+async fn list_robots(storage: &dyn ShellStorage) -> Result<()> {
+	let robots = storage.get_all_robots().await?;
+	if robots.is_empty() {
+		println!("No robots in the inventory");
+	}
+	for robot in robots {
+		println!(
+			"{:<24} {:<20} {}",
+			robot.name,
+			robot.manufacturer.as_deref().unwrap_or("-"),
+			robot.operational_note.as_deref().unwrap_or_default()
+		);
+	}
+	Ok(())
+}
+
+/// Keeps the assignee and any risk decision details unless an assignee is given
+async fn set_status(storage: &dyn ShellStorage, cve_id: &str, status: &str, assignee: String) -> Result<()> {
+	let status = parse_status(status)?;
+	let vuln = find(storage, cve_id).await?;
+	let id = vuln.vulnerability_id.context("Vulnerability has no ID")?;
+	let assignee = if assignee.is_empty() { vuln.assigned_to } else { Some(assignee) };
+	storage
+		.update_triage(id, status, assignee, vuln.risk_acceptance.unwrap_or_default())
+		.await?;
+	println!("{} is now {}", vuln.cve_id, status);
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use crate::db::storage::SqliteStorage;
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_completion() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (cve_id, severity) VALUES
+				('CVE-2024-0001', 'High'), ('CVE-2024-0002', 'Low'), ('CVE-2023-0001', 'Low');",
+		)?;
+		let helper = ShellHelper { storage: Arc::new(SqliteStorage::new(pool)) };
+
+		assert_eq!(helper.candidates("se", 2), (0, vec!["search".to_string()]));
+		assert_eq!(helper.candidates("show cve-2024", 13), (5, vec!["CVE-2024-0002".to_string(), "CVE-2024-0001".to_string()]));
+		assert_eq!(helper.candidates("status CVE-2024-0001 in", 23), (21, vec!["in-progress".to_string()]));
+		assert!(helper.candidates("robots ", 7).1.is_empty());
+
+		assert_eq!(parse_status("Accepted_Risk")?, TriageStatus::AcceptedRisk);
+		assert!(parse_status("done").is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,270 @@
+// src/db/backup.rs
+
+//! Compressed snapshots of a workspace database, taken with SQLite's online backup API
+//! so they stay consistent while the GUI or a sync keeps writing. Each workspace keeps
+//! them under `database/backups/<workspace>` as `<timestamp>.db.gz`.
+
+use crate::db::connection::{self, SqlitePool};
+use crate::db::schema;
+use crate::repositories::settings_repo::SettingsRepository;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use log::info;
+use rusqlite::{Connection, DatabaseName};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::task;
+
+/// Backups kept when no retention is configured
+pub const DEFAULT_KEEP: usize = 7;
+/// Scheduled backups are at least this far apart
+const BACKUP_INTERVAL_HOURS: i64 = 24;
+const EXTENSION: &str = ".db.gz";
+
+/// Automatic daily backups and how many backups to keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupPolicy {
+	pub daily: bool,
+	pub keep: usize,
+	pub last_backup: Option<DateTime<Utc>>,
+}
+
+impl Default for BackupPolicy {
+	fn default() -> Self {
+		Self { daily: false, keep: DEFAULT_KEEP, last_backup: None }
+	}
+}
+
+impl BackupPolicy {
+	pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+		self.daily && self.last_backup.is_none_or(|last| now - last >= Duration::hours(BACKUP_INTERVAL_HOURS))
+	}
+}
+
+/// The backups of one workspace
+#[derive(Debug, Clone)]
+pub struct Backups {
+	root: PathBuf,
+}
+
+impl Backups {
+	pub fn new(root: PathBuf) -> Self {
+		Self { root }
+	}
+
+	pub fn for_workspace(workspace: &str) -> Self {
+		Self::new(PathBuf::from("database").join("backups").join(workspace))
+	}
+
+	/// Copies the database behind `conn` into a compressed backup named after `now`,
+	/// followed by `label` when given
+	pub fn create(&self, conn: &Connection, now: DateTime<Utc>, label: Option<&str>) -> Result<PathBuf> {
+		fs::create_dir_all(&self.root).with_context(|| format!("Failed to create {:?}", self.root))?;
+		let label = label.map(|label| format!("-{}", label)).unwrap_or_default();
+		let target = self.root.join(format!("{}{}{}", now.format("%Y%m%dT%H%M%SZ"), label, EXTENSION));
+		let staging = tempfile::Builder::new()
+			.suffix(".db")
+			.tempfile_in(&self.root)
+			.context("Failed to create a staging file")?
+			.into_temp_path();
+		conn.backup(DatabaseName::Main, &staging, None)
+			.context("Failed to back up the database")?;
+
+		// Written under a temporary name so an interrupted backup is never listed
+		let partial = target.with_extension("gz.partial");
+		let mut encoder = GzEncoder::new(
+			File::create(&partial).with_context(|| format!("Failed to create {:?}", partial))?,
+			Compression::default(),
+		);
+		io::copy(&mut BufReader::new(File::open(&staging)?), &mut encoder)
+			.context("Failed to compress the backup")?;
+		encoder.finish()?;
+		fs::rename(&partial, &target).with_context(|| format!("Failed to write {:?}", target))?;
+
+		info!("Backed up the database to {:?}", target);
+		Ok(target)
+	}
+
+	/// Backups newest first
+	pub fn list(&self) -> Result<Vec<PathBuf>> {
+		if !self.root.is_dir() {
+			return Ok(Vec::new());
+		}
+		let mut backups = Vec::new();
+		for entry in fs::read_dir(&self.root).with_context(|| format!("Failed to list {:?}", self.root))? {
+			let path = entry?.path();
+			if path.file_name().is_some_and(|name| name.to_string_lossy().ends_with(EXTENSION)) {
+				backups.push(path);
+			}
+		}
+		// Timestamped names sort by age
+		backups.sort();
+		backups.reverse();
+		Ok(backups)
+	}
+
+	/// Deletes all but the `keep` newest unlabelled backups, returning how many were
+	/// deleted. Labelled backups, such as the copy taken before a restore, are left alone:
+	/// a small retention right after a restore must not delete the only pre-restore state.
+	pub fn prune(&self, keep: usize) -> Result<usize> {
+		let expired: Vec<PathBuf> = self.list()?
+			.into_iter()
+			.filter(|path| !is_labelled(path))
+			.skip(keep.max(1))
+			.collect();
+		for path in &expired {
+			fs::remove_file(path).with_context(|| format!("Failed to remove {:?}", path))?;
+		}
+		if !expired.is_empty() {
+			info!("Removed {} old backups from {:?}", expired.len(), self.root);
+		}
+		Ok(expired.len())
+	}
+}
+
+/// Whether a backup was named with a label after its timestamp, which has no dashes
+fn is_labelled(path: &Path) -> bool {
+	path.file_name()
+		.map(|name| name.to_string_lossy())
+		.is_some_and(|name| name.trim_end_matches(EXTENSION).contains('-'))
+}
+
+/// Backs up a workspace off the async workers, then deletes the backups beyond the
+/// configured retention; counts as the day's scheduled backup
+pub async fn back_up(pool: Arc<SqlitePool>, workspace: &str) -> Result<PathBuf> {
+	let settings = SettingsRepository::new(pool.clone());
+	let mut policy = settings.get_backup_policy().await?;
+	let backups = Backups::for_workspace(workspace);
+	let now = Utc::now();
+	let path = task::spawn_blocking({
+		let backups = backups.clone();
+		move || {
+			let conn = connection::get_conn(&pool)?;
+			backups.create(&conn, now, None)
+		}
+	})
+		.await
+		.context("Failed to execute database operation")??;
+	backups.prune(policy.keep)?;
+	policy.last_backup = Some(now);
+	settings.set_backup_policy(&policy).await?;
+	Ok(path)
+}
+
+/// Takes the daily backup when enabled and a day has passed since the last one
+pub async fn back_up_if_due(pool: Arc<SqlitePool>, workspace: &str) -> Result<Option<PathBuf>> {
+	let policy = SettingsRepository::new(pool.clone()).get_backup_policy().await?;
+	if !policy.is_due(Utc::now()) {
+		return Ok(None);
+	}
+	back_up(pool, workspace).await.map(Some)
+}
+
+/// Replaces the database behind `conn` with a backup, compressed or not, once it has
+/// passed an integrity check. An older backup is migrated to the current schema.
+pub fn restore(conn: &mut Connection, backup: &Path) -> Result<()> {
+	let staging = tempfile::Builder::new()
+		.suffix(".db")
+		.tempfile()
+		.context("Failed to create a staging file")?
+		.into_temp_path();
+	let mut source = BufReader::new(File::open(backup).with_context(|| format!("Failed to open {:?}", backup))?);
+	let mut staged = File::create(&staging)?;
+	let copied = if backup.to_string_lossy().ends_with(".gz") {
+		io::copy(&mut GzDecoder::new(source), &mut staged)
+	} else {
+		io::copy(&mut source, &mut staged)
+	};
+	copied.with_context(|| format!("Failed to read {:?}", backup))?;
+	drop(staged);
+
+	{
+		let candidate = Connection::open(&staging).with_context(|| format!("{:?} is not a database", backup))?;
+		let integrity: String = candidate.query_row("PRAGMA integrity_check", [], |row| row.get(0))
+			.with_context(|| format!("{:?} is not a database", backup))?;
+		if integrity != "ok" {
+			bail!("{:?} is corrupt: {}", backup, integrity);
+		}
+		let has_vulnerabilities: bool = candidate.query_row(
+			"SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'vulnerabilities')",
+			[],
+			|row| row.get(0),
+		)?;
+		if !has_vulnerabilities {
+			bail!("{:?} is not an RVD database", backup);
+		}
+	}
+
+	conn.restore(DatabaseName::Main, &staging, None::<fn(rusqlite::backup::Progress)>)
+		.context("Failed to restore the database")?;
+	schema::check_schema_version(conn).context("Failed to migrate the restored database")?;
+	info!("Restored the database from {:?}", backup);
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_backup_restore_and_prune() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = connection::establish_pool_with_path(dir.path().join("test.db"))?;
+		let mut conn = pool.get()?;
+		conn.execute(
+			"INSERT INTO vulnerabilities (cve_id, description, severity) VALUES ('CVE-2024-0001', 'Triaged for months', 'High')",
+			[],
+		)?;
+
+		let backups = Backups::new(dir.path().join("backups"));
+		let now = Utc::now();
+		let first = backups.create(&conn, now, None)?;
+		assert!(first.to_string_lossy().ends_with(".db.gz"));
+
+		conn.execute("DELETE FROM vulnerabilities", [])?;
+		restore(&mut conn, &first)?;
+		let count: i64 = conn.query_row("SELECT COUNT(*) FROM vulnerabilities", [], |row| row.get(0))?;
+		assert_eq!(count, 1);
+
+		let not_a_database = dir.path().join("notes.txt");
+		fs::write(&not_a_database, "not a database")?;
+		assert!(restore(&mut conn, &not_a_database).is_err());
+
+		let second = backups.create(&conn, now + Duration::days(1), Some("pre-restore"))?;
+		let third = backups.create(&conn, now + Duration::days(2), None)?;
+		assert_eq!(backups.list()?, [third.clone(), second.clone(), first]);
+		assert_eq!(backups.prune(2)?, 0);
+		assert_eq!(backups.prune(1)?, 1);
+		assert_eq!(backups.list()?, [third, second]);
+
+		let mut policy = BackupPolicy { daily: true, ..BackupPolicy::default() };
+		assert!(policy.is_due(now));
+		policy.last_backup = Some(now - Duration::hours(2));
+		assert!(!policy.is_due(now));
+		Ok(())
+	}
+
+	#[test]
+	fn test_prune_keeps_pre_restore_backup() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = connection::establish_pool_with_path(dir.path().join("test.db"))?;
+		let conn = pool.get()?;
+		let backups = Backups::new(dir.path().join("backups"));
+		let now = Utc::now();
+		let older = backups.create(&conn, now, None)?;
+		let pre_restore = backups.create(&conn, now + Duration::hours(1), Some("pre-restore"))?;
+		let newest = backups.create(&conn, now + Duration::hours(2), None)?;
+
+		// A backup with the smallest retention right after a restore
+		assert_eq!(backups.prune(1)?, 1);
+		assert!(!older.exists());
+		assert_eq!(backups.list()?, [newest, pre_restore]);
+		Ok(())
+	}
+}
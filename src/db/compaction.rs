@@ -0,0 +1,159 @@
+// src/db/compaction.rs
+
+//! Reclaims the space SQLite keeps after large deletes: free pages stay in the file
+//! and the WAL only shrinks on a truncating checkpoint.
+
+use crate::utils::progress::ProgressReporter;
+use anyhow::{Context, Result};
+use log::info;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Share of the file that may be free pages before compaction is worth it
+const FREE_RATIO_THRESHOLD: f64 = 0.25;
+/// Free space below this is not worth a compaction pass, whatever the ratio
+const MIN_RECLAIMABLE_BYTES: u64 = 8 * 1024 * 1024;
+/// A WAL larger than this is compacted regardless of free pages
+const WAL_THRESHOLD_BYTES: u64 = 64 * 1024 * 1024;
+
+/// What to do on startup when the database has grown past the thresholds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompactionMode {
+	/// Ask in the GUI
+	#[default]
+	Prompt,
+	/// Compact without asking
+	Auto,
+	Off,
+}
+
+impl CompactionMode {
+	pub fn from_setting(value: &str) -> Option<Self> {
+		match value.trim().to_ascii_lowercase().as_str() {
+			"prompt" => Some(CompactionMode::Prompt),
+			"auto" => Some(CompactionMode::Auto),
+			"off" => Some(CompactionMode::Off),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for CompactionMode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			CompactionMode::Prompt => "prompt",
+			CompactionMode::Auto => "auto",
+			CompactionMode::Off => "off",
+		})
+	}
+}
+
+/// Size of the database file, its free pages and its WAL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageStats {
+	pub file_bytes: u64,
+	pub free_bytes: u64,
+	pub wal_bytes: u64,
+}
+
+impl StorageStats {
+	/// Space a compaction pass would give back
+	pub fn reclaimable_bytes(&self) -> u64 {
+		self.free_bytes + self.wal_bytes
+	}
+
+	pub fn needs_compaction(&self) -> bool {
+		let free_ratio = self.free_bytes as f64 / self.file_bytes.max(1) as f64;
+		(free_ratio > FREE_RATIO_THRESHOLD && self.free_bytes >= MIN_RECLAIMABLE_BYTES)
+			|| self.wal_bytes > WAL_THRESHOLD_BYTES
+	}
+}
+
+impl fmt::Display for StorageStats {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} database, {} free, {} write-ahead log",
+			megabytes(self.file_bytes),
+			megabytes(self.free_bytes),
+			megabytes(self.wal_bytes)
+		)
+	}
+}
+
+fn megabytes(bytes: u64) -> String {
+	format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+pub fn storage_stats(conn: &Connection) -> Result<StorageStats> {
+	let page_size: u64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+	let page_count: u64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+	let freelist_count: u64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+
+	// In-memory and temporary databases have no WAL file
+	let wal_bytes = conn
+		.path()
+		.filter(|path| !path.is_empty())
+		.and_then(|path| std::fs::metadata(format!("{}-wal", path)).ok())
+		.map_or(0, |metadata| metadata.len());
+
+	Ok(StorageStats {
+		file_bytes: page_size * page_count,
+		free_bytes: page_size * freelist_count,
+		wal_bytes,
+	})
+}
+
+/// Truncate the WAL and rebuild the file without free pages. Needs no other
+/// connection to be writing; returns the sizes afterwards.
+pub fn compact(conn: &Connection, progress: &ProgressReporter) -> Result<StorageStats> {
+	let before = storage_stats(conn)?;
+	let tracker = progress.start("Database compaction");
+
+	conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+		.context("Failed to checkpoint the write-ahead log")?;
+	tracker.update(0, 0.2);
+
+	conn.execute_batch("VACUUM").context("Failed to vacuum the database")?;
+	tracker.update(0, 0.9);
+
+	// VACUUM goes through the WAL as well
+	conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+		.context("Failed to checkpoint the write-ahead log")?;
+
+	let after = storage_stats(conn)?;
+	tracker.finish(0);
+	info!("Compacted database from {} to {}", before, after);
+	Ok(after)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_compact() -> Result<()> {
+		let dir = tempdir()?;
+		let conn = Connection::open(dir.path().join("test.db"))?;
+		conn.execute_batch(
+			"PRAGMA journal_mode = WAL;
+			 CREATE TABLE blobs (data BLOB);
+			 WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 3000)
+			 INSERT INTO blobs SELECT zeroblob(4096) FROM n;
+			 DELETE FROM blobs;
+			 PRAGMA wal_checkpoint(TRUNCATE);"
+		)?;
+
+		let before = storage_stats(&conn)?;
+		assert!(before.needs_compaction(), "{}", before);
+
+		let after = compact(&conn, &ProgressReporter::disabled())?;
+		assert_eq!(after.free_bytes, 0);
+		assert_eq!(after.wal_bytes, 0);
+		assert!(after.file_bytes < before.file_bytes);
+		assert!(!after.needs_compaction());
+		Ok(())
+	}
+}
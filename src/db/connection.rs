@@ -1,15 +1,49 @@
 // src/db/connection.rs
 
 use crate::db::schema;
+use crate::db::workspace::Workspaces;
 use anyhow::{Context, Result};
-use log::{error, info};
+use log::{error, info, warn};
 use r2d2::{Pool, PooledConnection};
 use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{Connection, ErrorCode};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
 pub type SqlitePool = Pool<SqliteConnectionManager>;
 pub type SqliteConnection = PooledConnection<SqliteConnectionManager>;
 
+/// Times a write is run again after finding the database busy or locked
+const WRITE_RETRIES: u32 = 4;
+/// Wait before the first retry, doubled for each further one
+const WRITE_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// How long the last pooled connection was checked out, in microseconds, 0 before the
+/// first checkin
+static LAST_CHECKOUT_MICROS: AtomicU64 = AtomicU64::new(0);
+
+/// Records how long each connection was held, which is how long the query or
+/// transaction run on it took
+#[derive(Debug)]
+struct QueryTimer;
+
+impl r2d2::HandleEvent for QueryTimer {
+	fn handle_checkin(&self, event: r2d2::event::CheckinEvent) {
+		let micros = u64::try_from(event.duration().as_micros()).unwrap_or(u64::MAX).max(1);
+		LAST_CHECKOUT_MICROS.store(micros, Ordering::Relaxed);
+	}
+}
+
+/// Duration of the most recent database call made through a pool, for the GUI's
+/// performance overlay
+pub fn last_query_duration() -> Option<Duration> {
+	match LAST_CHECKOUT_MICROS.load(Ordering::Relaxed) {
+		0 => None,
+		micros => Some(Duration::from_micros(micros)),
+	}
+}
+
 /// Establishes a connection pool with a custom database path
 pub fn establish_pool_with_path(custom_path: PathBuf) -> Result<SqlitePool> {
 	info!("SQLite database will be located at: {:?}", custom_path);
@@ -39,6 +73,7 @@ pub fn establish_pool_with_path(custom_path: PathBuf) -> Result<SqlitePool> {
 		.max_size(15)
 		.min_idle(Some(5))
 		.connection_timeout(std::time::Duration::from_secs(10))
+		.event_handler(Box::new(QueryTimer))
 		.build(manager)
 		.context("Failed to create SQLite connection pool")?;
 
@@ -65,18 +100,10 @@ pub fn establish_pool_with_path(custom_path: PathBuf) -> Result<SqlitePool> {
 	Ok(pool)
 }
 
-/// Establishes a connection pool with a default database path
-pub fn establish_pool() -> Result<SqlitePool> {
-	let default_path = get_default_db_path();
-	establish_pool_with_path(default_path)
-}
-
-/// Gets the default database path
-fn get_default_db_path() -> PathBuf {
-	let mut db_path = PathBuf::from(".");
-	db_path.push("database");
-	db_path.push("vulnerabilities.db");
-	db_path
+/// Establishes a connection pool for a named workspace, creating its database if needed
+pub fn establish_pool(workspace: &str) -> Result<SqlitePool> {
+	let path = Workspaces::default().database_path(workspace)?;
+	establish_pool_with_path(path)
 }
 
 /// Helper function to get a connection from the pool with proper error context
@@ -85,6 +112,42 @@ pub fn get_conn(pool: &SqlitePool) -> Result<SqliteConnection> {
 		.context("Failed to get database connection from pool")
 }
 
+/// Whether `error` comes from SQLite finding the database busy or locked by another connection
+pub fn is_busy(error: &anyhow::Error) -> bool {
+	error.chain().any(|cause| {
+		matches!(
+			cause.downcast_ref::<rusqlite::Error>(),
+			Some(rusqlite::Error::SqliteFailure(e, _)) if matches!(e.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+		)
+	})
+}
+
+/// Runs the write `op` on a pooled connection, running it again with a growing delay while
+/// another connection, e.g. a sync next to an edit in the GUI, keeps the database locked.
+/// The busy timeout does not cover a read transaction that turns into a write, which fails
+/// at once. `op` must leave no partial changes when it fails, so it writes in a transaction
+/// or with a single statement.
+pub fn with_write_retry<T>(pool: &SqlitePool, mut op: impl FnMut(&mut Connection) -> Result<T>) -> Result<T> {
+	let mut conn = get_conn(pool)?;
+	let mut retries = 0;
+	loop {
+		match op(&mut conn) {
+			Err(e) if is_busy(&e) => {
+				if retries == WRITE_RETRIES {
+					return Err(e.context(
+						"The database is still locked by another operation, such as a running import; try again once it finishes",
+					));
+				}
+				let delay = WRITE_RETRY_DELAY * 2u32.pow(retries);
+				retries += 1;
+				warn!("Database busy, retrying the write in {:?} ({}/{})", delay, retries, WRITE_RETRIES);
+				std::thread::sleep(delay);
+			}
+			result => return result,
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use std::thread;
@@ -136,10 +199,12 @@ mod tests {
 				let pool = pool.clone();
 				std::thread::spawn(move || {
 					let conn = pool.get().unwrap();
-					conn.execute(
-						"SELECT * FROM schema_version",
+					let version: i32 = conn.query_row(
+						"SELECT MAX(version) FROM schema_version",
 						[],
+						|row| row.get(0)
 					).unwrap();
+					assert_eq!(version, schema::SCHEMA_VERSION);
 					i
 				})
 			})
@@ -173,9 +238,39 @@ mod tests {
 	}
 
 	#[test]
-	fn test_default_path() {
-		let path = get_default_db_path();
+	fn test_write_retry() -> Result<()> {
+		let temp_dir = tempdir()?;
+		let pool = Pool::builder()
+			.max_size(1)
+			.build(SqliteConnectionManager::file(temp_dir.path().join("retry_test.db")))?;
+		let busy = || anyhow::Error::new(rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY), None))
+			.context("Failed to update vulnerability");
+
+		let mut attempts = 0;
+		let value = with_write_retry(&pool, |_| {
+			attempts += 1;
+			if attempts < 3 { Err(busy()) } else { Ok(attempts) }
+		})?;
+		assert_eq!(value, 3);
+
+		let err = with_write_retry(&pool, |_| -> Result<()> { Err(busy()) }).unwrap_err();
+		assert!(err.to_string().contains("still locked") && is_busy(&err));
+
+		attempts = 0;
+		let err = with_write_retry(&pool, |_| -> Result<()> {
+			attempts += 1;
+			anyhow::bail!("constraint failed")
+		}).unwrap_err();
+		assert!(!is_busy(&err));
+		assert_eq!(attempts, 1);
+		Ok(())
+	}
+
+	#[test]
+	fn test_default_path() -> Result<()> {
+		let path = Workspaces::default().database_path(crate::db::workspace::DEFAULT_WORKSPACE)?;
 		assert!(path.ends_with("database/vulnerabilities.db"));
+		Ok(())
 	}
 
 	#[test]
@@ -0,0 +1,196 @@
+// src/db/maintenance.rs
+
+//! Routine upkeep of a workspace database, which passes a gigabyte with full NVD data:
+//! an integrity check, fresh planner statistics and an incremental vacuum giving back
+//! the pages freed since the last run.
+
+use crate::db::compaction::{self, StorageStats};
+use crate::db::connection::{self, SqlitePool};
+use crate::repositories::settings_repo::SettingsRepository;
+use crate::utils::progress::ProgressReporter;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use tokio::task;
+
+/// Scheduled maintenance runs at least this far apart
+const MAINTENANCE_INTERVAL_DAYS: i64 = 7;
+/// `PRAGMA auto_vacuum` value that lets free pages be released on demand
+const AUTO_VACUUM_INCREMENTAL: i64 = 2;
+/// Integrity problems kept in a report; the check stops there as well
+const MAX_PROBLEMS: usize = 20;
+
+/// Whether maintenance runs weekly while the GUI is open, with the outcome of the last run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MaintenancePolicy {
+	pub weekly: bool,
+	pub last_report: Option<MaintenanceReport>,
+}
+
+impl MaintenancePolicy {
+	pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+		self.weekly
+			&& self.last_report
+				.as_ref()
+				.is_none_or(|report| now - report.finished_at >= Duration::days(MAINTENANCE_INTERVAL_DAYS))
+	}
+}
+
+/// Outcome of one maintenance run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceReport {
+	pub finished_at: DateTime<Utc>,
+	/// Problems found by the integrity check; empty when the database is intact
+	pub problems: Vec<String>,
+	pub before: StorageStats,
+	pub after: StorageStats,
+}
+
+impl MaintenanceReport {
+	pub fn is_intact(&self) -> bool {
+		self.problems.is_empty()
+	}
+
+	pub fn reclaimed_bytes(&self) -> u64 {
+		self.before.file_bytes.saturating_sub(self.after.file_bytes)
+	}
+}
+
+impl fmt::Display for MaintenanceReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if !self.is_intact() {
+			return write!(
+				f,
+				"Integrity check failed with {} problems (first: {}); the database was left as is, restore a backup",
+				self.problems.len(),
+				self.problems[0]
+			);
+		}
+		write!(
+			f,
+			"Integrity check passed, statistics updated, {:.1} MB reclaimed; now {}",
+			self.reclaimed_bytes() as f64 / (1024.0 * 1024.0),
+			self.after
+		)
+	}
+}
+
+/// Checks the integrity of the database behind `conn` and, when it is intact, refreshes
+/// the query planner statistics and releases free pages. The first run on a database
+/// rebuilds it once to enable incremental vacuuming.
+pub fn run(conn: &Connection, progress: &ProgressReporter) -> Result<MaintenanceReport> {
+	let before = compaction::storage_stats(conn)?;
+	let tracker = progress.start("Database maintenance");
+
+	let problems: Vec<String> = conn
+		.prepare(&format!("PRAGMA integrity_check({})", MAX_PROBLEMS))?
+		.query_map([], |row| row.get::<_, String>(0))?
+		.collect::<rusqlite::Result<Vec<_>>>()
+		.context("Failed to check the database integrity")?
+		.into_iter()
+		.filter(|line| line != "ok")
+		.collect();
+	tracker.update(0, 0.5);
+
+	if problems.is_empty() {
+		conn.execute_batch("ANALYZE").context("Failed to update the planner statistics")?;
+		tracker.update(0, 0.6);
+
+		let auto_vacuum: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?;
+		if auto_vacuum == AUTO_VACUUM_INCREMENTAL {
+			// Each step of the pragma releases one page
+			let mut stmt = conn.prepare("PRAGMA incremental_vacuum")?;
+			let mut rows = stmt.query([])?;
+			while rows.next().context("Failed to vacuum the database")?.is_some() {}
+		} else {
+			// The mode only takes effect on a rebuilt file
+			conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;")
+				.context("Failed to enable incremental vacuuming")?;
+		}
+		conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+			.context("Failed to checkpoint the write-ahead log")?;
+	} else {
+		warn!("Database integrity check found {} problems: {}", problems.len(), problems.join("; "));
+	}
+
+	let report = MaintenanceReport {
+		finished_at: Utc::now(),
+		problems,
+		before,
+		after: compaction::storage_stats(conn)?,
+	};
+	tracker.finish(0);
+	info!("Database maintenance finished: {}", report);
+	Ok(report)
+}
+
+/// Runs maintenance off the async workers and records the report as the last run
+pub async fn run_maintenance(pool: Arc<SqlitePool>, progress: ProgressReporter) -> Result<MaintenanceReport> {
+	let settings = SettingsRepository::new(pool.clone());
+	let report = task::spawn_blocking(move || {
+		let conn = connection::get_conn(&pool)?;
+		run(&conn, &progress)
+	})
+		.await
+		.context("Failed to execute database operation")??;
+	let mut policy = settings.get_maintenance_policy().await?;
+	policy.last_report = Some(report.clone());
+	settings.set_maintenance_policy(&policy).await?;
+	Ok(report)
+}
+
+/// Runs the weekly maintenance when enabled and a week has passed since the last run
+pub async fn run_if_due(pool: Arc<SqlitePool>, progress: ProgressReporter) -> Result<Option<MaintenanceReport>> {
+	let policy = SettingsRepository::new(pool.clone()).get_maintenance_policy().await?;
+	if !policy.is_due(Utc::now()) {
+		return Ok(None);
+	}
+	run_maintenance(pool, progress).await.map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_maintenance() -> Result<()> {
+		let dir = tempdir()?;
+		let conn = Connection::open(dir.path().join("test.db"))?;
+		conn.execute_batch(
+			"PRAGMA journal_mode = WAL;
+			 CREATE TABLE blobs (data BLOB);
+			 WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 1000)
+			 INSERT INTO blobs SELECT zeroblob(4096) FROM n;
+			 DELETE FROM blobs;"
+		)?;
+
+		let first = run(&conn, &ProgressReporter::disabled())?;
+		assert!(first.is_intact());
+		assert_eq!(first.after.free_bytes, 0);
+		assert!(first.reclaimed_bytes() > 0);
+		let auto_vacuum: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?;
+		assert_eq!(auto_vacuum, AUTO_VACUUM_INCREMENTAL);
+
+		// Later runs release freed pages without a rebuild
+		conn.execute_batch(
+			"WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 500)
+			 INSERT INTO blobs SELECT zeroblob(4096) FROM n;
+			 DELETE FROM blobs;"
+		)?;
+		let second = run(&conn, &ProgressReporter::disabled())?;
+		assert_eq!(second.after.free_bytes, 0);
+		assert!(second.reclaimed_bytes() > 0);
+
+		let mut policy = MaintenancePolicy { weekly: true, last_report: None };
+		assert!(policy.is_due(second.finished_at));
+		policy.last_report = Some(second.clone());
+		assert!(!policy.is_due(second.finished_at + Duration::days(1)));
+		assert!(policy.is_due(second.finished_at + Duration::days(7)));
+		Ok(())
+	}
+}
@@ -1,4 +1,11 @@
 // src/db/mod.rs
 
+pub mod backup;
+pub mod compaction;
 pub mod connection;
+pub mod maintenance;
+#[cfg(feature = "postgres")]
+pub mod postgres;
 pub mod schema;
+pub mod storage;
+pub mod workspace;
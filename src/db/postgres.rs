@@ -0,0 +1,509 @@
+// src/db/postgres.rs
+
+//! Shared PostgreSQL server database, built with the `postgres` feature. It holds the
+//! vulnerabilities with their triage state and the robot inventory, filled from a local
+//! workspace with `rvd push-database`; imports and enrichment keep running locally.
+
+use crate::db::storage::{like_prefix, ShellStorage, CVE_ID_ORDER};
+use crate::models::robot::{Criticality, Robot};
+use crate::models::vulnerability::{Alias, CvssVersion, RiskAcceptance, TriageStatus, Vulnerability};
+use crate::repositories::access;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use log::info;
+use postgres::Row;
+use postgres_native_tls::MakeTlsConnector;
+use r2d2::Pool;
+use r2d2_postgres::PostgresConnectionManager;
+use std::sync::Arc;
+use tokio::task;
+
+pub type PostgresPool = Pool<PostgresConnectionManager<MakeTlsConnector>>;
+
+const SCHEMA_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS vulnerabilities (
+		vulnerability_id BIGSERIAL PRIMARY KEY,
+		cve_id TEXT NOT NULL UNIQUE,
+		description TEXT,
+		severity TEXT NOT NULL,
+		impact TEXT,
+		mitigation TEXT,
+		published_date DATE,
+		cvss_score DOUBLE PRECISION,
+		cvss_version TEXT,
+		kev_date_added DATE,
+		source TEXT
+	);
+
+	CREATE TABLE IF NOT EXISTS vulnerability_status (
+		vulnerability_id BIGINT PRIMARY KEY REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE,
+		status TEXT NOT NULL,
+		assigned_to TEXT,
+		updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+		justification TEXT,
+		approved_by TEXT,
+		accepted_at TIMESTAMPTZ,
+		expires_on DATE
+	);
+
+	CREATE TABLE IF NOT EXISTS vulnerability_weaknesses (
+		vulnerability_id BIGINT NOT NULL REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE,
+		cwe_id TEXT NOT NULL,
+		PRIMARY KEY (vulnerability_id, cwe_id)
+	);
+
+	CREATE TABLE IF NOT EXISTS vulnerability_aliases (
+		alias TEXT PRIMARY KEY,
+		vulnerability_id BIGINT NOT NULL REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE,
+		source TEXT NOT NULL
+	);
+
+	CREATE TABLE IF NOT EXISTS robots (
+		robot_id SERIAL PRIMARY KEY,
+		name TEXT NOT NULL UNIQUE,
+		specifications TEXT,
+		manufacturer TEXT,
+		model TEXT,
+		firmware_version TEXT,
+		os TEXT,
+		ros_distro TEXT,
+		operational_note TEXT,
+		risk_score DOUBLE PRECISION,
+		criticality TEXT NOT NULL DEFAULT 'Medium'
+	);";
+
+/// Columns read by `vulnerability_from_row`, for `vulnerabilities v` with `STATUS_JOIN`
+const VULNERABILITY_COLUMNS: &str = "
+	v.vulnerability_id, v.cve_id, v.description, v.severity, v.impact, v.mitigation, v.published_date,
+	v.cvss_score, COALESCE(s.status, 'Open'), s.assigned_to, s.justification, s.approved_by, s.accepted_at,
+	s.expires_on, v.kev_date_added,
+	(SELECT string_agg(w.cwe_id, ' ' ORDER BY w.cwe_id) FROM vulnerability_weaknesses w
+	 WHERE w.vulnerability_id = v.vulnerability_id),
+	v.cvss_version, v.source,
+	(SELECT string_agg(a.alias || ' ' || a.source, E'\\n' ORDER BY a.alias) FROM vulnerability_aliases a
+	 WHERE a.vulnerability_id = v.vulnerability_id)";
+
+const STATUS_JOIN: &str = "LEFT JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id";
+
+const ROBOT_COLUMNS: &str =
+	"robot_id, name, specifications, manufacturer, model, firmware_version, os, ros_distro, operational_note, risk_score, criticality";
+
+fn vulnerability_from_row(row: &Row) -> Result<Vulnerability> {
+	let status = TriageStatus::from_db(&row.try_get::<_, String>(8)?);
+	let risk_acceptance = if status.is_risk_decision() {
+		Some(RiskAcceptance {
+			justification: row.try_get(10)?,
+			approved_by: row.try_get(11)?,
+			accepted_at: row.try_get::<_, Option<DateTime<Utc>>>(12)?,
+			expires_on: row.try_get::<_, Option<NaiveDate>>(13)?,
+		})
+	} else {
+		None
+	};
+
+	Ok(Vulnerability {
+		vulnerability_id: row.try_get(0)?,
+		cve_id: row.try_get(1)?,
+		description: row.try_get(2)?,
+		severity: row.try_get(3)?,
+		impact: row.try_get(4)?,
+		mitigation: row.try_get(5)?,
+		published_date: row.try_get(6)?,
+		cvss_score: row.try_get(7)?,
+		cvss_version: row.try_get::<_, Option<String>>(16)?.and_then(|version| CvssVersion::from_db(&version)),
+		status,
+		assigned_to: row.try_get(9)?,
+		risk_acceptance,
+		kev_date_added: row.try_get(14)?,
+		cwe_ids: row.try_get::<_, Option<String>>(15)?
+			.map(|ids| ids.split_whitespace().map(str::to_string).collect())
+			.unwrap_or_default(),
+		source: row.try_get(17)?,
+		aliases: row.try_get::<_, Option<String>>(18)?
+			.map(|aliases| {
+				aliases
+					.lines()
+					.filter_map(|line| line.split_once(' '))
+					.map(|(id, source)| Alias { id: id.to_string(), source: source.to_string() })
+					.collect()
+			})
+			.unwrap_or_default(),
+		// Manual edit locks and field sources stay in the local workspace
+		locks: Vec::new(),
+		provenance: Vec::new(),
+	})
+}
+
+fn robot_from_row(row: &Row) -> Result<Robot> {
+	Ok(Robot {
+		robot_id: row.try_get(0)?,
+		name: row.try_get(1)?,
+		specifications: row.try_get(2)?,
+		manufacturer: row.try_get(3)?,
+		model: row.try_get(4)?,
+		firmware_version: row.try_get(5)?,
+		os: row.try_get(6)?,
+		ros_distro: row.try_get(7)?,
+		operational_note: row.try_get(8)?,
+		risk_score: row.try_get(9)?,
+		criticality: Criticality::parse(&row.try_get::<_, String>(10)?),
+		// Inventory freshness is tracked by the local workspace
+		inventory_refreshed_at: None,
+		inventory_source: None,
+	})
+}
+
+/// Entries written by a push
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PushSummary {
+	pub vulnerabilities: usize,
+	pub robots: usize,
+}
+
+/// A pool closed on a thread of its own when dropped, since closing the blocking
+/// client's connections starts their runtimes, which panics on an async worker
+struct ServerPool(Option<PostgresPool>);
+
+impl std::ops::Deref for ServerPool {
+	type Target = PostgresPool;
+
+	fn deref(&self) -> &PostgresPool {
+		self.0.as_ref().expect("The pool is only taken when dropped")
+	}
+}
+
+impl Drop for ServerPool {
+	fn drop(&mut self) {
+		if let Some(pool) = self.0.take() {
+			let _ = std::thread::spawn(move || drop(pool)).join();
+		}
+	}
+}
+
+pub struct PostgresStorage {
+	pool: Arc<ServerPool>,
+}
+
+impl PostgresStorage {
+	/// Connects to the server at `url` and creates the tables that do not exist yet.
+	/// TLS is used as the URL's `sslmode` asks, e.g. `?sslmode=require`.
+	pub fn connect(url: &str) -> Result<Self> {
+		let config: postgres::Config = url.parse().context("Invalid PostgreSQL URL")?;
+		let tls = MakeTlsConnector::new(native_tls::TlsConnector::new().context("Failed to set up TLS")?);
+		// The blocking client starts a runtime of its own, which panics on an async worker
+		let pool = std::thread::scope(|scope| {
+			scope.spawn(|| -> Result<PostgresPool> {
+				let pool = Pool::builder()
+					.max_size(5)
+					.connection_timeout(std::time::Duration::from_secs(10))
+					.build(PostgresConnectionManager::new(config, tls))
+					.context("Failed to connect to the PostgreSQL server")?;
+				pool.get()
+					.context("Failed to get database connection")?
+					.batch_execute(SCHEMA_SQL)
+					.context("Failed to initialize the PostgreSQL schema")?;
+				Ok(pool)
+			}).join().map_err(|_| anyhow::anyhow!("Connecting to the PostgreSQL server panicked"))?
+		})?;
+		info!("Connected to the PostgreSQL server database");
+		Ok(Self { pool: Arc::new(ServerPool(Some(pool))) })
+	}
+
+	/// Runs `query` on a pooled connection off the async workers
+	async fn with_client<T, F>(&self, query: F) -> Result<T>
+	where
+		T: Send + 'static,
+		F: FnOnce(&mut postgres::Client) -> Result<T> + Send + 'static,
+	{
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let mut client = pool.get().context("Failed to get database connection")?;
+			query(&mut client)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Writes the vulnerabilities, with triage state, weaknesses and aliases, and the
+	/// robots of a local workspace. Entries already on the server are overwritten;
+	/// vulnerabilities are matched by CVE ID and robots by name.
+	pub async fn push(&self, vulnerabilities: Vec<Vulnerability>, robots: Vec<Robot>) -> Result<PushSummary> {
+		access::require_write_access()?;
+		self.with_client(move |client| {
+			let mut tx = client.transaction()?;
+			for vuln in &vulnerabilities {
+				let row = tx.query_one(
+					"INSERT INTO vulnerabilities
+						(cve_id, description, severity, impact, mitigation, published_date, cvss_score, cvss_version, kev_date_added, source)
+					 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+					 ON CONFLICT (cve_id) DO UPDATE SET
+						description = excluded.description, severity = excluded.severity, impact = excluded.impact,
+						mitigation = excluded.mitigation, published_date = excluded.published_date,
+						cvss_score = excluded.cvss_score, cvss_version = excluded.cvss_version,
+						kev_date_added = excluded.kev_date_added, source = excluded.source
+					 RETURNING vulnerability_id",
+					&[
+						&vuln.cve_id,
+						&vuln.description,
+						&vuln.severity,
+						&vuln.impact,
+						&vuln.mitigation,
+						&vuln.published_date,
+						&vuln.cvss_score,
+						&vuln.cvss_version.map(|v| v.as_str()),
+						&vuln.kev_date_added,
+						&vuln.source,
+					],
+				).with_context(|| format!("Failed to push {}", vuln.cve_id))?;
+				let id: i64 = row.get(0);
+
+				let acceptance = vuln.risk_acceptance.clone().unwrap_or_default();
+				tx.execute(
+					"INSERT INTO vulnerability_status
+						(vulnerability_id, status, assigned_to, justification, approved_by, accepted_at, expires_on)
+					 VALUES ($1, $2, $3, $4, $5, $6, $7)
+					 ON CONFLICT (vulnerability_id) DO UPDATE SET
+						status = excluded.status, assigned_to = excluded.assigned_to, updated_at = now(),
+						justification = excluded.justification, approved_by = excluded.approved_by,
+						accepted_at = excluded.accepted_at, expires_on = excluded.expires_on",
+					&[
+						&id,
+						&vuln.status.as_str(),
+						&vuln.assigned_to,
+						&acceptance.justification,
+						&acceptance.approved_by,
+						&acceptance.accepted_at,
+						&acceptance.expires_on,
+					],
+				)?;
+				for cwe_id in &vuln.cwe_ids {
+					tx.execute(
+						"INSERT INTO vulnerability_weaknesses (vulnerability_id, cwe_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+						&[&id, cwe_id],
+					)?;
+				}
+				for alias in &vuln.aliases {
+					tx.execute(
+						"INSERT INTO vulnerability_aliases (alias, vulnerability_id, source) VALUES ($1, $2, $3)
+						 ON CONFLICT (alias) DO UPDATE SET vulnerability_id = excluded.vulnerability_id, source = excluded.source",
+						&[&alias.id, &id, &alias.source],
+					)?;
+				}
+			}
+
+			for robot in &robots {
+				tx.execute(
+					"INSERT INTO robots
+						(name, specifications, manufacturer, model, firmware_version, os, ros_distro, operational_note, risk_score, criticality)
+					 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+					 ON CONFLICT (name) DO UPDATE SET
+						specifications = excluded.specifications, manufacturer = excluded.manufacturer,
+						model = excluded.model, firmware_version = excluded.firmware_version, os = excluded.os,
+						ros_distro = excluded.ros_distro, operational_note = excluded.operational_note,
+						risk_score = excluded.risk_score, criticality = excluded.criticality",
+					&[
+						&robot.name,
+						&robot.specifications,
+						&robot.manufacturer,
+						&robot.model,
+						&robot.firmware_version,
+						&robot.os,
+						&robot.ros_distro,
+						&robot.operational_note,
+						&robot.risk_score,
+						&robot.criticality.as_str(),
+					],
+				).with_context(|| format!("Failed to push robot {}", robot.name))?;
+			}
+
+			tx.commit().context("Failed to commit the push")?;
+			Ok(PushSummary { vulnerabilities: vulnerabilities.len(), robots: robots.len() })
+		})
+			.await
+	}
+}
+
+#[async_trait]
+impl ShellStorage for PostgresStorage {
+	async fn search_vulnerabilities(&self, text: &str, limit: usize) -> Result<(Vec<Vulnerability>, bool)> {
+		let pattern = format!("%{}%", text.trim().replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+		self.with_client(move |client| {
+			let rows = client.query(
+				&format!(
+					"SELECT {} FROM vulnerabilities v {}
+					 WHERE v.cve_id ILIKE $1 OR v.description ILIKE $1
+						OR EXISTS (SELECT 1 FROM vulnerability_aliases a WHERE a.vulnerability_id = v.vulnerability_id AND a.alias ILIKE $1)
+					 ORDER BY v.published_date DESC NULLS LAST, v.cve_id DESC
+					 LIMIT $2",
+					VULNERABILITY_COLUMNS, STATUS_JOIN
+				),
+				&[&pattern, &(limit as i64 + 1)],
+			)?;
+			let more = rows.len() > limit;
+			let vulnerabilities = rows.iter().take(limit).map(vulnerability_from_row).collect::<Result<Vec<_>>>()?;
+			Ok((vulnerabilities, more))
+		})
+			.await
+	}
+
+	async fn get_vulnerability_by_cve(&self, cve_id: &str) -> Result<Option<Vulnerability>> {
+		let cve_id = cve_id.to_string();
+		self.with_client(move |client| {
+			client
+				.query_opt(
+					&format!("SELECT {} FROM vulnerabilities v {} WHERE upper(v.cve_id) = upper($1)", VULNERABILITY_COLUMNS, STATUS_JOIN),
+					&[&cve_id],
+				)?
+				.as_ref()
+				.map(vulnerability_from_row)
+				.transpose()
+		})
+			.await
+	}
+
+	async fn update_triage(
+		&self,
+		vulnerability_id: i64,
+		status: TriageStatus,
+		assigned_to: Option<String>,
+		acceptance: RiskAcceptance,
+	) -> Result<()> {
+		access::require_write_access()?;
+		acceptance.validate(status)?;
+		self.with_client(move |client| {
+			let assigned_to = assigned_to.filter(|a| !a.trim().is_empty());
+			let acceptance = if status.is_risk_decision() { acceptance } else { RiskAcceptance::default() };
+			let trimmed = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+			client.execute(
+				"INSERT INTO vulnerability_status
+					(vulnerability_id, status, assigned_to, updated_at, justification, approved_by, accepted_at, expires_on)
+				 VALUES ($1, $2, $3, now(), $4, $5, CASE WHEN $6 THEN now() END, $7)
+				 ON CONFLICT (vulnerability_id) DO UPDATE SET
+					status = excluded.status,
+					assigned_to = excluded.assigned_to,
+					updated_at = excluded.updated_at,
+					justification = excluded.justification,
+					approved_by = excluded.approved_by,
+					accepted_at = excluded.accepted_at,
+					expires_on = excluded.expires_on",
+				&[
+					&vulnerability_id,
+					&status.as_str(),
+					&assigned_to,
+					&trimmed(acceptance.justification),
+					&trimmed(acceptance.approved_by),
+					&status.is_risk_decision(),
+					&acceptance.expires_on,
+				],
+			).context("Failed to update triage status")?;
+			Ok(())
+		})
+			.await
+	}
+
+	async fn get_all_robots(&self) -> Result<Vec<Robot>> {
+		self.with_client(|client| {
+			client
+				.query(&format!("SELECT {} FROM robots ORDER BY lower(name)", ROBOT_COLUMNS), &[])?
+				.iter()
+				.map(robot_from_row)
+				.collect()
+		})
+			.await
+	}
+
+	fn cve_ids_with_prefix(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+		let mut client = self.pool.get().context("Failed to get database connection")?;
+		Ok(client
+			.query(
+				&format!("SELECT cve_id FROM vulnerabilities WHERE cve_id LIKE $1 ESCAPE '\\' ORDER BY {} LIMIT $2", CVE_ID_ORDER),
+				&[&like_prefix(prefix), &(limit as i64)],
+			)?
+			.iter()
+			.map(|row| row.get(0))
+			.collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::vulnerability::Alias;
+
+	/// Server database the test writes to, e.g. `postgres://postgres@localhost/rvd_test`;
+	/// the test is skipped when it is not set
+	const TEST_DATABASE_URL_ENV: &str = "RVD_TEST_DATABASE_URL";
+
+	#[tokio::test]
+	async fn test_postgres_storage() -> Result<()> {
+		let Ok(url) = std::env::var(TEST_DATABASE_URL_ENV) else {
+			eprintln!("Skipping the PostgreSQL test, {} is not set", TEST_DATABASE_URL_ENV);
+			return Ok(());
+		};
+		let storage = Arc::new(PostgresStorage::connect(&url)?);
+		// Connecting again keeps the tables created by the first connection
+		PostgresStorage::connect(&url)?;
+
+		let mut vuln = Vulnerability {
+			description: Some("Buffer overflow in the ROS bridge".to_string()),
+			published_date: NaiveDate::from_ymd_opt(1999, 1, 2),
+			cvss_score: Some(8.1),
+			cvss_version: Some(CvssVersion::V31),
+			status: TriageStatus::InProgress,
+			assigned_to: Some("ana".to_string()),
+			cwe_ids: vec!["CWE-120".to_string()],
+			aliases: vec![Alias { id: "GHSA-xxxx-9001".to_string(), source: "GHSA".to_string() }],
+			..Vulnerability::new("CVE-1999-9001".to_string(), "High".to_string())
+		};
+		let robot = Robot {
+			ros_distro: Some("ROS 2 Humble".to_string()),
+			operational_note: Some("Cell 4".to_string()),
+			criticality: Criticality::High,
+			..Robot::new("pg-test-arm-01".to_string())
+		};
+		let summary = storage.push(vec![vuln.clone()], vec![robot.clone()]).await?;
+		assert_eq!((summary.vulnerabilities, summary.robots), (1, 1));
+		// Pushing again overwrites instead of duplicating
+		vuln.severity = "Critical".to_string();
+		storage.push(vec![vuln], vec![robot]).await?;
+
+		let stored = storage.get_vulnerability_by_cve("cve-1999-9001").await?.unwrap();
+		assert_eq!(stored.severity, "Critical");
+		assert_eq!(stored.status, TriageStatus::InProgress);
+		assert_eq!(stored.assigned_to.as_deref(), Some("ana"));
+		assert_eq!(stored.published_date, NaiveDate::from_ymd_opt(1999, 1, 2));
+		assert_eq!(stored.cvss_version, Some(CvssVersion::V31));
+		assert_eq!(stored.cwe_ids, ["CWE-120"]);
+		assert_eq!(stored.aliases[0].id, "GHSA-xxxx-9001");
+
+		let (found, _) = storage.search_vulnerabilities("ghsa-xxxx-9001", 10).await?;
+		assert_eq!(found.iter().map(|v| v.cve_id.as_str()).collect::<Vec<_>>(), ["CVE-1999-9001"]);
+		let completer = storage.clone();
+		let completions = task::spawn_blocking(move || {
+			let matching = completer.cve_ids_with_prefix("CVE-1999-900", 5)?;
+			// `_` in the prefix is not a wildcard
+			let escaped = completer.cve_ids_with_prefix("CVE-1999-900_", 5)?;
+			anyhow::Ok((matching, escaped))
+		}).await??;
+		assert_eq!(completions, (vec!["CVE-1999-9001".to_string()], Vec::new()));
+
+		let acceptance = RiskAcceptance {
+			justification: Some("Robot is air-gapped".to_string()),
+			approved_by: Some("Plant manager".to_string()),
+			..Default::default()
+		};
+		storage.update_triage(stored.vulnerability_id.unwrap(), TriageStatus::AcceptedRisk, None, acceptance).await?;
+		let accepted = storage.get_vulnerability_by_cve("CVE-1999-9001").await?.unwrap();
+		assert_eq!(accepted.status, TriageStatus::AcceptedRisk);
+		let acceptance = accepted.risk_acceptance.unwrap();
+		assert_eq!(acceptance.approved_by.as_deref(), Some("Plant manager"));
+		assert!(acceptance.accepted_at.is_some());
+
+		let robots = storage.get_all_robots().await?;
+		let robot = robots.iter().find(|r| r.name == "pg-test-arm-01").unwrap();
+		assert_eq!(robot.criticality, Criticality::High);
+		assert_eq!(robot.operational_note.as_deref(), Some("Cell 4"));
+		Ok(())
+	}
+}
@@ -4,14 +4,452 @@ use rusqlite::{Connection, params};
 use anyhow::{Result, Context};
 use log::{info, warn};
 
+/// Schema version reached once all migrations have been applied
+pub const SCHEMA_VERSION: i32 = 44;
+
+/// Columns holding timestamps, stored as UTC RFC 3339 strings
+const TIMESTAMP_COLUMNS: &[(&str, &str)] = &[
+	("schema_version", "installed_on"),
+	("robots", "created_at"),
+	("robots", "updated_at"),
+	("robot_software", "installed_date"),
+	("vulnerability_status", "updated_at"),
+	("notes", "created_at"),
+	("notes", "updated_at"),
+	("enrichment_runs", "started_at"),
+	("enrichment_runs", "finished_at"),
+	("enrichment_attempts", "attempted_at"),
+];
+
+/// CWE weakness IDs of each vulnerability, e.g. `CWE-787`, as assigned by the NVD
+const WEAKNESSES_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS vulnerability_weaknesses (
+		vulnerability_id INTEGER NOT NULL,
+		cwe_id TEXT NOT NULL,
+		PRIMARY KEY (vulnerability_id, cwe_id),
+		FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE
+	);
+";
+
+/// Daily snapshots of fleet-wide metrics, one value per metric and UTC day
+const METRICS_HISTORY_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS metrics_history (
+		recorded_on TEXT NOT NULL,
+		metric TEXT NOT NULL,
+		value REAL NOT NULL,
+		PRIMARY KEY (recorded_on, metric)
+	);
+";
+
+/// Daily snapshots of open exposures and robot risk scores as JSON, one per UTC day,
+/// compared by the diff report
+const REPORT_SNAPSHOTS_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS report_snapshots (
+		taken_on TEXT PRIMARY KEY,
+		content TEXT NOT NULL
+	);
+";
+
+/// Other IDs of a vulnerability in other feeds, such as GHSA, OSV or RVD IDs of a
+/// CVE, with the feed that uses them. Entries stored under an alias are merged into
+/// the vulnerability it names.
+const ALIASES_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS vulnerability_aliases (
+		alias TEXT PRIMARY KEY,
+		vulnerability_id INTEGER NOT NULL,
+		source TEXT NOT NULL,
+		FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE
+	);
+	CREATE INDEX IF NOT EXISTS idx_vulnerability_aliases ON vulnerability_aliases(vulnerability_id);
+";
+
+/// Fields of a vulnerability edited by hand, with the edited value and the editor. The
+/// triggers put a locked value back whenever an import or NVD refresh overwrites it;
+/// the severity change alert skips locked severities, which only change by hand.
+const FIELD_LOCKS_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS field_locks (
+		vulnerability_id INTEGER NOT NULL,
+		field TEXT NOT NULL CHECK (field IN ('description', 'severity', 'impact', 'mitigation')),
+		value TEXT,
+		locked_by TEXT NOT NULL,
+		locked_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+		PRIMARY KEY (vulnerability_id, field),
+		FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE
+	);
+
+	CREATE TRIGGER IF NOT EXISTS keep_locked_description AFTER UPDATE OF description ON vulnerabilities
+	WHEN EXISTS (
+		SELECT 1 FROM field_locks l
+		WHERE l.vulnerability_id = NEW.vulnerability_id AND l.field = 'description' AND l.value IS NOT NEW.description
+	)
+	BEGIN
+		UPDATE vulnerabilities SET description = (
+			SELECT value FROM field_locks WHERE vulnerability_id = NEW.vulnerability_id AND field = 'description'
+		) WHERE vulnerability_id = NEW.vulnerability_id;
+	END;
+
+	CREATE TRIGGER IF NOT EXISTS keep_locked_severity AFTER UPDATE OF severity ON vulnerabilities
+	WHEN EXISTS (
+		SELECT 1 FROM field_locks l
+		WHERE l.vulnerability_id = NEW.vulnerability_id AND l.field = 'severity' AND l.value IS NOT NEW.severity
+	)
+	BEGIN
+		UPDATE vulnerabilities SET severity = (
+			SELECT value FROM field_locks WHERE vulnerability_id = NEW.vulnerability_id AND field = 'severity'
+		) WHERE vulnerability_id = NEW.vulnerability_id;
+	END;
+
+	CREATE TRIGGER IF NOT EXISTS keep_locked_impact AFTER UPDATE OF impact ON vulnerabilities
+	WHEN EXISTS (
+		SELECT 1 FROM field_locks l
+		WHERE l.vulnerability_id = NEW.vulnerability_id AND l.field = 'impact' AND l.value IS NOT NEW.impact
+	)
+	BEGIN
+		UPDATE vulnerabilities SET impact = (
+			SELECT value FROM field_locks WHERE vulnerability_id = NEW.vulnerability_id AND field = 'impact'
+		) WHERE vulnerability_id = NEW.vulnerability_id;
+	END;
+
+	CREATE TRIGGER IF NOT EXISTS keep_locked_mitigation AFTER UPDATE OF mitigation ON vulnerabilities
+	WHEN EXISTS (
+		SELECT 1 FROM field_locks l
+		WHERE l.vulnerability_id = NEW.vulnerability_id AND l.field = 'mitigation' AND l.value IS NOT NEW.mitigation
+	)
+	BEGIN
+		UPDATE vulnerabilities SET mitigation = (
+			SELECT value FROM field_locks WHERE vulnerability_id = NEW.vulnerability_id AND field = 'mitigation'
+		) WHERE vulnerability_id = NEW.vulnerability_id;
+	END;
+
+	CREATE TRIGGER IF NOT EXISTS alert_severity_change AFTER UPDATE OF severity ON vulnerabilities
+	WHEN OLD.severity IS NOT NEW.severity
+		AND EXISTS (SELECT 1 FROM settings WHERE key = 'alerts')
+		AND NOT EXISTS (SELECT 1 FROM field_locks WHERE vulnerability_id = NEW.vulnerability_id AND field = 'severity')
+		AND EXISTS (
+			SELECT 1 FROM affected_software af
+			JOIN robot_software rs ON rs.version_id = af.version_id
+			WHERE af.vulnerability_id = NEW.vulnerability_id
+		)
+	BEGIN
+		INSERT INTO alert_outbox (kind, vulnerability_id, detail)
+		VALUES ('severity', NEW.vulnerability_id, OLD.severity || ' -> ' || NEW.severity);
+	END;
+";
+
+/// Which source last changed each tracked field of a vulnerability: an import, a feed or
+/// an analyst. Enrichment leaves fields an analyst set alone.
+const FIELD_PROVENANCE_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS field_provenance (
+		vulnerability_id INTEGER NOT NULL,
+		field TEXT NOT NULL,
+		source TEXT NOT NULL,
+		updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+		PRIMARY KEY (vulnerability_id, field),
+		FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE
+	);
+";
+
+/// Outbox of email alerts. The triggers queue an alert, once per robot and CVE, when a
+/// deployed robot becomes exposed to a vulnerability, but only while alerting is
+/// configured. The severity change alert is defined with the field locks.
+const ALERT_OUTBOX_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS alert_outbox (
+		alert_id INTEGER PRIMARY KEY AUTOINCREMENT,
+		kind TEXT NOT NULL,
+		vulnerability_id INTEGER NOT NULL,
+		robot_id INTEGER,
+		detail TEXT,
+		created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+		sent_at TEXT,
+		UNIQUE(kind, vulnerability_id, robot_id),
+		FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE
+	);
+	CREATE INDEX IF NOT EXISTS idx_alert_outbox_pending ON alert_outbox(sent_at);
+
+	CREATE TRIGGER IF NOT EXISTS alert_exposure_on_correlation AFTER INSERT ON affected_software
+	WHEN EXISTS (SELECT 1 FROM settings WHERE key = 'alerts')
+	BEGIN
+		INSERT OR IGNORE INTO alert_outbox (kind, vulnerability_id, robot_id)
+		SELECT 'exposure', NEW.vulnerability_id, rs.robot_id FROM robot_software rs WHERE rs.version_id = NEW.version_id;
+	END;
+
+	CREATE TRIGGER IF NOT EXISTS alert_exposure_on_install AFTER INSERT ON robot_software
+	WHEN EXISTS (SELECT 1 FROM settings WHERE key = 'alerts')
+	BEGIN
+		INSERT OR IGNORE INTO alert_outbox (kind, vulnerability_id, robot_id)
+		SELECT 'exposure', af.vulnerability_id, NEW.robot_id FROM affected_software af WHERE af.version_id = NEW.version_id;
+	END;
+
+";
+
+/// Software of soft-deleted robots, parked here so a deleted robot drops out of every
+/// exposure query and alert until it is restored
+const SOFT_DELETE_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS deleted_robot_software (
+		robot_id INTEGER NOT NULL,
+		version_id INTEGER NOT NULL,
+		installed_date TEXT NOT NULL,
+		PRIMARY KEY (robot_id, version_id),
+		FOREIGN KEY (robot_id) REFERENCES robots(robot_id) ON DELETE CASCADE,
+		FOREIGN KEY (version_id) REFERENCES software_versions(version_id)
+	);
+";
+
+/// Who changed which record when, with the changed fields as a JSON array of
+/// `FieldChange`s. Entries outlive the records they are about. Entries written by one
+/// bulk operation share a `batch_id`, the ID of the batch's first entry.
+const AUDIT_LOG_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS audit_log (
+		audit_id INTEGER PRIMARY KEY AUTOINCREMENT,
+		recorded_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+		actor TEXT NOT NULL,
+		entity TEXT NOT NULL,
+		entity_id INTEGER,
+		label TEXT NOT NULL,
+		action TEXT NOT NULL,
+		changes TEXT NOT NULL,
+		batch_id INTEGER
+	);
+	CREATE INDEX IF NOT EXISTS idx_audit_log_entity ON audit_log(entity, audit_id);
+";
+
+/// Runs of the vulnerability list and robot inventory imports. Vulnerabilities a run creates carry its ID in
+/// `vulnerabilities.import_run_id`; the values of those it overwrites are kept in
+/// `import_run_previous` as JSON, so the run can be reverted.
+const IMPORT_RUNS_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS import_runs (
+		run_id INTEGER PRIMARY KEY AUTOINCREMENT,
+		source TEXT NOT NULL,
+		actor TEXT NOT NULL,
+		started_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+		finished_at TEXT,
+		status TEXT NOT NULL DEFAULT 'running',
+		created INTEGER NOT NULL DEFAULT 0,
+		updated INTEGER NOT NULL DEFAULT 0,
+		skipped INTEGER NOT NULL DEFAULT 0
+	);
+
+	CREATE TABLE IF NOT EXISTS import_run_previous (
+		run_id INTEGER NOT NULL,
+		vulnerability_id INTEGER NOT NULL,
+		previous TEXT NOT NULL,
+		PRIMARY KEY (run_id, vulnerability_id),
+		FOREIGN KEY (run_id) REFERENCES import_runs(run_id) ON DELETE CASCADE,
+		FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE
+	);
+";
+
+/// Security commissioning checklist: the items every robot goes through, and per robot
+/// the items done. Open items count towards the robot's risk score.
+const COMMISSIONING_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS commissioning_items (
+		item_id INTEGER PRIMARY KEY AUTOINCREMENT,
+		label TEXT NOT NULL UNIQUE COLLATE NOCASE,
+		position INTEGER NOT NULL DEFAULT 0
+	);
+
+	CREATE TABLE IF NOT EXISTS robot_commissioning (
+		robot_id INTEGER NOT NULL,
+		item_id INTEGER NOT NULL,
+		completed_by TEXT NOT NULL,
+		completed_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+		PRIMARY KEY (robot_id, item_id),
+		FOREIGN KEY (robot_id) REFERENCES robots(robot_id) ON DELETE CASCADE,
+		FOREIGN KEY (item_id) REFERENCES commissioning_items(item_id) ON DELETE CASCADE
+	);
+";
+
+/// Issues raised in an external ticketing system (Jira or a generic REST tracker) to
+/// remediate a vulnerability, with the ticket's status as of the last sync
+const TICKETS_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS vulnerability_tickets (
+		vulnerability_id INTEGER PRIMARY KEY,
+		ticket_key TEXT NOT NULL,
+		url TEXT,
+		status TEXT,
+		created_by TEXT NOT NULL,
+		created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+		synced_at TEXT,
+		FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE
+	);
+";
+
+/// Feeds the background update syncs with, each with its own schedule and the outcome
+/// of its last run. The built-in sources are seeded disabled, except the NVD API, which
+/// the background update always used.
+const DATA_SOURCES_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS data_sources (
+		source_id INTEGER PRIMARY KEY AUTOINCREMENT,
+		name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+		kind TEXT NOT NULL,
+		url TEXT,
+		enabled INTEGER NOT NULL DEFAULT 0,
+		interval_minutes INTEGER NOT NULL,
+		last_run_at TEXT,
+		last_status TEXT,
+		last_message TEXT
+	);
+
+	INSERT OR IGNORE INTO data_sources (name, kind, url, enabled, interval_minutes) VALUES
+		('NVD', 'nvd', NULL, 1, 60),
+		('CISA KEV', 'kev', 'https://www.cisa.gov/sites/default/files/feeds/known_exploited_vulnerabilities.json', 0, 1440),
+		('FIRST EPSS', 'epss', 'https://epss.cyentia.com/epss_scores-current.csv.gz', 0, 1440),
+		('OSV', 'osv', 'https://api.osv.dev', 0, 1440),
+		('GitHub Advisories', 'ghsa', NULL, 0, 1440);
+";
+
+/// Last modification of each vulnerability, which offline bundles are merged by. An
+/// update that sets `updated_at` itself keeps that time, as a merged bundle entry does;
+/// rows from before the column have none and lose to any dated copy.
+const VULNERABILITY_TOUCH_SQL: &str = "
+	CREATE TRIGGER IF NOT EXISTS touch_vulnerability_on_insert AFTER INSERT ON vulnerabilities
+	WHEN NEW.updated_at IS NULL
+	BEGIN
+		UPDATE vulnerabilities SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+		WHERE vulnerability_id = NEW.vulnerability_id;
+	END;
+
+	CREATE TRIGGER IF NOT EXISTS touch_vulnerability_on_update AFTER UPDATE ON vulnerabilities
+	WHEN NEW.updated_at IS OLD.updated_at AND (
+		NEW.description IS NOT OLD.description OR NEW.severity IS NOT OLD.severity
+		OR NEW.impact IS NOT OLD.impact OR NEW.mitigation IS NOT OLD.mitigation
+		OR NEW.published_date IS NOT OLD.published_date OR NEW.cvss_score IS NOT OLD.cvss_score
+		OR NEW.cvss_version IS NOT OLD.cvss_version OR NEW.kev_date_added IS NOT OLD.kev_date_added
+		OR NEW.epss_score IS NOT OLD.epss_score OR NEW.source IS NOT OLD.source
+		OR NEW.deleted_at IS NOT OLD.deleted_at
+	)
+	BEGIN
+		UPDATE vulnerabilities SET updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+		WHERE vulnerability_id = NEW.vulnerability_id;
+	END;
+";
+
+/// What each data source sync changed, as JSON, for the What's New panel and the
+/// changelog. Runs that changed nothing are not kept.
+const SYNC_DELTAS_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS sync_deltas (
+		delta_id INTEGER PRIMARY KEY AUTOINCREMENT,
+		started_at TEXT NOT NULL,
+		finished_at TEXT NOT NULL,
+		-- JSON array of the names of the data sources that ran
+		sources TEXT NOT NULL,
+		content TEXT NOT NULL
+	);
+";
+
+/// Rows an import run left out and why, up to a bounded number per run
+const IMPORT_RUN_SKIPPED_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS import_run_skipped (
+		run_id INTEGER NOT NULL,
+		line INTEGER NOT NULL,
+		cve_id TEXT NOT NULL,
+		reason TEXT NOT NULL,
+		PRIMARY KEY (run_id, line),
+		FOREIGN KEY (run_id) REFERENCES import_runs(run_id) ON DELETE CASCADE
+	);
+";
+
+/// Robots of the robot inventory import runs: those a run creates carry its ID in
+/// `robots.import_run_id`, the fields and software of those it overwrites are kept as JSON
+const IMPORT_RUN_ROBOTS_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS import_run_robot_previous (
+		run_id INTEGER NOT NULL,
+		robot_id INTEGER NOT NULL,
+		previous TEXT NOT NULL,
+		PRIMARY KEY (run_id, robot_id),
+		FOREIGN KEY (run_id) REFERENCES import_runs(run_id) ON DELETE CASCADE,
+		FOREIGN KEY (robot_id) REFERENCES robots(robot_id) ON DELETE CASCADE
+	);
+";
+
+/// Compliance controls vulnerabilities are mapped to, seeded with the IEC 62443-3-3
+/// system requirements and ISO/IEC 27001:2022 Annex A controls findings on robots most
+/// often bear on. More are added with the compliance-controls command.
+const COMPLIANCE_SQL: &str = "
+	CREATE TABLE IF NOT EXISTS compliance_controls (
+		control_id INTEGER PRIMARY KEY AUTOINCREMENT,
+		standard TEXT NOT NULL COLLATE NOCASE,
+		code TEXT NOT NULL COLLATE NOCASE,
+		title TEXT NOT NULL,
+		UNIQUE (standard, code) ON CONFLICT IGNORE
+	);
+
+	CREATE TABLE IF NOT EXISTS vulnerability_controls (
+		vulnerability_id INTEGER NOT NULL,
+		control_id INTEGER NOT NULL,
+		linked_by TEXT NOT NULL,
+		linked_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+		PRIMARY KEY (vulnerability_id, control_id),
+		FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE,
+		FOREIGN KEY (control_id) REFERENCES compliance_controls(control_id) ON DELETE CASCADE
+	);
+
+	CREATE INDEX IF NOT EXISTS idx_vulnerability_controls_control
+	ON vulnerability_controls(control_id);
+
+	INSERT INTO compliance_controls (standard, code, title) VALUES
+		('IEC 62443-3-3', 'SR 1.1', 'Human user identification and authentication'),
+		('IEC 62443-3-3', 'SR 1.7', 'Strength of password-based authentication'),
+		('IEC 62443-3-3', 'SR 2.1', 'Authorization enforcement'),
+		('IEC 62443-3-3', 'SR 3.1', 'Communication integrity'),
+		('IEC 62443-3-3', 'SR 3.4', 'Software and information integrity'),
+		('IEC 62443-3-3', 'SR 4.1', 'Information confidentiality'),
+		('IEC 62443-3-3', 'SR 5.1', 'Network segmentation'),
+		('IEC 62443-3-3', 'SR 7.6', 'Network and security configuration settings'),
+		('ISO/IEC 27001 Annex A', 'A.5.7', 'Threat intelligence'),
+		('ISO/IEC 27001 Annex A', 'A.8.5', 'Secure authentication'),
+		('ISO/IEC 27001 Annex A', 'A.8.8', 'Management of technical vulnerabilities'),
+		('ISO/IEC 27001 Annex A', 'A.8.9', 'Configuration management'),
+		('ISO/IEC 27001 Annex A', 'A.8.19', 'Installation of software on operational systems'),
+		('ISO/IEC 27001 Annex A', 'A.8.20', 'Networks security'),
+		('ISO/IEC 27001 Annex A', 'A.8.22', 'Segregation of networks'),
+		('ISO/IEC 27001 Annex A', 'A.8.32', 'Change management');
+";
+
+/// Severity labels by rank, compared case-insensitively; anything else ranks 0
+const SEVERITY_RANKS: &[(&str, i64)] = &[("critical", 4), ("high", 3), ("medium", 2), ("low", 1)];
+
+/// SQL ranking the severity label in `column`. The browse index is built on this
+/// expression, so queries must use it unchanged to be served by the index.
+pub(crate) fn severity_rank_sql(column: &str) -> String {
+	let cases = SEVERITY_RANKS
+		.iter()
+		.map(|(label, rank)| format!("WHEN '{}' THEN {}", label, rank))
+		.collect::<Vec<_>>()
+		.join(" ");
+	format!("CASE lower({}) {} ELSE 0 END", column, cases)
+}
+
+/// Rust counterpart of `severity_rank_sql`
+pub(crate) fn severity_rank(severity: &str) -> i64 {
+	SEVERITY_RANKS
+		.iter()
+		.find(|(label, _)| severity.eq_ignore_ascii_case(label))
+		.map_or(0, |(_, rank)| *rank)
+}
+
+/// Indexes serving the ORDER BY of each sort order of the vulnerability list, with
+/// the ID as tie-breaker for keyset pagination
+fn browse_indexes_sql() -> String {
+	format!(
+		"CREATE INDEX IF NOT EXISTS idx_vulnerability_browse_published
+			ON vulnerabilities(COALESCE(published_date, ''), vulnerability_id);
+		 CREATE INDEX IF NOT EXISTS idx_vulnerability_browse_severity
+			ON vulnerabilities({}, vulnerability_id);",
+		severity_rank_sql("severity")
+	)
+}
+
 /// Initialize the database schema
 pub fn create_tables(conn: &Connection) -> Result<()> {
 	conn.execute_batch(
 		"
+		-- Timestamps are UTC in RFC 3339 format, e.g. 2024-05-01T12:30:00Z
+
 		-- Schema version tracking
 		CREATE TABLE IF NOT EXISTS schema_version (
 			version INTEGER PRIMARY KEY,
-			installed_on TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+			installed_on TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
 			description TEXT NOT NULL
 		);
 
@@ -23,7 +461,22 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
 			severity TEXT NOT NULL,
 			impact TEXT,
 			mitigation TEXT,
-			published_date TEXT
+			published_date TEXT,
+			cvss_score REAL,
+			-- CVSS version of cvss_score, such as 3.1 or 4.0
+			cvss_version TEXT,
+			-- Date CISA added the CVE to its Known Exploited Vulnerabilities catalog
+			kev_date_added TEXT,
+			-- FIRST EPSS probability of exploitation within 30 days
+			epss_score REAL,
+			-- Advisory database the entry was imported from when not the NVD, e.g. Alias Robotics RVD
+			source TEXT,
+			-- Set while the entry is in Recently deleted
+			deleted_at TEXT,
+			-- Import run that created the entry, if a CSV or spreadsheet import did
+			import_run_id INTEGER,
+			-- Last modification, kept by triggers
+			updated_at TEXT
 		);
 
 		-- Vulnerability indexes
@@ -38,9 +491,29 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
 			robot_id INTEGER PRIMARY KEY AUTOINCREMENT,
 			name TEXT NOT NULL,
 			manufacturer TEXT,
+			model TEXT,
+			firmware_version TEXT,
+			os TEXT,
+			-- ROS distribution, e.g. ROS 2 Humble; its codename is kept installed as the ros product
+			ros_distro TEXT,
 			specifications TEXT,
-			created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
-			updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+			-- Free-text context for responders, e.g. air-gapped or scheduled for retirement
+			operational_note TEXT,
+			-- 0-100, recomputed from the exposure of the installed software
+			risk_score REAL,
+			-- Business criticality weighting the robot in the fleet risk index
+			criticality TEXT NOT NULL DEFAULT 'Medium',
+			created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+			updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+			-- Set while the robot is in Recently deleted
+			deleted_at TEXT,
+			-- When and how the installed software was last recorded, see InventorySource
+			inventory_refreshed_at TEXT,
+			inventory_source TEXT,
+			-- Set once a stale inventory alert went out, cleared by the next refresh
+			inventory_alerted_at TEXT,
+			-- Robot inventory import that created the robot
+			import_run_id INTEGER
 		);
 
 		-- Robot indexes
@@ -53,6 +526,8 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
 			product_name TEXT NOT NULL,
 			vendor TEXT NOT NULL,
 			description TEXT,
+			-- SPDX license identifier or expression, e.g. Apache-2.0 OR MIT
+			license TEXT,
 			UNIQUE(product_name, vendor)
 		);
 
@@ -62,6 +537,9 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
 			product_id INTEGER NOT NULL,
 			version_number TEXT NOT NULL,
 			release_date TEXT,
+			-- End of vendor support, YYYY-MM-DD
+			eol_date TEXT,
+			notes TEXT,
 			FOREIGN KEY (product_id) REFERENCES software_products(product_id),
 			UNIQUE(product_id, version_number)
 		);
@@ -70,7 +548,7 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
 		CREATE TABLE IF NOT EXISTS robot_software (
 			robot_id INTEGER NOT NULL,
 			version_id INTEGER NOT NULL,
-			installed_date TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+			installed_date TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
 			PRIMARY KEY (robot_id, version_id),
 			FOREIGN KEY (robot_id) REFERENCES robots(robot_id) ON DELETE CASCADE,
 			FOREIGN KEY (version_id) REFERENCES software_versions(version_id)
@@ -100,34 +578,294 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
 
 		CREATE INDEX IF NOT EXISTS idx_robot_software_lookup
 		ON robot_software(robot_id, version_id);
+
+		-- Triage workflow per vulnerability
+		CREATE TABLE IF NOT EXISTS vulnerability_status (
+			vulnerability_id INTEGER PRIMARY KEY,
+			status TEXT NOT NULL DEFAULT 'Open',
+			assigned_to TEXT,
+			updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+			-- Risk decision record for Accepted Risk and False Positive
+			justification TEXT,
+			approved_by TEXT,
+			accepted_at TEXT,
+			expires_on TEXT,
+			FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE
+		);
+
+		CREATE INDEX IF NOT EXISTS idx_vulnerability_status_lookup
+		ON vulnerability_status(status);
+
+		-- Free-text notes attached to vulnerabilities and robots
+		CREATE TABLE IF NOT EXISTS notes (
+			note_id INTEGER PRIMARY KEY AUTOINCREMENT,
+			entity_type TEXT NOT NULL,
+			entity_id INTEGER NOT NULL,
+			body TEXT NOT NULL,
+			created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+			updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+		);
+
+		CREATE INDEX IF NOT EXISTS idx_notes_entity
+		ON notes(entity_type, entity_id);
+
+		-- NVD enrichment progress, kept across restarts
+		CREATE TABLE IF NOT EXISTS enrichment_runs (
+			run_id INTEGER PRIMARY KEY AUTOINCREMENT,
+			started_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+			finished_at TEXT,
+			batch_size INTEGER NOT NULL,
+			updated INTEGER NOT NULL DEFAULT 0,
+			unchanged INTEGER NOT NULL DEFAULT 0,
+			failed INTEGER NOT NULL DEFAULT 0,
+			rate_limited INTEGER NOT NULL DEFAULT 0
+		);
+
+		CREATE TABLE IF NOT EXISTS enrichment_attempts (
+			vulnerability_id INTEGER PRIMARY KEY,
+			run_id INTEGER NOT NULL,
+			outcome TEXT NOT NULL,
+			attempted_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+			consecutive_failures INTEGER NOT NULL DEFAULT 0,
+			FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE,
+			FOREIGN KEY (run_id) REFERENCES enrichment_runs(run_id)
+		);
+
+		-- Installation-wide settings
+		CREATE TABLE IF NOT EXISTS settings (
+			key TEXT PRIMARY KEY,
+			value TEXT NOT NULL
+		);
+
+		-- Reference links and vendor advisory IDs of each vulnerability
+		CREATE TABLE IF NOT EXISTS vulnerability_references (
+			reference_id INTEGER PRIMARY KEY AUTOINCREMENT,
+			vulnerability_id INTEGER NOT NULL,
+			url TEXT NOT NULL,
+			source TEXT,
+			advisory_id TEXT,
+			-- NVD reference tags such as Patch or Exploit, comma separated
+			tags TEXT,
+			UNIQUE(vulnerability_id, url),
+			FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE
+		);
+		CREATE INDEX IF NOT EXISTS idx_references_advisory ON vulnerability_references(advisory_id);
 		"
 	).context("Failed to create tables")?;
 
+	conn.execute_batch(ALERT_OUTBOX_SQL).context("Failed to create alert outbox")?;
+	conn.execute_batch(WEAKNESSES_SQL).context("Failed to create weaknesses table")?;
+	conn.execute_batch(METRICS_HISTORY_SQL).context("Failed to create metrics history")?;
+	conn.execute_batch(REPORT_SNAPSHOTS_SQL).context("Failed to create report snapshots")?;
+	conn.execute_batch(ALIASES_SQL).context("Failed to create aliases table")?;
+	conn.execute_batch(FIELD_LOCKS_SQL).context("Failed to create field locks")?;
+	conn.execute_batch(FIELD_PROVENANCE_SQL).context("Failed to create field provenance")?;
+	conn.execute_batch(SOFT_DELETE_SQL).context("Failed to create soft delete support")?;
+	conn.execute_batch(AUDIT_LOG_SQL).context("Failed to create audit log")?;
+	conn.execute_batch(IMPORT_RUNS_SQL).context("Failed to create import runs")?;
+	conn.execute_batch(COMMISSIONING_SQL).context("Failed to create commissioning checklist")?;
+	conn.execute_batch(COMPLIANCE_SQL).context("Failed to create compliance controls")?;
+	conn.execute_batch(TICKETS_SQL).context("Failed to create tickets")?;
+	conn.execute_batch(DATA_SOURCES_SQL).context("Failed to create data sources")?;
+	conn.execute_batch(VULNERABILITY_TOUCH_SQL).context("Failed to create modification tracking")?;
+	conn.execute_batch(SYNC_DELTAS_SQL).context("Failed to create sync deltas")?;
+	conn.execute_batch(IMPORT_RUN_SKIPPED_SQL).context("Failed to create import run skipped rows")?;
+	conn.execute_batch(IMPORT_RUN_ROBOTS_SQL).context("Failed to create import run robots")?;
+	conn.execute_batch(&browse_indexes_sql()).context("Failed to create browse indexes")?;
+
 	Ok(())
 }
 
-/// Check and upgrade schema version if needed
+/// Check and upgrade schema version if needed, applying migrations one after another
 pub fn check_schema_version(conn: &Connection) -> Result<()> {
-	let current_version = get_schema_version(conn)?;
+	loop {
+		let current_version = get_schema_version(conn)?;
 
-	match current_version {
-		0 => {
-			apply_initial_migration(conn)?;
-			update_schema_version(conn, 1, "Initial schema")?;
-		}
-		1 => {
-			apply_software_tracking_migration(conn)?;
-			update_schema_version(conn, 2, "Added software tracking")?;
-		}
-		2 => {
-			apply_robot_migration(conn)?;
-			update_schema_version(conn, 3, "Added robot management")?;
-		}
-		3 => {
-			info!("Database schema is up to date");
-		}
-		v => {
-			warn!("Unknown schema version: {}. No migration applied", v);
+		match current_version {
+			0 => {
+				apply_initial_migration(conn)?;
+				update_schema_version(conn, 1, "Initial schema")?;
+			}
+			1 => {
+				apply_software_tracking_migration(conn)?;
+				update_schema_version(conn, 2, "Added software tracking")?;
+			}
+			2 => {
+				apply_robot_migration(conn)?;
+				update_schema_version(conn, 3, "Added robot management")?;
+			}
+			3 => {
+				apply_triage_migration(conn)?;
+				update_schema_version(conn, 4, "Added triage workflow")?;
+			}
+			4 => {
+				apply_cvss_migration(conn)?;
+				update_schema_version(conn, 5, "Added CVSS scores")?;
+			}
+			5 => {
+				apply_notes_migration(conn)?;
+				update_schema_version(conn, 6, "Added notes")?;
+			}
+			6 => {
+				apply_enrichment_migration(conn)?;
+				update_schema_version(conn, 7, "Added NVD enrichment progress")?;
+			}
+			7 => {
+				apply_enrichment_failures_migration(conn)?;
+				update_schema_version(conn, 8, "Added enrichment failure tracking")?;
+			}
+			8 => {
+				apply_settings_migration(conn)?;
+				update_schema_version(conn, 9, "Added settings")?;
+			}
+			9 => {
+				apply_references_migration(conn)?;
+				update_schema_version(conn, 10, "Added vulnerability references")?;
+			}
+			10 => {
+				apply_robot_note_migration(conn)?;
+				update_schema_version(conn, 11, "Added robot operational notes")?;
+			}
+			11 => {
+				apply_utc_timestamps_migration(conn)?;
+				update_schema_version(conn, 12, "Stored timestamps as UTC RFC 3339")?;
+			}
+			12 => {
+				apply_risk_acceptance_migration(conn)?;
+				update_schema_version(conn, 13, "Added risk acceptance records")?;
+			}
+			13 => {
+				apply_alerts_migration(conn)?;
+				update_schema_version(conn, 14, "Added email alert outbox")?;
+			}
+			14 => {
+				apply_browse_indexes_migration(conn)?;
+				update_schema_version(conn, 15, "Added indexes for sorted browsing")?;
+			}
+			15 => {
+				apply_software_license_migration(conn)?;
+				update_schema_version(conn, 16, "Added software licenses")?;
+			}
+			16 => {
+				apply_weaknesses_migration(conn)?;
+				update_schema_version(conn, 17, "Added CWE weaknesses")?;
+			}
+			17 => {
+				apply_kev_migration(conn)?;
+				update_schema_version(conn, 18, "Added known exploited vulnerabilities")?;
+			}
+			18 => {
+				apply_reference_tags_migration(conn)?;
+				update_schema_version(conn, 19, "Added reference tags")?;
+			}
+			19 => {
+				apply_version_metadata_migration(conn)?;
+				update_schema_version(conn, 20, "Added software version metadata")?;
+			}
+			20 => {
+				apply_risk_score_migration(conn)?;
+				update_schema_version(conn, 21, "Added EPSS and robot risk scores")?;
+			}
+			21 => {
+				apply_fleet_risk_migration(conn)?;
+				update_schema_version(conn, 22, "Added robot criticality and metrics history")?;
+			}
+			22 => {
+				apply_robot_model_migration(conn)?;
+				update_schema_version(conn, 23, "Added robot model")?;
+			}
+			23 => {
+				apply_cvss_version_migration(conn)?;
+				update_schema_version(conn, 24, "Added CVSS version")?;
+			}
+			24 => {
+				apply_robot_platform_migration(conn)?;
+				update_schema_version(conn, 25, "Added robot firmware, OS and ROS distribution")?;
+			}
+			25 => {
+				apply_vulnerability_source_migration(conn)?;
+				update_schema_version(conn, 26, "Added vulnerability source")?;
+			}
+			26 => {
+				apply_report_snapshots_migration(conn)?;
+				update_schema_version(conn, 27, "Added report snapshots")?;
+			}
+			27 => {
+				apply_aliases_migration(conn)?;
+				update_schema_version(conn, 28, "Added vulnerability aliases")?;
+			}
+			28 => {
+				apply_field_locks_migration(conn)?;
+				update_schema_version(conn, 29, "Added manual edit locks")?;
+			}
+			29 => {
+				apply_soft_delete_migration(conn)?;
+				update_schema_version(conn, 30, "Added soft delete")?;
+			}
+			30 => {
+				apply_audit_log_migration(conn)?;
+				update_schema_version(conn, 31, "Added audit log")?;
+			}
+			31 => {
+				apply_import_runs_migration(conn)?;
+				update_schema_version(conn, 32, "Added revertible import runs")?;
+			}
+			32 => {
+				apply_commissioning_migration(conn)?;
+				update_schema_version(conn, 33, "Added robot commissioning checklist")?;
+			}
+			33 => {
+				apply_inventory_freshness_migration(conn)?;
+				update_schema_version(conn, 34, "Added robot inventory freshness")?;
+			}
+			34 => {
+				apply_compliance_migration(conn)?;
+				update_schema_version(conn, 35, "Added compliance control mapping")?;
+			}
+			35 => {
+				apply_audit_batches_migration(conn)?;
+				update_schema_version(conn, 36, "Added audit log batches")?;
+			}
+			36 => {
+				apply_tickets_migration(conn)?;
+				update_schema_version(conn, 37, "Added remediation tickets")?;
+			}
+			37 => {
+				apply_data_sources_migration(conn)?;
+				update_schema_version(conn, 38, "Added data sources")?;
+			}
+			38 => {
+				apply_modification_tracking_migration(conn)?;
+				update_schema_version(conn, 39, "Added vulnerability modification times")?;
+			}
+			39 => {
+				apply_sync_deltas_migration(conn)?;
+				update_schema_version(conn, 40, "Added sync deltas")?;
+			}
+			40 => {
+				apply_import_report_migration(conn)?;
+				update_schema_version(conn, 41, "Added skipped rows of import runs")?;
+			}
+			41 => {
+				apply_robot_import_runs_migration(conn)?;
+				update_schema_version(conn, 42, "Added revertible robot inventory imports")?;
+			}
+			42 => {
+				apply_impact_lock_migration(conn)?;
+				update_schema_version(conn, 43, "Added locks for impact edited by hand")?;
+			}
+			43 => {
+				apply_field_provenance_migration(conn)?;
+				update_schema_version(conn, 44, "Added field provenance")?;
+			}
+			SCHEMA_VERSION => {
+				info!("Database schema is up to date");
+				break;
+			}
+			v => {
+				warn!("Unknown schema version: {}. No migration applied", v);
+				break;
+			}
 		}
 	}
 
@@ -138,7 +876,7 @@ fn get_schema_version(conn: &Connection) -> Result<i32> {
 	conn.execute_batch(
 		"CREATE TABLE IF NOT EXISTS schema_version (
 			version INTEGER PRIMARY KEY,
-			installed_on TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+			installed_on TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
 			description TEXT NOT NULL
 		);"
 	)?;
@@ -152,6 +890,33 @@ fn get_schema_version(conn: &Connection) -> Result<i32> {
 	Ok(version)
 }
 
+/// Adds a column unless it already exists, e.g. because `create_tables` created it on a fresh database
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+	let exists = conn
+		.prepare(&format!("PRAGMA table_info({})", table))?
+		.query_map([], |row| row.get::<_, String>(1))?
+		.collect::<rusqlite::Result<Vec<_>>>()?
+		.iter()
+		.any(|name| name == column);
+
+	if !exists {
+		conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {};", table, column, definition))
+			.with_context(|| format!("Failed to add column {}.{}", table, column))?;
+	}
+
+	Ok(())
+}
+
+/// Default value declared for a column, as written in its definition
+fn column_default(conn: &Connection, table: &str, column: &str) -> Result<Option<String>> {
+	let defaults = conn
+		.prepare(&format!("PRAGMA table_info({})", table))?
+		.query_map([], |row| Ok((row.get::<_, String>(1)?, row.get::<_, Option<String>>(4)?)))?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+
+	Ok(defaults.into_iter().find(|(name, _)| name == column).and_then(|(_, default)| default))
+}
+
 fn update_schema_version(conn: &Connection, version: i32, description: &str) -> Result<()> {
 	conn.execute(
 		"INSERT INTO schema_version (version, description) VALUES (?, ?)",
@@ -254,22 +1019,416 @@ fn apply_robot_migration(conn: &Connection) -> Result<()> {
 	Ok(())
 }
 
+fn apply_triage_migration(conn: &Connection) -> Result<()> {
+	info!("Applying triage workflow migration");
+
+	conn.execute_batch(
+		"CREATE TABLE IF NOT EXISTS vulnerability_status (
+			vulnerability_id INTEGER PRIMARY KEY,
+			status TEXT NOT NULL DEFAULT 'Open',
+			assigned_to TEXT,
+			updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+			FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE
+		);
+
+		CREATE INDEX IF NOT EXISTS idx_vulnerability_status_lookup
+		ON vulnerability_status(status);"
+	)?;
+
+	Ok(())
+}
+
+fn apply_cvss_migration(conn: &Connection) -> Result<()> {
+	info!("Applying CVSS score migration");
+	add_column_if_missing(conn, "vulnerabilities", "cvss_score", "REAL")
+}
+
+fn apply_notes_migration(conn: &Connection) -> Result<()> {
+	info!("Applying notes migration");
+
+	conn.execute_batch(
+		"CREATE TABLE IF NOT EXISTS notes (
+			note_id INTEGER PRIMARY KEY AUTOINCREMENT,
+			entity_type TEXT NOT NULL,
+			entity_id INTEGER NOT NULL,
+			body TEXT NOT NULL,
+			created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+			updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+		);
+
+		CREATE INDEX IF NOT EXISTS idx_notes_entity
+		ON notes(entity_type, entity_id);"
+	)?;
+
+	Ok(())
+}
+
+fn apply_enrichment_migration(conn: &Connection) -> Result<()> {
+	info!("Applying NVD enrichment progress migration");
+
+	conn.execute_batch(
+		"CREATE TABLE IF NOT EXISTS enrichment_runs (
+			run_id INTEGER PRIMARY KEY AUTOINCREMENT,
+			started_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+			finished_at TEXT,
+			batch_size INTEGER NOT NULL,
+			updated INTEGER NOT NULL DEFAULT 0,
+			unchanged INTEGER NOT NULL DEFAULT 0,
+			failed INTEGER NOT NULL DEFAULT 0,
+			rate_limited INTEGER NOT NULL DEFAULT 0
+		);
+
+		CREATE TABLE IF NOT EXISTS enrichment_attempts (
+			vulnerability_id INTEGER PRIMARY KEY,
+			run_id INTEGER NOT NULL,
+			outcome TEXT NOT NULL,
+			attempted_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+			FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE,
+			FOREIGN KEY (run_id) REFERENCES enrichment_runs(run_id)
+		);"
+	)?;
+
+	Ok(())
+}
+
+fn apply_enrichment_failures_migration(conn: &Connection) -> Result<()> {
+	info!("Applying enrichment failure tracking migration");
+	add_column_if_missing(conn, "enrichment_attempts", "consecutive_failures", "INTEGER NOT NULL DEFAULT 0")
+}
+
+fn apply_settings_migration(conn: &Connection) -> Result<()> {
+	info!("Applying settings migration");
+
+	conn.execute_batch(
+		"CREATE TABLE IF NOT EXISTS settings (
+			key TEXT PRIMARY KEY,
+			value TEXT NOT NULL
+		);"
+	)?;
+
+	Ok(())
+}
+
+fn apply_references_migration(conn: &Connection) -> Result<()> {
+	info!("Applying vulnerability references migration");
+
+	conn.execute_batch(
+		"CREATE TABLE IF NOT EXISTS vulnerability_references (
+			reference_id INTEGER PRIMARY KEY AUTOINCREMENT,
+			vulnerability_id INTEGER NOT NULL,
+			url TEXT NOT NULL,
+			source TEXT,
+			advisory_id TEXT,
+			UNIQUE(vulnerability_id, url),
+			FOREIGN KEY (vulnerability_id) REFERENCES vulnerabilities(vulnerability_id) ON DELETE CASCADE
+		);
+		CREATE INDEX IF NOT EXISTS idx_references_advisory ON vulnerability_references(advisory_id);"
+	)?;
+
+	Ok(())
+}
+
+fn apply_robot_note_migration(conn: &Connection) -> Result<()> {
+	info!("Applying robot operational note migration");
+	add_column_if_missing(conn, "robots", "operational_note", "TEXT")
+}
+
+fn apply_utc_timestamps_migration(conn: &Connection) -> Result<()> {
+	info!("Applying UTC timestamp migration");
+
+	for (table, column) in TIMESTAMP_COLUMNS {
+		// CURRENT_TIMESTAMP values are already UTC, only the format changes
+		conn.execute(
+			&format!(
+				"UPDATE {table} SET {column} = strftime('%Y-%m-%dT%H:%M:%SZ', {column})
+				 WHERE {column} NOT LIKE '%T%' AND strftime('%Y-%m-%dT%H:%M:%SZ', {column}) IS NOT NULL"
+			),
+			[],
+		).with_context(|| format!("Failed to convert {}.{}", table, column))?;
+
+		// SQLite cannot change the default of an existing column, so tables created before
+		// this version rewrite the old default on insert instead
+		if column_default(conn, table, column)?.as_deref() == Some("CURRENT_TIMESTAMP") {
+			conn.execute_batch(&format!(
+				"CREATE TRIGGER IF NOT EXISTS {table}_{column}_utc AFTER INSERT ON {table}
+				 WHEN NEW.{column} NOT LIKE '%T%'
+				 BEGIN
+					UPDATE {table} SET {column} = strftime('%Y-%m-%dT%H:%M:%SZ', NEW.{column}) WHERE rowid = NEW.rowid;
+				 END;"
+			)).with_context(|| format!("Failed to create timestamp trigger for {}.{}", table, column))?;
+		}
+	}
+
+	Ok(())
+}
+
+fn apply_risk_acceptance_migration(conn: &Connection) -> Result<()> {
+	info!("Applying risk acceptance migration");
+	add_column_if_missing(conn, "vulnerability_status", "justification", "TEXT")?;
+	add_column_if_missing(conn, "vulnerability_status", "approved_by", "TEXT")?;
+	add_column_if_missing(conn, "vulnerability_status", "accepted_at", "TEXT")?;
+	add_column_if_missing(conn, "vulnerability_status", "expires_on", "TEXT")
+}
+
+fn apply_alerts_migration(conn: &Connection) -> Result<()> {
+	info!("Applying email alert migration");
+	conn.execute_batch(ALERT_OUTBOX_SQL)?;
+	Ok(())
+}
+
+fn apply_browse_indexes_migration(conn: &Connection) -> Result<()> {
+	info!("Applying browse index migration");
+	conn.execute_batch(&browse_indexes_sql())?;
+	Ok(())
+}
+
+fn apply_software_license_migration(conn: &Connection) -> Result<()> {
+	info!("Applying software license migration");
+	add_column_if_missing(conn, "software_products", "license", "TEXT")
+}
+
+fn apply_weaknesses_migration(conn: &Connection) -> Result<()> {
+	info!("Applying CWE weaknesses migration");
+	conn.execute_batch(WEAKNESSES_SQL)?;
+	Ok(())
+}
+
+fn apply_kev_migration(conn: &Connection) -> Result<()> {
+	info!("Applying known exploited vulnerabilities migration");
+	add_column_if_missing(conn, "vulnerabilities", "kev_date_added", "TEXT")
+}
+
+fn apply_reference_tags_migration(conn: &Connection) -> Result<()> {
+	info!("Applying reference tags migration");
+	add_column_if_missing(conn, "vulnerability_references", "tags", "TEXT")
+}
+
+fn apply_version_metadata_migration(conn: &Connection) -> Result<()> {
+	info!("Applying software version metadata migration");
+	add_column_if_missing(conn, "software_versions", "eol_date", "TEXT")?;
+	add_column_if_missing(conn, "software_versions", "notes", "TEXT")
+}
+
+fn apply_risk_score_migration(conn: &Connection) -> Result<()> {
+	info!("Applying risk score migration");
+	add_column_if_missing(conn, "vulnerabilities", "epss_score", "REAL")?;
+	add_column_if_missing(conn, "robots", "risk_score", "REAL")
+}
+
+fn apply_fleet_risk_migration(conn: &Connection) -> Result<()> {
+	info!("Applying fleet risk migration");
+	add_column_if_missing(conn, "robots", "criticality", "TEXT NOT NULL DEFAULT 'Medium'")?;
+	conn.execute_batch(METRICS_HISTORY_SQL)?;
+	Ok(())
+}
+
+fn apply_robot_model_migration(conn: &Connection) -> Result<()> {
+	info!("Applying robot model migration");
+	add_column_if_missing(conn, "robots", "model", "TEXT")
+}
+
+fn apply_cvss_version_migration(conn: &Connection) -> Result<()> {
+	info!("Applying CVSS version migration");
+	add_column_if_missing(conn, "vulnerabilities", "cvss_version", "TEXT")
+}
+
+fn apply_robot_platform_migration(conn: &Connection) -> Result<()> {
+	info!("Applying robot platform migration");
+	add_column_if_missing(conn, "robots", "firmware_version", "TEXT")?;
+	add_column_if_missing(conn, "robots", "os", "TEXT")?;
+	add_column_if_missing(conn, "robots", "ros_distro", "TEXT")
+}
+
+fn apply_vulnerability_source_migration(conn: &Connection) -> Result<()> {
+	info!("Applying vulnerability source migration");
+	add_column_if_missing(conn, "vulnerabilities", "source", "TEXT")
+}
+
+fn apply_report_snapshots_migration(conn: &Connection) -> Result<()> {
+	info!("Applying report snapshots migration");
+	conn.execute_batch(REPORT_SNAPSHOTS_SQL)?;
+	Ok(())
+}
+
+fn apply_aliases_migration(conn: &Connection) -> Result<()> {
+	info!("Applying vulnerability aliases migration");
+	conn.execute_batch(ALIASES_SQL)?;
+	// The GitHub advisory import kept the GHSA ID of a CVE as its advisory page reference
+	conn.execute(
+		"INSERT OR IGNORE INTO vulnerability_aliases (alias, vulnerability_id, source)
+		 SELECT r.advisory_id, r.vulnerability_id, r.source FROM vulnerability_references r
+		 JOIN vulnerabilities v ON v.vulnerability_id = r.vulnerability_id
+		 WHERE r.source = 'GitHub Advisory Database' AND r.advisory_id LIKE 'GHSA-%' AND v.cve_id != r.advisory_id",
+		[],
+	)?;
+	Ok(())
+}
+
+fn apply_field_locks_migration(conn: &Connection) -> Result<()> {
+	info!("Applying field locks migration");
+	// Replaced by the version that skips locked severities
+	conn.execute_batch("DROP TRIGGER IF EXISTS alert_severity_change;")?;
+	conn.execute_batch(FIELD_LOCKS_SQL)?;
+	Ok(())
+}
+
+fn apply_soft_delete_migration(conn: &Connection) -> Result<()> {
+	info!("Applying soft delete migration");
+	add_column_if_missing(conn, "robots", "deleted_at", "TEXT")?;
+	add_column_if_missing(conn, "vulnerabilities", "deleted_at", "TEXT")?;
+	conn.execute_batch(SOFT_DELETE_SQL)?;
+	Ok(())
+}
+
+fn apply_audit_log_migration(conn: &Connection) -> Result<()> {
+	info!("Applying audit log migration");
+	conn.execute_batch(AUDIT_LOG_SQL)?;
+	Ok(())
+}
+
+fn apply_import_runs_migration(conn: &Connection) -> Result<()> {
+	info!("Applying import runs migration");
+	conn.execute_batch(IMPORT_RUNS_SQL)?;
+	add_column_if_missing(conn, "vulnerabilities", "import_run_id", "INTEGER")?;
+	conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_vulnerabilities_import_run ON vulnerabilities(import_run_id);")?;
+	Ok(())
+}
+
+fn apply_commissioning_migration(conn: &Connection) -> Result<()> {
+	info!("Applying commissioning checklist migration");
+	conn.execute_batch(COMMISSIONING_SQL)?;
+	Ok(())
+}
+
+fn apply_inventory_freshness_migration(conn: &Connection) -> Result<()> {
+	info!("Applying inventory freshness migration");
+	add_column_if_missing(conn, "robots", "inventory_refreshed_at", "TEXT")?;
+	add_column_if_missing(conn, "robots", "inventory_source", "TEXT")?;
+	add_column_if_missing(conn, "robots", "inventory_alerted_at", "TEXT")?;
+	// Until now the software was only recorded when it changed
+	conn.execute(
+		"UPDATE robots SET inventory_refreshed_at = strftime('%Y-%m-%dT%H:%M:%SZ', COALESCE(
+			(SELECT MAX(installed_date) FROM robot_software rs WHERE rs.robot_id = robots.robot_id),
+			created_at
+		 ))
+		 WHERE inventory_refreshed_at IS NULL",
+		[],
+	)?;
+	Ok(())
+}
+
+fn apply_compliance_migration(conn: &Connection) -> Result<()> {
+	info!("Applying compliance controls migration");
+	conn.execute_batch(COMPLIANCE_SQL)?;
+	Ok(())
+}
+
+fn apply_audit_batches_migration(conn: &Connection) -> Result<()> {
+	info!("Applying audit log batches migration");
+	add_column_if_missing(conn, "audit_log", "batch_id", "INTEGER")?;
+	conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_audit_log_batch ON audit_log(batch_id);")?;
+	Ok(())
+}
+
+fn apply_tickets_migration(conn: &Connection) -> Result<()> {
+	info!("Applying remediation tickets migration");
+	conn.execute_batch(TICKETS_SQL)?;
+	Ok(())
+}
+
+/// The NVD source takes over the interval and last sync of the single background sync
+fn apply_data_sources_migration(conn: &Connection) -> Result<()> {
+	info!("Applying data sources migration");
+	conn.execute_batch(DATA_SOURCES_SQL)?;
+	conn.execute_batch(
+		"UPDATE data_sources SET
+			interval_minutes = COALESCE(
+				(SELECT CAST(value AS INTEGER) FROM settings WHERE key = 'sync_interval_minutes'),
+				interval_minutes
+			),
+			last_run_at = (SELECT value FROM settings WHERE key = 'last_sync')
+		 WHERE kind = 'nvd';
+		 DELETE FROM settings WHERE key IN ('sync_interval_minutes', 'last_sync');",
+	)?;
+	Ok(())
+}
+
+fn apply_modification_tracking_migration(conn: &Connection) -> Result<()> {
+	info!("Applying modification tracking migration");
+	add_column_if_missing(conn, "vulnerabilities", "updated_at", "TEXT")?;
+	conn.execute_batch(VULNERABILITY_TOUCH_SQL)?;
+	Ok(())
+}
+
+fn apply_sync_deltas_migration(conn: &Connection) -> Result<()> {
+	info!("Applying sync deltas migration");
+	conn.execute_batch(SYNC_DELTAS_SQL)?;
+	Ok(())
+}
+
+fn apply_import_report_migration(conn: &Connection) -> Result<()> {
+	info!("Applying import report migration");
+	add_column_if_missing(conn, "import_runs", "skipped", "INTEGER NOT NULL DEFAULT 0")?;
+	conn.execute_batch(IMPORT_RUN_SKIPPED_SQL)?;
+	Ok(())
+}
+
+fn apply_robot_import_runs_migration(conn: &Connection) -> Result<()> {
+	info!("Applying robot import runs migration");
+	add_column_if_missing(conn, "robots", "import_run_id", "INTEGER")?;
+	conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_robots_import_run ON robots(import_run_id);")?;
+	conn.execute_batch(IMPORT_RUN_ROBOTS_SQL)?;
+	Ok(())
+}
+
+fn apply_impact_lock_migration(conn: &Connection) -> Result<()> {
+	info!("Applying impact lock migration");
+	// SQLite cannot change a CHECK constraint, so the table is rebuilt. The triggers reading
+	// it are dropped first so the rename leaves them alone, and come back with the new table.
+	conn.execute_batch(
+		"DROP TRIGGER IF EXISTS keep_locked_description;
+		 DROP TRIGGER IF EXISTS keep_locked_severity;
+		 DROP TRIGGER IF EXISTS keep_locked_impact;
+		 DROP TRIGGER IF EXISTS keep_locked_mitigation;
+		 DROP TRIGGER IF EXISTS alert_severity_change;
+		 ALTER TABLE field_locks RENAME TO field_locks_old;",
+	)?;
+	conn.execute_batch(FIELD_LOCKS_SQL)?;
+	conn.execute_batch(
+		"INSERT INTO field_locks (vulnerability_id, field, value, locked_by, locked_at)
+		 SELECT vulnerability_id, field, value, locked_by, locked_at FROM field_locks_old;
+		 DROP TABLE field_locks_old;",
+	)?;
+	Ok(())
+}
+
+fn apply_field_provenance_migration(conn: &Connection) -> Result<()> {
+	info!("Applying field provenance migration");
+	conn.execute_batch(FIELD_PROVENANCE_SQL)?;
+	// Fields locked so far were all set by hand
+	conn.execute_batch(
+		"INSERT OR IGNORE INTO field_provenance (vulnerability_id, field, source, updated_at)
+		 SELECT vulnerability_id, field, 'manual', locked_at FROM field_locks;",
+	)?;
+	Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use rusqlite::Connection;
-	use tempfile::tempdir;
+	use tempfile::{tempdir, TempDir};
 
-	fn setup_test_db() -> Result<Connection> {
+	fn setup_test_db() -> Result<(Connection, TempDir)> {
 		let dir = tempdir()?;
 		let path = dir.path().join("test.db");
 		let conn = Connection::open(path)?;
-		Ok(conn)
+		Ok((conn, dir))
 	}
 
 	#[test]
 	fn test_schema_creation() -> Result<()> {
-		let conn = setup_test_db()?;
+		let (conn, _dir) = setup_test_db()?;
 		create_tables(&conn)?;
 
 		let tables: Vec<String> = conn
@@ -283,13 +1442,16 @@ mod tests {
 		assert!(tables.contains(&"software_versions".to_string()));
 		assert!(tables.contains(&"robot_software".to_string()));
 		assert!(tables.contains(&"affected_software".to_string()));
+		assert!(tables.contains(&"vulnerability_status".to_string()));
+		assert!(tables.contains(&"enrichment_runs".to_string()));
+		assert!(tables.contains(&"vulnerability_references".to_string()));
 
 		Ok(())
 	}
 
 	#[test]
 	fn test_schema_version_management() -> Result<()> {
-		let conn = setup_test_db()?;
+		let (conn, _dir) = setup_test_db()?;
 		assert_eq!(get_schema_version(&conn)?, 0);
 		update_schema_version(&conn, 1, "Test migration")?;
 		assert_eq!(get_schema_version(&conn)?, 1);
@@ -298,9 +1460,45 @@ mod tests {
 
 	#[test]
 	fn test_migrations() -> Result<()> {
-		let conn = setup_test_db()?;
+		let (conn, _dir) = setup_test_db()?;
+		check_schema_version(&conn)?;
+		assert_eq!(get_schema_version(&conn)?, SCHEMA_VERSION);
+
+		// Running again on an up-to-date database is a no-op
 		check_schema_version(&conn)?;
-		assert_eq!(get_schema_version(&conn)?, 3);
+		assert_eq!(get_schema_version(&conn)?, SCHEMA_VERSION);
 		Ok(())
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn test_migrations_on_fresh_schema() -> Result<()> {
+		let (conn, _dir) = setup_test_db()?;
+		// Column-adding migrations must not fail when create_tables already has the columns
+		create_tables(&conn)?;
+		check_schema_version(&conn)?;
+		assert_eq!(get_schema_version(&conn)?, SCHEMA_VERSION);
+		Ok(())
+	}
+
+	#[test]
+	fn test_utc_timestamp_migration() -> Result<()> {
+		let (conn, _dir) = setup_test_db()?;
+		// Migrating from scratch creates the tables with the old CURRENT_TIMESTAMP defaults
+		check_schema_version(&conn)?;
+		conn.execute_batch(
+			"INSERT INTO notes (entity_type, entity_id, body, created_at, updated_at)
+			 VALUES ('robot', 1, 'old', '2024-05-01 12:30:00', '2024-05-01 12:30:00');
+			 DELETE FROM schema_version WHERE version >= 12;"
+		)?;
+		check_schema_version(&conn)?;
+
+		let created: String = conn.query_row("SELECT created_at FROM notes WHERE body = 'old'", [], |row| row.get(0))?;
+		assert_eq!(created, "2024-05-01T12:30:00Z");
+
+		// New rows relying on the old default are rewritten by the trigger
+		conn.execute("INSERT INTO notes (entity_type, entity_id, body) VALUES ('robot', 1, 'new')", [])?;
+		let created: String = conn.query_row("SELECT created_at FROM notes WHERE body = 'new'", [], |row| row.get(0))?;
+		assert!(created.contains('T') && created.ends_with('Z'), "{}", created);
+		Ok(())
+	}
+}
@@ -0,0 +1,167 @@
+// src/db/storage.rs
+
+//! A partial storage backend covering only the terminal shell: its vulnerability and
+//! robot operations run on the local SQLite workspace, or with the `postgres` feature
+//! on a PostgreSQL server that several people triage in at once.
+//!
+//! This is not a storage layer for the repositories. The GUI, imports, enrichment and
+//! the other repositories work on the SQLite workspace directly, and a server database
+//! is filled from a workspace with `push-database`, a one-way copy. Moving them behind
+//! a storage trait is tracked in the README.
+
+use crate::db::connection::SqlitePool;
+use crate::models::robot::Robot;
+use crate::models::vulnerability::{RiskAcceptance, TriageStatus, Vulnerability};
+use crate::repositories::robot_repo::RobotRepository;
+use crate::repositories::vulnerability_repo::{SortOrder, VulnerabilityFilter, VulnerabilityRepository};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Environment variable holding the URL of a shared server database, e.g.
+/// `postgres://rvd@db.example.com/rvd`
+pub const DATABASE_URL_ENV: &str = "RVD_DATABASE_URL";
+
+#[async_trait]
+pub trait ShellStorage: Send + Sync {
+	/// Up to `limit` vulnerabilities whose ID, description or aliases contain `text`,
+	/// and whether more match
+	async fn search_vulnerabilities(&self, text: &str, limit: usize) -> Result<(Vec<Vulnerability>, bool)>;
+
+	/// The vulnerability with this CVE ID, ignoring case
+	async fn get_vulnerability_by_cve(&self, cve_id: &str) -> Result<Option<Vulnerability>>;
+
+	/// Sets the triage status; the risk decision details are only kept for risk decisions
+	async fn update_triage(
+		&self,
+		vulnerability_id: i64,
+		status: TriageStatus,
+		assigned_to: Option<String>,
+		acceptance: RiskAcceptance,
+	) -> Result<()>;
+
+	async fn get_all_robots(&self) -> Result<Vec<Robot>>;
+
+	/// Up to `limit` CVE IDs starting with `prefix`, by year and then sequence number,
+	/// highest first. Blocks, for completion off the async workers.
+	fn cve_ids_with_prefix(&self, prefix: &str, limit: usize) -> Result<Vec<String>>;
+}
+
+/// Orders CVE IDs by year and then sequence number, highest first. Within a year a
+/// longer sequence number is the higher one, so CVE-2024-10000 comes before CVE-2024-9999.
+pub(crate) const CVE_ID_ORDER: &str = "substr(cve_id, 5, 4) DESC, length(cve_id) DESC, cve_id DESC";
+
+/// `LIKE` pattern matching IDs that start with `prefix`, for use with `ESCAPE '\'`, so
+/// that `%` and `_` in the prefix match only themselves
+pub(crate) fn like_prefix(prefix: &str) -> String {
+	format!("{}%", prefix.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"))
+}
+
+/// The local SQLite workspace, through the repositories
+pub struct SqliteStorage {
+	pool: Arc<SqlitePool>,
+}
+
+impl SqliteStorage {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+}
+
+#[async_trait]
+impl ShellStorage for SqliteStorage {
+	async fn search_vulnerabilities(&self, text: &str, limit: usize) -> Result<(Vec<Vulnerability>, bool)> {
+		let filter = VulnerabilityFilter { search: text.to_string(), ..VulnerabilityFilter::default() };
+		let page = VulnerabilityRepository::new(self.pool.clone())
+			.search_vulnerabilities(filter, SortOrder::default(), 0, limit)
+			.await?;
+		Ok((page.vulnerabilities, page.total_pages > 1))
+	}
+
+	async fn get_vulnerability_by_cve(&self, cve_id: &str) -> Result<Option<Vulnerability>> {
+		VulnerabilityRepository::new(self.pool.clone()).get_vulnerability_by_cve(cve_id).await
+	}
+
+	async fn update_triage(
+		&self,
+		vulnerability_id: i64,
+		status: TriageStatus,
+		assigned_to: Option<String>,
+		acceptance: RiskAcceptance,
+	) -> Result<()> {
+		VulnerabilityRepository::new(self.pool.clone())
+			.update_triage(vulnerability_id, status, assigned_to, acceptance)
+			.await
+	}
+
+	async fn get_all_robots(&self) -> Result<Vec<Robot>> {
+		RobotRepository::new(self.pool.clone()).get_all_robots().await
+	}
+
+	fn cve_ids_with_prefix(&self, prefix: &str, limit: usize) -> Result<Vec<String>> {
+		let conn = self.pool.get().context("Failed to get database connection")?;
+		let mut stmt = conn.prepare(&format!(
+			"SELECT cve_id FROM vulnerabilities WHERE cve_id LIKE ?1 ESCAPE '\\' AND deleted_at IS NULL
+			 ORDER BY {} LIMIT ?2",
+			CVE_ID_ORDER
+		))?;
+		let ids = stmt
+			.query_map(rusqlite::params![like_prefix(prefix), limit as i64], |row| row.get(0))?
+			.collect::<rusqlite::Result<Vec<String>>>()?;
+		Ok(ids)
+	}
+}
+
+/// The server database at `url`
+pub fn open(url: &str) -> Result<Arc<dyn ShellStorage>> {
+	match url {
+		#[cfg(feature = "postgres")]
+		url if url.starts_with("postgres://") || url.starts_with("postgresql://") => {
+			Ok(Arc::new(super::postgres::PostgresStorage::connect(url)?))
+		}
+		url if url.starts_with("postgres://") || url.starts_with("postgresql://") => {
+			anyhow::bail!("This build of RVD has no PostgreSQL support; rebuild it with --features postgres")
+		}
+		// Not echoing the URL, it may hold a password
+		url => anyhow::bail!(
+			"Unsupported database URL scheme '{}', expected postgres://...",
+			url.split(':').next().unwrap_or_default()
+		),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_sqlite_storage() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO robots (name, manufacturer, criticality) VALUES ('cobot-02', 'ABB', 'High'), ('arm-01', 'KUKA', 'Low');
+			 INSERT INTO software_products (product_name, vendor) VALUES ('ros-core', 'OSRF');
+			 INSERT INTO vulnerabilities (cve_id, description, severity) VALUES
+				('CVE-2024-0001', 'Buffer overflow in the ROS bridge', 'High'), ('CVE-2024-0002', 'Other', 'Low'),
+				('CVE-2024-10000', 'Longer sequence number', 'Low'), ('CVE-2023-99999', 'Older year', 'Low');",
+		)?;
+		let storage = SqliteStorage::new(pool);
+
+		let robots = storage.get_all_robots().await?;
+		assert_eq!(robots.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(), ["arm-01", "cobot-02"]);
+		assert_eq!(robots[1].criticality.as_str(), "High");
+
+		let (found, more) = storage.search_vulnerabilities("ros bridge", 1).await?;
+		assert_eq!((found.len(), found[0].cve_id.as_str(), more), (1, "CVE-2024-0001", false));
+		assert_eq!(storage.cve_ids_with_prefix("CVE-2024", 2)?, ["CVE-2024-10000", "CVE-2024-0002"]);
+		assert_eq!(storage.cve_ids_with_prefix("cve-", 4)?.last().map(String::as_str), Some("CVE-2023-99999"));
+		assert!(storage.cve_ids_with_prefix("CVE-2024-000_", 5)?.is_empty());
+		assert!(storage.cve_ids_with_prefix("%", 5)?.is_empty());
+
+		let error = open("mysql://rvd:secret@db/rvd").err().map(|e| e.to_string()).unwrap_or_default();
+		assert!(error.contains("'mysql'") && !error.contains("secret"));
+		Ok(())
+	}
+}
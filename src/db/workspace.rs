@@ -0,0 +1,120 @@
+// src/db/workspace.rs
+
+//! Named workspaces, each a separate SQLite file (e.g. one per customer). Settings
+//! such as role, time zone and alerts live in each database, so every workspace
+//! keeps its own configuration. The `default` workspace is the original database.
+
+use anyhow::{bail, Context, Result};
+use std::path::PathBuf;
+
+pub const DEFAULT_WORKSPACE: &str = "default";
+const WORKSPACES_DIR: &str = "workspaces";
+/// Remembers the workspace chosen last, read before any database is open
+const CURRENT_FILE: &str = "current_workspace";
+
+/// The workspaces kept under one data directory
+#[derive(Debug, Clone)]
+pub struct Workspaces {
+	root: PathBuf,
+}
+
+impl Default for Workspaces {
+	fn default() -> Self {
+		Self::new(PathBuf::from("database"))
+	}
+}
+
+impl Workspaces {
+	pub fn new(root: PathBuf) -> Self {
+		Self { root }
+	}
+
+	/// Database file of a workspace; it is created when first opened
+	pub fn database_path(&self, name: &str) -> Result<PathBuf> {
+		validate_name(name)?;
+		Ok(if name == DEFAULT_WORKSPACE {
+			self.root.join("vulnerabilities.db")
+		} else {
+			self.root.join(WORKSPACES_DIR).join(format!("{}.db", name))
+		})
+	}
+
+	/// The default workspace followed by the others in name order
+	pub fn list(&self) -> Result<Vec<String>> {
+		let mut names = Vec::new();
+		let dir = self.root.join(WORKSPACES_DIR);
+		if dir.is_dir() {
+			for entry in std::fs::read_dir(&dir).with_context(|| format!("Failed to list {:?}", dir))? {
+				let path = entry?.path();
+				if path.extension().is_some_and(|ext| ext == "db") {
+					if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+						if validate_name(name).is_ok() && name != DEFAULT_WORKSPACE {
+							names.push(name.to_string());
+						}
+					}
+				}
+			}
+		}
+		names.sort();
+		names.insert(0, DEFAULT_WORKSPACE.to_string());
+		Ok(names)
+	}
+
+	/// The workspace opened when none is given, the last one chosen
+	pub fn current(&self) -> String {
+		std::fs::read_to_string(self.current_file())
+			.ok()
+			.map(|name| name.trim().to_string())
+			.filter(|name| validate_name(name).is_ok())
+			.unwrap_or_else(|| DEFAULT_WORKSPACE.to_string())
+	}
+
+	pub fn set_current(&self, name: &str) -> Result<()> {
+		validate_name(name)?;
+		std::fs::create_dir_all(&self.root).context("Failed to create database directory")?;
+		std::fs::write(self.current_file(), name).context("Failed to save the current workspace")
+	}
+
+	fn current_file(&self) -> PathBuf {
+		self.root.join(CURRENT_FILE)
+	}
+}
+
+/// Workspace names become file names, so only letters, digits, '-' and '_' are allowed
+pub fn validate_name(name: &str) -> Result<()> {
+	if name.is_empty() || name.len() > 64 {
+		bail!("Workspace names must be 1 to 64 characters long");
+	}
+	if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+		bail!("Invalid workspace name '{}': use letters, digits, '-' and '_'", name);
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_workspaces() -> Result<()> {
+		let dir = tempdir()?;
+		let workspaces = Workspaces::new(dir.path().to_path_buf());
+		assert_eq!(workspaces.list()?, [DEFAULT_WORKSPACE]);
+		assert_eq!(workspaces.current(), DEFAULT_WORKSPACE);
+		assert!(workspaces.database_path(DEFAULT_WORKSPACE)?.ends_with("vulnerabilities.db"));
+
+		let acme = workspaces.database_path("acme")?;
+		std::fs::create_dir_all(acme.parent().unwrap())?;
+		std::fs::write(&acme, "")?;
+		std::fs::write(acme.with_file_name("notes.txt"), "")?;
+		assert_eq!(workspaces.list()?, [DEFAULT_WORKSPACE, "acme"]);
+
+		workspaces.set_current("acme")?;
+		assert_eq!(workspaces.current(), "acme");
+
+		assert!(workspaces.database_path("../acme").is_err());
+		assert!(workspaces.set_current("").is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,86 @@
+use super::state::AppState;
+use super::types::Message;
+use super::formatters::{format_error, format_muted};
+use crate::utils::update_check::CURRENT_VERSION;
+use iced::{
+	theme,
+	widget::{button, column, container, row, scrollable, Checkbox, Text},
+	Alignment, Element, Length,
+};
+
+pub trait AboutViewRenderer {
+	fn about_dialog(&self) -> Element<'_, Message>;
+}
+
+impl AboutViewRenderer for AppState {
+	fn about_dialog(&self) -> Element<'_, Message> {
+		let status = &self.update_status;
+		let theme = self.theme();
+
+		let outcome: Element<Message> = if !status.settings.enabled {
+			Text::new("RVD does not look for new releases, as many sites run without internet access")
+				.size(14)
+				.style(theme::Text::Color(format_muted(&theme)))
+				.into()
+		} else if status.checking {
+			Text::new("Looking for a new release...").size(14).into()
+		} else if let Some(err) = &status.error {
+			Text::new(format!("Could not check for a new release: {}", err))
+				.size(14)
+				.style(theme::Text::Color(format_error(&theme)))
+				.into()
+		} else if let Some(update) = &status.update {
+			let mut notes = column![Text::new(format!("New version available: {}", update.title)).size(18)].spacing(8);
+			if let Some(url) = &update.url {
+				notes = notes.push(Text::new(format!("Download: {}", url)).size(14));
+			}
+			let release_notes = if update.notes.trim().is_empty() { "No release notes" } else { update.notes.as_str() };
+			notes
+				.push(
+					container(scrollable(Text::new(release_notes).size(14)).height(Length::Fixed(240.0)))
+						.style(theme::Container::Box)
+						.padding(10)
+						.width(Length::Fill),
+				)
+				.into()
+		} else {
+			Text::new("This is the latest release").size(14).into()
+		};
+
+		container(
+			column![
+				row![
+					Text::new("About RVD").size(28).width(Length::Fill),
+					button(Text::new("Close").size(16))
+						.on_press(Message::AboutClosed)
+						.style(theme::Button::Destructive)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				Text::new(format!("Robot Vulnerability Database {}", CURRENT_VERSION)).size(16),
+				Text::new(format!("Workspace: {}", self.workspace)).size(14),
+				row![
+					Checkbox::new("Check for new releases on startup", status.settings.enabled)
+						.on_toggle(Message::UpdateCheckToggled)
+						.spacing(5),
+					button(Text::new("Check Now").size(14))
+						.on_press_maybe((status.settings.enabled && !status.checking).then_some(Message::UpdateCheckRequested))
+						.style(theme::Button::Secondary)
+						.padding(6),
+				]
+					.spacing(20)
+					.align_items(Alignment::Center),
+				Text::new(format!("Release endpoint: {}", status.settings.endpoint))
+					.size(12)
+					.style(theme::Text::Color(format_muted(&theme))),
+				outcome,
+			]
+				.spacing(15),
+		)
+			.padding(20)
+			.width(Length::Fill)
+			.style(theme::Container::Box)
+			.into()
+	}
+}
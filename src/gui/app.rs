@@ -1,86 +1,243 @@
-use iced::{Application, Command, Element, Settings, Size, Theme};
+use iced::{subscription, Application, Command, Element, Settings, Size, Subscription, Theme};
 use std::sync::Arc;
+use std::time::Instant;
 use anyhow::{Result, Context};
-use log::error;
+use log::{error, info, warn};
 
 use crate::db::connection::SqlitePool;
+use crate::models::data_source::DataSource;
+use crate::models::csv_mapping::CsvMapping;
+use crate::models::note::NoteEntity;
+use crate::models::trash::DeletedKind;
+use crate::reports::open_html_report;
+use crate::utils::progress::{CancellationToken, ProgressReceiver, ProgressReporter};
+use crate::utils::update_check;
+use super::appearance::DetailLayout;
 use super::state::AppState;
-use super::types::{Message, Tab};
+use super::types::{AuditLogView, CsvColumn, CsvImportView, DataSourcesView, FieldEditForm, FilterAuditEntity, MaintenanceStatus, Message, SyncInterval, Tab};
 use super::views::ViewRenderer;
+use super::toast::{ToastLevel, ToastViewRenderer};
+use super::profiler::{self, Profiler, ProfilerViewRenderer};
 use super::robot_view::RobotViewRenderer;
-use super::database::{load_vulnerabilities, load_robots};
-use super::constants::{LOAD_PAGE_SIZE, DISPLAY_PAGE_SIZE, SCROLL_THRESHOLD};
+use super::graph_view::GraphViewRenderer;
+use super::maintenance_view::MaintenanceViewRenderer;
+use super::about_view::AboutViewRenderer;
+use super::trash_view::TrashViewRenderer;
+use super::audit_view::AuditViewRenderer;
+use super::import_history_view::ImportHistoryViewRenderer;
+use super::data_sources_view::DataSourcesViewRenderer;
+use super::whats_new_view::WhatsNewViewRenderer;
+use super::csv_import_view::CsvImportViewRenderer;
+use super::software_view::SoftwareViewRenderer;
+use super::trends_view::TrendsViewRenderer;
+use super::database::{load_vulnerabilities, load_vulnerability_by_cve, load_robots, load_risky_software, load_enrichment_progress, load_statistics_report, load_quick_filter_counts, check_compaction, compact_database, load_nvd_health, load_row_tint, save_row_tint, load_list_layout, save_list_layout, load_table_columns, save_table_columns, load_theme, save_theme, load_detail_layout, save_detail_layout, load_color_blind_safe, save_color_blind_safe, open_workspace, load_graph, load_version_metadata, save_version_metadata, load_trends};
+use crate::db::compaction::CompactionMode;
+use crate::db::maintenance;
+use super::constants::{DISPLAY_PAGE_SIZE, SCROLL_THRESHOLD, TOAST_TICK, TOP_RISKY_SOFTWARE_LIMIT};
 
 
 pub struct VulnerabilityApp {
 	state: AppState,
+	progress_rx: ProgressReceiver,
+	progress: ProgressReporter,
+	cancel: CancellationToken,
 }
 
 impl Application for VulnerabilityApp {
 	type Executor = iced::executor::Default;
 	type Message = Message;
 	type Theme = Theme;
-	type Flags = Arc<SqlitePool>;
+	type Flags = (Arc<SqlitePool>, ProgressReceiver, ProgressReporter, String, Option<String>);
 
-	fn new(pool: Self::Flags) -> (Self, Command<Self::Message>) {
-		let app = VulnerabilityApp {
-			state: AppState::new(pool.clone()),
+	fn new((pool, progress_rx, progress, workspace, open_cve): Self::Flags) -> (Self, Command<Self::Message>) {
+		let mut app = VulnerabilityApp {
+			state: AppState::new(pool, workspace),
+			progress_rx,
+			cancel: progress.cancellation_token(),
+			progress,
 		};
-
-		// Convert error types properly in Command::perform callbacks
-		(
-			app,
-			Command::batch(vec![
-				Command::perform(
-					load_vulnerabilities(
-						pool.clone(),
-						String::new(),
-						0,
-						LOAD_PAGE_SIZE,
-						super::types::SortField::None,
-						true,
-						super::types::FilterSeverity::All,
-					),
-					|result| Message::VulnerabilitiesLoaded(result.map_err(|e| e.to_string())),
-				),
-				Command::perform(
-					load_robots(pool),
-					|result| Message::RobotsLoaded(result.map_err(|e| e.to_string())),
-				),
-			])
-		)
+		// A deep link starts with a search for the CVE, which is then opened
+		if let Some(cve_id) = open_cve {
+			app.state.search_query = cve_id.clone();
+			app.state.pending_open = Some(cve_id);
+		}
+		let load = app.load_workspace();
+		(app, load)
 	}
 
 	fn title(&self) -> String {
-		String::from("Robot Vulnerability Management System")
+		format!("Robot Vulnerability Management System - {}", self.state.workspace)
+	}
+
+	fn theme(&self) -> Theme {
+		self.state.theme()
 	}
 
 	fn update(&mut self, message: Message) -> Command<Message> {
+		if message.modifies_data() && !self.state.role.can_edit() {
+			self.state.toasts.warning(format!("The {} role is read-only", self.state.role));
+			return Command::none();
+		}
+		if let Some(profiler) = &mut self.state.profiler {
+			if !matches!(message, Message::ProfilerFrame(_)) {
+				profiler.message(Instant::now());
+			}
+		}
+
 		match message {
 			Message::TabSelected(tab) => {
+				let load = match tab {
+					Tab::Software => self.load_software_versions(),
+					Tab::Trends => Command::perform(
+						load_trends(self.state.pool.clone()),
+						|result| Message::TrendsLoaded(result.map_err(|e| e.to_string())),
+					),
+					_ => Command::none(),
+				};
 				self.state.current_tab = tab;
+				self.state.about_open = false;
+				self.state.maintenance = None;
+				self.state.trash = None;
+				self.state.audit = None;
+				self.state.import_runs = None;
+				self.state.data_sources_view = None;
+				self.state.whats_new = None;
+				self.state.csv_import = None;
 				self.state.clear_selection();
+				load
+			}
+
+			Message::SoftwareVersionsLoaded(result) => {
+				match result {
+					Ok(versions) => {
+						self.state.software_versions = versions;
+						// Drop versions that no longer exist from the selection
+						let selected = self.state.software_versions
+							.iter()
+							.map(|version| version.version_id)
+							.filter(|id| self.state.selected_versions.contains(id))
+							.collect();
+						self.state.select_versions(selected);
+					}
+					Err(err) => {
+						error!("Failed to load software versions: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::VersionFilterChanged(filter) => {
+				self.state.version_filter = filter;
+				Command::none()
+			}
+
+			Message::VersionToggled(version_id, selected) => {
+				let mut versions = self.state.selected_versions.clone();
+				if selected {
+					versions.insert(version_id);
+				} else {
+					versions.remove(&version_id);
+				}
+				self.state.select_versions(versions);
+				Command::none()
+			}
+
+			Message::SelectShownVersions => {
+				let mut versions = self.state.selected_versions.clone();
+				versions.extend(self.state.shown_versions().iter().map(|version| version.version_id));
+				self.state.select_versions(versions);
+				Command::none()
+			}
+
+			Message::ClearVersionSelection => {
+				self.state.select_versions(Default::default());
+				Command::none()
+			}
+
+			Message::VersionReleaseDateChanged(value) => {
+				self.state.version_editor.release_date = Some(value);
+				Command::none()
+			}
+
+			Message::VersionEolDateChanged(value) => {
+				self.state.version_editor.eol_date = Some(value);
+				Command::none()
+			}
+
+			Message::VersionNotesChanged(value) => {
+				self.state.version_editor.notes = Some(value);
+				Command::none()
+			}
+
+			Message::SaveVersionMetadata => {
+				let change = match self.state.version_editor.change() {
+					Ok(change) if change.is_empty() => {
+						self.state.toasts.warning("Nothing to save; edit a field first");
+						return Command::none();
+					}
+					Ok(change) => change,
+					Err(err) => {
+						self.state.toasts.error(err);
+						return Command::none();
+					}
+				};
+				Command::perform(
+					save_version_metadata(
+						self.state.pool.clone(),
+						self.state.selected_versions.iter().copied().collect(),
+						change,
+					),
+					|result| Message::VersionMetadataSaved(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::VersionMetadataSaved(result) => {
+				match result {
+					Ok(updated) => {
+						self.state.toasts.success(format!("Updated {} software versions", updated));
+						return self.load_software_versions();
+					}
+					Err(err) => {
+						error!("Failed to save version metadata: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
 				Command::none()
 			}
 
 			// Vulnerability-related messages with proper error handling
 			Message::VulnerabilitiesLoaded(result) => {
 				self.state.loading = false;
+				let pending_open = self.state.pending_open.take();
 				match result {
-					Ok((new_vulnerabilities, total_pages)) => {
-						if self.state.last_loaded_page > 0 {
-							self.state.vulnerabilities.extend(new_vulnerabilities);
-						} else {
-							self.state.vulnerabilities = new_vulnerabilities;
+					Ok(page) => {
+						self.state.displayed_vulnerabilities = page.vulnerabilities;
+						self.state.card_menu = None;
+						self.state.total_pages = page.total_pages;
+						if let Some(next) = page.next {
+							let page = self.state.current_page;
+							self.state.page_cursors.truncate(page);
+							if self.state.page_cursors.len() == page {
+								self.state.page_cursors.push(next);
+							}
+						}
+						if let Some(cve_id) = pending_open {
+							let found = self.state.displayed_vulnerabilities
+								.iter()
+								.position(|v| v.cve_id.eq_ignore_ascii_case(&cve_id));
+							return match found {
+								Some(idx) => self.update(Message::VulnerabilitySelected(idx)),
+								None => Command::perform(
+									load_vulnerability_by_cve(self.state.pool.clone(), cve_id),
+									|result| Message::DeepLinkResolved(result.map_err(|e| e.to_string())),
+								),
+							};
 						}
-						self.state.last_loaded_page += 1;
-						self.state.total_pages = total_pages;
-						self.state.update_displayed_vulnerabilities();
-						self.state.error_message = None;
 					}
 					Err(err) => {
 						error!("Failed to load vulnerabilities: {}", err);
-						self.state.error_message = Some(err);
+						self.state.toasts.error(err);
 					}
 				}
 				Command::none()
@@ -110,27 +267,8 @@ impl Application for VulnerabilityApp {
 				if page < self.state.total_pages {
 					self.state.current_page = page;
 					self.state.selected_vulnerability = None;
-					self.state.update_displayed_vulnerabilities();
-
-					if page >= self.state.last_loaded_page * (LOAD_PAGE_SIZE / DISPLAY_PAGE_SIZE) {
-						self.state.loading = true;
-						let pool = self.state.pool.clone();
-						let query = self.state.search_query.clone();
-						Command::perform(
-							load_vulnerabilities(
-								pool,
-								query,
-								self.state.last_loaded_page + 1,
-								LOAD_PAGE_SIZE,
-								self.state.sort_field.clone(),
-								self.state.sort_ascending,
-								self.state.filter_severity.clone(),
-							),
-							|result| Message::VulnerabilitiesLoaded(result.map_err(|e| e.to_string())),
-						)
-					} else {
-						Command::none()
-					}
+					self.state.loading = true;
+					self.load_page()
 				} else {
 					Command::none()
 				}
@@ -140,46 +278,18 @@ impl Application for VulnerabilityApp {
 				self.state.loading = true;
 				self.state.selected_vulnerability = None;
 				self.state.current_page = 0;
-				self.state.last_loaded_page = 0;
-				self.state.vulnerabilities.clear();
+				self.state.page_cursors.clear();
 				self.state.displayed_vulnerabilities.clear();
-				let pool = self.state.pool.clone();
-				let query = self.state.search_query.clone();
-				Command::perform(
-					load_vulnerabilities(
-						pool,
-						query,
-						0,
-						LOAD_PAGE_SIZE,
-						self.state.sort_field.clone(),
-						self.state.sort_ascending,
-						self.state.filter_severity.clone(),
-					),
-					|result| Message::VulnerabilitiesLoaded(result.map_err(|e| e.to_string())),
-				)
+				self.load_page()
 			}
 
 			Message::SearchSubmitted => {
 				self.state.current_page = 0;
-				self.state.last_loaded_page = 0;
+				self.state.page_cursors.clear();
 				self.state.loading = true;
 				self.state.selected_vulnerability = None;
-				self.state.vulnerabilities.clear();
 				self.state.displayed_vulnerabilities.clear();
-				let pool = self.state.pool.clone();
-				let query = self.state.search_query.clone();
-				Command::perform(
-					load_vulnerabilities(
-						pool,
-						query,
-						0,
-						LOAD_PAGE_SIZE,
-						self.state.sort_field.clone(),
-						self.state.sort_ascending,
-						self.state.filter_severity.clone(),
-					),
-					|result| Message::VulnerabilitiesLoaded(result.map_err(|e| e.to_string())),
-				)
+				self.load_page()
 			}
 
 			Message::SortFieldSelected(field) => {
@@ -197,214 +307,2179 @@ impl Application for VulnerabilityApp {
 				self.update(Message::RefreshData)
 			}
 
-			Message::ToggleStatistics(value) => {
-				self.state.show_statistics = value;
-				Command::none()
+			Message::FilterStatusChanged(status) => {
+				self.state.filter_status = status;
+				self.update(Message::RefreshData)
 			}
 
-			Message::VulnerabilitySelected(idx) => {
-				self.state.selected_vulnerability = Some(idx);
-				Command::none()
+			Message::FilterWeaknessChanged(weakness) => {
+				self.state.filter_weakness = weakness;
+				self.update(Message::RefreshData)
 			}
 
-			Message::ClearSelection => {
-				self.state.clear_selection();
+			Message::QuickFilterToggled(filter) => {
+				if !self.state.quick_filters.remove(&filter) {
+					self.state.quick_filters.insert(filter);
+				}
+				self.update(Message::RefreshData)
+			}
+
+			Message::QuickFilterCountsLoaded(result) => {
+				match result {
+					Ok(counts) => self.state.quick_filter_counts = counts,
+					Err(err) => error!("Failed to count quick filters: {}", err),
+				}
 				Command::none()
 			}
 
-			Message::ScrollChanged(offset) => {
-				self.state.scroll_offset = offset;
-				if offset > SCROLL_THRESHOLD && self.state.current_page + 1 < self.state.total_pages {
-					self.update(Message::PageChanged(self.state.current_page + 1))
+			Message::ToggleStatistics(value) => {
+				self.state.show_statistics = value;
+				if value {
+					self.load_statistics()
 				} else {
 					Command::none()
 				}
 			}
 
-			// Robot-related messages with proper error handling
-			Message::RobotsLoaded(result) => {
+			Message::EnrichmentProgressLoaded(result) => {
 				match result {
-					Ok(robots) => {
-						self.state.robots = robots;
-						self.state.error_message = None;
+					Ok(progress) => self.state.enrichment_progress = Some(progress),
+					Err(err) => error!("Failed to load enrichment progress: {}", err),
+				}
+				Command::none()
+			}
+
+			Message::StatisticsLoaded(result) => {
+				match result {
+					Ok(report) => self.state.statistics = Some(report),
+					Err(err) => {
+						error!("Failed to load statistics: {}", err);
+						self.state.toasts.error(err);
 					}
+				}
+				Command::none()
+			}
+
+			Message::TrendsLoaded(result) => {
+				match result {
+					Ok(trends) => self.state.trends = Some(trends),
 					Err(err) => {
-						error!("Failed to load robots: {}", err);
-						self.state.error_message = Some(err);
+						error!("Failed to load trends: {}", err);
+						self.state.toasts.error(err);
 					}
 				}
 				Command::none()
 			}
 
-			Message::AddRobotClicked => {
-				self.state.clear_robot_form();
-				self.state.showing_robot_form = true; // Add this line
+			Message::RiskySoftwareLoaded(result) => {
+				match result {
+					Ok(software) => self.state.risky_software = software,
+					Err(err) => {
+						error!("Failed to load risky software: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
 				Command::none()
 			}
 
+			Message::RiskySoftwareSelected(idx) => {
+				if let Some(software) = self.state.risky_software.get(idx).cloned() {
+					self.state.software_filter = Some(software);
+					self.update(Message::RefreshData)
+				} else {
+					Command::none()
+				}
+			}
 
-			Message::EditRobotClicked(robot_id) => {
-				// Solution to fix E0502 error: Limit the immutable borrow scope
-				let robot_opt = self.state.robots.iter()
-					.find(|r| r.robot_id == Some(robot_id))
-					.cloned(); // Clone to avoid holding the immutable borrow
+			Message::ClearSoftwareFilter => {
+				self.state.software_filter = None;
+				self.update(Message::RefreshData)
+			}
 
-				if let Some(robot) = robot_opt {
-					self.state.set_robot_form(&robot);
+			Message::VulnerabilitySelected(idx) => {
+				self.state.card_menu = None;
+				self.state.select_vulnerability(idx);
+				let vulnerability_id = self.state.displayed_vulnerabilities
+					.get(idx)
+					.and_then(|vuln| vuln.vulnerability_id);
+				match vulnerability_id {
+					Some(id) => Command::batch(vec![
+						self.load_notes(),
+						Command::perform(
+							super::database::load_references(self.state.pool.clone(), id),
+							|result| Message::ReferencesLoaded(result.map_err(|e| e.to_string())),
+						),
+						Command::perform(
+							super::database::load_related(self.state.pool.clone(), id),
+							|result| Message::RelatedLoaded(result.map_err(|e| e.to_string())),
+						),
+						self.load_linked_controls(id),
+						Command::perform(
+							super::database::load_ticket(self.state.pool.clone(), id),
+							move |result| Message::TicketLoaded(id, result.map_err(|e| e.to_string())),
+						),
+					]),
+					None => self.load_notes(),
 				}
-				Command::none()
 			}
 
-			Message::DeleteRobotClicked(robot_id) => {
-				Command::perform(
-					super::database::delete_robot(self.state.pool.clone(), robot_id),
-					|result| Message::RobotDeleted(result.map_err(|e| e.to_string())),
-				)
+			Message::DeepLinkResolved(result) => {
+				match result {
+					Ok((_, Some(vulnerability))) => {
+						self.state.displayed_vulnerabilities.insert(0, vulnerability);
+						return self.update(Message::VulnerabilitySelected(0));
+					}
+					Ok((cve_id, None)) => {
+						self.state.toasts.error(format!("{} is not in the database", cve_id));
+					}
+					Err(err) => {
+						error!("Failed to open linked vulnerability: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
 			}
 
-			Message::RobotFormNameChanged(name) => {
-				self.state.robot_form.name = name;
+			Message::TriageStatusSelected(status) => {
+				self.state.triage_status = status;
 				Command::none()
 			}
 
-			Message::RobotFormManufacturerChanged(manufacturer) => {
-				self.state.robot_form.manufacturer = manufacturer;
+			Message::TriageAssigneeChanged(assignee) => {
+				self.state.triage_assignee = assignee;
 				Command::none()
 			}
 
-			Message::RobotFormSpecificationsChanged(specifications) => {
-				self.state.robot_form.specifications = specifications;
+			Message::TriageJustificationChanged(justification) => {
+				self.state.triage_justification = justification;
 				Command::none()
 			}
 
-			Message::RobotFormSoftwareAdded(version) => {
-				if !version.trim().is_empty() {
-					self.state.robot_form.software_versions.push(version);
-				}
+			Message::TriageApproverChanged(approver) => {
+				self.state.triage_approver = approver;
 				Command::none()
 			}
 
-			Message::RobotFormSoftwareRemoved(idx) => {
-				if idx < self.state.robot_form.software_versions.len() {
-					self.state.robot_form.software_versions.remove(idx);
-				}
+			Message::TriageExpiryChanged(expires) => {
+				self.state.triage_expires = expires;
 				Command::none()
 			}
 
-			Message::RobotFormSubmitted => {
-				let form = self.state.robot_form.clone();
-				if let Err(err) = super::types::validate_robot_form(&form) {
-					self.state.error_message = Some(err.to_string());
-					return Command::none();
+			Message::TriageSaved => {
+				let vulnerability_id = self.state.selected_vulnerability
+					.and_then(|idx| self.state.displayed_vulnerabilities.get(idx))
+					.and_then(|v| v.vulnerability_id);
+
+				let acceptance = match self.state.triage_acceptance() {
+					Ok(acceptance) => acceptance,
+					Err(err) => {
+						self.state.toasts.error(err);
+						return Command::none();
+					}
+				};
+
+				match vulnerability_id {
+					Some(id) => Command::perform(
+						super::database::update_triage(
+							self.state.pool.clone(),
+							id,
+							self.state.triage_status,
+							self.state.triage_assignee.clone(),
+							acceptance,
+						),
+						|result| Message::TriageUpdated(result.map_err(|e| e.to_string())),
+					),
+					None => Command::none(),
 				}
+			}
 
-				let pool = self.state.pool.clone();
-				if let Some(id) = self.state.editing_robot_id {
-					Command::perform(
-						super::database::update_robot(pool, id, form),
-						|result| Message::RobotUpdated(result.map_err(|e| e.to_string())),
-					)
-				} else {
-					Command::perform(
-						super::database::add_robot(pool, form),
-						|result| Message::RobotAdded(result.map_err(|e| e.to_string())),
-					)
+			Message::TriageUpdated(result) => {
+				match result {
+					Ok((id, status, assigned_to, risk_acceptance)) => {
+						self.state.apply_triage(id, status, assigned_to, risk_acceptance);
+						self.state.toasts.success(format!("Status set to {}", status));
+					}
+					Err(err) => {
+						error!("Failed to update triage status: {}", err);
+						self.state.toasts.error(err);
+					}
 				}
+				Command::none()
 			}
 
-			Message::RobotFormCancelled => {
-				self.state.clear_robot_form();
-				self.state.showing_robot_form = false;
+			Message::FieldEditStarted => {
+				self.state.field_edit = self.state.selected_vulnerability
+					.and_then(|idx| self.state.displayed_vulnerabilities.get(idx))
+					.map(FieldEditForm::from_vulnerability);
+				Command::none()
+			}
+
+			Message::FieldEditDescriptionChanged(description) => {
+				if let Some(form) = &mut self.state.field_edit {
+					form.description = description;
+				}
 				Command::none()
 			}
 
+			Message::FieldEditSeverityChanged(severity) => {
+				if let Some(form) = &mut self.state.field_edit {
+					form.severity = severity;
+				}
+				Command::none()
+			}
 
-			Message::RobotFilterChanged(filter) => {
-				self.state.robot_filter = filter;
-				self.state.filter_robots();
+			Message::FieldEditImpactChanged(impact) => {
+				if let Some(form) = &mut self.state.field_edit {
+					form.impact = impact;
+				}
 				Command::none()
 			}
 
-			Message::RobotFilterTypeChanged(filter_type) => {
-				self.state.robot_filter_type = filter_type;
-				self.state.filter_robots();
+			Message::FieldEditMitigationChanged(mitigation) => {
+				if let Some(form) = &mut self.state.field_edit {
+					form.mitigation = mitigation;
+				}
 				Command::none()
 			}
 
-			Message::RobotSelected(idx) => {
-				self.state.selected_robot = Some(idx);
+			Message::FieldEditCancelled => {
+				self.state.field_edit = None;
 				Command::none()
 			}
 
-			Message::RobotAdded(result) => {
-				match result {
-					Ok(_) => {
-						self.state.clear_robot_form();
-						Command::perform(
-							load_robots(self.state.pool.clone()),
-							|result| Message::RobotsLoaded(result.map_err(|e| e.to_string())),
-						)
-					}
-					Err(err) => {
-						self.state.error_message = Some(err);
-						Command::none()
-					}
+			Message::FieldEditSaved => {
+				let Some(vuln) = self.state.selected_vulnerability
+					.and_then(|idx| self.state.displayed_vulnerabilities.get(idx))
+				else {
+					return Command::none();
+				};
+				let (Some(form), Some(id)) = (&self.state.field_edit, vuln.vulnerability_id) else {
+					return Command::none();
+				};
+				let edits = form.changes(vuln);
+				if edits.is_empty() {
+					self.state.field_edit = None;
+					return Command::none();
+				}
+				Command::perform(
+					super::database::edit_fields(self.state.pool.clone(), id, edits),
+					|result| Message::FieldsUpdated(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::FieldUnlockClicked(field) => {
+				let vulnerability_id = self.state.selected_vulnerability
+					.and_then(|idx| self.state.displayed_vulnerabilities.get(idx))
+					.and_then(|v| v.vulnerability_id);
+				match vulnerability_id {
+					Some(id) => Command::perform(
+						super::database::unlock_field(self.state.pool.clone(), id, field),
+						|result| Message::FieldsUpdated(result.map_err(|e| e.to_string())),
+					),
+					None => Command::none(),
 				}
 			}
 
-			Message::RobotUpdated(result) => {
+			Message::FieldsUpdated(result) => {
 				match result {
-					Ok(_) => {
-						self.state.clear_robot_form();
-						Command::perform(
-							load_robots(self.state.pool.clone()),
-							|result| Message::RobotsLoaded(result.map_err(|e| e.to_string())),
-						)
+					Ok(vulnerability) => {
+						self.state.field_edit = None;
+						self.state.apply_fields(vulnerability);
+						self.state.toasts.success("Vulnerability updated");
 					}
 					Err(err) => {
-						self.state.error_message = Some(err);
-						Command::none()
+						error!("Failed to update vulnerability fields: {}", err);
+						self.state.toasts.error(err);
 					}
 				}
+				Command::none()
 			}
 
-			Message::RobotDeleted(result) => {
+			Message::NvdFetchClicked => {
+				let Some(vuln) = self.state.selected_vulnerability
+					.and_then(|idx| self.state.displayed_vulnerabilities.get(idx))
+				else {
+					return Command::none();
+				};
+				self.state.nvd_fetching = true;
+				Command::perform(
+					super::database::fetch_from_nvd(self.state.pool.clone(), vuln.clone()),
+					|result| Message::NvdFetched(result.map_err(|e| format!("{:#}", e))),
+				)
+			}
+
+			Message::NvdFetched(result) => {
+				self.state.nvd_fetching = false;
 				match result {
-					Ok(_) => {
-						Command::perform(
-							load_robots(self.state.pool.clone()),
-							|result| Message::RobotsLoaded(result.map_err(|e| e.to_string())),
-						)
+					Ok(Some(vulnerability)) => {
+						self.state.apply_fields(vulnerability);
+						self.state.toasts.success("Missing fields filled in from the NVD");
+					}
+					Ok(None) => {
+						self.state.toasts.success("The NVD had nothing to add");
 					}
 					Err(err) => {
-						self.state.error_message = Some(err);
-						Command::none()
+						error!("{}", err);
+						self.state.toasts.error(err);
 					}
 				}
+				Command::none()
 			}
 
-			// Error handling
-			Message::ShowError(error) => {
-				self.state.error_message = Some(error);
-				Command::none()
+			Message::PrintDetail => {
+				match self.state.print_layout() {
+					Some((file_name, html)) => Command::perform(
+						open_html_report(file_name, html),
+						|result| Message::PrintOpened(
+							result
+								.map(|path| path.display().to_string())
+								.map_err(|e| e.to_string()),
+						),
+					),
+					None => Command::none(),
+				}
 			}
 
-			Message::ClearError => {
-				self.state.error_message = None;
+			Message::RiskReportRequested => {
+				let pool = self.state.pool.clone();
+				Command::perform(
+					async move {
+						let html = super::database::risk_acceptance_report(pool).await?;
+						open_html_report("risk-acceptance-report.html".to_string(), html).await
+					},
+					|result: anyhow::Result<std::path::PathBuf>| Message::PrintOpened(
+						result
+							.map(|path| path.display().to_string())
+							.map_err(|e| e.to_string()),
+					),
+				)
+			}
+
+			Message::ComplianceReportRequested => {
+				let pool = self.state.pool.clone();
+				Command::perform(
+					async move {
+						let html = super::database::compliance_report(pool).await?;
+						open_html_report("compliance-coverage-report.html".to_string(), html).await
+					},
+					|result: anyhow::Result<std::path::PathBuf>| Message::PrintOpened(
+						result
+							.map(|path| path.display().to_string())
+							.map_err(|e| e.to_string()),
+					),
+				)
+			}
+
+			Message::ShareListRequested => {
+				Command::perform(
+					super::database::share_vulnerabilities(self.state.pool.clone(), self.state.vulnerability_query()),
+					|result| Message::ShareListSaved(
+						result
+							.map(|path| path.display().to_string())
+							.map_err(|e| e.to_string()),
+					),
+				)
+			}
+
+			Message::ShareListSaved(result) => {
+				match result {
+					Ok(path) => {
+						info!("Saved shareable vulnerability list to {}", path);
+						self.state.toasts.success(format!("Saved {}", path));
+					}
+					Err(err) => {
+						error!("Failed to save shareable vulnerability list: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::PrintOpened(result) => {
+				match result {
+					Ok(path) => {
+						info!("Opened print layout {}", path);
+						self.state.toasts.success("Opened in the browser");
+					}
+					Err(err) => {
+						error!("Failed to open print layout: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::CompactionChecked(result) => {
+				match result {
+					Ok(Some((CompactionMode::Auto, stats))) => {
+						info!("Compacting database on startup: {}", stats);
+						return self.update(Message::CompactDatabase);
+					}
+					Ok(Some((_, stats))) => self.state.compaction_offer = Some(stats),
+					Ok(None) => {}
+					Err(err) => error!("Failed to check database size: {}", err),
+				}
+				Command::none()
+			}
+
+			Message::NvdHealthLoaded(result) => {
+				match result {
+					Ok(health) => self.state.nvd_health = health,
+					Err(err) => error!("Failed to load NVD status: {}", err),
+				}
+				Command::none()
+			}
+
+			Message::ListLayoutLoaded(result) => {
+				match result {
+					Ok(layout) => self.state.list_layout = layout,
+					Err(err) => error!("Failed to load list layout setting: {}", err),
+				}
+				Command::none()
+			}
+
+			Message::ListLayoutChanged(layout) => {
+				self.state.list_layout = layout;
+				Command::perform(
+					save_list_layout(self.state.pool.clone(), layout),
+					|result| Message::ListLayoutSaved(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::ListLayoutSaved(result) | Message::TableColumnsSaved(result) => {
+				if let Err(err) = result {
+					error!("Failed to save list layout setting: {}", err);
+					self.state.toasts.error(err);
+				}
+				Command::none()
+			}
+
+			Message::TableColumnsLoaded(result) => {
+				match result {
+					Ok(columns) => self.state.table_columns = columns,
+					Err(err) => error!("Failed to load table column widths: {}", err),
+				}
+				Command::none()
+			}
+
+			Message::TableColumnResized(column, delta) => {
+				self.state.table_columns.resize(column, delta);
+				Command::none()
+			}
+
+			Message::TableColumnResizeFinished => Command::perform(
+				save_table_columns(self.state.pool.clone(), self.state.table_columns),
+				|result| Message::TableColumnsSaved(result.map_err(|e| e.to_string())),
+			),
+
+			Message::SortHeaderClicked(field) => {
+				if self.state.sort_field == field {
+					self.state.sort_ascending = !self.state.sort_ascending;
+				} else {
+					self.state.sort_field = field;
+					self.state.sort_ascending = true;
+				}
+				self.update(Message::RefreshData)
+			}
+
+			Message::RowTintLoaded(result) => {
+				match result {
+					Ok(tint) => self.state.row_tint = tint,
+					Err(err) => error!("Failed to load row tint setting: {}", err),
+				}
+				Command::none()
+			}
+
+			Message::RowTintChanged(tint) => {
+				self.state.row_tint = tint;
+				Command::perform(
+					save_row_tint(self.state.pool.clone(), tint),
+					|result| Message::RowTintSaved(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::RowTintSaved(result) => {
+				if let Err(err) = result {
+					error!("Failed to save row tint setting: {}", err);
+					self.state.toasts.error(err);
+				}
+				Command::none()
+			}
+
+			Message::ThemeLoaded(result) => {
+				match result {
+					Ok(choice) => self.state.theme_choice = choice,
+					Err(err) => error!("Failed to load theme setting: {}", err),
+				}
+				Command::none()
+			}
+
+			Message::ThemeChanged(choice) => {
+				self.state.theme_choice = choice;
+				Command::perform(
+					save_theme(self.state.pool.clone(), choice),
+					|result| Message::ThemeSaved(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::ThemeSaved(result) => {
+				if let Err(err) = result {
+					error!("Failed to save theme setting: {}", err);
+					self.state.toasts.error(err);
+				}
+				Command::none()
+			}
+
+			Message::DetailLayoutLoaded(result) => {
+				match result {
+					Ok(layout) => self.state.detail_layout = layout,
+					Err(err) => error!("Failed to load detail layout setting: {}", err),
+				}
+				Command::none()
+			}
+
+			Message::DetailLayoutChanged(layout) => {
+				self.state.detail_layout = layout;
+				Command::perform(
+					save_detail_layout(self.state.pool.clone(), layout),
+					|result| Message::DetailLayoutSaved(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::DetailLayoutSaved(result) => {
+				if let Err(err) = result {
+					error!("Failed to save detail layout setting: {}", err);
+					self.state.toasts.error(err);
+				}
+				Command::none()
+			}
+
+			Message::ColorBlindSafeLoaded(result) => {
+				match result {
+					Ok(enabled) => self.set_color_blind_safe(enabled),
+					Err(err) => error!("Failed to load color-blind safe setting: {}", err),
+				}
+				Command::none()
+			}
+
+			Message::ColorBlindSafeToggled(enabled) => {
+				self.set_color_blind_safe(enabled);
+				Command::perform(
+					save_color_blind_safe(self.state.pool.clone(), enabled),
+					|result| Message::ColorBlindSafeSaved(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::ColorBlindSafeSaved(result) => {
+				if let Err(err) = result {
+					error!("Failed to save color-blind safe setting: {}", err);
+					self.state.toasts.error(err);
+				}
+				Command::none()
+			}
+
+			Message::CompactDatabase => {
+				self.state.compaction_offer = None;
+				Command::perform(
+					compact_database(self.state.pool.clone(), self.progress.clone()),
+					|result| Message::DatabaseCompacted(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::CompactionDismissed => {
+				self.state.compaction_offer = None;
+				Command::none()
+			}
+
+			Message::DatabaseCompacted(result) => {
+				match result {
+					Ok(stats) => {
+						info!("Database compacted: {}", stats);
+						self.state.toasts.success(format!("Database compacted: {}", stats));
+					}
+					Err(err) => {
+						error!("Failed to compact database: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::MaintenanceOpened => {
+				Command::perform(
+					super::database::load_maintenance_status(self.state.pool.clone()),
+					|result| Message::MaintenanceLoaded(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::MaintenanceLoaded(result) => {
+				match result {
+					Ok((policy, stats)) => {
+						let running = self.state.maintenance.as_ref().is_some_and(|m| m.running);
+						self.state.about_open = false;
+						self.state.trash = None;
+						self.state.audit = None;
+						self.state.import_runs = None;
+						self.state.data_sources_view = None;
+						self.state.whats_new = None;
+						self.state.csv_import = None;
+						self.state.maintenance = Some(MaintenanceStatus { policy, stats, running });
+					}
+					Err(err) => {
+						error!("Failed to load maintenance status: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::MaintenanceClosed => {
+				self.state.maintenance = None;
+				Command::none()
+			}
+
+			Message::MaintenanceRun => {
+				let Some(maintenance) = &mut self.state.maintenance else {
+					return Command::none();
+				};
+				maintenance.running = true;
+				Command::perform(
+					maintenance::run_maintenance(self.state.pool.clone(), self.progress.clone()),
+					|result| Message::MaintenanceFinished(result.map_err(|e| format!("{:#}", e))),
+				)
+			}
+
+			Message::MaintenanceFinished(result) => {
+				if let Some(maintenance) = &mut self.state.maintenance {
+					maintenance.running = false;
+				}
+				match result {
+					Ok(report) => {
+						if report.is_intact() {
+							self.state.toasts.success(report.to_string());
+						} else {
+							self.state.toasts.error(report.to_string());
+						}
+						if let Some(maintenance) = &mut self.state.maintenance {
+							maintenance.stats = report.after;
+							maintenance.policy.last_report = Some(report);
+						}
+					}
+					Err(err) => {
+						error!("Database maintenance failed: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::MaintenanceScheduleToggled(weekly) => {
+				if let Some(maintenance) = &mut self.state.maintenance {
+					maintenance.policy.weekly = weekly;
+				}
+				Command::perform(
+					super::database::save_maintenance_schedule(self.state.pool.clone(), weekly),
+					|result| Message::MaintenanceScheduleSaved(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::MaintenanceScheduleSaved(result) => {
+				if let Err(err) = result {
+					error!("Failed to save maintenance schedule: {}", err);
+					self.state.toasts.error(err);
+				}
+				Command::none()
+			}
+
+			Message::AboutOpened => {
+				self.state.graph = None;
+				self.state.maintenance = None;
+				self.state.trash = None;
+				self.state.audit = None;
+				self.state.import_runs = None;
+				self.state.data_sources_view = None;
+				self.state.whats_new = None;
+				self.state.csv_import = None;
+				self.state.about_open = true;
+				Command::none()
+			}
+
+			Message::AboutClosed => {
+				self.state.about_open = false;
+				Command::none()
+			}
+
+			Message::UpdateCheckLoaded(result) => {
+				match result {
+					Ok(settings) => {
+						self.state.update_status.settings = settings;
+						self.check_for_update()
+					}
+					Err(err) => {
+						error!("Failed to load update check setting: {}", err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::UpdateCheckRequested => self.check_for_update(),
+
+			Message::UpdateChecked(result) => {
+				let status = &mut self.state.update_status;
+				status.checking = false;
+				match result {
+					Ok(update) => {
+						status.update = update;
+						status.error = None;
+					}
+					// Only shown in the About dialog; being offline is normal on many sites
+					Err(err) => {
+						warn!("Update check failed: {}", err);
+						status.error = Some(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::UpdateCheckToggled(enabled) => {
+				let status = &mut self.state.update_status;
+				status.settings.enabled = enabled;
+				if !enabled {
+					status.update = None;
+					status.error = None;
+				}
+				Command::batch(vec![
+					Command::perform(
+						super::database::save_update_check(self.state.pool.clone(), status.settings.clone()),
+						|result| Message::UpdateCheckSaved(result.map_err(|e| e.to_string())),
+					),
+					self.check_for_update(),
+				])
+			}
+
+			Message::UpdateCheckSaved(result) => {
+				if let Err(err) = result {
+					error!("Failed to save update check setting: {}", err);
+					self.state.toasts.error(err);
+				}
+				Command::none()
+			}
+
+			Message::DataSourcesOpened => {
+				self.state.about_open = false;
+				self.state.graph = None;
+				self.state.maintenance = None;
+				self.state.trash = None;
+				self.state.audit = None;
+				self.state.import_runs = None;
+				self.state.whats_new = None;
+				self.state.csv_import = None;
+				self.state.data_sources_view = Some(DataSourcesView::default());
+				self.load_data_sources()
+			}
+
+			Message::DataSourcesClosed => {
+				self.state.data_sources_view = None;
+				Command::none()
+			}
+
+			Message::DataSourcesLoaded(result) => {
+				match result {
+					Ok(sources) => self.state.data_sources = sources,
+					Err(err) => {
+						error!("Failed to load data sources: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::DataSourceToggled(source_id, enabled) => self.save_data_source(source_id, |source| source.enabled = enabled),
+
+			Message::DataSourceIntervalSelected(source_id, SyncInterval(minutes)) => {
+				self.save_data_source(source_id, |source| source.interval_minutes = minutes)
+			}
+
+			Message::DataSourceUrlChanged(source_id, url) => {
+				if let Some(view) = &mut self.state.data_sources_view {
+					view.urls.insert(source_id, url);
+				}
+				Command::none()
+			}
+
+			Message::DataSourceUrlSubmitted(source_id) => {
+				match self.state.data_sources_view.as_mut().and_then(|view| view.urls.remove(&source_id)) {
+					Some(url) => self.save_data_source(source_id, |source| source.url = Some(url)),
+					None => Command::none(),
+				}
+			}
+
+			Message::DataSourceFeedNameChanged(name) => {
+				if let Some(view) = &mut self.state.data_sources_view {
+					view.feed_name = name;
+				}
+				Command::none()
+			}
+
+			Message::DataSourceFeedUrlChanged(url) => {
+				if let Some(view) = &mut self.state.data_sources_view {
+					view.feed_url = url;
+				}
+				Command::none()
+			}
+
+			Message::DataSourceFeedAdded => {
+				let Some(view) = &mut self.state.data_sources_view else {
+					return Command::none();
+				};
+				let (name, url) = (std::mem::take(&mut view.feed_name), std::mem::take(&mut view.feed_url));
+				Command::perform(
+					super::database::add_data_feed(self.state.pool.clone(), name, url),
+					|result| Message::DataSourcesLoaded(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::DataSourceRemoveClicked(source_id) => Command::perform(
+				super::database::remove_data_source(self.state.pool.clone(), source_id),
+				|result| Message::DataSourcesLoaded(result.map_err(|e| e.to_string())),
+			),
+
+			Message::DataSourceRunClicked(source_id) => self.sync_data_sources(Some(source_id)),
+
+			Message::SyncNowClicked => self.sync_data_sources(None),
+
+			Message::SyncFinished(result) => {
+				self.state.syncing = false;
+				match result {
+					Ok(summary) => {
+						self.state.toasts.success(format!("Sync finished: {}", summary));
+					}
+					Err(err) => {
+						error!("Sync failed: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				let mut reload = vec![self.load_data_sources(), self.load_nvd_health()];
+				if self.state.whats_new.is_some() {
+					reload.push(self.load_sync_deltas());
+				}
+				Command::batch(reload)
+			}
+
+			Message::WhatsNewOpened => self.load_sync_deltas(),
+
+			Message::WhatsNewLoaded(result) => {
+				match result {
+					Ok(deltas) => {
+						self.state.about_open = false;
+						self.state.graph = None;
+						self.state.maintenance = None;
+						self.state.trash = None;
+						self.state.audit = None;
+						self.state.import_runs = None;
+						self.state.data_sources_view = None;
+						self.state.csv_import = None;
+						self.state.whats_new = Some(deltas);
+					}
+					Err(err) => {
+						error!("Failed to load what the syncs changed: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::WhatsNewClosed => {
+				self.state.whats_new = None;
+				Command::none()
+			}
+
+			Message::WhatsNewExportRequested => Command::perform(
+				super::database::export_changelog(self.state.pool.clone()),
+				|result| Message::WhatsNewExported(
+					result
+						.map(|path| path.display().to_string())
+						.map_err(|e| e.to_string()),
+				),
+			),
+
+			Message::WhatsNewExported(result) => {
+				match result {
+					Ok(path) => {
+						info!("Exported the sync changelog to {}", path);
+						self.state.toasts.success(format!("Saved {}", path));
+					}
+					Err(err) => {
+						error!("Failed to export the sync changelog: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::CsvImportOpened => {
+				self.state.about_open = false;
+				self.state.graph = None;
+				self.state.maintenance = None;
+				self.state.trash = None;
+				self.state.audit = None;
+				self.state.import_runs = None;
+				self.state.data_sources_view = None;
+				self.state.whats_new = None;
+				self.state.csv_import = Some(CsvImportView::default());
+				Command::perform(
+					super::database::load_csv_presets(self.state.pool.clone()),
+					|result| Message::CsvPresetsLoaded(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::CsvImportClosed => {
+				self.state.csv_import = None;
+				Command::none()
+			}
+
+			Message::CsvImportPathChanged(path) => {
+				if let Some(view) = &mut self.state.csv_import {
+					view.path = path;
+					// The headers were read from the previous file
+					view.headers.clear();
+				}
+				Command::none()
+			}
+
+			Message::CsvImportHeadersRequested => match &self.state.csv_import {
+				Some(view) if !view.path.trim().is_empty() => Command::perform(
+					super::database::read_import_headers(view.path.clone()),
+					|result| Message::CsvImportHeadersRead(result.map_err(|e| format!("{:#}", e))),
+				),
+				_ => Command::none(),
+			},
+
+			Message::CsvImportHeadersRead(result) => {
+				let Some(view) = &mut self.state.csv_import else {
+					return Command::none();
+				};
+				match result {
+					Ok(headers) => {
+						// Keep a mapping that fits the file, else use a preset that does, else guess
+						let fits = |mapping: &CsvMapping| {
+							mapping.headers().iter().all(|name| headers.iter().any(|header| header.eq_ignore_ascii_case(name.trim())))
+						};
+						if !fits(&view.mapping) {
+							view.mapping = view.presets
+								.iter()
+								.map(|(_, mapping)| mapping)
+								.find(|mapping| fits(mapping))
+								.cloned()
+								.unwrap_or_else(|| CsvMapping::guess(&headers));
+						}
+						view.headers = headers;
+					}
+					Err(err) => {
+						error!("Failed to read the CSV headers: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::CsvImportColumnSelected(field, column) => {
+				if let Some(view) = &mut self.state.csv_import {
+					view.mapping.set_column(field, match column {
+						CsvColumn::Unmapped => None,
+						CsvColumn::Header(header) => Some(header),
+					});
+				}
+				Command::none()
+			}
+
+			Message::CsvImportSourceChanged(source) => {
+				if let Some(view) = &mut self.state.csv_import {
+					view.mapping.source = source;
+				}
+				Command::none()
+			}
+
+			Message::CsvImportMergePolicySelected(policy) => {
+				if let Some(view) = &mut self.state.csv_import {
+					view.mapping.merge_policy = policy;
+				}
+				Command::none()
+			}
+
+			Message::CsvPresetsLoaded(result) => {
+				match result {
+					Ok(presets) => {
+						if let Some(view) = &mut self.state.csv_import {
+							view.presets = presets;
+						}
+					}
+					Err(err) => {
+						error!("Failed to load the CSV presets: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::CsvPresetSelected(name) => {
+				if let Some(view) = &mut self.state.csv_import {
+					if let Some((_, mapping)) = view.presets.iter().find(|(preset, _)| *preset == name) {
+						view.mapping = mapping.clone();
+						view.preset_name = name;
+					}
+				}
+				Command::none()
+			}
+
+			Message::CsvPresetNameChanged(name) => {
+				if let Some(view) = &mut self.state.csv_import {
+					view.preset_name = name;
+				}
+				Command::none()
+			}
+
+			Message::CsvPresetSaved => match &self.state.csv_import {
+				Some(view) if !view.preset_name.trim().is_empty() && view.missing_fields().is_empty() => {
+					Command::perform(
+						super::database::save_csv_preset(self.state.pool.clone(), view.preset_name.clone(), view.mapping.clone()),
+						|result| Message::CsvPresetsLoaded(result.map_err(|e| e.to_string())),
+					)
+				}
+				_ => Command::none(),
+			},
+
+			Message::CsvImportStarted => {
+				let Some(view) = &mut self.state.csv_import else {
+					return Command::none();
+				};
+				if view.headers.is_empty() || !view.missing_fields().is_empty() || view.importing {
+					return Command::none();
+				}
+				view.importing = true;
+				Command::perform(
+					super::database::import_vulnerability_file(
+						self.state.pool.clone(),
+						view.path.clone(),
+						view.mapping.clone(),
+						self.progress.clone(),
+					),
+					|result| Message::CsvImported(result.map_err(|e| format!("{:#}", e))),
+				)
+			}
+
+			Message::CsvImported(result) => {
+				match result {
+					Ok(report) => {
+						info!("CSV import completed: {}", report);
+						self.state.csv_import = None;
+						if report.skipped == 0 {
+							self.state.toasts.success(report.to_string());
+						} else {
+							self.state.toasts.warning(format!("{}; Import History lists the skipped rows", report));
+						}
+					}
+					Err(err) => {
+						error!("CSV import failed: {}", err);
+						if let Some(view) = &mut self.state.csv_import {
+							view.importing = false;
+						}
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::TrashOpened => self.load_trash(),
+
+			Message::TrashLoaded(result) => {
+				match result {
+					Ok(items) => {
+						self.state.about_open = false;
+						self.state.graph = None;
+						self.state.maintenance = None;
+						self.state.audit = None;
+						self.state.import_runs = None;
+						self.state.data_sources_view = None;
+						self.state.whats_new = None;
+						self.state.csv_import = None;
+						self.state.trash = Some(items);
+					}
+					Err(err) => {
+						error!("Failed to load recently deleted entries: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::TrashClosed => {
+				self.state.trash = None;
+				Command::none()
+			}
+
+			Message::AuditOpened => {
+				self.state.audit = Some(AuditLogView {
+					entity: FilterAuditEntity::All,
+					search: String::new(),
+					entries: Vec::new(),
+				});
+				self.load_audit_log()
+			}
+
+			Message::AuditLoaded(result) => {
+				match result {
+					Ok(entries) => {
+						if let Some(audit) = &mut self.state.audit {
+							self.state.about_open = false;
+							self.state.graph = None;
+							self.state.maintenance = None;
+							self.state.trash = None;
+							self.state.import_runs = None;
+							self.state.data_sources_view = None;
+							self.state.whats_new = None;
+							self.state.csv_import = None;
+							audit.entries = entries;
+						}
+					}
+					Err(err) => {
+						error!("Failed to load the audit log: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::AuditClosed => {
+				self.state.audit = None;
+				Command::none()
+			}
+
+			Message::ImportHistoryOpened => self.load_import_runs(),
+
+			Message::ImportHistoryLoaded(result) => {
+				match result {
+					Ok(runs) => {
+						self.state.about_open = false;
+						self.state.graph = None;
+						self.state.maintenance = None;
+						self.state.trash = None;
+						self.state.audit = None;
+						self.state.data_sources_view = None;
+						self.state.whats_new = None;
+						self.state.csv_import = None;
+						self.state.import_runs = Some(runs);
+					}
+					Err(err) => {
+						error!("Failed to load the import history: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::ImportHistoryClosed => {
+				self.state.import_runs = None;
+				self.state.import_skipped = None;
+				Command::none()
+			}
+
+			Message::ImportSkippedRowsClicked(run_id) => {
+				// A second click hides the rows again
+				if self.state.import_skipped.as_ref().is_some_and(|(shown, _)| *shown == run_id) {
+					self.state.import_skipped = None;
+					return Command::none();
+				}
+				Command::perform(
+					super::database::load_skipped_rows(self.state.pool.clone(), run_id),
+					move |result| Message::ImportSkippedRowsLoaded(run_id, result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::ImportSkippedRowsLoaded(run_id, result) => {
+				match result {
+					Ok(rows) => self.state.import_skipped = Some((run_id, rows)),
+					Err(err) => {
+						error!("Failed to load the skipped rows: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::ImportRevertClicked(run_id) => {
+				Command::perform(
+					super::database::revert_import(self.state.pool.clone(), run_id),
+					|result| Message::ImportReverted(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::ImportReverted(result) => {
+				match result {
+					Ok(summary) => {
+						self.state.toasts.success(format!("Import reverted: {}", summary));
+						Command::batch([self.load_import_runs(), self.reload_after_deletion()])
+					}
+					Err(err) => {
+						error!("Failed to revert the import: {}", err);
+						self.state.toasts.error(err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::AuditBatchRevertClicked(batch_id) => {
+				Command::perform(
+					super::database::revert_audit_batch(self.state.pool.clone(), batch_id),
+					|result| Message::AuditBatchReverted(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::AuditBatchReverted(result) => {
+				match result {
+					Ok(reverted) => {
+						self.state.toasts.success(format!("Reverted the changes to {} records", reverted));
+						Command::batch([self.load_audit_log(), self.load_software_versions()])
+					}
+					Err(err) => {
+						error!("Failed to revert the batch: {}", err);
+						self.state.toasts.error(err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::AuditEntityChanged(entity) => {
+				if let Some(audit) = &mut self.state.audit {
+					audit.entity = entity;
+				}
+				self.load_audit_log()
+			}
+
+			Message::AuditSearchChanged(search) => {
+				if let Some(audit) = &mut self.state.audit {
+					audit.search = search;
+				}
+				self.load_audit_log()
+			}
+
+			Message::AuditExportRequested => {
+				let Some(audit) = &self.state.audit else {
+					return Command::none();
+				};
+				Command::perform(
+					super::database::export_audit_log(self.state.pool.clone(), audit.entity, audit.search.clone()),
+					|result| Message::AuditExported(
+						result
+							.map(|path| path.display().to_string())
+							.map_err(|e| e.to_string()),
+					),
+				)
+			}
+
+			Message::AuditExported(result) => {
+				match result {
+					Ok(path) => {
+						info!("Exported the audit log to {}", path);
+						self.state.toasts.success(format!("Saved {}", path));
+					}
+					Err(err) => {
+						error!("Failed to export the audit log: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::DeleteVulnerabilityClicked(vulnerability_id) => {
+				let cve_id = self.state.displayed_vulnerabilities
+					.iter()
+					.find(|v| v.vulnerability_id == Some(vulnerability_id))
+					.map(|v| v.cve_id.clone())
+					.unwrap_or_default();
+				Command::perform(
+					super::database::delete_vulnerability(self.state.pool.clone(), vulnerability_id),
+					move |result| Message::VulnerabilityDeleted(
+						result.map(|()| (vulnerability_id, cve_id)).map_err(|e| e.to_string())
+					),
+				)
+			}
+
+			Message::VulnerabilityDeleted(result) => {
+				match result {
+					Ok((vulnerability_id, cve_id)) => {
+						self.state.clear_selection();
+						self.state.toasts.with_action(
+							ToastLevel::Success,
+							format!("{} moved to Recently Deleted", cve_id),
+							"Undo",
+							Message::RestoreClicked(DeletedKind::Vulnerability, vulnerability_id),
+						);
+						self.reload_after_deletion()
+					}
+					Err(err) => {
+						error!("Failed to delete vulnerability: {}", err);
+						self.state.toasts.error(err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::RestoreClicked(kind, id) => {
+				Command::perform(
+					super::database::restore_deleted(self.state.pool.clone(), kind, id),
+					move |result| Message::Restored(result.map(|()| kind).map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::Restored(result) => {
+				match result {
+					Ok(kind) => {
+						self.state.toasts.success(format!("{} restored", kind));
+						self.reload_after_deletion()
+					}
+					Err(err) => {
+						error!("Failed to restore: {}", err);
+						self.state.toasts.error(err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::PurgeClicked(kind, id) => {
+				Command::perform(
+					super::database::purge_deleted(self.state.pool.clone(), kind, id),
+					|result| Message::Purged(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::Purged(result) => {
+				match result {
+					Ok(()) => {
+						self.state.toasts.success("Deleted for good");
+						self.load_trash()
+					}
+					Err(err) => {
+						error!("Failed to purge: {}", err);
+						self.state.toasts.error(err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::ClearSelection => {
+				self.state.clear_selection();
+				Command::none()
+			}
+
+			Message::ScrollChanged(offset) => {
+				self.state.scroll_offset = offset;
+				if offset > SCROLL_THRESHOLD && self.state.current_page + 1 < self.state.total_pages {
+					self.update(Message::PageChanged(self.state.current_page + 1))
+				} else {
+					Command::none()
+				}
+			}
+
+			Message::WorkspaceSelected(name) => {
+				if name == self.state.workspace {
+					return Command::none();
+				}
+				self.state.loading = true;
+				Command::perform(
+					open_workspace(name),
+					|result| Message::WorkspaceOpened(result.map_err(|e| format!("{:#}", e))),
+				)
+			}
+
+			Message::WorkspaceOpened(result) => {
+				match result {
+					Ok((name, pool)) => {
+						// Nothing loaded from the previous workspace carries over
+						let toasts = std::mem::take(&mut self.state.toasts);
+						self.state = AppState::new(pool, name);
+						self.state.toasts = toasts;
+						self.state.toasts.success(format!("Opened workspace {}", self.state.workspace));
+						return self.load_workspace();
+					}
+					Err(err) => {
+						self.state.loading = false;
+						error!("Failed to switch workspace: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			// Robot-related messages with proper error handling
+			Message::RobotsLoaded(result) => {
+				match result {
+					Ok(robots) => {
+						// Rescored robots may move when sorted by risk; keep the same one selected
+						let selected = self.selected_robot_id();
+						self.state.robots = robots;
+						self.state.sort_robots();
+						if let Some(robot_id) = selected {
+							self.state.selected_robot = self.state.get_displayed_robots()
+								.iter()
+								.position(|robot| robot.robot_id == Some(robot_id));
+						}
+					}
+					Err(err) => {
+						error!("Failed to load robots: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::AddRobotClicked => {
+				self.state.clear_robot_form();
+				self.state.showing_robot_form = true; // Add this line
+				self.load_vendor_names()
+			}
+
+
+			Message::EditRobotClicked(robot_id) => {
+				// Solution to fix E0502 error: Limit the immutable borrow scope
+				let robot_opt = self.state.robots.iter()
+					.find(|r| r.robot_id == Some(robot_id))
+					.cloned(); // Clone to avoid holding the immutable borrow
+
+				if let Some(robot) = robot_opt {
+					self.state.set_robot_form(&robot);
+					return Command::batch(vec![self.load_robot_software(robot_id), self.load_vendor_names()]);
+				}
+				Command::none()
+			}
+
+			Message::VendorNamesLoaded(result) => {
+				match result {
+					Ok(names) => self.state.vendor_names = names,
+					Err(err) => error!("Failed to load vendor names: {}", err),
+				}
+				Command::none()
+			}
+
+			Message::LoadRobotSoftware(robot_id) => self.load_robot_software(robot_id),
+
+			Message::RobotSoftwareLoaded(robot_id, result) => {
+				// Only fill the form of the robot being edited, or the detail of the
+				// selected robot while no form is open
+				let current = match self.state.editing_robot_id {
+					Some(editing) => editing == robot_id,
+					None => !self.state.showing_robot_form,
+				};
+				match result {
+					Ok(software) if current => self.state.robot_form.software_versions = software,
+					Ok(_) => {}
+					Err(err) => {
+						error!("Failed to load robot software: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::DeleteRobotClicked(robot_id) => {
+				Command::perform(
+					super::database::delete_robot(self.state.pool.clone(), robot_id),
+					move |result| Message::RobotDeleted(result.map(|()| robot_id).map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::RobotFormNameChanged(name) => {
+				self.state.robot_form.name = name;
+				Command::none()
+			}
+
+			Message::RobotFormManufacturerChanged(manufacturer) => {
+				self.state.robot_form.manufacturer = manufacturer;
+				Command::none()
+			}
+
+			Message::RobotFormModelChanged(model) => {
+				self.state.robot_form.model = model;
+				Command::none()
+			}
+
+			Message::RobotFormFirmwareChanged(firmware_version) => {
+				self.state.robot_form.firmware_version = firmware_version;
+				Command::none()
+			}
+
+			Message::RobotFormOsChanged(os) => {
+				self.state.robot_form.os = os;
+				Command::none()
+			}
+
+			Message::RobotFormRosDistroChanged(ros_distro) => {
+				self.state.robot_form.ros_distro = ros_distro;
+				Command::none()
+			}
+
+			Message::RobotFormOperationalNoteChanged(note) => {
+				self.state.robot_form.operational_note = note;
+				Command::none()
+			}
+
+			Message::RobotFormCriticalityChanged(criticality) => {
+				self.state.robot_form.criticality = criticality;
+				Command::none()
+			}
+
+			Message::RobotFormSpecificationsChanged(specifications) => {
+				self.state.robot_form.specifications = specifications;
+				Command::none()
+			}
+
+			Message::RobotFormSoftwareAdded(version) => {
+				if !version.trim().is_empty() {
+					self.state.robot_form.software_versions.push(version);
+				}
+				Command::none()
+			}
+
+			Message::RobotFormSoftwareRemoved(idx) => {
+				if idx < self.state.robot_form.software_versions.len() {
+					self.state.robot_form.software_versions.remove(idx);
+				}
+				Command::none()
+			}
+
+			Message::RobotFormSubmitted => {
+				let form = self.state.robot_form.clone();
+				if let Err(err) = super::types::validate_robot_form(&form) {
+					self.state.toasts.error(err.to_string());
+					return Command::none();
+				}
+
+				let pool = self.state.pool.clone();
+				if let Some(id) = self.state.editing_robot_id {
+					Command::perform(
+						super::database::update_robot(pool, id, form),
+						|result| Message::RobotUpdated(result.map_err(|e| e.to_string())),
+					)
+				} else {
+					Command::perform(
+						super::database::add_robot(pool, form),
+						|result| Message::RobotAdded(result.map_err(|e| e.to_string())),
+					)
+				}
+			}
+
+			Message::RobotFormCancelled => {
+				self.state.clear_robot_form();
+				self.state.showing_robot_form = false;
+				Command::none()
+			}
+
+
+			Message::RobotFilterChanged(filter) => {
+				self.state.robot_filter = filter;
+				self.state.filter_robots();
+				Command::none()
+			}
+
+			Message::RobotSortChanged(sort) => {
+				self.state.robot_sort = sort;
+				self.state.sort_robots();
+				Command::none()
+			}
+
+			Message::RobotFilterTypeChanged(filter_type) => {
+				self.state.robot_filter_type = filter_type;
+				self.state.filter_robots();
+				Command::none()
+			}
+
+			Message::RobotSelected(idx) => {
+				self.state.selected_robot = Some(idx);
+				let robot_id = self.state.get_displayed_robots()
+					.get(idx)
+					.and_then(|r| r.robot_id);
+				self.state.set_notes_entity(robot_id.map(|id| (NoteEntity::Robot, id as i64)));
+				self.state.robot_vulnerabilities.clear();
+				self.state.robot_checklist.clear();
+				match robot_id {
+					Some(id) => Command::batch(vec![
+						self.load_notes(),
+						self.load_robot_software(id),
+						self.update(Message::LoadRobotVulnerabilities(id)),
+						self.load_checklist(id),
+					]),
+					None => self.load_notes(),
+				}
+			}
+
+			Message::LoadRobotVulnerabilities(robot_id) => Command::perform(
+				super::database::load_robot_vulnerabilities(self.state.pool.clone(), robot_id),
+				move |result| Message::RobotVulnerabilitiesLoaded(robot_id, result.map_err(|e| e.to_string())),
+			),
+
+			Message::RobotVulnerabilitiesLoaded(robot_id, result) => {
+				match result {
+					Ok(vulnerabilities) if self.selected_robot_id() == Some(robot_id) => self.state.robot_vulnerabilities = vulnerabilities,
+					Ok(_) => {}
+					Err(err) => {
+						error!("Failed to load robot vulnerabilities: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::ChecklistLoaded(robot_id, result) => {
+				match result {
+					Ok(entries) if self.selected_robot_id() == Some(robot_id) => self.state.robot_checklist = entries,
+					Ok(_) => {}
+					Err(err) => {
+						error!("Failed to load the commissioning checklist: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::ChecklistItemToggled(robot_id, item_id, done) => Command::perform(
+				super::database::save_checklist_item(self.state.pool.clone(), robot_id, item_id, done),
+				move |result| Message::ChecklistItemSaved(robot_id, result.map_err(|e| e.to_string())),
+			),
+
+			Message::ChecklistItemSaved(robot_id, result) => {
+				if let Err(err) = &result {
+					error!("Failed to save the commissioning checklist: {}", err);
+					self.state.toasts.error(err.clone());
+				}
+				// The robot's risk score changed with the checklist
+				Command::batch([
+					self.load_checklist(robot_id),
+					Command::perform(
+						load_robots(self.state.pool.clone()),
+						|result| Message::RobotsLoaded(result.map_err(|e| e.to_string())),
+					),
+				])
+			}
+
+			Message::InventoryMaxAgeLoaded(result) => {
+				match result {
+					Ok(days) => self.state.inventory_max_age_days = days,
+					Err(err) => error!("Failed to load the inventory maximum age: {}", err),
+				}
+				Command::none()
+			}
+
+			Message::InventoryConfirmClicked(robot_id) => Command::perform(
+				super::database::confirm_inventory(self.state.pool.clone(), robot_id),
+				|result| Message::InventoryConfirmed(result.map_err(|e| e.to_string())),
+			),
+
+			Message::InventoryConfirmed(result) => {
+				match result {
+					Ok(()) => {
+						self.state.toasts.success("Inventory confirmed as current");
+						Command::perform(
+							load_robots(self.state.pool.clone()),
+							|result| Message::RobotsLoaded(result.map_err(|e| e.to_string())),
+						)
+					}
+					Err(err) => {
+						error!("Failed to confirm the inventory: {}", err);
+						self.state.toasts.error(err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::OpenVulnerability(cve_id) => {
+				self.state.whats_new = None;
+				self.state.current_tab = Tab::Vulnerabilities;
+				self.state.clear_selection();
+				let loaded = self.state.displayed_vulnerabilities
+					.iter()
+					.position(|v| v.cve_id == cve_id);
+				if let Some(idx) = loaded {
+					return self.update(Message::VulnerabilitySelected(idx));
+				}
+				Command::perform(
+					load_vulnerability_by_cve(self.state.pool.clone(), cve_id),
+					|result| Message::DeepLinkResolved(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::GraphRequested(center) => Command::perform(
+				load_graph(self.state.pool.clone(), center),
+				|result| Message::GraphLoaded(result.map_err(|e| format!("{:#}", e))),
+			),
+
+			Message::GraphLoaded(result) => {
+				match result {
+					Ok(graph) => self.state.graph = Some(graph),
+					Err(err) => {
+						error!("Failed to load relationship graph: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::ReferencesLoaded(result) => {
+				match result {
+					Ok(references) => self.state.references = references,
+					Err(err) => {
+						error!("Failed to load references: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::RelatedLoaded(result) => {
+				match result {
+					Ok(related) => self.state.related = related,
+					Err(err) => {
+						error!("Failed to load related vulnerabilities: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::ComplianceControlsLoaded(result) => {
+				match result {
+					Ok(controls) => self.state.compliance_controls = controls,
+					Err(err) => error!("Failed to load compliance controls: {}", err),
+				}
+				Command::none()
+			}
+
+			Message::LinkedControlsLoaded(vulnerability_id, result) => {
+				match result {
+					Ok(controls) if self.selected_vulnerability_id() == Some(vulnerability_id) => {
+						self.state.linked_controls = controls;
+					}
+					Ok(_) => {}
+					Err(err) => {
+						error!("Failed to load the vulnerability's compliance controls: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::ControlLinkToggled(vulnerability_id, control_id, linked) => Command::perform(
+				super::database::save_control_link(self.state.pool.clone(), vulnerability_id, control_id, linked),
+				move |result| Message::ControlLinkSaved(vulnerability_id, result.map_err(|e| e.to_string())),
+			),
+
+			Message::ControlLinkSaved(vulnerability_id, result) => {
+				if let Err(err) = result {
+					error!("Failed to save the compliance mapping: {}", err);
+					self.state.toasts.error(err);
+				}
+				self.load_linked_controls(vulnerability_id)
+			}
+
+			Message::TicketingLoaded(result) => {
+				match result {
+					Ok(enabled) => self.state.ticketing_enabled = enabled,
+					Err(err) => {
+						error!("Failed to load ticketing settings: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::TicketLoaded(vulnerability_id, result) => {
+				match result {
+					Ok(ticket) if self.selected_vulnerability_id() == Some(vulnerability_id) => self.state.ticket = ticket,
+					Ok(_) => {}
+					Err(err) => {
+						error!("Failed to load the remediation ticket: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::TicketCreateClicked => {
+				let Some(id) = self.selected_vulnerability_id() else {
+					return Command::none();
+				};
+				self.state.ticket_creating = true;
+				Command::perform(
+					super::database::create_ticket(self.state.pool.clone(), id),
+					move |result| Message::TicketCreated(id, result.map_err(|e| format!("{:#}", e))),
+				)
+			}
+
+			Message::TicketCreated(vulnerability_id, result) => {
+				self.state.ticket_creating = false;
+				match result {
+					Ok(ticket) => {
+						self.state.toasts.success(format!("Raised {}", ticket.key));
+						if self.selected_vulnerability_id() == Some(vulnerability_id) {
+							self.state.ticket = Some(ticket);
+						}
+					}
+					Err(err) => {
+						error!("Failed to raise a remediation ticket: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
 				Command::none()
 			}
 
+			Message::LinkOpened(url) => {
+				self.state.card_menu = None;
+				if let Err(err) = open::that(&url) {
+					error!("Failed to open {}: {}", url, err);
+					self.state.toasts.error(format!("Failed to open {}", url));
+				}
+				Command::none()
+			}
+
+			Message::CopyToClipboard(what, text) => {
+				self.state.card_menu = None;
+				self.state.toasts.success(format!("{} copied", what));
+				iced::clipboard::write(text)
+			}
+
+			Message::CardMenuOpened(idx) => {
+				self.state.card_menu = Some(idx);
+				Command::none()
+			}
+
+			Message::CardMenuClosed => {
+				self.state.card_menu = None;
+				Command::none()
+			}
+
+			Message::GraphClosed => {
+				self.state.graph = None;
+				Command::none()
+			}
+
+			Message::NotesLoaded(result) => {
+				match result {
+					Ok(notes) => self.state.notes = notes,
+					Err(err) => {
+						error!("Failed to load notes: {}", err);
+						self.state.toasts.error(err);
+					}
+				}
+				Command::none()
+			}
+
+			Message::NoteInputChanged(text) => {
+				self.state.note_input = text;
+				Command::none()
+			}
+
+			Message::NoteEditClicked(note_id) => {
+				if let Some(note) = self.state.notes.iter().find(|n| n.note_id == Some(note_id)) {
+					self.state.note_input = note.body.clone();
+					self.state.editing_note_id = Some(note_id);
+				}
+				Command::none()
+			}
+
+			Message::NoteEditCancelled => {
+				self.state.note_input.clear();
+				self.state.editing_note_id = None;
+				Command::none()
+			}
+
+			Message::NoteSubmitted => {
+				match self.state.notes_entity {
+					Some((entity_type, entity_id)) if !self.state.note_input.trim().is_empty() => {
+						Command::perform(
+							super::database::save_note(
+								self.state.pool.clone(),
+								entity_type,
+								entity_id,
+								self.state.editing_note_id,
+								self.state.note_input.clone(),
+							),
+							|result| Message::NoteSaved(result.map_err(|e| e.to_string())),
+						)
+					}
+					_ => Command::none(),
+				}
+			}
+
+			Message::NoteDeleteClicked(note_id) => {
+				let Some(note) = self.state.notes.iter().find(|n| n.note_id == Some(note_id)).cloned() else {
+					return Command::none();
+				};
+				Command::perform(
+					super::database::delete_note(self.state.pool.clone(), note_id),
+					move |result| Message::NoteDeleted(result.map(|()| note).map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::NoteDeleted(result) => {
+				match result {
+					Ok(note) => {
+						self.state.toasts.with_action(
+							ToastLevel::Success,
+							"Note deleted",
+							"Undo",
+							Message::NoteRestored(note),
+						);
+						self.load_notes()
+					}
+					Err(err) => {
+						error!("Failed to delete note: {}", err);
+						self.state.toasts.error(err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::NoteRestored(note) => {
+				Command::perform(
+					super::database::save_note(
+						self.state.pool.clone(),
+						note.entity_type,
+						note.entity_id,
+						None,
+						note.body,
+					),
+					|result| Message::NoteSaved(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::NoteSaved(result) => {
+				match result {
+					Ok(()) => {
+						self.state.note_input.clear();
+						self.state.editing_note_id = None;
+						self.load_notes()
+					}
+					Err(err) => {
+						error!("Failed to save note: {}", err);
+						self.state.toasts.error(err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::RobotImportPathChanged(path) => {
+				self.state.robot_import_path = path;
+				Command::none()
+			}
+
+			Message::ImportRobotData(path) => {
+				if path.trim().is_empty() {
+					return Command::none();
+				}
+				self.state.robot_import_errors.clear();
+				Command::perform(
+					super::database::import_robot_inventory(self.state.pool.clone(), path),
+					|result| Message::RobotsImported(result.map_err(|e| e.to_string())),
+				)
+			}
+
+			Message::RobotsImported(result) => {
+				match result {
+					Ok(summary) => {
+						info!("Imported robots: {}", summary);
+						if summary.errors.is_empty() {
+							self.state.toasts.success(summary.to_string());
+						} else {
+							self.state.toasts.error(summary.to_string());
+						}
+						self.state.robot_import_path.clear();
+						self.state.robot_import_errors = summary.errors;
+						Command::perform(
+							load_robots(self.state.pool.clone()),
+							|result| Message::RobotsLoaded(result.map_err(|e| e.to_string())),
+						)
+					}
+					Err(err) => {
+						error!("Failed to import robots: {}", err);
+						self.state.toasts.error(err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::DismissRobotImportErrors => {
+				self.state.robot_import_errors.clear();
+				Command::none()
+			}
+
+			Message::RobotAdded(result) => {
+				match result {
+					Ok(_) => {
+						self.state.clear_robot_form();
+						self.state.toasts.success("Robot added");
+						Command::perform(
+							load_robots(self.state.pool.clone()),
+							|result| Message::RobotsLoaded(result.map_err(|e| e.to_string())),
+						)
+					}
+					Err(err) => {
+						self.state.toasts.error(err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::RobotUpdated(result) => {
+				match result {
+					Ok(_) => {
+						self.state.clear_robot_form();
+						self.state.toasts.success("Robot updated");
+						Command::perform(
+							load_robots(self.state.pool.clone()),
+							|result| Message::RobotsLoaded(result.map_err(|e| e.to_string())),
+						)
+					}
+					Err(err) => {
+						self.state.toasts.error(err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::RobotDeleted(result) => {
+				match result {
+					Ok(robot_id) => {
+						self.state.toasts.with_action(
+							ToastLevel::Success,
+							"Robot moved to Recently Deleted",
+							"Undo",
+							Message::RestoreClicked(DeletedKind::Robot, robot_id.into()),
+						);
+						self.reload_after_deletion()
+					}
+					Err(err) => {
+						self.state.toasts.error(err);
+						Command::none()
+					}
+				}
+			}
+
+			Message::ShowError(error) => {
+				self.state.toasts.error(error);
+				Command::none()
+			}
+
+			Message::ToastDismissed(id) => {
+				self.state.toasts.dismiss(id);
+				Command::none()
+			}
+
+			Message::ToastActionClicked(id) => {
+				match self.state.toasts.dismiss(id).and_then(|toast| toast.action) {
+					Some((_, action)) => self.update(action),
+					None => Command::none(),
+				}
+			}
+
+			Message::ToastTick(now) => {
+				self.state.toasts.expire(now);
+				Command::none()
+			}
+
+			Message::ProfilerToggled => {
+				self.state.profiler = match self.state.profiler {
+					Some(_) => None,
+					None => Some(Profiler::new(Instant::now())),
+				};
+				Command::none()
+			}
+
+			Message::ProfilerFrame(at) => {
+				if let Some(profiler) = &mut self.state.profiler {
+					profiler.frame(at);
+				}
+				Command::none()
+			}
+
+			Message::CancelOperation => {
+				self.cancel.cancel();
+				self.state.cancel_requested = true;
+				Command::none()
+			}
+
+			Message::LoadingProgress(progress) => {
+				if !progress.finished {
+					self.state.progress = Some(progress);
+					return Command::none();
+				}
+
+				self.state.progress = None;
+				self.state.cancel_requested = false;
+				if progress.cancelled {
+					info!("{} cancelled", progress.operation);
+					self.state.toasts.warning(format!(
+						"{} cancelled after {} records",
+						progress.operation, progress.processed
+					));
+				} else {
+					info!("{} finished", progress.operation);
+				}
+				// Enrichment runs update the NVD status and, when scheduled, the last runs of the sources
+				let health = Command::batch(vec![self.load_nvd_health(), self.load_data_sources()]);
+				// Show the imported data unless the user is reading a record
+				if self.state.selected_vulnerability.is_none() {
+					Command::batch(vec![health, self.update(Message::RefreshData)])
+				} else {
+					health
+				}
+			}
+
 			_ => Command::none(),
 		}
 	}
 
+	fn subscription(&self) -> Subscription<Message> {
+		let progress = subscription::unfold(
+			"background-progress",
+			self.progress_rx.clone(),
+			|mut progress_rx| async move {
+				if progress_rx.changed().await.is_err() {
+					// No more workers can report; stay idle
+					std::future::pending::<()>().await;
+				}
+				let progress = progress_rx.borrow_and_update().clone();
+				(Message::LoadingProgress(progress), progress_rx)
+			},
+		);
+
+		let mut subscriptions = vec![progress, profiler::shortcut_subscription()];
+		// Only tick while a toast is waiting to expire
+		if !self.state.toasts.is_empty() {
+			subscriptions.push(iced::time::every(TOAST_TICK).map(Message::ToastTick));
+		}
+		// Redraw continuously while the performance overlay measures frame times
+		if self.state.profiler.is_some() {
+			subscriptions.push(iced::window::frames().map(Message::ProfilerFrame));
+		}
+		Subscription::batch(subscriptions)
+	}
+
 	fn view(&self) -> Element<Message> {
 		let content = iced::widget::column![
+			self.state.profiler_overlay(),
 			self.state.tab_selector(),
-			match self.state.current_tab {
-				Tab::Vulnerabilities => self.vulnerability_view(),
-				Tab::RobotInventory => self.robot_view(),
-			}
+			self.state.toast_stack(),
+			self.state.compaction_banner(),
+			self.state.nvd_outage_banner(),
+			self.state.progress_indicator(),
+			iced::widget::container(match (self.open_dialog(), &self.state.current_tab) {
+				(Some(dialog), _) => dialog,
+				(None, Tab::Vulnerabilities) => self.vulnerability_view(),
+				(None, Tab::RobotInventory) => self.robot_view(),
+				(None, Tab::Software) => self.state.software_view(),
+				(None, Tab::Trends) => self.state.trends_view(),
+			})
+				.height(iced::Length::Fill),
+			self.state.sync_status_bar(),
 		]
 			.spacing(20)
 			.padding(20)
@@ -419,27 +2494,315 @@ impl Application for VulnerabilityApp {
 }
 
 impl VulnerabilityApp {
-	fn vulnerability_view(&self) -> Element<Message> {
-		if let Some(idx) = self.state.selected_vulnerability {
-			if let Some(vuln) = self.state.displayed_vulnerabilities.get(idx) {
-				return self.state.vulnerability_detail(vuln);
-			}
+	/// Loads what is shown on opening a workspace
+	fn load_workspace(&self) -> Command<Message> {
+		let pool = self.state.pool.clone();
+		// Convert error types properly in Command::perform callbacks
+		Command::batch(vec![
+			self.load_page(),
+			Command::perform(
+				load_robots(pool.clone()),
+				|result| Message::RobotsLoaded(result.map_err(|e| e.to_string())),
+			),
+			Command::perform(
+				load_row_tint(pool.clone()),
+				|result| Message::RowTintLoaded(result.map_err(|e| e.to_string())),
+			),
+			Command::perform(
+				load_list_layout(pool.clone()),
+				|result| Message::ListLayoutLoaded(result.map_err(|e| e.to_string())),
+			),
+			Command::perform(
+				load_table_columns(pool.clone()),
+				|result| Message::TableColumnsLoaded(result.map_err(|e| e.to_string())),
+			),
+			Command::perform(
+				load_theme(pool.clone()),
+				|result| Message::ThemeLoaded(result.map_err(|e| e.to_string())),
+			),
+			Command::perform(
+				load_detail_layout(pool.clone()),
+				|result| Message::DetailLayoutLoaded(result.map_err(|e| e.to_string())),
+			),
+			Command::perform(
+				load_color_blind_safe(pool.clone()),
+				|result| Message::ColorBlindSafeLoaded(result.map_err(|e| e.to_string())),
+			),
+			Command::perform(
+				super::database::load_inventory_max_age(pool.clone()),
+				|result| Message::InventoryMaxAgeLoaded(result.map_err(|e| e.to_string())),
+			),
+			Command::perform(
+				super::database::load_compliance_controls(pool.clone()),
+				|result| Message::ComplianceControlsLoaded(result.map_err(|e| e.to_string())),
+			),
+			Command::perform(
+				check_compaction(pool),
+				|result| Message::CompactionChecked(result.map_err(|e| e.to_string())),
+			),
+			self.load_nvd_health(),
+			self.load_data_sources(),
+			Command::perform(
+				super::database::load_update_check(self.state.pool.clone()),
+				|result| Message::UpdateCheckLoaded(result.map_err(|e| e.to_string())),
+			),
+			Command::perform(
+				super::database::load_ticketing_enabled(self.state.pool.clone()),
+				|result| Message::TicketingLoaded(result.map_err(|e| e.to_string())),
+			),
+		])
+	}
+
+	fn load_linked_controls(&self, vulnerability_id: i64) -> Command<Message> {
+		Command::perform(
+			super::database::load_linked_controls(self.state.pool.clone(), vulnerability_id),
+			move |result| Message::LinkedControlsLoaded(vulnerability_id, result.map_err(|e| e.to_string())),
+		)
+	}
+
+	fn load_robot_software(&self, robot_id: i32) -> Command<Message> {
+		Command::perform(
+			super::database::load_robot_software(self.state.pool.clone(), robot_id),
+			move |result| Message::RobotSoftwareLoaded(robot_id, result.map_err(|e| e.to_string())),
+		)
+	}
+
+	fn load_vendor_names(&self) -> Command<Message> {
+		Command::perform(
+			super::database::load_vendor_names(self.state.pool.clone()),
+			|result| Message::VendorNamesLoaded(result.map_err(|e| e.to_string())),
+		)
+	}
+
+	fn load_software_versions(&self) -> Command<Message> {
+		Command::perform(
+			load_version_metadata(self.state.pool.clone()),
+			|result| Message::SoftwareVersionsLoaded(result.map_err(|e| e.to_string())),
+		)
+	}
+
+	/// Formatters only get the theme, so they read the palette from a global, like the
+	/// display time zone
+	fn set_color_blind_safe(&mut self, enabled: bool) {
+		self.state.color_blind_safe = enabled;
+		super::formatters::set_color_blind_safe(enabled);
+	}
+
+	/// Asks the release endpoint for a newer version, unless the check is off
+	fn check_for_update(&mut self) -> Command<Message> {
+		let status = &mut self.state.update_status;
+		if !status.settings.enabled {
+			return Command::none();
+		}
+		status.checking = true;
+		let settings = status.settings.clone();
+		Command::perform(
+			async move { update_check::check(&settings).await },
+			|result| Message::UpdateChecked(result.map_err(|e| format!("{:#}", e))),
+		)
+	}
+
+	fn load_data_sources(&self) -> Command<Message> {
+		Command::perform(
+			super::database::load_data_sources(self.state.pool.clone()),
+			|result| Message::DataSourcesLoaded(result.map_err(|e| e.to_string())),
+		)
+	}
+
+	/// Saves a data source with one setting changed in the dialog
+	fn save_data_source(&self, source_id: i64, change: impl FnOnce(&mut DataSource)) -> Command<Message> {
+		let Some(mut source) = self.state.data_sources.iter().find(|source| source.source_id == source_id).cloned() else {
+			return Command::none();
+		};
+		change(&mut source);
+		Command::perform(
+			super::database::save_data_source(self.state.pool.clone(), source_id, source.enabled, source.interval_minutes, source.url),
+			|result| Message::DataSourcesLoaded(result.map_err(|e| e.to_string())),
+		)
+	}
+
+	fn sync_data_sources(&mut self, source_id: Option<i64>) -> Command<Message> {
+		self.state.syncing = true;
+		Command::perform(
+			super::database::sync_data_sources(self.state.pool.clone(), source_id, self.progress.clone()),
+			|result| Message::SyncFinished(result.map_err(|e| format!("{:#}", e))),
+		)
+	}
+
+	fn load_nvd_health(&self) -> Command<Message> {
+		Command::perform(
+			load_nvd_health(self.state.pool.clone()),
+			|result| Message::NvdHealthLoaded(result.map_err(|e| e.to_string())),
+		)
+	}
+
+	/// Loads the current page of vulnerabilities with the current query, seeking from
+	/// the end of the previous page when it has been loaded
+	fn load_page(&self) -> Command<Message> {
+		let page = self.state.current_page;
+		let after = page.checked_sub(1).and_then(|previous| self.state.page_cursors.get(previous).cloned());
+		let load = Command::perform(
+			load_vulnerabilities(
+				self.state.pool.clone(),
+				self.state.vulnerability_query(),
+				page,
+				after,
+				DISPLAY_PAGE_SIZE,
+			),
+			|result| Message::VulnerabilitiesLoaded(result.map_err(|e| e.to_string())),
+		);
+		// Filters only change by reloading from the first page
+		if page > 0 {
+			return load;
+		}
+		let mut commands = vec![
+			load,
+			Command::perform(
+				load_quick_filter_counts(self.state.pool.clone(), self.state.vulnerability_query()),
+				|result| Message::QuickFilterCountsLoaded(result.map_err(|e| e.to_string())),
+			),
+		];
+		// The statistics follow the filters
+		if self.state.show_statistics {
+			commands.push(self.load_statistics());
+		}
+		Command::batch(commands)
+	}
+
+	/// Loads the dashboard data, computed in the database rather than from the loaded page
+	fn load_statistics(&self) -> Command<Message> {
+		let pool = self.state.pool.clone();
+		Command::batch(vec![
+			Command::perform(
+				load_statistics_report(pool.clone(), self.state.vulnerability_query()),
+				|result| Message::StatisticsLoaded(result.map_err(|e| e.to_string())),
+			),
+			Command::perform(
+				load_risky_software(pool.clone(), TOP_RISKY_SOFTWARE_LIMIT),
+				|result| Message::RiskySoftwareLoaded(result.map_err(|e| e.to_string())),
+			),
+			Command::perform(
+				load_enrichment_progress(pool),
+				|result| Message::EnrichmentProgressLoaded(result.map_err(|e| e.to_string())),
+			),
+		])
+	}
+
+	/// The dialog shown over the tabs, if one is open
+	fn open_dialog(&self) -> Option<Element<'_, Message>> {
+		let state = &self.state;
+		if let Some(graph) = &state.graph {
+			Some(state.relationship_graph(graph))
+		} else if let Some(maintenance) = &state.maintenance {
+			Some(state.maintenance_dialog(maintenance))
+		} else if let Some(items) = &state.trash {
+			Some(state.trash_dialog(items))
+		} else if let Some(audit) = &state.audit {
+			Some(state.audit_dialog(audit))
+		} else if let Some(runs) = &state.import_runs {
+			Some(state.import_history_dialog(runs))
+		} else if let Some(view) = &state.data_sources_view {
+			Some(state.data_sources_dialog(view))
+		} else if let Some(deltas) = &state.whats_new {
+			Some(state.whats_new_dialog(deltas))
+		} else if let Some(view) = &state.csv_import {
+			Some(state.csv_import_dialog(view))
+		} else {
+			state.about_open.then(|| state.about_dialog())
+		}
+	}
+
+	fn load_checklist(&self, robot_id: i32) -> Command<Message> {
+		Command::perform(
+			super::database::load_checklist(self.state.pool.clone(), robot_id),
+			move |result| Message::ChecklistLoaded(robot_id, result.map_err(|e| e.to_string())),
+		)
+	}
+
+	/// ID of the robot shown in the detail view
+	fn selected_vulnerability_id(&self) -> Option<i64> {
+		self.state.selected_vulnerability
+			.and_then(|idx| self.state.displayed_vulnerabilities.get(idx))
+			.and_then(|vuln| vuln.vulnerability_id)
+	}
+
+	fn selected_robot_id(&self) -> Option<i32> {
+		self.state.selected_robot
+			.and_then(|idx| self.state.get_displayed_robots().get(idx))
+			.and_then(|robot| robot.robot_id)
+	}
+
+	fn load_trash(&self) -> Command<Message> {
+		Command::perform(
+			super::database::load_deleted_items(self.state.pool.clone()),
+			|result| Message::TrashLoaded(result.map_err(|e| e.to_string())),
+		)
+	}
+
+	fn load_sync_deltas(&self) -> Command<Message> {
+		Command::perform(
+			super::database::load_sync_deltas(self.state.pool.clone()),
+			|result| Message::WhatsNewLoaded(result.map_err(|e| e.to_string())),
+		)
+	}
+
+	fn load_import_runs(&self) -> Command<Message> {
+		Command::perform(
+			super::database::load_import_runs(self.state.pool.clone()),
+			|result| Message::ImportHistoryLoaded(result.map_err(|e| e.to_string())),
+		)
+	}
+
+	fn load_audit_log(&self) -> Command<Message> {
+		let Some(audit) = &self.state.audit else {
+			return Command::none();
+		};
+		Command::perform(
+			super::database::load_audit_log(self.state.pool.clone(), audit.entity, audit.search.clone()),
+			|result| Message::AuditLoaded(result.map_err(|e| e.to_string())),
+		)
+	}
+
+	/// Reloads the lists a deletion or restore changes, and Recently Deleted while it is open
+	fn reload_after_deletion(&self) -> Command<Message> {
+		let mut commands = vec![
+			self.load_page(),
+			Command::perform(
+				load_robots(self.state.pool.clone()),
+				|result| Message::RobotsLoaded(result.map_err(|e| e.to_string())),
+			),
+		];
+		if self.state.trash.is_some() {
+			commands.push(self.load_trash());
+		}
+		Command::batch(commands)
+	}
+
+	/// Reloads the notes of the record shown in the detail view
+	fn load_notes(&self) -> Command<Message> {
+		match self.state.notes_entity {
+			Some((entity_type, entity_id)) => Command::perform(
+				super::database::load_notes(self.state.pool.clone(), entity_type, entity_id),
+				|result| Message::NotesLoaded(result.map_err(|e| e.to_string())),
+			),
+			None => Command::none(),
 		}
+	}
+
+	fn vulnerability_view(&self) -> Element<Message> {
+		let detail = self.state.selected_vulnerability
+			.and_then(|idx| self.state.displayed_vulnerabilities.get(idx))
+			.map(|vuln| self.state.vulnerability_detail(vuln));
 
 		let title = iced::widget::text("Vulnerability Management")
 			.size(30);
 
-		iced::widget::column![
+		let list = iced::widget::column![
 			title,
 			self.state.control_panel(),
 			self.state.search_bar(),
-			if let Some(ref error) = self.state.error_message {
-				iced::widget::text(error)
-					.style(iced::theme::Text::Color(iced::Color::from_rgb(1.0, 0.0, 0.0)))
-					.size(16)
-			} else {
-				iced::widget::text("")
-			},
+			self.state.quick_filters(),
+			self.state.software_filter_banner(),
 			if self.state.show_statistics {
 				self.state.statistics()
 			} else {
@@ -450,8 +2813,9 @@ impl VulnerabilityApp {
 		]
 			.spacing(20)
 			.padding(20)
-			.width(iced::Length::Fill)
-			.into()
+			.width(iced::Length::Fill);
+
+		self.with_detail(list.into(), detail)
 	}
 
 	fn robot_view(&self) -> Element<Message> {
@@ -459,29 +2823,50 @@ impl VulnerabilityApp {
 			return self.state.robot_form();
 		}
 
-		if let Some(idx) = self.state.selected_robot {
-			if let Some(robot) = self.state.robots.get(idx) {
-				return self.state.robot_detail(robot);
-			}
-		}
+		let detail = self.state.selected_robot
+			.and_then(|idx| self.state.get_displayed_robots().get(idx))
+			.map(|robot| self.state.robot_detail(robot));
 
 		let title = iced::widget::text("Robot Inventory")
 			.size(30);
 
-		iced::widget::column![
+		let list = iced::widget::column![
 			title,
 			self.state.robot_control_panel(),
+			self.state.robot_import_errors(),
 			self.state.robot_list(),
 		]
 			.spacing(20)
 			.padding(20)
-			.width(iced::Length::Fill)
-			.into()
+			.width(iced::Length::Fill);
+
+		self.with_detail(list.into(), detail)
+	}
+
+	/// Shows the detail of the selection in place of the list, or beside it in the
+	/// split layout
+	fn with_detail<'a>(&self, list: Element<'a, Message>, detail: Option<Element<'a, Message>>) -> Element<'a, Message> {
+		match (detail, self.state.detail_layout) {
+			(None, _) => list,
+			(Some(detail), DetailLayout::Replace) => detail,
+			(Some(detail), DetailLayout::Split) => iced::widget::row![
+				iced::widget::container(list).width(iced::Length::FillPortion(2)),
+				iced::widget::container(detail).width(iced::Length::FillPortion(3)),
+			]
+				.spacing(10)
+				.into(),
+		}
 	}
 }
 
-pub async fn run(pool: Arc<SqlitePool>) -> Result<()> {
-	let mut settings = Settings::with_flags(pool);
+pub async fn run(
+	pool: Arc<SqlitePool>,
+	progress_rx: ProgressReceiver,
+	progress: ProgressReporter,
+	workspace: String,
+	open_cve: Option<String>,
+) -> Result<()> {
+	let mut settings = Settings::with_flags((pool, progress_rx, progress, workspace, open_cve));
 	settings.window.size = Size::new(1024.0, 768.0);
 	settings.window.min_size = Some(Size::new(800.0, 600.0));
 	settings.window.resizable = true;
@@ -0,0 +1,145 @@
+//! Look of the window, chosen in the toolbar and saved per workspace: a light or dark
+//! theme, and whether details open beside the list or in its place
+
+use iced::Theme;
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum ThemeChoice {
+	Light,
+	Dark,
+	/// Follows the desktop's light or dark setting, read on startup
+	#[default]
+	System,
+}
+
+impl ThemeChoice {
+	pub const ALL: [ThemeChoice; 3] = [ThemeChoice::Light, ThemeChoice::Dark, ThemeChoice::System];
+
+	pub fn from_setting(value: &str) -> Option<Self> {
+		match value.trim().to_ascii_lowercase().as_str() {
+			"light" => Some(ThemeChoice::Light),
+			"dark" => Some(ThemeChoice::Dark),
+			"system" => Some(ThemeChoice::System),
+			_ => None,
+		}
+	}
+
+	pub fn as_setting(&self) -> &'static str {
+		match self {
+			ThemeChoice::Light => "light",
+			ThemeChoice::Dark => "dark",
+			ThemeChoice::System => "system",
+		}
+	}
+
+	pub fn theme(&self, system_dark: bool) -> Theme {
+		match self {
+			ThemeChoice::Light => Theme::Light,
+			ThemeChoice::Dark => Theme::Dark,
+			ThemeChoice::System if system_dark => Theme::Dark,
+			ThemeChoice::System => Theme::Light,
+		}
+	}
+}
+
+impl std::fmt::Display for ThemeChoice {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ThemeChoice::Light => write!(f, "Light theme"),
+			ThemeChoice::Dark => write!(f, "Dark theme"),
+			ThemeChoice::System => write!(f, "System theme"),
+		}
+	}
+}
+
+/// Where the detail of a selected vulnerability or robot is shown
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum DetailLayout {
+	/// The detail replaces the list until it is closed
+	#[default]
+	Replace,
+	/// The detail opens in a pane right of the list, which stays usable
+	Split,
+}
+
+impl DetailLayout {
+	pub const ALL: [DetailLayout; 2] = [DetailLayout::Replace, DetailLayout::Split];
+
+	pub fn from_setting(value: &str) -> Option<Self> {
+		match value.trim().to_ascii_lowercase().as_str() {
+			"replace" => Some(DetailLayout::Replace),
+			"split" => Some(DetailLayout::Split),
+			_ => None,
+		}
+	}
+
+	pub fn as_setting(&self) -> &'static str {
+		match self {
+			DetailLayout::Replace => "replace",
+			DetailLayout::Split => "split",
+		}
+	}
+}
+
+impl std::fmt::Display for DetailLayout {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			DetailLayout::Replace => write!(f, "Full-screen details"),
+			DetailLayout::Split => write!(f, "Split view"),
+		}
+	}
+}
+
+/// Output of a command, or `None` when it cannot be run or fails
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+	let output = Command::new(program).args(args).output().ok()?;
+	output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Whether the desktop is set to a dark look. Unknown desktops count as light.
+pub fn system_prefers_dark() -> bool {
+	if std::env::var("GTK_THEME").is_ok_and(|theme| theme.to_ascii_lowercase().contains("dark")) {
+		return true;
+	}
+	if cfg!(target_os = "macos") {
+		// Only set while dark mode is on
+		command_output("defaults", &["read", "-g", "AppleInterfaceStyle"]).is_some_and(|style| style.contains("Dark"))
+	} else if cfg!(windows) {
+		command_output(
+			"reg",
+			&["query", r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize", "/v", "AppsUseLightTheme"],
+		)
+			.is_some_and(|value| value.contains("0x0"))
+	} else {
+		command_output("gsettings", &["get", "org.gnome.desktop.interface", "color-scheme"])
+			.is_some_and(|scheme| scheme.contains("prefer-dark"))
+			|| command_output("gsettings", &["get", "org.gnome.desktop.interface", "gtk-theme"])
+				.is_some_and(|theme| theme.to_ascii_lowercase().contains("dark"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_theme_choice() {
+		for choice in ThemeChoice::ALL {
+			assert_eq!(ThemeChoice::from_setting(choice.as_setting()), Some(choice));
+		}
+		assert_eq!(ThemeChoice::from_setting(" Dark "), Some(ThemeChoice::Dark));
+		assert_eq!(ThemeChoice::from_setting("blue"), None);
+		assert_eq!(ThemeChoice::System.theme(true), Theme::Dark);
+		assert_eq!(ThemeChoice::System.theme(false), Theme::Light);
+		assert_eq!(ThemeChoice::Light.theme(true), Theme::Light);
+	}
+
+	#[test]
+	fn test_detail_layout() {
+		for layout in DetailLayout::ALL {
+			assert_eq!(DetailLayout::from_setting(layout.as_setting()), Some(layout));
+		}
+		assert_eq!(DetailLayout::from_setting("tabs"), None);
+	}
+}
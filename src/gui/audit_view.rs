@@ -0,0 +1,97 @@
+use super::constants::AUDIT_LOG_LIMIT;
+use super::state::AppState;
+use super::types::{AuditLogView, FilterAuditEntity, Message};
+use super::formatters::format_muted;
+use crate::utils::time;
+use iced::{
+	theme,
+	widget::{button, column, container, pick_list, row, scrollable, text_input, Column, Space, Text},
+	Alignment, Element, Length,
+};
+
+pub trait AuditViewRenderer {
+	fn audit_dialog<'a>(&'a self, audit: &'a AuditLogView) -> Element<'a, Message>;
+}
+
+impl AuditViewRenderer for AppState {
+	fn audit_dialog<'a>(&'a self, audit: &'a AuditLogView) -> Element<'a, Message> {
+		let muted = theme::Text::Color(format_muted(&self.theme()));
+		let can_edit = self.role.can_edit();
+
+		let list: Element<Message> = if audit.entries.is_empty() {
+			Text::new("No changes recorded").size(16).into()
+		} else {
+			scrollable(
+				Column::with_children(audit.entries.iter().enumerate().map(|(idx, entry)| {
+					// Offered once per bulk operation, on its latest entry
+					let revert: Element<Message> = match entry.batch_id {
+						Some(batch_id) if !audit.entries[..idx].iter().any(|e| e.batch_id == Some(batch_id)) => {
+							button(Text::new("Revert batch").size(14))
+								.on_press_maybe(can_edit.then_some(Message::AuditBatchRevertClicked(batch_id)))
+								.style(theme::Button::Secondary)
+								.padding(4)
+								.width(Length::Fixed(100.0))
+								.into()
+						}
+						_ => Space::with_width(Length::Fixed(100.0)).into(),
+					};
+					row![
+						Text::new(time::format_local(entry.recorded_at)).size(14).width(Length::Fixed(150.0)),
+						Text::new(&entry.actor).size(14).width(Length::Fixed(110.0)),
+						Text::new(entry.entity.to_string()).size(14).width(Length::Fixed(130.0)),
+						Text::new(&entry.label).size(14).width(Length::Fixed(200.0)),
+						Text::new(entry.action.to_string()).size(14).width(Length::Fixed(70.0)),
+						Text::new(entry.summary()).size(14).width(Length::Fill).style(muted),
+						revert,
+					]
+						.spacing(15)
+						.align_items(Alignment::Center)
+						.into()
+				}))
+					.spacing(8),
+			)
+				.height(Length::Fill)
+				.into()
+		};
+
+		container(
+			column![
+				row![
+					Text::new("Audit Log").size(28).width(Length::Fill),
+					button(Text::new("Export CSV").size(16))
+						.on_press(Message::AuditExportRequested)
+						.style(theme::Button::Secondary)
+						.padding(5),
+					button(Text::new("Close").size(16))
+						.on_press(Message::AuditClosed)
+						.style(theme::Button::Destructive)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				Text::new(format!(
+					"Who changed which record and how, newest first. The latest {} matching changes are shown; the export has them all. \
+					 Reverting a bulk edit sets its records back, unless one was changed again since.",
+					AUDIT_LOG_LIMIT
+				))
+					.size(14),
+				row![
+					pick_list(FilterAuditEntity::options(), Some(audit.entity), Message::AuditEntityChanged)
+						.padding(8),
+					text_input("Search by record, user or change...", &audit.search)
+						.on_input(Message::AuditSearchChanged)
+						.padding(8)
+						.width(Length::Fill),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				list,
+			]
+				.spacing(15),
+		)
+			.padding(20)
+			.width(Length::Fill)
+			.style(theme::Container::Box)
+			.into()
+	}
+}
@@ -0,0 +1,412 @@
+use super::formatters::{format_muted, format_severity, format_severity_label};
+use super::state::AppState;
+use super::types::Message;
+use crate::models::statistics::ExposurePoint;
+use crate::models::trends::TrendSeries;
+use iced::{
+	alignment::{Horizontal, Vertical},
+	mouse, theme,
+	widget::{
+		canvas::{self, path::Arc, Canvas, Frame, Geometry, Path, Stroke},
+		column, container, row, Column, Space, Text,
+	},
+	Alignment, Color, Element, Length, Point, Radians, Rectangle, Renderer, Size, Theme,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+pub(super) const CHART_HEIGHT: f32 = 180.0;
+const DONUT_WIDTH: f32 = 22.0;
+/// Room below the plot for axis labels
+const AXIS_HEIGHT: f32 = 18.0;
+/// Room above the plot for the value of the hovered bar or point
+const LABEL_HEIGHT: f32 = 18.0;
+
+pub trait ChartRenderer {
+	fn statistics_charts(&self) -> Element<'_, Message>;
+}
+
+impl ChartRenderer for AppState {
+	/// Severity donut and vulnerabilities published per year side by side, with the
+	/// trend of the fleet's open exposures below
+	fn statistics_charts(&self) -> Element<'_, Message> {
+		let Some(report) = &self.statistics else {
+			return Space::with_height(Length::Shrink).into();
+		};
+		let theme = self.theme();
+		let counts: Vec<(&'static str, i64)> = report
+			.severity_counts()
+			.into_iter()
+			.filter(|&(severity, count)| count > 0 || severity != "Unknown")
+			.collect();
+		let total: i64 = counts.iter().map(|(_, count)| count).sum();
+
+		let legend = Column::with_children(counts.iter().map(|&(severity, count)| {
+			Text::new(format!(
+				"{}  {} ({}%)",
+				format_severity_label(severity),
+				count,
+				(count * 100) / total.max(1)
+			))
+				.style(theme::Text::Color(format_severity(severity, &theme)))
+				.size(15)
+				.into()
+		}))
+			.spacing(6);
+
+		let trend: Element<Message> = if report.exposure_trend.len() < 2 {
+			Text::new("The trend appears once risk scores were recorded on two days")
+				.style(theme::Text::Color(format_muted(&theme)))
+				.size(14)
+				.into()
+		} else {
+			Canvas::new(ExposureTrend { points: &report.exposure_trend })
+				.width(Length::Fill)
+				.height(Length::Fixed(CHART_HEIGHT))
+				.into()
+		};
+
+		column![
+			row![
+				chart_box(
+					"Severity Distribution",
+					row![
+						Canvas::new(SeverityDonut { counts })
+							.width(Length::Fixed(CHART_HEIGHT))
+							.height(Length::Fixed(CHART_HEIGHT)),
+						legend,
+					]
+						.spacing(15)
+						.align_items(Alignment::Center)
+						.into(),
+				),
+				chart_box(
+					"Published per Year",
+					Canvas::new(YearBars { years: &report.published_per_year })
+						.width(Length::Fill)
+						.height(Length::Fixed(CHART_HEIGHT))
+						.into(),
+				),
+			]
+				.spacing(10),
+			chart_box("Open Exposures on the Fleet", trend),
+		]
+			.spacing(10)
+			.into()
+	}
+}
+
+pub(super) fn chart_box<'a>(title: &'a str, chart: Element<'a, Message>) -> Element<'a, Message> {
+	container(column![Text::new(title).size(16), chart].spacing(8))
+		.style(theme::Container::Box)
+		.padding(10)
+		.width(Length::Fill)
+		.into()
+}
+
+fn label(content: String, position: Point, color: Color, horizontal_alignment: Horizontal) -> canvas::Text {
+	canvas::Text {
+		content,
+		position,
+		color,
+		size: 12.0.into(),
+		horizontal_alignment,
+		vertical_alignment: Vertical::Center,
+		..canvas::Text::default()
+	}
+}
+
+/// Share of each severity as a ring segment, the total in the middle
+struct SeverityDonut {
+	counts: Vec<(&'static str, i64)>,
+}
+
+impl canvas::Program<Message> for SeverityDonut {
+	type State = ();
+
+	fn draw(
+		&self,
+		_state: &Self::State,
+		renderer: &Renderer,
+		theme: &Theme,
+		bounds: Rectangle,
+		_cursor: mouse::Cursor,
+	) -> Vec<Geometry> {
+		let mut frame = Frame::new(renderer, bounds.size());
+		let center = frame.center();
+		let radius = bounds.width.min(bounds.height) / 2.0 - DONUT_WIDTH / 2.0;
+		let total: i64 = self.counts.iter().map(|(_, count)| count).sum();
+
+		let ring = |start: f32, end: f32| Path::new(|builder| {
+			builder.arc(Arc { center, radius, start_angle: Radians(start), end_angle: Radians(end) });
+		});
+		if total == 0 {
+			frame.stroke(&ring(0.0, TAU), Stroke::default().with_color(format_muted(theme)).with_width(DONUT_WIDTH));
+		}
+		// Clockwise from the top
+		let mut start = -FRAC_PI_2;
+		for &(severity, count) in self.counts.iter().filter(|(_, count)| *count > 0) {
+			let end = start + TAU * count as f32 / total as f32;
+			frame.stroke(
+				&ring(start, end),
+				Stroke::default().with_color(format_severity(severity, theme)).with_width(DONUT_WIDTH),
+			);
+			start = end;
+		}
+
+		frame.fill_text(canvas::Text {
+			size: 24.0.into(),
+			..label(total.to_string(), center, theme.palette().text, Horizontal::Center)
+		});
+		vec![frame.into_geometry()]
+	}
+}
+
+/// Vulnerabilities per publication year as bars, the count of the hovered one above it
+struct YearBars<'a> {
+	years: &'a BTreeMap<String, i64>,
+}
+
+impl canvas::Program<Message> for YearBars<'_> {
+	type State = ();
+
+	fn draw(
+		&self,
+		_state: &Self::State,
+		renderer: &Renderer,
+		theme: &Theme,
+		bounds: Rectangle,
+		cursor: mouse::Cursor,
+	) -> Vec<Geometry> {
+		let mut frame = Frame::new(renderer, bounds.size());
+		let muted = format_muted(theme);
+		let plot_height = bounds.height - AXIS_HEIGHT - LABEL_HEIGHT;
+		let baseline = bounds.height - AXIS_HEIGHT;
+		frame.stroke(
+			&Path::line(Point::new(0.0, baseline), Point::new(bounds.width, baseline)),
+			Stroke::default().with_color(muted).with_width(1.0),
+		);
+		if self.years.is_empty() {
+			frame.fill_text(label(
+				"No publication dates yet".to_string(),
+				Point::new(bounds.width / 2.0, baseline / 2.0),
+				muted,
+				Horizontal::Center,
+			));
+			return vec![frame.into_geometry()];
+		}
+
+		let max = self.years.values().copied().max().unwrap_or(0).max(1);
+		let slot = bounds.width / self.years.len() as f32;
+		// Label every year only where they fit, otherwise every few years
+		let label_every = (40.0 / slot).ceil().max(1.0) as usize;
+		let hovered = cursor.position_in(bounds).map(|position| (position.x / slot) as usize);
+
+		for (idx, (year, &count)) in self.years.iter().enumerate() {
+			let height = plot_height * count as f32 / max as f32;
+			let x = slot * idx as f32 + slot * 0.15;
+			let color = if hovered == Some(idx) { theme.palette().text } else { theme.palette().primary };
+			frame.fill_rectangle(Point::new(x, baseline - height), Size::new(slot * 0.7, height), color);
+
+			let middle = slot * (idx as f32 + 0.5);
+			if idx % label_every == 0 || hovered == Some(idx) {
+				frame.fill_text(label(year.clone(), Point::new(middle, baseline + AXIS_HEIGHT / 2.0), muted, Horizontal::Center));
+			}
+			if hovered == Some(idx) {
+				frame.fill_text(label(
+					count.to_string(),
+					Point::new(middle, baseline - height - LABEL_HEIGHT / 2.0),
+					theme.palette().text,
+					Horizontal::Center,
+				));
+			}
+		}
+		vec![frame.into_geometry()]
+	}
+}
+
+/// Open exposures per recorded day as a line, the day nearest the cursor described above it
+struct ExposureTrend<'a> {
+	points: &'a [ExposurePoint],
+}
+
+impl canvas::Program<Message> for ExposureTrend<'_> {
+	type State = ();
+
+	fn draw(
+		&self,
+		_state: &Self::State,
+		renderer: &Renderer,
+		theme: &Theme,
+		bounds: Rectangle,
+		cursor: mouse::Cursor,
+	) -> Vec<Geometry> {
+		let mut frame = Frame::new(renderer, bounds.size());
+		let muted = format_muted(theme);
+		let max = self.points.iter().map(|point| point.open_exposures).max().unwrap_or(0).max(1);
+		// Room left of the plot for the scale
+		let left = 36.0;
+		let baseline = bounds.height - AXIS_HEIGHT;
+		let plot_height = baseline - LABEL_HEIGHT;
+		let step = (bounds.width - left - 10.0) / (self.points.len() - 1).max(1) as f32;
+		let position = |idx: usize, point: &ExposurePoint| {
+			Point::new(left + step * idx as f32, baseline - plot_height * point.open_exposures as f32 / max as f32)
+		};
+
+		frame.stroke(
+			&Path::line(Point::new(left, baseline), Point::new(bounds.width, baseline)),
+			Stroke::default().with_color(muted).with_width(1.0),
+		);
+		frame.fill_text(label(max.to_string(), Point::new(left - 6.0, LABEL_HEIGHT), muted, Horizontal::Right));
+		frame.fill_text(label("0".to_string(), Point::new(left - 6.0, baseline), muted, Horizontal::Right));
+		if let (Some(first), Some(last)) = (self.points.first(), self.points.last()) {
+			let y = baseline + AXIS_HEIGHT / 2.0;
+			frame.fill_text(label(first.day.clone(), Point::new(left, y), muted, Horizontal::Left));
+			frame.fill_text(label(last.day.clone(), Point::new(bounds.width, y), muted, Horizontal::Right));
+		}
+
+		let line = Path::new(|builder| {
+			for (idx, point) in self.points.iter().enumerate() {
+				if idx == 0 {
+					builder.move_to(position(idx, point));
+				} else {
+					builder.line_to(position(idx, point));
+				}
+			}
+		});
+		frame.stroke(&line, Stroke::default().with_color(theme.palette().primary).with_width(2.0));
+
+		let hovered = cursor.position_in(bounds).map(|cursor| {
+			(((cursor.x - left) / step).round().max(0.0) as usize).min(self.points.len() - 1)
+		});
+		if let Some(idx) = hovered {
+			let point = &self.points[idx];
+			let at = position(idx, point);
+			frame.fill(&Path::circle(at, 4.0), theme.palette().text);
+			// Keep the description inside the chart near either edge
+			let alignment = if at.x < bounds.width / 3.0 {
+				Horizontal::Left
+			} else if at.x > bounds.width * 2.0 / 3.0 {
+				Horizontal::Right
+			} else {
+				Horizontal::Center
+			};
+			frame.fill_text(label(
+				format!("{}: {} open, fleet risk {:.1}", point.day, point.open_exposures, point.fleet_risk_index),
+				Point::new(at.x, (at.y - LABEL_HEIGHT / 2.0).max(LABEL_HEIGHT / 2.0)),
+				theme.palette().text,
+				alignment,
+			));
+		}
+		vec![frame.into_geometry()]
+	}
+}
+
+/// Several series over the same days as lines, the values on the day nearest the
+/// cursor described above the plot
+pub(super) struct TrendLines<'a> {
+	pub series: Vec<(&'a TrendSeries, Color)>,
+}
+
+impl TrendLines<'_> {
+	/// Every day any series has a value on, oldest first
+	fn days(&self) -> Vec<&str> {
+		self.series
+			.iter()
+			.flat_map(|(series, _)| series.points.iter().map(|point| point.day.as_str()))
+			.collect::<BTreeSet<_>>()
+			.into_iter()
+			.collect()
+	}
+}
+
+impl canvas::Program<Message> for TrendLines<'_> {
+	type State = ();
+
+	fn draw(
+		&self,
+		_state: &Self::State,
+		renderer: &Renderer,
+		theme: &Theme,
+		bounds: Rectangle,
+		cursor: mouse::Cursor,
+	) -> Vec<Geometry> {
+		let mut frame = Frame::new(renderer, bounds.size());
+		let muted = format_muted(theme);
+		let days = self.days();
+		let max = self.series
+			.iter()
+			.flat_map(|(series, _)| series.points.iter().map(|point| point.value))
+			.fold(1.0, f64::max);
+		let left = 36.0;
+		let baseline = bounds.height - AXIS_HEIGHT;
+		let plot_height = baseline - LABEL_HEIGHT;
+		let step = (bounds.width - left - 10.0) / (days.len().max(2) - 1) as f32;
+		let position = |day: &str, value: f64| {
+			let idx = days.iter().position(|known| *known == day).unwrap_or(0);
+			Point::new(left + step * idx as f32, baseline - plot_height * (value / max) as f32)
+		};
+
+		frame.stroke(
+			&Path::line(Point::new(left, baseline), Point::new(bounds.width, baseline)),
+			Stroke::default().with_color(muted).with_width(1.0),
+		);
+		frame.fill_text(label(format!("{:.0}", max), Point::new(left - 6.0, LABEL_HEIGHT), muted, Horizontal::Right));
+		frame.fill_text(label("0".to_string(), Point::new(left - 6.0, baseline), muted, Horizontal::Right));
+		if let (Some(first), Some(last)) = (days.first(), days.last()) {
+			let y = baseline + AXIS_HEIGHT / 2.0;
+			frame.fill_text(label(first.to_string(), Point::new(left, y), muted, Horizontal::Left));
+			frame.fill_text(label(last.to_string(), Point::new(bounds.width, y), muted, Horizontal::Right));
+		}
+
+		for (series, color) in &self.series {
+			let line = Path::new(|builder| {
+				for (idx, point) in series.points.iter().enumerate() {
+					if idx == 0 {
+						builder.move_to(position(&point.day, point.value));
+					} else {
+						builder.line_to(position(&point.day, point.value));
+					}
+				}
+			});
+			frame.stroke(&line, Stroke::default().with_color(*color).with_width(2.0));
+			// A single day has no line to show
+			if let [point] = series.points.as_slice() {
+				frame.fill(&Path::circle(position(&point.day, point.value), 3.0), *color);
+			}
+		}
+
+		let hovered = cursor.position_in(bounds).filter(|_| !days.is_empty()).map(|cursor| {
+			(((cursor.x - left) / step).round().max(0.0) as usize).min(days.len() - 1)
+		});
+		if let Some(idx) = hovered {
+			let day = days[idx];
+			let values: Vec<String> = self.series
+				.iter()
+				.filter_map(|(series, _)| {
+					let point = series.points.iter().find(|point| point.day == day)?;
+					Some(format!("{} {:.1}", series.label, point.value))
+				})
+				.collect();
+			let x = left + step * idx as f32;
+			frame.stroke(
+				&Path::line(Point::new(x, LABEL_HEIGHT), Point::new(x, baseline)),
+				Stroke::default().with_color(muted).with_width(1.0),
+			);
+			let alignment = if x < bounds.width / 3.0 {
+				Horizontal::Left
+			} else if x > bounds.width * 2.0 / 3.0 {
+				Horizontal::Right
+			} else {
+				Horizontal::Center
+			};
+			frame.fill_text(label(
+				format!("{}: {}", day, values.join(", ")),
+				Point::new(x, LABEL_HEIGHT / 2.0),
+				theme.palette().text,
+				alignment,
+			));
+		}
+		vec![frame.into_geometry()]
+	}
+}
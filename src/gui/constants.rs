@@ -1,3 +1,8 @@
 pub const DISPLAY_PAGE_SIZE: usize = 15;      // Number of items shown per page
-pub const LOAD_PAGE_SIZE: usize = 324607;     // Number of items loaded from DB at once
-pub const SCROLL_THRESHOLD: f32 = 0.8;        // When to trigger next page load
\ No newline at end of file
+pub const SCROLL_THRESHOLD: f32 = 0.8;        // When to trigger next page load
+pub const TOP_RISKY_SOFTWARE_LIMIT: usize = 10; // Entries in the top risky software widget
+pub const NAME_SUGGESTION_LIMIT: usize = 4;   // Known manufacturer/vendor spellings offered below a field
+pub const RELATED_VULNERABILITY_LIMIT: usize = 10; // Related CVEs listed in the vulnerability detail
+pub const AUDIT_LOG_LIMIT: usize = 500;       // Latest audit entries shown in the audit log dialog
+pub const WHATS_NEW_ROW_LIMIT: usize = 20;    // Changes listed per kind and sync in the What's New dialog
+pub const TOAST_TICK: std::time::Duration = std::time::Duration::from_secs(1); // How often expired toasts are removed
@@ -0,0 +1,155 @@
+use super::state::AppState;
+use super::types::{CsvImportView, Message};
+use super::formatters::{format_error, format_muted};
+use crate::models::csv_mapping::{CsvField, MergePolicy};
+use iced::{
+	theme,
+	widget::{button, column, container, pick_list, row, scrollable, text_input, Column, Space, Text},
+	Alignment, Element, Length,
+};
+
+pub trait CsvImportViewRenderer {
+	fn csv_import_dialog<'a>(&'a self, view: &'a CsvImportView) -> Element<'a, Message>;
+}
+
+impl AppState {
+	/// A field with the pick list of the file's columns
+	fn csv_field_row<'a>(&'a self, view: &'a CsvImportView, field: CsvField) -> Element<'a, Message> {
+		let label = if field.is_required() { format!("{} *", field) } else { field.to_string() };
+		row![
+			Text::new(label).size(14).width(Length::Fixed(120.0)),
+			pick_list(
+				view.column_options(field),
+				view.selected_column(field),
+				move |column| Message::CsvImportColumnSelected(field, column),
+			)
+				.placeholder("Choose a column")
+				.text_size(14)
+				.padding(4)
+				.width(Length::Fixed(260.0)),
+		]
+			.spacing(10)
+			.align_items(Alignment::Center)
+			.into()
+	}
+}
+
+impl CsvImportViewRenderer for AppState {
+	fn csv_import_dialog<'a>(&'a self, view: &'a CsvImportView) -> Element<'a, Message> {
+		let theme = self.theme();
+		let can_edit = self.role.can_edit();
+		let missing = view.missing_fields();
+		let can_import = can_edit && !view.importing && !view.headers.is_empty() && missing.is_empty();
+		let can_save = can_edit && !view.headers.is_empty() && missing.is_empty() && !view.preset_name.trim().is_empty();
+
+		let mapping: Element<Message> = if view.headers.is_empty() {
+			Text::new("Read the headers of the file to choose the column for each field")
+				.size(14)
+				.style(theme::Text::Color(format_muted(&theme)))
+				.into()
+		} else {
+			let mut fields = Column::with_children(CsvField::ALL.into_iter().map(|field| self.csv_field_row(view, field)))
+				.spacing(8);
+			if !missing.is_empty() {
+				let missing: Vec<String> = missing.iter().map(ToString::to_string).collect();
+				fields = fields.push(
+					Text::new(format!("Choose the column for {}", missing.join(", ")))
+						.size(14)
+						.style(theme::Text::Color(format_error(&theme))),
+				);
+			}
+			scrollable(fields).height(Length::Fill).into()
+		};
+
+		let preset_names: Vec<String> = view.presets.iter().map(|(name, _)| name.clone()).collect();
+		let selected_preset = preset_names.iter().find(|name| **name == view.preset_name).cloned();
+
+		container(
+			column![
+				row![
+					Text::new("Import CSV").size(28).width(Length::Fill),
+					button(Text::new("Close").size(16))
+						.on_press(Message::CsvImportClosed)
+						.style(theme::Button::Destructive)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				Text::new(
+					"Imports a vulnerability list from a CSV file or an .xlsx workbook in any layout. \
+					 Columns are matched by header name; fields marked * are required. Locked fields of CVEs \
+					 already recorded are kept whatever the merge policy. \
+					 Save the mapping as a preset to reuse it for the next export from the same system.",
+				)
+					.size(14),
+				row![
+					text_input("Path to a .csv or .xlsx file", &view.path)
+						.on_input(Message::CsvImportPathChanged)
+						.on_submit(Message::CsvImportHeadersRequested)
+						.size(14)
+						.padding(5),
+					button(Text::new("Read Headers").size(14))
+						.on_press_maybe((!view.path.trim().is_empty()).then_some(Message::CsvImportHeadersRequested))
+						.style(theme::Button::Secondary)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				row![
+					Text::new("Preset").size(14).width(Length::Fixed(120.0)),
+					pick_list(preset_names, selected_preset, Message::CsvPresetSelected)
+						.placeholder(if view.presets.is_empty() { "No saved presets" } else { "Choose a preset" })
+						.text_size(14)
+						.padding(4)
+						.width(Length::Fixed(260.0)),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				row![
+					Text::new("Source").size(14).width(Length::Fixed(120.0)),
+					text_input("System the file is exported from", &view.mapping.source)
+						.on_input(Message::CsvImportSourceChanged)
+						.size(14)
+						.padding(5)
+						.width(Length::Fixed(260.0)),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				row![
+					Text::new("Existing CVEs").size(14).width(Length::Fixed(120.0)),
+					pick_list(&MergePolicy::ALL[..], Some(view.mapping.merge_policy), Message::CsvImportMergePolicySelected)
+						.text_size(14)
+						.padding(4)
+						.width(Length::Fixed(260.0)),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				mapping,
+				row![
+					text_input("Preset name", &view.preset_name)
+						.on_input(Message::CsvPresetNameChanged)
+						.on_submit(Message::CsvPresetSaved)
+						.size(14)
+						.padding(5)
+						.width(Length::Fixed(200.0)),
+					button(Text::new("Save Preset").size(14))
+						.on_press_maybe(can_save.then_some(Message::CsvPresetSaved))
+						.style(theme::Button::Secondary)
+						.padding(5),
+					Space::with_width(Length::Fill),
+					button(Text::new(if view.importing { "Importing..." } else { "Import" }).size(16))
+						.on_press_maybe(can_import.then_some(Message::CsvImportStarted))
+						.style(theme::Button::Primary)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+			]
+				.spacing(15),
+		)
+			.padding(20)
+			.width(Length::Fill)
+			.style(theme::Container::Box)
+			.into()
+	}
+}
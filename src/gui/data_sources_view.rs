@@ -0,0 +1,136 @@
+use super::state::AppState;
+use super::types::{DataSourcesView, Message, SyncInterval};
+use super::formatters::{format_error, format_muted, format_success};
+use crate::models::data_source::{DataSource, RunStatus};
+use crate::utils::time;
+use iced::{
+	theme,
+	widget::{button, column, container, pick_list, row, scrollable, text_input, Checkbox, Column, Text},
+	Alignment, Element, Length,
+};
+
+pub trait DataSourcesViewRenderer {
+	fn data_sources_dialog<'a>(&'a self, view: &'a DataSourcesView) -> Element<'a, Message>;
+}
+
+impl AppState {
+	fn data_source_row<'a>(&'a self, view: &'a DataSourcesView, source: &'a DataSource) -> Element<'a, Message> {
+		let theme = self.theme();
+		let can_edit = self.role.can_edit();
+		let busy = self.syncing || self.progress.is_some();
+		let source_id = source.source_id;
+
+		let (status, color) = match (source.last_run_at, source.last_status) {
+			(None, _) => ("Never ran".to_string(), format_muted(&theme)),
+			(Some(at), None) => (format!("Started {}", time::format_local(at)), format_muted(&theme)),
+			(Some(at), Some(status)) => (
+				format!(
+					"{} {}: {}",
+					if status == RunStatus::Succeeded { "Succeeded" } else { "Failed" },
+					time::format_local(at),
+					source.last_message.as_deref().unwrap_or_default(),
+				),
+				if status == RunStatus::Succeeded { format_success(&theme) } else { format_error(&theme) },
+			),
+		};
+
+		let mut settings = row![
+			Text::new(&source.name).size(16).width(Length::Fixed(180.0)),
+			Text::new(source.kind.to_string())
+				.size(14)
+				.style(theme::Text::Color(format_muted(&theme)))
+				.width(Length::Fixed(140.0)),
+			Checkbox::new("Enabled", source.enabled)
+				.on_toggle_maybe(can_edit.then_some(move |enabled| Message::DataSourceToggled(source_id, enabled)))
+				.spacing(5),
+			pick_list(
+				SyncInterval::CHOICES,
+				Some(SyncInterval(source.interval_minutes)),
+				move |interval| Message::DataSourceIntervalSelected(source_id, interval),
+			)
+				.text_size(14)
+				.padding(4),
+			button(Text::new("Run Now").size(14))
+				.on_press_maybe((can_edit && !busy).then_some(Message::DataSourceRunClicked(source_id)))
+				.style(theme::Button::Secondary)
+				.padding(5),
+		]
+			.spacing(15)
+			.align_items(Alignment::Center);
+		if source.is_custom() {
+			settings = settings.push(
+				button(Text::new("Remove").size(14))
+					.on_press_maybe(can_edit.then_some(Message::DataSourceRemoveClicked(source_id)))
+					.style(theme::Button::Destructive)
+					.padding(5),
+			);
+		}
+
+		let mut entry = column![settings].spacing(6);
+		if source.kind.uses_url() {
+			let url = view.urls.get(&source_id).map(String::as_str).or(source.url.as_deref()).unwrap_or_default();
+			entry = entry.push(
+				text_input("https://...", url)
+					.on_input(move |url| Message::DataSourceUrlChanged(source_id, url))
+					.on_submit(Message::DataSourceUrlSubmitted(source_id))
+					.size(14)
+					.padding(5),
+			);
+		}
+		entry.push(Text::new(status).size(14).style(theme::Text::Color(color))).into()
+	}
+}
+
+impl DataSourcesViewRenderer for AppState {
+	fn data_sources_dialog<'a>(&'a self, view: &'a DataSourcesView) -> Element<'a, Message> {
+		let can_edit = self.role.can_edit();
+		let can_add = can_edit && !view.feed_name.trim().is_empty() && !view.feed_url.trim().is_empty();
+
+		container(
+			column![
+				row![
+					Text::new("Data Sources").size(28).width(Length::Fill),
+					button(Text::new("Close").size(16))
+						.on_press(Message::DataSourcesClosed)
+						.style(theme::Button::Destructive)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				Text::new(
+					"Feeds synced in the background while RVD is open, each on its own schedule. \
+					 URLs are saved when Enter is pressed. The GitHub advisories need a token in GITHUB_TOKEN.",
+				)
+					.size(14),
+				scrollable(
+					Column::with_children(self.data_sources.iter().map(|source| self.data_source_row(view, source)))
+						.spacing(18),
+				)
+					.height(Length::Fill),
+				Text::new("Add an NVD JSON feed, e.g. a mirror on the internal network").size(16),
+				row![
+					text_input("Name", &view.feed_name)
+						.on_input(Message::DataSourceFeedNameChanged)
+						.size(14)
+						.padding(5)
+						.width(Length::Fixed(180.0)),
+					text_input("https://.../nvdcve-2.0-modified.json.gz", &view.feed_url)
+						.on_input(Message::DataSourceFeedUrlChanged)
+						.size(14)
+						.padding(5),
+					button(Text::new("Add Feed").size(14))
+						.on_press_maybe(can_add.then_some(Message::DataSourceFeedAdded))
+						.style(theme::Button::Primary)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+			]
+				.spacing(15),
+		)
+			.padding(20)
+			.width(Length::Fill)
+			.style(theme::Container::Box)
+			.into()
+	}
+}
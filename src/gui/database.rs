@@ -1,88 +1,581 @@
-use crate::db::connection::SqlitePool;
-use crate::models::{robot::Robot, vulnerability::Vulnerability};
-use crate::repositories::vulnerability_repo::VulnerabilityRepository;
-use super::types::{FilterSeverity, RobotForm, SortField};
+use crate::db::compaction::{self, CompactionMode, StorageStats};
+use crate::db::connection::{self, SqlitePool};
+use crate::db::maintenance::MaintenancePolicy;
+use crate::db::workspace::Workspaces;
+use crate::repositories::settings_repo::SettingsRepository;
+use crate::utils::nvd_api::NvdApiClient;
+use crate::utils::progress::ProgressReporter;
+use crate::utils::robot_import::{import_robots, RobotImportSummary};
+use crate::utils::csv_importer;
+use crate::models::csv_mapping::CsvMapping;
+use crate::utils::time;
+use crate::models::{robot::{Criticality, InventorySource, Robot, RobotExposure}, vulnerability::{LockedField, RelatedVulnerability, RiskAcceptance, TriageStatus, Vulnerability}};
+use crate::reports::{audit, changelog, compliance, risk_acceptance, save_to_downloads, share, Layout};
+use crate::repositories::{access, audit_repo};
+use crate::models::audit::{AuditAction, AuditEntity};
+use crate::repositories::vulnerability_repo::{
+	PageCursor, QuickFilter, SortColumn, SortOrder, VulnerabilityFilter, VulnerabilityPage, VulnerabilityRepository,
+};
+use super::appearance::{DetailLayout, ThemeChoice};
+use super::types::{FilterAuditEntity, FilterSeverity, FilterStatus, FilterWeakness, ListLayout, RobotForm, RowTint, SortField, TableColumns, VulnerabilityQuery};
+use crate::models::software::{RiskySoftware, VersionMetadata, VersionMetadataChange};
+use crate::repositories::robot_repo::{mark_inventory_refreshed, refresh_risk_scores, RobotRepository};
+use crate::repositories::software_repo::{set_robot_software, SoftwareRepository};
+use crate::repositories::note_repo::NoteRepository;
+use crate::repositories::reference_repo::ReferenceRepository;
+use crate::repositories::ticket_repo::TicketRepository;
+use crate::models::ticket::Ticket;
+use crate::models::data_source::DataSource;
+use crate::repositories::data_source_repo::DataSourceRepository;
+use crate::utils::data_sources;
+use crate::utils::ticketing;
+use crate::models::reference::Reference;
+use crate::models::note::{Note, NoteEntity};
+use crate::models::graph::{GraphCenter, RelationshipGraph};
+use crate::repositories::graph_repo::GraphRepository;
+use crate::models::enrichment::EnrichmentProgress;
+use crate::models::nvd_health::NvdHealth;
+use crate::models::statistics::StatisticsReport;
+use crate::models::trends::FleetTrends;
+use crate::repositories::enrichment_repo::EnrichmentRepository;
+use crate::repositories::metrics_repo::{MetricsRepository, TREND_DAYS};
+use crate::utils::update_check::UpdateCheckSettings;
+use crate::repositories::statistics_repo::StatisticsRepository;
+use crate::repositories::sync_delta_repo::SyncDeltaRepository;
+use crate::repositories::trash_repo::TrashRepository;
+use crate::repositories::import_run_repo::ImportRunRepository;
+use crate::repositories::commissioning_repo::CommissioningRepository;
+use crate::repositories::compliance_repo::ComplianceRepository;
+use crate::repositories::audit_repo::{AuditFilter, AuditRepository};
+use crate::models::audit::AuditEntry;
+use crate::models::trash::{DeletedItem, DeletedKind};
+use crate::models::import_run::{ImportReport, ImportRun, RevertSummary, SkippedRow};
+use crate::models::sync_delta::SyncDelta;
+use crate::models::commissioning::ChecklistEntry;
+use crate::models::compliance::ComplianceControl;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use log::{error, info, debug};
 use tokio::task;
 use anyhow::{Result, Context, bail};
 use rusqlite::{params, Transaction};
-use chrono::NaiveDateTime;
+use chrono::{Local, Utc};
+
+const ROW_TINT_KEY: &str = "row_tint";
+const LIST_LAYOUT_KEY: &str = "list_layout";
+const TABLE_COLUMNS_KEY: &str = "table_columns";
+const THEME_KEY: &str = "theme";
+const DETAIL_LAYOUT_KEY: &str = "detail_layout";
+const COLOR_BLIND_SAFE_KEY: &str = "color_blind_safe";
+
+/// Maps the list query of the GUI to the repository filter
+fn vulnerability_filter(query: VulnerabilityQuery) -> VulnerabilityFilter {
+	VulnerabilityFilter {
+		search: query.search,
+		status: match query.filter_status {
+			FilterStatus::All => None,
+			FilterStatus::Only(status) => Some(status),
+		},
+		severity: match query.filter_severity {
+			FilterSeverity::All => None,
+			FilterSeverity::High => Some("high".to_string()),
+			FilterSeverity::Medium => Some("medium".to_string()),
+			FilterSeverity::Low => Some("low".to_string()),
+		},
+		version_id: query.version_id,
+		quick: query.quick_filters,
+		weakness_class: match query.filter_weakness {
+			FilterWeakness::All => None,
+			FilterWeakness::Only(class) => Some(class),
+		},
+	}
+}
+
+/// Order of the list for the query's sort field
+fn list_order(query: &VulnerabilityQuery) -> SortOrder {
+	let column = match query.sort_field {
+		SortField::CVE => Some(SortColumn::CveId),
+		SortField::Severity => Some(SortColumn::Severity),
+		SortField::Date => Some(SortColumn::Published),
+		SortField::Cvss => Some(SortColumn::Risk),
+		SortField::Status => Some(SortColumn::Status),
+		SortField::None | SortField::RobotName | SortField::Manufacturer => None,
+	};
+	match column {
+		Some(column) => SortOrder { column, ascending: query.sort_ascending },
+		// A remediation queue is worked through highest risk first
+		None if query.version_id.is_some() => SortOrder { column: SortColumn::Risk, ascending: false },
+		None => SortOrder::default(),
+	}
+}
 
-/// Loads vulnerabilities from the database with filtering and sorting.
+/// Count of each quick filter chip within the rest of the query
+pub async fn load_quick_filter_counts(pool: Arc<SqlitePool>, query: VulnerabilityQuery) -> Result<Vec<(QuickFilter, i64)>> {
+	VulnerabilityRepository::new(pool)
+		.count_quick_filters(vulnerability_filter(query))
+		.await
+		.context("Failed to count quick filters")
+}
+
+/// Loads one page of vulnerabilities, continuing after `after` when the previous page
+/// is known and jumping by page number otherwise. Filtering, sorting and the page
+/// count are all done by the database.
+#[tracing::instrument(level = "debug", skip(pool))]
 pub async fn load_vulnerabilities(
 	pool: Arc<SqlitePool>,
-	search_query: String,
+	query: VulnerabilityQuery,
 	page: usize,
+	after: Option<PageCursor>,
 	page_size: usize,
-	sort_field: SortField,
-	sort_ascending: bool,
-	filter_severity: FilterSeverity,
-) -> Result<(Vec<Vulnerability>, usize)> {
+) -> Result<VulnerabilityPage> {
 	let repo = VulnerabilityRepository::new(pool.clone());
+	let order = list_order(&query);
+	let filter = vulnerability_filter(query);
 
-	let (mut vulnerabilities, total_pages) = repo
-		.search_vulnerabilities(&search_query, page, page_size)
+	if after.is_some() || page == 0 {
+		repo.search_vulnerabilities_after(filter, order, after, page_size).await
+	} else {
+		repo.search_vulnerabilities(filter, order, page, page_size).await
+	}
+		.context("Failed to search vulnerabilities")
+}
+
+/// Renders all vulnerabilities matching the query, not just the loaded page, as a
+/// shareable page and saves it to the downloads folder.
+pub async fn share_vulnerabilities(pool: Arc<SqlitePool>, query: VulnerabilityQuery) -> Result<PathBuf> {
+	let order = list_order(&query);
+	let filter = vulnerability_filter(query);
+	let summary = filter.summary();
+	let vulnerabilities = VulnerabilityRepository::new(pool)
+		.get_matching_vulnerabilities(filter, order)
 		.await
-		.context("Failed to search vulnerabilities")?;
+		.context("Failed to load vulnerabilities to share")?;
+	let file_name = format!("rvd-vulnerabilities-{}.html", Local::now().format("%Y-%m-%d-%H%M"));
+	save_to_downloads(file_name, share::vulnerability_list_html(&vulnerabilities, &summary)).await
+}
 
-	// Apply severity filtering
-	if !matches!(filter_severity, FilterSeverity::All) {
-		let severity = match filter_severity {
-			FilterSeverity::High => "high",
-			FilterSeverity::Medium => "medium",
-			FilterSeverity::Low => "low",
-			FilterSeverity::All => unreachable!(),
-		};
-		vulnerabilities.retain(|v| v.severity.to_lowercase() == severity);
+/// Looks up a deep-linked vulnerability by CVE ID.
+pub async fn load_vulnerability_by_cve(pool: Arc<SqlitePool>, cve_id: String) -> Result<(String, Option<Vulnerability>)> {
+	let vulnerability = VulnerabilityRepository::new(pool)
+		.get_vulnerability_by_cve(&cve_id)
+		.await
+		.with_context(|| format!("Failed to look up {}", cve_id))?;
+	Ok((cve_id, vulnerability))
+}
+
+/// Loads the software versions carrying the most fleet-wide risk.
+pub async fn load_risky_software(pool: Arc<SqlitePool>, limit: usize) -> Result<Vec<RiskySoftware>> {
+	SoftwareRepository::new(pool)
+		.get_top_risky_software(limit)
+		.await
+		.context("Failed to rank risky software")
+}
+
+/// Loads how much NVD enrichment is left and how the last batch went.
+pub async fn load_enrichment_progress(pool: Arc<SqlitePool>) -> Result<EnrichmentProgress> {
+	EnrichmentRepository::new(pool)
+		.get_progress()
+		.await
+		.context("Failed to load enrichment progress")
+}
+
+/// Loads the counts shown in the statistics panel, computed in the database over the
+/// vulnerabilities matching the list's filters.
+pub async fn load_statistics_report(pool: Arc<SqlitePool>, query: VulnerabilityQuery) -> Result<StatisticsReport> {
+	StatisticsRepository::new(pool)
+		.get_statistics_matching(vulnerability_filter(query))
+		.await
+		.context("Failed to load statistics")
+}
+
+/// Loads the exposure metrics recorded over the last year for the Trends tab.
+pub async fn load_trends(pool: Arc<SqlitePool>) -> Result<FleetTrends> {
+	MetricsRepository::new(pool)
+		.get_trends(TREND_DAYS)
+		.await
+		.context("Failed to load trends")
+}
+
+/// Saves the triage status, assignee and risk decision of a vulnerability.
+pub async fn update_triage(
+	pool: Arc<SqlitePool>,
+	vulnerability_id: i64,
+	status: TriageStatus,
+	assigned_to: String,
+	acceptance: RiskAcceptance,
+) -> Result<(i64, TriageStatus, Option<String>, Option<RiskAcceptance>)> {
+	let repo = VulnerabilityRepository::new(pool);
+	let assigned_to = Some(assigned_to.trim().to_string()).filter(|a| !a.is_empty());
+
+	repo.update_triage(vulnerability_id, status, assigned_to.clone(), acceptance.clone())
+		.await
+		.context("Failed to update triage status")?;
+
+	let risk_acceptance = status.is_risk_decision()
+		.then(|| RiskAcceptance { accepted_at: Some(Utc::now()), ..acceptance });
+	Ok((vulnerability_id, status, assigned_to, risk_acceptance))
+}
+
+/// Saves fields edited by hand, locked under the current user's name, and reloads the vulnerability
+pub async fn edit_fields(
+	pool: Arc<SqlitePool>,
+	vulnerability_id: i64,
+	edits: Vec<(LockedField, Option<String>)>,
+) -> Result<Vulnerability> {
+	let repo = VulnerabilityRepository::new(pool);
+	repo.edit_fields(vulnerability_id, edits, access::current_user())
+		.await
+		.context("Failed to save the edited fields")?;
+	repo.get_vulnerability_by_id(vulnerability_id).await
+}
+
+/// Lets automatic refreshes update a field again and reloads the vulnerability
+pub async fn unlock_field(pool: Arc<SqlitePool>, vulnerability_id: i64, field: LockedField) -> Result<Vulnerability> {
+	let repo = VulnerabilityRepository::new(pool);
+	repo.unlock_field(vulnerability_id, field)
+		.await
+		.with_context(|| format!("Failed to unlock the {}", field))?;
+	repo.get_vulnerability_by_id(vulnerability_id).await
+}
+
+/// Fills in the missing fields of a vulnerability from the NVD ahead of background jobs
+/// and reloads it, or returns None when the NVD had nothing to add
+pub async fn fetch_from_nvd(pool: Arc<SqlitePool>, vulnerability: Vulnerability) -> Result<Option<Vulnerability>> {
+	let Some(vulnerability_id) = vulnerability.vulnerability_id else {
+		return Ok(None);
+	};
+	// Asked for by hand, so the NVD's current record rather than a cached one
+	let updated = NvdApiClient::new(pool.clone())?
+		.bypassing_cache()
+		.fetch_now(&vulnerability)
+		.await
+		.with_context(|| format!("Failed to fetch {} from the NVD", vulnerability.cve_id))?;
+	if !updated {
+		return Ok(None);
 	}
+	VulnerabilityRepository::new(pool).get_vulnerability_by_id(vulnerability_id).await.map(Some)
+}
 
-	// Apply sorting
-	match sort_field {
-		SortField::CVE => {
-			vulnerabilities.sort_by(|a, b| {
-				if sort_ascending {
-					a.cve_id.cmp(&b.cve_id)
-				} else {
-					b.cve_id.cmp(&a.cve_id)
-				}
-			});
-		},
-		SortField::Severity => {
-			vulnerabilities.sort_by(|a, b| {
-				if sort_ascending {
-					a.severity.cmp(&b.severity)
-				} else {
-					b.severity.cmp(&a.severity)
-				}
-			});
-		},
-		SortField::Date => {
-			vulnerabilities.sort_by(|a, b| {
-				if sort_ascending {
-					a.published_date.cmp(&b.published_date)
-				} else {
-					b.published_date.cmp(&a.published_date)
-				}
-			});
-		},
-		SortField::None => (),
-		SortField::RobotName | SortField::Manufacturer => (),
+pub async fn load_data_sources(pool: Arc<SqlitePool>) -> Result<Vec<DataSource>> {
+	DataSourceRepository::new(pool).get_sources().await
+}
+
+/// Saves a source's settings and returns the updated sources
+pub async fn save_data_source(
+	pool: Arc<SqlitePool>,
+	source_id: i64,
+	enabled: bool,
+	interval_minutes: u32,
+	url: Option<String>,
+) -> Result<Vec<DataSource>> {
+	let repo = DataSourceRepository::new(pool);
+	repo.update_source(source_id, enabled, interval_minutes, url).await?;
+	repo.get_sources().await
+}
+
+/// Adds an NVD JSON feed synced daily and returns the updated sources
+pub async fn add_data_feed(pool: Arc<SqlitePool>, name: String, url: String) -> Result<Vec<DataSource>> {
+	let repo = DataSourceRepository::new(pool);
+	repo.add_feed(&name, &url, 24 * 60).await?;
+	repo.get_sources().await
+}
+
+pub async fn remove_data_source(pool: Arc<SqlitePool>, source_id: i64) -> Result<Vec<DataSource>> {
+	let repo = DataSourceRepository::new(pool);
+	repo.remove_source(source_id).await?;
+	repo.get_sources().await
+}
+
+/// Runs one source, or every enabled one, now, reporting progress like the scheduled
+/// runs, which then wait a full interval
+pub async fn sync_data_sources(pool: Arc<SqlitePool>, source_id: Option<i64>, progress: ProgressReporter) -> Result<String> {
+	let nvd_client = NvdApiClient::new(pool.clone())?;
+	let Some(source_id) = source_id else {
+		let ran = data_sources::sync_sources(pool, &nvd_client, None, progress).await?;
+		return Ok(format!("{} data sources synced", ran));
+	};
+	let source = DataSourceRepository::new(pool.clone())
+		.get_sources()
+		.await?
+		.into_iter()
+		.find(|source| source.source_id == source_id)
+		.context("Data source not found")?;
+	let message = data_sources::sync_source(pool, &nvd_client, &source, progress)
+		.await
+		.with_context(|| format!("Failed to sync {}", source.name))?;
+	Ok(format!("{}: {}", source.name, message))
+}
+
+/// What the syncs changed, newest first
+pub async fn load_sync_deltas(pool: Arc<SqlitePool>) -> Result<Vec<SyncDelta>> {
+	SyncDeltaRepository::new(pool).get_deltas(None).await
+}
+
+/// Saves the changelog of every recorded sync as CSV to the downloads folder
+pub async fn export_changelog(pool: Arc<SqlitePool>) -> Result<PathBuf> {
+	let deltas = SyncDeltaRepository::new(pool).get_deltas(None).await?;
+	let file_name = format!("rvd-sync-changelog-{}.csv", Local::now().format("%Y-%m-%d-%H%M"));
+	save_to_downloads(file_name, changelog::report_csv(&deltas)?).await
+}
+
+/// Opens another workspace and applies its role, time zone and network settings. It becomes the one
+/// opened on the next start.
+pub async fn open_workspace(name: String) -> Result<(String, Arc<SqlitePool>)> {
+	let workspace = name.clone();
+	let pool = task::spawn_blocking(move || connection::establish_pool(&workspace))
+		.await
+		.context("Failed to execute database operation")?
+		.with_context(|| format!("Failed to open workspace '{}'", name))?;
+	let pool = Arc::new(pool);
+
+	let settings = SettingsRepository::new(pool.clone());
+	access::set_current_role(settings.get_role().await?);
+	crate::utils::time::set_display_time_zone(settings.get_time_zone().await?);
+	crate::utils::http::set_network_settings(settings.get_network_settings().await?);
+	Workspaces::default().set_current(&name)?;
+	info!("Switched to workspace {}", name);
+	Ok((name, pool))
+}
+
+/// Storage sizes and the configured mode when the database is worth compacting on startup
+pub async fn check_compaction(pool: Arc<SqlitePool>) -> Result<Option<(CompactionMode, StorageStats)>> {
+	let mode = SettingsRepository::new(pool.clone()).get_compaction_mode().await?;
+	if mode == CompactionMode::Off {
+		return Ok(None);
 	}
 
-	Ok((vulnerabilities, total_pages))
+	let stats = task::spawn_blocking(move || {
+		let conn = pool.get().context("Failed to get database connection")?;
+		compaction::storage_stats(&conn)
+	})
+		.await
+		.context("Failed to execute database operation")??;
+	Ok(stats.needs_compaction().then_some((mode, stats)))
+}
+
+/// Reachability of the NVD as of the last enrichment run
+pub async fn load_nvd_health(pool: Arc<SqlitePool>) -> Result<NvdHealth> {
+	SettingsRepository::new(pool).get_nvd_health().await
+}
+
+/// Cards or table; missing or unknown values mean cards
+pub async fn load_list_layout(pool: Arc<SqlitePool>) -> Result<ListLayout> {
+	Ok(SettingsRepository::new(pool).get(LIST_LAYOUT_KEY).await?
+		.and_then(|value| ListLayout::from_setting(&value))
+		.unwrap_or_default())
+}
+
+pub async fn save_list_layout(pool: Arc<SqlitePool>, layout: ListLayout) -> Result<()> {
+	SettingsRepository::new(pool).set(LIST_LAYOUT_KEY, layout.as_setting()).await
+}
+
+pub async fn load_table_columns(pool: Arc<SqlitePool>) -> Result<TableColumns> {
+	Ok(SettingsRepository::new(pool).get(TABLE_COLUMNS_KEY).await?
+		.and_then(|value| TableColumns::from_setting(&value))
+		.unwrap_or_default())
+}
+
+pub async fn save_table_columns(pool: Arc<SqlitePool>, columns: TableColumns) -> Result<()> {
+	SettingsRepository::new(pool).set(TABLE_COLUMNS_KEY, &columns.as_setting()).await
+}
+
+/// How the rows of the vulnerability list are tinted; missing or unknown values mean off
+pub async fn load_row_tint(pool: Arc<SqlitePool>) -> Result<RowTint> {
+	Ok(SettingsRepository::new(pool).get(ROW_TINT_KEY).await?
+		.and_then(|value| RowTint::from_setting(&value))
+		.unwrap_or_default())
+}
+
+pub async fn save_row_tint(pool: Arc<SqlitePool>, tint: RowTint) -> Result<()> {
+	SettingsRepository::new(pool).set(ROW_TINT_KEY, tint.as_setting()).await
+}
+
+/// Light, dark or system theme; missing or unknown values mean system
+pub async fn load_theme(pool: Arc<SqlitePool>) -> Result<ThemeChoice> {
+	Ok(SettingsRepository::new(pool).get(THEME_KEY).await?
+		.and_then(|value| ThemeChoice::from_setting(&value))
+		.unwrap_or_default())
+}
+
+pub async fn save_theme(pool: Arc<SqlitePool>, choice: ThemeChoice) -> Result<()> {
+	SettingsRepository::new(pool).set(THEME_KEY, choice.as_setting()).await
+}
+
+pub async fn load_detail_layout(pool: Arc<SqlitePool>) -> Result<DetailLayout> {
+	Ok(SettingsRepository::new(pool).get(DETAIL_LAYOUT_KEY).await?
+		.and_then(|value| DetailLayout::from_setting(&value))
+		.unwrap_or_default())
+}
+
+pub async fn save_detail_layout(pool: Arc<SqlitePool>, layout: DetailLayout) -> Result<()> {
+	SettingsRepository::new(pool).set(DETAIL_LAYOUT_KEY, layout.as_setting()).await
+}
+
+pub async fn load_color_blind_safe(pool: Arc<SqlitePool>) -> Result<bool> {
+	Ok(SettingsRepository::new(pool).get(COLOR_BLIND_SAFE_KEY).await?.as_deref() == Some("true"))
+}
+
+pub async fn save_color_blind_safe(pool: Arc<SqlitePool>, enabled: bool) -> Result<()> {
+	SettingsRepository::new(pool).set(COLOR_BLIND_SAFE_KEY, if enabled { "true" } else { "false" }).await
+}
+
+/// Compacts the database, reporting progress like other long-running operations.
+pub async fn compact_database(pool: Arc<SqlitePool>, progress: ProgressReporter) -> Result<StorageStats> {
+	task::spawn_blocking(move || {
+		let conn = pool.get().context("Failed to get database connection")?;
+		compaction::compact(&conn, &progress)
+	})
+		.await
+		.context("Failed to execute database operation")?
+}
+
+/// Maintenance schedule and last report with the current storage sizes
+pub async fn load_maintenance_status(pool: Arc<SqlitePool>) -> Result<(MaintenancePolicy, StorageStats)> {
+	let policy = SettingsRepository::new(pool.clone()).get_maintenance_policy().await?;
+	let stats = task::spawn_blocking(move || {
+		let conn = pool.get().context("Failed to get database connection")?;
+		compaction::storage_stats(&conn)
+	})
+		.await
+		.context("Failed to execute database operation")??;
+	Ok((policy, stats))
+}
+
+pub async fn save_maintenance_schedule(pool: Arc<SqlitePool>, weekly: bool) -> Result<()> {
+	let settings = SettingsRepository::new(pool);
+	let mut policy = settings.get_maintenance_policy().await?;
+	policy.weekly = weekly;
+	settings.set_maintenance_policy(&policy).await
+}
+
+pub async fn load_update_check(pool: Arc<SqlitePool>) -> Result<UpdateCheckSettings> {
+	SettingsRepository::new(pool).get_update_check().await
+}
+
+pub async fn save_update_check(pool: Arc<SqlitePool>, settings: UpdateCheckSettings) -> Result<()> {
+	SettingsRepository::new(pool).set_update_check(&settings).await
 }
 
-/// Loads all robots from the database with their software versions.
+/// Renders the risk acceptance report of all accepted and suppressed vulnerabilities.
+pub async fn risk_acceptance_report(pool: Arc<SqlitePool>) -> Result<String> {
+	let decisions = VulnerabilityRepository::new(pool)
+		.get_risk_decisions()
+		.await
+		.context("Failed to load risk decisions")?;
+	Ok(risk_acceptance::report_html(&decisions, Local::now().date_naive(), Layout::Print))
+}
+
+/// Renders the compliance coverage report of the fleet.
+pub async fn compliance_report(pool: Arc<SqlitePool>) -> Result<String> {
+	let coverage = ComplianceRepository::new(pool)
+		.get_coverage()
+		.await
+		.context("Failed to load compliance coverage")?;
+	Ok(compliance::report_html(&coverage, Layout::Print))
+}
+
+pub async fn load_compliance_controls(pool: Arc<SqlitePool>) -> Result<Vec<ComplianceControl>> {
+	ComplianceRepository::new(pool).get_controls().await
+}
+
+pub async fn load_linked_controls(pool: Arc<SqlitePool>, vulnerability_id: i64) -> Result<Vec<ComplianceControl>> {
+	ComplianceRepository::new(pool).get_linked_controls(vulnerability_id).await
+}
+
+pub async fn save_control_link(pool: Arc<SqlitePool>, vulnerability_id: i64, control_id: i64, linked: bool) -> Result<()> {
+	ComplianceRepository::new(pool).set_linked(vulnerability_id, control_id, linked).await
+}
+
+/// Loads the notes attached to a vulnerability or robot.
+pub async fn load_notes(pool: Arc<SqlitePool>, entity_type: NoteEntity, entity_id: i64) -> Result<Vec<Note>> {
+	NoteRepository::new(pool)
+		.get_notes(entity_type, entity_id)
+		.await
+		.context("Failed to load notes")
+}
+
+/// Whether remediation tickets can be raised
+pub async fn load_ticketing_enabled(pool: Arc<SqlitePool>) -> Result<bool> {
+	ticketing::is_enabled(pool).await.context("Failed to load ticketing settings")
+}
+
+pub async fn load_ticket(pool: Arc<SqlitePool>, vulnerability_id: i64) -> Result<Option<Ticket>> {
+	TicketRepository::new(pool)
+		.get_ticket(vulnerability_id)
+		.await
+		.context("Failed to load the remediation ticket")
+}
+
+/// Raises a remediation ticket in the configured ticketing system
+pub async fn create_ticket(pool: Arc<SqlitePool>, vulnerability_id: i64) -> Result<Ticket> {
+	ticketing::create_ticket(pool, vulnerability_id)
+		.await
+		.context("Failed to raise a remediation ticket")
+}
+
+pub async fn load_references(pool: Arc<SqlitePool>, vulnerability_id: i64) -> Result<Vec<Reference>> {
+	ReferenceRepository::new(pool)
+		.get_references(vulnerability_id)
+		.await
+		.context("Failed to load references")
+}
+
+/// Loads the vulnerabilities sharing an affected product or CWE with the given one
+pub async fn load_related(pool: Arc<SqlitePool>, vulnerability_id: i64) -> Result<Vec<RelatedVulnerability>> {
+	VulnerabilityRepository::new(pool)
+		.get_related_vulnerabilities(vulnerability_id, super::constants::RELATED_VULNERABILITY_LIMIT)
+		.await
+		.context("Failed to load related vulnerabilities")
+}
+
+pub async fn load_graph(pool: Arc<SqlitePool>, center: GraphCenter) -> Result<RelationshipGraph> {
+	GraphRepository::new(pool)
+		.get_graph(center)
+		.await
+		.context("Failed to load relationship graph")
+}
+
+/// Adds a new note, or replaces the text of `note_id` when editing.
+pub async fn save_note(
+	pool: Arc<SqlitePool>,
+	entity_type: NoteEntity,
+	entity_id: i64,
+	note_id: Option<i64>,
+	body: String,
+) -> Result<()> {
+	if body.trim().is_empty() {
+		bail!("Note text is empty");
+	}
+
+	let repo = NoteRepository::new(pool);
+	match note_id {
+		Some(id) => repo.update_note(id, body).await,
+		None => repo.add_note(Note::new(entity_type, entity_id, body)).await.map(|_| ()),
+	}
+}
+
+/// Deletes a note.
+pub async fn delete_note(pool: Arc<SqlitePool>, note_id: i64) -> Result<()> {
+	NoteRepository::new(pool).delete_note(note_id).await
+}
+
+/// Loads all robots from the database, rescoring their risk first.
+#[tracing::instrument(level = "debug", skip(pool))]
 pub async fn load_robots(pool: Arc<SqlitePool>) -> Result<Vec<Robot>> {
 	let pool = pool.clone();
 	task::spawn_blocking(move || {
 		let conn = pool.get().context("Failed to get database connection")?;
+		// Viewers see the scores as of the last rescoring by an admin
+		if access::current_role().can_edit() {
+			refresh_risk_scores(&conn).context("Failed to refresh risk scores")?;
+		}
 
 		let mut stmt = conn
 			.prepare(
-				"SELECT r.robot_id, r.name, r.specifications, r.manufacturer
-				 FROM robots r"
+				"SELECT r.robot_id, r.name, r.specifications, r.manufacturer, r.operational_note, r.risk_score, r.criticality, r.model,
+				        r.firmware_version, r.os, r.ros_distro, r.inventory_refreshed_at, r.inventory_source
+				 FROM robots r
+				 WHERE r.deleted_at IS NULL"
 			)
 			.context("Failed to prepare statement")?;
 
@@ -93,6 +586,15 @@ pub async fn load_robots(pool: Arc<SqlitePool>) -> Result<Vec<Robot>> {
 					name: row.get(1)?,
 					specifications: row.get(2)?,
 					manufacturer: row.get(3)?,
+					model: row.get(7)?,
+					firmware_version: row.get(8)?,
+					os: row.get(9)?,
+					ros_distro: row.get(10)?,
+					operational_note: row.get(4)?,
+					risk_score: row.get(5)?,
+					criticality: Criticality::parse(&row.get::<_, String>(6)?),
+					inventory_refreshed_at: row.get::<_, Option<String>>(11)?.as_deref().and_then(time::parse_utc),
+					inventory_source: row.get::<_, Option<String>>(12)?.as_deref().and_then(InventorySource::parse),
 				})
 			})
 			.context("Failed to execute query")?;
@@ -108,88 +610,295 @@ pub async fn load_robots(pool: Arc<SqlitePool>) -> Result<Vec<Robot>> {
 		.context("Task join error")?
 }
 
-/// Adds a new robot to the database.
+/// Manufacturer and vendor names in use, most used spelling first where they differ
+/// only in case, for completing the robot form
+pub async fn load_vendor_names(pool: Arc<SqlitePool>) -> Result<Vec<String>> {
+	task::spawn_blocking(move || {
+		let conn = pool.get().context("Failed to get database connection")?;
+		let mut stmt = conn.prepare(
+			"SELECT trim(name) AS name FROM (
+				SELECT manufacturer AS name FROM robots WHERE deleted_at IS NULL
+				UNION ALL
+				SELECT vendor FROM software_products
+			 )
+			 WHERE trim(COALESCE(name, '')) != ''
+			 GROUP BY trim(name)
+			 ORDER BY COUNT(*) DESC, name",
+		)?;
+		let names = stmt
+			.query_map([], |row| row.get::<_, String>(0))?
+			.collect::<rusqlite::Result<Vec<_>>>()
+			.context("Failed to load vendor names")?;
+
+		let mut seen = std::collections::HashSet::new();
+		Ok(names.into_iter().filter(|name| seen.insert(name.to_lowercase())).collect())
+	})
+		.await
+		.context("Task join error")?
+}
+
+/// Adds a new robot with its software to the database.
 pub async fn add_robot(pool: Arc<SqlitePool>, form: RobotForm) -> Result<Robot> {
+	access::require_write_access()?;
 	let pool = pool.clone();
 	let form_clone = form.clone();
+	let software = form.software_refs().map_err(anyhow::Error::msg)?;
 
 	task::spawn_blocking(move || {
-		let conn = pool.get().context("Failed to get database connection")?;
-
-		conn.execute(
-			"INSERT INTO robots (name, manufacturer, specifications) VALUES (?1, ?2, ?3)",
-			params![
-				form_clone.name,
-				form_clone.manufacturer,
-				form_clone.specifications,
-			],
-		).context("Failed to insert robot")?;
-
-		let id = conn.last_insert_rowid();
+		let operational_note = form_clone.operational_note_value();
+		let model = form_clone.model_value();
+		let (firmware_version, os, ros_distro) = form_clone.platform_values();
+		let id = connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			tx.execute(
+				"INSERT INTO robots (name, manufacturer, specifications, operational_note, criticality, model,
+				 firmware_version, os, ros_distro)
+				 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+				params![
+					form_clone.name,
+					form_clone.manufacturer,
+					form_clone.specifications,
+					operational_note,
+					form_clone.criticality.as_str(),
+					model,
+					firmware_version,
+					os,
+					ros_distro,
+				],
+			).context("Failed to insert robot")?;
+
+			let id = tx.last_insert_rowid();
+			set_robot_software(&tx, id, &software).context("Failed to save robot software")?;
+			mark_inventory_refreshed(&tx, id, InventorySource::Manual)?;
+			audit_repo::record_change(&tx, AuditEntity::Robot, id, AuditAction::Insert, None)?;
+			tx.commit()?;
+			Ok(id)
+		})?;
 
 		Ok(Robot {
 			robot_id: Some(id as i32),
 			name: form_clone.name,
 			manufacturer: Some(form_clone.manufacturer),
+			model,
+			firmware_version,
+			os,
+			ros_distro,
 			specifications: Some(form_clone.specifications),
+			operational_note,
+			risk_score: None,
+			criticality: form_clone.criticality,
+			inventory_refreshed_at: Some(Utc::now()),
+			inventory_source: Some(InventorySource::Manual),
 		})
 	})
 		.await
 		.context("Task join error")?
 }
 
-/// Updates an existing robot in the database.
+/// Updates an existing robot and replaces its software in the database.
 pub async fn update_robot(pool: Arc<SqlitePool>, id: i32, form: RobotForm) -> Result<Robot> {
+	access::require_write_access()?;
 	let pool = pool.clone();
 	let form_clone = form.clone();
+	let software = form.software_refs().map_err(anyhow::Error::msg)?;
 
 	task::spawn_blocking(move || {
-		let conn = pool.get().context("Failed to get database connection")?;
-
-		let result = conn.execute(
-			"UPDATE robots SET name = ?1, manufacturer = ?2, specifications = ?3
-			 WHERE robot_id = ?4",
-			params![
-				form_clone.name,
-				form_clone.manufacturer,
-				form_clone.specifications,
-				id
-			],
-		).context("Failed to update robot")?;
-
-		if result != 1 {
-			bail!("Robot not found");
-		}
+		let operational_note = form_clone.operational_note_value();
+		let model = form_clone.model_value();
+		let (firmware_version, os, ros_distro) = form_clone.platform_values();
+		let (inventory_refreshed_at, inventory_source) = connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let before = audit_repo::snapshot(&tx, AuditEntity::Robot, id.into())?;
+			let result = tx.execute(
+				"UPDATE robots SET name = ?1, manufacturer = ?2, specifications = ?3, operational_note = ?4, criticality = ?5,
+				 model = ?6, firmware_version = ?7, os = ?8, ros_distro = ?9
+				 WHERE robot_id = ?10",
+				params![
+					form_clone.name,
+					form_clone.manufacturer,
+					form_clone.specifications,
+					operational_note,
+					form_clone.criticality.as_str(),
+					model,
+					firmware_version,
+					os,
+					ros_distro,
+					id
+				],
+			).context("Failed to update robot")?;
+
+			if result != 1 {
+				bail!("Robot not found");
+			}
+			// Saving the form unchanged does not vouch for the software being current
+			if set_robot_software(&tx, id.into(), &software).context("Failed to save robot software")? {
+				mark_inventory_refreshed(&tx, id.into(), InventorySource::Manual)?;
+			}
+			audit_repo::record_change(&tx, AuditEntity::Robot, id.into(), AuditAction::Update, before)?;
+			let inventory: (Option<String>, Option<String>) = tx.query_row(
+				"SELECT inventory_refreshed_at, inventory_source FROM robots WHERE robot_id = ?1",
+				[id],
+				|row| Ok((row.get(0)?, row.get(1)?)),
+			)?;
+			tx.commit()?;
+			Ok(inventory)
+		})?;
 
 		Ok(Robot {
 			robot_id: Some(id),
 			name: form_clone.name,
 			manufacturer: Some(form_clone.manufacturer),
+			model,
+			firmware_version,
+			os,
+			ros_distro,
 			specifications: Some(form_clone.specifications),
+			operational_note,
+			risk_score: None,
+			criticality: form_clone.criticality,
+			inventory_refreshed_at: inventory_refreshed_at.as_deref().and_then(time::parse_utc),
+			inventory_source: inventory_source.as_deref().and_then(InventorySource::parse),
 		})
 	})
 		.await
 		.context("Task join error")?
 }
 
-/// Deletes a robot from the database.
+/// Imports a robot inventory file, reporting the rows it skipped.
+pub async fn import_robot_inventory(pool: Arc<SqlitePool>, path: String) -> Result<RobotImportSummary> {
+	import_robots(PathBuf::from(path.trim()), pool).await
+}
+
+/// Column headers of a vulnerability CSV or .xlsx file, to map before importing it
+pub async fn read_import_headers(path: String) -> Result<Vec<String>> {
+	task::spawn_blocking(move || csv_importer::read_headers(Path::new(path.trim())))
+		.await
+		.context("Failed to execute database operation")?
+}
+
+/// Imports a vulnerability CSV or, by its extension, the first sheet of an .xlsx
+/// workbook with the chosen column mapping
+pub async fn import_vulnerability_file(
+	pool: Arc<SqlitePool>,
+	path: String,
+	mapping: CsvMapping,
+	progress: ProgressReporter,
+) -> Result<ImportReport> {
+	let path = path.trim().to_string();
+	if Path::new(&path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("xlsx")) {
+		csv_importer::import_vulnerabilities_from_xlsx(path, None, pool, mapping, progress).await
+	} else {
+		csv_importer::import_vulnerabilities_from_csv(path, pool, mapping, progress).await
+	}
+}
+
+pub async fn load_csv_presets(pool: Arc<SqlitePool>) -> Result<Vec<(String, CsvMapping)>> {
+	SettingsRepository::new(pool).list_csv_presets().await
+}
+
+/// Saves the mapping as a named preset, returning every preset
+pub async fn save_csv_preset(pool: Arc<SqlitePool>, name: String, mapping: CsvMapping) -> Result<Vec<(String, CsvMapping)>> {
+	let repo = SettingsRepository::new(pool);
+	repo.save_csv_preset(name.trim(), &mapping).await?;
+	repo.list_csv_presets().await
+}
+
+/// Software entries of a robot as the robot form lists them
+pub async fn load_robot_software(pool: Arc<SqlitePool>, robot_id: i32) -> Result<Vec<String>> {
+	let software = SoftwareRepository::new(pool).get_robot_software(robot_id.into()).await?;
+	Ok(software.iter().map(ToString::to_string).collect())
+}
+
+/// Vulnerabilities affecting a robot's software, highest CVSS first
+pub async fn load_robot_vulnerabilities(pool: Arc<SqlitePool>, robot_id: i32) -> Result<Vec<RobotExposure>> {
+	RobotRepository::new(pool).get_robot_vulnerabilities(robot_id.into()).await
+}
+
+pub async fn load_checklist(pool: Arc<SqlitePool>, robot_id: i32) -> Result<Vec<ChecklistEntry>> {
+	CommissioningRepository::new(pool).get_checklist(robot_id.into()).await
+}
+
+pub async fn save_checklist_item(pool: Arc<SqlitePool>, robot_id: i32, item_id: i64, done: bool) -> Result<()> {
+	CommissioningRepository::new(pool).set_done(robot_id.into(), item_id, done).await
+}
+
+pub async fn load_inventory_max_age(pool: Arc<SqlitePool>) -> Result<u32> {
+	SettingsRepository::new(pool).get_inventory_max_age_days().await
+}
+
+pub async fn confirm_inventory(pool: Arc<SqlitePool>, robot_id: i32) -> Result<()> {
+	RobotRepository::new(pool).confirm_inventory(robot_id.into()).await
+}
+
+/// Software versions with their metadata for the Software tab
+pub async fn load_version_metadata(pool: Arc<SqlitePool>) -> Result<Vec<VersionMetadata>> {
+	SoftwareRepository::new(pool).get_version_metadata().await
+}
+
+pub async fn save_version_metadata(pool: Arc<SqlitePool>, version_ids: Vec<i64>, change: VersionMetadataChange) -> Result<usize> {
+	SoftwareRepository::new(pool).update_version_metadata(version_ids, change).await
+}
+
+/// Moves a robot to Recently deleted
 pub async fn delete_robot(pool: Arc<SqlitePool>, id: i32) -> Result<()> {
-	let pool = pool.clone();
-	task::spawn_blocking(move || {
-		let conn = pool.get().context("Failed to get database connection")?;
+	TrashRepository::new(pool).delete(DeletedKind::Robot, id.into()).await
+}
 
-		let result = conn
-			.execute("DELETE FROM robots WHERE robot_id = ?1", params![id])
-			.context("Failed to delete robot")?;
+/// Moves a vulnerability to Recently deleted
+pub async fn delete_vulnerability(pool: Arc<SqlitePool>, id: i64) -> Result<()> {
+	TrashRepository::new(pool).delete(DeletedKind::Vulnerability, id).await
+}
 
-		if result != 1 {
-			bail!("Robot not found");
-		}
+pub async fn load_deleted_items(pool: Arc<SqlitePool>) -> Result<Vec<DeletedItem>> {
+	TrashRepository::new(pool).get_deleted().await
+}
 
-		Ok(())
-	})
+pub async fn restore_deleted(pool: Arc<SqlitePool>, kind: DeletedKind, id: i64) -> Result<()> {
+	TrashRepository::new(pool).restore(kind, id).await
+}
+
+pub async fn purge_deleted(pool: Arc<SqlitePool>, kind: DeletedKind, id: i64) -> Result<()> {
+	TrashRepository::new(pool).purge(kind, id).await
+}
+
+pub async fn load_import_runs(pool: Arc<SqlitePool>) -> Result<Vec<ImportRun>> {
+	ImportRunRepository::new(pool).get_runs().await
+}
+
+pub async fn load_skipped_rows(pool: Arc<SqlitePool>, run_id: i64) -> Result<Vec<SkippedRow>> {
+	ImportRunRepository::new(pool).get_skipped_rows(run_id).await
+}
+
+pub async fn revert_import(pool: Arc<SqlitePool>, run_id: i64) -> Result<RevertSummary> {
+	ImportRunRepository::new(pool).revert(run_id).await
+}
+
+pub async fn revert_audit_batch(pool: Arc<SqlitePool>, batch_id: i64) -> Result<usize> {
+	AuditRepository::new(pool).revert_batch(batch_id).await
+}
+
+fn audit_filter(entity: FilterAuditEntity, search: String) -> AuditFilter {
+	let entity = match entity {
+		FilterAuditEntity::All => None,
+		FilterAuditEntity::Only(entity) => Some(entity),
+	};
+	AuditFilter { entity, search }
+}
+
+pub async fn load_audit_log(pool: Arc<SqlitePool>, entity: FilterAuditEntity, search: String) -> Result<Vec<AuditEntry>> {
+	AuditRepository::new(pool)
+		.get_entries(audit_filter(entity, search), Some(super::constants::AUDIT_LOG_LIMIT))
 		.await
-		.context("Task join error")?
+}
+
+/// Saves every matching audit entry, not only those shown, as CSV to the downloads folder
+pub async fn export_audit_log(pool: Arc<SqlitePool>, entity: FilterAuditEntity, search: String) -> Result<PathBuf> {
+	let entries = AuditRepository::new(pool)
+		.get_entries(audit_filter(entity, search), None)
+		.await
+		.context("Failed to load the audit log to export")?;
+	let file_name = format!("rvd-audit-log-{}.csv", Local::now().format("%Y-%m-%d-%H%M"));
+	save_to_downloads(file_name, audit::report_csv(&entries)?).await
 }
 
 #[cfg(test)]
@@ -209,7 +918,8 @@ mod tests {
 				robot_id INTEGER PRIMARY KEY,
 				name TEXT NOT NULL,
 				manufacturer TEXT,
-				specifications TEXT
+				specifications TEXT,
+				operational_note TEXT
 			);"
 		)?;
 		Ok(pool)
@@ -224,22 +934,38 @@ mod tests {
 			name: "TestBot".to_string(),
 			manufacturer: "TestMfg".to_string(),
 			specifications: "Test Specs".to_string(),
-			software_versions: Vec::new(),
+			operational_note: "air-gapped".to_string(),
+			model: "KR 6".to_string(),
+			firmware_version: "8.3.2".to_string(),
+			os: String::new(),
+			ros_distro: String::new(),
+			criticality: Criticality::High,
+			software_versions: vec!["OSRF/ros-core 1.0".to_string(), "firmware 2.0".to_string()],
 		};
 
 		let robot = add_robot(pool.clone(), form.clone()).await?;
 		assert_eq!(robot.name, "TestBot");
+		let software = load_robot_software(pool.clone(), robot.robot_id.unwrap()).await?;
+		assert_eq!(software, ["OSRF/ros-core 1.0", "TestMfg/firmware 2.0"]);
+		assert_eq!(load_vendor_names(pool.clone()).await?, ["TestMfg", "OSRF"]);
 
 		// Test Read
 		let robots = load_robots(pool.clone()).await?;
 		assert_eq!(robots.len(), 1);
 		assert_eq!(robots[0].name, "TestBot");
+		assert_eq!(robots[0].operational_note.as_deref(), Some("air-gapped"));
+		assert_eq!(robots[0].criticality, Criticality::High);
+		assert_eq!(robots[0].model.as_deref(), Some("KR 6"));
+		assert_eq!((robots[0].firmware_version.as_deref(), robots[0].os.as_deref()), (Some("8.3.2"), None));
 
 		// Test Update
 		let mut updated_form = form.clone();
 		updated_form.name = "UpdatedBot".to_string();
+		updated_form.software_versions = vec!["TestMfg/firmware 2.1".to_string()];
 		let updated = update_robot(pool.clone(), robot.robot_id.unwrap(), updated_form).await?;
 		assert_eq!(updated.name, "UpdatedBot");
+		let software = load_robot_software(pool.clone(), robot.robot_id.unwrap()).await?;
+		assert_eq!(software, ["TestMfg/firmware 2.1"]);
 
 		// Test Delete
 		delete_robot(pool.clone(), robot.robot_id.unwrap()).await?;
@@ -1,26 +1,171 @@
 use chrono::NaiveDate;
-use iced::Color;
+use iced::{Color, Theme};
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::models::risk::RiskBand;
+use crate::models::vulnerability::TriageStatus;
+use crate::utils::ghsa::GHSA_SOURCE;
+use crate::utils::rvd_import::RVD_SOURCE;
 
-pub fn format_severity(severity: &str) -> Color {
+/// Hues of the severity, risk and triage colors; they are lightened on dark themes and
+/// blended into the theme's background for row tints
+struct Hues {
+	critical: Color,
+	high: Color,
+	medium: Color,
+	low: Color,
+	in_progress: Color,
+	accepted: Color,
+}
+
+const STANDARD_HUES: Hues = Hues {
+	critical: Color::from_rgb(0.75, 0.1, 0.1),
+	high: Color::from_rgb(0.9, 0.2, 0.2),
+	medium: Color::from_rgb(0.95, 0.5, 0.2),
+	low: Color::from_rgb(0.2, 0.7, 0.2),
+	in_progress: Color::from_rgb(1.0, 0.8, 0.1),
+	accepted: Color::from_rgb(0.3, 0.4, 1.0),
+};
+
+/// Okabe-Ito colors, which stay distinct with the common color vision deficiencies
+const COLOR_BLIND_SAFE_HUES: Hues = Hues {
+	critical: Color::from_rgb(0.6, 0.2, 0.0),
+	high: Color::from_rgb(0.835, 0.369, 0.0),
+	medium: Color::from_rgb(0.902, 0.624, 0.0),
+	low: Color::from_rgb(0.0, 0.447, 0.698),
+	in_progress: Color::from_rgb(0.941, 0.894, 0.259),
+	accepted: Color::from_rgb(0.8, 0.475, 0.655),
+};
+
+static COLOR_BLIND_SAFE: AtomicBool = AtomicBool::new(false);
+
+/// Switches severities to the color-blind safe palette, marked with shapes as well
+pub fn set_color_blind_safe(enabled: bool) {
+	COLOR_BLIND_SAFE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn color_blind_safe() -> bool {
+	COLOR_BLIND_SAFE.load(Ordering::Relaxed)
+}
+
+fn hues() -> &'static Hues {
+	if color_blind_safe() {
+		&COLOR_BLIND_SAFE_HUES
+	} else {
+		&STANDARD_HUES
+	}
+}
+
+/// Blends `from` towards `to`; an amount of 0 gives `from`, 1 gives `to`
+fn mix(from: Color, to: Color, amount: f32) -> Color {
+	Color::from_rgb(
+		from.r + (to.r - from.r) * amount,
+		from.g + (to.g - from.g) * amount,
+		from.b + (to.b - from.b) * amount,
+	)
+}
+
+/// `color` as text color, lightened towards the text color on dark themes to stay readable
+fn readable(color: Color, theme: &Theme) -> Color {
+	if theme.extended_palette().is_dark {
+		mix(color, theme.palette().text, 0.35)
+	} else {
+		color
+	}
+}
+
+/// Background tinted with `color`, light or dark with the theme
+fn tint(color: Color, amount: f32, theme: &Theme) -> Color {
+	mix(theme.palette().background, color, amount)
+}
+
+/// Text of secondary details such as dates and counts
+pub fn format_muted(theme: &Theme) -> Color {
+	mix(theme.palette().text, theme.palette().background, 0.45)
+}
+
+/// Text of errors and warnings shown inline, e.g. under a form field
+pub fn format_error(theme: &Theme) -> Color {
+	readable(theme.palette().danger, theme)
+}
+
+/// Text of confirmations, e.g. a finished import
+pub fn format_success(theme: &Theme) -> Color {
+	readable(theme.palette().success, theme)
+}
+
+/// Text of notices that need attention but are not errors
+pub fn format_warning(theme: &Theme) -> Color {
+	readable(hues().medium, theme)
+}
+
+/// Text of clickable links, e.g. reference URLs
+pub fn format_link(theme: &Theme) -> Color {
+	readable(theme.palette().primary, theme)
+}
+
+pub fn format_severity(severity: &str, theme: &Theme) -> Color {
+	let hues = hues();
 	match severity.to_lowercase().as_str() {
-		"high" => Color::from_rgb(0.9, 0.2, 0.2),    // Brighter red
-		"medium" => Color::from_rgb(0.95, 0.5, 0.2), // Bright orange
-		"low" => Color::from_rgb(0.2, 0.7, 0.2),     // Bright green
-		_ => Color::from_rgb(0.6, 0.6, 0.6),         // Lighter gray
+		"critical" => readable(hues.critical, theme),
+		"high" => readable(hues.high, theme),
+		"medium" => readable(hues.medium, theme),
+		"low" => readable(hues.low, theme),
+		_ => format_muted(theme),
 	}
 }
 
-pub fn format_severity_background(severity: &str) -> Color {
+/// Shape telling severities apart without their color
+pub fn severity_mark(severity: &str) -> &'static str {
 	match severity.to_lowercase().as_str() {
-		"high" => Color::from_rgb(1.0, 0.9, 0.9),    // Light red background
-		"medium" => Color::from_rgb(1.0, 0.95, 0.9), // Light orange background
-		"low" => Color::from_rgb(0.9, 1.0, 0.9),     // Light green background
-		_ => Color::from_rgb(0.95, 0.95, 0.95),      // Light gray background
+		"critical" => "◆",
+		"high" => "▲",
+		"medium" => "■",
+		"low" => "●",
+		_ => "○",
 	}
 }
 
+/// Severity as displayed, preceded by its shape on the color-blind safe palette
+pub fn format_severity_label(severity: &str) -> String {
+	if color_blind_safe() {
+		format!("{} {}", severity_mark(severity), severity)
+	} else {
+		severity.to_string()
+	}
+}
 
+pub fn format_risk(band: RiskBand, theme: &Theme) -> Color {
+	let hues = hues();
+	match band {
+		RiskBand::Critical => readable(hues.critical, theme),
+		RiskBand::High => readable(hues.high, theme),
+		RiskBand::Medium => readable(hues.medium, theme),
+		RiskBand::Low => readable(hues.low, theme),
+		RiskBand::None => format_muted(theme),
+	}
+}
+
+pub fn format_severity_background(severity: &str, theme: &Theme) -> Color {
+	let hues = hues();
+	match severity.to_lowercase().as_str() {
+		"critical" => tint(hues.high, 0.22, theme),
+		"high" => tint(hues.high, 0.14, theme),
+		"medium" => tint(hues.medium, 0.14, theme),
+		"low" => tint(hues.low, 0.14, theme),
+		_ => tint(theme.palette().text, 0.06, theme),
+	}
+}
 
+pub fn format_status_background(status: TriageStatus, theme: &Theme) -> Color {
+	let hues = hues();
+	match status {
+		TriageStatus::Open => tint(hues.medium, 0.18, theme),
+		TriageStatus::InProgress => tint(hues.in_progress, 0.18, theme),
+		TriageStatus::Mitigated => tint(hues.low, 0.14, theme),
+		TriageStatus::AcceptedRisk => tint(hues.accepted, 0.12, theme),
+		TriageStatus::FalsePositive => tint(theme.palette().text, 0.06, theme),
+	}
+}
 
 pub fn format_date(date: Option<NaiveDate>) -> String {
 	date.map_or_else(
@@ -29,6 +174,23 @@ pub fn format_date(date: Option<NaiveDate>) -> String {
 	)
 }
 
+/// Short labels of the feeds a merged entry combines, e.g. "NVD + GHSA"; empty for
+/// entries from a single feed
+pub fn format_sources(sources: &[&str]) -> String {
+	if sources.len() < 2 {
+		return String::new();
+	}
+	sources
+		.iter()
+		.map(|source| match *source {
+			GHSA_SOURCE => "GHSA",
+			RVD_SOURCE => "RVD",
+			other => other,
+		})
+		.collect::<Vec<_>>()
+		.join(" + ")
+}
+
 pub fn format_loading_message(progress: f32, operation_type: &str) -> String {
 	format!("{} ({:.0}%)", operation_type, progress)
 }
\ No newline at end of file
@@ -0,0 +1,207 @@
+use super::formatters::{color_blind_safe, format_muted, format_severity, severity_mark};
+use super::state::AppState;
+use super::types::Message;
+use crate::models::graph::{NodeKind, RelationshipGraph};
+use iced::{
+	alignment::{Horizontal, Vertical},
+	mouse, theme,
+	widget::{
+		button, canvas::{self, event, Canvas, Frame, Geometry, LineDash, Path, Stroke},
+		column, container, row, Space, Text,
+	},
+	Alignment, Color, Element, Length, Point, Rectangle, Renderer, Theme,
+};
+use std::f32::consts::TAU;
+
+const NODE_RADIUS: f32 = 14.0;
+const CENTER_RADIUS: f32 = 20.0;
+const ROBOT_COLOR: Color = Color::from_rgb(0.2, 0.45, 0.85);
+const SOFTWARE_COLOR: Color = Color::from_rgb(0.55, 0.35, 0.75);
+const EDGE_COLOR: Color = Color::from_rgb(0.6, 0.6, 0.6);
+
+pub trait GraphViewRenderer {
+	fn relationship_graph<'a>(&'a self, graph: &'a RelationshipGraph) -> Element<'a, Message>;
+}
+
+impl GraphViewRenderer for AppState {
+	fn relationship_graph<'a>(&'a self, graph: &'a RelationshipGraph) -> Element<'a, Message> {
+		let title = graph.nodes
+			.first()
+			.map(|center| format!("Relationships of {}", center.label))
+			.unwrap_or_default();
+
+		let legend = row![
+			legend_entry("Robot", ROBOT_COLOR),
+			legend_entry("Software version", SOFTWARE_COLOR),
+			legend_entry("CVE (by severity)", format_severity("high", &self.theme())),
+			Text::new("Dashed: same product. Click a robot or CVE to center on it.")
+				.size(12)
+				.style(theme::Text::Color(format_muted(&self.theme()))),
+		]
+			.spacing(20)
+			.align_items(Alignment::Center);
+
+		let omitted: Element<Message> = if graph.omitted > 0 {
+			Text::new(format!("{} more connected records not shown", graph.omitted))
+				.size(12)
+				.into()
+		} else {
+			Space::with_height(Length::Shrink).into()
+		};
+
+		container(
+			column![
+				row![
+					Text::new(title).size(28).width(Length::Fill),
+					button(Text::new("Close").size(16))
+						.on_press(Message::GraphClosed)
+						.style(theme::Button::Destructive)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				legend,
+				omitted,
+				Canvas::new(GraphCanvas { graph })
+					.width(Length::Fill)
+					.height(Length::Fill),
+			]
+				.spacing(10),
+		)
+			.padding(10)
+			.style(theme::Container::Box)
+			.into()
+	}
+}
+
+fn legend_entry(label: &str, color: Color) -> Element<'_, Message> {
+	row![
+		Text::new("●").size(16).style(theme::Text::Color(color)),
+		Text::new(label).size(12),
+	]
+		.spacing(4)
+		.align_items(Alignment::Center)
+		.into()
+}
+
+/// Draws the graph in rings around its center, one ring per hop
+struct GraphCanvas<'a> {
+	graph: &'a RelationshipGraph,
+}
+
+impl GraphCanvas<'_> {
+	fn positions(&self, bounds: Rectangle) -> Vec<Point> {
+		let depths = self.graph.depths();
+		let max_depth = depths.iter().copied().max().unwrap_or(0).max(1);
+		let center = Point::new(bounds.width / 2.0, bounds.height / 2.0);
+		let ring_spacing = ((bounds.width.min(bounds.height) / 2.0 - 40.0) / max_depth as f32).max(NODE_RADIUS * 3.0);
+
+		let mut positions = vec![center; self.graph.nodes.len()];
+		for depth in 1..=max_depth {
+			let ring: Vec<usize> = (0..depths.len()).filter(|&idx| depths[idx] == depth).collect();
+			let radius = ring_spacing * depth as f32;
+			// Offset alternate rings so labels of neighbouring rings do not line up
+			let offset = depth as f32 * 0.35;
+			for (i, &idx) in ring.iter().enumerate() {
+				let angle = offset + TAU * i as f32 / ring.len() as f32;
+				positions[idx] = Point::new(center.x + radius * angle.cos(), center.y + radius * angle.sin());
+			}
+		}
+		positions
+	}
+
+	/// Index of the node under the cursor
+	fn node_at(&self, bounds: Rectangle, cursor: mouse::Cursor) -> Option<usize> {
+		let position = cursor.position_in(bounds)?;
+		self.positions(bounds)
+			.iter()
+			.position(|node| node.distance(position) <= CENTER_RADIUS)
+	}
+}
+
+impl canvas::Program<Message> for GraphCanvas<'_> {
+	type State = ();
+
+	fn update(
+		&self,
+		_state: &mut Self::State,
+		event: canvas::Event,
+		bounds: Rectangle,
+		cursor: mouse::Cursor,
+	) -> (event::Status, Option<Message>) {
+		if let canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event {
+			// The center is already shown
+			let clicked = self.node_at(bounds, cursor).filter(|&idx| idx > 0);
+			if let Some(center) = clicked.and_then(|idx| self.graph.nodes[idx].center()) {
+				return (event::Status::Captured, Some(Message::GraphRequested(center)));
+			}
+		}
+		(event::Status::Ignored, None)
+	}
+
+	fn draw(
+		&self,
+		_state: &Self::State,
+		renderer: &Renderer,
+		theme: &Theme,
+		bounds: Rectangle,
+		cursor: mouse::Cursor,
+	) -> Vec<Geometry> {
+		let mut frame = Frame::new(renderer, bounds.size());
+		let positions = self.positions(bounds);
+
+		for edge in &self.graph.edges {
+			let line = Path::line(positions[edge.from], positions[edge.to]);
+			let mut stroke = Stroke::default().with_color(EDGE_COLOR).with_width(1.5);
+			if edge.related {
+				stroke.line_dash = LineDash { segments: &[6.0, 4.0], offset: 0 };
+			}
+			frame.stroke(&line, stroke);
+		}
+
+		let hovered = self.node_at(bounds, cursor);
+		for (idx, node) in self.graph.nodes.iter().enumerate() {
+			let radius = if idx == 0 { CENTER_RADIUS } else { NODE_RADIUS };
+			let color = match node.kind {
+				NodeKind::Robot => ROBOT_COLOR,
+				NodeKind::SoftwareVersion => SOFTWARE_COLOR,
+				NodeKind::Vulnerability => format_severity(node.severity.as_deref().unwrap_or_default(), theme),
+			};
+			let circle = Path::circle(positions[idx], radius);
+			frame.fill(&circle, color);
+			if hovered == Some(idx) && node.center().is_some() {
+				frame.stroke(&circle, Stroke::default().with_color(theme.palette().text).with_width(2.0));
+			}
+
+			let content = match (node.kind, &node.severity) {
+				(NodeKind::Vulnerability, Some(severity)) if color_blind_safe() => {
+					format!("{} {}", severity_mark(severity), node.label)
+				}
+				_ => node.label.clone(),
+			};
+			frame.fill_text(canvas::Text {
+				content,
+				position: Point::new(positions[idx].x, positions[idx].y + radius + 4.0),
+				color: theme.palette().text,
+				size: 12.0.into(),
+				horizontal_alignment: Horizontal::Center,
+				vertical_alignment: Vertical::Top,
+				..canvas::Text::default()
+			});
+		}
+
+		vec![frame.into_geometry()]
+	}
+
+	fn mouse_interaction(
+		&self,
+		_state: &Self::State,
+		bounds: Rectangle,
+		cursor: mouse::Cursor,
+	) -> mouse::Interaction {
+		match self.node_at(bounds, cursor) {
+			Some(idx) if idx > 0 && self.graph.nodes[idx].center().is_some() => mouse::Interaction::Pointer,
+			_ => mouse::Interaction::default(),
+		}
+	}
+}
@@ -0,0 +1,109 @@
+use super::state::AppState;
+use super::types::Message;
+use super::formatters::format_muted;
+use crate::models::import_run::ImportRun;
+use crate::utils::time;
+use iced::{
+	theme,
+	widget::{button, column, container, row, scrollable, Column, Text},
+	Alignment, Element, Length,
+};
+
+pub trait ImportHistoryViewRenderer {
+	fn import_history_dialog<'a>(&'a self, runs: &'a [ImportRun]) -> Element<'a, Message>;
+}
+
+impl ImportHistoryViewRenderer for AppState {
+	fn import_history_dialog<'a>(&'a self, runs: &'a [ImportRun]) -> Element<'a, Message> {
+		let can_edit = self.role.can_edit();
+		let muted = theme::Text::Color(format_muted(&self.theme()));
+
+		let list: Element<Message> = if runs.is_empty() {
+			Text::new("Nothing was imported yet").size(16).into()
+		} else {
+			scrollable(
+				Column::with_children(runs.iter().map(|run| {
+					row![
+						Text::new(time::format_local(run.started_at)).size(14).width(Length::Fixed(150.0)),
+						Text::new(&run.actor).size(14).width(Length::Fixed(110.0)),
+						Text::new(&run.source).size(16).width(Length::Fill),
+						Text::new(format!("{} added, {} overwritten", run.created, run.updated))
+							.size(14)
+							.style(muted),
+						button(Text::new(format!("{} skipped", run.skipped)).size(14))
+							.on_press_maybe((run.skipped > 0).then_some(Message::ImportSkippedRowsClicked(run.run_id)))
+							.style(if self.import_skipped.as_ref().is_some_and(|(shown, _)| *shown == run.run_id) {
+								theme::Button::Primary
+							} else {
+								theme::Button::Secondary
+							})
+							.padding(5),
+						Text::new(run.status.to_string()).size(14).width(Length::Fixed(90.0)),
+						button(Text::new("Revert").size(14))
+							.on_press_maybe((can_edit && run.can_revert()).then_some(Message::ImportRevertClicked(run.run_id)))
+							.style(theme::Button::Destructive)
+							.padding(5),
+					]
+						.spacing(15)
+						.align_items(Alignment::Center)
+						.into()
+				}))
+					.spacing(8),
+			)
+				.height(Length::Fill)
+				.into()
+		};
+
+		let skipped = self.import_skipped.as_ref().and_then(|(run_id, rows)| {
+			let run = runs.iter().find(|run| run.run_id == *run_id)?;
+			let mut panel = column![Text::new(format!("Skipped by {}", run.source)).size(18)].spacing(8);
+			if run.skipped as usize > rows.len() {
+				panel = panel.push(
+					Text::new(format!("The first {} of {} skipped rows are kept", rows.len(), run.skipped))
+						.size(14)
+						.style(muted),
+				);
+			}
+			let list = Column::with_children(rows.iter().map(|row| {
+				row![
+					Text::new(format!("Line {}", row.line)).size(14).width(Length::Fixed(90.0)),
+					Text::new(&row.cve_id).size(14).width(Length::Fixed(150.0)),
+					Text::new(&row.reason).size(14).style(muted),
+				]
+					.spacing(15)
+					.into()
+			}))
+				.spacing(4);
+			Some(panel.push(scrollable(list).height(Length::Fill)).height(Length::FillPortion(1)))
+		});
+
+		let mut content = column![
+			row![
+				Text::new("Import History").size(28).width(Length::Fill),
+				button(Text::new("Close").size(16))
+					.on_press(Message::ImportHistoryClosed)
+					.style(theme::Button::Destructive)
+					.padding(5),
+			]
+				.spacing(10)
+				.align_items(Alignment::Center),
+			Text::new(
+				"Vulnerability list and robot inventory imports, newest first. Reverting one deletes the \
+				 vulnerabilities or robots it added and sets those it overwrote back to their previous values. \
+				 Rows an import could not read, such as a malformed CVE ID, are listed under its skipped count."
+			)
+				.size(14),
+			container(list).height(Length::FillPortion(2)),
+		]
+			.spacing(15);
+		if let Some(skipped) = skipped {
+			content = content.push(skipped);
+		}
+
+		container(content)
+			.padding(20)
+			.width(Length::Fill)
+			.style(theme::Container::Box)
+			.into()
+	}
+}
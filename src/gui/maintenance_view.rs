@@ -0,0 +1,69 @@
+use super::state::AppState;
+use super::types::{MaintenanceStatus, Message};
+use super::formatters::{format_error, format_muted};
+use crate::utils::time;
+use iced::{
+	theme,
+	widget::{button, column, container, row, Checkbox, Text},
+	Alignment, Element, Length,
+};
+
+pub trait MaintenanceViewRenderer {
+	fn maintenance_dialog<'a>(&'a self, maintenance: &'a MaintenanceStatus) -> Element<'a, Message>;
+}
+
+impl MaintenanceViewRenderer for AppState {
+	fn maintenance_dialog<'a>(&'a self, maintenance: &'a MaintenanceStatus) -> Element<'a, Message> {
+		// Vacuuming needs the database to itself, like imports and compaction
+		let busy = maintenance.running || self.progress.is_some();
+
+		let last_run: Element<Message> = match &maintenance.policy.last_report {
+			Some(report) => Text::new(format!("Last run {}: {}", time::format_local(report.finished_at), report))
+				.size(14)
+				.style(theme::Text::Color(if report.is_intact() {
+					format_muted(&self.theme())
+				} else {
+					format_error(&self.theme())
+				}))
+				.into(),
+			None => Text::new("Maintenance has not run on this workspace yet").size(14).into(),
+		};
+
+		container(
+			column![
+				row![
+					Text::new("Database Maintenance").size(28).width(Length::Fill),
+					button(Text::new("Close").size(16))
+						.on_press(Message::MaintenanceClosed)
+						.style(theme::Button::Destructive)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				Text::new(format!("Storage: {}", maintenance.stats)).size(16),
+				Text::new(
+					"Checks the database for corruption, refreshes the statistics used to plan queries \
+					 and releases free pages. A large database can take several minutes.",
+				)
+					.size(14),
+				row![
+					button(Text::new(if maintenance.running { "Running..." } else { "Run Now" }).size(16))
+						.on_press_maybe((!busy).then_some(Message::MaintenanceRun))
+						.style(theme::Button::Primary)
+						.padding(8),
+					Checkbox::new("Run weekly while RVD is open", maintenance.policy.weekly)
+						.on_toggle(Message::MaintenanceScheduleToggled)
+						.spacing(5),
+				]
+					.spacing(20)
+					.align_items(Alignment::Center),
+				last_run,
+			]
+				.spacing(15),
+		)
+			.padding(20)
+			.width(Length::Fill)
+			.style(theme::Container::Box)
+			.into()
+	}
+}
@@ -2,10 +2,27 @@ pub(crate) mod app;
 mod state;
 mod types;
 mod views;
+mod table_view;
 mod formatters;
+mod appearance;
 mod database;
 mod constants;
 mod helpers;
 mod robot_view;
+mod software_view;
+mod notes_view;
+mod graph_view;
+mod charts;
+mod trends_view;
+mod maintenance_view;
+mod about_view;
+mod trash_view;
+mod audit_view;
+mod import_history_view;
+mod data_sources_view;
+mod whats_new_view;
+mod csv_import_view;
+mod toast;
+mod profiler;
 
 
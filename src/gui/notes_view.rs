@@ -0,0 +1,128 @@
+use super::state::AppState;
+use super::types::Message;
+use super::formatters::format_muted;
+use crate::models::note::Note;
+use crate::utils::time;
+use iced::{
+	theme,
+	widget::{button, column, container, row, text_input, Column, Text},
+	Alignment, Element, Length,
+};
+
+pub trait NotesViewRenderer {
+	fn notes_panel(&self) -> Element<'_, Message>;
+	fn note_card<'a>(&self, note: &'a Note) -> Element<'a, Message>;
+}
+
+impl NotesViewRenderer for AppState {
+	fn notes_panel(&self) -> Element<'_, Message> {
+		let notes: Element<Message> = if self.notes.is_empty() {
+			Text::new("No notes yet")
+				.size(14)
+				.into()
+		} else {
+			Column::with_children(
+				self.notes
+					.iter()
+					.map(|note| self.note_card(note))
+					.collect::<Vec<Element<'_, Message>>>(),
+			)
+				.spacing(8)
+				.into()
+		};
+
+		if !self.role.can_edit() {
+			return column![
+				Text::new("Notes").size(20),
+				notes,
+			]
+				.spacing(10)
+				.padding(10)
+				.into();
+		}
+
+		let editing = self.editing_note_id.is_some();
+		let mut actions = row![
+			text_input("Add a note...", &self.note_input)
+				.on_input(Message::NoteInputChanged)
+				.on_submit(Message::NoteSubmitted)
+				.padding(8)
+				.width(Length::Fill),
+			button(Text::new(if editing { "Save Note" } else { "Add Note" }).size(14))
+				.on_press(Message::NoteSubmitted)
+				.style(theme::Button::Primary)
+				.padding(8),
+		]
+			.spacing(10)
+			.align_items(Alignment::Center);
+
+		if editing {
+			actions = actions.push(
+				button(Text::new("Cancel").size(14))
+					.on_press(Message::NoteEditCancelled)
+					.style(theme::Button::Secondary)
+					.padding(8),
+			);
+		}
+
+		column![
+			Text::new("Notes").size(20),
+			notes,
+			actions,
+		]
+			.spacing(10)
+			.padding(10)
+			.into()
+	}
+
+	fn note_card<'a>(&self, note: &'a Note) -> Element<'a, Message> {
+		let note_id = note.note_id.unwrap_or_default();
+		let timestamp = note.created_at
+			.map(time::format_local)
+			.unwrap_or_default();
+		let header = if note.is_edited() {
+			format!("{} (edited)", timestamp)
+		} else {
+			timestamp
+		};
+
+		let mut header_row = row![
+			Text::new(header)
+				.size(12)
+				.style(theme::Text::Color(format_muted(&self.theme())))
+				.width(Length::Fill),
+		]
+			.spacing(6)
+			.align_items(Alignment::Center);
+
+		if self.role.can_edit() {
+			header_row = header_row
+				.push(
+					button(Text::new("Edit").size(12))
+						.on_press(Message::NoteEditClicked(note_id))
+						.style(theme::Button::Secondary)
+						.padding(4),
+				)
+				.push(
+					button(Text::new("Delete").size(12))
+						.on_press(Message::NoteDeleteClicked(note_id))
+						.style(theme::Button::Destructive)
+						.padding(4),
+				);
+		}
+
+		container(
+			column![
+				header_row,
+				Text::new(&note.body)
+					.size(14)
+					.width(Length::Fill),
+			]
+				.spacing(4),
+		)
+			.style(theme::Container::Box)
+			.padding(8)
+			.width(Length::Fill)
+			.into()
+	}
+}
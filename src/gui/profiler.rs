@@ -0,0 +1,133 @@
+use super::state::AppState;
+use super::types::Message;
+use super::formatters::format_muted;
+use crate::db::connection;
+use iced::{
+	keyboard::{self, Key, Modifiers},
+	theme,
+	widget::{container, row, Space, Text},
+	Element, Length,
+};
+use std::time::{Duration, Instant};
+
+/// Window over which messages are counted for the messages per second figure
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Figures shown in the performance overlay, collected while it is open
+#[derive(Debug)]
+pub struct Profiler {
+	last_frame: Option<Instant>,
+	/// Time between the last two redraws
+	frame_time: Option<Duration>,
+	window_start: Instant,
+	window_messages: u32,
+	messages_per_sec: f64,
+}
+
+impl Profiler {
+	pub fn new(now: Instant) -> Self {
+		Self {
+			last_frame: None,
+			frame_time: None,
+			window_start: now,
+			window_messages: 0,
+			messages_per_sec: 0.0,
+		}
+	}
+
+	/// Records a redraw. The overlay keeps the window redrawing, so the time between
+	/// frames is how long a full update and redraw take.
+	pub fn frame(&mut self, at: Instant) {
+		if let Some(last) = self.last_frame {
+			self.frame_time = Some(at.saturating_duration_since(last));
+		}
+		self.last_frame = Some(at);
+		self.roll_window(at);
+	}
+
+	/// Counts a message handled by the application, other than redraws
+	pub fn message(&mut self, at: Instant) {
+		self.window_messages += 1;
+		self.roll_window(at);
+	}
+
+	fn roll_window(&mut self, now: Instant) {
+		let elapsed = now.saturating_duration_since(self.window_start);
+		if elapsed >= RATE_WINDOW {
+			self.messages_per_sec = f64::from(self.window_messages) / elapsed.as_secs_f64();
+			self.window_messages = 0;
+			self.window_start = now;
+		}
+	}
+}
+
+/// Hidden shortcut toggling the overlay: Ctrl+Shift+P
+pub fn toggle_shortcut(key: Key, modifiers: Modifiers) -> Option<Message> {
+	match key {
+		Key::Character(c) if modifiers.control() && modifiers.shift() && c.eq_ignore_ascii_case("p") => {
+			Some(Message::ProfilerToggled)
+		}
+		_ => None,
+	}
+}
+
+pub fn shortcut_subscription() -> iced::Subscription<Message> {
+	keyboard::on_key_press(toggle_shortcut)
+}
+
+fn format_duration(duration: Option<Duration>) -> String {
+	duration.map_or_else(|| "–".to_string(), |d| format!("{:.1} ms", d.as_secs_f64() * 1000.0))
+}
+
+pub trait ProfilerViewRenderer {
+	fn profiler_overlay(&self) -> Element<'_, Message>;
+}
+
+impl ProfilerViewRenderer for AppState {
+	fn profiler_overlay(&self) -> Element<'_, Message> {
+		let Some(profiler) = &self.profiler else {
+			return Space::with_height(Length::Shrink).into();
+		};
+		let metric = |label: &str, value: String| {
+			Text::new(format!("{}: {}", label, value))
+				.size(13)
+				.style(theme::Text::Color(format_muted(&self.theme())))
+		};
+
+		container(
+			row![
+				metric("Frame", format_duration(profiler.frame_time)),
+				metric("Messages/s", format!("{:.0}", profiler.messages_per_sec)),
+				metric("Last query", format_duration(connection::last_query_duration())),
+				metric("Rows in memory", self.rows_in_memory().to_string()),
+			]
+				.spacing(20),
+		)
+			.style(theme::Container::Box)
+			.padding(8)
+			.width(Length::Fill)
+			.into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_profiler() {
+		let start = Instant::now();
+		let mut profiler = Profiler::new(start);
+		profiler.frame(start);
+		assert_eq!(profiler.frame_time, None);
+		profiler.frame(start + Duration::from_millis(16));
+		assert_eq!(profiler.frame_time, Some(Duration::from_millis(16)));
+
+		for i in 0..10 {
+			profiler.message(start + Duration::from_millis(100 * i));
+		}
+		assert_eq!(profiler.messages_per_sec, 0.0);
+		profiler.message(start + Duration::from_secs(2));
+		assert_eq!(profiler.messages_per_sec, 5.5);
+	}
+}
@@ -1,10 +1,19 @@
-use super::types::{Message, RobotFilterType, Tab};
+use super::types::{Message, RobotFilterType, RobotSort, Tab};
 use super::state::AppState;
-use crate::models::robot::Robot;
+use super::notes_view::NotesViewRenderer;
+use crate::models::commissioning::{self, ChecklistEntry};
+use crate::models::graph::GraphCenter;
+use crate::models::robot::{Criticality, Robot, RobotExposure};
+use super::appearance::{DetailLayout, ThemeChoice};
+use super::formatters::{format_error, format_muted, format_risk, format_severity, format_severity_label, format_warning};
+use crate::models::risk::RiskBand;
+use crate::utils::{product_match, time};
+use super::constants::NAME_SUGGESTION_LIMIT;
+use chrono::Utc;
 use iced::{
 	theme,
 	widget::{
-		button, column, container, pick_list, row, scrollable, text_input, Column,
+		button, column, container, pick_list, row, scrollable, text_input, Checkbox, Column,
 		Rule, Space, Text,
 	},
 	Alignment, Element, Length, Theme, Renderer,
@@ -16,6 +25,7 @@ pub trait RobotViewRenderer {
 	fn robot_form(&self) -> Element<Message, Theme, Renderer>;
 	fn robot_detail<'a>(&'a self, robot: &'a Robot) -> Element<'a, Message, Theme, Renderer>;
 	fn robot_control_panel(&self) -> Element<Message, Theme, Renderer>;
+	fn robot_import_errors(&self) -> Element<'_, Message, Theme, Renderer>;
 	fn tab_selector(&self) -> Element<Message, Theme, Renderer>;
 }
 
@@ -33,20 +43,23 @@ impl RobotViewRenderer for AppState {
 					.horizontal_alignment(iced::alignment::Horizontal::Center))
 				.into()
 		} else if self.robots.is_empty() {
-			Column::<Message, Theme, Renderer>::new()
+			let mut empty = Column::<Message, Theme, Renderer>::new()
 				.push(Space::with_height(Length::Fixed(40.0)))
 				.push(Text::new("No robots found")
 					.size(20)
-					.horizontal_alignment(iced::alignment::Horizontal::Center))
-				.push(Space::with_height(Length::Fixed(20.0)))
-				.push(container(
-					button(Text::new("Add Robot").size(16))
-						.on_press(Message::AddRobotClicked)
-						.padding(12)
-						.style(theme::Button::Primary)
-				)
-					.center_x())
-				.into()
+					.horizontal_alignment(iced::alignment::Horizontal::Center));
+			if self.role.can_edit() {
+				empty = empty
+					.push(Space::with_height(Length::Fixed(20.0)))
+					.push(container(
+						button(Text::new("Add Robot").size(16))
+							.on_press(Message::AddRobotClicked)
+							.padding(12)
+							.style(theme::Button::Primary)
+					)
+						.center_x());
+			}
+			empty.into()
 		} else {
 			let displayed_robots = self.get_displayed_robots();
 			let mut list = Column::<Message, Theme, Renderer>::new().spacing(12);
@@ -65,12 +78,36 @@ impl RobotViewRenderer for AppState {
 			.into()
 	}
 
-	fn robot_card<'a>(&'a self, robot: &'a Robot, _idx: usize) -> Element<'a, Message, Theme, Renderer> {
+	fn robot_card<'a>(&'a self, robot: &'a Robot, idx: usize) -> Element<'a, Message, Theme, Renderer> {
 		let name = &robot.name;
-		let manufacturer = robot.manufacturer.as_deref().unwrap_or("Unknown Manufacturer");
+		let manufacturer = make_and_model(robot);
 		let specifications = robot.specifications.as_deref().unwrap_or("No specifications available");
 		let robot_id = robot.robot_id.unwrap_or(0);
 
+		let mut actions = row![
+			button(Text::new("Details"))
+				.on_press(Message::RobotSelected(idx))
+				.style(theme::Button::Secondary)
+				.padding(8),
+		]
+			.spacing(8);
+
+		if self.role.can_edit() {
+			actions = actions
+				.push(
+					button(Text::new("Edit"))
+						.on_press(Message::EditRobotClicked(robot_id))
+						.style(theme::Button::Secondary)
+						.padding(8),
+				)
+				.push(
+					button(Text::new("Delete"))
+						.on_press(Message::DeleteRobotClicked(robot_id))
+						.style(theme::Button::Destructive)
+						.padding(8),
+				);
+		}
+
 		container(
 			column![
 				row![
@@ -80,20 +117,14 @@ impl RobotViewRenderer for AppState {
 							.width(Length::Fill),
 						Text::new(manufacturer)
 							.size(14),
+						operational_note(robot, &self.theme()),
+						stale_inventory(robot, self.inventory_max_age_days, &self.theme()),
 					]
 					.width(Length::Fill),
 
-					row![
-						button(Text::new("Edit"))
-							.on_press(Message::EditRobotClicked(robot_id))
-							.style(theme::Button::Secondary)
-							.padding(8),
-						button(Text::new("Delete"))
-							.on_press(Message::DeleteRobotClicked(robot_id))
-							.style(theme::Button::Destructive)
-							.padding(8),
-					]
-					.spacing(8),
+					risk_badge(robot, &self.theme()),
+
+					actions,
 				]
 				.align_items(Alignment::Center),
 
@@ -162,7 +193,7 @@ impl RobotViewRenderer for AppState {
 				if self.robot_form.name.is_empty() {
 					Text::new("This field is required")
 						.size(12)
-						.style(theme::Text::Color(iced::Color::from_rgb8(200, 0, 0)))
+						.style(theme::Text::Color(format_error(&self.theme())))
 				} else {
 					Text::new("")
 				},
@@ -176,15 +207,62 @@ impl RobotViewRenderer for AppState {
 					.on_input(Message::RobotFormManufacturerChanged)
 					.padding(10)
 					.width(Length::Fill),
+				name_suggestions(
+					product_match::complete(&self.robot_form.manufacturer, &self.vendor_names, NAME_SUGGESTION_LIMIT)
+						.into_iter()
+						.map(|name| (name.clone(), Message::RobotFormManufacturerChanged(name)))
+						.collect(),
+				),
 				if self.robot_form.manufacturer.is_empty() {
 					Text::new("This field is required")
 						.size(12)
-						.style(theme::Text::Color(iced::Color::from_rgb8(200, 0, 0)))
+						.style(theme::Text::Color(format_error(&self.theme())))
 				} else {
 					Text::new("")
 				},
 			]
 			.spacing(5),
+			// Model
+			column![
+				Text::new("Model")
+					.size(16),
+				text_input("e.g. KR 6 R900", &self.robot_form.model)
+					.on_input(Message::RobotFormModelChanged)
+					.padding(10)
+					.width(Length::Fill),
+			]
+			.spacing(5),
+			// Platform
+			row![
+				column![
+					Text::new("Firmware")
+						.size(16),
+					text_input("e.g. 8.3.2", &self.robot_form.firmware_version)
+						.on_input(Message::RobotFormFirmwareChanged)
+						.padding(10)
+						.width(Length::Fill),
+				]
+				.spacing(5),
+				column![
+					Text::new("Operating System")
+						.size(16),
+					text_input("e.g. Ubuntu 22.04", &self.robot_form.os)
+						.on_input(Message::RobotFormOsChanged)
+						.padding(10)
+						.width(Length::Fill),
+				]
+				.spacing(5),
+				column![
+					Text::new("ROS Distribution")
+						.size(16),
+					text_input("e.g. ROS 2 Humble", &self.robot_form.ros_distro)
+						.on_input(Message::RobotFormRosDistroChanged)
+						.padding(10)
+						.width(Length::Fill),
+				]
+				.spacing(5),
+			]
+			.spacing(10),
 			// Specifications
 			column![
 				Text::new("Specifications *")
@@ -196,12 +274,30 @@ impl RobotViewRenderer for AppState {
 				if self.robot_form.specifications.is_empty() {
 					Text::new("This field is required")
 						.size(12)
-						.style(theme::Text::Color(iced::Color::from_rgb8(200, 0, 0)))
+						.style(theme::Text::Color(format_error(&self.theme())))
 				} else {
 					Text::new("")
 				},
 			]
 			.spacing(5),
+			// Operational note
+			column![
+				Text::new("Operational Note")
+					.size(16),
+				text_input("e.g. air-gapped, scheduled for retirement Q3", &self.robot_form.operational_note)
+					.on_input(Message::RobotFormOperationalNoteChanged)
+					.padding(10)
+					.width(Length::Fill),
+			]
+			.spacing(5),
+			// Criticality
+			column![
+				Text::new("Criticality")
+					.size(16),
+				pick_list(&Criticality::ALL[..], Some(self.robot_form.criticality), Message::RobotFormCriticalityChanged)
+					.padding(10),
+			]
+			.spacing(5),
 		]
 				.spacing(15)
 				.padding(10),
@@ -257,7 +353,7 @@ impl RobotViewRenderer for AppState {
 			software_versions,
 			row![
 				text_input(
-					"Enter software version",
+					"vendor/product version, e.g. OSRF/ros-core 1.2.0",
 					&self.software_version_input,
 				)
 				.on_input(Message::RobotFormSoftwareVersionInput)
@@ -270,6 +366,12 @@ impl RobotViewRenderer for AppState {
 					.padding(10),
 			]
 			.spacing(10),
+			name_suggestions(
+				vendor_completions(&self.software_version_input, &self.vendor_names)
+					.into_iter()
+					.map(|(vendor, entry)| (vendor, Message::RobotFormSoftwareVersionInput(entry)))
+					.collect(),
+			),
 		]
 				.spacing(15)
 				.padding(10),
@@ -314,7 +416,7 @@ impl RobotViewRenderer for AppState {
 				if !can_submit {
 					Text::new("* Required fields must be filled")
 						.size(14)
-						.style(theme::Text::Color(iced::Color::from_rgb8(200, 0, 0)))
+						.style(theme::Text::Color(format_error(&self.theme())))
 				} else {
 					Text::new("")
 				},
@@ -322,13 +424,6 @@ impl RobotViewRenderer for AppState {
 			]
 			.spacing(10)
 			.align_items(Alignment::Center),
-			if let Some(error) = &self.error_message {
-				Text::new(error)
-					.size(14)
-					.style(theme::Text::Color(iced::Color::from_rgb8(200, 0, 0)))
-			} else {
-				Text::new("")
-			},
 		]
 				.spacing(20)
 				.padding(20)
@@ -340,7 +435,7 @@ impl RobotViewRenderer for AppState {
 	}
 
 	fn robot_detail<'a>(&'a self, robot: &'a Robot) -> Element<'a, Message, Theme, Renderer> {
-		let manufacturer = robot.manufacturer.as_deref().unwrap_or("Unknown");
+		let manufacturer = make_and_model(robot);
 		let specifications = robot.specifications.as_deref().unwrap_or("No specifications available");
 
 		// 1. Explicit type annotation for software_versions
@@ -364,18 +459,30 @@ impl RobotViewRenderer for AppState {
 				row![
 					Text::new(&robot.name).size(28),
 					Space::with_width(Length::Fill),
+					button(Text::new("Relationship Graph").size(16))
+						.on_press_maybe(robot.robot_id.map(|id| Message::GraphRequested(GraphCenter::Robot(id as i64))))
+						.style(theme::Button::Secondary)
+						.padding(8),
+					button(Text::new("Print / Save as PDF").size(16))
+						.on_press(Message::PrintDetail)
+						.style(theme::Button::Secondary)
+						.padding(8),
 					button(Text::new("×").size(28))
 						.on_press(Message::ClearSelection)
 						.style(theme::Button::Destructive)
 						.padding(8),
-				],
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
 
 				Rule::horizontal(10),
 
 				container(
 					column![
-						Text::new("Manufacturer").size(16),
+						Text::new("Make and Model").size(16),
 						Text::new(manufacturer).size(14),
+						Text::new(platform(robot)).size(14),
+						operational_note(robot, &self.theme()),
 					]
 				)
 				.style(theme::Container::Box)
@@ -393,11 +500,25 @@ impl RobotViewRenderer for AppState {
 				container(
 					column![
 						Text::new("Software Versions").size(16),
-						software_versions
+						software_versions,
+						inventory_freshness(robot, self.inventory_max_age_days, self.role.can_edit(), &self.theme()),
 					]
+						.spacing(8)
 				)
 				.style(theme::Container::Box)
 				.padding(16),
+
+				container(exposure_list(&self.robot_vulnerabilities, &self.theme()))
+				.style(theme::Container::Box)
+				.padding(16),
+
+				container(checklist_panel(robot, &self.robot_checklist, self.role.can_edit(), &self.theme()))
+				.style(theme::Container::Box)
+				.padding(16),
+
+				container(self.notes_panel())
+				.style(theme::Container::Box)
+				.padding(6),
 			]
 				.spacing(16)
 		)
@@ -428,12 +549,35 @@ impl RobotViewRenderer for AppState {
 					.padding(8)
 					.width(Length::Fixed(200.0)),
 
+				pick_list(RobotSort::ALL, Some(self.robot_sort), Message::RobotSortChanged)
+					.width(Length::Fixed(160.0))
+					.padding(8),
+
 				Space::with_width(Length::Fill),
 
-				button(Text::new("Add Robot").size(16))
-					.on_press(Message::AddRobotClicked)
-					.style(theme::Button::Primary)
-					.padding(12),
+				if self.role.can_edit() {
+					Element::from(
+						row![
+							text_input("Robot inventory CSV or JSON...", &self.robot_import_path)
+								.on_input(Message::RobotImportPathChanged)
+								.on_submit(Message::ImportRobotData(self.robot_import_path.clone()))
+								.padding(8)
+								.width(Length::Fixed(240.0)),
+							button(Text::new("Import").size(16))
+								.on_press(Message::ImportRobotData(self.robot_import_path.clone()))
+								.style(theme::Button::Secondary)
+								.padding(12),
+							button(Text::new("Add Robot").size(16))
+								.on_press(Message::AddRobotClicked)
+								.style(theme::Button::Primary)
+								.padding(12),
+						]
+							.spacing(8)
+							.align_items(Alignment::Center),
+					)
+				} else {
+					Text::new("Read-only (viewer)").size(14).into()
+				},
 			]
 				.spacing(12)
 				.align_items(Alignment::Center)
@@ -443,6 +587,41 @@ impl RobotViewRenderer for AppState {
 			.into()
 	}
 
+	/// Rows the last robot import skipped
+	fn robot_import_errors(&self) -> Element<'_, Message, Theme, Renderer> {
+		if self.robot_import_errors.is_empty() {
+			return Space::with_height(Length::Shrink).into();
+		}
+
+		container(
+			column![
+				row![
+					Text::new(format!("{} rows were not imported", self.robot_import_errors.len()))
+						.size(16)
+						.style(theme::Text::Color(format_error(&self.theme()))),
+					Space::with_width(Length::Fill),
+					button(Text::new("Dismiss").size(14))
+						.on_press(Message::DismissRobotImportErrors)
+						.style(theme::Button::Secondary)
+						.padding(5),
+				]
+					.align_items(Alignment::Center),
+				scrollable(
+					Column::with_children(
+						self.robot_import_errors.iter().map(|error| Text::new(error.to_string()).size(14).into()),
+					)
+						.spacing(4),
+				)
+					.height(Length::Fixed(120.0)),
+			]
+				.spacing(8),
+		)
+			.style(theme::Container::Box)
+			.padding(15)
+			.width(Length::Fill)
+			.into()
+	}
+
 	fn tab_selector(&self) -> Element<Message, Theme, Renderer> {
 		container(
 			row![
@@ -463,8 +642,85 @@ impl RobotViewRenderer for AppState {
 					})
 					.on_press(Message::TabSelected(Tab::RobotInventory))
 					.padding(12),
+
+				button(Text::new("Software").size(16))
+					.style(if matches!(self.current_tab, Tab::Software) {
+						theme::Button::Primary
+					} else {
+						theme::Button::Secondary
+					})
+					.on_press(Message::TabSelected(Tab::Software))
+					.padding(12),
+
+				button(Text::new("Trends").size(16))
+					.style(if matches!(self.current_tab, Tab::Trends) {
+						theme::Button::Primary
+					} else {
+						theme::Button::Secondary
+					})
+					.on_press(Message::TabSelected(Tab::Trends))
+					.padding(12),
+
+				Space::with_width(Length::Fill),
+				button(Text::new("Maintenance").size(16))
+					.style(if self.maintenance.is_some() {
+						theme::Button::Primary
+					} else {
+						theme::Button::Secondary
+					})
+					.on_press(Message::MaintenanceOpened)
+					.padding(12),
+				button(Text::new("Recently Deleted").size(16))
+					.style(if self.trash.is_some() {
+						theme::Button::Primary
+					} else {
+						theme::Button::Secondary
+					})
+					.on_press(Message::TrashOpened)
+					.padding(12),
+				button(Text::new("Audit Log").size(16))
+					.style(if self.audit.is_some() {
+						theme::Button::Primary
+					} else {
+						theme::Button::Secondary
+					})
+					.on_press(Message::AuditOpened)
+					.padding(12),
+				button(Text::new("Import History").size(16))
+					.style(if self.import_runs.is_some() {
+						theme::Button::Primary
+					} else {
+						theme::Button::Secondary
+					})
+					.on_press(Message::ImportHistoryOpened)
+					.padding(12),
+				// A new release is only pointed out here, never in a popup
+				button(Text::new(if self.update_status.update.is_some() { "About (update available)" } else { "About" }).size(16))
+					.style(if self.about_open {
+						theme::Button::Primary
+					} else {
+						theme::Button::Secondary
+					})
+					.on_press(Message::AboutOpened)
+					.padding(12),
+				Text::new("Workspace").size(16),
+				pick_list(
+					self.workspaces.clone(),
+					Some(self.workspace.clone()),
+					Message::WorkspaceSelected,
+				)
+					.width(Length::Fixed(180.0))
+					.padding(8),
+				pick_list(&ThemeChoice::ALL[..], Some(self.theme_choice), Message::ThemeChanged)
+					.padding(8),
+				pick_list(&DetailLayout::ALL[..], Some(self.detail_layout), Message::DetailLayoutChanged)
+					.padding(8),
+				Checkbox::new("Color-blind safe", self.color_blind_safe)
+					.on_toggle(Message::ColorBlindSafeToggled)
+					.spacing(5),
 			]
 				.spacing(12)
+				.align_items(Alignment::Center)
 		)
 			.style(theme::Container::Box)
 			.padding(15)
@@ -472,6 +728,253 @@ impl RobotViewRenderer for AppState {
 	}
 }
 
+/// Risk score colored by band, or nothing before the robot was first scored
+fn risk_badge<'a>(robot: &'a Robot, theme: &Theme) -> Element<'a, Message, Theme, Renderer> {
+	match robot.risk_score {
+		Some(score) => {
+			let band = RiskBand::of(score);
+			container(
+				Text::new(format!("Risk {:.0} ({})", score, band))
+					.size(16)
+					.style(theme::Text::Color(format_risk(band, theme))),
+			)
+				.padding([0, 12])
+				.into()
+		}
+		None => Space::with_width(Length::Shrink).into(),
+	}
+}
+
+/// Known spellings offered below a name field, each filling in its message when picked
+fn name_suggestions<'a>(suggestions: Vec<(String, Message)>) -> Element<'a, Message, Theme, Renderer> {
+	if suggestions.is_empty() {
+		return Space::with_height(Length::Shrink).into();
+	}
+	let mut suggestion_row = row![Text::new("Known as:").size(12)]
+		.spacing(6)
+		.align_items(Alignment::Center);
+	for (name, message) in suggestions {
+		suggestion_row = suggestion_row.push(
+			button(Text::new(name).size(12))
+				.on_press(message)
+				.style(theme::Button::Secondary)
+				.padding(4),
+		);
+	}
+	suggestion_row.into()
+}
+
+/// Vendor completions for a software entry while its vendor part is being typed, each
+/// with the entry as it reads once the vendor is picked
+fn vendor_completions(entry: &str, known: &[String]) -> Vec<(String, String)> {
+	let (vendor, rest) = match entry.split_once('/') {
+		Some((vendor, rest)) => (vendor, rest),
+		// Past the first word of an entry without a vendor, the product is being typed
+		None if entry.trim().contains(' ') => return Vec::new(),
+		None => (entry, ""),
+	};
+	product_match::complete(vendor, known, NAME_SUGGESTION_LIMIT)
+		.into_iter()
+		.map(|name| {
+			let completed = format!("{}/{}", name, rest);
+			(name, completed)
+		})
+		.collect()
+}
+
+/// Manufacturer followed by the model, if known
+fn make_and_model(robot: &Robot) -> String {
+	match (&robot.manufacturer, &robot.model) {
+		(Some(manufacturer), Some(model)) => format!("{} {}", manufacturer, model),
+		(Some(manufacturer), None) => manufacturer.clone(),
+		(None, Some(model)) => model.clone(),
+		(None, None) => "Unknown Manufacturer".to_string(),
+	}
+}
+
+/// Firmware, operating system and ROS distribution as far as recorded
+fn platform(robot: &Robot) -> String {
+	let parts: Vec<String> = [
+		robot.firmware_version.as_ref().map(|firmware| format!("Firmware {}", firmware)),
+		robot.os.clone(),
+		robot.ros_distro.clone(),
+	]
+		.into_iter()
+		.flatten()
+		.collect();
+	if parts.is_empty() {
+		"Platform not recorded".to_string()
+	} else {
+		parts.join(" · ")
+	}
+}
+
+/// The robot's operational note highlighted for responders, or nothing when it has none
+fn operational_note<'a>(robot: &'a Robot, theme: &Theme) -> Element<'a, Message, Theme, Renderer> {
+	match &robot.operational_note {
+		Some(note) => Text::new(format!("Note: {}", note))
+			.size(14)
+			.style(theme::Text::Color(format_warning(theme)))
+			.into(),
+		None => Space::with_height(Length::Shrink).into(),
+	}
+}
+
+/// A warning on robot cards whose inventory is older than the maximum age, or nothing
+fn stale_inventory<'a>(robot: &Robot, max_age_days: u32, theme: &Theme) -> Element<'a, Message, Theme, Renderer> {
+	if !robot.inventory_is_stale(max_age_days, Utc::now()) {
+		return Space::with_height(Length::Shrink).into();
+	}
+	let since = robot.inventory_refreshed_at
+		.map_or_else(|| "never recorded".to_string(), |refreshed| format!("refreshed {}", time::format_local(refreshed)));
+	Text::new(format!("Stale inventory: {}", since))
+		.size(14)
+		.style(theme::Text::Color(format_warning(theme)))
+		.into()
+}
+
+/// When and how the software list was last refreshed, warning once it is older than
+/// the maximum age, with a button for editors to confirm it is still current
+fn inventory_freshness<'a>(
+	robot: &Robot,
+	max_age_days: u32,
+	can_edit: bool,
+	theme: &Theme,
+) -> Element<'a, Message, Theme, Renderer> {
+	let mut line = match robot.inventory_refreshed_at {
+		Some(refreshed) => format!("Inventory refreshed {}", time::format_local(refreshed)),
+		None => "Inventory never recorded".to_string(),
+	};
+	if let Some(source) = robot.inventory_source {
+		line.push_str(&format!(" ({})", source));
+	}
+	let color = if robot.inventory_is_stale(max_age_days, Utc::now()) {
+		line.push_str(&format!(" · older than {} days, its vulnerability matches may be out of date", max_age_days));
+		format_warning(theme)
+	} else {
+		format_muted(theme)
+	};
+	let text = Text::new(line).size(14).style(theme::Text::Color(color));
+
+	let mut freshness = row![text].spacing(10).align_items(Alignment::Center);
+	if can_edit {
+		freshness = freshness.push(
+			button(Text::new("Confirm current").size(14))
+				.on_press_maybe(robot.robot_id.map(Message::InventoryConfirmClicked))
+				.style(theme::Button::Secondary)
+				.padding(6),
+		);
+	}
+	freshness.into()
+}
+
+/// Vulnerabilities of the robot's software as loaded, highest CVSS first; each opens
+/// on the Vulnerabilities tab
+fn exposure_list<'a>(exposures: &'a [RobotExposure], theme: &Theme) -> Element<'a, Message, Theme, Renderer> {
+	let now = Utc::now();
+	let open = exposures.iter().filter(|exposure| exposure.vulnerability.status.is_unresolved()).count();
+	let rows = exposures.iter().map(|exposure| {
+		let vuln = &exposure.vulnerability;
+		let cvss = match vuln.cvss_score {
+			Some(score) => format!("{} {:.1}", vuln.cvss_name(), score),
+			None => format!("CVSS ~{:.1}", vuln.effective_cvss()),
+		};
+		let exposed = match exposure.exposure_days(now) {
+			Some(days) => format!("exposed {} days", days),
+			None => String::new(),
+		};
+		row![
+			button(Text::new(&vuln.cve_id).size(14))
+				.on_press(Message::OpenVulnerability(vuln.cve_id.clone()))
+				.style(theme::Button::Text)
+				.padding(0)
+				.width(Length::Fixed(160.0)),
+			Text::new(format_severity_label(&vuln.severity))
+				.size(14)
+				.style(theme::Text::Color(format_severity(&vuln.severity, theme)))
+				.width(Length::Fixed(80.0)),
+			Text::new(cvss).size(14).width(Length::Fixed(90.0)),
+			Text::new(vuln.status.as_str()).size(14).width(Length::Fixed(110.0)),
+			Text::new(exposed).size(14).style(theme::Text::Color(format_muted(theme))),
+		]
+			.spacing(10)
+			.align_items(Alignment::Center)
+			.into()
+	});
+	let longest = match exposures.iter().filter_map(|exposure| exposure.exposure_days(now)).max() {
+		Some(days) => format!(", the oldest for {} days", days),
+		None => String::new(),
+	};
+
+	column![
+		Text::new(format!(
+			"Vulnerability Exposure: {} open of {} affecting this robot{}",
+			open,
+			exposures.len(),
+			longest
+		))
+			.size(16),
+		Column::with_children(rows).spacing(6),
+	]
+		.spacing(8)
+		.into()
+}
+
+/// The robot's commissioning checklist, ticked off item by item; open items raise its
+/// risk score
+fn checklist_panel<'a>(
+	robot: &Robot,
+	entries: &'a [ChecklistEntry],
+	can_edit: bool,
+	theme: &Theme,
+) -> Element<'a, Message, Theme, Renderer> {
+	if entries.is_empty() {
+		return column![
+			Text::new("Commissioning Checklist").size(16),
+			Text::new("No checklist items are configured; add them with the checklist-items command")
+				.size(14)
+				.style(theme::Text::Color(format_muted(theme))),
+		]
+			.spacing(8)
+			.into();
+	}
+
+	let robot_id = robot.robot_id;
+	let rows = entries.iter().map(|entry| {
+		let item_id = entry.item.item_id;
+		let completed = match &entry.completed {
+			Some((actor, at)) => format!("{}, {}", actor, time::format_local(*at)),
+			None => String::new(),
+		};
+		row![
+			Checkbox::new(entry.item.label.as_str(), entry.is_done())
+				.on_toggle_maybe(robot_id.filter(|_| can_edit).map(|robot_id| {
+					move |done| Message::ChecklistItemToggled(robot_id, item_id, done)
+				}))
+				.spacing(5)
+				.width(Length::Fill),
+			Text::new(completed).size(14).style(theme::Text::Color(format_muted(theme))),
+		]
+			.spacing(10)
+			.align_items(Alignment::Center)
+			.into()
+	});
+
+	let open = entries.iter().filter(|entry| !entry.is_done()).count();
+	column![
+		row![
+			Text::new(format!("Commissioning Checklist: {}", commissioning::progress(entries))).size(16),
+			Text::new(if open > 0 { "Open items raise the risk score" } else { "" })
+				.size(14)
+				.style(theme::Text::Color(format_warning(theme))),
+		]
+			.spacing(10),
+		Column::with_children(rows).spacing(6),
+	]
+		.spacing(8)
+		.into()
+}
+
 // Add these helper functions if not already present
 impl AppState {
 
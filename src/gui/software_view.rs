@@ -0,0 +1,152 @@
+use super::state::AppState;
+use super::types::Message;
+use super::formatters::format_error;
+use crate::models::software::VersionMetadata;
+use chrono::{Local, NaiveDate};
+use iced::{
+	theme,
+	widget::{button, checkbox, column, container, row, scrollable, text_input, Column, Space, Text},
+	Alignment, Element, Length,
+};
+
+pub trait SoftwareViewRenderer {
+	fn software_view(&self) -> Element<'_, Message>;
+	fn version_editor(&self) -> Element<'_, Message>;
+	fn version_row<'a>(&self, version: &'a VersionMetadata, today: NaiveDate) -> Element<'a, Message>;
+}
+
+impl SoftwareViewRenderer for AppState {
+	fn software_view(&self) -> Element<'_, Message> {
+		let shown = self.shown_versions();
+		let today = Local::now().date_naive();
+
+		let controls = container(
+			row![
+				text_input("Filter by product, vendor or version...", &self.version_filter)
+					.on_input(Message::VersionFilterChanged)
+					.padding(8)
+					.width(Length::Fixed(320.0)),
+				button(Text::new("Select shown").size(14))
+					.on_press(Message::SelectShownVersions)
+					.style(theme::Button::Secondary)
+					.padding(8),
+				button(Text::new("Clear selection").size(14))
+					.on_press(Message::ClearVersionSelection)
+					.style(theme::Button::Secondary)
+					.padding(8),
+				Space::with_width(Length::Fill),
+				Text::new(format!("{} of {} versions selected", self.selected_versions.len(), self.software_versions.len()))
+					.size(14),
+			]
+				.spacing(12)
+				.align_items(Alignment::Center),
+		)
+			.style(theme::Container::Box)
+			.padding(15);
+
+		let list: Element<Message> = if shown.is_empty() {
+			Text::new("No software versions").size(14).into()
+		} else {
+			scrollable(
+				Column::with_children(shown.into_iter().map(|version| self.version_row(version, today)))
+					.spacing(8),
+			)
+				.height(Length::Fill)
+				.into()
+		};
+
+		column![
+			Text::new("Software").size(30),
+			controls,
+			self.version_editor(),
+			list,
+		]
+			.spacing(20)
+			.padding(20)
+			.width(Length::Fill)
+			.into()
+	}
+
+	/// Metadata form for the selected versions; fields left untouched are not saved
+	fn version_editor(&self) -> Element<'_, Message> {
+		if self.selected_versions.is_empty() || !self.role.can_edit() {
+			return Space::with_height(Length::Shrink).into();
+		}
+		let bulk = self.selected_versions.len() > 1;
+		let unchanged = |placeholder: &'static str| if bulk { "unchanged" } else { placeholder };
+		let editor = &self.version_editor;
+
+		container(
+			column![
+				Text::new(if bulk {
+					format!("Edit {} versions; only the fields you change are applied to all", self.selected_versions.len())
+				} else {
+					"Edit version".to_string()
+				})
+					.size(16),
+				row![
+					Text::new("Released").size(14),
+					text_input(unchanged("YYYY-MM-DD"), editor.release_date.as_deref().unwrap_or_default())
+						.on_input(Message::VersionReleaseDateChanged)
+						.padding(8)
+						.width(Length::Fixed(140.0)),
+					Text::new("End of life").size(14),
+					text_input(unchanged("YYYY-MM-DD"), editor.eol_date.as_deref().unwrap_or_default())
+						.on_input(Message::VersionEolDateChanged)
+						.padding(8)
+						.width(Length::Fixed(140.0)),
+					Text::new("Notes").size(14),
+					text_input(unchanged("Notes"), editor.notes.as_deref().unwrap_or_default())
+						.on_input(Message::VersionNotesChanged)
+						.on_submit(Message::SaveVersionMetadata)
+						.padding(8)
+						.width(Length::Fill),
+					button(Text::new("Save").size(14))
+						.on_press(Message::SaveVersionMetadata)
+						.style(theme::Button::Primary)
+						.padding(8),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+			]
+				.spacing(10),
+		)
+			.style(theme::Container::Box)
+			.padding(15)
+			.into()
+	}
+
+	fn version_row<'a>(&self, version: &'a VersionMetadata, today: NaiveDate) -> Element<'a, Message> {
+		let version_id = version.version_id;
+		let selected = self.selected_versions.contains(&version_id);
+		let released = match (version.release_date, version.age_days(today)) {
+			(Some(date), Some(days)) => format!("Released {} ({:.1} years ago)", date, days as f64 / 365.25),
+			_ => "Release date unknown".to_string(),
+		};
+		let eol: Element<Message> = match version.eol_date {
+			Some(date) if version.is_eol(today) => Text::new(format!("End of life since {}", date))
+				.size(14)
+				.style(theme::Text::Color(format_error(&self.theme())))
+				.into(),
+			Some(date) => Text::new(format!("Supported until {}", date)).size(14).into(),
+			None => Space::with_width(Length::Shrink).into(),
+		};
+
+		container(
+			row![
+				checkbox(version.label(), selected)
+					.on_toggle_maybe(self.role.can_edit().then_some(move |checked| Message::VersionToggled(version_id, checked)))
+					.width(Length::FillPortion(3)),
+				Text::new(released).size(14).width(Length::FillPortion(2)),
+				container(eol).width(Length::FillPortion(2)),
+				Text::new(format!("{} robots", version.robot_count)).size(14).width(Length::FillPortion(1)),
+				Text::new(version.notes.as_deref().unwrap_or_default()).size(14).width(Length::FillPortion(3)),
+			]
+				.spacing(10)
+				.align_items(Alignment::Center),
+		)
+			.style(theme::Container::Box)
+			.padding(8)
+			.into()
+	}
+}
@@ -1,90 +1,412 @@
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use crate::db::connection::SqlitePool;
-use crate::models::vulnerability::Vulnerability;
-use crate::models::robot::Robot;
-use super::types::{SortField, FilterSeverity, RobotFilterType, RobotForm, Tab};
-use super::constants::DISPLAY_PAGE_SIZE;
+use crate::models::vulnerability::{RelatedVulnerability, RiskAcceptance, TriageStatus, Vulnerability};
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::db::compaction::StorageStats;
+use crate::models::nvd_health::NvdHealth;
+use crate::db::workspace::Workspaces;
+use super::toast::Toasts;
+use super::profiler::Profiler;
+use super::appearance::{self, DetailLayout, ThemeChoice};
+use iced::Theme;
+use crate::models::robot::{Criticality, Robot, RobotExposure, DEFAULT_INVENTORY_MAX_AGE_DAYS};
+use crate::utils::robot_import::RowError;
+use crate::models::software::{RiskySoftware, VersionMetadata};
+use crate::models::enrichment::EnrichmentProgress;
+use crate::models::statistics::StatisticsReport;
+use crate::models::trends::FleetTrends;
+use crate::models::note::{Note, NoteEntity};
+use crate::models::reference::Reference;
+use crate::models::graph::RelationshipGraph;
+use crate::models::trash::DeletedItem;
+use crate::models::import_run::{ImportRun, SkippedRow};
+use crate::models::sync_delta::SyncDelta;
+use crate::models::commissioning::ChecklistEntry;
+use crate::models::compliance::ComplianceControl;
+use crate::models::data_source::DataSource;
+use crate::models::ticket::Ticket;
+use crate::models::role::Role;
+use crate::repositories::access;
+use crate::repositories::vulnerability_repo::{PageCursor, QuickFilter};
+use crate::utils::progress::Progress;
+use crate::reports::print;
+use super::types::{AuditLogView, CsvImportView, DataSourcesView, SortField, FieldEditForm, FilterSeverity, FilterStatus, FilterWeakness, MaintenanceStatus, ListLayout, UpdateStatus, RobotFilterType, RobotForm, RobotSort, RowTint, TableColumns, Tab, VersionEditor, VulnerabilityQuery};
 
 #[derive(Debug)]
 pub struct AppState {
 	// Database connection
 	pub pool: Arc<SqlitePool>,
+	/// Name of the open workspace and of all workspaces to switch to
+	pub workspace: String,
+	pub workspaces: Vec<String>,
 
 	// Vulnerability-related fields
-	pub vulnerabilities: Vec<Vulnerability>,
+	/// The page of vulnerabilities on screen, loaded one page per query
 	pub displayed_vulnerabilities: Vec<Vulnerability>,
-	pub error_message: Option<String>,
+	pub toasts: Toasts,
+	/// Performance overlay, toggled by a hidden shortcut
+	pub profiler: Option<Profiler>,
 	pub search_query: String,
 	pub current_page: usize,
+	/// Where each page loaded in order from the first ends, so Next and Prev seek
+	/// instead of counting rows
+	pub page_cursors: Vec<PageCursor>,
 	pub total_pages: usize,
 	pub loading: bool,
 	pub sort_field: SortField,
 	pub sort_ascending: bool,
 	pub filter_severity: FilterSeverity,
+	pub filter_status: FilterStatus,
+	pub filter_weakness: FilterWeakness,
+	pub quick_filters: BTreeSet<QuickFilter>,
+	/// Matches of each quick filter within the rest of the current filters
+	pub quick_filter_counts: Vec<(QuickFilter, i64)>,
 	pub show_statistics: bool,
+	pub row_tint: RowTint,
+	pub list_layout: ListLayout,
+	pub table_columns: TableColumns,
+	pub theme_choice: ThemeChoice,
+	pub detail_layout: DetailLayout,
+	/// Severities use the color-blind safe palette and are marked with shapes
+	pub color_blind_safe: bool,
+	/// Days after which a robot's inventory is flagged as stale
+	pub inventory_max_age_days: u32,
+	/// Whether the desktop was set to dark on startup, for the System theme
+	pub system_dark: bool,
+	pub risky_software: Vec<RiskySoftware>,
+	pub enrichment_progress: Option<EnrichmentProgress>,
+	/// Counts for the statistics panel over the vulnerabilities matching the filters
+	pub statistics: Option<StatisticsReport>,
+	/// Recorded exposure metrics plotted on the Trends tab
+	pub trends: Option<FleetTrends>,
+	/// Role of this installation; viewers get a read-only interface
+	pub role: Role,
+	/// Latest event of a running import or sync, cleared when it finishes
+	pub progress: Option<Progress>,
+	/// Cancel was clicked and the running operation has not stopped yet
+	pub cancel_requested: bool,
+	/// Storage found on startup to be worth compacting, until compacted or dismissed
+	pub compaction_offer: Option<StorageStats>,
+	/// Maintenance dialog, shown over the tabs while open
+	pub maintenance: Option<MaintenanceStatus>,
+	/// About dialog, shown over the tabs while open
+	pub about_open: bool,
+	pub update_status: UpdateStatus,
+	/// Data sources with their schedules and last runs, shown in the status bar
+	pub data_sources: Vec<DataSource>,
+	/// Data sources dialog, shown over the tabs while open
+	pub data_sources_view: Option<DataSourcesView>,
+	/// When the application started, from which the first syncs are scheduled
+	pub started_at: DateTime<Utc>,
+	/// Set while a sync started by hand runs
+	pub syncing: bool,
+	/// Recently deleted robots and vulnerabilities, shown over the tabs while open
+	pub trash: Option<Vec<DeletedItem>>,
+	/// Audit log dialog, shown over the tabs while open
+	pub audit: Option<AuditLogView>,
+	/// Import history dialog, shown over the tabs while open
+	pub import_runs: Option<Vec<ImportRun>>,
+	/// Skipped rows of the run picked in the import history, by run ID
+	pub import_skipped: Option<(i64, Vec<SkippedRow>)>,
+	/// What the syncs changed, newest first, shown over the tabs while open
+	pub whats_new: Option<Vec<SyncDelta>>,
+	/// CSV import dialog, shown over the tabs while open
+	pub csv_import: Option<CsvImportView>,
+	/// Shown as a banner while the NVD is down
+	pub nvd_health: NvdHealth,
+	pub software_filter: Option<RiskySoftware>,
 	pub selected_vulnerability: Option<usize>,
+	/// Card whose menu of copy and open actions is shown, by index in the displayed list
+	pub card_menu: Option<usize>,
+	/// CVE ID from a deep link, opened once the first page has loaded
+	pub pending_open: Option<String>,
 	pub scroll_offset: f32,
-	pub last_loaded_page: usize,
 	pub software_version_input: String,
+	pub triage_status: TriageStatus,
+	pub triage_assignee: String,
+	pub triage_justification: String,
+	pub triage_approver: String,
+	/// Expiry of a risk decision as typed, YYYY-MM-DD or empty for none
+	pub triage_expires: String,
+	/// Hand edits of the selected vulnerability, while its fields are being edited
+	pub field_edit: Option<FieldEditForm>,
+	/// Set while missing fields are requested from the NVD
+	pub nvd_fetching: bool,
+
+	// Notes of the record shown in a detail view
+	pub notes_entity: Option<(NoteEntity, i64)>,
+	pub notes: Vec<Note>,
+	pub note_input: String,
+	pub editing_note_id: Option<i64>,
+
+	/// References of the selected vulnerability
+	pub references: Vec<Reference>,
+	/// Vulnerabilities in the same component as the selected one
+	pub related: Vec<RelatedVulnerability>,
+	/// Compliance controls the selected vulnerability is mapped to
+	pub linked_controls: Vec<ComplianceControl>,
+	/// Every known compliance control, offered for mapping
+	pub compliance_controls: Vec<ComplianceControl>,
+	/// Whether a ticketing system is configured to raise remediation tickets in
+	pub ticketing_enabled: bool,
+	/// Remediation ticket of the selected vulnerability
+	pub ticket: Option<Ticket>,
+	/// Set while a ticket is being raised
+	pub ticket_creating: bool,
+
+	/// Relationship graph shown over the detail view it was opened from
+	pub graph: Option<RelationshipGraph>,
 
 	// Robot-related fields
 	pub current_tab: Tab,
 	pub robots: Vec<Robot>,
 	pub robot_form: RobotForm,
+	/// Manufacturer and vendor spellings in use, offered while filling in the robot form
+	pub vendor_names: Vec<String>,
 	pub robot_filter: String,
 	pub robot_filter_type: RobotFilterType,
+	pub robot_sort: RobotSort,
 	pub selected_robot: Option<usize>,
 	pub editing_robot_id: Option<i32>,
 	pub showing_robot_form: bool,
 	pub filtered_robots: Vec<Robot>,
+	/// Vulnerabilities affecting the selected robot's software, with how long it is exposed
+	pub robot_vulnerabilities: Vec<RobotExposure>,
+	/// Commissioning checklist of the selected robot
+	pub robot_checklist: Vec<ChecklistEntry>,
+	/// Path of the robot inventory file to import, as typed
+	pub robot_import_path: String,
+	/// Rows the last robot import skipped, until dismissed
+	pub robot_import_errors: Vec<RowError>,
+
+	// Software tab
+	pub software_versions: Vec<VersionMetadata>,
+	pub version_filter: String,
+	pub selected_versions: BTreeSet<i64>,
+	pub version_editor: VersionEditor,
 }
 
 impl AppState {
-	pub fn new(pool: Arc<SqlitePool>) -> Self {
+	pub fn new(pool: Arc<SqlitePool>, workspace: String) -> Self {
+		let workspaces = Workspaces::default().list().unwrap_or_else(|_| vec![workspace.clone()]);
 		Self {
 			// Database connection
 			pool,
+			workspace,
+			workspaces,
 
 			// Vulnerability-related initialization
-			vulnerabilities: Vec::new(),
 			displayed_vulnerabilities: Vec::new(),
-			error_message: None,
+			toasts: Toasts::default(),
+			profiler: None,
 			search_query: String::new(),
 			current_page: 0,
+			page_cursors: Vec::new(),
 			total_pages: 0,
 			loading: true,
 			sort_field: SortField::None,
 			sort_ascending: true,
 			filter_severity: FilterSeverity::All,
+			filter_status: FilterStatus::All,
+			filter_weakness: FilterWeakness::All,
+			quick_filters: BTreeSet::new(),
+			quick_filter_counts: Vec::new(),
 			show_statistics: false,
+			row_tint: RowTint::default(),
+			list_layout: ListLayout::default(),
+			table_columns: TableColumns::default(),
+			theme_choice: ThemeChoice::default(),
+			detail_layout: DetailLayout::default(),
+			color_blind_safe: false,
+			inventory_max_age_days: DEFAULT_INVENTORY_MAX_AGE_DAYS,
+			system_dark: appearance::system_prefers_dark(),
+			risky_software: Vec::new(),
+			enrichment_progress: None,
+			statistics: None,
+			trends: None,
+			role: access::current_role(),
+			progress: None,
+			cancel_requested: false,
+			compaction_offer: None,
+			maintenance: None,
+			about_open: false,
+			update_status: UpdateStatus::default(),
+			data_sources: Vec::new(),
+			data_sources_view: None,
+			started_at: Utc::now(),
+			syncing: false,
+			trash: None,
+			audit: None,
+			import_runs: None,
+			import_skipped: None,
+			whats_new: None,
+			csv_import: None,
+			nvd_health: NvdHealth::default(),
+			software_filter: None,
 			selected_vulnerability: None,
+			card_menu: None,
+			pending_open: None,
 			scroll_offset: 0.0,
-			last_loaded_page: 0,
+			triage_status: TriageStatus::Open,
+			triage_assignee: String::new(),
+			triage_justification: String::new(),
+			triage_approver: String::new(),
+			triage_expires: String::new(),
+			field_edit: None,
+			nvd_fetching: false,
+
+			notes_entity: None,
+			notes: Vec::new(),
+			note_input: String::new(),
+			editing_note_id: None,
+
+			references: Vec::new(),
+			related: Vec::new(),
+			linked_controls: Vec::new(),
+			compliance_controls: Vec::new(),
+			ticketing_enabled: false,
+			ticket: None,
+			ticket_creating: false,
+			graph: None,
 
 			// Robot-related initialization
 			current_tab: Tab::Vulnerabilities,
 			robots: Vec::new(),
 			filtered_robots: Vec::new(),
+			vendor_names: Vec::new(),
 			robot_form: RobotForm {
 				name: String::new(),
 				manufacturer: String::new(),
+				model: String::new(),
+				firmware_version: String::new(),
+				os: String::new(),
+				ros_distro: String::new(),
 				specifications: String::new(),
+				operational_note: String::new(),
+				criticality: Criticality::default(),
 				software_versions: Vec::new(),
 			},
 			robot_filter: String::new(),
 			robot_filter_type: RobotFilterType::All,
+			robot_sort: RobotSort::Name,
 			selected_robot: None,
 			editing_robot_id: None,
 			showing_robot_form: false,
 			software_version_input: String::new(),
+			robot_vulnerabilities: Vec::new(),
+			robot_checklist: Vec::new(),
+			robot_import_path: String::new(),
+			robot_import_errors: Vec::new(),
+
+			software_versions: Vec::new(),
+			version_filter: String::new(),
+			selected_versions: BTreeSet::new(),
+			version_editor: VersionEditor::default(),
+		}
+	}
+
+	/// Software versions matching the filter on the Software tab
+	pub fn shown_versions(&self) -> Vec<&VersionMetadata> {
+		let filter = self.version_filter.trim().to_lowercase();
+		self.software_versions
+			.iter()
+			.filter(|version| filter.is_empty() || version.label().to_lowercase().contains(&filter))
+			.collect()
+	}
+
+	/// Replaces the version selection and resets the editor to match it
+	pub fn select_versions(&mut self, selected: BTreeSet<i64>) {
+		self.selected_versions = selected;
+		let versions: Vec<&VersionMetadata> = self.software_versions
+			.iter()
+			.filter(|version| self.selected_versions.contains(&version.version_id))
+			.collect();
+		self.version_editor = VersionEditor::for_selection(&versions);
+	}
+
+	pub fn vulnerability_query(&self) -> VulnerabilityQuery {
+		VulnerabilityQuery {
+			search: self.search_query.clone(),
+			sort_field: self.sort_field.clone(),
+			sort_ascending: self.sort_ascending,
+			filter_severity: self.filter_severity.clone(),
+			filter_status: self.filter_status.clone(),
+			filter_weakness: self.filter_weakness,
+			version_id: self.software_filter.as_ref().map(|s| s.version_id),
+			quick_filters: self.quick_filters.clone(),
+		}
+	}
+
+	/// Loads the triage fields of the selected vulnerability into the detail form
+	pub fn select_vulnerability(&mut self, idx: usize) {
+		self.selected_vulnerability = Some(idx);
+		self.references.clear();
+		self.related.clear();
+		self.linked_controls.clear();
+		self.ticket = None;
+		self.field_edit = None;
+		if let Some(vuln) = self.displayed_vulnerabilities.get(idx) {
+			self.triage_status = vuln.status;
+			self.triage_assignee = vuln.assigned_to.clone().unwrap_or_default();
+			let acceptance = vuln.risk_acceptance.clone().unwrap_or_default();
+			self.triage_justification = acceptance.justification.unwrap_or_default();
+			self.triage_approver = acceptance.approved_by.unwrap_or_default();
+			self.triage_expires = acceptance.expires_on.map(|d| d.to_string()).unwrap_or_default();
+			self.set_notes_entity(vuln.vulnerability_id.map(|id| (NoteEntity::Vulnerability, id)));
+		}
+	}
+
+	/// Switches the notes panel to another record, discarding any unsaved input
+	pub fn set_notes_entity(&mut self, entity: Option<(NoteEntity, i64)>) {
+		self.notes_entity = entity;
+		self.notes.clear();
+		self.note_input.clear();
+		self.editing_note_id = None;
+	}
+
+	/// Risk decision details entered in the triage form
+	pub fn triage_acceptance(&self) -> Result<RiskAcceptance, String> {
+		let text = |value: &str| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+		let expires_on = match text(&self.triage_expires) {
+			Some(date) => Some(
+				NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+					.map_err(|_| format!("Expiry '{}' is not a date like 2025-06-30", date))?,
+			),
+			None => None,
+		};
+		Ok(RiskAcceptance {
+			justification: text(&self.triage_justification),
+			approved_by: text(&self.triage_approver),
+			accepted_at: None,
+			expires_on,
+		})
+	}
+
+	/// Applies a saved triage change to the loaded page
+	pub fn apply_triage(
+		&mut self,
+		vulnerability_id: i64,
+		status: TriageStatus,
+		assigned_to: Option<String>,
+		risk_acceptance: Option<RiskAcceptance>,
+	) {
+		for vuln in self.displayed_vulnerabilities.iter_mut()
+			.filter(|v| v.vulnerability_id == Some(vulnerability_id))
+		{
+			vuln.status = status;
+			vuln.assigned_to = assigned_to.clone();
+			vuln.risk_acceptance = risk_acceptance.clone();
 		}
 	}
 
-	pub fn update_displayed_vulnerabilities(&mut self) {
-		let start = self.current_page * DISPLAY_PAGE_SIZE;
-		let end = (start + DISPLAY_PAGE_SIZE).min(self.vulnerabilities.len());
-		self.displayed_vulnerabilities = self.vulnerabilities[start..end].to_vec();
-		self.total_pages = (self.vulnerabilities.len() + DISPLAY_PAGE_SIZE - 1) / DISPLAY_PAGE_SIZE;
+	/// Replaces the loaded copies of a vulnerability after it was edited or unlocked
+	pub fn apply_fields(&mut self, updated: Vulnerability) {
+		for vuln in self.displayed_vulnerabilities.iter_mut()
+			.filter(|v| v.vulnerability_id == updated.vulnerability_id)
+		{
+			*vuln = updated.clone();
+		}
 	}
 
 	pub fn show_robot_form(&mut self) {
@@ -96,7 +418,13 @@ impl AppState {
 		self.robot_form = RobotForm {
 			name: String::new(),
 			manufacturer: String::new(),
+			model: String::new(),
+			firmware_version: String::new(),
+			os: String::new(),
+			ros_distro: String::new(),
 			specifications: String::new(),
+			operational_note: String::new(),
+			criticality: Criticality::default(),
 			software_versions: Vec::new(),
 		};
 		self.editing_robot_id = None;
@@ -106,13 +434,30 @@ impl AppState {
 		self.robot_form = RobotForm {
 			name: robot.name.clone(),
 			manufacturer: robot.manufacturer.clone().unwrap_or_default(),
+			model: robot.model.clone().unwrap_or_default(),
+			firmware_version: robot.firmware_version.clone().unwrap_or_default(),
+			os: robot.os.clone().unwrap_or_default(),
+			ros_distro: robot.ros_distro.clone().unwrap_or_default(),
 			specifications: robot.specifications.clone().unwrap_or_default(),
+			operational_note: robot.operational_note.clone().unwrap_or_default(),
+			criticality: robot.criticality,
 			software_versions: Vec::new(),
 		};
 		self.editing_robot_id = robot.robot_id;
 		self.showing_robot_form = true;
 	}
 
+	/// Orders the robots by the chosen sort and reapplies the filter
+	pub fn sort_robots(&mut self) {
+		match self.robot_sort {
+			RobotSort::Name => self.robots.sort_by_key(|robot| robot.name.to_lowercase()),
+			RobotSort::Risk => self.robots.sort_by(|a, b| {
+				b.risk_score.unwrap_or_default().total_cmp(&a.risk_score.unwrap_or_default())
+			}),
+		}
+		self.filter_robots();
+	}
+
 	pub fn filter_robots(&mut self) {
 		self.filtered_robots = self.robots.clone();
 		let filter = self.robot_filter.to_lowercase();
@@ -126,7 +471,8 @@ impl AppState {
 				RobotFilterType::All => {
 					robot.name.to_lowercase().contains(&filter) ||
 						robot.manufacturer.as_ref().map_or(false, |m| m.to_lowercase().contains(&filter)) ||
-						robot.specifications.as_ref().map_or(false, |s| s.to_lowercase().contains(&filter))
+						robot.specifications.as_ref().is_some_and(|s| s.to_lowercase().contains(&filter)) ||
+						robot.operational_note.as_ref().is_some_and(|n| n.to_lowercase().contains(&filter))
 				},
 				RobotFilterType::ByManufacturer => {
 					robot.manufacturer
@@ -151,35 +497,59 @@ impl AppState {
 		});
 	}
 
-	pub fn sort_robots(&mut self) {
-		match self.sort_field {
-			SortField::RobotName => {
-				self.filtered_robots.sort_by(|a, b| {
-					if self.sort_ascending {
-						a.name.cmp(&b.name)
-					} else {
-						b.name.cmp(&a.name)
-					}
-				});
-			},
-			SortField::Manufacturer => {
-				self.filtered_robots.sort_by(|a, b| {
-					let a_manufacturer = a.manufacturer.as_deref().unwrap_or("");
-					let b_manufacturer = b.manufacturer.as_deref().unwrap_or("");
-					if self.sort_ascending {
-						a_manufacturer.cmp(b_manufacturer)
-					} else {
-						b_manufacturer.cmp(a_manufacturer)
-					}
-				});
-			},
-			_ => (), // Other sort fields don't apply to robots
+	/// File name and print-optimized HTML of the detail view currently open, if any
+	pub fn print_layout(&self) -> Option<(String, String)> {
+		match self.current_tab {
+			Tab::Vulnerabilities => {
+				let vuln = self.displayed_vulnerabilities.get(self.selected_vulnerability?)?;
+				Some((
+					format!("{}.html", vuln.cve_id),
+					print::vulnerability_detail_html(vuln, &self.notes),
+				))
+			}
+			Tab::RobotInventory => {
+				let robot = self.get_displayed_robots().get(self.selected_robot?)?;
+				Some((
+					format!("robot-{}.html", robot.robot_id.unwrap_or_default()),
+					print::robot_detail_html(robot, &self.robot_form.software_versions, &self.notes),
+				))
+			}
+			Tab::Software | Tab::Trends => None,
 		}
 	}
 
+	/// Records held by the loaded lists, shown in the performance overlay
+	pub fn rows_in_memory(&self) -> usize {
+		self.displayed_vulnerabilities.len()
+			+ self.risky_software.len()
+			+ self.notes.len()
+			+ self.references.len()
+			+ self.related.len()
+			+ self.robots.len()
+			+ self.filtered_robots.len()
+			+ self.robot_vulnerabilities.len()
+			+ self.robot_checklist.len()
+			+ self.software_versions.len()
+			+ self.trash.as_ref().map_or(0, Vec::len)
+			+ self.import_runs.as_ref().map_or(0, Vec::len)
+			+ self.whats_new.as_ref().map_or(0, Vec::len)
+			+ self.audit.as_ref().map_or(0, |audit| audit.entries.len())
+			+ self.graph.as_ref().map_or(0, |graph| graph.nodes.len() + graph.edges.len())
+	}
+
+	/// The theme the window is drawn with
+	pub fn theme(&self) -> Theme {
+		self.theme_choice.theme(self.system_dark)
+	}
+
 	pub fn clear_selection(&mut self) {
+		self.set_notes_entity(None);
+		self.graph = None;
+		self.field_edit = None;
 		self.selected_vulnerability = None;
 		self.selected_robot = None;
+		self.robot_vulnerabilities.clear();
+		self.robot_checklist.clear();
 		self.editing_robot_id = None;
 		self.showing_robot_form = false;
 	}
@@ -188,7 +558,7 @@ impl AppState {
 		if self.robot_form.name.trim().is_empty() ||
 			self.robot_form.manufacturer.trim().is_empty() ||
 			self.robot_form.specifications.trim().is_empty() {
-			self.error_message = Some("All fields are required".to_string());
+			self.toasts.error("All fields are required");
 			return false;
 		}
 		true
@@ -208,10 +578,6 @@ impl AppState {
 		}
 	}
 
-	pub fn update_error_message(&mut self, message: Option<String>) {
-		self.error_message = message;
-	}
-
 	pub fn is_form_valid(&self) -> bool {
 		!self.robot_form.name.trim().is_empty() &&
 			!self.robot_form.manufacturer.trim().is_empty() &&
@@ -223,7 +589,6 @@ impl AppState {
 		self.editing_robot_id = None;
 		self.selected_robot = None;
 		self.clear_robot_form();
-		self.error_message = None;
 	}
 
 	pub fn handle_robot_edit(&mut self, robot_id: i32) -> bool {
@@ -237,7 +602,7 @@ impl AppState {
 			self.set_robot_form(&robot);
 			true
 		} else {
-			self.error_message = Some("Robot not found".to_string());
+			self.toasts.error("Robot not found");
 			false
 		}
 	}
@@ -0,0 +1,192 @@
+use super::formatters::{format_date, format_muted, format_severity, format_severity_label};
+use super::state::AppState;
+use super::types::{Message, TableColumn};
+use crate::models::vulnerability::Vulnerability;
+use iced::{
+	mouse, theme,
+	widget::{
+		button, canvas::{self, event, Canvas, Frame, Geometry, Path, Stroke},
+		column, container, row, scrollable, Column, Row, Text,
+	},
+	Alignment, Element, Length, Point, Rectangle, Renderer, Theme,
+};
+
+/// Width of the draggable divider right of each header
+const DIVIDER_WIDTH: f32 = 8.0;
+const HEADER_HEIGHT: f32 = 28.0;
+
+pub trait TableViewRenderer {
+	fn vulnerability_table(&self) -> Element<'_, Message>;
+}
+
+impl TableViewRenderer for AppState {
+	/// The loaded vulnerabilities as table rows under sortable, resizable headers
+	fn vulnerability_table(&self) -> Element<'_, Message> {
+		let header = Row::with_children(TableColumn::ALL.iter().map(|&column| {
+			let field = column.sort_field();
+			let arrow = match (self.sort_field == field, self.sort_ascending) {
+				(true, true) => " ↑",
+				(true, false) => " ↓",
+				(false, _) => "",
+			};
+			row![
+				button(Text::new(format!("{}{}", column.label(), arrow)).size(14))
+					.on_press(Message::SortHeaderClicked(field))
+					.style(theme::Button::Text)
+					.padding([4, 6])
+					.width(Length::Fill),
+				Canvas::new(ColumnDivider { column })
+					.width(Length::Fixed(DIVIDER_WIDTH))
+					.height(Length::Fixed(HEADER_HEIGHT)),
+			]
+				.width(Length::Fixed(self.table_columns.width(column)))
+				.align_items(Alignment::Center)
+				.into()
+		}));
+
+		let rows = Column::with_children(
+			self.displayed_vulnerabilities
+				.iter()
+				.enumerate()
+				.map(|(idx, vuln)| self.table_row(vuln, idx)),
+		)
+			.spacing(2);
+
+		column![
+			container(header).style(theme::Container::Box).padding([0, 10]),
+			scrollable(container(rows).width(Length::Fill).padding([0, 10]))
+				.on_scroll(|viewport| Message::ScrollChanged(viewport.relative_offset().y))
+				.height(Length::Fill),
+		]
+			.spacing(4)
+			.into()
+	}
+}
+
+impl AppState {
+	fn table_row<'a>(&self, vuln: &'a Vulnerability, idx: usize) -> Element<'a, Message> {
+		let theme = self.theme();
+		let cell = |column: TableColumn, content: Text<'a>| {
+			container(content.size(14))
+				.width(Length::Fixed(self.table_columns.width(column)))
+				.padding([0, 6])
+		};
+		let cvss = vuln.cvss_score.map_or_else(|| "-".to_string(), |score| format!("{:.1}", score));
+
+		let is_selected = self.selected_vulnerability == Some(idx);
+		let style = match self.row_tint.background(vuln, &theme) {
+			_ if is_selected => theme::Container::Transparent,
+			Some(tint) => (move |theme: &Theme| container::Appearance {
+				background: Some(tint.into()),
+				text_color: Some(theme.palette().text),
+				..Default::default()
+			}).into(),
+			None => theme::Container::Transparent,
+		};
+
+		button(
+			container(
+				row![
+					cell(TableColumn::Cve, Text::new(&vuln.cve_id)),
+					cell(
+						TableColumn::Severity,
+						Text::new(format_severity_label(&vuln.severity))
+							.style(theme::Text::Color(format_severity(&vuln.severity, &theme))),
+					),
+					cell(TableColumn::Cvss, Text::new(cvss)),
+					cell(
+						TableColumn::Published,
+						Text::new(format_date(vuln.published_date)).style(theme::Text::Color(format_muted(&theme))),
+					),
+					cell(TableColumn::Status, Text::new(vuln.status.as_str())),
+				]
+					.align_items(Alignment::Center),
+			)
+				.width(Length::Fill)
+				.padding([4, 0])
+				.style(style),
+		)
+			.style(if is_selected {
+				theme::Button::Primary
+			} else {
+				theme::Button::Text
+			})
+			.on_press(Message::VulnerabilitySelected(idx))
+			.padding(0)
+			.width(Length::Fill)
+			.into()
+	}
+}
+
+/// Handle between two headers; dragging it resizes the column on its left
+struct ColumnDivider {
+	column: TableColumn,
+}
+
+impl canvas::Program<Message> for ColumnDivider {
+	/// Horizontal cursor position while dragging
+	type State = Option<f32>;
+
+	fn update(
+		&self,
+		state: &mut Self::State,
+		event: canvas::Event,
+		bounds: Rectangle,
+		cursor: mouse::Cursor,
+	) -> (event::Status, Option<Message>) {
+		let canvas::Event::Mouse(event) = event else {
+			return (event::Status::Ignored, None);
+		};
+		match (event, *state) {
+			(mouse::Event::ButtonPressed(mouse::Button::Left), None) => match cursor.position_over(bounds) {
+				Some(position) => {
+					*state = Some(position.x);
+					(event::Status::Captured, None)
+				}
+				None => (event::Status::Ignored, None),
+			},
+			// The drag continues when the cursor leaves the handle
+			(mouse::Event::CursorMoved { position }, Some(last)) => {
+				*state = Some(position.x);
+				(event::Status::Captured, Some(Message::TableColumnResized(self.column, position.x - last)))
+			}
+			(mouse::Event::ButtonReleased(mouse::Button::Left), Some(_)) => {
+				*state = None;
+				(event::Status::Captured, Some(Message::TableColumnResizeFinished))
+			}
+			_ => (event::Status::Ignored, None),
+		}
+	}
+
+	fn draw(
+		&self,
+		state: &Self::State,
+		renderer: &Renderer,
+		theme: &Theme,
+		bounds: Rectangle,
+		cursor: mouse::Cursor,
+	) -> Vec<Geometry> {
+		let mut frame = Frame::new(renderer, bounds.size());
+		let active = state.is_some() || cursor.is_over(bounds);
+		let color = if active { theme.palette().primary } else { format_muted(theme) };
+		let x = bounds.width / 2.0;
+		frame.stroke(
+			&Path::line(Point::new(x, 4.0), Point::new(x, bounds.height - 4.0)),
+			Stroke::default().with_color(color).with_width(if active { 2.0 } else { 1.0 }),
+		);
+		vec![frame.into_geometry()]
+	}
+
+	fn mouse_interaction(
+		&self,
+		state: &Self::State,
+		bounds: Rectangle,
+		cursor: mouse::Cursor,
+	) -> mouse::Interaction {
+		if state.is_some() || cursor.is_over(bounds) {
+			mouse::Interaction::ResizingHorizontally
+		} else {
+			mouse::Interaction::default()
+		}
+	}
+}
@@ -0,0 +1,170 @@
+use super::state::AppState;
+use super::types::Message;
+use super::formatters::{format_error, format_success, format_warning};
+use iced::{
+	theme,
+	widget::{button, container, row, Column, Space, Text},
+	Alignment, Color, Element, Length, Theme,
+};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+	Success,
+	Warning,
+	Error,
+}
+
+impl ToastLevel {
+	/// How long a toast stays up unless dismissed; errors stay longest
+	fn lifetime(&self) -> Duration {
+		match self {
+			ToastLevel::Success => Duration::from_secs(4),
+			ToastLevel::Warning => Duration::from_secs(6),
+			ToastLevel::Error => Duration::from_secs(10),
+		}
+	}
+
+	fn color(&self, theme: &Theme) -> Color {
+		match self {
+			ToastLevel::Success => format_success(theme),
+			ToastLevel::Warning => format_warning(theme),
+			ToastLevel::Error => format_error(theme),
+		}
+	}
+}
+
+/// A short-lived notification, optionally with one action such as Undo
+#[derive(Debug, Clone)]
+pub struct Toast {
+	pub id: u64,
+	pub level: ToastLevel,
+	pub message: String,
+	pub action: Option<(String, Message)>,
+	expires_at: Instant,
+}
+
+/// Toasts on screen, oldest first
+#[derive(Debug, Default)]
+pub struct Toasts {
+	items: Vec<Toast>,
+	next_id: u64,
+}
+
+impl Toasts {
+	pub fn success(&mut self, message: impl Into<String>) -> u64 {
+		self.push(ToastLevel::Success, message.into(), None)
+	}
+
+	pub fn warning(&mut self, message: impl Into<String>) -> u64 {
+		self.push(ToastLevel::Warning, message.into(), None)
+	}
+
+	pub fn error(&mut self, message: impl Into<String>) -> u64 {
+		self.push(ToastLevel::Error, message.into(), None)
+	}
+
+	/// A toast with a button that sends `action`, e.g. Undo. It stays up twice as long
+	/// to leave time to react.
+	pub fn with_action(&mut self, level: ToastLevel, message: impl Into<String>, label: &str, action: Message) -> u64 {
+		self.push(level, message.into(), Some((label.to_string(), action)))
+	}
+
+	fn push(&mut self, level: ToastLevel, message: String, action: Option<(String, Message)>) -> u64 {
+		let lifetime = if action.is_some() { level.lifetime() * 2 } else { level.lifetime() };
+		self.next_id += 1;
+		self.items.push(Toast {
+			id: self.next_id,
+			level,
+			message,
+			action,
+			expires_at: Instant::now() + lifetime,
+		});
+		self.next_id
+	}
+
+	/// Remove a toast, returning it so its action can run
+	pub fn dismiss(&mut self, id: u64) -> Option<Toast> {
+		let index = self.items.iter().position(|toast| toast.id == id)?;
+		Some(self.items.remove(index))
+	}
+
+	pub fn expire(&mut self, now: Instant) {
+		self.items.retain(|toast| toast.expires_at > now);
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+		self.items.iter()
+	}
+}
+
+pub trait ToastViewRenderer {
+	fn toast_stack(&self) -> Element<'_, Message>;
+}
+
+impl ToastViewRenderer for AppState {
+	fn toast_stack(&self) -> Element<'_, Message> {
+		if self.toasts.is_empty() {
+			return Space::with_height(Length::Shrink).into();
+		}
+
+		Column::with_children(self.toasts.iter().map(|toast| {
+			let mut content = row![
+				Text::new(&toast.message)
+					.size(15)
+					.style(theme::Text::Color(toast.level.color(&self.theme())))
+					.width(Length::Fill),
+			]
+				.spacing(10)
+				.align_items(Alignment::Center);
+
+			if let Some((label, _)) = &toast.action {
+				content = content.push(
+					button(Text::new(label.as_str()).size(14))
+						.on_press(Message::ToastActionClicked(toast.id))
+						.style(theme::Button::Primary)
+						.padding(5),
+				);
+			}
+			content = content.push(
+				button(Text::new("Dismiss").size(14))
+					.on_press(Message::ToastDismissed(toast.id))
+					.style(theme::Button::Secondary)
+					.padding(5),
+			);
+
+			container(content)
+				.style(theme::Container::Box)
+				.padding(10)
+				.width(Length::Fill)
+				.into()
+		}))
+			.spacing(5)
+			.into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_toasts() {
+		let mut toasts = Toasts::default();
+		let saved = toasts.success("Saved");
+		let deleted = toasts.with_action(ToastLevel::Success, "Note deleted", "Undo", Message::RefreshData);
+		assert_ne!(saved, deleted);
+
+		toasts.expire(Instant::now() + Duration::from_secs(5));
+		assert_eq!(toasts.iter().map(|t| t.id).collect::<Vec<_>>(), vec![deleted]);
+
+		let toast = toasts.dismiss(deleted).unwrap();
+		assert!(matches!(toast.action, Some((_, Message::RefreshData))));
+		assert!(toasts.is_empty());
+		assert!(toasts.dismiss(deleted).is_none());
+	}
+}
@@ -0,0 +1,79 @@
+use super::state::AppState;
+use super::types::Message;
+use super::formatters::format_muted;
+use crate::models::trash::{DeletedItem, TRASH_RETENTION_DAYS};
+use crate::utils::time;
+use iced::{
+	theme,
+	widget::{button, column, container, row, scrollable, Column, Text},
+	Alignment, Element, Length,
+};
+
+pub trait TrashViewRenderer {
+	fn trash_dialog<'a>(&'a self, items: &'a [DeletedItem]) -> Element<'a, Message>;
+}
+
+impl TrashViewRenderer for AppState {
+	fn trash_dialog<'a>(&'a self, items: &'a [DeletedItem]) -> Element<'a, Message> {
+		let can_edit = self.role.can_edit();
+
+		let list: Element<Message> = if items.is_empty() {
+			Text::new("Nothing was deleted recently").size(16).into()
+		} else {
+			scrollable(
+				Column::with_children(items.iter().map(|item| {
+					row![
+						Text::new(item.kind.to_string()).size(14).width(Length::Fixed(110.0)),
+						Text::new(&item.label).size(16).width(Length::Fill),
+						Text::new(format!(
+							"Deleted {}, purged after {}",
+							time::format_local(item.deleted_at),
+							time::format_local(item.purged_after())
+						))
+							.size(14)
+							.style(theme::Text::Color(format_muted(&self.theme()))),
+						button(Text::new("Restore").size(14))
+							.on_press_maybe(can_edit.then_some(Message::RestoreClicked(item.kind, item.id)))
+							.style(theme::Button::Primary)
+							.padding(5),
+						button(Text::new("Delete Forever").size(14))
+							.on_press_maybe(can_edit.then_some(Message::PurgeClicked(item.kind, item.id)))
+							.style(theme::Button::Destructive)
+							.padding(5),
+					]
+						.spacing(15)
+						.align_items(Alignment::Center)
+						.into()
+				}))
+					.spacing(8),
+			)
+				.height(Length::Fill)
+				.into()
+		};
+
+		container(
+			column![
+				row![
+					Text::new("Recently Deleted").size(28).width(Length::Fill),
+					button(Text::new("Close").size(16))
+						.on_press(Message::TrashClosed)
+						.style(theme::Button::Destructive)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				Text::new(format!(
+					"Deleted robots and vulnerabilities can be restored for {} days before they are removed for good.",
+					TRASH_RETENTION_DAYS
+				))
+					.size(14),
+				list,
+			]
+				.spacing(15),
+		)
+			.padding(20)
+			.width(Length::Fill)
+			.style(theme::Container::Box)
+			.into()
+	}
+}
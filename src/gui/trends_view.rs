@@ -0,0 +1,106 @@
+use super::charts::{chart_box, TrendLines, CHART_HEIGHT};
+use super::formatters::{format_muted, format_severity, format_warning};
+use super::state::AppState;
+use super::types::Message;
+use crate::models::trends::TrendSeries;
+use iced::{
+	theme,
+	widget::{column, container, row, scrollable, Canvas, Row, Text},
+	Color, Element, Length,
+};
+
+pub trait TrendsViewRenderer {
+	fn trends_view(&self) -> Element<'_, Message>;
+}
+
+impl TrendsViewRenderer for AppState {
+	/// Open critical vulnerabilities per robot criticality and the robot risk scores
+	/// over the recorded days
+	fn trends_view(&self) -> Element<'_, Message> {
+		let theme = self.theme();
+		let title = Text::new("Trends").size(30);
+		let Some(trends) = self.trends.as_ref() else {
+			return column![title, Text::new("Loading trends...").size(14)].spacing(20).into();
+		};
+		if trends.is_empty() {
+			return column![
+				title,
+				Text::new("No metrics recorded yet; the background update records them every hour while the application runs")
+					.style(theme::Text::Color(format_muted(&theme)))
+					.size(14),
+			]
+				.spacing(20)
+				.into();
+		}
+
+		let criticals: Vec<(&TrendSeries, Color)> = trends.open_criticals
+			.iter()
+			.map(|series| (series, format_severity(&series.label, &theme)))
+			.collect();
+		let risk = vec![
+			(&trends.mean_risk_score, theme.palette().primary),
+			(&trends.fleet_risk_index, format_warning(&theme)),
+		];
+		let total = trends.total_open_criticals();
+
+		let summary = container(
+			row![
+				summary_value("Open critical vulnerabilities", &total, 0),
+				summary_value("Mean robot risk score", &trends.mean_risk_score, 1),
+				summary_value("Fleet risk index", &trends.fleet_risk_index, 1),
+			]
+				.spacing(40),
+		)
+			.style(theme::Container::Box)
+			.padding(15)
+			.width(Length::Fill);
+
+		scrollable(
+			column![
+				title,
+				summary,
+				chart_box(
+					"Open Critical Vulnerabilities by Robot Criticality",
+					column![legend(&criticals), trend_chart(criticals)].spacing(8).into(),
+				),
+				chart_box("Robot Risk", column![legend(&risk), trend_chart(risk)].spacing(8).into()),
+			]
+				.spacing(20),
+		)
+			.into()
+	}
+}
+
+fn trend_chart<'a>(series: Vec<(&'a TrendSeries, Color)>) -> Element<'a, Message> {
+	Canvas::new(TrendLines { series })
+		.width(Length::Fill)
+		.height(Length::Fixed(CHART_HEIGHT))
+		.into()
+}
+
+fn legend<'a>(series: &[(&'a TrendSeries, Color)]) -> Element<'a, Message> {
+	Row::with_children(series.iter().map(|(series, color)| {
+		Text::new(format!("■ {}", series.label))
+			.style(theme::Text::Color(*color))
+			.size(14)
+			.into()
+	}))
+		.spacing(16)
+		.into()
+}
+
+/// The latest value of a series with how much it moved since the first recorded day
+fn summary_value<'a>(label: &'a str, series: &TrendSeries, decimals: usize) -> Element<'a, Message> {
+	let latest = series.points.last().map_or("-".to_string(), |point| format!("{:.*}", decimals, point.value));
+	let change = match (series.change(), series.points.first()) {
+		(Some(change), Some(first)) => format!("{:+.*} since {}", decimals, change, first.day),
+		_ => "No earlier value recorded yet".to_string(),
+	};
+	column![
+		Text::new(label).size(14),
+		Text::new(latest).size(32),
+		Text::new(change).size(12),
+	]
+		.spacing(4)
+		.into()
+}
@@ -1,11 +1,45 @@
-use crate::models::vulnerability::Vulnerability;
-use crate::models::robot::Robot;
+use crate::models::vulnerability::{LockedField, RelatedVulnerability, RiskAcceptance, TriageStatus, Vulnerability};
+use crate::repositories::vulnerability_repo::{QuickFilter, VulnerabilityPage};
+use crate::models::robot::{Criticality, Robot, RobotExposure};
+use crate::models::note::Note;
+use crate::models::reference::Reference;
+use crate::models::graph::{GraphCenter, RelationshipGraph};
+use crate::models::software::{RiskySoftware, VersionMetadata, VersionMetadataChange};
+use crate::models::interchange::SoftwareRef;
+use crate::models::enrichment::EnrichmentProgress;
+use crate::models::statistics::StatisticsReport;
+use crate::models::trends::FleetTrends;
+use crate::models::trash::{DeletedItem, DeletedKind};
+use crate::models::audit::{AuditEntity, AuditEntry};
+use crate::models::import_run::{ImportReport, ImportRun, RevertSummary, SkippedRow};
+use crate::models::csv_mapping::{CsvField, CsvMapping, MergePolicy};
+use crate::models::sync_delta::SyncDelta;
+use crate::models::commissioning::ChecklistEntry;
+use crate::models::compliance::ComplianceControl;
+use crate::models::data_source::DataSource;
+use crate::models::ticket::Ticket;
+use crate::models::weakness::WeaknessClass;
+use crate::utils::progress::Progress;
+use super::appearance::{DetailLayout, ThemeChoice};
+use super::formatters::{format_severity_background, format_status_background};
+use iced::{Color, Theme};
+use crate::utils::robot_import::RobotImportSummary;
+use crate::db::compaction::{CompactionMode, StorageStats};
+use crate::db::maintenance::{MaintenancePolicy, MaintenanceReport};
+use crate::utils::update_check::{AvailableUpdate, UpdateCheckSettings};
+use crate::models::nvd_health::NvdHealth;
+use crate::db::connection::SqlitePool;
+use std::collections::{BTreeMap, BTreeSet};
+use chrono::NaiveDate;
+use std::sync::Arc;
 use anyhow::Result;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Tab {
 	Vulnerabilities,
 	RobotInventory,
+	Software,
+	Trends,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -13,6 +47,8 @@ pub enum SortField {
 	CVE,
 	Severity,
 	Date,
+	Cvss,
+	Status,
 	None,
 	// Add robot-specific sort fields
 	RobotName,
@@ -25,6 +61,8 @@ impl std::fmt::Display for SortField {
 			SortField::CVE => write!(f, "CVE ID"),
 			SortField::Severity => write!(f, "Severity"),
 			SortField::Date => write!(f, "Date"),
+			SortField::Cvss => write!(f, "CVSS"),
+			SortField::Status => write!(f, "Status"),
 			SortField::None => write!(f, "No Sort"),
 			SortField::RobotName => write!(f, "Robot Name"),
 			SortField::Manufacturer => write!(f, "Manufacturer"),
@@ -51,6 +89,90 @@ impl std::fmt::Display for FilterSeverity {
 	}
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FilterStatus {
+	All,
+	Only(TriageStatus),
+}
+
+impl FilterStatus {
+	pub fn options() -> Vec<FilterStatus> {
+		std::iter::once(FilterStatus::All)
+			.chain(TriageStatus::ALL.iter().copied().map(FilterStatus::Only))
+			.collect()
+	}
+}
+
+impl std::fmt::Display for FilterStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			FilterStatus::All => write!(f, "All Statuses"),
+			FilterStatus::Only(status) => write!(f, "{}", status),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FilterWeakness {
+	All,
+	Only(WeaknessClass),
+}
+
+impl FilterWeakness {
+	pub fn options() -> Vec<FilterWeakness> {
+		std::iter::once(FilterWeakness::All)
+			.chain(WeaknessClass::ALL.iter().copied().map(FilterWeakness::Only))
+			.collect()
+	}
+}
+
+impl std::fmt::Display for FilterWeakness {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			FilterWeakness::All => write!(f, "All Weaknesses"),
+			FilterWeakness::Only(class) => write!(f, "{}", class),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterAuditEntity {
+	All,
+	Only(AuditEntity),
+}
+
+impl FilterAuditEntity {
+	pub fn options() -> Vec<FilterAuditEntity> {
+		std::iter::once(FilterAuditEntity::All)
+			.chain(AuditEntity::ALL.iter().copied().map(FilterAuditEntity::Only))
+			.collect()
+	}
+}
+
+impl std::fmt::Display for FilterAuditEntity {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			FilterAuditEntity::All => write!(f, "All Records"),
+			FilterAuditEntity::Only(entity) => write!(f, "{}", entity),
+		}
+	}
+}
+
+/// Search, filter and sort settings used to load the vulnerability list
+#[derive(Debug, Clone)]
+pub struct VulnerabilityQuery {
+	pub search: String,
+	pub sort_field: SortField,
+	pub sort_ascending: bool,
+	pub filter_severity: FilterSeverity,
+	pub filter_status: FilterStatus,
+	pub filter_weakness: FilterWeakness,
+	/// Restricts the list to the remediation queue of one software version
+	pub version_id: Option<i64>,
+	/// Active quick filter chips, all of which must match
+	pub quick_filters: BTreeSet<QuickFilter>,
+}
+
 #[derive(Debug, Clone)]
 pub enum OperationType {
 	Loading,
@@ -81,18 +203,446 @@ impl std::fmt::Display for RobotFilterType {
 	}
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RobotSort {
+	Name,
+	Risk,
+}
+
+impl RobotSort {
+	pub const ALL: [RobotSort; 2] = [RobotSort::Name, RobotSort::Risk];
+}
+
+impl std::fmt::Display for RobotSort {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			RobotSort::Name => write!(f, "Sort by Name"),
+			RobotSort::Risk => write!(f, "Sort by Risk"),
+		}
+	}
+}
+
+/// Whether vulnerabilities are listed as cards or as table rows
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum ListLayout {
+	#[default]
+	Cards,
+	Table,
+}
+
+impl ListLayout {
+	pub const ALL: [ListLayout; 2] = [ListLayout::Cards, ListLayout::Table];
+
+	pub fn from_setting(value: &str) -> Option<Self> {
+		match value.trim().to_ascii_lowercase().as_str() {
+			"cards" => Some(ListLayout::Cards),
+			"table" => Some(ListLayout::Table),
+			_ => None,
+		}
+	}
+
+	pub fn as_setting(&self) -> &'static str {
+		match self {
+			ListLayout::Cards => "cards",
+			ListLayout::Table => "table",
+		}
+	}
+}
+
+impl std::fmt::Display for ListLayout {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ListLayout::Cards => write!(f, "Cards"),
+			ListLayout::Table => write!(f, "Table"),
+		}
+	}
+}
+
+/// Column of the vulnerability table
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TableColumn {
+	Cve,
+	Severity,
+	Cvss,
+	Published,
+	Status,
+}
+
+impl TableColumn {
+	pub const ALL: [TableColumn; 5] = [
+		TableColumn::Cve,
+		TableColumn::Severity,
+		TableColumn::Cvss,
+		TableColumn::Published,
+		TableColumn::Status,
+	];
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			TableColumn::Cve => "CVE",
+			TableColumn::Severity => "Severity",
+			TableColumn::Cvss => "CVSS",
+			TableColumn::Published => "Published",
+			TableColumn::Status => "Status",
+		}
+	}
+
+	/// Sort field of the list when the column's header is clicked
+	pub fn sort_field(&self) -> SortField {
+		match self {
+			TableColumn::Cve => SortField::CVE,
+			TableColumn::Severity => SortField::Severity,
+			TableColumn::Cvss => SortField::Cvss,
+			TableColumn::Published => SortField::Date,
+			TableColumn::Status => SortField::Status,
+		}
+	}
+
+	fn index(&self) -> usize {
+		*self as usize
+	}
+}
+
+/// Widths of the table columns in pixels, adjusted by dragging the header dividers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TableColumns {
+	widths: [f32; TableColumn::ALL.len()],
+}
+
+impl TableColumns {
+	const MIN_WIDTH: f32 = 40.0;
+	const MAX_WIDTH: f32 = 600.0;
+
+	pub fn width(&self, column: TableColumn) -> f32 {
+		self.widths[column.index()]
+	}
+
+	pub fn resize(&mut self, column: TableColumn, delta: f32) {
+		let width = &mut self.widths[column.index()];
+		*width = (*width + delta).clamp(Self::MIN_WIDTH, Self::MAX_WIDTH);
+	}
+
+	/// Parses the comma-separated widths of a setting, `None` unless all are given
+	pub fn from_setting(value: &str) -> Option<Self> {
+		let widths: Vec<f32> = value
+			.split(',')
+			.map(|width| width.trim().parse::<f32>().ok().filter(|width| width.is_finite()))
+			.collect::<Option<_>>()?;
+		let mut columns = Self { widths: widths.try_into().ok()? };
+		for column in TableColumn::ALL {
+			columns.resize(column, 0.0);
+		}
+		Some(columns)
+	}
+
+	pub fn as_setting(&self) -> String {
+		self.widths.iter().map(|width| format!("{:.0}", width)).collect::<Vec<_>>().join(",")
+	}
+}
+
+impl Default for TableColumns {
+	fn default() -> Self {
+		Self { widths: [170.0, 110.0, 70.0, 110.0, 130.0] }
+	}
+}
+
+/// What the rows of the vulnerability list are tinted by
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum RowTint {
+	#[default]
+	Off,
+	Severity,
+	Status,
+}
+
+impl RowTint {
+	pub const ALL: [RowTint; 3] = [RowTint::Off, RowTint::Severity, RowTint::Status];
+
+	pub fn from_setting(value: &str) -> Option<Self> {
+		match value.trim().to_ascii_lowercase().as_str() {
+			"off" => Some(RowTint::Off),
+			"severity" => Some(RowTint::Severity),
+			"status" => Some(RowTint::Status),
+			_ => None,
+		}
+	}
+
+	pub fn as_setting(&self) -> &'static str {
+		match self {
+			RowTint::Off => "off",
+			RowTint::Severity => "severity",
+			RowTint::Status => "status",
+		}
+	}
+
+	/// Background of the vulnerability's row, `None` when rows are not tinted
+	pub fn background(&self, vuln: &Vulnerability, theme: &Theme) -> Option<Color> {
+		match self {
+			RowTint::Off => None,
+			RowTint::Severity => Some(format_severity_background(&vuln.severity, theme)),
+			RowTint::Status => Some(format_status_background(vuln.status, theme)),
+		}
+	}
+}
+
+impl std::fmt::Display for RowTint {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			RowTint::Off => write!(f, "No row tint"),
+			RowTint::Severity => write!(f, "Tint by severity"),
+			RowTint::Status => write!(f, "Tint by status"),
+		}
+	}
+}
+
+/// Contents of the maintenance dialog
+#[derive(Debug, Clone)]
+pub struct MaintenanceStatus {
+	pub policy: MaintenancePolicy,
+	pub stats: StorageStats,
+	/// A run was started from the dialog and has not finished yet
+	pub running: bool,
+}
+
+/// Settings and outcome of the check for new releases, shown in the About dialog
+#[derive(Debug, Clone, Default)]
+pub struct UpdateStatus {
+	pub settings: UpdateCheckSettings,
+	/// Newer release found by the last check
+	pub update: Option<AvailableUpdate>,
+	/// Why the last check failed
+	pub error: Option<String>,
+	pub checking: bool,
+}
+
+/// Interval between syncs of a data source offered in the data sources dialog, in minutes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncInterval(pub u32);
+
+impl SyncInterval {
+	pub const CHOICES: [SyncInterval; 7] = [
+		SyncInterval(15),
+		SyncInterval(30),
+		SyncInterval(60),
+		SyncInterval(120),
+		SyncInterval(240),
+		SyncInterval(720),
+		SyncInterval(1440),
+	];
+}
+
+impl std::fmt::Display for SyncInterval {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.0 {
+			minutes if minutes % 60 == 0 => write!(f, "Every {} h", minutes / 60),
+			minutes => write!(f, "Every {} min", minutes),
+		}
+	}
+}
+
+/// Edits in the data sources dialog that are not saved yet
+#[derive(Debug, Clone, Default)]
+pub struct DataSourcesView {
+	/// URLs as typed, by source ID; saved when submitted
+	pub urls: BTreeMap<i64, String>,
+	/// Name and URL of an NVD JSON feed to add
+	pub feed_name: String,
+	pub feed_url: String,
+}
+
+/// The CSV import dialog: the file, its headers and the column chosen for each field
+#[derive(Debug, Clone, Default)]
+pub struct CsvImportView {
+	pub path: String,
+	/// Headers of the file, empty until it is read
+	pub headers: Vec<String>,
+	pub mapping: CsvMapping,
+	/// Saved presets, by name
+	pub presets: Vec<(String, CsvMapping)>,
+	/// Name to save the mapping under
+	pub preset_name: String,
+	pub importing: bool,
+}
+
+impl CsvImportView {
+	/// Choices for a field: the file's headers, and for optional fields leaving it out
+	pub fn column_options(&self, field: CsvField) -> Vec<CsvColumn> {
+		let unmapped = (!field.is_required()).then_some(CsvColumn::Unmapped);
+		unmapped.into_iter().chain(self.headers.iter().cloned().map(CsvColumn::Header)).collect()
+	}
+
+	/// The column chosen for a field, if the file has it
+	pub fn selected_column(&self, field: CsvField) -> Option<CsvColumn> {
+		match self.mapping.column(field) {
+			Some(header) => self.headers
+				.iter()
+				.find(|h| h.eq_ignore_ascii_case(header))
+				.cloned()
+				.map(CsvColumn::Header),
+			None if field.is_required() => None,
+			None => Some(CsvColumn::Unmapped),
+		}
+	}
+
+	/// Required fields with no column of the file chosen
+	pub fn missing_fields(&self) -> Vec<CsvField> {
+		CsvField::ALL
+			.into_iter()
+			.filter(|field| field.is_required() && self.selected_column(*field).is_none())
+			.collect()
+	}
+}
+
+/// A column offered in the CSV import dialog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvColumn {
+	/// The field is not imported
+	Unmapped,
+	Header(String),
+}
+
+impl std::fmt::Display for CsvColumn {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			CsvColumn::Unmapped => f.write_str("(not imported)"),
+			CsvColumn::Header(header) => f.write_str(header),
+		}
+	}
+}
+
+/// Contents of the audit log dialog
+#[derive(Debug, Clone)]
+pub struct AuditLogView {
+	pub entity: FilterAuditEntity,
+	pub search: String,
+	/// Newest first, up to `AUDIT_LOG_LIMIT`
+	pub entries: Vec<AuditEntry>,
+}
+
+/// Description, severity, impact and mitigation of the selected vulnerability being edited by hand
+#[derive(Debug, Clone, Default)]
+pub struct FieldEditForm {
+	pub description: String,
+	pub severity: String,
+	pub impact: String,
+	pub mitigation: String,
+}
+
+impl FieldEditForm {
+	pub fn from_vulnerability(vuln: &Vulnerability) -> Self {
+		Self {
+			description: vuln.description.clone().unwrap_or_default(),
+			severity: vuln.severity.clone(),
+			impact: vuln.impact.clone().unwrap_or_default(),
+			mitigation: vuln.mitigation.clone().unwrap_or_default(),
+		}
+	}
+
+	/// The fields that differ from `vuln`, which are the ones to save and lock
+	pub fn changes(&self, vuln: &Vulnerability) -> Vec<(LockedField, Option<String>)> {
+		let original = Self::from_vulnerability(vuln);
+		[
+			(LockedField::Description, &self.description, &original.description),
+			(LockedField::Severity, &self.severity, &original.severity),
+			(LockedField::Impact, &self.impact, &original.impact),
+			(LockedField::Mitigation, &self.mitigation, &original.mitigation),
+		]
+			.into_iter()
+			.filter(|(_, edited, original)| edited.trim() != original.trim())
+			.map(|(field, edited, _)| (field, Some(edited.clone())))
+			.collect()
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct RobotForm {
 	pub name: String,
 	pub manufacturer: String,
+	pub model: String,
+	pub firmware_version: String,
+	pub os: String,
+	pub ros_distro: String,
 	pub specifications: String,
+	pub operational_note: String,
+	pub criticality: Criticality,
 	pub software_versions: Vec<String>,
 }
 
+impl RobotForm {
+	/// The operational note to store; blank input clears it
+	pub fn operational_note_value(&self) -> Option<String> {
+		Some(self.operational_note.trim().to_string()).filter(|note| !note.is_empty())
+	}
+
+	/// The model to store; blank input clears it
+	pub fn model_value(&self) -> Option<String> {
+		Some(self.model.trim().to_string()).filter(|model| !model.is_empty())
+	}
+
+	/// Firmware version, operating system and ROS distribution to store; blank inputs clear them
+	pub fn platform_values(&self) -> (Option<String>, Option<String>, Option<String>) {
+		let value = |input: &str| Some(input.trim().to_string()).filter(|v| !v.is_empty());
+		(value(&self.firmware_version), value(&self.os), value(&self.ros_distro))
+	}
+
+	/// The software entries to store; software without a vendor is by the manufacturer
+	pub fn software_refs(&self) -> Result<Vec<SoftwareRef>, String> {
+		self.software_versions
+			.iter()
+			.map(|entry| {
+				SoftwareRef::parse(entry, self.manufacturer.trim()).ok_or_else(|| {
+					format!("Software '{}' is not in the form 'vendor/product version'", entry)
+				})
+			})
+			.collect()
+	}
+}
+
+/// Metadata input for the selected software versions. A field stays `None` until
+/// edited, and fields left `None` are not written, so a bulk edit only changes what
+/// was typed; an emptied field clears the value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VersionEditor {
+	pub release_date: Option<String>,
+	pub eol_date: Option<String>,
+	pub notes: Option<String>,
+}
+
+impl VersionEditor {
+	/// Prefilled with the current values when a single version is selected
+	pub fn for_selection(selected: &[&VersionMetadata]) -> Self {
+		match selected {
+			[version] => Self {
+				release_date: Some(version.release_date.map(|d| d.to_string()).unwrap_or_default()),
+				eol_date: Some(version.eol_date.map(|d| d.to_string()).unwrap_or_default()),
+				notes: Some(version.notes.clone().unwrap_or_default()),
+			},
+			_ => Self::default(),
+		}
+	}
+
+	pub fn change(&self) -> Result<VersionMetadataChange, String> {
+		let date = |label: &str, value: &Option<String>| -> Result<Option<Option<NaiveDate>>, String> {
+			match value.as_deref().map(str::trim) {
+				None => Ok(None),
+				Some("") => Ok(Some(None)),
+				Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+					.map(|date| Some(Some(date)))
+					.map_err(|_| format!("{} '{}' is not a date like 2024-05-31", label, date)),
+			}
+		};
+		Ok(VersionMetadataChange {
+			release_date: date("Release date", &self.release_date)?,
+			eol_date: date("EOL date", &self.eol_date)?,
+			notes: self.notes.as_deref().map(|notes| Some(notes.trim().to_string()).filter(|n| !n.is_empty())),
+		})
+	}
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
 	// Existing vulnerability messages
-	VulnerabilitiesLoaded(Result<(Vec<Vulnerability>, usize), String>),
+	VulnerabilitiesLoaded(Result<VulnerabilityPage, String>),
 	SearchQueryChanged(String),
 	PageChanged(usize),
 	RefreshData,
@@ -100,14 +650,47 @@ pub enum Message {
 	SortFieldSelected(SortField),
 	ToggleSortOrder,
 	FilterSeverityChanged(FilterSeverity),
+	FilterStatusChanged(FilterStatus),
+	FilterWeaknessChanged(FilterWeakness),
+	QuickFilterToggled(QuickFilter),
+	QuickFilterCountsLoaded(Result<Vec<(QuickFilter, i64)>, String>),
 	ToggleStatistics(bool),
+	RiskySoftwareLoaded(Result<Vec<RiskySoftware>, String>),
+	EnrichmentProgressLoaded(Result<EnrichmentProgress, String>),
+	StatisticsLoaded(Result<StatisticsReport, String>),
+	TrendsLoaded(Result<FleetTrends, String>),
+	RiskySoftwareSelected(usize),
+	ClearSoftwareFilter,
 	VulnerabilitySelected(usize),
+	/// A deep-linked CVE that was not on the first page, looked up by ID
+	DeepLinkResolved(Result<(String, Option<Vulnerability>), String>),
 	ClearSelection,
 	ScrollChanged(f32),
-	LoadingProgress(f32),
+	LoadingProgress(Progress),
+	CancelOperation,
 	OperationTypeChanged(OperationType),
 	ClearSearch,
 	ExportData,
+	TriageStatusSelected(TriageStatus),
+	TriageAssigneeChanged(String),
+	TriageJustificationChanged(String),
+	TriageApproverChanged(String),
+	TriageExpiryChanged(String),
+	TriageSaved,
+	TriageUpdated(Result<(i64, TriageStatus, Option<String>, Option<RiskAcceptance>), String>),
+	FieldEditStarted,
+	FieldEditDescriptionChanged(String),
+	FieldEditSeverityChanged(String),
+	FieldEditImpactChanged(String),
+	FieldEditMitigationChanged(String),
+	FieldEditSaved,
+	FieldEditCancelled,
+	FieldUnlockClicked(LockedField),
+	/// The vulnerability as stored after an edit or unlock
+	FieldsUpdated(Result<Vulnerability, String>),
+	NvdFetchClicked,
+	/// The refetched vulnerability, or None when the NVD had nothing to add
+	NvdFetched(Result<Option<Vulnerability>, String>),
 	RobotFormSoftwareVersionInput(String),
 	RobotFormSoftwareVersionSubmit,
 
@@ -116,6 +699,30 @@ pub enum Message {
 	RobotsLoaded(Result<Vec<Robot>, String>),
 	RobotSelected(usize),
 	RobotFilterChanged(String),
+	RobotSortChanged(RobotSort),
+	ListLayoutLoaded(Result<ListLayout, String>),
+	ListLayoutChanged(ListLayout),
+	ListLayoutSaved(Result<(), String>),
+	TableColumnsLoaded(Result<TableColumns, String>),
+	/// A column's header divider was dragged by the given number of pixels
+	TableColumnResized(TableColumn, f32),
+	/// The divider was released; the widths are saved
+	TableColumnResizeFinished,
+	TableColumnsSaved(Result<(), String>),
+	/// A table header was clicked: sort by its column, or reverse the order if already
+	SortHeaderClicked(SortField),
+	RowTintLoaded(Result<RowTint, String>),
+	RowTintChanged(RowTint),
+	RowTintSaved(Result<(), String>),
+	ThemeLoaded(Result<ThemeChoice, String>),
+	ThemeChanged(ThemeChoice),
+	ThemeSaved(Result<(), String>),
+	DetailLayoutLoaded(Result<DetailLayout, String>),
+	DetailLayoutChanged(DetailLayout),
+	DetailLayoutSaved(Result<(), String>),
+	ColorBlindSafeLoaded(Result<bool, String>),
+	ColorBlindSafeToggled(bool),
+	ColorBlindSafeSaved(Result<(), String>),
 	RobotFilterTypeChanged(RobotFilterType),
 	AddRobotClicked,
 	EditRobotClicked(i32),
@@ -126,8 +733,15 @@ pub enum Message {
 	// Robot form messages
 	RobotFormNameChanged(String),
 	RobotFormManufacturerChanged(String),
+	RobotFormModelChanged(String),
+	RobotFormFirmwareChanged(String),
+	RobotFormOsChanged(String),
+	RobotFormRosDistroChanged(String),
 	RobotFormSpecificationsChanged(String),
+	RobotFormOperationalNoteChanged(String),
+	RobotFormCriticalityChanged(Criticality),
 	RobotFormSoftwareAdded(String),
+	VendorNamesLoaded(Result<Vec<String>, String>),
 	RobotFormSoftwareRemoved(usize),
 	RobotFormSubmitted,
 	RobotFormCancelled,
@@ -135,22 +749,251 @@ pub enum Message {
 	// Robot operation results
 	RobotAdded(Result<Robot, String>),
 	RobotUpdated(Result<Robot, String>),
-	RobotDeleted(Result<(), String>),
+	/// The ID of the deleted robot, kept for the Undo toast
+	RobotDeleted(Result<i32, String>),
 
 	// Software and vulnerability correlation
 	LoadRobotVulnerabilities(i32),
-	RobotVulnerabilitiesLoaded(Result<Vec<Vulnerability>, String>),
+	RobotVulnerabilitiesLoaded(i32, Result<Vec<RobotExposure>, String>),
+	ChecklistLoaded(i32, Result<Vec<ChecklistEntry>, String>),
+	/// Robot ID, checklist item ID and whether the item is now done
+	ChecklistItemToggled(i32, i64, bool),
+	ChecklistItemSaved(i32, Result<(), String>),
+	// Compliance controls the selected vulnerability is mapped to
+	ComplianceControlsLoaded(Result<Vec<ComplianceControl>, String>),
+	LinkedControlsLoaded(i64, Result<Vec<ComplianceControl>, String>),
+	/// Vulnerability ID, control ID and whether they are now linked
+	ControlLinkToggled(i64, i64, bool),
+	ControlLinkSaved(i64, Result<(), String>),
+	// Remediation ticket of the selected vulnerability
+	/// Whether ticketing is configured, so tickets can be raised
+	TicketingLoaded(Result<bool, String>),
+	TicketLoaded(i64, Result<Option<Ticket>, String>),
+	TicketCreateClicked,
+	TicketCreated(i64, Result<Ticket, String>),
+	/// Days after which a robot's inventory is flagged as stale
+	InventoryMaxAgeLoaded(Result<u32, String>),
+	/// Record that the robot's recorded software is still current
+	InventoryConfirmClicked(i32),
+	InventoryConfirmed(Result<(), String>),
+	/// Switch to the Vulnerabilities tab with this CVE open
+	OpenVulnerability(String),
 	LoadRobotSoftware(i32),
-	RobotSoftwareLoaded(Result<Vec<String>, String>),
+	RobotSoftwareLoaded(i32, Result<Vec<String>, String>),
+
+	// Notes on the selected vulnerability or robot
+	NotesLoaded(Result<Vec<Note>, String>),
+	NoteInputChanged(String),
+	NoteSubmitted,
+	NoteEditClicked(i64),
+	NoteEditCancelled,
+	NoteDeleteClicked(i64),
+	NoteSaved(Result<(), String>),
+	/// The deleted note, kept so it can be restored
+	NoteDeleted(Result<Note, String>),
+	NoteRestored(Note),
+
+	// Copy and open actions of the detail view and of the card menu
+	/// Opens a web page in the system browser
+	LinkOpened(String),
+	/// Puts text on the clipboard; the first value names it in the confirmation
+	CopyToClipboard(&'static str, String),
+	/// Right click on the card at this index of the displayed list
+	CardMenuOpened(usize),
+	CardMenuClosed,
+
+	// References of the selected vulnerability
+	ReferencesLoaded(Result<Vec<Reference>, String>),
+	RelatedLoaded(Result<Vec<RelatedVulnerability>, String>),
+
+	// Relationship graph around a CVE or robot
+	GraphRequested(GraphCenter),
+	GraphLoaded(Result<RelationshipGraph, String>),
+	GraphClosed,
+
+	// Print layout of the open detail view, or of the risk acceptance and compliance reports
+	PrintDetail,
+	RiskReportRequested,
+	ComplianceReportRequested,
+	PrintOpened(Result<String, String>),
+	/// Save the filtered list as a read-only page for people without RVD
+	ShareListRequested,
+	ShareListSaved(Result<String, String>),
+
+	// Startup compaction of a database with much free space
+	CompactionChecked(Result<Option<(CompactionMode, StorageStats)>, String>),
+	CompactDatabase,
+	CompactionDismissed,
+	DatabaseCompacted(Result<StorageStats, String>),
+
+	// Maintenance dialog
+	MaintenanceOpened,
+	MaintenanceLoaded(Result<(MaintenancePolicy, StorageStats), String>),
+	MaintenanceClosed,
+	MaintenanceRun,
+	MaintenanceFinished(Result<MaintenanceReport, String>),
+	MaintenanceScheduleToggled(bool),
+	MaintenanceScheduleSaved(Result<(), String>),
+
+	// About dialog and the check for new releases
+	AboutOpened,
+	AboutClosed,
+	UpdateCheckLoaded(Result<UpdateCheckSettings, String>),
+	UpdateCheckRequested,
+	UpdateChecked(Result<Option<AvailableUpdate>, String>),
+	UpdateCheckToggled(bool),
+	UpdateCheckSaved(Result<(), String>),
+
+	// Data sources, shown in the status bar and managed in their dialog
+	DataSourcesOpened,
+	DataSourcesClosed,
+	DataSourcesLoaded(Result<Vec<DataSource>, String>),
+	DataSourceToggled(i64, bool),
+	DataSourceIntervalSelected(i64, SyncInterval),
+	DataSourceUrlChanged(i64, String),
+	DataSourceUrlSubmitted(i64),
+	DataSourceFeedNameChanged(String),
+	DataSourceFeedUrlChanged(String),
+	DataSourceFeedAdded,
+	DataSourceRemoveClicked(i64),
+	/// Runs one source now
+	DataSourceRunClicked(i64),
+	/// Runs every enabled source now
+	SyncNowClicked,
+	/// Summary of the sync
+	SyncFinished(Result<String, String>),
+
+	// Recently deleted robots and vulnerabilities
+	TrashOpened,
+	TrashLoaded(Result<Vec<DeletedItem>, String>),
+	TrashClosed,
+
+	// Audit log of data changes
+	AuditOpened,
+	AuditLoaded(Result<Vec<AuditEntry>, String>),
+	AuditClosed,
+	AuditEntityChanged(FilterAuditEntity),
+	AuditSearchChanged(String),
+	AuditExportRequested,
+	AuditExported(Result<String, String>),
+	AuditBatchRevertClicked(i64),
+	AuditBatchReverted(Result<usize, String>),
+
+	// Import history, with reverting a bad import
+	ImportHistoryOpened,
+	ImportHistoryLoaded(Result<Vec<ImportRun>, String>),
+	ImportHistoryClosed,
+	ImportRevertClicked(i64),
+	ImportReverted(Result<RevertSummary, String>),
+	ImportSkippedRowsClicked(i64),
+	ImportSkippedRowsLoaded(i64, Result<Vec<SkippedRow>, String>),
+
+	// Importing a vulnerability CSV with a chosen column mapping
+	CsvImportOpened,
+	CsvImportClosed,
+	CsvImportPathChanged(String),
+	CsvImportHeadersRequested,
+	CsvImportHeadersRead(Result<Vec<String>, String>),
+	CsvImportColumnSelected(CsvField, CsvColumn),
+	CsvImportSourceChanged(String),
+	CsvImportMergePolicySelected(MergePolicy),
+	CsvPresetsLoaded(Result<Vec<(String, CsvMapping)>, String>),
+	CsvPresetSelected(String),
+	CsvPresetNameChanged(String),
+	CsvPresetSaved,
+	CsvImportStarted,
+	CsvImported(Result<ImportReport, String>),
+
+	// What the data source syncs changed
+	WhatsNewOpened,
+	WhatsNewLoaded(Result<Vec<SyncDelta>, String>),
+	WhatsNewClosed,
+	WhatsNewExportRequested,
+	WhatsNewExported(Result<String, String>),
+	DeleteVulnerabilityClicked(i64),
+	/// The ID and CVE ID of the deleted vulnerability, kept for the Undo toast
+	VulnerabilityDeleted(Result<(i64, String), String>),
+	RestoreClicked(DeletedKind, i64),
+	Restored(Result<DeletedKind, String>),
+	PurgeClicked(DeletedKind, i64),
+	Purged(Result<(), String>),
+
+	// Software tab: version metadata, edited one at a time or in bulk
+	SoftwareVersionsLoaded(Result<Vec<VersionMetadata>, String>),
+	VersionFilterChanged(String),
+	VersionToggled(i64, bool),
+	SelectShownVersions,
+	ClearVersionSelection,
+	VersionReleaseDateChanged(String),
+	VersionEolDateChanged(String),
+	VersionNotesChanged(String),
+	SaveVersionMetadata,
+	VersionMetadataSaved(Result<usize, String>),
+
+	// NVD outage state recorded by the last enrichment run
+	NvdHealthLoaded(Result<NvdHealth, String>),
+
+	// Switching to another workspace database
+	WorkspaceSelected(String),
+	WorkspaceOpened(Result<(String, Arc<SqlitePool>), String>),
 
 	// Batch operations
 	ExportRobotData,
+	RobotImportPathChanged(String),
 	ImportRobotData(String),
+	RobotsImported(Result<RobotImportSummary, String>),
+	DismissRobotImportErrors,
 	BatchUpdateRobots,
 
-	// Error handling
+	// Toast notifications
 	ShowError(String),
-	ClearError,
+	ToastDismissed(u64),
+	ToastActionClicked(u64),
+	ToastTick(std::time::Instant),
+
+	// Performance overlay
+	ProfilerToggled,
+	ProfilerFrame(std::time::Instant),
+}
+
+impl Message {
+	/// Messages that change stored data and are ignored for read-only roles
+	pub fn modifies_data(&self) -> bool {
+		matches!(
+			self,
+			Message::TriageSaved
+				| Message::FieldEditSaved
+				| Message::FieldUnlockClicked(_)
+				| Message::NvdFetchClicked
+				| Message::DataSourceToggled(..)
+				| Message::DataSourceIntervalSelected(..)
+				| Message::DataSourceUrlSubmitted(_)
+				| Message::DataSourceFeedAdded
+				| Message::DataSourceRemoveClicked(_)
+				| Message::DataSourceRunClicked(_)
+				| Message::SyncNowClicked
+				| Message::AddRobotClicked
+				| Message::EditRobotClicked(_)
+				| Message::DeleteRobotClicked(_)
+				| Message::DeleteVulnerabilityClicked(_)
+				| Message::RestoreClicked(..)
+				| Message::PurgeClicked(..)
+				| Message::ChecklistItemToggled(..)
+				| Message::InventoryConfirmClicked(_)
+				| Message::ControlLinkToggled(..)
+				| Message::TicketCreateClicked
+				| Message::ImportRevertClicked(_)
+				| Message::CsvPresetSaved
+				| Message::CsvImportStarted
+				| Message::AuditBatchRevertClicked(_)
+				| Message::RobotFormSubmitted
+				| Message::NoteSubmitted
+				| Message::NoteEditClicked(_)
+				| Message::NoteDeleteClicked(_)
+				| Message::ImportRobotData(_)
+				| Message::BatchUpdateRobots
+		)
+	}
 }
 
 // Helper function to convert operation type to string for logging/display
@@ -171,7 +1014,13 @@ pub fn default_robot_form() -> RobotForm {
 	RobotForm {
 		name: String::new(),
 		manufacturer: String::new(),
+		model: String::new(),
+		firmware_version: String::new(),
+		os: String::new(),
+		ros_distro: String::new(),
 		specifications: String::new(),
+		operational_note: String::new(),
+		criticality: Criticality::default(),
 		software_versions: Vec::new(),
 	}
 }
@@ -184,5 +1033,27 @@ pub fn validate_robot_form(form: &RobotForm) -> Result<(), String> {
 	if form.manufacturer.trim().is_empty() {
 		return Err("Manufacturer is required".to_string());
 	}
+	form.software_refs()?;
 	Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_table_columns_setting() {
+		let mut columns = TableColumns::default();
+		columns.resize(TableColumn::Cvss, 25.0);
+		columns.resize(TableColumn::Status, -1000.0);
+		assert_eq!(columns.width(TableColumn::Cvss), 95.0);
+		assert_eq!(columns.width(TableColumn::Status), TableColumns::MIN_WIDTH);
+		assert_eq!(TableColumns::from_setting(&columns.as_setting()), Some(columns));
+		assert_eq!(TableColumns::from_setting("1,2,3"), None);
+		assert_eq!(TableColumns::from_setting("170,110,9000,110,x"), None);
+		assert_eq!(
+			TableColumns::from_setting("170, 110, 9000, 110, 130").map(|columns| columns.width(TableColumn::Cvss)),
+			Some(TableColumns::MAX_WIDTH)
+		);
+	}
+}
@@ -1,16 +1,27 @@
+use super::charts::ChartRenderer;
 use super::constants::DISPLAY_PAGE_SIZE;
-use super::formatters::{format_date, format_severity};
+use super::formatters::{format_date, format_link, format_muted, format_risk, format_severity, format_severity_label, format_sources};
+use super::notes_view::NotesViewRenderer;
 use super::state::AppState;
-use super::types::Message;
-use crate::models::vulnerability::Vulnerability;
+use super::table_view::TableViewRenderer;
+use super::types::{FilterWeakness, ListLayout, Message, RowTint};
+use crate::models::compliance::ComplianceControl;
+use crate::models::graph::GraphCenter;
+use crate::models::reference::Reference;
+use crate::models::risk::RiskBand;
+use crate::models::vulnerability::{LockedField, ProvenanceField, ProvenanceSource, RelatedVulnerability, TriageStatus, Vulnerability};
+use crate::models::weakness::WeaknessClass;
+use crate::repositories::vulnerability_repo::QuickFilter;
+use crate::utils::time;
+use chrono::Utc;
 use iced::{
 	alignment::{Horizontal, Vertical},
 	theme,
 	widget::{
-		button, column, container, pick_list, row, scrollable, text_input, Checkbox, Column, Row,
+		button, column, container, mouse_area, pick_list, progress_bar, row, scrollable, text_input, Checkbox, Column, Row,
 		Rule, Space, Text,
 	},
-	Alignment, Color, Element, Length,
+	Alignment, Element, Length, Theme,
 };
 
 pub trait ViewRenderer {
@@ -28,6 +39,20 @@ pub trait ViewRenderer {
 		vuln: &'a Vulnerability,
 	) -> Element<'a, Message>;
 	fn control_panel(&self) -> Element<Message>;
+	fn top_risky_software(&self) -> Element<'_, Message>;
+	fn weakness_classes(&self) -> Element<'_, Message>;
+	fn fleet_risk_index(&self) -> Element<'_, Message>;
+	fn quick_filters(&self) -> Element<'_, Message>;
+	fn software_filter_banner(&self) -> Element<'_, Message>;
+	fn enrichment_status(&self) -> Element<'_, Message>;
+	fn triage_controls<'a>(&'a self, vuln: &'a Vulnerability) -> Element<'a, Message>;
+	fn ticket_controls(&self) -> Element<'_, Message>;
+	fn editable_field<'a>(&'a self, vuln: &'a Vulnerability, field: LockedField) -> Element<'a, Message>;
+	fn field_edit_controls(&self) -> Element<'_, Message>;
+	fn progress_indicator(&self) -> Element<'_, Message>;
+	fn compaction_banner(&self) -> Element<'_, Message>;
+	fn nvd_outage_banner(&self) -> Element<'_, Message>;
+	fn sync_status_bar(&self) -> Element<'_, Message>;
 }
 
 impl ViewRenderer for AppState {
@@ -35,7 +60,7 @@ impl ViewRenderer for AppState {
 		container(
 			row![
 				text_input(
-					"Search by CVE ID, description, or severity...",
+					"Search by CVE ID, alias, description, advisory ID or reference URL...",
 					&self.search_query
 				)
 				.on_input(Message::SearchQueryChanged)
@@ -68,22 +93,11 @@ impl ViewRenderer for AppState {
 	}
 
 	fn statistics(&self) -> Element<Message> {
-		let total = self.vulnerabilities.len();
-		let high = self
-			.vulnerabilities
-			.iter()
-			.filter(|v| v.severity.eq_ignore_ascii_case("high"))
-			.count();
-		let medium = self
-			.vulnerabilities
-			.iter()
-			.filter(|v| v.severity.eq_ignore_ascii_case("medium"))
-			.count();
-		let low = self
-			.vulnerabilities
-			.iter()
-			.filter(|v| v.severity.eq_ignore_ascii_case("low"))
-			.count();
+		let total = self.statistics.as_ref().map_or(0, |report| report.totals.vulnerabilities);
+		let total = match self.statistics.as_ref().and_then(|report| report.filter.as_ref()) {
+			Some(filter) => format!("Matching Vulnerabilities: {} ({})", total, filter),
+			None => format!("Total Vulnerabilities: {}", total),
+		};
 
 		container(
 			column![
@@ -93,55 +107,17 @@ impl ViewRenderer for AppState {
 				Space::with_height(Length::Fixed(10.0)),
 				Rule::horizontal(1),
 				Space::with_height(Length::Fixed(10.0)),
-				Text::new(format!("Total Vulnerabilities: {}", total))
+				self.fleet_risk_index(),
+				Text::new(total)
 					.size(18)
 					.horizontal_alignment(Horizontal::Center),
+				self.enrichment_status(),
 				Space::with_height(Length::Fixed(10.0)),
-				row![
-					container(
-						column![
-							Text::new("High Severity")
-								.style(theme::Text::Color(format_severity("high")))
-								.size(16),
-							Text::new(format!("{} ({}%)", high, (high * 100) / total.max(1)))
-								.size(24)
-								.horizontal_alignment(Horizontal::Center),
-						]
-						.spacing(4),
-					)
-					.style(theme::Container::Box)
-					.padding(10)
-					.width(Length::Fill),
-					container(
-						column![
-							Text::new("Medium Severity")
-								.style(theme::Text::Color(format_severity("medium")))
-								.size(16),
-							Text::new(format!("{} ({}%)", medium, (medium * 100) / total.max(1)))
-								.size(24)
-								.horizontal_alignment(Horizontal::Center),
-						]
-						.spacing(4),
-					)
-					.style(theme::Container::Box)
-					.padding(10)
-					.width(Length::Fill),
-					container(
-						column![
-							Text::new("Low Severity")
-								.style(theme::Text::Color(format_severity("low")))
-								.size(16),
-							Text::new(format!("{} ({}%)", low, (low * 100) / total.max(1)))
-								.size(24)
-								.horizontal_alignment(Horizontal::Center),
-						]
-						.spacing(4),
-					)
-					.style(theme::Container::Box)
-					.padding(10)
-					.width(Length::Fill),
-				]
-				.spacing(10),
+				self.statistics_charts(),
+				Space::with_height(Length::Fixed(10.0)),
+				self.top_risky_software(),
+				Space::with_height(Length::Fixed(10.0)),
+				self.weakness_classes(),
 			]
 				.spacing(10),
 		)
@@ -151,6 +127,10 @@ impl ViewRenderer for AppState {
 	}
 
 	fn vulnerability_list(&self) -> Element<Message> {
+		if self.list_layout == ListLayout::Table && !self.displayed_vulnerabilities.is_empty() {
+			return self.vulnerability_table();
+		}
+
 		let content = if self.loading && self.displayed_vulnerabilities.is_empty() {
 			column![
 				Space::with_height(Length::Fixed(20.0)),
@@ -192,25 +172,47 @@ impl ViewRenderer for AppState {
 		idx: usize,
 	) -> Element<'a, Message> {
 		let is_selected = self.selected_vulnerability == Some(idx);
+		let style = match self.row_tint.background(vuln, &self.theme()) {
+			_ if is_selected => theme::Container::Box,
+			Some(tint) => (move |theme: &Theme| container::Appearance {
+				background: Some(tint.into()),
+				text_color: Some(theme.palette().text),
+				..Default::default()
+			}).into(),
+			None => theme::Container::Transparent,
+		};
 
-		button(
+		let card = button(
 			container(
 				column![
 					row![
 						Text::new(&vuln.cve_id)
 							.size(18)
 							.width(Length::FillPortion(2)),
-						Text::new(&vuln.severity)
+						Text::new(if vuln.kev_date_added.is_some() { "KEV" } else { "" })
+							.size(14)
+							.style(theme::Text::Color(format_severity("high", &self.theme())))
+							.width(Length::Shrink),
+						Text::new(vuln.status.as_str())
+							.size(14)
+							.width(Length::Shrink),
+						Text::new(format_severity_label(&vuln.severity))
 							.size(14)
-							.style(theme::Text::Color(format_severity(&vuln.severity)))
+							.style(theme::Text::Color(format_severity(&vuln.severity, &self.theme())))
 							.width(Length::Shrink)
 							.horizontal_alignment(Horizontal::Right),
 					]
 					.spacing(10)
 					.align_items(Alignment::Center),
-					Text::new(format_date(vuln.published_date))
-						.size(12)
-						.style(theme::Text::Color(Color::from_rgb8(100, 100, 100))),
+					row![
+						Text::new(format_date(vuln.published_date))
+							.size(12)
+							.style(theme::Text::Color(format_muted(&self.theme()))),
+						Text::new(format_sources(&vuln.sources()))
+							.size(12)
+							.style(theme::Text::Color(format_link(&self.theme()))),
+					]
+					.spacing(10),
 					Space::with_height(Length::Fixed(5.0)),
 					Text::new(
 						vuln.description
@@ -224,11 +226,7 @@ impl ViewRenderer for AppState {
 					.padding(10),
 			)
 				.width(Length::Fill)
-				.style(if is_selected {
-					theme::Container::Box
-				} else {
-					theme::Container::Transparent
-				}),
+				.style(style),
 		)
 			.style(if is_selected {
 				theme::Button::Primary
@@ -236,7 +234,29 @@ impl ViewRenderer for AppState {
 				theme::Button::Secondary
 			})
 			.on_press(Message::VulnerabilitySelected(idx))
-			.width(Length::Fill)
+			.width(Length::Fill);
+		let card = mouse_area(card).on_right_press(Message::CardMenuOpened(idx));
+		if self.card_menu != Some(idx) {
+			return card.into();
+		}
+
+		column![
+			card,
+			container(
+				copy_and_open_actions(vuln)
+					.push(Space::with_width(Length::Fill))
+					.push(
+						button(Text::new("Close").size(14))
+							.on_press(Message::CardMenuClosed)
+							.style(theme::Button::Secondary)
+							.padding(5),
+					)
+					.align_items(Alignment::Center),
+			)
+				.style(theme::Container::Box)
+				.padding(8)
+				.width(Length::Fill),
+		]
 			.into()
 	}
 
@@ -304,67 +324,136 @@ impl ViewRenderer for AppState {
 					Text::new(&vuln.cve_id)
 						.size(28)
 						.width(Length::Fill),
+					button(Text::new("Edit").size(16))
+						.on_press_maybe((self.role.can_edit() && self.field_edit.is_none()).then_some(Message::FieldEditStarted))
+						.style(theme::Button::Secondary)
+						.padding(5),
+					button(Text::new(if self.nvd_fetching { "Fetching..." } else { "Fetch from NVD" }).size(16))
+						.on_press_maybe((self.role.can_edit() && !self.nvd_fetching).then_some(Message::NvdFetchClicked))
+						.style(theme::Button::Secondary)
+						.padding(5),
+					button(Text::new("Relationship Graph").size(16))
+						.on_press_maybe(vuln.vulnerability_id.map(|id| Message::GraphRequested(GraphCenter::Vulnerability(id))))
+						.style(theme::Button::Secondary)
+						.padding(5),
+					button(Text::new("Print / Save as PDF").size(16))
+						.on_press(Message::PrintDetail)
+						.style(theme::Button::Secondary)
+						.padding(5),
+					button(Text::new("Delete").size(16))
+						.on_press_maybe(
+							vuln.vulnerability_id
+								.filter(|_| self.role.can_edit())
+								.map(Message::DeleteVulnerabilityClicked)
+						)
+						.style(theme::Button::Destructive)
+						.padding(5),
 					button(Text::new("Close").size(16))
 						.on_press(Message::ClearSelection)
 						.style(theme::Button::Destructive)
 						.padding(5),
 				]
+				.spacing(10)
 				.align_items(Alignment::Center)
 				.padding(10),
+				copy_and_open_actions(vuln).padding([0, 10]),
 				Rule::horizontal(1),
 				// Severity and date
 				row![
 					Text::new("Severity:")
 						.size(16),
-					Text::new(&vuln.severity)
-						.size(16)
-						.style(theme::Text::Color(format_severity(&vuln.severity))),
+					self.editable_field(vuln, LockedField::Severity),
+					Space::with_width(Length::Fixed(20.0)),
+					Text::new(match vuln.cvss_score {
+						Some(score) => format!("{}: {:.1}", vuln.cvss_name(), score),
+						None => format!("CVSS: ~{:.1} (estimated from severity)", vuln.effective_cvss()),
+					})
+						.size(14),
 					Space::with_width(Length::Fixed(20.0)),
 					Text::new(format!("Published: {}", format_date(vuln.published_date)))
 						.size(14),
+					Text::new(format!("Sources: {}", vuln.sources().join(", ")))
+						.size(14),
 				]
 				.spacing(10)
 				.padding(10),
+				field_lock(vuln, LockedField::Severity, self.role.can_edit(), &self.theme()),
+				field_sources(vuln, &[ProvenanceField::Severity, ProvenanceField::CvssScore, ProvenanceField::PublishedDate], &self.theme()),
+				self.field_edit_controls(),
+				Text::new(if vuln.aliases.is_empty() {
+					String::new()
+				} else {
+					format!("Also known as: {}", vuln.aliases.iter().map(|alias| alias.id.as_str()).collect::<Vec<_>>().join(", "))
+				})
+					.size(14),
+				Rule::horizontal(1),
+				// Triage
+				column![
+					Text::new("Triage").size(20),
+					self.triage_controls(vuln),
+					self.ticket_controls(),
+				]
+				.spacing(5)
+				.padding(10),
 				Rule::horizontal(1),
 				// Description
 				column![
 					Text::new("Description").size(20),
-					Text::new(
-						vuln.description
-							.as_deref()
-							.unwrap_or("No description available"),
-					)
-					.size(16)
-					.width(Length::Fill),
+					field_lock(vuln, LockedField::Description, self.role.can_edit(), &self.theme()),
+					field_sources(vuln, &[ProvenanceField::Description], &self.theme()),
+					self.editable_field(vuln, LockedField::Description),
+				]
+				.spacing(5)
+				.padding(10),
+				// Weaknesses
+				column![
+					Text::new("Weaknesses").size(20),
+					weakness_list(vuln),
+				]
+				.spacing(5)
+				.padding(10),
+				// Compliance controls
+				column![
+					Text::new("Compliance Controls").size(20),
+					control_list(vuln, &self.linked_controls, &self.compliance_controls, self.role.can_edit(), &self.theme()),
+				]
+				.spacing(5)
+				.padding(10),
+				// References
+				column![
+					Text::new("References").size(20),
+					reference_list(&self.references, &self.theme()),
+				]
+				.spacing(5)
+				.padding(10),
+				// Related CVEs
+				column![
+					Text::new("Related CVEs").size(20),
+					related_list(&self.related, &self.theme()),
 				]
 				.spacing(5)
 				.padding(10),
 				// Impact
 				column![
 					Text::new("Impact").size(20),
-					Text::new(
-						vuln.impact
-							.as_deref()
-							.unwrap_or("No impact information available"),
-					)
-					.size(16)
-					.width(Length::Fill),
+					field_lock(vuln, LockedField::Impact, self.role.can_edit(), &self.theme()),
+					field_sources(vuln, &[ProvenanceField::Impact], &self.theme()),
+					self.editable_field(vuln, LockedField::Impact),
 				]
 				.spacing(5)
 				.padding(10),
 				// Mitigation
 				column![
 					Text::new("Mitigation").size(20),
-					Text::new(
-						vuln.mitigation
-							.as_deref()
-							.unwrap_or("No mitigation steps available"),
-					)
-					.size(16)
-					.width(Length::Fill),
+					field_lock(vuln, LockedField::Mitigation, self.role.can_edit(), &self.theme()),
+					field_sources(vuln, &[ProvenanceField::Mitigation], &self.theme()),
+					self.editable_field(vuln, LockedField::Mitigation),
+					self.field_edit_controls(),
 				]
 				.spacing(5)
 				.padding(10),
+				Rule::horizontal(1),
+				self.notes_panel(),
 			]
 					.spacing(10),
 			),
@@ -384,6 +473,8 @@ impl ViewRenderer for AppState {
 						super::types::SortField::CVE,
 						super::types::SortField::Severity,
 						super::types::SortField::Date,
+						super::types::SortField::Cvss,
+						super::types::SortField::Status,
 					],
 					Some(self.sort_field.clone()),
 					Message::SortFieldSelected,
@@ -407,7 +498,43 @@ impl ViewRenderer for AppState {
 				)
 				.width(Length::Fixed(150.0))
 				.padding(5),
+				pick_list(
+					super::types::FilterStatus::options(),
+					Some(self.filter_status.clone()),
+					Message::FilterStatusChanged,
+				)
+				.width(Length::Fixed(150.0))
+				.padding(5),
+				pick_list(
+					FilterWeakness::options(),
+					Some(self.filter_weakness),
+					Message::FilterWeaknessChanged,
+				)
+				.width(Length::Fixed(200.0))
+				.padding(5),
 				Space::with_width(Length::Fill),
+				button(Text::new("Import CSV").size(14))
+					.on_press_maybe(self.role.can_edit().then_some(Message::CsvImportOpened))
+					.style(theme::Button::Secondary)
+					.padding(5),
+				button(Text::new("Risk Report").size(14))
+					.on_press(Message::RiskReportRequested)
+					.style(theme::Button::Secondary)
+					.padding(5),
+				button(Text::new("Compliance Report").size(14))
+					.on_press(Message::ComplianceReportRequested)
+					.style(theme::Button::Secondary)
+					.padding(5),
+				button(Text::new("Share List").size(14))
+					.on_press(Message::ShareListRequested)
+					.style(theme::Button::Secondary)
+					.padding(5),
+				pick_list(&ListLayout::ALL[..], Some(self.list_layout), Message::ListLayoutChanged)
+					.width(Length::Fixed(100.0))
+					.padding(5),
+				pick_list(&RowTint::ALL[..], Some(self.row_tint), Message::RowTintChanged)
+					.width(Length::Fixed(150.0))
+					.padding(5),
 				Checkbox::new("Show Statistics", self.show_statistics)
 					.on_toggle(Message::ToggleStatistics)
 					.spacing(5),
@@ -419,4 +546,782 @@ impl ViewRenderer for AppState {
 			.padding(10)
 			.into()
 	}
+
+	fn top_risky_software(&self) -> Element<'_, Message> {
+		let rows: Element<Message> = if self.risky_software.is_empty() {
+			Text::new("No deployed software with unresolved vulnerabilities")
+				.size(14)
+				.into()
+		} else {
+			Column::with_children(
+				self.risky_software
+					.iter()
+					.enumerate()
+					.map(|(idx, software)| {
+						button(
+							row![
+								Text::new(format!("{}.", idx + 1))
+									.size(14)
+									.width(Length::Fixed(30.0)),
+								Text::new(software.label())
+									.size(14)
+									.width(Length::Fill),
+								Text::new(software.license.as_deref().unwrap_or("License unknown"))
+									.size(14)
+									.width(Length::Fixed(130.0)),
+								Text::new(format!("{} robots", software.robot_count))
+									.size(14)
+									.width(Length::Fixed(90.0)),
+								Text::new(format!("CVSS Σ {:.1}", software.cvss_sum))
+									.size(14)
+									.width(Length::Fixed(110.0)),
+								Text::new(format!("Risk {:.1}", software.risk_score()))
+									.size(14)
+									.width(Length::Fixed(100.0))
+									.horizontal_alignment(Horizontal::Right),
+							]
+							.spacing(10)
+							.align_items(Alignment::Center),
+						)
+						.style(theme::Button::Secondary)
+						.on_press(Message::RiskySoftwareSelected(idx))
+						.width(Length::Fill)
+						.into()
+					})
+					.collect::<Vec<Element<'_, Message>>>(),
+			)
+				.spacing(4)
+				.into()
+		};
+
+		column![
+			Text::new("Top Risky Software")
+				.size(20),
+			Text::new("Robots deployed × summed CVSS of open vulnerabilities. Click to open the remediation queue.")
+				.size(12)
+				.style(theme::Text::Color(format_muted(&self.theme()))),
+			rows,
+		]
+			.spacing(6)
+			.into()
+	}
+
+	/// Headline fleet risk index with its trend since the last recorded day
+	fn fleet_risk_index(&self) -> Element<'_, Message> {
+		let Some(fleet_risk) = self.statistics.as_ref().and_then(|report| report.fleet_risk.as_ref()) else {
+			return Space::with_height(Length::Shrink).into();
+		};
+		let band = RiskBand::of(fleet_risk.index);
+		let trend = match (fleet_risk.change(), &fleet_risk.previous_on) {
+			(Some(change), Some(previous_on)) => format!("{} {:+.1} since {}", fleet_risk.trend_arrow(), change, previous_on),
+			_ => "No earlier value recorded yet".to_string(),
+		};
+
+		container(
+			row![
+				column![
+					Text::new("Fleet Risk Index").size(16),
+					Text::new("Robot risk weighted by criticality").size(12),
+				]
+					.spacing(4),
+				Space::with_width(Length::Fill),
+				Text::new(format!("{:.1}", fleet_risk.index))
+					.size(40)
+					.style(theme::Text::Color(format_risk(band, &self.theme()))),
+				column![
+					Text::new(fleet_risk.trend_arrow()).size(28),
+					Text::new(band.as_str()).size(12).style(theme::Text::Color(format_risk(band, &self.theme()))),
+				]
+					.align_items(Alignment::Center),
+				Text::new(trend).size(14).width(Length::Fixed(220.0)),
+			]
+				.spacing(16)
+				.align_items(Alignment::Center),
+		)
+			.style(theme::Container::Box)
+			.padding(15)
+			.width(Length::Fill)
+			.into()
+	}
+
+	fn weakness_classes(&self) -> Element<'_, Message> {
+		let Some(report) = &self.statistics else {
+			return Space::with_height(Length::Shrink).into();
+		};
+		let skew = report.exposure_skew().map(|rollup| rollup.class);
+
+		let rows: Element<Message> = if report.by_weakness_class.is_empty() {
+			Text::new("No CWE data yet; it is imported from the NVD")
+				.size(14)
+				.into()
+		} else {
+			Column::with_children(
+				report.by_weakness_class
+					.iter()
+					.map(|rollup| {
+						let color = if skew == Some(rollup.class) {
+							format_severity("high", &self.theme())
+						} else {
+							self.theme().palette().text
+						};
+						row![
+							button(Text::new(rollup.class.label()).size(14).style(theme::Text::Color(color)))
+								.on_press(Message::FilterWeaknessChanged(FilterWeakness::Only(rollup.class)))
+								.style(theme::Button::Text)
+								.padding(0)
+								.width(Length::Fill),
+							Text::new(format!("{} CVEs", rollup.vulnerabilities))
+								.size(14)
+								.width(Length::Fixed(90.0)),
+							Text::new(format!("{} on fleet", rollup.fleet_exposure))
+								.size(14)
+								.width(Length::Fixed(90.0)),
+							progress_bar(0.0..=1.0, rollup.exposure_share as f32)
+								.height(Length::Fixed(10.0))
+								.width(Length::Fixed(150.0)),
+							Text::new(format!("{:.0}%", rollup.exposure_share * 100.0))
+								.size(14)
+								.width(Length::Fixed(50.0))
+								.horizontal_alignment(Horizontal::Right),
+						]
+							.spacing(10)
+							.align_items(Alignment::Center)
+							.into()
+					})
+					.collect::<Vec<Element<'_, Message>>>(),
+			)
+				.spacing(4)
+				.into()
+		};
+
+		let summary = match report.exposure_skew() {
+			Some(rollup) => Text::new(format!(
+				"Fleet exposure skews toward {} ({:.0}% of exposed CVEs with a known CWE)",
+				rollup.class.label().to_lowercase(),
+				rollup.exposure_share * 100.0,
+			))
+				.size(14)
+				.style(theme::Text::Color(format_severity("high", &self.theme()))),
+			None => Text::new("Share of open CVEs affecting deployed software, by CWE class.")
+				.size(12)
+				.style(theme::Text::Color(format_muted(&self.theme()))),
+		};
+
+		column![
+			Text::new("Weakness Classes")
+				.size(20),
+			summary,
+			rows,
+		]
+			.spacing(6)
+			.into()
+	}
+
+	fn quick_filters(&self) -> Element<'_, Message> {
+		let chips = QuickFilter::ALL.iter().map(|&filter| {
+			let label = match self.quick_filter_counts.iter().find(|(f, _)| *f == filter) {
+				Some((_, count)) => format!("{} ({})", filter.label(), count),
+				None => filter.label().to_string(),
+			};
+			let style = if self.quick_filters.contains(&filter) {
+				theme::Button::Primary
+			} else {
+				theme::Button::Secondary
+			};
+			button(Text::new(label).size(14))
+				.on_press(Message::QuickFilterToggled(filter))
+				.style(style)
+				.padding([4, 10])
+				.into()
+		});
+		Row::with_children(chips)
+			.spacing(8)
+			.align_items(Alignment::Center)
+			.into()
+	}
+
+	fn software_filter_banner(&self) -> Element<'_, Message> {
+		match &self.software_filter {
+			Some(software) => container(
+				row![
+					Text::new(format!("Remediation queue: {}", software.label()))
+						.size(16)
+						.width(Length::Fill),
+					button(Text::new("Show all").size(14))
+						.on_press(Message::ClearSoftwareFilter)
+						.style(theme::Button::Secondary)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+			)
+				.style(theme::Container::Box)
+				.padding(10)
+				.into(),
+			None => Space::with_height(Length::Shrink).into(),
+		}
+	}
+
+	fn compaction_banner(&self) -> Element<'_, Message> {
+		match &self.compaction_offer {
+			Some(stats) => container(
+				row![
+					Text::new(format!(
+						"The database could shrink by {:.1} MB ({}). Compact it now?",
+						stats.reclaimable_bytes() as f64 / (1024.0 * 1024.0),
+						stats
+					))
+						.size(16)
+						.width(Length::Fill),
+					button(Text::new("Compact").size(14))
+						.on_press(Message::CompactDatabase)
+						.style(theme::Button::Primary)
+						.padding(5),
+					button(Text::new("Not now").size(14))
+						.on_press(Message::CompactionDismissed)
+						.style(theme::Button::Secondary)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+			)
+				.style(theme::Container::Box)
+				.padding(10)
+				.into(),
+			None => Space::with_height(Length::Shrink).into(),
+		}
+	}
+
+	fn nvd_outage_banner(&self) -> Element<'_, Message> {
+		let health = &self.nvd_health;
+		match health.failing_since.filter(|_| health.is_outage()) {
+			Some(since) => container(
+				Text::new(format!(
+					"NVD unreachable since {} (last successful contact: {}). Showing cached data; enrichment checks again on its next run.",
+					time::format_local(since),
+					health.last_success.map(time::format_local).unwrap_or_else(|| "never".to_string())
+				))
+					.size(16)
+					.width(Length::Fill),
+			)
+				.style(theme::Container::Box)
+				.padding(10)
+				.into(),
+			None => Space::with_height(Length::Shrink).into(),
+		}
+	}
+
+	/// Last and next background sync over the enabled data sources, with buttons running
+	/// them now and opening their settings
+	fn sync_status_bar(&self) -> Element<'_, Message> {
+		let theme = self.theme();
+		let enabled = || self.data_sources.iter().filter(|source| source.enabled);
+		let last = enabled()
+			.filter_map(|source| source.last_run_at)
+			.max()
+			.map_or_else(|| "never".to_string(), time::format_local);
+		let next = match enabled().min_by_key(|source| source.next_run(self.started_at)) {
+			_ if self.syncing => "running".to_string(),
+			Some(source) if source.next_run(self.started_at) <= Utc::now() => format!("{} due", source.name),
+			Some(source) => format!("{} at {}", source.name, time::format_local(source.next_run(self.started_at))),
+			None => "no source enabled".to_string(),
+		};
+		let running = self.syncing || self.progress.is_some();
+
+		container(
+			row![
+				Text::new(format!("Last sync: {}    Next sync: {}", last, next))
+					.size(14)
+					.style(theme::Text::Color(format_muted(&theme)))
+					.width(Length::Fill),
+				button(Text::new("Data Sources").size(14))
+					.on_press(Message::DataSourcesOpened)
+					.style(if self.data_sources_view.is_some() {
+						theme::Button::Primary
+					} else {
+						theme::Button::Secondary
+					})
+					.padding(5),
+				button(Text::new("What's New").size(14))
+					.on_press(Message::WhatsNewOpened)
+					.style(if self.whats_new.is_some() {
+						theme::Button::Primary
+					} else {
+						theme::Button::Secondary
+					})
+					.padding(5),
+				button(Text::new(if self.syncing { "Syncing..." } else { "Sync Now" }).size(14))
+					.on_press_maybe((!running && self.role.can_edit()).then_some(Message::SyncNowClicked))
+					.style(theme::Button::Secondary)
+					.padding(5),
+			]
+				.spacing(10)
+				.align_items(Alignment::Center),
+		)
+			.style(theme::Container::Box)
+			.padding([4, 10])
+			.width(Length::Fill)
+			.into()
+	}
+
+	fn triage_controls<'a>(&'a self, vuln: &'a Vulnerability) -> Element<'a, Message> {
+		if !self.role.can_edit() {
+			let status = row![
+				Text::new(format!("Status: {}", vuln.status)).size(16),
+				Text::new(format!(
+					"Assigned to: {}",
+					vuln.assigned_to.as_deref().unwrap_or("Unassigned")
+				))
+				.size(16),
+			]
+			.spacing(20);
+
+			let Some(acceptance) = &vuln.risk_acceptance else {
+				return status.into();
+			};
+			return column![
+				status,
+				Text::new(format!(
+					"Justification: {}",
+					acceptance.justification.as_deref().unwrap_or("None given")
+				))
+				.size(14),
+				Text::new(format!(
+					"Approved by: {}    Expires: {}",
+					acceptance.approved_by.as_deref().unwrap_or("Nobody"),
+					acceptance.expires_on.map(|d| d.to_string()).unwrap_or_else(|| "Never".to_string()),
+				))
+				.size(14),
+			]
+			.spacing(5)
+			.into();
+		}
+
+		let status = row![
+			Text::new("Status:").size(16),
+			pick_list(
+				TriageStatus::ALL,
+				Some(self.triage_status),
+				Message::TriageStatusSelected,
+			)
+			.width(Length::Fixed(170.0))
+			.padding(5),
+			Text::new("Assigned to:").size(16),
+			text_input("Unassigned", &self.triage_assignee)
+				.on_input(Message::TriageAssigneeChanged)
+				.on_submit(Message::TriageSaved)
+				.padding(5)
+				.width(Length::Fixed(200.0)),
+			button(Text::new("Save").size(16))
+				.on_press(Message::TriageSaved)
+				.style(theme::Button::Primary)
+				.padding(5),
+		]
+		.spacing(10)
+		.align_items(Alignment::Center);
+
+		if !self.triage_status.is_risk_decision() {
+			return status.into();
+		}
+
+		let decision = row![
+			Text::new("Justification:").size(16),
+			text_input("Why the risk is acceptable", &self.triage_justification)
+				.on_input(Message::TriageJustificationChanged)
+				.padding(5)
+				.width(Length::Fill),
+			Text::new("Approved by:").size(16),
+			text_input("Approver", &self.triage_approver)
+				.on_input(Message::TriageApproverChanged)
+				.padding(5)
+				.width(Length::Fixed(160.0)),
+			Text::new("Expires:").size(16),
+			text_input("YYYY-MM-DD", &self.triage_expires)
+				.on_input(Message::TriageExpiryChanged)
+				.padding(5)
+				.width(Length::Fixed(120.0)),
+		]
+		.spacing(10)
+		.align_items(Alignment::Center);
+
+		column![status, decision].spacing(10).into()
+	}
+
+	/// The remediation ticket of the selected vulnerability, or a button raising one
+	fn ticket_controls(&self) -> Element<'_, Message> {
+		let theme = self.theme();
+		if let Some(ticket) = &self.ticket {
+			let status = ticket.status.as_deref().unwrap_or("status unknown");
+			let synced = ticket.synced_at
+				.map(|synced| format!(", synced {}", time::format_local(synced)))
+				.unwrap_or_default();
+			let key: Element<Message> = match &ticket.url {
+				Some(url) => button(Text::new(ticket.key.as_str()).size(14).style(theme::Text::Color(format_link(&theme))))
+					.on_press(Message::LinkOpened(url.clone()))
+					.style(theme::Button::Text)
+					.padding(0)
+					.into(),
+				None => Text::new(ticket.key.as_str()).size(14).into(),
+			};
+			return row![
+				Text::new("Ticket:").size(14),
+				key,
+				Text::new(format!("{}{}", status, synced))
+					.size(14)
+					.style(theme::Text::Color(format_muted(&theme))),
+			]
+				.spacing(8)
+				.align_items(Alignment::Center)
+				.into();
+		}
+		if !self.ticketing_enabled || !self.role.can_edit() {
+			return Space::with_height(0).into();
+		}
+		button(Text::new(if self.ticket_creating { "Creating Ticket..." } else { "Create Ticket" }).size(14))
+			.on_press_maybe((!self.ticket_creating).then_some(Message::TicketCreateClicked))
+			.style(theme::Button::Secondary)
+			.padding(5)
+			.into()
+	}
+
+	/// A field of the detail view, or its input while the fields are edited by hand
+	fn editable_field<'a>(&'a self, vuln: &'a Vulnerability, field: LockedField) -> Element<'a, Message> {
+		if let Some(form) = &self.field_edit {
+			return match field {
+				LockedField::Description => text_input("No description available", &form.description)
+					.on_input(Message::FieldEditDescriptionChanged)
+					.padding(5)
+					.width(Length::Fill)
+					.into(),
+				LockedField::Severity => text_input("Severity", &form.severity)
+					.on_input(Message::FieldEditSeverityChanged)
+					.on_submit(Message::FieldEditSaved)
+					.padding(5)
+					.width(Length::Fixed(120.0))
+					.into(),
+				LockedField::Impact => text_input("No impact information available", &form.impact)
+					.on_input(Message::FieldEditImpactChanged)
+					.padding(5)
+					.width(Length::Fill)
+					.into(),
+				LockedField::Mitigation => text_input("No mitigation steps available", &form.mitigation)
+					.on_input(Message::FieldEditMitigationChanged)
+					.padding(5)
+					.width(Length::Fill)
+					.into(),
+			};
+		}
+		match field {
+			LockedField::Description => Text::new(vuln.description.as_deref().unwrap_or("No description available"))
+				.size(16)
+				.width(Length::Fill)
+				.into(),
+			LockedField::Severity => Text::new(format_severity_label(&vuln.severity))
+				.size(16)
+				.style(theme::Text::Color(format_severity(&vuln.severity, &self.theme())))
+				.into(),
+			LockedField::Impact => Text::new(vuln.impact.as_deref().unwrap_or("No impact information available"))
+				.size(16)
+				.width(Length::Fill)
+				.into(),
+			LockedField::Mitigation => Text::new(vuln.mitigation.as_deref().unwrap_or("No mitigation steps available"))
+				.size(16)
+				.width(Length::Fill)
+				.into(),
+		}
+	}
+
+	fn field_edit_controls(&self) -> Element<'_, Message> {
+		if self.field_edit.is_none() {
+			return Space::with_height(Length::Fixed(0.0)).into();
+		}
+		row![
+			button(Text::new("Save").size(16))
+				.on_press(Message::FieldEditSaved)
+				.style(theme::Button::Primary)
+				.padding(5),
+			button(Text::new("Cancel").size(16))
+				.on_press(Message::FieldEditCancelled)
+				.style(theme::Button::Secondary)
+				.padding(5),
+			Text::new("Changed fields are marked as manually curated so NVD syncs and imports keep your edits")
+				.size(14)
+				.style(theme::Text::Color(format_muted(&self.theme()))),
+		]
+			.spacing(10)
+			.padding([0, 10])
+			.align_items(Alignment::Center)
+			.into()
+	}
+
+	fn progress_indicator(&self) -> Element<'_, Message> {
+		let Some(progress) = &self.progress else {
+			return Space::with_height(Length::Shrink).into();
+		};
+
+		let cancel = if self.cancel_requested {
+			button(Text::new("Cancelling...").size(14))
+				.style(theme::Button::Secondary)
+				.padding(5)
+		} else {
+			button(Text::new("Cancel").size(14))
+				.on_press(Message::CancelOperation)
+				.style(theme::Button::Destructive)
+				.padding(5)
+		};
+
+		container(
+			column![
+				Text::new(progress.summary()).size(14),
+				row![
+					progress_bar(0.0..=1.0, progress.fraction).height(Length::Fixed(8.0)),
+					cancel,
+				]
+				.spacing(10)
+				.align_items(Alignment::Center),
+			]
+			.spacing(5),
+		)
+		.style(theme::Container::Box)
+		.padding(10)
+		.width(Length::Fill)
+		.into()
+	}
+
+	fn enrichment_status(&self) -> Element<'_, Message> {
+		let Some(progress) = &self.enrichment_progress else {
+			return Space::with_height(Length::Shrink).into();
+		};
+
+		let remaining = format!(
+			"NVD enrichment: {} entries still incomplete ({} not yet attempted)",
+			progress.remaining_unknown, progress.never_attempted
+		);
+		let last_run = match &progress.last_run {
+			Some(run) => format!(
+				"Last batch{}: {}",
+				run.finished_at
+					.map(|t| format!(" at {}", time::format_local(t)))
+					.unwrap_or_default(),
+				run.summary()
+			),
+			None => "No enrichment batch has completed yet".to_string(),
+		};
+
+		column![
+			Text::new(remaining)
+				.size(14)
+				.horizontal_alignment(Horizontal::Center),
+			Text::new(last_run)
+				.size(12)
+				.style(theme::Text::Color(format_muted(&self.theme())))
+				.horizontal_alignment(Horizontal::Center),
+		]
+			.spacing(2)
+			.width(Length::Fill)
+			.align_items(Alignment::Center)
+			.into()
+	}
+}
+
+/// Who locked a field edited by hand, with the action that unlocks it
+fn field_lock<'a>(vuln: &'a Vulnerability, field: LockedField, can_edit: bool, theme: &Theme) -> Element<'a, Message> {
+	let Some(lock) = vuln.lock(field) else {
+		return Space::with_height(Length::Fixed(0.0)).into();
+	};
+	let edited = match lock.locked_at {
+		Some(locked_at) => format!("🔒 Manually curated by {} on {}", lock.locked_by, time::format_local(locked_at)),
+		None => format!("🔒 Manually curated by {}", lock.locked_by),
+	};
+	row![
+		Text::new(edited)
+			.size(14)
+			.style(theme::Text::Color(format_muted(theme))),
+		button(Text::new("Unlock").size(14))
+			.on_press_maybe(can_edit.then_some(Message::FieldUnlockClicked(field)))
+			.style(theme::Button::Text)
+			.padding(0),
+	]
+		.spacing(10)
+		.padding([0, 10])
+		.align_items(Alignment::Center)
+		.into()
+}
+
+/// Where the values of `fields` came from; manual edits are shown by their lock instead
+fn field_sources<'a>(vuln: &'a Vulnerability, fields: &[ProvenanceField], theme: &Theme) -> Element<'a, Message> {
+	let sources: Vec<String> = fields
+		.iter()
+		.filter_map(|field| vuln.provenance(*field))
+		.filter(|provenance| provenance.source != ProvenanceSource::Manual)
+		.map(|provenance| match provenance.updated_at {
+			Some(updated_at) => format!("{} from {} on {}", provenance.field, provenance.source, time::format_local(updated_at)),
+			None => format!("{} from {}", provenance.field, provenance.source),
+		})
+		.collect();
+	if sources.is_empty() {
+		return Space::with_height(Length::Fixed(0.0)).into();
+	}
+	container(
+		Text::new(format!("Source: {}", sources.join(" · ")))
+			.size(14)
+			.style(theme::Text::Color(format_muted(theme))),
+	)
+		.padding([0, 10])
+		.into()
+}
+
+/// CWEs of a vulnerability; clicking one lists all vulnerabilities of its class
+fn weakness_list(vuln: &Vulnerability) -> Element<'_, Message> {
+	if vuln.cwe_ids.is_empty() {
+		return Text::new("No CWE recorded").size(16).into();
+	}
+	Row::with_children(vuln.cwe_ids.iter().map(|cwe_id| {
+		let class = WeaknessClass::of(cwe_id);
+		button(Text::new(format!("{} · {}", cwe_id, class)).size(14))
+			.on_press(Message::FilterWeaknessChanged(FilterWeakness::Only(class)))
+			.style(theme::Button::Secondary)
+			.padding([4, 10])
+			.into()
+	}))
+		.spacing(8)
+		.into()
+}
+
+/// Compliance controls the vulnerability is mapped to, with a picker for editors to map
+/// it to further controls and a button to remove each mapping
+fn control_list<'a>(
+	vuln: &Vulnerability,
+	linked: &'a [ComplianceControl],
+	controls: &[ComplianceControl],
+	can_edit: bool,
+	theme: &Theme,
+) -> Element<'a, Message> {
+	let Some(vulnerability_id) = vuln.vulnerability_id else {
+		return Space::with_height(Length::Shrink).into();
+	};
+	let mut list = Column::with_children(linked.iter().map(|control| {
+		let mut entry = row![
+			Text::new(format!("{} {}", control.standard, control.code)).size(14),
+			Text::new(&control.title).size(14).style(theme::Text::Color(format_muted(theme))),
+		]
+			.spacing(10)
+			.align_items(Alignment::Center);
+		if can_edit {
+			entry = entry.push(
+				button(Text::new("×").size(14))
+					.on_press(Message::ControlLinkToggled(vulnerability_id, control.control_id, false))
+					.style(theme::Button::Text)
+					.padding([0, 6]),
+			);
+		}
+		entry.into()
+	}))
+		.spacing(4);
+	if linked.is_empty() {
+		list = list.push(Text::new("Not mapped to any compliance control").size(16));
+	}
+	if can_edit {
+		let unlinked: Vec<ComplianceControl> = controls
+			.iter()
+			.filter(|control| !linked.contains(control))
+			.cloned()
+			.collect();
+		list = list.push(
+			pick_list(unlinked, None::<ComplianceControl>, move |control| {
+				Message::ControlLinkToggled(vulnerability_id, control.control_id, true)
+			})
+				.placeholder("Map to control...")
+				.text_size(14)
+				.width(Length::Fixed(420.0))
+				.padding(5),
+		);
+	}
+	list.into()
+}
+
+/// Vulnerabilities sharing an affected product or CWE, most closely related first;
+/// clicking one opens it
+fn related_list<'a>(related: &'a [RelatedVulnerability], theme: &Theme) -> Element<'a, Message> {
+	if related.is_empty() {
+		return Text::new("No related CVEs found").size(16).into();
+	}
+	Column::with_children(related.iter().map(|related| {
+		row![
+			button(Text::new(&related.cve_id).size(14))
+				.on_press(Message::OpenVulnerability(related.cve_id.clone()))
+				.style(theme::Button::Text)
+				.padding(0)
+				.width(Length::Fixed(160.0)),
+			Text::new(format_severity_label(&related.severity))
+				.size(14)
+				.style(theme::Text::Color(format_severity(&related.severity, theme)))
+				.width(Length::Fixed(80.0)),
+			Text::new(format!("CVSS {:.1}", related.effective_cvss)).size(14).width(Length::Fixed(80.0)),
+			Text::new(related.status.to_string()).size(14).width(Length::Fixed(110.0)),
+			Text::new(related.reason())
+				.size(12)
+				.style(theme::Text::Color(format_muted(theme))),
+		]
+			.spacing(10)
+			.align_items(Alignment::Center)
+			.into()
+	}))
+		.spacing(4)
+		.into()
+}
+
+/// Links of a vulnerability with their tags; links open in the browser
+/// Buttons copying the ID and description of a vulnerability and opening its NVD and
+/// cve.org pages, shown in the detail view and in the menu of a list card
+fn copy_and_open_actions<'a>(vuln: &Vulnerability) -> Row<'a, Message> {
+	let action = |label: &'a str, message: Option<Message>| {
+		button(Text::new(label).size(14))
+			.on_press_maybe(message)
+			.style(theme::Button::Secondary)
+			.padding(5)
+	};
+	row![
+		action("Copy ID", Some(Message::CopyToClipboard("ID", vuln.cve_id.clone()))),
+		action(
+			"Copy Description",
+			vuln.description.clone().map(|description| Message::CopyToClipboard("Description", description)),
+		),
+		action("Open in NVD", vuln.nvd_url().map(Message::LinkOpened)),
+		action("Open on cve.org", vuln.cve_record_url().map(Message::LinkOpened)),
+	]
+		.spacing(10)
+}
+
+fn reference_list<'a>(references: &'a [Reference], theme: &Theme) -> Element<'a, Message> {
+	if references.is_empty() {
+		return Text::new("No references recorded").size(16).into();
+	}
+	Column::with_children(references.iter().map(|reference| {
+		let link: Element<Message> = if reference.is_link() {
+			button(Text::new(&reference.url).size(14).style(theme::Text::Color(format_link(theme))))
+				.on_press(Message::LinkOpened(reference.url.clone()))
+				.style(theme::Button::Text)
+				.padding(0)
+				.into()
+		} else {
+			Text::new(&reference.url).size(14).into()
+		};
+		let details: Vec<&str> = reference.tags
+			.iter()
+			.map(String::as_str)
+			.chain(reference.source.as_deref())
+			.collect();
+		row![
+			link,
+			Text::new(details.join(" · "))
+				.size(12)
+				.style(theme::Text::Color(format_muted(theme))),
+		]
+			.spacing(10)
+			.align_items(Alignment::Center)
+			.into()
+	}))
+		.spacing(4)
+		.into()
 }
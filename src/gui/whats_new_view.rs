@@ -0,0 +1,144 @@
+use super::state::AppState;
+use super::types::Message;
+use super::constants::WHATS_NEW_ROW_LIMIT;
+use super::formatters::{format_muted, format_severity, format_severity_label};
+use crate::models::sync_delta::SyncDelta;
+use crate::utils::time;
+use iced::{
+	theme,
+	widget::{button, column, container, row, scrollable, Column, Text},
+	Alignment, Element, Length, Theme,
+};
+
+pub trait WhatsNewViewRenderer {
+	fn whats_new_dialog<'a>(&'a self, deltas: &'a [SyncDelta]) -> Element<'a, Message>;
+}
+
+/// A CVE that opens when clicked, with its severity and an optional note
+fn cve_row<'a>(cve_id: &'a str, severity: &'a str, note: String, theme: &Theme) -> Element<'a, Message> {
+	row![
+		button(Text::new(cve_id).size(14))
+			.on_press(Message::OpenVulnerability(cve_id.to_string()))
+			.style(theme::Button::Text)
+			.padding(0)
+			.width(Length::Fixed(160.0)),
+		Text::new(format_severity_label(severity))
+			.size(14)
+			.style(theme::Text::Color(format_severity(severity, theme)))
+			.width(Length::Fixed(80.0)),
+		Text::new(note).size(14),
+	]
+		.spacing(10)
+		.align_items(Alignment::Center)
+		.into()
+}
+
+/// A titled list of at most `WHATS_NEW_ROW_LIMIT` rows, or nothing when empty
+fn section<'a>(title: &str, total: usize, rows: Vec<Element<'a, Message>>, theme: &Theme) -> Option<Element<'a, Message>> {
+	if total == 0 {
+		return None;
+	}
+	let mut list = Column::with_children(rows).spacing(4);
+	if total > WHATS_NEW_ROW_LIMIT {
+		list = list.push(
+			Text::new(format!("and {} more; export the changelog for all", total - WHATS_NEW_ROW_LIMIT))
+				.size(14)
+				.style(theme::Text::Color(format_muted(theme))),
+		);
+	}
+	Some(column![Text::new(format!("{} ({})", title, total)).size(16), list].spacing(6).into())
+}
+
+impl AppState {
+	fn sync_delta_entry<'a>(&'a self, delta: &'a SyncDelta) -> Element<'a, Message> {
+		let theme = self.theme();
+		let changes = &delta.changes;
+
+		let new = section(
+			"New vulnerabilities",
+			changes.new_vulnerabilities.len(),
+			changes.new_vulnerabilities
+				.iter()
+				.take(WHATS_NEW_ROW_LIMIT)
+				.map(|vuln| cve_row(&vuln.cve_id, &vuln.severity, String::new(), &theme))
+				.collect(),
+			&theme,
+		);
+		let severity = section(
+			"Severity changes",
+			changes.severity_changes.len(),
+			changes.severity_changes
+				.iter()
+				.take(WHATS_NEW_ROW_LIMIT)
+				.map(|change| cve_row(&change.cve_id, &change.after, format!("was {}", format_severity_label(&change.before)), &theme))
+				.collect(),
+			&theme,
+		);
+		let exposed = section(
+			"Newly exposed robots",
+			changes.new_exposures.len(),
+			changes.new_exposures
+				.iter()
+				.take(WHATS_NEW_ROW_LIMIT)
+				.map(|exposure| cve_row(&exposure.cve_id, &exposure.severity, exposure.robot.clone(), &theme))
+				.collect(),
+			&theme,
+		);
+
+		let mut entry = column![
+			row![
+				Text::new(time::format_local(delta.started_at)).size(18),
+				Text::new(delta.sources.join(", ")).size(14).style(theme::Text::Color(format_muted(&theme))),
+			]
+				.spacing(15)
+				.align_items(Alignment::Center),
+			Text::new(changes.to_string()).size(14),
+		]
+			.spacing(8);
+		for section in [new, severity, exposed].into_iter().flatten() {
+			entry = entry.push(section);
+		}
+		entry.into()
+	}
+}
+
+impl WhatsNewViewRenderer for AppState {
+	fn whats_new_dialog<'a>(&'a self, deltas: &'a [SyncDelta]) -> Element<'a, Message> {
+		let list: Element<Message> = if deltas.is_empty() {
+			Text::new("No sync changed anything yet").size(16).into()
+		} else {
+			scrollable(Column::with_children(deltas.iter().map(|delta| self.sync_delta_entry(delta))).spacing(25))
+				.height(Length::Fill)
+				.into()
+		};
+
+		container(
+			column![
+				row![
+					Text::new("What's New").size(28).width(Length::Fill),
+					button(Text::new("Export Changelog").size(16))
+						.on_press_maybe((!deltas.is_empty()).then_some(Message::WhatsNewExportRequested))
+						.style(theme::Button::Secondary)
+						.padding(5),
+					button(Text::new("Close").size(16))
+						.on_press(Message::WhatsNewClosed)
+						.style(theme::Button::Destructive)
+						.padding(5),
+				]
+					.spacing(10)
+					.align_items(Alignment::Center),
+				Text::new(
+					"What each data source sync changed, newest first. Syncs that changed nothing are left out. \
+					 Click a CVE to open it."
+				)
+					.size(14),
+				list,
+			]
+				.spacing(15),
+		)
+			.padding(20)
+			.width(Length::Fill)
+			.style(theme::Container::Box)
+			.into()
+	}
+}
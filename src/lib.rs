@@ -0,0 +1,26 @@
+// src/lib.rs
+
+//! Data layer of the Robot Vulnerability Database: the models, the SQLite schema and
+//! connection pool, and the repositories reading and writing it, shared by the GUI and
+//! CLI binary and usable by other Rust tools working on the same database.
+//!
+//! ```no_run
+//! use rvd_core::db::connection;
+//! use rvd_core::repositories::vulnerability_repo::VulnerabilityRepository;
+//! use std::sync::Arc;
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let pool = Arc::new(connection::establish_pool("default")?);
+//! let vulnerability = VulnerabilityRepository::new(pool).get_vulnerability_by_cve("CVE-2024-0001").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Writes are checked against the role set with `repositories::access::set_current_role`,
+//! as the binary does from the workspace settings on startup.
+
+pub mod db;
+pub mod models;
+pub mod reports;
+pub mod repositories;
+pub mod utils;
@@ -1,44 +1,74 @@
 // src/main.rs
 
-mod db;
-mod models;
-mod repositories;
+mod cli;
 mod gui;
-mod utils;
+
+use rvd_core::{db, models, reports, repositories, utils};
 
 use anyhow::{Context, Result};
+use clap::Parser;
 use db::connection::{self, SqlitePool};
 use db::schema;
+use db::workspace::Workspaces;
 use gui::app;
 use log::{error, info, warn};
+use repositories::access;
+use repositories::metrics_repo::MetricsRepository;
+use repositories::settings_repo::SettingsRepository;
+use repositories::trash_repo::TrashRepository;
 use repositories::vulnerability_repo::VulnerabilityRepository;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::signal;
 use tokio::time::{sleep, Duration};
+use chrono::Utc;
+use models::csv_mapping::CsvMapping;
+use models::data_source::SYNC_BATCH_SIZE;
 use utils::csv_importer::import_vulnerabilities_from_csv;
 use utils::nvd_api::NvdApiClient;
+use utils::progress::{self, Cancelled, ProgressReceiver, ProgressReporter};
 
-const BATCH_SIZE: usize = 50;
-const UPDATE_INTERVAL: Duration = Duration::from_secs(3600); // 1 hour
+/// How often the scheduler checks which data sources are due, so changes to their
+/// schedules and syncs started by hand take effect without a restart
+const SCHEDULE_POLL: Duration = Duration::from_secs(60);
+/// How often backups, maintenance, ticket sync and alerts are checked
+const HOUSEKEEPING_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 struct App {
 	pool: Arc<SqlitePool>,
 	nvd_client: NvdApiClient,
 	vulnerability_repo: VulnerabilityRepository,
+	progress: ProgressReporter,
+	progress_rx: ProgressReceiver,
+	workspace: String,
+	/// Vulnerability to show on startup, from a deep link
+	open_cve: Option<String>,
 	shutdown_signal: tokio::sync::broadcast::Sender<()>,
 }
 
 impl App {
-	async fn new() -> Result<Self> {
+	async fn new(workspace: String, open_cve: Option<String>) -> Result<Self> {
 		utils::logger::init();
-		info!("Starting Vulnerability Management Database application");
+		info!("Starting Vulnerability Management Database application in workspace {}", workspace);
 
 		let (shutdown_tx, _) = tokio::sync::broadcast::channel(1);
 		let pool = Arc::new(
-			connection::establish_pool()
-				.context("Failed to establish database connection pool")?,
+			connection::establish_pool(&workspace)
+				.with_context(|| format!("Failed to open workspace '{}'", workspace))?,
 		);
+		Workspaces::default().set_current(&workspace)?;
+
+		let settings = SettingsRepository::new(pool.clone());
+		let role = settings.get_role().await?;
+		access::set_current_role(role);
+		info!("Running with the {} role", role);
+		utils::time::set_display_time_zone(settings.get_time_zone().await?);
+		utils::http::set_network_settings(settings.get_network_settings().await?);
+		if let Some(directives) = settings.get_log_filter().await? {
+			if let Err(e) = utils::logger::apply_configured_filter(&directives) {
+				warn!("Ignoring the configured log filter: {:#}", e);
+			}
+		}
 
 		let vulnerability_repo = VulnerabilityRepository::new(pool.clone());
 
@@ -47,10 +77,16 @@ impl App {
 
 		info!("Database connection pool and NVD client established");
 
+		let (progress, progress_rx) = progress::channel();
+
 		Ok(App {
 			pool,
 			nvd_client,
 			vulnerability_repo,
+			progress,
+			progress_rx,
+			workspace,
+			open_cve,
 			shutdown_signal: shutdown_tx,
 		})
 	}
@@ -62,6 +98,8 @@ impl App {
 		Ok(())
 	}
 
+	/// Import the bundled CSV into an empty database in the background, so the GUI
+	/// can show the import progress instead of starting only once it is done.
 	async fn import_initial_data(&self) -> Result<()> {
 		let vulnerabilities = self.vulnerability_repo.get_all_vulnerabilities()
 			.await
@@ -70,33 +108,41 @@ impl App {
 		if vulnerabilities.is_empty() {
 			info!("Database is empty, starting initial data import");
 			let csv_path = self.get_csv_path()?;
+			let pool = self.pool.clone();
+			let nvd_client = self.nvd_client.clone();
+			let progress = self.progress.clone();
 
-			match import_vulnerabilities_from_csv(csv_path.to_string_lossy().into_owned(), self.pool.clone()).await {
-				Ok(count) => {
-					info!("Successfully imported {} vulnerabilities from CSV", count);
+			tokio::spawn(async move {
+				match import_vulnerabilities_from_csv(
+					csv_path.to_string_lossy().into_owned(),
+					pool,
+					CsvMapping::default(),
+					progress.clone(),
+				).await {
+					Ok(report) => {
+						info!("Initial CSV import finished: {}", report);
 
-					// After CSV import, update with NVD data
-					info!("Init NVD");
-					match self.update_vulnerability_data(true).await {
-						Ok(updated) => info!("Updated {} vulnerabilities with NVD data", updated),
-						Err(e) => warn!("Some NVD updates failed: {}", e),
+						// After CSV import, update with NVD data
+						info!("Init NVD");
+						match nvd_client.batch_update_vulnerabilities(SYNC_BATCH_SIZE * 2, progress).await {
+							Ok(updated) => info!("Updated {} vulnerabilities with NVD data", updated),
+							Err(e) => warn!("Some NVD updates failed: {}", e),
+						}
+					}
+					Err(e) if e.is::<Cancelled>() => {
+						info!("Initial CSV import cancelled; NVD enrichment skipped");
+					}
+					Err(e) => {
+						error!("Failed to import vulnerabilities from CSV: {}", e);
 					}
 				}
-				Err(e) => {
-					error!("Failed to import vulnerabilities from CSV: {}", e);
-				}
-			}
+			});
 		} else {
 			info!("Database contains {} vulnerabilities", vulnerabilities.len());
 		}
 		Ok(())
 	}
 
-	async fn update_vulnerability_data(&self, initial: bool) -> Result<usize> {
-		let batch_size = if initial { BATCH_SIZE * 2 } else { BATCH_SIZE };
-		self.nvd_client.batch_update_vulnerabilities(batch_size).await
-	}
-
 	fn get_csv_path(&self) -> Result<PathBuf> {
 		let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
 		path.push("src");
@@ -111,15 +157,65 @@ impl App {
 
 	async fn start_update_scheduler(&self) -> Result<()> {
 		let nvd_client = self.nvd_client.clone();
+		let pool = self.pool.clone();
+		let progress = self.progress.clone();
+		let workspace = self.workspace.clone();
 		let mut shutdown_rx = self.shutdown_signal.subscribe();
+		let started = Utc::now();
 
 		tokio::spawn(async move {
+			let mut housekeeping_at = tokio::time::Instant::now();
 			loop {
 				tokio::select! {
-					_ = sleep(UPDATE_INTERVAL) => {
-						match nvd_client.batch_update_vulnerabilities(BATCH_SIZE).await {
-							Ok(count) => info!("Scheduled update completed: {} vulnerabilities updated", count),
-							Err(e) => error!("Scheduled update failed: {}", e),
+					_ = sleep(SCHEDULE_POLL) => {
+						match utils::data_sources::sync_sources(pool.clone(), &nvd_client, Some(started), progress.clone()).await {
+							Ok(0) => {}
+							Ok(ran) => info!("Scheduled sync ran {} data sources", ran),
+							Err(e) => error!("Failed to read the data sources: {:#}", e),
+						}
+						if housekeeping_at.elapsed() < HOUSEKEEPING_INTERVAL {
+							continue;
+						}
+						housekeeping_at = tokio::time::Instant::now();
+						// Daily, when robotics terms are configured
+						match nvd_client.discover_keyword_matches(false, progress.clone()).await {
+							Ok(summary) if summary.searches > 0 => info!("Keyword discovery completed: {}", summary),
+							Ok(_) => {}
+							Err(e) => error!("Keyword discovery failed: {:#}", e),
+						}
+						match db::backup::back_up_if_due(pool.clone(), &workspace).await {
+							Ok(Some(path)) => info!("Scheduled backup written to {:?}", path),
+							Ok(None) => {}
+							Err(e) => error!("Scheduled backup failed: {:#}", e),
+						}
+						// Before maintenance, so the vacuum releases the purged pages
+						if access::current_role().can_edit() {
+							match TrashRepository::new(pool.clone()).purge_expired().await {
+								Ok(0) => {}
+								Ok(purged) => info!("Purged {} entries deleted more than 30 days ago", purged),
+								Err(e) => error!("Failed to purge deleted entries: {:#}", e),
+							}
+							// Today's point of the Trends tab, updated every run
+							if let Err(e) = MetricsRepository::new(pool.clone()).record_today().await {
+								error!("Failed to record daily metrics: {:#}", e);
+							}
+							// Triage statuses follow the remediation tickets
+							match utils::ticketing::sync(pool.clone()).await {
+								Ok(0) => {}
+								Ok(changed) => info!("Ticket sync changed the triage status of {} vulnerabilities", changed),
+								Err(e) => warn!("Failed to sync remediation tickets: {:#}", e),
+							}
+						}
+						// After the backup, so a copy exists before the file is vacuumed
+						match db::maintenance::run_if_due(pool.clone(), progress.clone()).await {
+							Ok(Some(report)) if report.is_intact() => info!("Scheduled maintenance completed: {}", report),
+							Ok(Some(report)) => error!("Scheduled maintenance: {}", report),
+							Ok(None) => {}
+							Err(e) => error!("Scheduled maintenance failed: {:#}", e),
+						}
+						// Also sends the daily digest once it is due
+						if let Err(e) = utils::alerts::dispatch(pool.clone(), false).await {
+							warn!("Failed to send email alerts: {}", e);
 						}
 					}
 					_ = shutdown_rx.recv() => {
@@ -133,10 +229,39 @@ impl App {
 		Ok(())
 	}
 
+	/// Import files dropped into the watch folder, when one is set and the role may
+	/// import
+	async fn start_watch_folder(&self) -> Result<()> {
+		let Some(dir) = SettingsRepository::new(self.pool.clone()).get_watch_folder().await? else {
+			return Ok(());
+		};
+		if !access::current_role().can_edit() {
+			info!("Not watching {:?}: the {} role is read-only", dir, access::current_role());
+			return Ok(());
+		}
+		let pool = self.pool.clone();
+		let progress = self.progress.clone();
+		let mut shutdown_rx = self.shutdown_signal.subscribe();
+
+		tokio::spawn(async move {
+			tokio::select! {
+				result = utils::watch_folder::watch(pool, dir, progress) => {
+					if let Err(e) = result {
+						error!("Watch folder stopped: {:#}", e);
+					}
+				}
+				_ = shutdown_rx.recv() => info!("Watch folder received shutdown signal"),
+			}
+		});
+
+		Ok(())
+	}
+
 	async fn run(&self) -> Result<()> {
 		self.init_database().await?;
 		self.import_initial_data().await?;
 		self.start_update_scheduler().await?;
+		self.start_watch_folder().await?;
 
 		let mut shutdown_rx = self.shutdown_signal.subscribe();
 
@@ -154,7 +279,13 @@ impl App {
 		});
 
 		tokio::select! {
-			result = app::run(self.pool.clone()) => {
+			result = app::run(
+				self.pool.clone(),
+				self.progress_rx.clone(),
+				self.progress.clone(),
+				self.workspace.clone(),
+				self.open_cve.clone(),
+			) => {
 				if let Err(e) = result {
 					error!("GUI application error: {}", e);
 					return Err(e.into());
@@ -179,6 +310,21 @@ impl App {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-	let app = App::new().await?;
-	app.run().await
-}
\ No newline at end of file
+	let cli = cli::Cli::parse();
+	cli.enter_data_dir()?;
+	let workspace = cli.workspace();
+
+	match cli.command {
+		Some(command) => {
+			utils::logger::init();
+			cli::run(command, &workspace).await
+		}
+		None => {
+			let open_cve = cli.open.or(cli.link)
+				.map(|target| utils::deep_link::parse_target(&target))
+				.transpose()?;
+			let app = App::new(workspace, open_cve).await?;
+			app.run().await
+		}
+	}
+}
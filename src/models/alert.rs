@@ -0,0 +1,107 @@
+// src/models/alert.rs
+
+use crate::utils::time;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// SMTP delivery of email alerts. The password is read from the `RVD_SMTP_PASSWORD`
+/// environment variable so it never lands in the database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertSettings {
+	pub smtp_host: String,
+	#[serde(default = "default_smtp_port")]
+	pub smtp_port: u16,
+	#[serde(default)]
+	pub smtp_username: Option<String>,
+	pub from: String,
+	pub recipients: Vec<String>,
+	/// Collect alerts into one email a day instead of sending each right away
+	#[serde(default)]
+	pub digest: bool,
+}
+
+fn default_smtp_port() -> u16 {
+	587
+}
+
+/// What an alert reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+	/// A deployed robot is newly affected by a vulnerability
+	Exposure,
+	/// The severity of a vulnerability affecting deployed robots changed
+	SeverityChange,
+}
+
+impl AlertKind {
+	pub fn from_db(value: &str) -> Option<Self> {
+		match value {
+			"exposure" => Some(AlertKind::Exposure),
+			"severity" => Some(AlertKind::SeverityChange),
+			_ => None,
+		}
+	}
+}
+
+/// A queued alert, joined with the CVE and robot it is about
+#[derive(Debug, Clone)]
+pub struct Alert {
+	pub alert_id: i64,
+	pub kind: AlertKind,
+	pub cve_id: String,
+	pub severity: String,
+	pub robot_name: Option<String>,
+	/// Operational note of the robot, e.g. "Do not reboot during a shift"
+	pub robot_note: Option<String>,
+	/// Old and new severity of a severity change, e.g. "Medium -> High"
+	pub detail: Option<String>,
+	pub created_at: Option<DateTime<Utc>>,
+}
+
+impl Alert {
+	/// One-line description used in subjects and digests
+	pub fn summary(&self) -> String {
+		match self.kind {
+			AlertKind::Exposure => format!(
+				"{} is exposed to {} ({})",
+				robot_label(self.robot_name.as_deref().unwrap_or("A robot"), self.robot_note.as_deref()),
+				self.cve_id,
+				self.severity
+			),
+			AlertKind::SeverityChange => format!(
+				"Severity of {} changed: {}",
+				self.cve_id,
+				self.detail.as_deref().unwrap_or(&self.severity)
+			),
+		}
+	}
+}
+
+/// A robot whose installed software was not refreshed within the configured maximum age,
+/// so its exposure may be out of date
+#[derive(Debug, Clone)]
+pub struct StaleInventory {
+	pub robot_id: i64,
+	pub robot_name: String,
+	pub operational_note: Option<String>,
+	pub refreshed_at: Option<DateTime<Utc>>,
+}
+
+impl StaleInventory {
+	/// One-line description used in subjects and digests
+	pub fn summary(&self) -> String {
+		let robot = robot_label(&self.robot_name, self.operational_note.as_deref());
+		match self.refreshed_at {
+			Some(refreshed) => format!("Inventory of {} not refreshed since {}", robot, time::format_local(refreshed)),
+			None => format!("Inventory of {} was never recorded", robot),
+		}
+	}
+}
+
+/// The robot's name followed by its operational note, on one line so it also fits a subject
+fn robot_label(name: &str, note: Option<&str>) -> String {
+	match note.map(|note| note.split_whitespace().collect::<Vec<_>>().join(" ")).filter(|note| !note.is_empty()) {
+		Some(note) => format!("{} (note: {})", name, note),
+		None => name.to_string(),
+	}
+}
@@ -0,0 +1,194 @@
+// src/models/audit.rs
+
+//! Audit trail of changes to the data. The repositories record who changed which
+//! record and how, so the log can serve as compliance evidence.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Kind of record an audit entry is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditEntity {
+	Vulnerability,
+	Robot,
+	SoftwareProduct,
+	SoftwareVersion,
+	Note,
+	Setting,
+	/// A feed or file import, recorded as one entry per run
+	Import,
+}
+
+impl AuditEntity {
+	pub const ALL: [AuditEntity; 7] = [
+		AuditEntity::Vulnerability,
+		AuditEntity::Robot,
+		AuditEntity::SoftwareProduct,
+		AuditEntity::SoftwareVersion,
+		AuditEntity::Note,
+		AuditEntity::Setting,
+		AuditEntity::Import,
+	];
+
+	/// Value stored in the `audit_log.entity` column
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			AuditEntity::Vulnerability => "vulnerability",
+			AuditEntity::Robot => "robot",
+			AuditEntity::SoftwareProduct => "software_product",
+			AuditEntity::SoftwareVersion => "software_version",
+			AuditEntity::Note => "note",
+			AuditEntity::Setting => "setting",
+			AuditEntity::Import => "import",
+		}
+	}
+
+	pub fn from_db(value: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|entity| entity.as_str() == value)
+	}
+}
+
+impl fmt::Display for AuditEntity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.pad(match self {
+			AuditEntity::Vulnerability => "Vulnerability",
+			AuditEntity::Robot => "Robot",
+			AuditEntity::SoftwareProduct => "Software product",
+			AuditEntity::SoftwareVersion => "Software version",
+			AuditEntity::Note => "Note",
+			AuditEntity::Setting => "Setting",
+			AuditEntity::Import => "Import",
+		})
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+	Insert,
+	Update,
+	/// Moved to Recently deleted, or removed outright where there is no undo
+	Delete,
+	/// Taken back out of Recently deleted
+	Restore,
+	/// Deleted for good from Recently deleted
+	Purge,
+	Import,
+}
+
+impl AuditAction {
+	pub const ALL: [AuditAction; 6] = [
+		AuditAction::Insert,
+		AuditAction::Update,
+		AuditAction::Delete,
+		AuditAction::Restore,
+		AuditAction::Purge,
+		AuditAction::Import,
+	];
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			AuditAction::Insert => "insert",
+			AuditAction::Update => "update",
+			AuditAction::Delete => "delete",
+			AuditAction::Restore => "restore",
+			AuditAction::Purge => "purge",
+			AuditAction::Import => "import",
+		}
+	}
+
+	pub fn from_db(value: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|action| action.as_str() == value)
+	}
+}
+
+impl fmt::Display for AuditAction {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.pad(self.as_str())
+	}
+}
+
+/// Old and new value of one field; `None` where the field was empty or the record
+/// did not exist
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldChange {
+	pub field: String,
+	pub old: Option<String>,
+	pub new: Option<String>,
+}
+
+impl FieldChange {
+	pub fn new(field: &str, old: Option<String>, new: Option<String>) -> Self {
+		Self { field: field.to_string(), old, new }
+	}
+
+	/// Changes between two sets of field values, in the order of the fields
+	pub fn diff(before: &[(&str, Option<String>)], after: &[(&str, Option<String>)]) -> Vec<FieldChange> {
+		let value = |fields: &[(&str, Option<String>)], field: &str| {
+			fields.iter().find(|(name, _)| *name == field).and_then(|(_, value)| value.clone())
+		};
+		let mut fields: Vec<&str> = after.iter().map(|(field, _)| *field).collect();
+		fields.extend(before.iter().map(|(field, _)| *field).filter(|field| !after.iter().any(|(name, _)| name == field)));
+		fields
+			.into_iter()
+			.map(|field| FieldChange::new(field, value(before, field), value(after, field)))
+			.filter(|change| change.old != change.new)
+			.collect()
+	}
+}
+
+impl fmt::Display for FieldChange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match (&self.old, &self.new) {
+			(None, Some(new)) => write!(f, "{}: {}", self.field, new),
+			(old, new) => write!(
+				f,
+				"{}: {} → {}",
+				self.field,
+				old.as_deref().unwrap_or("–"),
+				new.as_deref().unwrap_or("–")
+			),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+	pub audit_id: i64,
+	pub recorded_at: DateTime<Utc>,
+	/// User who made the change
+	pub actor: String,
+	pub entity: AuditEntity,
+	/// ID of the record, absent for settings and imports
+	pub entity_id: Option<i64>,
+	/// CVE ID, robot name or the like, kept as it was at the time of the change
+	pub label: String,
+	pub action: AuditAction,
+	pub changes: Vec<FieldChange>,
+	/// Shared by the entries of one bulk operation, which can be reverted together
+	pub batch_id: Option<i64>,
+}
+
+impl AuditEntry {
+	/// Changes on one line, e.g. "severity: Low → High; assigned_to: ana"
+	pub fn summary(&self) -> String {
+		self.changes.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_diff() {
+		let before = [("severity", Some("Low".to_string())), ("assigned_to", None), ("impact", Some("x".to_string()))];
+		let after = [("severity", Some("High".to_string())), ("assigned_to", Some("ana".to_string())), ("impact", Some("x".to_string()))];
+		let changes = FieldChange::diff(&before, &after);
+		assert_eq!(changes.iter().map(ToString::to_string).collect::<Vec<_>>(), ["severity: Low → High", "assigned_to: ana"]);
+
+		// A removed record lists every field it had
+		let changes = FieldChange::diff(&before, &[]);
+		assert_eq!(changes.iter().map(ToString::to_string).collect::<Vec<_>>(), ["severity: Low → –", "impact: x → –"]);
+	}
+}
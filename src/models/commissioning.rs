@@ -0,0 +1,33 @@
+// src/models/commissioning.rs
+
+//! Security commissioning checklist. The items, e.g. "Default passwords changed" or
+//! "Network segmented", are configured once per workspace and every robot goes
+//! through them; each open item counts towards the robot's risk score.
+
+use chrono::{DateTime, Utc};
+
+/// A configured checklist item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+	pub item_id: i64,
+	pub label: String,
+}
+
+/// A checklist item as it stands for one robot
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChecklistEntry {
+	pub item: ChecklistItem,
+	/// Who ticked the item off and when, `None` while it is open
+	pub completed: Option<(String, DateTime<Utc>)>,
+}
+
+impl ChecklistEntry {
+	pub fn is_done(&self) -> bool {
+		self.completed.is_some()
+	}
+}
+
+/// Items done out of all items, e.g. "3 of 5 done"
+pub fn progress(entries: &[ChecklistEntry]) -> String {
+	format!("{} of {} done", entries.iter().filter(|entry| entry.is_done()).count(), entries.len())
+}
@@ -0,0 +1,76 @@
+// src/models/compliance.rs
+
+//! Compliance controls from standards such as IEC 62443-3-3 and ISO/IEC 27001 Annex A.
+//! Vulnerabilities are linked to the controls they bear on, so operators can evidence
+//! per control which findings on the fleet were identified and how they were handled.
+
+use std::fmt;
+
+/// A control of a standard, e.g. "SR 5.1 Network segmentation" of IEC 62443-3-3
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComplianceControl {
+	pub control_id: i64,
+	pub standard: String,
+	pub code: String,
+	pub title: String,
+}
+
+impl ComplianceControl {
+	/// Orders controls by standard, then by the numbers in their codes, so "A.8.9"
+	/// comes before "A.8.20"
+	pub fn sort_key(&self) -> (String, Vec<u32>, String) {
+		let numbers = self.code
+			.split(|c: char| !c.is_ascii_digit())
+			.filter_map(|part| part.parse().ok())
+			.collect();
+		(self.standard.to_lowercase(), numbers, self.code.to_lowercase())
+	}
+}
+
+impl fmt::Display for ComplianceControl {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} {} {}", self.standard, self.code, self.title)
+	}
+}
+
+/// How the findings on the fleet linked to a control stand
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlCoverage {
+	pub control: ComplianceControl,
+	/// Linked vulnerabilities affecting at least one robot
+	pub findings: usize,
+	/// Of those, the ones not resolved yet
+	pub open: usize,
+	/// Robots affected by any of the findings
+	pub robots: usize,
+}
+
+impl ControlCoverage {
+	/// "No findings linked", "2 open" or "All handled"
+	pub fn status(&self) -> String {
+		match (self.findings, self.open) {
+			(0, _) => "No findings linked".to_string(),
+			(_, 0) => "All handled".to_string(),
+			(_, open) => format!("{} open", open),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_sort_key() {
+		let control = |code: &str| ComplianceControl {
+			control_id: 0,
+			standard: "ISO/IEC 27001 Annex A".to_string(),
+			code: code.to_string(),
+			title: String::new(),
+		};
+		let mut controls = [control("A.8.20"), control("A.8.9"), control("A.5.7")];
+		controls.sort_by_key(ComplianceControl::sort_key);
+		let codes: Vec<&str> = controls.iter().map(|c| c.code.as_str()).collect();
+		assert_eq!(codes, ["A.5.7", "A.8.9", "A.8.20"]);
+	}
+}
@@ -0,0 +1,261 @@
+// src/models/csv_mapping.rs
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Which CSV column feeds each vulnerability field, saved as a named preset so a
+/// recurring export from the same source system imports without remapping.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsvMapping {
+	/// System the CSV is exported from, e.g. "MITRE" or a scanner name
+	pub source: String,
+	pub cve_id: String,
+	pub severity: String,
+	pub description: String,
+	#[serde(default)]
+	pub references: Option<String>,
+	#[serde(default)]
+	pub published_date: Option<String>,
+	#[serde(default)]
+	pub impact: Option<String>,
+	#[serde(default)]
+	pub mitigation: Option<String>,
+	/// How a row for a CVE already in the database is merged into it
+	#[serde(default)]
+	pub merge_policy: MergePolicy,
+}
+
+/// How an import merges a row into a vulnerability that is already recorded. Locked
+/// fields are never overwritten, whatever the policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergePolicy {
+	/// Every mapped field takes the value in the file, even when that is empty
+	Overwrite,
+	/// A field takes the value in the file unless that is empty, so values added by
+	/// hand or by another source survive a file that lacks them
+	#[default]
+	NewestNonEmpty,
+	/// Recorded vulnerabilities are left as they are; only new CVEs are added
+	KeepExisting,
+}
+
+impl MergePolicy {
+	pub const ALL: [MergePolicy; 3] = [MergePolicy::Overwrite, MergePolicy::NewestNonEmpty, MergePolicy::KeepExisting];
+
+	/// Name used in presets and on the command line
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			MergePolicy::Overwrite => "overwrite",
+			MergePolicy::NewestNonEmpty => "newest-non-empty",
+			MergePolicy::KeepExisting => "keep-existing",
+		}
+	}
+
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|policy| policy.as_str().eq_ignore_ascii_case(name.trim()))
+	}
+}
+
+impl fmt::Display for MergePolicy {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			MergePolicy::Overwrite => "Overwrite every field",
+			MergePolicy::NewestNonEmpty => "Update non-empty fields",
+			MergePolicy::KeepExisting => "Keep existing entries",
+		})
+	}
+}
+
+impl Default for CsvMapping {
+	/// Layout of the MITRE CVE list (allitems.csv)
+	fn default() -> Self {
+		Self {
+			source: "MITRE".to_string(),
+			cve_id: "Name".to_string(),
+			severity: "Status".to_string(),
+			description: "Description".to_string(),
+			references: Some("References".to_string()),
+			published_date: Some("Phase".to_string()),
+			impact: Some("Votes".to_string()),
+			mitigation: Some("Comments".to_string()),
+			merge_policy: MergePolicy::default(),
+		}
+	}
+}
+
+/// A vulnerability field a CSV column can be mapped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvField {
+	CveId,
+	Severity,
+	Description,
+	References,
+	PublishedDate,
+	Impact,
+	Mitigation,
+}
+
+impl CsvField {
+	pub const ALL: [CsvField; 7] = [
+		CsvField::CveId,
+		CsvField::Severity,
+		CsvField::Description,
+		CsvField::References,
+		CsvField::PublishedDate,
+		CsvField::Impact,
+		CsvField::Mitigation,
+	];
+
+	/// Whether every import needs a column for the field
+	pub fn is_required(self) -> bool {
+		matches!(self, CsvField::CveId | CsvField::Severity | CsvField::Description)
+	}
+
+	/// Lowercase words that mark a header as holding the field, best match first
+	fn keywords(self) -> &'static [&'static str] {
+		match self {
+			CsvField::CveId => &["cve id", "cve", "name", "identifier", "id"],
+			CsvField::Severity => &["severity", "risk", "rating", "status"],
+			CsvField::Description => &["description", "summary", "details", "title"],
+			CsvField::References => &["references", "reference", "url", "link"],
+			CsvField::PublishedDate => &["published", "date", "phase"],
+			CsvField::Impact => &["impact", "votes"],
+			CsvField::Mitigation => &["mitigation", "solution", "remediation", "fix", "comments"],
+		}
+	}
+}
+
+impl fmt::Display for CsvField {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			CsvField::CveId => "CVE ID",
+			CsvField::Severity => "Severity",
+			CsvField::Description => "Description",
+			CsvField::References => "References",
+			CsvField::PublishedDate => "Published",
+			CsvField::Impact => "Impact",
+			CsvField::Mitigation => "Mitigation",
+		})
+	}
+}
+
+impl CsvMapping {
+	/// Guesses the mapping for a file with these headers: the MITRE layout when every
+	/// one of its columns is there, otherwise the header naming each field most
+	/// plainly, each header used at most once. Required fields nothing matches are
+	/// left empty.
+	pub fn guess(headers: &[String]) -> Self {
+		let mitre = Self::default();
+		if mitre.headers().iter().all(|name| headers.iter().any(|header| header.trim().eq_ignore_ascii_case(name))) {
+			return mitre;
+		}
+
+		let mut mapping = Self {
+			source: String::new(),
+			cve_id: String::new(),
+			severity: String::new(),
+			description: String::new(),
+			references: None,
+			published_date: None,
+			impact: None,
+			mitigation: None,
+			merge_policy: MergePolicy::default(),
+		};
+		let mut used = vec![false; headers.len()];
+		for field in CsvField::ALL {
+			let matched = field.keywords().iter().find_map(|keyword| {
+				headers.iter().enumerate().position(|(index, header)| {
+					let header = header.trim().to_ascii_lowercase();
+					!used[index] && (header == *keyword || header.split(|c: char| !c.is_ascii_alphanumeric()).any(|word| word == *keyword))
+				})
+			});
+			if let Some(index) = matched {
+				used[index] = true;
+				mapping.set_column(field, Some(headers[index].trim().to_string()));
+			}
+		}
+		mapping
+	}
+
+	/// Header of the column mapped to `field`, if any
+	pub fn column(&self, field: CsvField) -> Option<&str> {
+		match field {
+			CsvField::CveId => Some(self.cve_id.as_str()),
+			CsvField::Severity => Some(self.severity.as_str()),
+			CsvField::Description => Some(self.description.as_str()),
+			CsvField::References => self.references.as_deref(),
+			CsvField::PublishedDate => self.published_date.as_deref(),
+			CsvField::Impact => self.impact.as_deref(),
+			CsvField::Mitigation => self.mitigation.as_deref(),
+		}
+		.filter(|header| !header.is_empty())
+	}
+
+	/// Maps `field` to the column named `header`, or unmaps it
+	pub fn set_column(&mut self, field: CsvField, header: Option<String>) {
+		match field {
+			CsvField::CveId => self.cve_id = header.unwrap_or_default(),
+			CsvField::Severity => self.severity = header.unwrap_or_default(),
+			CsvField::Description => self.description = header.unwrap_or_default(),
+			CsvField::References => self.references = header,
+			CsvField::PublishedDate => self.published_date = header,
+			CsvField::Impact => self.impact = header,
+			CsvField::Mitigation => self.mitigation = header,
+		}
+	}
+
+	/// Required fields with no column mapped
+	pub fn unmapped_required(&self) -> Vec<CsvField> {
+		CsvField::ALL
+			.into_iter()
+			.filter(|field| field.is_required() && self.column(*field).is_none())
+			.collect()
+	}
+
+	/// Every mapped column header, required ones first
+	pub fn headers(&self) -> Vec<&str> {
+		[
+			Some(&self.cve_id),
+			Some(&self.severity),
+			Some(&self.description),
+			self.references.as_ref(),
+			self.published_date.as_ref(),
+			self.impact.as_ref(),
+			self.mitigation.as_ref(),
+		]
+			.into_iter()
+			.flatten()
+			.map(String::as_str)
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn headers(names: &[&str]) -> Vec<String> {
+		names.iter().map(|name| name.to_string()).collect()
+	}
+
+	#[test]
+	fn test_guess() {
+		let mitre = headers(&["Name", "Status", "Description", "References", "Phase", "Votes", "Comments"]);
+		assert_eq!(CsvMapping::guess(&mitre), CsvMapping::default());
+
+		let scanner = headers(&["Plugin ID", "CVE", "Risk", "Synopsis", "Solution", "See Also", "Published Date"]);
+		let mapping = CsvMapping::guess(&scanner);
+		assert_eq!(mapping.column(CsvField::CveId), Some("CVE"));
+		assert_eq!(mapping.column(CsvField::Severity), Some("Risk"));
+		assert_eq!(mapping.column(CsvField::PublishedDate), Some("Published Date"));
+		assert_eq!(mapping.column(CsvField::Mitigation), Some("Solution"));
+		assert_eq!(mapping.column(CsvField::References), None);
+		assert_eq!(mapping.unmapped_required(), vec![CsvField::Description]);
+
+		let mut mapping = mapping;
+		mapping.set_column(CsvField::Description, Some("Synopsis".to_string()));
+		assert!(mapping.unmapped_required().is_empty());
+		assert_eq!(mapping.headers(), vec!["CVE", "Risk", "Synopsis", "Published Date", "Solution"]);
+	}
+}
@@ -0,0 +1,160 @@
+// src/models/data_source.rs
+
+//! Feeds the background update syncs with, each enabled and scheduled on its own
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Shortest interval accepted, to stay well within the feeds' rate limits
+pub const MIN_SYNC_INTERVAL_MINUTES: u32 = 5;
+/// Vulnerabilities refreshed from the NVD API per sync
+pub const SYNC_BATCH_SIZE: usize = 50;
+
+/// What a data source fetches and how it is imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SourceKind {
+	/// Enrichment of tracked CVEs through the NVD CVE API
+	Nvd,
+	/// The CISA Known Exploited Vulnerabilities catalog
+	Kev,
+	/// The FIRST EPSS daily scores
+	Epss,
+	/// The OSV API, queried for the installed packages
+	Osv,
+	/// The GitHub Advisory Database, queried for the installed packages
+	Ghsa,
+	/// An NVD JSON 2.0 feed at any URL, e.g. an internal mirror
+	NvdFeed,
+}
+
+impl SourceKind {
+	/// Value stored in the `data_sources.kind` column
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			SourceKind::Nvd => "nvd",
+			SourceKind::Kev => "kev",
+			SourceKind::Epss => "epss",
+			SourceKind::Osv => "osv",
+			SourceKind::Ghsa => "ghsa",
+			SourceKind::NvdFeed => "nvd_feed",
+		}
+	}
+
+	pub fn from_db(value: &str) -> Option<Self> {
+		match value {
+			"nvd" => Some(SourceKind::Nvd),
+			"kev" => Some(SourceKind::Kev),
+			"epss" => Some(SourceKind::Epss),
+			"osv" => Some(SourceKind::Osv),
+			"ghsa" => Some(SourceKind::Ghsa),
+			"nvd_feed" => Some(SourceKind::NvdFeed),
+			_ => None,
+		}
+	}
+
+	/// Whether the source is fetched from its configured URL
+	pub fn uses_url(&self) -> bool {
+		matches!(self, SourceKind::Kev | SourceKind::Epss | SourceKind::Osv | SourceKind::NvdFeed)
+	}
+}
+
+impl std::fmt::Display for SourceKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.pad(match self {
+			SourceKind::Nvd => "NVD API",
+			SourceKind::Kev => "KEV catalog",
+			SourceKind::Epss => "EPSS scores",
+			SourceKind::Osv => "OSV API",
+			SourceKind::Ghsa => "GitHub advisories",
+			SourceKind::NvdFeed => "NVD JSON feed",
+		})
+	}
+}
+
+/// Outcome of a data source's last run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+	Succeeded,
+	Failed,
+}
+
+impl RunStatus {
+	/// Value stored in the `data_sources.last_status` column
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			RunStatus::Succeeded => "succeeded",
+			RunStatus::Failed => "failed",
+		}
+	}
+
+	pub fn from_db(value: &str) -> Option<Self> {
+		match value {
+			"succeeded" => Some(RunStatus::Succeeded),
+			"failed" => Some(RunStatus::Failed),
+			_ => None,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataSource {
+	pub source_id: i64,
+	pub name: String,
+	pub kind: SourceKind,
+	pub url: Option<String>,
+	pub enabled: bool,
+	pub interval_minutes: u32,
+	/// Start of the last run, scheduled or started by hand
+	pub last_run_at: Option<DateTime<Utc>>,
+	/// `None` while a run is in progress and before the first one
+	pub last_status: Option<RunStatus>,
+	/// Summary of the last run, or why it failed
+	pub last_message: Option<String>,
+}
+
+impl DataSource {
+	/// Feeds added by users can be removed; the built-in sources only disabled
+	pub fn is_custom(&self) -> bool {
+		self.kind == SourceKind::NvdFeed
+	}
+
+	/// When the next run starts; one interval after `started` when none ran yet
+	pub fn next_run(&self, started: DateTime<Utc>) -> DateTime<Utc> {
+		self.last_run_at.unwrap_or(started) + Duration::minutes(self.interval_minutes.into())
+	}
+
+	pub fn is_due(&self, now: DateTime<Utc>, started: DateTime<Utc>) -> bool {
+		self.enabled && now >= self.next_run(started)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_is_due() {
+		let started = Utc::now();
+		let mut source = DataSource {
+			source_id: 1,
+			name: "NVD".to_string(),
+			kind: SourceKind::Nvd,
+			url: None,
+			enabled: true,
+			interval_minutes: 60,
+			last_run_at: None,
+			last_status: None,
+			last_message: None,
+		};
+		assert!(!source.is_due(started + Duration::minutes(59), started));
+		assert!(source.is_due(started + Duration::minutes(60), started));
+
+		// A run started by hand pushes the next one back
+		source.last_run_at = Some(started + Duration::minutes(50));
+		assert!(!source.is_due(started + Duration::minutes(60), started));
+		source.interval_minutes = 10;
+		assert!(source.is_due(started + Duration::minutes(60), started));
+		source.enabled = false;
+		assert!(!source.is_due(started + Duration::minutes(60), started));
+	}
+}
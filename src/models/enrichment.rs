@@ -0,0 +1,65 @@
+// src/models/enrichment.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Result of trying to enrich a single vulnerability from the NVD
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnrichmentOutcome {
+	Updated,
+	Unchanged,
+	Failed,
+	RateLimited,
+}
+
+impl EnrichmentOutcome {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			EnrichmentOutcome::Updated => "updated",
+			EnrichmentOutcome::Unchanged => "unchanged",
+			EnrichmentOutcome::Failed => "failed",
+			EnrichmentOutcome::RateLimited => "rate_limited",
+		}
+	}
+}
+
+/// One batch of NVD enrichment with its outcome tallies
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnrichmentRun {
+	pub run_id: Option<i64>,
+	pub started_at: Option<DateTime<Utc>>,
+	pub finished_at: Option<DateTime<Utc>>,
+	pub batch_size: i64,
+	pub updated: i64,
+	pub unchanged: i64,
+	pub failed: i64,
+	pub rate_limited: i64,
+}
+
+impl EnrichmentRun {
+	pub fn record(&mut self, outcome: EnrichmentOutcome) {
+		match outcome {
+			EnrichmentOutcome::Updated => self.updated += 1,
+			EnrichmentOutcome::Unchanged => self.unchanged += 1,
+			EnrichmentOutcome::Failed => self.failed += 1,
+			EnrichmentOutcome::RateLimited => self.rate_limited += 1,
+		}
+	}
+
+	pub fn summary(&self) -> String {
+		format!(
+			"{} updated, {} unchanged, {} failed, {} rate-limited",
+			self.updated, self.unchanged, self.failed, self.rate_limited
+		)
+	}
+}
+
+/// What is left to enrich, as shown on the dashboard
+#[derive(Debug, Clone, Default)]
+pub struct EnrichmentProgress {
+	/// Vulnerabilities still missing NVD-provided fields
+	pub remaining_unknown: i64,
+	/// Of those, how many were never attempted yet
+	pub never_attempted: i64,
+	pub last_run: Option<EnrichmentRun>,
+}
@@ -0,0 +1,134 @@
+// src/models/graph.rs
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Record a relationship graph is centered on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GraphCenter {
+	Vulnerability(i64),
+	Robot(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeKind {
+	Vulnerability,
+	SoftwareVersion,
+	Robot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+	pub kind: NodeKind,
+	pub id: i64,
+	pub label: String,
+	/// Severity of a vulnerability node
+	pub severity: Option<String>,
+}
+
+impl GraphNode {
+	/// Graph to open when the node is clicked; software versions have none
+	pub fn center(&self) -> Option<GraphCenter> {
+		match self.kind {
+			NodeKind::Vulnerability => Some(GraphCenter::Vulnerability(self.id)),
+			NodeKind::Robot => Some(GraphCenter::Robot(self.id)),
+			NodeKind::SoftwareVersion => None,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+	pub from: usize,
+	pub to: usize,
+	/// Related by product rather than a direct link, drawn dashed
+	pub related: bool,
+}
+
+/// Records connected to a robot or CVE. The center is the first node.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RelationshipGraph {
+	pub nodes: Vec<GraphNode>,
+	pub edges: Vec<GraphEdge>,
+	/// Connected records left out to keep the graph readable
+	pub omitted: usize,
+}
+
+impl RelationshipGraph {
+	/// Index of the node, adding it when it is not in the graph yet
+	pub fn add_node(&mut self, kind: NodeKind, id: i64, label: String, severity: Option<String>) -> usize {
+		if let Some(idx) = self.find(kind, id) {
+			return idx;
+		}
+		self.nodes.push(GraphNode { kind, id, label, severity });
+		self.nodes.len() - 1
+	}
+
+	pub fn find(&self, kind: NodeKind, id: i64) -> Option<usize> {
+		self.nodes.iter().position(|node| node.kind == kind && node.id == id)
+	}
+
+	pub fn add_edge(&mut self, from: usize, to: usize, related: bool) {
+		let exists = self.edges
+			.iter()
+			.any(|e| (e.from, e.to) == (from, to) || (e.from, e.to) == (to, from));
+		if from != to && !exists {
+			self.edges.push(GraphEdge { from, to, related });
+		}
+	}
+
+	/// Number of hops from the center for each node, ignoring edge direction.
+	/// Nodes not connected to the center are placed one ring past the farthest.
+	pub fn depths(&self) -> Vec<usize> {
+		let mut depths = vec![usize::MAX; self.nodes.len()];
+		if self.nodes.is_empty() {
+			return depths;
+		}
+		depths[0] = 0;
+		let mut queue = VecDeque::from([0]);
+		while let Some(idx) = queue.pop_front() {
+			for edge in &self.edges {
+				let next = if edge.from == idx {
+					edge.to
+				} else if edge.to == idx {
+					edge.from
+				} else {
+					continue;
+				};
+				if depths[next] == usize::MAX {
+					depths[next] = depths[idx] + 1;
+					queue.push_back(next);
+				}
+			}
+		}
+
+		let farthest = depths.iter().copied().filter(|&d| d != usize::MAX).max().unwrap_or(0);
+		for depth in depths.iter_mut().filter(|d| **d == usize::MAX) {
+			*depth = farthest + 1;
+		}
+		depths
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_depths() {
+		let mut graph = RelationshipGraph::default();
+		let cve = graph.add_node(NodeKind::Vulnerability, 1, "CVE-2024-0001".to_string(), None);
+		let version = graph.add_node(NodeKind::SoftwareVersion, 7, "ros-core 1.0".to_string(), None);
+		let robot = graph.add_node(NodeKind::Robot, 3, "arm-01".to_string(), None);
+		let loose = graph.add_node(NodeKind::Robot, 4, "arm-02".to_string(), None);
+		graph.add_edge(cve, version, false);
+		graph.add_edge(version, robot, false);
+		graph.add_edge(robot, version, false);
+
+		assert_eq!(graph.add_node(NodeKind::Robot, 3, "arm-01".to_string(), None), robot);
+		assert_eq!(graph.edges.len(), 2);
+		assert_eq!(graph.depths(), [0, 1, 2, 3]);
+		assert_eq!(graph.nodes[loose].center(), Some(GraphCenter::Robot(4)));
+		assert_eq!(graph.nodes[version].center(), None);
+	}
+}
@@ -0,0 +1,156 @@
+// src/models/import_run.rs
+
+//! Runs of the vulnerability list imports (CSV and spreadsheets) and the robot
+//! inventory imports. Each vulnerability or robot a run creates is tagged with it and
+//! the previous values of the ones it overwrites are kept, so a bad run can be
+//! reverted in one go.
+
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportRunStatus {
+	/// Still importing, or the application stopped before the run finished
+	Running,
+	Completed,
+	/// Stopped by an error; the batches before it stay imported
+	Failed,
+	/// Cancelled by the user; the batches before it stay imported
+	Cancelled,
+	Reverted,
+}
+
+impl ImportRunStatus {
+	pub const ALL: [ImportRunStatus; 5] = [
+		ImportRunStatus::Running,
+		ImportRunStatus::Completed,
+		ImportRunStatus::Failed,
+		ImportRunStatus::Cancelled,
+		ImportRunStatus::Reverted,
+	];
+
+	/// Value stored in the `import_runs.status` column
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			ImportRunStatus::Running => "running",
+			ImportRunStatus::Completed => "completed",
+			ImportRunStatus::Failed => "failed",
+			ImportRunStatus::Cancelled => "cancelled",
+			ImportRunStatus::Reverted => "reverted",
+		}
+	}
+
+	pub fn from_db(value: &str) -> Self {
+		Self::ALL.into_iter().find(|status| status.as_str() == value).unwrap_or(ImportRunStatus::Failed)
+	}
+}
+
+impl fmt::Display for ImportRunStatus {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.pad(match self {
+			ImportRunStatus::Running => "Running",
+			ImportRunStatus::Completed => "Completed",
+			ImportRunStatus::Failed => "Failed",
+			ImportRunStatus::Cancelled => "Cancelled",
+			ImportRunStatus::Reverted => "Reverted",
+		})
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRun {
+	pub run_id: i64,
+	/// Imported file, e.g. "CSV file allitems.csv" or "Robot inventory fleet.csv"
+	pub source: String,
+	pub actor: String,
+	pub started_at: DateTime<Utc>,
+	pub finished_at: Option<DateTime<Utc>>,
+	pub status: ImportRunStatus,
+	/// Vulnerabilities, or robots for a robot inventory, the run added
+	pub created: i64,
+	/// Existing vulnerabilities or robots the run overwrote
+	pub updated: i64,
+	/// Rows the run could not import
+	pub skipped: i64,
+}
+
+impl ImportRun {
+	/// A run can be reverted once it has stopped, whether or not it got to the end
+	pub fn can_revert(&self) -> bool {
+		!matches!(self.status, ImportRunStatus::Running | ImportRunStatus::Reverted)
+	}
+}
+
+/// What reverting a run undid
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RevertSummary {
+	/// Vulnerabilities the run had added, now deleted
+	pub removed: usize,
+	/// Vulnerabilities set back to their values from before the run
+	pub restored: usize,
+	/// Robots the run had added, now deleted
+	pub robots_removed: usize,
+	/// Robots set back to their fields and software from before the run
+	pub robots_restored: usize,
+}
+
+impl fmt::Display for RevertSummary {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		// A run imports either vulnerabilities or robots
+		if self.robots_removed + self.robots_restored > 0 {
+			write!(f, "{} robots removed, {} restored", self.robots_removed, self.robots_restored)
+		} else {
+			write!(f, "{} vulnerabilities removed, {} restored", self.removed, self.restored)
+		}
+	}
+}
+
+/// Skipped rows kept per run; further ones are only counted, so a broken file of any
+/// size is reported in bounded memory
+pub const MAX_REPORTED_SKIPS: usize = 500;
+
+/// A row an import left out, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedRow {
+	/// Line of the CSV file, or row of the sheet, where the record starts
+	pub line: usize,
+	/// The CVE ID as written in the row, empty when it could not be read or the row
+	/// lists a robot
+	pub cve_id: String,
+	pub reason: String,
+}
+
+/// What an import of a vulnerability list did with each row
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+	pub imported: usize,
+	/// Every row left out, including those beyond `skipped_rows`
+	pub skipped: usize,
+	/// The first `MAX_REPORTED_SKIPS` rows left out, in file order
+	pub skipped_rows: Vec<SkippedRow>,
+}
+
+impl ImportReport {
+	pub fn skip(&mut self, line: usize, cve_id: &str, reason: String) {
+		self.skipped += 1;
+		if self.skipped_rows.len() < MAX_REPORTED_SKIPS {
+			self.skipped_rows.push(SkippedRow { line, cve_id: cve_id.trim().to_string(), reason });
+		}
+	}
+}
+
+impl fmt::Display for ImportReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} vulnerabilities imported, {} rows skipped", self.imported, self.skipped)
+	}
+}
+
+impl fmt::Display for SkippedRow {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.cve_id.is_empty() {
+			write!(f, "line {}: {}", self.line, self.reason)
+		} else {
+			write!(f, "line {} ({}): {}", self.line, self.cve_id, self.reason)
+		}
+	}
+}
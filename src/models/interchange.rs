@@ -0,0 +1,178 @@
+// src/models/interchange.rs
+
+//! RVD interchange format for moving fleet assessments between instances.
+//!
+//! Records reference each other by natural keys (CVE ID, product/vendor/version,
+//! robot name/manufacturer) instead of database IDs, so a document exported from
+//! one database can be merged into another.
+
+use crate::utils::product_match::ProductSuggestion;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Value of the `format` field identifying an interchange document
+pub const INTERCHANGE_FORMAT: &str = "rvd-interchange";
+/// Current document version; importers reject newer versions they do not understand
+pub const INTERCHANGE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterchangeDocument {
+	pub format: String,
+	pub version: u32,
+	/// RFC 3339 UTC timestamp of the export
+	pub exported_at: String,
+	#[serde(default)]
+	pub software: Vec<InterchangeProduct>,
+	#[serde(default)]
+	pub robots: Vec<InterchangeRobot>,
+	/// Which software versions are affected by which CVEs
+	#[serde(default)]
+	pub correlations: Vec<InterchangeCorrelation>,
+	/// Triage state and notes per CVE
+	#[serde(default)]
+	pub assessments: Vec<InterchangeAssessment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SoftwareRef {
+	pub product_name: String,
+	pub vendor: String,
+	pub version_number: String,
+}
+
+impl SoftwareRef {
+	/// Parse an entry of the robot form, `vendor/product version` or `product version`
+	/// for software by `default_vendor`
+	pub fn parse(entry: &str, default_vendor: &str) -> Option<Self> {
+		let (name, version) = entry.trim().rsplit_once(char::is_whitespace)?;
+		let (vendor, product) = name.split_once('/').unwrap_or((default_vendor, name));
+		let (vendor, product) = (vendor.trim(), product.trim());
+		if vendor.is_empty() || product.is_empty() {
+			return None;
+		}
+		Some(Self {
+			product_name: product.to_string(),
+			vendor: vendor.to_string(),
+			version_number: version.to_string(),
+		})
+	}
+}
+
+/// The form entry the reference parses back from
+impl fmt::Display for SoftwareRef {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}/{} {}", self.vendor, self.product_name, self.version_number)
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterchangeProduct {
+	pub product_name: String,
+	pub vendor: String,
+	#[serde(default)]
+	pub description: Option<String>,
+	/// SPDX license identifier or expression, as found in the SBOM
+	#[serde(default)]
+	pub license: Option<String>,
+	#[serde(default)]
+	pub versions: Vec<InterchangeVersion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterchangeVersion {
+	pub version_number: String,
+	#[serde(default)]
+	pub release_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterchangeRobot {
+	pub name: String,
+	#[serde(default)]
+	pub manufacturer: Option<String>,
+	#[serde(default)]
+	pub specifications: Option<String>,
+	#[serde(default)]
+	pub operational_note: Option<String>,
+	#[serde(default)]
+	pub installed_software: Vec<SoftwareRef>,
+	#[serde(default)]
+	pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterchangeCorrelation {
+	pub cve_id: String,
+	pub software: SoftwareRef,
+	pub affected_version_pattern: String,
+	#[serde(default)]
+	pub fixed_in_version: Option<String>,
+	#[serde(default = "default_confidence")]
+	pub detection_confidence: f64,
+}
+
+fn default_confidence() -> f64 {
+	1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterchangeAssessment {
+	pub cve_id: String,
+	pub status: String,
+	#[serde(default)]
+	pub assigned_to: Option<String>,
+	#[serde(default)]
+	pub notes: Vec<String>,
+}
+
+/// Counts of what an import created or changed
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct InterchangeImportSummary {
+	pub software_versions: usize,
+	pub robots_created: usize,
+	pub robots_updated: usize,
+	pub correlations: usize,
+	/// Correlations found by re-matching robots whose installed software changed
+	pub derived_correlations: usize,
+	pub assessments: usize,
+	pub notes: usize,
+	/// Products the import created because no existing product matched exactly
+	pub new_products: Vec<NewProduct>,
+}
+
+/// A product created by an import, with the existing products it may duplicate
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewProduct {
+	pub product_name: String,
+	pub vendor: String,
+	pub suggestions: Vec<ProductSuggestion>,
+}
+
+impl InterchangeDocument {
+	pub fn new(exported_at: String) -> Self {
+		Self {
+			format: INTERCHANGE_FORMAT.to_string(),
+			version: INTERCHANGE_VERSION,
+			exported_at,
+			software: Vec::new(),
+			robots: Vec::new(),
+			correlations: Vec::new(),
+			assessments: Vec::new(),
+		}
+	}
+
+	/// Check that this is an interchange document this build can read
+	pub fn validate(&self) -> anyhow::Result<()> {
+		if self.format != INTERCHANGE_FORMAT {
+			anyhow::bail!("Not an RVD interchange document (format '{}')", self.format);
+		}
+		if self.version == 0 || self.version > INTERCHANGE_VERSION {
+			anyhow::bail!(
+				"Unsupported interchange version {} (this build reads up to {})",
+				self.version,
+				INTERCHANGE_VERSION
+			);
+		}
+		Ok(())
+	}
+}
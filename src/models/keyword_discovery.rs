@@ -0,0 +1,85 @@
+// src/models/keyword_discovery.rs
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Days searched back on the first run
+pub const DEFAULT_LOOKBACK_DAYS: i64 = 120;
+/// Longest publication date range the NVD accepts in one query
+pub const MAX_WINDOW_DAYS: i64 = 120;
+/// Scheduled runs are at least this far apart
+pub const DISCOVERY_INTERVAL_HOURS: i64 = 24;
+
+/// Robotics terms (ROS, robot models...) periodically searched in the NVD so that new
+/// CVEs mentioning them are imported even when no installed software matches
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeywordDiscovery {
+	pub terms: Vec<String>,
+	/// Publication date up to which all terms have been searched
+	pub searched_until: Option<NaiveDate>,
+	pub last_run: Option<DateTime<Utc>>,
+}
+
+impl KeywordDiscovery {
+	/// Adds a term unless it is already configured, ignoring case
+	pub fn add_term(&mut self, term: &str) -> bool {
+		let term = term.trim();
+		if term.is_empty() || self.terms.iter().any(|t| t.eq_ignore_ascii_case(term)) {
+			return false;
+		}
+		self.terms.push(term.to_string());
+		true
+	}
+
+	pub fn remove_term(&mut self, term: &str) -> bool {
+		let before = self.terms.len();
+		self.terms.retain(|t| !t.eq_ignore_ascii_case(term.trim()));
+		self.terms.len() != before
+	}
+
+	pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+		!self.terms.is_empty()
+			&& self.last_run.is_none_or(|last| now - last >= Duration::hours(DISCOVERY_INTERVAL_HOURS))
+	}
+
+	/// Inclusive publication date ranges still to search up to `today`, oldest first.
+	/// The last searched day is searched again, as CVEs keep being published during it.
+	pub fn windows(&self, today: NaiveDate) -> Vec<(NaiveDate, NaiveDate)> {
+		let mut start = self.searched_until.unwrap_or(today - Duration::days(DEFAULT_LOOKBACK_DAYS));
+		let mut windows = Vec::new();
+		while start <= today {
+			let end = (start + Duration::days(MAX_WINDOW_DAYS - 1)).min(today);
+			windows.push((start, end));
+			start = end + Duration::days(1);
+		}
+		windows
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_windows_and_schedule() {
+		let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+		let mut discovery = KeywordDiscovery::default();
+		let now = Utc::now();
+		assert!(!discovery.is_due(now));
+		assert!(discovery.add_term("ROS 2"));
+		assert!(!discovery.add_term("ros 2"));
+		assert!(discovery.is_due(now));
+
+		let today = date(2024, 6, 30);
+		let windows = discovery.windows(today);
+		assert_eq!(windows, [(date(2024, 3, 2), date(2024, 6, 29)), (date(2024, 6, 30), today)]);
+
+		discovery.searched_until = Some(date(2024, 6, 29));
+		assert_eq!(discovery.windows(today), [(date(2024, 6, 29), today)]);
+
+		discovery.last_run = Some(now - Duration::hours(2));
+		assert!(!discovery.is_due(now));
+		assert!(discovery.remove_term("Ros 2"));
+		assert!(discovery.terms.is_empty());
+	}
+}
@@ -0,0 +1,227 @@
+// src/models/matrix.rs
+
+//! Affected-software matrix: one row per CVE and one column per deployed software
+//! version or robot, each cell saying whether the CVE affects it, is fixed in it or
+//! cannot be told from the recorded fix versions. Vulnerability managers track these
+//! in spreadsheets.
+
+use crate::models::vulnerability::TriageStatus;
+use crate::utils::version_match;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// What the columns of the matrix are
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatrixColumns {
+	/// Software versions installed on at least one robot
+	#[default]
+	Versions,
+	Robots,
+}
+
+/// Status of a CVE on one software version or robot. A robot takes the worst status
+/// of its installed versions, in the order below.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatrixCell {
+	NotAffected,
+	/// At or after the version the CVE was fixed in
+	Fixed,
+	/// The CVE affects other versions of the product and no fix version tells about this one
+	Unknown,
+	Affected,
+}
+
+impl MatrixCell {
+	/// Cell text; blank where the CVE has nothing to do with the column
+	pub fn label(&self) -> &'static str {
+		match self {
+			MatrixCell::NotAffected => "",
+			MatrixCell::Fixed => "fixed",
+			MatrixCell::Unknown => "unknown",
+			MatrixCell::Affected => "affected",
+		}
+	}
+}
+
+/// A software version installed on a robot
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeployedVersion {
+	pub version_id: i64,
+	pub product_id: i64,
+	pub product_name: String,
+	pub vendor: String,
+	pub version_number: String,
+}
+
+impl DeployedVersion {
+	pub fn label(&self) -> String {
+		format!("{} {} ({})", self.product_name, self.version_number, self.vendor)
+	}
+}
+
+/// A recorded correlation of a CVE with a version of a deployed product
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrixCorrelation {
+	pub cve_id: String,
+	pub severity: String,
+	pub status: TriageStatus,
+	pub version_id: i64,
+	pub product_id: i64,
+	pub fixed_in_version: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrixRow {
+	pub cve_id: String,
+	pub severity: String,
+	pub status: TriageStatus,
+	/// In the order of `AffectedMatrix::columns`
+	pub cells: Vec<MatrixCell>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AffectedMatrix {
+	pub columns: Vec<String>,
+	/// CVEs with at least one non-blank cell, by CVE ID
+	pub rows: Vec<MatrixRow>,
+}
+
+impl AffectedMatrix {
+	/// Builds the matrix from the deployed versions, the correlations of their products
+	/// and the versions installed on each robot, given as robot name and version ID
+	pub fn build(
+		columns: MatrixColumns,
+		versions: &[DeployedVersion],
+		correlations: &[MatrixCorrelation],
+		installs: &[(String, i64)],
+	) -> Self {
+		let mut by_cve: BTreeMap<&str, Vec<&MatrixCorrelation>> = BTreeMap::new();
+		for correlation in correlations {
+			by_cve.entry(&correlation.cve_id).or_default().push(correlation);
+		}
+
+		let robots: BTreeMap<&str, Vec<usize>> = installs.iter().fold(BTreeMap::new(), |mut robots, (robot, version_id)| {
+			if let Some(idx) = versions.iter().position(|v| v.version_id == *version_id) {
+				robots.entry(robot.as_str()).or_insert_with(Vec::new).push(idx);
+			}
+			robots
+		});
+
+		let column_names = match columns {
+			MatrixColumns::Versions => versions.iter().map(DeployedVersion::label).collect(),
+			MatrixColumns::Robots => robots.keys().map(|name| name.to_string()).collect(),
+		};
+
+		let rows = by_cve
+			.into_values()
+			.filter_map(|cve_correlations| {
+				let version_cells: Vec<MatrixCell> = versions.iter().map(|v| version_cell(v, &cve_correlations)).collect();
+				let cells: Vec<MatrixCell> = match columns {
+					MatrixColumns::Versions => version_cells,
+					MatrixColumns::Robots => robots
+						.values()
+						.map(|installed| {
+							installed.iter().map(|&idx| version_cells[idx]).max().unwrap_or(MatrixCell::NotAffected)
+						})
+						.collect(),
+				};
+				let first = cve_correlations[0];
+				cells.iter().any(|cell| *cell != MatrixCell::NotAffected).then(|| MatrixRow {
+					cve_id: first.cve_id.clone(),
+					severity: first.severity.clone(),
+					status: first.status,
+					cells,
+				})
+			})
+			.collect();
+
+		Self { columns: column_names, rows }
+	}
+}
+
+/// Status of a CVE on one version, from its own correlation or else from the fix
+/// versions recorded for other versions of the same product
+fn version_cell(version: &DeployedVersion, correlations: &[&MatrixCorrelation]) -> MatrixCell {
+	let is_fixed = |fixed_in: Option<&str>| {
+		fixed_in.is_some_and(|fixed| version_match::compare_versions(&version.version_number, fixed) != Ordering::Less)
+	};
+	if let Some(own) = correlations.iter().find(|c| c.version_id == version.version_id) {
+		return if is_fixed(own.fixed_in_version.as_deref()) { MatrixCell::Fixed } else { MatrixCell::Affected };
+	}
+
+	let same_product: Vec<&&MatrixCorrelation> = correlations.iter().filter(|c| c.product_id == version.product_id).collect();
+	if same_product.is_empty() {
+		MatrixCell::NotAffected
+	} else if same_product.iter().any(|c| is_fixed(c.fixed_in_version.as_deref())) {
+		MatrixCell::Fixed
+	} else {
+		MatrixCell::Unknown
+	}
+}
+
+/// Orders deployed versions by product, then by version number
+pub fn sort_versions(versions: &mut [DeployedVersion]) {
+	versions.sort_by(|a, b| {
+		(&a.product_name, &a.vendor)
+			.cmp(&(&b.product_name, &b.vendor))
+			.then_with(|| version_match::compare_versions(&a.version_number, &b.version_number))
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn version(version_id: i64, product_id: i64, version_number: &str) -> DeployedVersion {
+		DeployedVersion {
+			version_id,
+			product_id,
+			product_name: format!("product{}", product_id),
+			vendor: "OSRF".to_string(),
+			version_number: version_number.to_string(),
+		}
+	}
+
+	fn correlation(cve_id: &str, version_id: i64, product_id: i64, fixed_in: Option<&str>) -> MatrixCorrelation {
+		MatrixCorrelation {
+			cve_id: cve_id.to_string(),
+			severity: "High".to_string(),
+			status: TriageStatus::Open,
+			version_id,
+			product_id,
+			fixed_in_version: fixed_in.map(str::to_string),
+		}
+	}
+
+	#[test]
+	fn test_build() {
+		let mut versions = [version(3, 2, "5.0"), version(2, 1, "10.0"), version(1, 1, "1.0")];
+		sort_versions(&mut versions);
+		let correlations = [
+			// Affects 1.0, fixed in 1.5, so 10.0 has the fix
+			correlation("CVE-2024-0001", 1, 1, Some("1.5")),
+			// Recorded for an undeployed version without a fix version
+			correlation("CVE-2024-0002", 9, 1, None),
+			correlation("CVE-2024-0003", 3, 2, None),
+		];
+		let installs = [("arm-01".to_string(), 1), ("arm-01".to_string(), 3), ("arm-02".to_string(), 2)];
+
+		let matrix = AffectedMatrix::build(MatrixColumns::Versions, &versions, &correlations, &installs);
+		assert_eq!(matrix.columns, ["product1 1.0 (OSRF)", "product1 10.0 (OSRF)", "product2 5.0 (OSRF)"]);
+		let cells: Vec<Vec<&str>> = matrix.rows.iter().map(|row| row.cells.iter().map(MatrixCell::label).collect()).collect();
+		assert_eq!(cells, [
+			vec!["affected", "fixed", ""],
+			vec!["unknown", "unknown", ""],
+			vec!["", "", "affected"],
+		]);
+
+		let matrix = AffectedMatrix::build(MatrixColumns::Robots, &versions, &correlations, &installs);
+		assert_eq!(matrix.columns, ["arm-01", "arm-02"]);
+		let cells: Vec<Vec<MatrixCell>> = matrix.rows.into_iter().map(|row| row.cells).collect();
+		assert_eq!(cells, [
+			vec![MatrixCell::Affected, MatrixCell::Fixed],
+			vec![MatrixCell::Unknown, MatrixCell::Unknown],
+			vec![MatrixCell::Affected, MatrixCell::NotAffected],
+		]);
+	}
+}
@@ -1,6 +1,31 @@
 // src/models/mod.rs
 
+pub mod alert;
+pub mod audit;
+pub mod commissioning;
+pub mod compliance;
+pub mod csv_mapping;
+pub mod data_source;
+pub mod enrichment;
+pub mod graph;
+pub mod import_run;
+pub mod interchange;
+pub mod keyword_discovery;
+pub mod matrix;
+pub mod note;
+pub mod offline_bundle;
+pub mod nvd_health;
+pub mod reference;
+pub mod risk;
 pub mod robot;
+pub mod role;
+pub mod snapshot;
+pub mod sync_delta;
+pub mod statistics;
+pub mod ticket;
+pub mod trash;
+pub mod trends;
 pub mod vulnerability;
+pub mod weakness;
 pub(crate) mod vulnerability_csv;
-pub(crate) mod software;
+pub mod software;
@@ -0,0 +1,49 @@
+// src/models/note.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Kind of record a note is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoteEntity {
+	Vulnerability,
+	Robot,
+}
+
+impl NoteEntity {
+	/// Value stored in the `notes.entity_type` column
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			NoteEntity::Vulnerability => "vulnerability",
+			NoteEntity::Robot => "robot",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Note {
+	pub note_id: Option<i64>,
+	pub entity_type: NoteEntity,
+	pub entity_id: i64,
+	pub body: String,
+	pub created_at: Option<DateTime<Utc>>,
+	pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Note {
+	pub fn new(entity_type: NoteEntity, entity_id: i64, body: String) -> Self {
+		Self {
+			note_id: None,
+			entity_type,
+			entity_id,
+			body,
+			created_at: None,
+			updated_at: None,
+		}
+	}
+
+	/// Whether the note was changed after it was first written
+	pub fn is_edited(&self) -> bool {
+		self.updated_at.is_some() && self.updated_at != self.created_at
+	}
+}
@@ -0,0 +1,59 @@
+// src/models/nvd_health.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Consecutive failed requests, each already retried, after which the NVD counts as down
+pub const OUTAGE_THRESHOLD: u32 = 3;
+
+/// Whether the NVD API could be reached lately. Rate limiting is not a failure: the
+/// NVD answered, it only asked to slow down.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NvdHealth {
+	pub last_success: Option<DateTime<Utc>>,
+	/// First failure since the last success
+	pub failing_since: Option<DateTime<Utc>>,
+	pub consecutive_failures: u32,
+}
+
+impl NvdHealth {
+	pub fn record_success(&mut self, at: DateTime<Utc>) {
+		self.last_success = Some(at);
+		self.failing_since = None;
+		self.consecutive_failures = 0;
+	}
+
+	pub fn record_failure(&mut self, at: DateTime<Utc>) {
+		self.failing_since.get_or_insert(at);
+		self.consecutive_failures += 1;
+	}
+
+	pub fn is_outage(&self) -> bool {
+		self.consecutive_failures >= OUTAGE_THRESHOLD
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::Duration;
+
+	#[test]
+	fn test_outage_detection() {
+		let start = Utc::now();
+		let mut health = NvdHealth::default();
+		health.record_success(start);
+
+		for minutes in 1..=OUTAGE_THRESHOLD {
+			assert!(!health.is_outage());
+			health.record_failure(start + Duration::minutes(minutes.into()));
+		}
+		assert!(health.is_outage());
+		assert_eq!(health.failing_since, Some(start + Duration::minutes(1)));
+		assert_eq!(health.last_success, Some(start));
+
+		health.record_success(start + Duration::hours(1));
+		assert!(!health.is_outage());
+		assert_eq!(health.failing_since, None);
+	}
+}
@@ -0,0 +1,153 @@
+// src/models/offline_bundle.rs
+
+//! Offline bundles carry the vulnerability database and the sync state of its data
+//! sources from a connected instance to air-gapped ones, which merge them by the
+//! last-modified time of each record.
+
+use crate::models::reference::Reference;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Value of the `format` field identifying an offline bundle
+pub const BUNDLE_FORMAT: &str = "rvd-offline-bundle";
+/// Current bundle version; importers reject newer versions they do not understand
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineBundle {
+	pub format: String,
+	pub version: u32,
+	/// RFC 3339 UTC timestamp of the export
+	pub exported_at: String,
+	#[serde(default)]
+	pub vulnerabilities: Vec<BundleVulnerability>,
+	/// When each data source last ran on the exporting instance
+	#[serde(default)]
+	pub sources: Vec<BundleSource>,
+}
+
+/// A vulnerability with everything recorded about it, matched by CVE ID or alias
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleVulnerability {
+	pub cve_id: String,
+	#[serde(default)]
+	pub description: Option<String>,
+	pub severity: String,
+	#[serde(default)]
+	pub impact: Option<String>,
+	#[serde(default)]
+	pub mitigation: Option<String>,
+	#[serde(default)]
+	pub published_date: Option<String>,
+	#[serde(default)]
+	pub cvss_score: Option<f64>,
+	#[serde(default)]
+	pub cvss_version: Option<String>,
+	#[serde(default)]
+	pub kev_date_added: Option<String>,
+	#[serde(default)]
+	pub epss_score: Option<f64>,
+	#[serde(default)]
+	pub source: Option<String>,
+	/// Set when the entry is in Recently deleted
+	#[serde(default)]
+	pub deleted_at: Option<String>,
+	/// Last modification; entries from before modification times were kept have none
+	#[serde(default)]
+	pub updated_at: Option<String>,
+	#[serde(default)]
+	pub references: Vec<Reference>,
+	/// CWE IDs, e.g. `CWE-787`
+	#[serde(default)]
+	pub weaknesses: Vec<String>,
+	#[serde(default)]
+	pub aliases: Vec<BundleAlias>,
+	#[serde(default)]
+	pub triage: Option<BundleTriage>,
+	#[serde(default)]
+	pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleAlias {
+	pub alias: String,
+	/// Feed that uses the alias, e.g. GHSA or OSV
+	pub source: String,
+}
+
+/// Triage state and risk decision of a vulnerability
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleTriage {
+	pub status: String,
+	#[serde(default)]
+	pub assigned_to: Option<String>,
+	#[serde(default)]
+	pub justification: Option<String>,
+	#[serde(default)]
+	pub approved_by: Option<String>,
+	#[serde(default)]
+	pub accepted_at: Option<String>,
+	#[serde(default)]
+	pub expires_on: Option<String>,
+	pub updated_at: String,
+}
+
+/// Sync state of a data source, matched by name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleSource {
+	pub name: String,
+	#[serde(default)]
+	pub last_run_at: Option<String>,
+	#[serde(default)]
+	pub last_status: Option<String>,
+	#[serde(default)]
+	pub last_message: Option<String>,
+}
+
+/// Counts of what merging a bundle changed
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BundleImportSummary {
+	pub created: usize,
+	pub updated: usize,
+	/// Entries this instance has a copy of at least as recent as the bundle's
+	pub unchanged: usize,
+	pub triage_updated: usize,
+	pub sources_updated: usize,
+}
+
+impl fmt::Display for BundleImportSummary {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} vulnerabilities new, {} updated, {} unchanged, {} triage updates, {} data sources advanced",
+			self.created, self.updated, self.unchanged, self.triage_updated, self.sources_updated,
+		)
+	}
+}
+
+impl OfflineBundle {
+	pub fn new(exported_at: String) -> Self {
+		Self {
+			format: BUNDLE_FORMAT.to_string(),
+			version: BUNDLE_VERSION,
+			exported_at,
+			vulnerabilities: Vec::new(),
+			sources: Vec::new(),
+		}
+	}
+
+	/// Check that this is an offline bundle this build can read
+	pub fn validate(&self) -> anyhow::Result<()> {
+		if self.format != BUNDLE_FORMAT {
+			anyhow::bail!("Not an RVD offline bundle (format '{}')", self.format);
+		}
+		if self.version == 0 || self.version > BUNDLE_VERSION {
+			anyhow::bail!(
+				"Unsupported offline bundle version {} (this build reads up to {})",
+				self.version,
+				BUNDLE_VERSION
+			);
+		}
+		Ok(())
+	}
+}
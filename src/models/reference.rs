@@ -0,0 +1,108 @@
+// src/models/reference.rs
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+lazy_static! {
+	/// Vendor and CERT advisory identifiers commonly quoted in advisory emails
+	static ref ADVISORY_ID: Regex = Regex::new(
+		r"(?i)\b(GHSA(?:-[0-9a-z]{4}){3}|RH[SBE]A-\d{4}:\d{3,}|D[SL]A-\d{3,}-\d+|USN-\d{3,}-\d+|ICSA-\d{2}-\d{3}-\d{2}[a-z]?|GLSA-\d{6}-\d{2}|VU#\d{5,})"
+	).expect("advisory pattern is valid");
+}
+
+/// A link or advisory cited by a vulnerability
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reference {
+	/// The URL, or the bare identifier when the source gives no link
+	pub url: String,
+	/// Who published the reference, e.g. "CONFIRM" or "secalert@redhat.com"
+	pub source: Option<String>,
+	pub advisory_id: Option<String>,
+	/// What the NVD says the link is, e.g. "Patch", "Exploit" or "Vendor Advisory"
+	#[serde(default)]
+	pub tags: Vec<String>,
+}
+
+impl Reference {
+	pub fn new(url: String, source: Option<String>) -> Self {
+		let advisory_id = advisory_id(&url);
+		Self { url, source, advisory_id, tags: Vec::new() }
+	}
+
+	pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+		self.tags = tags;
+		self
+	}
+
+	pub fn has_tag(&self, tag: &str) -> bool {
+		self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+	}
+
+	/// Whether the reference can be opened in a browser
+	pub fn is_link(&self) -> bool {
+		self.url.starts_with("https://") || self.url.starts_with("http://")
+	}
+}
+
+/// Finds the first advisory identifier in `text`, normalised to its canonical case
+/// (GHSA IDs are lower case after the prefix, all others upper case).
+pub fn advisory_id(text: &str) -> Option<String> {
+	let found = ADVISORY_ID.find(text)?.as_str();
+	match found.get(..5) {
+		Some(prefix) if prefix.eq_ignore_ascii_case("GHSA-") => {
+			Some(format!("GHSA-{}", found[5..].to_lowercase()))
+		}
+		_ => Some(found.to_uppercase()),
+	}
+}
+
+/// Parses the MITRE "References" column, e.g.
+/// `URL:https://example.com/a   |   REDHAT:RHSA-2005:123`.
+pub fn parse_csv_references(raw: &str) -> Vec<Reference> {
+	raw.split('|')
+		.map(str::trim)
+		.filter(|entry| !entry.is_empty())
+		.map(|entry| match entry.split_once(':') {
+			// The source tag is an upper-case word such as MISC or CONFIRM; anything
+			// else (e.g. "https:") is part of the value
+			Some((source, value))
+				if !source.is_empty()
+					&& source.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-') =>
+			{
+				Reference::new(value.trim().to_string(), Some(source.to_string()))
+			}
+			_ => Reference::new(entry.to_string(), None),
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_advisory_id() {
+		assert_eq!(advisory_id("https://access.redhat.com/errata/rhsa-2024:1234"), Some("RHSA-2024:1234".to_string()));
+		assert_eq!(
+			advisory_id("https://github.com/advisories/GHSA-ABCD-1234-wxyz"),
+			Some("GHSA-abcd-1234-wxyz".to_string())
+		);
+		assert_eq!(advisory_id("https://www.cisa.gov/news-events/ics-advisories/icsa-24-051-02"), Some("ICSA-24-051-02".to_string()));
+		assert_eq!(advisory_id("https://example.com/blog/post"), None);
+	}
+
+	#[test]
+	fn test_parse_csv_references() {
+		let refs = parse_csv_references("URL:https://example.com/a   |   REDHAT:RHSA-2005:123   |   ");
+		assert_eq!(refs.len(), 2);
+		assert_eq!(refs[0].url, "https://example.com/a");
+		assert_eq!(refs[0].source.as_deref(), Some("URL"));
+		assert_eq!(refs[1].url, "RHSA-2005:123");
+		assert_eq!(refs[1].advisory_id.as_deref(), Some("RHSA-2005:123"));
+
+		let bare = parse_csv_references("https://example.com/b");
+		assert_eq!(bare[0].url, "https://example.com/b");
+		assert_eq!(bare[0].source, None);
+	}
+}
@@ -0,0 +1,130 @@
+// src/models/risk.rs
+
+//! Per-robot risk score. Each unresolved vulnerability on each installed software
+//! version counts as an exposure with a chance of being exploited (1 when CISA lists
+//! it as known exploited, otherwise its EPSS probability) and a damage of CVSS / 10.
+//! The score is the chance that at least one exposure hurts, weighted by damage, on a
+//! 0-100 scale, so it grows with every exposure but never past 100. Each open item of
+//! the robot's commissioning checklist counts as one more exposure.
+//!
+//! The fleet risk index averages the robot scores weighted by robot criticality, so
+//! one exposed production-critical robot outweighs several exposed test benches.
+
+use crate::models::robot::Criticality;
+use std::fmt;
+
+/// Exploitation probability assumed for vulnerabilities without an EPSS score
+pub const DEFAULT_EXPLOIT_PROBABILITY: f64 = 0.1;
+
+/// One unresolved vulnerability on one software version installed on the robot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exposure {
+	pub cvss: f64,
+	/// EPSS probability of exploitation in the next 30 days
+	pub epss: Option<f64>,
+	pub known_exploited: bool,
+}
+
+impl Exposure {
+	/// An open commissioning checklist item, e.g. default passwords not changed, weighed
+	/// like a medium vulnerability without an EPSS score
+	pub const OPEN_CHECKLIST_ITEM: Exposure = Exposure { cvss: 5.0, epss: None, known_exploited: false };
+
+	fn likelihood(&self) -> f64 {
+		if self.known_exploited {
+			1.0
+		} else {
+			self.epss.unwrap_or(DEFAULT_EXPLOIT_PROBABILITY).clamp(0.0, 1.0)
+		}
+	}
+}
+
+/// Risk score from 0 (nothing exposed) to 100
+pub fn robot_risk_score(exposures: &[Exposure]) -> f64 {
+	let spared: f64 = exposures
+		.iter()
+		.map(|exposure| 1.0 - exposure.likelihood() * (exposure.cvss / 10.0).clamp(0.0, 1.0))
+		.product();
+	((1.0 - spared) * 100.0 * 10.0).round() / 10.0
+}
+
+/// Fleet risk index from 0 to 100 over the robots' scores and criticality
+pub fn fleet_risk_index(robots: &[(f64, Criticality)]) -> f64 {
+	let total_weight: f64 = robots.iter().map(|(_, criticality)| criticality.weight()).sum();
+	if total_weight == 0.0 {
+		return 0.0;
+	}
+	let weighted: f64 = robots.iter().map(|(score, criticality)| score * criticality.weight()).sum();
+	(weighted / total_weight * 10.0).round() / 10.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RiskBand {
+	None,
+	Low,
+	Medium,
+	High,
+	Critical,
+}
+
+impl RiskBand {
+	pub fn of(score: f64) -> Self {
+		match score {
+			s if s >= 70.0 => RiskBand::Critical,
+			s if s >= 40.0 => RiskBand::High,
+			s if s >= 15.0 => RiskBand::Medium,
+			s if s > 0.0 => RiskBand::Low,
+			_ => RiskBand::None,
+		}
+	}
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			RiskBand::None => "None",
+			RiskBand::Low => "Low",
+			RiskBand::Medium => "Medium",
+			RiskBand::High => "High",
+			RiskBand::Critical => "Critical",
+		}
+	}
+}
+
+impl fmt::Display for RiskBand {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_robot_risk_score() {
+		assert_eq!(robot_risk_score(&[]), 0.0);
+
+		let kev = Exposure { cvss: 10.0, epss: None, known_exploited: true };
+		assert_eq!(robot_risk_score(&[kev]), 100.0);
+
+		let unscored = Exposure { cvss: 8.0, epss: None, known_exploited: false };
+		let likely = Exposure { cvss: 8.0, epss: Some(0.9), known_exploited: false };
+		assert_eq!(robot_risk_score(&[unscored]), 8.0);
+		assert_eq!(robot_risk_score(&[likely]), 72.0);
+		// More exposures only ever raise the score
+		assert!(robot_risk_score(&[unscored, unscored]) > robot_risk_score(&[unscored]));
+
+		assert_eq!(robot_risk_score(&[Exposure::OPEN_CHECKLIST_ITEM; 2]), 9.8);
+
+		assert_eq!(RiskBand::of(72.0), RiskBand::Critical);
+		assert_eq!(RiskBand::of(8.0), RiskBand::Low);
+		assert_eq!(RiskBand::of(0.0), RiskBand::None);
+	}
+
+	#[test]
+	fn test_fleet_risk_index() {
+		assert_eq!(fleet_risk_index(&[]), 0.0);
+		assert_eq!(fleet_risk_index(&[(30.0, Criticality::Medium), (60.0, Criticality::Medium)]), 45.0);
+		// The critical robot counts eight times as much as the low one
+		assert_eq!(fleet_risk_index(&[(90.0, Criticality::Critical), (0.0, Criticality::Low)]), 80.0);
+	}
+}
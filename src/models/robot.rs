@@ -1,6 +1,12 @@
 // src/models/robot.rs
 
+use crate::models::vulnerability::Vulnerability;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Days after which an inventory counts as stale unless configured otherwise
+pub const DEFAULT_INVENTORY_MAX_AGE_DAYS: u32 = 90;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Robot {
@@ -8,6 +14,32 @@ pub struct Robot {
 	pub name: String,
 	pub specifications: Option<String>,
 	pub manufacturer: Option<String>,
+	#[serde(default)]
+	pub model: Option<String>,
+	#[serde(default)]
+	pub firmware_version: Option<String>,
+	/// Operating system, e.g. "Ubuntu 22.04"
+	#[serde(default)]
+	pub os: Option<String>,
+	/// ROS distribution as entered, e.g. "ROS 2 Humble", see `ros_codename`
+	#[serde(default)]
+	pub ros_distro: Option<String>,
+	/// Operational context shown next to the robot in alerts and reports,
+	/// e.g. "air-gapped" or "scheduled for retirement Q3"
+	#[serde(default)]
+	pub operational_note: Option<String>,
+	/// Fleet risk score from 0 to 100 as of the last scoring, see `models::risk`
+	#[serde(default)]
+	pub risk_score: Option<f64>,
+	/// How much the business depends on the robot, weighting it in the fleet risk index
+	#[serde(default)]
+	pub criticality: Criticality,
+	/// When the installed software was last recorded or confirmed; vulnerability matches
+	/// against an old inventory may no longer hold
+	#[serde(default)]
+	pub inventory_refreshed_at: Option<DateTime<Utc>>,
+	#[serde(default)]
+	pub inventory_source: Option<InventorySource>,
 }
 
 impl Robot {
@@ -17,6 +49,15 @@ impl Robot {
 			name,
 			specifications: None,
 			manufacturer: None,
+			model: None,
+			firmware_version: None,
+			os: None,
+			ros_distro: None,
+			operational_note: None,
+			risk_score: None,
+			criticality: Criticality::default(),
+			inventory_refreshed_at: None,
+			inventory_source: None,
 		}
 	}
 
@@ -29,4 +70,191 @@ impl Robot {
 		self.specifications = Some(specifications);
 		self
 	}
-}
\ No newline at end of file
+
+	/// Whether the inventory was last refreshed more than `max_age_days` ago, or never
+	pub fn inventory_is_stale(&self, max_age_days: u32, now: DateTime<Utc>) -> bool {
+		self.inventory_refreshed_at
+			.is_none_or(|refreshed| now - refreshed > Duration::days(max_age_days.into()))
+	}
+}
+
+/// A vulnerability affecting software installed on a robot, with since when the robot
+/// has been exposed to it
+#[derive(Debug, Clone)]
+pub struct RobotExposure {
+	pub vulnerability: Vulnerability,
+	/// When the affected software was installed or the vulnerability published,
+	/// whichever came later
+	pub exposed_since: Option<DateTime<Utc>>,
+}
+
+impl RobotExposure {
+	/// `installed` is when the earliest affected version still on the robot was installed
+	pub fn new(vulnerability: Vulnerability, installed: Option<DateTime<Utc>>) -> Self {
+		let published = vulnerability.published_date.map(|date| date.and_time(NaiveTime::MIN).and_utc());
+		Self { exposed_since: installed.max(published), vulnerability }
+	}
+
+	/// Whole days the robot has been exposed as of `now`; `None` once the vulnerability
+	/// is resolved
+	pub fn exposure_days(&self, now: DateTime<Utc>) -> Option<i64> {
+		if !self.vulnerability.status.is_unresolved() {
+			return None;
+		}
+		self.exposed_since.map(|since| (now - since).num_days().max(0))
+	}
+}
+
+/// How a robot's inventory was last refreshed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InventorySource {
+	/// Software entered or changed in the robot form
+	Manual,
+	/// A CSV or JSON robot inventory import
+	Import,
+	/// A fleet interchange document from another RVD workspace
+	FleetImport,
+	/// Someone checked the robot and confirmed the recorded software is still current
+	Confirmed,
+}
+
+impl InventorySource {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			InventorySource::Manual => "manual",
+			InventorySource::Import => "import",
+			InventorySource::FleetImport => "fleet_import",
+			InventorySource::Confirmed => "confirmed",
+		}
+	}
+
+	pub fn parse(value: &str) -> Option<Self> {
+		[Self::Manual, Self::Import, Self::FleetImport, Self::Confirmed]
+			.into_iter()
+			.find(|source| source.as_str() == value)
+	}
+}
+
+impl fmt::Display for InventorySource {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			InventorySource::Manual => "entered manually",
+			InventorySource::Import => "inventory import",
+			InventorySource::FleetImport => "fleet import",
+			InventorySource::Confirmed => "confirmed",
+		})
+	}
+}
+
+/// Codename of a ROS distribution written like "ROS 2 Humble", "ros2-jazzy" or "noetic",
+/// lowercased as ROS versions are recorded
+pub fn ros_codename(distro: &str) -> Option<String> {
+	distro
+		.split(|c: char| !c.is_alphanumeric())
+		.map(str::to_lowercase)
+		.find(|word| !word.is_empty() && !matches!(word.as_str(), "ros" | "ros1" | "ros2") && word.parse::<u32>().is_err())
+}
+
+/// Codenames of the ROS 1 distributions, which ended with Noetic
+const ROS1_CODENAMES: [&str; 13] = [
+	"boxturtle", "cturtle", "diamondback", "electric", "fuerte", "groovy", "hydro",
+	"indigo", "jade", "kinetic", "lunar", "melodic", "noetic",
+];
+
+/// Product the ROS distribution `codename` is installed as: "ros1" or "ros2". The
+/// codenames of the two generations interleave alphabetically, so comparing them across
+/// generations would match ROS 1 advisories such as `<= melodic` against ROS 2 Humble.
+pub fn ros_product(codename: &str) -> &'static str {
+	if ROS1_CODENAMES.contains(&codename) { "ros1" } else { "ros2" }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Criticality {
+	Low,
+	#[default]
+	Medium,
+	High,
+	Critical,
+}
+
+impl Criticality {
+	pub const ALL: [Criticality; 4] = [Criticality::Low, Criticality::Medium, Criticality::High, Criticality::Critical];
+
+	/// Weight of the robot's risk score in the fleet risk index
+	pub fn weight(&self) -> f64 {
+		match self {
+			Criticality::Low => 0.5,
+			Criticality::Medium => 1.0,
+			Criticality::High => 2.0,
+			Criticality::Critical => 4.0,
+		}
+	}
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Criticality::Low => "Low",
+			Criticality::Medium => "Medium",
+			Criticality::High => "High",
+			Criticality::Critical => "Critical",
+		}
+	}
+
+	/// The criticality named `value`, ignoring case
+	pub fn from_label(value: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|c| c.as_str().eq_ignore_ascii_case(value.trim()))
+	}
+
+	/// Parses the stored value, treating anything unknown as the default
+	pub fn parse(value: &str) -> Self {
+		Self::from_label(value).unwrap_or_default()
+	}
+}
+
+impl fmt::Display for Criticality {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::vulnerability::TriageStatus;
+
+	#[test]
+	fn test_ros_codename() {
+		assert_eq!(ros_codename("ROS 2 Humble Hawksbill").as_deref(), Some("humble"));
+		assert_eq!(ros_codename("ros2-jazzy").as_deref(), Some("jazzy"));
+		assert_eq!(ros_codename("Noetic").as_deref(), Some("noetic"));
+		assert_eq!(ros_codename("ROS 2"), None);
+		assert_eq!(ros_product("noetic"), "ros1");
+		assert_eq!(ros_product("humble"), "ros2");
+	}
+
+	#[test]
+	fn test_inventory_is_stale() {
+		let now = Utc::now();
+		let mut robot = Robot::new("arm-01".to_string());
+		assert!(robot.inventory_is_stale(90, now));
+		robot.inventory_refreshed_at = Some(now - Duration::days(90));
+		assert!(!robot.inventory_is_stale(90, now));
+		assert!(robot.inventory_is_stale(30, now));
+	}
+
+	#[test]
+	fn test_exposure_days() {
+		let now = Utc::now();
+		let mut vulnerability = Vulnerability::new("CVE-2024-0001".to_string(), "High".to_string());
+		vulnerability.published_date = Some((now - Duration::days(30)).date_naive());
+
+		// Installed before the CVE was published, so exposed since publication
+		let exposure = RobotExposure::new(vulnerability.clone(), Some(now - Duration::days(400)));
+		assert_eq!(exposure.exposure_days(now), Some(30));
+		// Installed after publication
+		let exposure = RobotExposure::new(vulnerability.clone(), Some(now - Duration::days(10)));
+		assert_eq!(exposure.exposure_days(now), Some(10));
+
+		vulnerability.status = TriageStatus::Mitigated;
+		assert_eq!(RobotExposure::new(vulnerability, Some(now)).exposure_days(now), None);
+	}
+}
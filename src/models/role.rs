@@ -0,0 +1,41 @@
+// src/models/role.rs
+
+use serde::{Deserialize, Serialize};
+
+/// What the current user may do. Until user accounts exist this is configured per installation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Role {
+	#[default]
+	Admin,
+	Viewer,
+}
+
+impl Role {
+	pub const ALL: [Role; 2] = [Role::Admin, Role::Viewer];
+
+	/// Value stored in the settings table
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Role::Admin => "admin",
+			Role::Viewer => "viewer",
+		}
+	}
+
+	pub fn from_db(value: &str) -> Option<Self> {
+		Self::ALL
+			.iter()
+			.copied()
+			.find(|role| role.as_str().eq_ignore_ascii_case(value.trim()))
+	}
+
+	/// Whether records may be created, edited or deleted
+	pub fn can_edit(&self) -> bool {
+		matches!(self, Role::Admin)
+	}
+}
+
+impl std::fmt::Display for Role {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
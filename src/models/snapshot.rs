@@ -0,0 +1,136 @@
+// src/models/snapshot.rs
+
+//! Daily snapshots of the fleet's open exposures and risk scores, and the differences
+//! between two of them: the CVEs opened and closed in between and how each robot's risk
+//! moved, as reviewed in a recurring security meeting.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// State of the fleet at the end of a UTC day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FleetSnapshot {
+	pub taken_on: NaiveDate,
+	pub fleet_risk_index: f64,
+	pub robots: Vec<RobotSnapshot>,
+	/// Unresolved vulnerabilities affecting at least one robot, by CVE ID
+	pub open: Vec<OpenVulnerability>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RobotSnapshot {
+	pub robot_id: i64,
+	pub name: String,
+	pub risk_score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenVulnerability {
+	pub cve_id: String,
+	pub severity: String,
+	/// Names of the exposed robots
+	pub robots: Vec<String>,
+}
+
+/// Risk score of one robot in both snapshots; `None` where it was not recorded yet
+/// or was removed
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskDelta {
+	pub name: String,
+	pub before: Option<f64>,
+	pub after: Option<f64>,
+}
+
+impl RiskDelta {
+	pub fn change(&self) -> f64 {
+		self.after.unwrap_or_default() - self.before.unwrap_or_default()
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotDiff {
+	pub from: NaiveDate,
+	pub to: NaiveDate,
+	pub fleet_risk_before: f64,
+	pub fleet_risk_after: f64,
+	/// Open in the later snapshot only
+	pub opened: Vec<OpenVulnerability>,
+	/// Open in the earlier snapshot only, as it was then
+	pub closed: Vec<OpenVulnerability>,
+	/// Every robot of either snapshot, largest risk increase first
+	pub risk: Vec<RiskDelta>,
+}
+
+impl SnapshotDiff {
+	pub fn between(before: &FleetSnapshot, after: &FleetSnapshot) -> Self {
+		let only_in = |a: &FleetSnapshot, b: &FleetSnapshot| -> Vec<OpenVulnerability> {
+			a.open
+				.iter()
+				.filter(|vuln| !b.open.iter().any(|other| other.cve_id == vuln.cve_id))
+				.cloned()
+				.collect()
+		};
+
+		let mut risk: HashMap<i64, RiskDelta> = HashMap::new();
+		for robot in &before.robots {
+			risk.insert(robot.robot_id, RiskDelta { name: robot.name.clone(), before: Some(robot.risk_score), after: None });
+		}
+		for robot in &after.robots {
+			let delta = risk
+				.entry(robot.robot_id)
+				.or_insert_with(|| RiskDelta { name: robot.name.clone(), before: None, after: None });
+			delta.name = robot.name.clone();
+			delta.after = Some(robot.risk_score);
+		}
+		let mut risk: Vec<RiskDelta> = risk.into_values().collect();
+		risk.sort_by(|a, b| b.change().total_cmp(&a.change()).then_with(|| a.name.cmp(&b.name)));
+
+		SnapshotDiff {
+			from: before.taken_on,
+			to: after.taken_on,
+			fleet_risk_before: before.fleet_risk_index,
+			fleet_risk_after: after.fleet_risk_index,
+			opened: only_in(after, before),
+			closed: only_in(before, after),
+			risk,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_snapshot_diff() {
+		let robot = |robot_id, name: &str, risk_score| RobotSnapshot { robot_id, name: name.to_string(), risk_score };
+		let open = |cve_id: &str| OpenVulnerability {
+			cve_id: cve_id.to_string(),
+			severity: "High".to_string(),
+			robots: vec!["arm-01".to_string()],
+		};
+		let before = FleetSnapshot {
+			taken_on: NaiveDate::from_ymd_opt(2024, 5, 6).unwrap(),
+			fleet_risk_index: 20.0,
+			robots: vec![robot(1, "arm-01", 30.0), robot(2, "agv-01", 10.0)],
+			open: vec![open("CVE-2024-0001"), open("CVE-2024-0002")],
+		};
+		let after = FleetSnapshot {
+			taken_on: NaiveDate::from_ymd_opt(2024, 5, 13).unwrap(),
+			fleet_risk_index: 35.5,
+			robots: vec![robot(1, "arm-01 (cell 2)", 60.0), robot(3, "cobot-01", 5.0)],
+			open: vec![open("CVE-2024-0002"), open("CVE-2024-0003")],
+		};
+
+		let diff = SnapshotDiff::between(&before, &after);
+		assert_eq!(diff.opened.iter().map(|v| v.cve_id.as_str()).collect::<Vec<_>>(), ["CVE-2024-0003"]);
+		assert_eq!(diff.closed.iter().map(|v| v.cve_id.as_str()).collect::<Vec<_>>(), ["CVE-2024-0001"]);
+		assert_eq!(diff.risk, [
+			RiskDelta { name: "arm-01 (cell 2)".to_string(), before: Some(30.0), after: Some(60.0) },
+			RiskDelta { name: "cobot-01".to_string(), before: None, after: Some(5.0) },
+			RiskDelta { name: "agv-01".to_string(), before: Some(10.0), after: None },
+		]);
+		assert_eq!((diff.fleet_risk_before, diff.fleet_risk_after), (20.0, 35.5));
+	}
+}
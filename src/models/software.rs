@@ -1,6 +1,6 @@
 // src/models/software.rs
 
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,6 +9,9 @@ pub struct SoftwareProduct {
 	pub product_name: String,
 	pub vendor: String,
 	pub description: Option<String>,
+	/// SPDX license identifier or expression
+	#[serde(default)]
+	pub license: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +31,94 @@ pub struct AffectedSoftware {
 	pub detection_confidence: f64,
 }
 
+/// A deployed software version ranked by fleet-wide risk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskySoftware {
+	pub product_id: i64,
+	pub product_name: String,
+	pub vendor: String,
+	pub version_id: i64,
+	pub version_number: String,
+	pub license: Option<String>,
+	/// Number of robots with this version installed
+	pub robot_count: i64,
+	/// Sum of the CVSS scores of unresolved vulnerabilities affecting this version
+	pub cvss_sum: f64,
+}
+
+impl RiskySoftware {
+	/// Ranking score: robots deployed × summed CVSS of unresolved vulnerabilities
+	pub fn risk_score(&self) -> f64 {
+		self.robot_count as f64 * self.cvss_sum
+	}
+
+	pub fn label(&self) -> String {
+		format!("{} {} ({})", self.product_name, self.version_number, self.vendor)
+	}
+}
+
+/// A software version installed on a robot, as listed in the inventory report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryEntry {
+	pub robot_name: String,
+	pub product_name: String,
+	pub vendor: String,
+	pub version_number: String,
+	pub license: Option<String>,
+	/// Unresolved vulnerabilities affecting this version
+	pub open_vulnerabilities: i64,
+	pub max_cvss: Option<f64>,
+	/// Days the robot has been exposed to the oldest of them, counted from when the
+	/// version was installed or the vulnerability published, whichever came later
+	#[serde(default)]
+	pub max_exposure_days: Option<i64>,
+}
+
+/// A software version with the metadata maintained on the Software tab
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionMetadata {
+	pub version_id: i64,
+	pub product_name: String,
+	pub vendor: String,
+	pub version_number: String,
+	pub release_date: Option<NaiveDate>,
+	/// End of vendor support
+	pub eol_date: Option<NaiveDate>,
+	pub notes: Option<String>,
+	/// Robots with this version installed
+	pub robot_count: i64,
+}
+
+impl VersionMetadata {
+	pub fn label(&self) -> String {
+		format!("{} {} ({})", self.product_name, self.version_number, self.vendor)
+	}
+
+	/// Days since the release, the measure of how stale a deployed version is
+	pub fn age_days(&self, today: NaiveDate) -> Option<i64> {
+		self.release_date.map(|released| (today - released).num_days())
+	}
+
+	pub fn is_eol(&self, today: NaiveDate) -> bool {
+		self.eol_date.is_some_and(|eol| eol <= today)
+	}
+}
+
+/// Metadata written to one or more versions; `None` leaves a field as it is and
+/// `Some(None)` clears it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VersionMetadataChange {
+	pub release_date: Option<Option<NaiveDate>>,
+	pub eol_date: Option<Option<NaiveDate>>,
+	pub notes: Option<Option<String>>,
+}
+
+impl VersionMetadataChange {
+	pub fn is_empty(&self) -> bool {
+		self.release_date.is_none() && self.eol_date.is_none() && self.notes.is_none()
+	}
+}
+
 impl SoftwareProduct {
 	pub fn new(name: String, vendor: String) -> Self {
 		Self {
@@ -35,6 +126,7 @@ impl SoftwareProduct {
 			product_name: name,
 			vendor,
 			description: None,
+			license: None,
 		}
 	}
 }
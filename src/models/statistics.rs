@@ -0,0 +1,134 @@
+// src/models/statistics.rs
+
+use serde::{Deserialize, Serialize};
+use crate::models::weakness::WeaknessClass;
+use std::collections::BTreeMap;
+
+/// Share of the fleet's exposure above which a weakness class is highlighted
+pub const EXPOSURE_SKEW_SHARE: f64 = 0.4;
+
+/// Version of the exported statistics document; bump on breaking changes to its shape
+pub const STATISTICS_FORMAT_VERSION: u32 = 1;
+
+/// Dashboard aggregates over the whole database, serialized as a stable JSON document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatisticsReport {
+	pub format_version: u32,
+	/// RFC 3339 UTC timestamp of when the report was computed
+	pub generated_at: String,
+	/// Filter the vulnerability counts were restricted to, `None` for the whole database.
+	/// Totals, severities, statuses and publication years follow the filter; the fleet
+	/// and weakness rollups always cover everything.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub filter: Option<String>,
+	pub totals: Totals,
+	pub by_severity: BTreeMap<String, i64>,
+	pub by_status: BTreeMap<String, i64>,
+	/// Vulnerabilities per publication year, the basis for trend charts
+	pub published_per_year: BTreeMap<String, i64>,
+	/// Fleet rollups grouped by robot manufacturer
+	pub by_manufacturer: Vec<GroupRollup>,
+	/// Mean days from publication to being marked mitigated, if anything was mitigated
+	pub mttr_days: Option<f64>,
+	/// Vulnerabilities per top-level CWE weakness class, most exposed first
+	#[serde(default)]
+	pub by_weakness_class: Vec<WeaknessRollup>,
+	/// Criticality-weighted fleet risk index with its last recorded earlier value
+	#[serde(default)]
+	pub fleet_risk: Option<FleetRisk>,
+	/// Open exposures and fleet risk per recorded day, oldest first
+	#[serde(default)]
+	pub exposure_trend: Vec<ExposurePoint>,
+}
+
+impl StatisticsReport {
+	/// The weakness class the fleet's exposure skews toward, if one dominates
+	pub fn exposure_skew(&self) -> Option<&WeaknessRollup> {
+		self.by_weakness_class
+			.iter()
+			.filter(|rollup| rollup.class != WeaknessClass::Other)
+			.max_by(|a, b| a.exposure_share.total_cmp(&b.exposure_share))
+			.filter(|rollup| rollup.exposure_share >= EXPOSURE_SKEW_SHARE)
+	}
+
+	/// Vulnerabilities per severity from critical to low, summed case-insensitively since
+	/// severities are stored as imported, with any other severity last as "Unknown"
+	pub fn severity_counts(&self) -> Vec<(&'static str, i64)> {
+		let mut counts: Vec<(&'static str, i64)> = SEVERITIES.iter().map(|&severity| (severity, 0)).collect();
+		let mut unknown = 0;
+		for (severity, count) in &self.by_severity {
+			match counts.iter_mut().find(|(known, _)| known.eq_ignore_ascii_case(severity)) {
+				Some((_, total)) => *total += count,
+				None => unknown += count,
+			}
+		}
+		counts.push(("Unknown", unknown));
+		counts
+	}
+}
+
+/// Severities in the order the dashboard shows them
+const SEVERITIES: [&str; 4] = ["Critical", "High", "Medium", "Low"];
+
+/// The fleet on one day, from that day's report snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExposurePoint {
+	/// `YYYY-MM-DD` in UTC
+	pub day: String,
+	/// Unresolved vulnerabilities affecting at least one robot
+	pub open_exposures: i64,
+	pub fleet_risk_index: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetRisk {
+	/// 0 to 100, see `models::risk::fleet_risk_index`
+	pub index: f64,
+	/// Value recorded on the most recent earlier day, if any
+	pub previous: Option<f64>,
+	/// Day, `YYYY-MM-DD` in UTC, the previous value was recorded on
+	pub previous_on: Option<String>,
+}
+
+impl FleetRisk {
+	pub fn change(&self) -> Option<f64> {
+		self.previous.map(|previous| self.index - previous)
+	}
+
+	/// Arrow for the direction the index moved since the previous value
+	pub fn trend_arrow(&self) -> &'static str {
+		match self.change() {
+			Some(change) if change >= 0.05 => "↑",
+			Some(change) if change <= -0.05 => "↓",
+			_ => "→",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Totals {
+	pub vulnerabilities: i64,
+	pub unresolved_vulnerabilities: i64,
+	pub robots: i64,
+	pub software_versions: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupRollup {
+	pub group: String,
+	pub robots: i64,
+	/// Distinct unresolved vulnerabilities affecting software installed on the group's robots
+	pub unresolved_vulnerabilities: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeaknessRollup {
+	pub class: WeaknessClass,
+	/// Distinct vulnerabilities with a CWE of this class
+	pub vulnerabilities: i64,
+	/// Distinct unresolved vulnerabilities of this class affecting software installed on robots
+	pub fleet_exposure: i64,
+	/// Part of the fleet's exposure with a known CWE that falls in this class, 0 to 1.
+	/// A CVE with CWEs of two classes counts for both.
+	pub exposure_share: f64,
+}
@@ -0,0 +1,151 @@
+// src/models/sync_delta.rs
+
+//! What a data source sync changed: the vulnerabilities it added, the severities it
+//! changed and the robots it newly exposed, kept so analysts review the changes
+//! instead of the whole list.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// Severity of every vulnerability and which robots each exposes, compared before
+/// and after a sync
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncState {
+	/// Severity by CVE ID, of the vulnerabilities not in Recently deleted
+	pub severities: HashMap<String, String>,
+	/// Robot name and CVE ID of every unresolved exposure
+	pub exposures: BTreeSet<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewVulnerability {
+	pub cve_id: String,
+	pub severity: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeverityChange {
+	pub cve_id: String,
+	pub before: String,
+	pub after: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewExposure {
+	pub robot: String,
+	pub cve_id: String,
+	pub severity: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncChanges {
+	#[serde(default)]
+	pub new_vulnerabilities: Vec<NewVulnerability>,
+	#[serde(default)]
+	pub severity_changes: Vec<SeverityChange>,
+	/// Robots exposed to a vulnerability they were not exposed to before the sync
+	#[serde(default)]
+	pub new_exposures: Vec<NewExposure>,
+}
+
+/// The changes of one sync run
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncDelta {
+	pub delta_id: i64,
+	pub started_at: DateTime<Utc>,
+	pub finished_at: DateTime<Utc>,
+	/// Names of the data sources that ran
+	pub sources: Vec<String>,
+	pub changes: SyncChanges,
+}
+
+impl SyncChanges {
+	pub fn between(before: &SyncState, after: &SyncState) -> Self {
+		let severity = |cve_id: &str| after.severities.get(cve_id).cloned().unwrap_or_default();
+
+		let mut new_vulnerabilities: Vec<NewVulnerability> = after
+			.severities
+			.iter()
+			.filter(|(cve_id, _)| !before.severities.contains_key(*cve_id))
+			.map(|(cve_id, severity)| NewVulnerability { cve_id: cve_id.clone(), severity: severity.clone() })
+			.collect();
+		new_vulnerabilities.sort_by(|a, b| a.cve_id.cmp(&b.cve_id));
+
+		let mut severity_changes: Vec<SeverityChange> = after
+			.severities
+			.iter()
+			.filter_map(|(cve_id, severity)| {
+				let previous = before.severities.get(cve_id)?;
+				(previous != severity).then(|| SeverityChange {
+					cve_id: cve_id.clone(),
+					before: previous.clone(),
+					after: severity.clone(),
+				})
+			})
+			.collect();
+		severity_changes.sort_by(|a, b| a.cve_id.cmp(&b.cve_id));
+
+		let new_exposures = after
+			.exposures
+			.difference(&before.exposures)
+			.map(|(robot, cve_id)| NewExposure { robot: robot.clone(), cve_id: cve_id.clone(), severity: severity(cve_id) })
+			.collect();
+
+		Self { new_vulnerabilities, severity_changes, new_exposures }
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.new_vulnerabilities.is_empty() && self.severity_changes.is_empty() && self.new_exposures.is_empty()
+	}
+}
+
+impl fmt::Display for SyncChanges {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} new vulnerabilities, {} severity changes, {} new robot exposures",
+			self.new_vulnerabilities.len(),
+			self.severity_changes.len(),
+			self.new_exposures.len(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_changes_between() {
+		let state = |severities: &[(&str, &str)], exposures: &[(&str, &str)]| SyncState {
+			severities: severities.iter().map(|(cve, severity)| (cve.to_string(), severity.to_string())).collect(),
+			exposures: exposures.iter().map(|(robot, cve)| (robot.to_string(), cve.to_string())).collect(),
+		};
+		let before = state(
+			&[("CVE-2024-0001", "Medium"), ("CVE-2024-0002", "Low")],
+			&[("arm-01", "CVE-2024-0001")],
+		);
+		let after = state(
+			&[("CVE-2024-0001", "Critical"), ("CVE-2024-0002", "Low"), ("CVE-2024-0003", "High")],
+			&[("arm-01", "CVE-2024-0001"), ("agv-01", "CVE-2024-0003")],
+		);
+
+		let changes = SyncChanges::between(&before, &after);
+		assert_eq!(changes.new_vulnerabilities, [
+			NewVulnerability { cve_id: "CVE-2024-0003".to_string(), severity: "High".to_string() },
+		]);
+		assert_eq!(changes.severity_changes, [SeverityChange {
+			cve_id: "CVE-2024-0001".to_string(),
+			before: "Medium".to_string(),
+			after: "Critical".to_string(),
+		}]);
+		assert_eq!(changes.new_exposures, [NewExposure {
+			robot: "agv-01".to_string(),
+			cve_id: "CVE-2024-0003".to_string(),
+			severity: "High".to_string(),
+		}]);
+		assert!(SyncChanges::between(&after, &after).is_empty());
+	}
+}
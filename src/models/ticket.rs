@@ -0,0 +1,117 @@
+// src/models/ticket.rs
+
+//! Remediation tickets raised in an external tracker for a vulnerability, and how the
+//! tracker's statuses map onto triage statuses when they are synced back.
+
+use crate::models::vulnerability::TriageStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Kind of tracker tickets are raised in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TicketSystem {
+	/// Jira's REST API v2, e.g. https://example.atlassian.net
+	Jira,
+	/// Any tracker answering `POST {base}/issues` with `{"key", "url"}` and
+	/// `GET {base}/issues/{key}` with `{"status"}`
+	Generic,
+}
+
+impl TicketSystem {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TicketSystem::Jira => "jira",
+			TicketSystem::Generic => "generic",
+		}
+	}
+}
+
+impl std::fmt::Display for TicketSystem {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// Where remediation tickets are raised. The API token is read from the
+/// `RVD_TICKET_TOKEN` environment variable so it never lands in the database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TicketSettings {
+	pub system: TicketSystem,
+	pub base_url: String,
+	/// Jira project key; passed along as `project` to generic trackers
+	pub project: String,
+	#[serde(default = "default_issue_type")]
+	pub issue_type: String,
+	/// Jira account the token belongs to; without one the token is sent as a bearer token
+	#[serde(default)]
+	pub username: Option<String>,
+	/// Tracker statuses mapped to triage statuses, compared case-insensitively; they
+	/// take precedence over the built-in mapping. Risk decisions need a justification
+	/// and approver, so mappings to them are ignored.
+	#[serde(default)]
+	pub status_map: BTreeMap<String, TriageStatus>,
+}
+
+fn default_issue_type() -> String {
+	"Bug".to_string()
+}
+
+impl TicketSettings {
+	/// Triage status a ticket in `status` stands for, `None` when the status says
+	/// nothing about remediation
+	pub fn triage_status(&self, status: &str) -> Option<TriageStatus> {
+		let status = status.trim().to_lowercase();
+		if let Some((_, mapped)) = self.status_map.iter().find(|(name, _)| name.trim().to_lowercase() == status) {
+			return Some(*mapped).filter(|mapped| !mapped.is_risk_decision());
+		}
+		match status.as_str() {
+			"open" | "to do" | "todo" | "new" | "backlog" | "reopened" => Some(TriageStatus::Open),
+			"in progress" | "in review" | "in testing" => Some(TriageStatus::InProgress),
+			"done" | "closed" | "resolved" | "fixed" => Some(TriageStatus::Mitigated),
+			_ => None,
+		}
+	}
+}
+
+/// The ticket raised for a vulnerability
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ticket {
+	pub vulnerability_id: i64,
+	/// Key in the tracker, e.g. "SEC-42"
+	pub key: String,
+	pub url: Option<String>,
+	/// Status in the tracker as of the last sync
+	pub status: Option<String>,
+	pub created_by: String,
+	pub created_at: Option<DateTime<Utc>>,
+	pub synced_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_triage_status() {
+		let mut settings = TicketSettings {
+			system: TicketSystem::Jira,
+			base_url: "https://example.atlassian.net".to_string(),
+			project: "SEC".to_string(),
+			issue_type: default_issue_type(),
+			username: None,
+			status_map: BTreeMap::new(),
+		};
+		assert_eq!(settings.triage_status("Done"), Some(TriageStatus::Mitigated));
+		assert_eq!(settings.triage_status(" In Progress "), Some(TriageStatus::InProgress));
+		assert_eq!(settings.triage_status("Waiting for vendor"), None);
+
+		settings.status_map.insert("Waiting for Vendor".to_string(), TriageStatus::InProgress);
+		settings.status_map.insert("Done".to_string(), TriageStatus::InProgress);
+		settings.status_map.insert("Won't Fix".to_string(), TriageStatus::AcceptedRisk);
+		assert_eq!(settings.triage_status("waiting for vendor"), Some(TriageStatus::InProgress));
+		assert_eq!(settings.triage_status("DONE"), Some(TriageStatus::InProgress));
+		assert_eq!(settings.triage_status("won't fix"), None);
+	}
+}
@@ -0,0 +1,41 @@
+// src/models/trash.rs
+
+//! Robots and vulnerabilities in Recently deleted. Deleting only marks them, so an
+//! accidental delete can be undone until they are purged.
+
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+
+/// Days a deleted entry stays restorable before it is purged for good
+pub const TRASH_RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DeletedKind {
+	Robot,
+	Vulnerability,
+}
+
+impl fmt::Display for DeletedKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.pad(match self {
+			DeletedKind::Robot => "Robot",
+			DeletedKind::Vulnerability => "Vulnerability",
+		})
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeletedItem {
+	pub kind: DeletedKind,
+	pub id: i64,
+	/// Robot name or CVE ID
+	pub label: String,
+	pub deleted_at: DateTime<Utc>,
+}
+
+impl DeletedItem {
+	/// When the entry is purged unless restored first
+	pub fn purged_after(&self) -> DateTime<Utc> {
+		self.deleted_at + Duration::days(TRASH_RETENTION_DAYS)
+	}
+}
@@ -0,0 +1,149 @@
+// src/models/trends.rs
+
+//! Fleet exposure over time, from the daily values in `metrics_history`, so teams can
+//! show how the open critical vulnerabilities and robot risk scores came down.
+
+use crate::models::robot::Criticality;
+use serde::{Deserialize, Serialize};
+
+/// Metric name of the fleet risk index in `metrics_history`
+pub const FLEET_RISK_METRIC: &str = "fleet_risk_index";
+/// Metric name of the mean robot risk score in `metrics_history`
+pub const MEAN_RISK_METRIC: &str = "mean_risk_score";
+/// Prefix of the metric names of the open critical vulnerabilities per robot group
+const OPEN_CRITICALS_PREFIX: &str = "open_criticals:";
+
+/// Metric name of the open critical vulnerabilities on the robots of a criticality,
+/// e.g. "open_criticals:high"
+pub fn open_criticals_metric(group: Criticality) -> String {
+	format!("{}{}", OPEN_CRITICALS_PREFIX, group.as_str().to_lowercase())
+}
+
+/// The robot group an open criticals metric name is about
+pub fn open_criticals_group(metric: &str) -> Option<Criticality> {
+	metric.strip_prefix(OPEN_CRITICALS_PREFIX).and_then(Criticality::from_label)
+}
+
+/// A metric's value on one day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendPoint {
+	/// `YYYY-MM-DD` in UTC
+	pub day: String,
+	pub value: f64,
+}
+
+/// One plotted line, oldest day first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrendSeries {
+	pub label: String,
+	pub points: Vec<TrendPoint>,
+}
+
+impl TrendSeries {
+	pub fn new(label: impl Into<String>) -> Self {
+		Self { label: label.into(), points: Vec::new() }
+	}
+
+	/// How much the value moved from the first to the last recorded day, `None`
+	/// before two days were recorded
+	pub fn change(&self) -> Option<f64> {
+		match (self.points.first(), self.points.last()) {
+			(Some(first), Some(last)) if self.points.len() > 1 => Some(last.value - first.value),
+			_ => None,
+		}
+	}
+}
+
+/// The recorded history of the fleet's exposure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FleetTrends {
+	/// Open critical vulnerabilities per robot criticality, most critical group first
+	pub open_criticals: Vec<TrendSeries>,
+	pub mean_risk_score: TrendSeries,
+	pub fleet_risk_index: TrendSeries,
+}
+
+impl FleetTrends {
+	/// Sorts `(day, metric, value)` rows into series; unknown metrics are skipped
+	pub fn from_rows(rows: impl IntoIterator<Item = (String, String, f64)>) -> Self {
+		let mut open_criticals: Vec<(Criticality, TrendSeries)> = Criticality::ALL
+			.iter()
+			.rev()
+			.map(|&group| (group, TrendSeries::new(group.as_str())))
+			.collect();
+		let mut mean_risk_score = TrendSeries::new("Mean robot risk score");
+		let mut fleet_risk_index = TrendSeries::new("Fleet risk index");
+
+		for (day, metric, value) in rows {
+			let series = match metric.as_str() {
+				MEAN_RISK_METRIC => &mut mean_risk_score,
+				FLEET_RISK_METRIC => &mut fleet_risk_index,
+				other => match open_criticals_group(other) {
+					Some(group) => match open_criticals.iter_mut().find(|(known, _)| *known == group) {
+						Some((_, series)) => series,
+						None => continue,
+					},
+					None => continue,
+				},
+			};
+			series.points.push(TrendPoint { day, value });
+		}
+
+		Self {
+			open_criticals: open_criticals
+				.into_iter()
+				.map(|(_, series)| series)
+				.filter(|series| !series.points.is_empty())
+				.collect(),
+			mean_risk_score,
+			fleet_risk_index,
+		}
+	}
+
+	/// Open critical vulnerabilities summed over the groups per day; a vulnerability on
+	/// robots of two groups counts twice
+	pub fn total_open_criticals(&self) -> TrendSeries {
+		let mut total = TrendSeries::new("All robots");
+		for point in self.open_criticals.iter().flat_map(|series| &series.points) {
+			match total.points.iter_mut().find(|known| known.day == point.day) {
+				Some(known) => known.value += point.value,
+				None => total.points.push(point.clone()),
+			}
+		}
+		total.points.sort_by(|a, b| a.day.cmp(&b.day));
+		total
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.open_criticals.is_empty() && self.mean_risk_score.points.is_empty() && self.fleet_risk_index.points.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_from_rows() {
+		let row = |day: &str, metric: &str, value: f64| (day.to_string(), metric.to_string(), value);
+		let trends = FleetTrends::from_rows([
+			row("2024-01-01", "open_criticals:low", 1.0),
+			row("2024-01-01", "open_criticals:critical", 4.0),
+			row("2024-01-01", MEAN_RISK_METRIC, 42.0),
+			row("2024-01-01", "something_else", 7.0),
+			row("2024-01-02", "open_criticals:low", 0.0),
+			row("2024-01-02", "open_criticals:critical", 2.0),
+			row("2024-01-02", MEAN_RISK_METRIC, 30.5),
+		]);
+
+		let labels: Vec<&str> = trends.open_criticals.iter().map(|series| series.label.as_str()).collect();
+		assert_eq!(labels, ["Critical", "Low"]);
+		assert_eq!(trends.open_criticals[0].change(), Some(-2.0));
+		assert_eq!(trends.mean_risk_score.change(), Some(-11.5));
+		assert_eq!(trends.fleet_risk_index.change(), None);
+
+		let total = trends.total_open_criticals();
+		let values: Vec<f64> = total.points.iter().map(|point| point.value).collect();
+		assert_eq!(values, [5.0, 2.0]);
+	}
+}
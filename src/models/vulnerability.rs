@@ -1,8 +1,291 @@
 // src/models/vulnerability.rs
 
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Triage workflow state of a vulnerability
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TriageStatus {
+	#[default]
+	Open,
+	InProgress,
+	Mitigated,
+	AcceptedRisk,
+	FalsePositive,
+}
+
+impl TriageStatus {
+	pub const ALL: [TriageStatus; 5] = [
+		TriageStatus::Open,
+		TriageStatus::InProgress,
+		TriageStatus::Mitigated,
+		TriageStatus::AcceptedRisk,
+		TriageStatus::FalsePositive,
+	];
+
+	/// Value stored in the `vulnerability_status.status` column
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TriageStatus::Open => "Open",
+			TriageStatus::InProgress => "In Progress",
+			TriageStatus::Mitigated => "Mitigated",
+			TriageStatus::AcceptedRisk => "Accepted Risk",
+			TriageStatus::FalsePositive => "False Positive",
+		}
+	}
+
+	/// Whether the vulnerability still needs remediation work
+	pub fn is_unresolved(&self) -> bool {
+		matches!(self, TriageStatus::Open | TriageStatus::InProgress)
+	}
+
+	/// Whether the vulnerability was closed by a decision instead of a fix, which auditors
+	/// expect to see justified and approved
+	pub fn is_risk_decision(&self) -> bool {
+		matches!(self, TriageStatus::AcceptedRisk | TriageStatus::FalsePositive)
+	}
+
+	/// Parses a stored status, falling back to `Open` for unknown values
+	pub fn from_db(value: &str) -> Self {
+		Self::ALL
+			.iter()
+			.copied()
+			.find(|status| status.as_str().eq_ignore_ascii_case(value.trim()))
+			.unwrap_or_default()
+	}
+}
+
+impl std::fmt::Display for TriageStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
+
+/// Version of the CVSS standard a base score was computed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CvssVersion {
+	V2,
+	V30,
+	V31,
+	V40,
+}
+
+impl CvssVersion {
+	/// Value stored in the `vulnerabilities.cvss_version` column
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			CvssVersion::V2 => "2.0",
+			CvssVersion::V30 => "3.0",
+			CvssVersion::V31 => "3.1",
+			CvssVersion::V40 => "4.0",
+		}
+	}
+
+	pub fn from_db(value: &str) -> Option<Self> {
+		match value.trim().trim_start_matches(['v', 'V']) {
+			"2" | "2.0" => Some(CvssVersion::V2),
+			"3.0" => Some(CvssVersion::V30),
+			"3.1" => Some(CvssVersion::V31),
+			"4" | "4.0" => Some(CvssVersion::V40),
+			_ => None,
+		}
+	}
+}
+
+impl std::fmt::Display for CvssVersion {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "CVSS v{}", self.as_str())
+	}
+}
+
+/// Field an analyst can edit by hand; the edit is locked against automatic refreshes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LockedField {
+	Description,
+	Severity,
+	Impact,
+	Mitigation,
+}
+
+impl LockedField {
+	pub const ALL: [LockedField; 4] = [LockedField::Description, LockedField::Severity, LockedField::Impact, LockedField::Mitigation];
+
+	/// Column of `vulnerabilities` holding the field, also stored in `field_locks.field`
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			LockedField::Description => "description",
+			LockedField::Severity => "severity",
+			LockedField::Impact => "impact",
+			LockedField::Mitigation => "mitigation",
+		}
+	}
+
+	pub fn from_db(value: &str) -> Option<Self> {
+		Self::ALL.iter().copied().find(|field| field.as_str().eq_ignore_ascii_case(value.trim()))
+	}
+}
+
+impl std::fmt::Display for LockedField {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.as_str())
+	}
+}
+
+/// Field whose source is tracked, named after its column of `vulnerabilities`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProvenanceField {
+	Description,
+	Severity,
+	Impact,
+	Mitigation,
+	CvssScore,
+	PublishedDate,
+}
+
+impl ProvenanceField {
+	pub const ALL: [ProvenanceField; 6] = [
+		ProvenanceField::Description,
+		ProvenanceField::Severity,
+		ProvenanceField::Impact,
+		ProvenanceField::Mitigation,
+		ProvenanceField::CvssScore,
+		ProvenanceField::PublishedDate,
+	];
+
+	/// Column of `vulnerabilities` holding the field, also stored in `field_provenance.field`
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			ProvenanceField::Description => "description",
+			ProvenanceField::Severity => "severity",
+			ProvenanceField::Impact => "impact",
+			ProvenanceField::Mitigation => "mitigation",
+			ProvenanceField::CvssScore => "cvss_score",
+			ProvenanceField::PublishedDate => "published_date",
+		}
+	}
+
+	pub fn from_db(value: &str) -> Option<Self> {
+		Self::ALL.iter().copied().find(|field| field.as_str() == value.trim())
+	}
+}
+
+impl From<LockedField> for ProvenanceField {
+	fn from(field: LockedField) -> Self {
+		match field {
+			LockedField::Description => ProvenanceField::Description,
+			LockedField::Severity => ProvenanceField::Severity,
+			LockedField::Impact => ProvenanceField::Impact,
+			LockedField::Mitigation => ProvenanceField::Mitigation,
+		}
+	}
+}
+
+impl std::fmt::Display for ProvenanceField {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ProvenanceField::CvssScore => write!(f, "CVSS score"),
+			ProvenanceField::PublishedDate => write!(f, "published date"),
+			field => write!(f, "{}", field.as_str()),
+		}
+	}
+}
+
+/// Where the current value of a field came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProvenanceSource {
+	/// A CSV or spreadsheet import
+	Csv,
+	Nvd,
+	Osv,
+	/// The GitHub Advisory Database
+	Ghsa,
+	/// An analyst's edit
+	Manual,
+}
+
+impl ProvenanceSource {
+	pub const ALL: [ProvenanceSource; 5] = [
+		ProvenanceSource::Csv,
+		ProvenanceSource::Nvd,
+		ProvenanceSource::Osv,
+		ProvenanceSource::Ghsa,
+		ProvenanceSource::Manual,
+	];
+
+	/// Value stored in `field_provenance.source`
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			ProvenanceSource::Csv => "csv",
+			ProvenanceSource::Nvd => "nvd",
+			ProvenanceSource::Osv => "osv",
+			ProvenanceSource::Ghsa => "ghsa",
+			ProvenanceSource::Manual => "manual",
+		}
+	}
+
+	pub fn from_db(value: &str) -> Option<Self> {
+		Self::ALL.iter().copied().find(|source| source.as_str() == value.trim())
+	}
+}
+
+impl std::fmt::Display for ProvenanceSource {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let label = match self {
+			ProvenanceSource::Csv => "CSV import",
+			ProvenanceSource::Nvd => "NVD",
+			ProvenanceSource::Osv => "OSV",
+			ProvenanceSource::Ghsa => "GitHub Advisory Database",
+			ProvenanceSource::Manual => "manual edit",
+		};
+		write!(f, "{}", label)
+	}
+}
+
+/// Which source last changed a field and when
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldProvenance {
+	pub field: ProvenanceField,
+	pub source: ProvenanceSource,
+	pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Who last edited a field by hand and when
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldLock {
+	pub field: LockedField,
+	pub locked_by: String,
+	pub locked_at: Option<DateTime<Utc>>,
+}
+
+/// Why a risk was accepted or a finding suppressed, who approved it and until when
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RiskAcceptance {
+	pub justification: Option<String>,
+	pub approved_by: Option<String>,
+	/// When the decision was last saved
+	pub accepted_at: Option<DateTime<Utc>>,
+	/// Date from which the decision has to be reviewed again
+	pub expires_on: Option<NaiveDate>,
+}
+
+impl RiskAcceptance {
+	pub fn is_expired(&self, today: NaiveDate) -> bool {
+		self.expires_on.is_some_and(|expires| expires <= today)
+	}
+
+	/// Checks that the decision is documented well enough to record under `status`
+	pub fn validate(&self, status: TriageStatus) -> anyhow::Result<()> {
+		let missing = |value: &Option<String>| value.as_deref().is_none_or(|v| v.trim().is_empty());
+		if status.is_risk_decision() && missing(&self.justification) {
+			anyhow::bail!("A justification is required to mark a vulnerability as {}", status);
+		}
+		if status == TriageStatus::AcceptedRisk && missing(&self.approved_by) {
+			anyhow::bail!("Accepting a risk requires the name of the approver");
+		}
+		Ok(())
+	}
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Vulnerability {
 	pub vulnerability_id: Option<i64>,
@@ -12,6 +295,47 @@ pub struct Vulnerability {
 	pub impact: Option<String>,
 	pub mitigation: Option<String>,
 	pub published_date: Option<NaiveDate>,
+	#[serde(default)]
+	pub cvss_score: Option<f64>,
+	/// CVSS version `cvss_score` was computed with, when known
+	#[serde(default)]
+	pub cvss_version: Option<CvssVersion>,
+	#[serde(default)]
+	pub status: TriageStatus,
+	#[serde(default)]
+	pub assigned_to: Option<String>,
+	/// Present while the status is a risk decision
+	#[serde(default)]
+	pub risk_acceptance: Option<RiskAcceptance>,
+	/// When CISA listed the CVE as known exploited, if it did
+	#[serde(default)]
+	pub kev_date_added: Option<NaiveDate>,
+	/// CWE IDs of the weaknesses the NVD lists for the CVE
+	#[serde(default)]
+	pub cwe_ids: Vec<String>,
+	/// Advisory database the entry was imported from, `None` for the NVD and manual entries
+	#[serde(default)]
+	pub source: Option<String>,
+	/// IDs of the same vulnerability in other feeds
+	#[serde(default)]
+	pub aliases: Vec<Alias>,
+	/// Fields edited by hand, which imports and NVD refreshes leave as they are
+	#[serde(default)]
+	pub locks: Vec<FieldLock>,
+	/// Source of the fields that have one recorded
+	#[serde(default)]
+	pub provenance: Vec<FieldProvenance>,
+}
+
+/// Source label of entries from the NVD and of manual entries
+pub const NVD_SOURCE: &str = "NVD";
+
+/// Another feed's ID for a vulnerability, e.g. a GHSA ID of a CVE
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Alias {
+	pub id: String,
+	/// Feed the ID comes from, e.g. GitHub Advisory Database
+	pub source: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +345,27 @@ pub struct VulnerabilityDetails {
 	pub software_info: Vec<(super::software::SoftwareProduct, super::software::SoftwareVersion)>,
 }
 
+/// Another vulnerability in the same component as the one shown, found through a
+/// shared affected product or CWE
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatedVulnerability {
+	pub cve_id: String,
+	pub severity: String,
+	pub status: TriageStatus,
+	/// CVSS score, or the severity's midpoint when the score is unknown
+	pub effective_cvss: f64,
+	/// Names of the affected products both vulnerabilities have
+	pub shared_products: Vec<String>,
+	pub shared_cwes: Vec<String>,
+}
+
+impl RelatedVulnerability {
+	/// Why the vulnerability is listed, e.g. "ros-core · CWE-787"
+	pub fn reason(&self) -> String {
+		self.shared_products.iter().chain(&self.shared_cwes).map(String::as_str).collect::<Vec<_>>().join(" · ")
+	}
+}
+
 impl Vulnerability {
 	pub fn new(cve_id: String, severity: String) -> Self {
 		Self {
@@ -31,6 +376,77 @@ impl Vulnerability {
 			impact: None,
 			mitigation: None,
 			published_date: None,
+			cvss_score: None,
+			cvss_version: None,
+			status: TriageStatus::Open,
+			assigned_to: None,
+			risk_acceptance: None,
+			kev_date_added: None,
+			cwe_ids: Vec::new(),
+			source: None,
+			aliases: Vec::new(),
+			locks: Vec::new(),
+			provenance: Vec::new(),
+		}
+	}
+
+	pub fn lock(&self, field: LockedField) -> Option<&FieldLock> {
+		self.locks.iter().find(|lock| lock.field == field)
+	}
+
+	pub fn provenance(&self, field: ProvenanceField) -> Option<&FieldProvenance> {
+		self.provenance.iter().find(|provenance| provenance.field == field)
+	}
+
+	/// Whether an analyst set the field, which automatic enrichment then leaves alone
+	pub fn is_curated(&self, field: ProvenanceField) -> bool {
+		self.provenance(field).is_some_and(|provenance| provenance.source == ProvenanceSource::Manual)
+	}
+
+	/// Feeds contributing to the entry: where it was imported from, then the feeds of
+	/// its aliases
+	pub fn sources(&self) -> Vec<&str> {
+		let mut sources = vec![self.source.as_deref().unwrap_or(NVD_SOURCE)];
+		for alias in &self.aliases {
+			if !sources.contains(&alias.source.as_str()) {
+				sources.push(&alias.source);
+			}
 		}
+		sources
 	}
-}
\ No newline at end of file
+
+	/// CVSS base score, or a nominal score derived from the severity when NVD has none
+	pub fn effective_cvss(&self) -> f64 {
+		self.cvss_score.unwrap_or_else(|| nominal_cvss(&self.severity))
+	}
+
+	/// Name of the score for display, such as `CVSS v3.1`, or `CVSS` when the version is unknown
+	pub fn cvss_name(&self) -> String {
+		self.cvss_version.map_or_else(|| "CVSS".to_string(), |version| version.to_string())
+	}
+
+	/// The CVE's page in the NVD; `None` for entries stored under another feed's ID
+	pub fn nvd_url(&self) -> Option<String> {
+		self.is_cve().then(|| format!("https://nvd.nist.gov/vuln/detail/{}", self.cve_id))
+	}
+
+	/// The CVE record on cve.org, run by MITRE
+	pub fn cve_record_url(&self) -> Option<String> {
+		self.is_cve().then(|| format!("https://www.cve.org/CVERecord?id={}", self.cve_id))
+	}
+
+	fn is_cve(&self) -> bool {
+		self.cve_id.to_ascii_uppercase().starts_with("CVE-")
+	}
+}
+
+/// Midpoint of the CVSS v3 range of a qualitative severity rating
+pub fn nominal_cvss(severity: &str) -> f64 {
+	match severity.to_lowercase().as_str() {
+		"critical" => 9.5,
+		"high" => 8.0,
+		"medium" => 5.5,
+		"low" => 2.0,
+		_ => 0.0,
+	}
+}
@@ -1,7 +1,7 @@
 // src/models/vulnerability_record.rs
 
 use serde::Deserialize;
-use super::vulnerability::Vulnerability;
+use super::vulnerability::{TriageStatus, Vulnerability};
 
 #[derive(Debug, Deserialize)]
 pub struct VulnerabilityRecord {
@@ -37,6 +37,17 @@ impl From<VulnerabilityRecord> for Vulnerability {
 			impact: Some(record.votes),
 			mitigation: Some(record.comments),
 			published_date: None,
+			cvss_score: None,
+			cvss_version: None,
+			status: TriageStatus::Open,
+			assigned_to: None,
+			risk_acceptance: None,
+			kev_date_added: None,
+			cwe_ids: Vec::new(),
+			source: None,
+			aliases: Vec::new(),
+			locks: Vec::new(),
+			provenance: Vec::new(),
 		}
 	}
 }
\ No newline at end of file
@@ -0,0 +1,207 @@
+// src/models/weakness.rs
+
+//! Top-level weakness classes for CWE IDs. Each CWE walks up its parents in the
+//! CWE research view (CWE-1000) until it reaches one of the class roots below, so
+//! e.g. CWE-121 (stack overflow) rolls up through CWE-787 to memory safety.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum WeaknessClass {
+	MemorySafety,
+	Injection,
+	AccessControl,
+	Cryptography,
+	InputHandling,
+	ResourceManagement,
+	InformationExposure,
+	Concurrency,
+	Other,
+}
+
+/// CWEs that start a class; their descendants belong to it too
+const CLASS_ROOTS: &[(u32, WeaknessClass)] = &[
+	(119, WeaknessClass::MemorySafety),
+	(129, WeaknessClass::MemorySafety),
+	(190, WeaknessClass::MemorySafety),
+	(191, WeaknessClass::MemorySafety),
+	(476, WeaknessClass::MemorySafety),
+	(824, WeaknessClass::MemorySafety),
+	(825, WeaknessClass::MemorySafety),
+	(843, WeaknessClass::MemorySafety),
+	(908, WeaknessClass::MemorySafety),
+	(74, WeaknessClass::Injection),
+	(284, WeaknessClass::AccessControl),
+	(345, WeaknessClass::AccessControl),
+	(310, WeaknessClass::Cryptography),
+	(311, WeaknessClass::Cryptography),
+	(326, WeaknessClass::Cryptography),
+	(327, WeaknessClass::Cryptography),
+	(330, WeaknessClass::Cryptography),
+	(916, WeaknessClass::Cryptography),
+	(20, WeaknessClass::InputHandling),
+	(22, WeaknessClass::InputHandling),
+	(706, WeaknessClass::InputHandling),
+	(434, WeaknessClass::InputHandling),
+	(502, WeaknessClass::InputHandling),
+	(601, WeaknessClass::InputHandling),
+	(611, WeaknessClass::InputHandling),
+	(918, WeaknessClass::InputHandling),
+	(1321, WeaknessClass::InputHandling),
+	(400, WeaknessClass::ResourceManagement),
+	(404, WeaknessClass::ResourceManagement),
+	(834, WeaknessClass::ResourceManagement),
+	(200, WeaknessClass::InformationExposure),
+	(362, WeaknessClass::Concurrency),
+	(667, WeaknessClass::Concurrency),
+];
+
+/// Child to parent links of commonly reported CWEs
+const PARENTS: &[(u32, u32)] = &[
+	// Memory safety
+	(120, 119), (125, 119), (787, 119), (805, 119), (786, 119), (788, 119), (822, 119), (823, 119),
+	(121, 787), (122, 787), (123, 787), (124, 786), (126, 125), (127, 125), (806, 805),
+	(415, 825), (416, 825), (680, 190), (1341, 825),
+	// Injection
+	(77, 74), (79, 74), (89, 74), (90, 74), (91, 74), (94, 74), (113, 74), (643, 74), (917, 74), (943, 74), (1236, 74),
+	(78, 77), (88, 77), (95, 94), (96, 94), (564, 89), (80, 79), (83, 79), (87, 79),
+	// Access control and authentication
+	(269, 284), (285, 284), (287, 284), (862, 285), (863, 285), (732, 285), (276, 732), (639, 863), (250, 269),
+	(306, 287), (288, 287), (290, 287), (294, 287), (295, 287), (307, 287), (521, 287), (522, 287), (620, 287),
+	(640, 287), (798, 287), (259, 798), (321, 798), (1390, 287), (1391, 287),
+	(352, 345), (347, 345), (346, 345), (924, 345),
+	// Cryptography
+	(312, 311), (319, 311), (328, 327), (338, 330), (331, 330), (335, 330), (759, 916), (760, 916),
+	// Input handling
+	(23, 22), (24, 22), (35, 22), (36, 22), (59, 706), (1284, 20), (776, 611),
+	// Resource management
+	(770, 400), (789, 400), (1333, 400), (920, 400), (772, 404), (401, 772), (775, 772), (674, 834), (835, 834),
+	// Information exposure
+	(201, 200), (203, 200), (208, 203), (209, 200), (213, 200), (215, 200), (359, 200), (497, 200), (532, 200), (538, 200),
+	// Concurrency
+	(364, 362), (366, 362), (367, 362), (833, 667), (764, 667), (765, 667),
+];
+
+impl WeaknessClass {
+	pub const ALL: [WeaknessClass; 9] = [
+		WeaknessClass::MemorySafety,
+		WeaknessClass::Injection,
+		WeaknessClass::AccessControl,
+		WeaknessClass::Cryptography,
+		WeaknessClass::InputHandling,
+		WeaknessClass::ResourceManagement,
+		WeaknessClass::InformationExposure,
+		WeaknessClass::Concurrency,
+		WeaknessClass::Other,
+	];
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			WeaknessClass::MemorySafety => "Memory safety",
+			WeaknessClass::Injection => "Injection",
+			WeaknessClass::AccessControl => "Authentication and access control",
+			WeaknessClass::Cryptography => "Cryptography",
+			WeaknessClass::InputHandling => "Input and file handling",
+			WeaknessClass::ResourceManagement => "Resource management",
+			WeaknessClass::InformationExposure => "Information exposure",
+			WeaknessClass::Concurrency => "Concurrency",
+			WeaknessClass::Other => "Other",
+		}
+	}
+
+	/// Class of a CWE ID such as `CWE-416`; unknown CWEs are `Other`
+	pub fn of(cwe_id: &str) -> WeaknessClass {
+		let Some(mut cwe) = cwe_number(cwe_id) else {
+			return WeaknessClass::Other;
+		};
+		// The table is acyclic; the bound only guards against editing mistakes
+		for _ in 0..PARENTS.len() {
+			if let Some((_, class)) = CLASS_ROOTS.iter().find(|(root, _)| *root == cwe) {
+				return *class;
+			}
+			match PARENTS.iter().find(|(child, _)| *child == cwe) {
+				Some((_, parent)) => cwe = *parent,
+				None => break,
+			}
+		}
+		WeaknessClass::Other
+	}
+
+	/// The known CWE IDs of this class; `Other` has none, it is everything else
+	pub fn cwe_ids(&self) -> Vec<String> {
+		if *self == WeaknessClass::Other {
+			return Vec::new();
+		}
+		known_cwes()
+			.map(|cwe| format!("CWE-{}", cwe))
+			.filter(|cwe_id| WeaknessClass::of(cwe_id) == *self)
+			.collect()
+	}
+
+	/// The CWE IDs that belong to a class other than `Other`
+	pub fn classified_cwe_ids() -> Vec<String> {
+		known_cwes()
+			.map(|cwe| format!("CWE-{}", cwe))
+			.filter(|cwe_id| WeaknessClass::of(cwe_id) != WeaknessClass::Other)
+			.collect()
+	}
+}
+
+/// Every CWE in the tables, each once
+fn known_cwes() -> impl Iterator<Item = u32> {
+	let mut cwes: Vec<u32> = CLASS_ROOTS.iter()
+		.map(|(cwe, _)| *cwe)
+		.chain(PARENTS.iter().map(|(child, _)| *child))
+		.collect();
+	cwes.sort_unstable();
+	cwes.dedup();
+	cwes.into_iter()
+}
+
+impl std::fmt::Display for WeaknessClass {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.label())
+	}
+}
+
+/// Normalized `CWE-<n>` ID of a weakness as written by the NVD. The placeholders
+/// `NVD-CWE-Other` and `NVD-CWE-noinfo` carry no weakness and give `None`.
+pub fn normalize_cwe_id(value: &str) -> Option<String> {
+	cwe_number(value).map(|number| format!("CWE-{}", number))
+}
+
+fn cwe_number(value: &str) -> Option<u32> {
+	let value = value.trim();
+	let number = value.get(..4).filter(|prefix| prefix.eq_ignore_ascii_case("CWE-")).map(|_| &value[4..])?;
+	number.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_weakness_class() {
+		assert_eq!(WeaknessClass::of("CWE-121"), WeaknessClass::MemorySafety);
+		assert_eq!(WeaknessClass::of("CWE-416"), WeaknessClass::MemorySafety);
+		assert_eq!(WeaknessClass::of("cwe-78"), WeaknessClass::Injection);
+		assert_eq!(WeaknessClass::of("CWE-259"), WeaknessClass::AccessControl);
+		assert_eq!(WeaknessClass::of("CWE-59"), WeaknessClass::InputHandling);
+		assert_eq!(WeaknessClass::of("CWE-401"), WeaknessClass::ResourceManagement);
+		assert_eq!(WeaknessClass::of("CWE-1004"), WeaknessClass::Other);
+		assert_eq!(WeaknessClass::of("NVD-CWE-noinfo"), WeaknessClass::Other);
+
+		let memory = WeaknessClass::MemorySafety.cwe_ids();
+		assert!(memory.contains(&"CWE-121".to_string()) && memory.contains(&"CWE-119".to_string()));
+		assert!(!memory.contains(&"CWE-78".to_string()));
+		assert!(WeaknessClass::Other.cwe_ids().is_empty());
+		assert_eq!(
+			WeaknessClass::classified_cwe_ids().len(),
+			WeaknessClass::ALL.iter().map(|class| class.cwe_ids().len()).sum::<usize>()
+		);
+
+		assert_eq!(normalize_cwe_id(" cwe-787 ").as_deref(), Some("CWE-787"));
+		assert_eq!(normalize_cwe_id("NVD-CWE-Other"), None);
+		assert_eq!(normalize_cwe_id("CWE-"), None);
+	}
+}
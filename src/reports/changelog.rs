@@ -0,0 +1,139 @@
+// src/reports/changelog.rs
+
+//! Changelog of the data source syncs: per sync, the vulnerabilities it added, the
+//! severities it changed and the robots it newly exposed.
+
+use super::{escape_html, Layout};
+use super::print::page;
+use crate::models::sync_delta::SyncDelta;
+use crate::utils::time;
+use anyhow::{Context, Result};
+
+const HEADERS: [&str; 7] = ["Synced", "Sources", "Change", "CVE", "Robot", "Before", "After"];
+
+/// One report row per change, in `HEADERS` order
+fn rows(delta: &SyncDelta) -> Vec<[String; 7]> {
+	let row = |change: &str, cve_id: &str, robot: &str, before: &str, after: &str| [
+		time::format_local(delta.started_at),
+		delta.sources.join("; "),
+		change.to_string(),
+		cve_id.to_string(),
+		robot.to_string(),
+		before.to_string(),
+		after.to_string(),
+	];
+	let changes = &delta.changes;
+	changes.new_vulnerabilities
+		.iter()
+		.map(|vuln| row("New", &vuln.cve_id, "", "", &vuln.severity))
+		.chain(changes.severity_changes.iter().map(|change| row("Severity", &change.cve_id, "", &change.before, &change.after)))
+		.chain(changes.new_exposures.iter().map(|exposure| row("Exposed", &exposure.cve_id, &exposure.robot, "", &exposure.severity)))
+		.collect()
+}
+
+pub fn report_csv(deltas: &[SyncDelta]) -> Result<String> {
+	let mut writer = csv::Writer::from_writer(Vec::new());
+	writer.write_record(HEADERS)?;
+	for row in deltas.iter().flat_map(rows) {
+		writer.write_record(&row)?;
+	}
+	let bytes = writer.into_inner().context("Failed to write changelog CSV")?;
+	String::from_utf8(bytes).context("Changelog CSV is not valid UTF-8")
+}
+
+fn table(headers: &[&str], rows: Vec<Vec<&str>>) -> String {
+	if rows.is_empty() {
+		return "<p>None.</p>".to_string();
+	}
+	let head: String = headers.iter().map(|header| format!("<th>{}</th>", header)).collect();
+	let body: String = rows
+		.iter()
+		.map(|cells| format!("<tr>{}</tr>", cells.iter().map(|cell| format!("<td>{}</td>", escape_html(cell))).collect::<String>()))
+		.collect();
+	format!("<table class=\"list\"><thead><tr>{}</tr></thead><tbody>{}</tbody></table>", head, body)
+}
+
+/// HTML layout of the changelog, newest sync first as given
+pub fn report_html(deltas: &[SyncDelta], layout: Layout) -> String {
+	let content: String = if deltas.is_empty() {
+		"<p>No sync changed anything yet.</p>".to_string()
+	} else {
+		deltas
+			.iter()
+			.map(|delta| {
+				let changes = &delta.changes;
+				format!(
+					"<h2>{synced} &middot; {sources}</h2><p>{summary}.</p>\
+					 <h3>New vulnerabilities</h3>{new}\
+					 <h3>Severity changes</h3>{severity}\
+					 <h3>Newly exposed robots</h3>{exposed}",
+					synced = escape_html(&time::format_local(delta.started_at)),
+					sources = escape_html(&delta.sources.join(", ")),
+					summary = changes,
+					new = table(
+						&["CVE", "Severity"],
+						changes.new_vulnerabilities.iter().map(|vuln| vec![vuln.cve_id.as_str(), &vuln.severity]).collect(),
+					),
+					severity = table(
+						&["CVE", "Before", "After"],
+						changes.severity_changes
+							.iter()
+							.map(|change| vec![change.cve_id.as_str(), &change.before, &change.after])
+							.collect(),
+					),
+					exposed = table(
+						&["Robot", "CVE", "Severity"],
+						changes.new_exposures
+							.iter()
+							.map(|exposure| vec![exposure.robot.as_str(), &exposure.cve_id, &exposure.severity])
+							.collect(),
+					),
+				)
+			})
+			.collect()
+	};
+	page("Sync changelog", &format!("<h1>Sync changelog</h1>{}", content), layout)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::sync_delta::{NewExposure, NewVulnerability, SeverityChange, SyncChanges};
+
+	#[test]
+	fn test_report() -> Result<()> {
+		let started_at = time::parse_utc("2024-05-13T08:00:00Z").unwrap();
+		let deltas = [SyncDelta {
+			delta_id: 1,
+			started_at,
+			finished_at: started_at,
+			sources: vec!["NVD".to_string(), "CISA KEV".to_string()],
+			changes: SyncChanges {
+				new_vulnerabilities: vec![NewVulnerability { cve_id: "CVE-2024-0003".to_string(), severity: "High".to_string() }],
+				severity_changes: vec![SeverityChange {
+					cve_id: "CVE-2024-0001".to_string(),
+					before: "Medium".to_string(),
+					after: "Critical".to_string(),
+				}],
+				new_exposures: vec![NewExposure {
+					robot: "arm-01 <cell 2>".to_string(),
+					cve_id: "CVE-2024-0003".to_string(),
+					severity: "High".to_string(),
+				}],
+			},
+		}];
+
+		let csv = report_csv(&deltas)?;
+		let synced = time::format_local(started_at);
+		let mut lines = csv.lines().skip(1);
+		assert_eq!(lines.next().unwrap(), format!("{},NVD; CISA KEV,New,CVE-2024-0003,,,High", synced));
+		assert_eq!(lines.next().unwrap(), format!("{},NVD; CISA KEV,Severity,CVE-2024-0001,,Medium,Critical", synced));
+		assert_eq!(lines.next().unwrap(), format!("{},NVD; CISA KEV,Exposed,CVE-2024-0003,arm-01 <cell 2>,,High", synced));
+		assert_eq!(lines.next(), None);
+
+		let html = report_html(&deltas, Layout::Print);
+		assert!(html.contains("1 new vulnerabilities, 1 severity changes, 1 new robot exposures."));
+		assert!(html.contains("<td>arm-01 &lt;cell 2&gt;</td>"));
+		Ok(())
+	}
+}
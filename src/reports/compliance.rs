@@ -0,0 +1,100 @@
+// src/reports/compliance.rs
+
+//! Compliance coverage of the fleet: per control of IEC 62443, ISO/IEC 27001 Annex A
+//! and any added standards, the vulnerabilities found on the robots that were mapped
+//! to it and how many are still open, as evidence for audits.
+
+use super::{escape_html, Layout};
+use super::print::page;
+use crate::models::compliance::ControlCoverage;
+use anyhow::{Context, Result};
+
+const HEADERS: [&str; 7] = ["Standard", "Control", "Title", "Findings", "Open", "Robots affected", "Status"];
+
+/// One report row per control, in `HEADERS` order
+fn rows(coverage: &[ControlCoverage]) -> Vec<[String; 7]> {
+	coverage
+		.iter()
+		.map(|entry| [
+			entry.control.standard.clone(),
+			entry.control.code.clone(),
+			entry.control.title.clone(),
+			entry.findings.to_string(),
+			entry.open.to_string(),
+			entry.robots.to_string(),
+			entry.status(),
+		])
+		.collect()
+}
+
+/// HTML layout of the coverage report
+pub fn report_html(coverage: &[ControlCoverage], layout: Layout) -> String {
+	let mapped = coverage.iter().filter(|entry| entry.findings > 0).count();
+	let open = coverage.iter().filter(|entry| entry.open > 0).count();
+
+	let header: String = HEADERS.iter().map(|h| format!("<th>{}</th>", h)).collect();
+	let body: String = rows(coverage)
+		.iter()
+		.zip(coverage)
+		.map(|(row, entry)| {
+			let class = if entry.open > 0 { " class=\"open\"" } else { "" };
+			let cells: String = row.iter().map(|cell| format!("<td>{}</td>", escape_html(cell))).collect();
+			format!("<tr{}>{}</tr>", class, cells)
+		})
+		.collect();
+
+	let content = format!(
+		"<h1>Compliance Coverage</h1><p>{} controls, {} with findings on the fleet mapped to them, \
+		 {} with open findings.</p>\
+		 <table class=\"list\"><thead><tr>{}</tr></thead><tbody>{}</tbody></table>",
+		coverage.len(),
+		mapped,
+		open,
+		header,
+		body,
+	);
+	page("Compliance Coverage", &content, layout)
+}
+
+pub fn report_csv(coverage: &[ControlCoverage]) -> Result<String> {
+	let mut writer = csv::Writer::from_writer(Vec::new());
+	writer.write_record(HEADERS)?;
+	for row in rows(coverage) {
+		writer.write_record(&row)?;
+	}
+	let bytes = writer.into_inner().context("Failed to write compliance CSV")?;
+	String::from_utf8(bytes).context("Compliance CSV is not valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::compliance::ComplianceControl;
+
+	#[test]
+	fn test_report() -> Result<()> {
+		let entry = |code: &str, findings: usize, open: usize| ControlCoverage {
+			control: ComplianceControl {
+				control_id: 0,
+				standard: "IEC 62443-3-3".to_string(),
+				code: code.to_string(),
+				title: "Network segmentation".to_string(),
+			},
+			findings,
+			open,
+			robots: findings,
+		};
+		let coverage = [entry("SR 5.1", 2, 1), entry("SR 5.2", 0, 0)];
+
+		let csv = report_csv(&coverage)?;
+		let mut lines = csv.lines();
+		assert_eq!(lines.next().unwrap(), "Standard,Control,Title,Findings,Open,Robots affected,Status");
+		assert_eq!(lines.next().unwrap(), "IEC 62443-3-3,SR 5.1,Network segmentation,2,1,2,1 open");
+		assert_eq!(lines.next().unwrap(), "IEC 62443-3-3,SR 5.2,Network segmentation,0,0,0,No findings linked");
+
+		let html = report_html(&coverage, Layout::Print);
+		assert!(html.contains("2 controls, 1 with findings on the fleet mapped to them, 1 with open findings"));
+		assert!(html.contains("<tr class=\"open\">"));
+		Ok(())
+	}
+}
@@ -0,0 +1,155 @@
+// src/reports/diff.rs
+
+//! What changed between two fleet snapshots: CVEs newly opened and closed, and the
+//! risk change of each robot, for the weekly security review.
+
+use super::{escape_html, Layout};
+use super::print::page;
+use crate::models::snapshot::{OpenVulnerability, RiskDelta, SnapshotDiff};
+use anyhow::{Context, Result};
+
+const HEADERS: [&str; 6] = ["Change", "Item", "Severity", "Robots", "Before", "After"];
+
+fn score(value: Option<f64>) -> String {
+	value.map(|score| format!("{:.1}", score)).unwrap_or_default()
+}
+
+/// Robots whose score moved, appeared or disappeared
+fn changed_robots(diff: &SnapshotDiff) -> impl Iterator<Item = &RiskDelta> {
+	diff.risk.iter().filter(|delta| delta.before != delta.after)
+}
+
+/// One report row per opened or closed CVE and changed robot, in `HEADERS` order
+fn rows(diff: &SnapshotDiff) -> Vec<[String; 6]> {
+	let vulnerability = |change: &str, vuln: &OpenVulnerability| [
+		change.to_string(),
+		vuln.cve_id.clone(),
+		vuln.severity.clone(),
+		vuln.robots.join("; "),
+		String::new(),
+		String::new(),
+	];
+	diff.opened
+		.iter()
+		.map(|vuln| vulnerability("Opened", vuln))
+		.chain(diff.closed.iter().map(|vuln| vulnerability("Closed", vuln)))
+		.chain(changed_robots(diff).map(|delta| [
+			"Risk".to_string(),
+			delta.name.clone(),
+			String::new(),
+			String::new(),
+			score(delta.before),
+			score(delta.after),
+		]))
+		.collect()
+}
+
+fn vulnerability_table(vulnerabilities: &[OpenVulnerability]) -> String {
+	if vulnerabilities.is_empty() {
+		return "<p>None.</p>".to_string();
+	}
+	let body: String = vulnerabilities
+		.iter()
+		.map(|vuln| format!(
+			"<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+			escape_html(&vuln.cve_id),
+			escape_html(&vuln.severity),
+			escape_html(&vuln.robots.join(", ")),
+		))
+		.collect();
+	format!(
+		"<table class=\"list\"><thead><tr><th>CVE</th><th>Severity</th><th>Robots</th></tr></thead><tbody>{}</tbody></table>",
+		body
+	)
+}
+
+/// HTML layout of the diff
+pub fn report_html(diff: &SnapshotDiff, layout: Layout) -> String {
+	let robots: String = changed_robots(diff)
+		.map(|delta| format!(
+			"<tr><td>{}</td><td>{}</td><td>{}</td><td>{:+.1}</td></tr>",
+			escape_html(&delta.name),
+			score(delta.before),
+			score(delta.after),
+			delta.change(),
+		))
+		.collect();
+	let robots = if robots.is_empty() {
+		"<p>No robot's risk score changed.</p>".to_string()
+	} else {
+		format!(
+			"<table class=\"list\"><thead><tr><th>Robot</th><th>Before</th><th>After</th><th>Change</th></tr></thead>\
+			 <tbody>{}</tbody></table>",
+			robots
+		)
+	};
+
+	let content = format!(
+		"<h1>Changes from {from} to {to}</h1>\
+		 <p>{opened} vulnerabilities opened, {closed} closed. Fleet risk index {before:.1} &rarr; {after:.1} ({change:+.1}).</p>\
+		 <h2>Newly opened</h2>{opened_table}\
+		 <h2>Closed</h2>{closed_table}\
+		 <h2>Risk per robot</h2>{robots}",
+		from = diff.from,
+		to = diff.to,
+		opened = diff.opened.len(),
+		closed = diff.closed.len(),
+		before = diff.fleet_risk_before,
+		after = diff.fleet_risk_after,
+		change = diff.fleet_risk_after - diff.fleet_risk_before,
+		opened_table = vulnerability_table(&diff.opened),
+		closed_table = vulnerability_table(&diff.closed),
+		robots = robots,
+	);
+	page(&format!("Changes from {} to {}", diff.from, diff.to), &content, layout)
+}
+
+pub fn report_csv(diff: &SnapshotDiff) -> Result<String> {
+	let mut writer = csv::Writer::from_writer(Vec::new());
+	writer.write_record(HEADERS)?;
+	for row in rows(diff) {
+		writer.write_record(&row)?;
+	}
+	let bytes = writer.into_inner().context("Failed to write diff CSV")?;
+	String::from_utf8(bytes).context("Diff CSV is not valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::NaiveDate;
+
+	#[test]
+	fn test_report() -> Result<()> {
+		let vuln = |cve_id: &str| OpenVulnerability {
+			cve_id: cve_id.to_string(),
+			severity: "High".to_string(),
+			robots: vec!["arm-01".to_string(), "arm-02".to_string()],
+		};
+		let diff = SnapshotDiff {
+			from: NaiveDate::from_ymd_opt(2024, 5, 6).unwrap(),
+			to: NaiveDate::from_ymd_opt(2024, 5, 13).unwrap(),
+			fleet_risk_before: 20.0,
+			fleet_risk_after: 35.5,
+			opened: vec![vuln("CVE-2024-0003")],
+			closed: vec![],
+			risk: vec![
+				RiskDelta { name: "arm-01".to_string(), before: Some(30.0), after: Some(60.0) },
+				RiskDelta { name: "agv-01".to_string(), before: Some(10.0), after: Some(10.0) },
+			],
+		};
+
+		let csv = report_csv(&diff)?;
+		let mut lines = csv.lines();
+		assert_eq!(lines.next().unwrap(), "Change,Item,Severity,Robots,Before,After");
+		assert_eq!(lines.next().unwrap(), "Opened,CVE-2024-0003,High,arm-01; arm-02,,");
+		assert_eq!(lines.next().unwrap(), "Risk,arm-01,,,30.0,60.0");
+		assert_eq!(lines.next(), None);
+
+		let html = report_html(&diff, Layout::Print);
+		assert!(html.contains("1 vulnerabilities opened, 0 closed. Fleet risk index 20.0 &rarr; 35.5 (+15.5)"));
+		assert!(html.contains("<td>arm-01</td><td>30.0</td><td>60.0</td><td>+30.0</td>"));
+		assert!(!html.contains("agv-01"));
+		Ok(())
+	}
+}
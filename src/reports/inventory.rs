@@ -0,0 +1,99 @@
+// src/reports/inventory.rs
+
+//! Software inventory per robot with license identifiers, for license compliance
+//! reviews alongside the open vulnerability counts.
+
+use super::{escape_html, Layout};
+use super::print::page;
+use crate::models::software::InventoryEntry;
+use anyhow::{Context, Result};
+
+const HEADERS: [&str; 8] = [
+	"Robot", "Product", "Vendor", "Version", "License", "Open vulnerabilities", "Max CVSS", "Max exposure (days)",
+];
+
+/// One report row per installed version, in `HEADERS` order
+fn rows(inventory: &[InventoryEntry]) -> Vec<[String; 8]> {
+	inventory
+		.iter()
+		.map(|entry| [
+			entry.robot_name.clone(),
+			entry.product_name.clone(),
+			entry.vendor.clone(),
+			entry.version_number.clone(),
+			entry.license.clone().unwrap_or_default(),
+			entry.open_vulnerabilities.to_string(),
+			entry.max_cvss.map(|score| format!("{:.1}", score)).unwrap_or_default(),
+			entry.max_exposure_days.map(|days| days.to_string()).unwrap_or_default(),
+		])
+		.collect()
+}
+
+/// HTML layout of the inventory
+pub fn report_html(inventory: &[InventoryEntry], layout: Layout) -> String {
+	let unlicensed = inventory.iter().filter(|entry| entry.license.is_none()).count();
+	let longest = match inventory.iter().filter_map(|entry| entry.max_exposure_days).max() {
+		Some(days) => format!(" The longest open exposure is {} days.", days),
+		None => String::new(),
+	};
+
+	let header: String = HEADERS.iter().map(|h| format!("<th>{}</th>", h)).collect();
+	let body: String = rows(inventory)
+		.iter()
+		.map(|row| {
+			let cells: String = row.iter().map(|cell| format!("<td>{}</td>", escape_html(cell))).collect();
+			format!("<tr>{}</tr>", cells)
+		})
+		.collect();
+
+	let content = format!(
+		"<h1>Software Inventory</h1><p>{} installed software versions, {} without a known license.{}</p>\
+		 <table class=\"list\"><thead><tr>{}</tr></thead><tbody>{}</tbody></table>",
+		inventory.len(),
+		unlicensed,
+		longest,
+		header,
+		body,
+	);
+	page("Software Inventory", &content, layout)
+}
+
+pub fn report_csv(inventory: &[InventoryEntry]) -> Result<String> {
+	let mut writer = csv::Writer::from_writer(Vec::new());
+	writer.write_record(HEADERS)?;
+	for row in rows(inventory) {
+		writer.write_record(&row)?;
+	}
+	let bytes = writer.into_inner().context("Failed to write inventory CSV")?;
+	String::from_utf8(bytes).context("Inventory CSV is not valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_report() -> Result<()> {
+		let entry = |license: Option<&str>, max_cvss: Option<f64>| InventoryEntry {
+			robot_name: "arm-01".to_string(),
+			product_name: "ros-core".to_string(),
+			vendor: "OSRF".to_string(),
+			version_number: "1.0".to_string(),
+			license: license.map(str::to_string),
+			open_vulnerabilities: if max_cvss.is_some() { 2 } else { 0 },
+			max_cvss,
+			max_exposure_days: max_cvss.map(|_| 412),
+		};
+		let inventory = [entry(Some("Apache-2.0 OR MIT"), Some(7.0)), entry(None, None)];
+
+		let csv = report_csv(&inventory)?;
+		let mut lines = csv.lines();
+		assert_eq!(lines.next().unwrap(), "Robot,Product,Vendor,Version,License,Open vulnerabilities,Max CVSS,Max exposure (days)");
+		assert_eq!(lines.next().unwrap(), "arm-01,ros-core,OSRF,1.0,Apache-2.0 OR MIT,2,7.0,412");
+		assert_eq!(lines.next().unwrap(), "arm-01,ros-core,OSRF,1.0,,0,,");
+
+		let html = report_html(&inventory, Layout::Print);
+		assert!(html.contains("2 installed software versions, 1 without a known license. The longest open exposure is 412 days."));
+		Ok(())
+	}
+}
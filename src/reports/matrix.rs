@@ -0,0 +1,97 @@
+// src/reports/matrix.rs
+
+//! Affected-software matrix as CSV or as an Excel workbook with colored cells, for
+//! vulnerability managers who track exposure in spreadsheets.
+
+use crate::models::matrix::{AffectedMatrix, MatrixCell};
+use anyhow::{Context, Result};
+use rust_xlsxwriter::{Color, Format, FormatBorder, Workbook, XlsxError};
+
+const LEADING_HEADERS: [&str; 3] = ["CVE", "Severity", "Status"];
+
+/// The header row followed by one row per CVE
+fn records(matrix: &AffectedMatrix) -> Vec<Vec<String>> {
+	let header = LEADING_HEADERS.iter().map(|h| h.to_string()).chain(matrix.columns.iter().cloned()).collect();
+	std::iter::once(header)
+		.chain(matrix.rows.iter().map(|row| {
+			[row.cve_id.clone(), row.severity.clone(), row.status.to_string()]
+				.into_iter()
+				.chain(row.cells.iter().map(|cell| cell.label().to_string()))
+				.collect()
+		}))
+		.collect()
+}
+
+pub fn report_csv(matrix: &AffectedMatrix) -> Result<String> {
+	let mut writer = csv::Writer::from_writer(Vec::new());
+	for record in records(matrix) {
+		writer.write_record(&record)?;
+	}
+	let bytes = writer.into_inner().context("Failed to write matrix CSV")?;
+	String::from_utf8(bytes).context("Matrix CSV is not valid UTF-8")
+}
+
+/// Workbook with the header row and CVE column frozen and the cells colored by status
+pub fn report_xlsx(matrix: &AffectedMatrix) -> Result<Vec<u8>> {
+	write_workbook(matrix).context("Failed to write matrix workbook")
+}
+
+fn write_workbook(matrix: &AffectedMatrix) -> Result<Vec<u8>, XlsxError> {
+	let header = Format::new().set_bold().set_border_bottom(FormatBorder::Thin);
+	let cell_format = |cell: MatrixCell| match cell {
+		MatrixCell::Affected => Some(Format::new().set_background_color(Color::RGB(0xF4CCCC))),
+		MatrixCell::Unknown => Some(Format::new().set_background_color(Color::RGB(0xFFF2CC))),
+		MatrixCell::Fixed => Some(Format::new().set_background_color(Color::RGB(0xD9EAD3))),
+		MatrixCell::NotAffected => None,
+	};
+
+	let mut workbook = Workbook::new();
+	let sheet = workbook.add_worksheet();
+	sheet.set_name("Affected software")?;
+	for (col, title) in LEADING_HEADERS.iter().map(|h| h.to_string()).chain(matrix.columns.iter().cloned()).enumerate() {
+		sheet.write_string_with_format(0, col as u16, title, &header)?;
+	}
+	for (idx, row) in matrix.rows.iter().enumerate() {
+		let line = idx as u32 + 1;
+		sheet.write_string(line, 0, &row.cve_id)?;
+		sheet.write_string(line, 1, &row.severity)?;
+		sheet.write_string(line, 2, row.status.to_string())?;
+		for (col, cell) in row.cells.iter().enumerate() {
+			if let Some(format) = cell_format(*cell) {
+				sheet.write_string_with_format(line, (col + LEADING_HEADERS.len()) as u16, cell.label(), &format)?;
+			}
+		}
+	}
+	sheet.set_freeze_panes(1, 1)?;
+	sheet.autofit();
+	workbook.save_to_buffer()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::matrix::MatrixRow;
+	use crate::models::vulnerability::TriageStatus;
+
+	#[test]
+	fn test_report() -> Result<()> {
+		let matrix = AffectedMatrix {
+			columns: vec!["ros-core 1.0 (OSRF)".to_string(), "ros-core 2.0 (OSRF)".to_string()],
+			rows: vec![MatrixRow {
+				cve_id: "CVE-2024-0001".to_string(),
+				severity: "High".to_string(),
+				status: TriageStatus::Open,
+				cells: vec![MatrixCell::Affected, MatrixCell::NotAffected],
+			}],
+		};
+		let csv = report_csv(&matrix)?;
+		assert_eq!(
+			csv,
+			"CVE,Severity,Status,ros-core 1.0 (OSRF),ros-core 2.0 (OSRF)\nCVE-2024-0001,High,Open,affected,\n"
+		);
+
+		// An XLSX file is a zip archive
+		assert!(report_xlsx(&matrix)?.starts_with(b"PK"));
+		Ok(())
+	}
+}
@@ -0,0 +1,87 @@
+// src/reports/mod.rs
+
+pub mod audit;
+pub mod changelog;
+pub mod compliance;
+pub mod diff;
+pub mod inventory;
+pub mod matrix;
+pub mod print;
+pub mod risk_acceptance;
+pub mod share;
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use tokio::task;
+
+/// How an HTML report is meant to be used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+	/// Opens the print dialog on load, to print or save as PDF
+	Print,
+	/// Read-only page to send to stakeholders without RVD, with a row filter
+	Share,
+}
+
+/// Escape text for safe embedding into HTML element content and attribute values
+pub fn escape_html(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'"' => escaped.push_str("&quot;"),
+			'\'' => escaped.push_str("&#39;"),
+			_ => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+/// Write a rendered HTML report to the temp directory and open it with the default browser,
+/// from where it can be printed or saved as PDF
+pub async fn open_html_report(file_name: String, html: String) -> Result<PathBuf> {
+	task::spawn_blocking(move || {
+		let dir = std::env::temp_dir().join("rvd-reports");
+		std::fs::create_dir_all(&dir).context("Failed to create report directory")?;
+
+		let path = dir.join(file_name);
+		std::fs::write(&path, html)
+			.with_context(|| format!("Failed to write report to {:?}", path))?;
+		open::that(&path).with_context(|| format!("Failed to open {:?}", path))?;
+
+		Ok(path)
+	})
+		.await
+		.context("Failed to run report task")?
+}
+
+/// Save a shareable page or export to the downloads folder, falling back to the home
+/// directory, where it is easy to find and attach
+pub async fn save_to_downloads(file_name: String, contents: String) -> Result<PathBuf> {
+	task::spawn_blocking(move || {
+		let dir = dirs::download_dir()
+			.or_else(dirs::home_dir)
+			.unwrap_or_else(std::env::temp_dir);
+		std::fs::create_dir_all(&dir).context("Failed to create download directory")?;
+
+		let path = dir.join(file_name);
+		std::fs::write(&path, contents)
+			.with_context(|| format!("Failed to write {:?}", path))?;
+		Ok(path)
+	})
+		.await
+		.context("Failed to run report task")?
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_escape_html() {
+		assert_eq!(escape_html("<script>\"a\" & 'b'</script>"), "&lt;script&gt;&quot;a&quot; &amp; &#39;b&#39;&lt;/script&gt;");
+		assert_eq!(escape_html("plain"), "plain");
+	}
+}
@@ -0,0 +1,247 @@
+// src/reports/print.rs
+
+//! Print-optimized HTML layouts of single records, meant to be printed or saved as PDF
+//! from the browser and attached to work orders.
+
+use super::{escape_html, Layout};
+use crate::models::note::Note;
+use crate::utils::time;
+use crate::models::robot::Robot;
+use crate::models::vulnerability::Vulnerability;
+use chrono::Utc;
+
+const PRINT_STYLE: &str = "
+	@page { size: A4; margin: 15mm; }
+	body { font-family: sans-serif; font-size: 11pt; color: #000; max-width: 180mm; margin: 0 auto; }
+	h1 { font-size: 18pt; margin: 0 0 4mm 0; }
+	h2 { font-size: 12pt; border-bottom: 1px solid #888; padding-bottom: 1mm; margin: 6mm 0 2mm 0; }
+	table.facts { border-collapse: collapse; width: 100%; }
+	table.facts th { text-align: left; width: 40mm; padding: 1mm 2mm 1mm 0; vertical-align: top; }
+	table.facts td { padding: 1mm 0; }
+	table.list { border-collapse: collapse; width: 100%; font-size: 9pt; }
+	table.list th, table.list td { border: 1px solid #888; padding: 1mm; text-align: left; vertical-align: top; }
+	table.list tr { page-break-inside: avoid; }
+	.expired, .open { color: #b00; font-weight: bold; }
+	p, li { white-space: pre-wrap; }
+	.note { border-left: 2px solid #888; padding-left: 3mm; margin-bottom: 3mm; page-break-inside: avoid; }
+	.meta { color: #555; font-size: 9pt; }
+	footer { margin-top: 8mm; color: #555; font-size: 9pt; }
+	@media print { .no-print { display: none; } }
+	@media screen { body.share { max-width: none; margin: 0 5mm; } }
+";
+
+/// Hides the rows of every list table that do not contain the filter text
+const SHARE_SCRIPT: &str = "
+	function filterRows(text) {
+		text = text.toLowerCase();
+		document.querySelectorAll('table.list tbody tr').forEach(function (row) {
+			row.style.display = row.textContent.toLowerCase().includes(text) ? '' : 'none';
+		});
+	}
+";
+
+pub(super) fn page(title: &str, body: &str, layout: Layout) -> String {
+	let (body_attributes, toolbar, footer) = match layout {
+		Layout::Print => (
+			" onload=\"window.print()\"",
+			"<button onclick=\"window.print()\">Print / Save as PDF</button>".to_string(),
+			"Printed from RVD",
+		),
+		Layout::Share => (
+			" class=\"share\"",
+			format!(
+				"<input type=\"search\" placeholder=\"Filter rows...\" oninput=\"filterRows(this.value)\"> \
+				 <button onclick=\"window.print()\">Print / Save as PDF</button><script>{}</script>",
+				SHARE_SCRIPT
+			),
+			"Read-only snapshot shared from RVD",
+		),
+	};
+
+	format!(
+		"<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>{style}</style>
+</head>
+<body{body_attributes}>
+<p class=\"no-print\">{toolbar}</p>
+{body}
+<footer>{footer} on {printed}</footer>
+</body>
+</html>
+",
+		title = escape_html(title),
+		style = PRINT_STYLE,
+		body_attributes = body_attributes,
+		toolbar = toolbar,
+		body = body,
+		footer = footer,
+		printed = time::format_local(Utc::now()),
+	)
+}
+
+fn facts(rows: &[(&str, String)]) -> String {
+	let rows: String = rows
+		.iter()
+		.map(|(label, value)| format!("<tr><th>{}</th><td>{}</td></tr>", label, escape_html(value)))
+		.collect();
+	format!("<table class=\"facts\">{}</table>", rows)
+}
+
+fn section(heading: &str, text: Option<&str>, empty: &str) -> String {
+	format!(
+		"<h2>{}</h2><p>{}</p>",
+		heading,
+		escape_html(text.filter(|t| !t.trim().is_empty()).unwrap_or(empty))
+	)
+}
+
+fn notes_section(notes: &[Note]) -> String {
+	if notes.is_empty() {
+		return String::new();
+	}
+
+	let items: String = notes
+		.iter()
+		.map(|note| {
+			format!(
+				"<div class=\"note\"><div class=\"meta\">{}</div><p>{}</p></div>",
+				note.created_at
+					.map(time::format_local)
+					.unwrap_or_default(),
+				escape_html(&note.body)
+			)
+		})
+		.collect();
+	format!("<h2>Notes</h2>{}", items)
+}
+
+/// Print layout of one vulnerability with its triage state and notes
+pub fn vulnerability_detail_html(vuln: &Vulnerability, notes: &[Note]) -> String {
+	let cvss = match (vuln.cvss_score, vuln.cvss_version) {
+		(Some(score), Some(version)) => format!("{:.1} ({})", score, version),
+		(Some(score), None) => format!("{:.1}", score),
+		(None, _) => format!("~{:.1} (estimated from severity)", vuln.effective_cvss()),
+	};
+
+	let body = format!(
+		"<h1>{}</h1>{}{}{}{}{}{}",
+		escape_html(&vuln.cve_id),
+		facts(&[
+			("Severity", vuln.severity.clone()),
+			("CVSS", cvss),
+			("Published", vuln.published_date.map(|d| d.to_string()).unwrap_or_else(|| "Unknown".to_string())),
+			("Sources", vuln.sources().join(", ")),
+			("Also known as", match vuln.aliases.is_empty() {
+				true => "None".to_string(),
+				false => vuln.aliases.iter().map(|alias| alias.id.as_str()).collect::<Vec<_>>().join(", "),
+			}),
+			("Status", vuln.status.to_string()),
+			("Assigned to", vuln.assigned_to.clone().unwrap_or_else(|| "Unassigned".to_string())),
+		]),
+		risk_acceptance_facts(vuln),
+		section("Description", vuln.description.as_deref(), "No description available"),
+		section("Impact", vuln.impact.as_deref(), "No impact information available"),
+		section("Mitigation", vuln.mitigation.as_deref(), "No mitigation information available"),
+		notes_section(notes),
+	);
+
+	page(&vuln.cve_id, &body, Layout::Print)
+}
+
+fn risk_acceptance_facts(vuln: &Vulnerability) -> String {
+	let Some(acceptance) = &vuln.risk_acceptance else {
+		return String::new();
+	};
+	let or_none = |value: &Option<String>| value.clone().unwrap_or_else(|| "None".to_string());
+	format!(
+		"<h2>Risk Decision</h2>{}",
+		facts(&[
+			("Justification", or_none(&acceptance.justification)),
+			("Approved by", or_none(&acceptance.approved_by)),
+			("Recorded", acceptance.accepted_at.map(time::format_local).unwrap_or_default()),
+			("Expires", acceptance.expires_on.map(|d| d.to_string()).unwrap_or_else(|| "Never".to_string())),
+		])
+	)
+}
+
+/// Print layout of one robot with its installed software and notes
+pub fn robot_detail_html(robot: &Robot, software_versions: &[String], notes: &[Note]) -> String {
+	let software = if software_versions.is_empty() {
+		"<p>No software versions listed</p>".to_string()
+	} else {
+		format!(
+			"<ul>{}</ul>",
+			software_versions
+				.iter()
+				.map(|v| format!("<li>{}</li>", escape_html(v)))
+				.collect::<String>()
+		)
+	};
+
+	let mut robot_facts = vec![("Manufacturer", robot.manufacturer.clone().unwrap_or_else(|| "Unknown".to_string()))];
+	if let Some(model) = &robot.model {
+		robot_facts.push(("Model", model.clone()));
+	}
+	let platform = [
+		("Firmware", &robot.firmware_version),
+		("Operating system", &robot.os),
+		("ROS distribution", &robot.ros_distro),
+	];
+	for (label, value) in platform {
+		if let Some(value) = value {
+			robot_facts.push((label, value.clone()));
+		}
+	}
+	if let Some(note) = &robot.operational_note {
+		robot_facts.push(("Operational note", note.clone()));
+	}
+
+	let body = format!(
+		"<h1>{}</h1>{}{}<h2>Software Versions</h2>{}{}",
+		escape_html(&robot.name),
+		facts(&robot_facts),
+		section("Specifications", robot.specifications.as_deref(), "No specifications available"),
+		software,
+		notes_section(notes),
+	);
+
+	page(&robot.name, &body, Layout::Print)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::note::NoteEntity;
+
+	#[test]
+	fn test_vulnerability_detail_html() {
+		let mut vuln = Vulnerability::new("CVE-2024-0001".to_string(), "High".to_string());
+		vuln.description = Some("Overflow in <parser>".to_string());
+		let notes = vec![Note::new(NoteEntity::Vulnerability, 1, "Patched on line 3".to_string())];
+
+		let html = vulnerability_detail_html(&vuln, &notes);
+		assert!(html.contains("<h1>CVE-2024-0001</h1>"));
+		assert!(html.contains("Overflow in &lt;parser&gt;"));
+		assert!(html.contains("No impact information available"));
+		assert!(html.contains("Patched on line 3"));
+		assert!(html.contains("@page"));
+	}
+
+	#[test]
+	fn test_robot_detail_html() {
+		let mut robot = Robot::new("Arm & Co".to_string()).with_manufacturer("KUKA".to_string());
+		robot.operational_note = Some("Air-gapped".to_string());
+		robot.ros_distro = Some("ROS 2 Humble".to_string());
+		let html = robot_detail_html(&robot, &["ROS 2 Humble".to_string()], &[]);
+		assert!(html.contains("<h1>Arm &amp; Co</h1>"));
+		assert!(html.contains("<th>Operational note</th><td>Air-gapped</td>"));
+		assert!(html.contains("<th>ROS distribution</th><td>ROS 2 Humble</td>"));
+		assert!(!html.contains("Firmware"));
+		assert!(html.contains("<li>ROS 2 Humble</li>"));
+		assert!(!html.contains("<h2>Notes</h2>"));
+	}
+}
@@ -0,0 +1,113 @@
+// src/reports/risk_acceptance.rs
+
+//! Register of accepted risks and suppressed findings with their justification,
+//! approver and expiry, as auditors ask for it. HTML is printed or saved as PDF from
+//! the browser, or shared as a read-only page; CSV goes into spreadsheets.
+
+use super::{escape_html, Layout};
+use super::print::page;
+use crate::models::vulnerability::{RiskAcceptance, Vulnerability};
+use crate::utils::time;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+
+const HEADERS: [&str; 9] = [
+	"CVE", "Severity", "Status", "Justification", "Approved by", "Recorded", "Expires", "Expired", "Assigned to",
+];
+
+/// One report row per decision, in `HEADERS` order
+fn rows(decisions: &[Vulnerability], today: NaiveDate) -> Vec<[String; 9]> {
+	decisions
+		.iter()
+		.map(|vuln| {
+			let acceptance = vuln.risk_acceptance.clone().unwrap_or_default();
+			[
+				vuln.cve_id.clone(),
+				vuln.severity.clone(),
+				vuln.status.to_string(),
+				acceptance.justification.clone().unwrap_or_default(),
+				acceptance.approved_by.clone().unwrap_or_default(),
+				acceptance.accepted_at.map(time::format_local).unwrap_or_default(),
+				acceptance.expires_on.map(|d| d.to_string()).unwrap_or_else(|| "Never".to_string()),
+				expired_label(&acceptance, today).to_string(),
+				vuln.assigned_to.clone().unwrap_or_default(),
+			]
+		})
+		.collect()
+}
+
+fn expired_label(acceptance: &RiskAcceptance, today: NaiveDate) -> &'static str {
+	if acceptance.is_expired(today) { "Yes" } else { "No" }
+}
+
+/// HTML layout of the register
+pub fn report_html(decisions: &[Vulnerability], today: NaiveDate, layout: Layout) -> String {
+	let expired = decisions
+		.iter()
+		.filter(|v| v.risk_acceptance.as_ref().is_some_and(|a| a.is_expired(today)))
+		.count();
+
+	let header: String = HEADERS.iter().map(|h| format!("<th>{}</th>", h)).collect();
+	let body: String = rows(decisions, today)
+		.iter()
+		.map(|row| {
+			let class = if row[7] == "Yes" { " class=\"expired\"" } else { "" };
+			let cells: String = row.iter().map(|cell| format!("<td>{}</td>", escape_html(cell))).collect();
+			format!("<tr{}>{}</tr>", class, cells)
+		})
+		.collect();
+
+	let content = format!(
+		"<h1>Risk Acceptance Report</h1><p>{} risk decisions as of {}, {} expired and due for review.</p>\
+		 <table class=\"list\"><thead><tr>{}</tr></thead><tbody>{}</tbody></table>",
+		decisions.len(),
+		today,
+		expired,
+		header,
+		body,
+	);
+	page("Risk Acceptance Report", &content, layout)
+}
+
+pub fn report_csv(decisions: &[Vulnerability], today: NaiveDate) -> Result<String> {
+	let mut writer = csv::Writer::from_writer(Vec::new());
+	writer.write_record(HEADERS)?;
+	for row in rows(decisions, today) {
+		writer.write_record(&row)?;
+	}
+	let bytes = writer.into_inner().context("Failed to write risk acceptance CSV")?;
+	String::from_utf8(bytes).context("Risk acceptance CSV is not valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::vulnerability::TriageStatus;
+
+	#[test]
+	fn test_report() -> Result<()> {
+		let mut accepted = Vulnerability::new("CVE-2024-0001".to_string(), "High".to_string());
+		accepted.status = TriageStatus::AcceptedRisk;
+		accepted.risk_acceptance = Some(RiskAcceptance {
+			justification: Some("Robot is air-gapped, see \"cell 4\"".to_string()),
+			approved_by: Some("CISO".to_string()),
+			accepted_at: None,
+			expires_on: NaiveDate::from_ymd_opt(2025, 1, 31),
+		});
+		let today = NaiveDate::from_ymd_opt(2025, 2, 1).unwrap();
+
+		let csv = report_csv(&[accepted.clone()], today)?;
+		let mut lines = csv.lines();
+		assert!(lines.next().unwrap().starts_with("CVE,Severity,Status,Justification"));
+		assert_eq!(
+			lines.next().unwrap(),
+			"CVE-2024-0001,High,Accepted Risk,\"Robot is air-gapped, see \"\"cell 4\"\"\",CISO,,2025-01-31,Yes,"
+		);
+
+		let html = report_html(&[accepted], today, Layout::Print);
+		assert!(html.contains("1 risk decisions as of 2025-02-01, 1 expired"));
+		assert!(html.contains("<tr class=\"expired\">"));
+		assert!(html.contains("see &quot;cell 4&quot;"));
+		Ok(())
+	}
+}
@@ -0,0 +1,67 @@
+// src/reports/share.rs
+
+//! Self-contained, read-only HTML pages of a filtered vulnerability list for
+//! stakeholders who do not run RVD. Everything, styles and the row filter included,
+//! is inlined, so the single file opens from an email attachment or a file share.
+
+use super::print::page;
+use super::{escape_html, Layout};
+use crate::models::vulnerability::Vulnerability;
+
+const HEADERS: [&str; 7] = ["CVE", "Severity", "CVSS", "Status", "Published", "Known exploited", "Description"];
+
+/// Shareable page of the vulnerabilities matching a filter, described by `filter_summary`
+pub fn vulnerability_list_html(vulnerabilities: &[Vulnerability], filter_summary: &str) -> String {
+	let header: String = HEADERS.iter().map(|h| format!("<th>{}</th>", h)).collect();
+	let body: String = vulnerabilities
+		.iter()
+		.map(|vuln| {
+			let cells: String = [
+				vuln.cve_id.clone(),
+				vuln.severity.clone(),
+				match (vuln.cvss_score, vuln.cvss_version) {
+					(Some(score), Some(version)) => format!("{:.1} ({})", score, version),
+					(Some(score), None) => format!("{:.1}", score),
+					(None, _) => String::new(),
+				},
+				vuln.status.to_string(),
+				vuln.published_date.map(|d| d.to_string()).unwrap_or_default(),
+				vuln.kev_date_added.map(|d| format!("Since {}", d)).unwrap_or_default(),
+				vuln.description.clone().unwrap_or_default(),
+			]
+				.iter()
+				.map(|cell| format!("<td>{}</td>", escape_html(cell)))
+				.collect();
+			format!("<tr>{}</tr>", cells)
+		})
+		.collect();
+
+	let content = format!(
+		"<h1>Vulnerabilities</h1><p>{} vulnerabilities matching {}.</p>\
+		 <table class=\"list\"><thead><tr>{}</tr></thead><tbody>{}</tbody></table>",
+		vulnerabilities.len(),
+		escape_html(filter_summary),
+		header,
+		body,
+	);
+	page("Vulnerabilities", &content, Layout::Share)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_vulnerability_list_html() {
+		let mut vuln = Vulnerability::new("CVE-2024-0001".to_string(), "High".to_string());
+		vuln.description = Some("<script>alert(1)</script>".to_string());
+
+		let html = vulnerability_list_html(&[vuln], "severity High");
+		assert!(html.contains("1 vulnerabilities matching severity High"));
+		assert!(html.contains("<td>&lt;script&gt;alert(1)&lt;/script&gt;</td>"));
+		assert!(html.contains("function filterRows"));
+		assert!(!html.contains("onload"));
+		// Nothing is loaded from elsewhere
+		assert!(!html.contains("src=") && !html.contains("href="));
+	}
+}
@@ -0,0 +1,54 @@
+// src/repositories/access.rs
+
+//! Role of the current session, checked by every mutating data-layer call so that
+//! read-only users are refused no matter which view or command issued the change.
+
+use crate::models::role::Role;
+use std::sync::RwLock;
+
+static CURRENT_ROLE: RwLock<Role> = RwLock::new(Role::Admin);
+
+pub fn set_current_role(role: Role) {
+	*CURRENT_ROLE.write().unwrap_or_else(|e| e.into_inner()) = role;
+}
+
+pub fn current_role() -> Role {
+	*CURRENT_ROLE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Overrides the login name recorded as the author of manual edits
+pub const USER_ENV: &str = "RVD_USER";
+
+/// Name recorded as the author of manual edits: `RVD_USER`, else the login name
+pub fn current_user() -> String {
+	[USER_ENV, "USER", "USERNAME"]
+		.iter()
+		.filter_map(|var| std::env::var(var).ok())
+		.map(|name| name.trim().to_string())
+		.find(|name| !name.is_empty())
+		.unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Fails unless the current role may modify data
+pub fn require_write_access() -> anyhow::Result<()> {
+	ensure_can_edit(current_role())
+}
+
+fn ensure_can_edit(role: Role) -> anyhow::Result<()> {
+	if !role.can_edit() {
+		anyhow::bail!("Permission denied: the {} role is read-only", role);
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_viewer_is_refused() {
+		assert!(ensure_can_edit(Role::Admin).is_ok());
+		let err = ensure_can_edit(Role::Viewer).unwrap_err();
+		assert!(err.to_string().contains("read-only"));
+	}
+}
@@ -0,0 +1,225 @@
+// src/repositories/alert_repo.rs
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::alert::{Alert, AlertKind, StaleInventory};
+use crate::utils::time;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use anyhow::{Result, Context};
+use tokio::task;
+
+/// Email alerts queued by the schema triggers in `alert_outbox`
+pub struct AlertRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl AlertRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Alerts not sent yet, oldest first
+	pub async fn get_pending(&self) -> Result<Vec<Alert>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(
+				"SELECT a.alert_id, a.kind, v.cve_id, v.severity, r.name, r.operational_note, a.detail, a.created_at
+				 FROM alert_outbox a
+				 JOIN vulnerabilities v ON v.vulnerability_id = a.vulnerability_id
+				 LEFT JOIN robots r ON r.robot_id = a.robot_id
+				 WHERE a.sent_at IS NULL AND v.deleted_at IS NULL AND r.deleted_at IS NULL
+				 ORDER BY a.alert_id"
+			)?;
+
+			let rows = stmt.query_map([], |row| {
+				Ok((
+					row.get::<_, i64>(0)?,
+					row.get::<_, String>(1)?,
+					row.get::<_, String>(2)?,
+					row.get::<_, String>(3)?,
+					row.get::<_, Option<String>>(4)?,
+					row.get::<_, Option<String>>(5)?,
+					row.get::<_, Option<String>>(6)?,
+					row.get::<_, Option<String>>(7)?,
+				))
+			})?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to load pending alerts")?;
+
+			Ok(rows
+				.into_iter()
+				.filter_map(|(alert_id, kind, cve_id, severity, robot_name, robot_note, detail, created_at)| {
+					Some(Alert {
+						alert_id,
+						kind: AlertKind::from_db(&kind)?,
+						cve_id,
+						severity,
+						robot_name,
+						robot_note,
+						detail,
+						created_at: created_at.as_deref().and_then(time::parse_utc),
+					})
+				})
+				.collect())
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	pub async fn mark_sent(&self, alert_ids: Vec<i64>) -> Result<()> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			for alert_id in &alert_ids {
+				tx.execute(
+					"UPDATE alert_outbox SET sent_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE alert_id = ?1",
+					[alert_id],
+				)?;
+			}
+			tx.commit().context("Failed to mark alerts as sent")
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Robots whose inventory is older than `max_age_days` and that were not alerted
+	/// about since it was last refreshed
+	pub async fn get_stale_inventories(&self, max_age_days: u32) -> Result<Vec<StaleInventory>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(
+				"SELECT robot_id, name, operational_note, inventory_refreshed_at FROM robots
+				 WHERE deleted_at IS NULL AND inventory_alerted_at IS NULL
+				   AND (inventory_refreshed_at IS NULL
+				        OR inventory_refreshed_at < strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?1))
+				 ORDER BY name COLLATE NOCASE"
+			)?;
+			let stale = stmt.query_map([format!("-{} days", max_age_days)], |row| {
+				Ok(StaleInventory {
+					robot_id: row.get(0)?,
+					robot_name: row.get(1)?,
+					operational_note: row.get(2)?,
+					refreshed_at: row.get::<_, Option<String>>(3)?.as_deref().and_then(time::parse_utc),
+				})
+			})?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to load stale inventories")?;
+			Ok(stale)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Holds back further stale inventory alerts for the robots until their inventory
+	/// is refreshed
+	pub async fn mark_inventories_alerted(&self, robot_ids: Vec<i64>) -> Result<()> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			for robot_id in &robot_ids {
+				tx.execute(
+					"UPDATE robots SET inventory_alerted_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE robot_id = ?1",
+					[robot_id],
+				)?;
+			}
+			tx.commit().context("Failed to mark stale inventories as alerted")
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// When the last alert email went out, to space out digests
+	pub async fn last_sent_at(&self) -> Result<Option<DateTime<Utc>>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let sent_at: Option<String> = conn.query_row(
+				"SELECT MAX(sent_at) FROM alert_outbox",
+				[],
+				|row| row.get(0),
+			)?;
+			Ok(sent_at.as_deref().and_then(time::parse_utc))
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use crate::models::robot::InventorySource;
+	use crate::repositories::robot_repo::mark_inventory_refreshed;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_outbox_triggers() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let repo = AlertRepository::new(pool.clone());
+		let conn = pool.get()?;
+		conn.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity) VALUES (1, 'CVE-2024-0001', 'Medium');
+			 INSERT INTO robots (robot_id, name, operational_note) VALUES (1, 'arm-01', NULL), (2, 'arm-02', 'Cell 4 line');
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ros', 'OSRF');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, 'humble');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 1);
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES (1, 1, 'humble');"
+		)?;
+		// Nothing is queued while alerting is not configured
+		assert!(repo.get_pending().await?.is_empty());
+
+		conn.execute_batch(
+			"INSERT INTO settings (key, value) VALUES ('alerts', '{}');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (2, 1);
+			 UPDATE vulnerabilities SET severity = 'High' WHERE vulnerability_id = 1;
+			 UPDATE vulnerabilities SET description = 'Unrelated edit' WHERE vulnerability_id = 1;"
+		)?;
+		let pending = repo.get_pending().await?;
+		assert_eq!(pending.len(), 2);
+		assert_eq!(pending[0].summary(), "arm-02 (note: Cell 4 line) is exposed to CVE-2024-0001 (High)");
+		assert_eq!(pending[1].summary(), "Severity of CVE-2024-0001 changed: Medium -> High");
+
+		repo.mark_sent(pending.iter().map(|a| a.alert_id).collect()).await?;
+		assert!(repo.get_pending().await?.is_empty());
+		assert!(repo.last_sent_at().await?.is_some());
+
+		// The same exposure is only reported once
+		conn.execute_batch(
+			"DELETE FROM robot_software WHERE robot_id = 2;
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (2, 1);"
+		)?;
+		assert!(repo.get_pending().await?.is_empty());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_stale_inventories() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let repo = AlertRepository::new(pool.clone());
+		pool.get()?.execute_batch(
+			"INSERT INTO robots (robot_id, name, inventory_refreshed_at) VALUES
+				(1, 'arm-01', strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-10 days')),
+				(2, 'arm-02', '2020-01-01T00:00:00Z'),
+				(3, 'agv-01', NULL);"
+		)?;
+		let names = |stale: Vec<StaleInventory>| stale.into_iter().map(|s| s.robot_name).collect::<Vec<_>>();
+		assert_eq!(names(repo.get_stale_inventories(30).await?), ["agv-01", "arm-02"]);
+		assert_eq!(names(repo.get_stale_inventories(5).await?), ["agv-01", "arm-01", "arm-02"]);
+
+		repo.mark_inventories_alerted(vec![2, 3]).await?;
+		assert!(repo.get_stale_inventories(30).await?.is_empty());
+
+		// A refresh makes the robot alertable again once it goes stale
+		let conn = pool.get()?;
+		mark_inventory_refreshed(&conn, 2, InventorySource::Import)?;
+		assert!(repo.get_stale_inventories(30).await?.is_empty());
+		conn.execute("UPDATE robots SET inventory_refreshed_at = '2020-01-01T00:00:00Z' WHERE robot_id = 2", [])?;
+		assert_eq!(names(repo.get_stale_inventories(30).await?), ["arm-02"]);
+		Ok(())
+	}
+}
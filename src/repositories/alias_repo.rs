@@ -0,0 +1,336 @@
+// src/repositories/alias_repo.rs
+
+//! Alias resolution across feeds. A vulnerability is stored once, under its CVE ID
+//! where it has one, and the IDs other feeds use for it (GHSA, OSV or RVD IDs) are
+//! recorded as aliases. Entries that turn out to describe the same vulnerability are
+//! merged, keeping the richest description and metrics of either.
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::audit::{AuditAction, AuditEntity, FieldChange};
+use crate::models::vulnerability::{CvssVersion, NVD_SOURCE};
+use crate::repositories::{access, audit_repo};
+use crate::repositories::robot_repo::refresh_risk_scores;
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::Arc;
+use tokio::task;
+
+/// Tables whose rows belong to one vulnerability, with the column referencing it
+pub(crate) const VULNERABILITY_CHILDREN: [(&str, &str); 10] = [
+	("vulnerability_references", "vulnerability_id"),
+	("vulnerability_weaknesses", "vulnerability_id"),
+	("vulnerability_aliases", "vulnerability_id"),
+	("affected_software", "vulnerability_id"),
+	("vulnerability_status", "vulnerability_id"),
+	("enrichment_attempts", "vulnerability_id"),
+	("alert_outbox", "vulnerability_id"),
+	("field_locks", "vulnerability_id"),
+	("field_provenance", "vulnerability_id"),
+	("notes", "entity_id"),
+];
+
+/// The vulnerability stored under `id` or known by it as an alias
+pub(crate) fn resolve(conn: &Connection, id: &str) -> rusqlite::Result<Option<i64>> {
+	conn.query_row(
+		"SELECT vulnerability_id FROM vulnerabilities WHERE cve_id = ?1
+		 UNION ALL SELECT vulnerability_id FROM vulnerability_aliases WHERE alias = ?1
+		 LIMIT 1",
+		[id],
+		|row| row.get(0),
+	)
+		.optional()
+}
+
+/// ID under which the vulnerability known as `id` is stored, `id` itself when unknown
+pub(crate) fn canonical_id(conn: &Connection, id: &str) -> rusqlite::Result<String> {
+	let stored = conn.query_row(
+		"SELECT v.cve_id FROM vulnerability_aliases a
+		 JOIN vulnerabilities v ON v.vulnerability_id = a.vulnerability_id
+		 WHERE a.alias = ?1",
+		[id],
+		|row| row.get(0),
+	)
+		.optional()?;
+	Ok(stored.unwrap_or_else(|| id.to_string()))
+}
+
+/// Records `alias`, used by `source`, as another ID of the vulnerability stored under
+/// `id`. An entry stored under the alias, or one the alias already named, is merged
+/// into it. Returns whether an entry was merged.
+pub(crate) fn link_alias(conn: &Connection, id: &str, alias: &str, source: &str) -> Result<bool> {
+	let Some(kept) = resolve(conn, id)? else {
+		bail!("No vulnerability {}", id);
+	};
+	let duplicate = resolve(conn, alias)?.filter(|duplicate| *duplicate != kept);
+	if let Some(duplicate) = duplicate {
+		merge_into(conn, kept, duplicate)?;
+	}
+	let stored_under_alias: bool = conn.query_row(
+		"SELECT cve_id = ?2 FROM vulnerabilities WHERE vulnerability_id = ?1",
+		params![kept, alias],
+		|row| row.get(0),
+	)?;
+	if !stored_under_alias {
+		conn.execute(
+			"INSERT INTO vulnerability_aliases (alias, vulnerability_id, source) VALUES (?1, ?2, ?3)
+			 ON CONFLICT (alias) DO UPDATE SET vulnerability_id = excluded.vulnerability_id",
+			params![alias, kept, source],
+		)?;
+	}
+	Ok(duplicate.is_some())
+}
+
+/// The fields merged field by field
+#[derive(Debug, Clone, PartialEq)]
+struct MergedFields {
+	description: Option<String>,
+	severity: String,
+	impact: Option<String>,
+	mitigation: Option<String>,
+	published_date: Option<String>,
+	cvss_score: Option<f64>,
+	cvss_version: Option<String>,
+	kev_date_added: Option<String>,
+	epss_score: Option<f64>,
+}
+
+const MERGED_COLUMNS: &str =
+	"description, severity, impact, mitigation, published_date, cvss_score, cvss_version, kev_date_added, epss_score";
+
+fn merged_fields(conn: &Connection, vulnerability_id: i64) -> rusqlite::Result<MergedFields> {
+	conn.query_row(
+		&format!("SELECT {} FROM vulnerabilities WHERE vulnerability_id = ?1", MERGED_COLUMNS),
+		[vulnerability_id],
+		|row| Ok(MergedFields {
+			description: row.get(0)?,
+			severity: row.get(1)?,
+			impact: row.get(2)?,
+			mitigation: row.get(3)?,
+			published_date: row.get(4)?,
+			cvss_score: row.get(5)?,
+			cvss_version: row.get(6)?,
+			kev_date_added: row.get(7)?,
+			epss_score: row.get(8)?,
+		}),
+	)
+}
+
+/// The richer of two texts: the longer one, `kept` on ties
+fn richer(kept: Option<String>, other: Option<String>) -> Option<String> {
+	let length = |text: &Option<String>| text.as_deref().map_or(0, |t| t.trim().len());
+	if length(&other) > length(&kept) { other } else { kept }
+}
+
+/// Combines two entries of one vulnerability: the longer texts, the metrics of the
+/// newer CVSS version (a score beats none), the earliest publication date and any
+/// exploitation data either has
+fn merge_fields(kept: MergedFields, other: MergedFields) -> MergedFields {
+	let unknown = |severity: &str| severity.trim().is_empty() || severity.eq_ignore_ascii_case("unknown");
+	let metrics_rank = |fields: &MergedFields| {
+		(fields.cvss_score.is_some(), fields.cvss_version.as_deref().and_then(CvssVersion::from_db))
+	};
+	let (metrics, fallback) = if metrics_rank(&other) > metrics_rank(&kept) { (&other, &kept) } else { (&kept, &other) };
+	let severity = if unknown(&metrics.severity) { &fallback.severity } else { &metrics.severity };
+
+	MergedFields {
+		severity: severity.clone(),
+		cvss_score: metrics.cvss_score,
+		cvss_version: metrics.cvss_version.clone(),
+		published_date: match (&kept.published_date, &other.published_date) {
+			(Some(a), Some(b)) => Some(a.min(b).clone()),
+			(a, b) => a.clone().or_else(|| b.clone()),
+		},
+		kev_date_added: kept.kev_date_added.clone().or_else(|| other.kev_date_added.clone()),
+		epss_score: kept.epss_score.or(other.epss_score),
+		description: richer(kept.description, other.description),
+		impact: richer(kept.impact, other.impact),
+		mitigation: richer(kept.mitigation, other.mitigation),
+	}
+}
+
+/// Collapses `duplicate` into `kept`: references, correlations, aliases, triage state,
+/// field locks and notes move over (those of `kept` win on conflicts), the fields are
+/// merged except those locked by an edit, and the duplicate's ID becomes an alias
+pub(crate) fn merge_into(conn: &Connection, kept: i64, duplicate: i64) -> Result<()> {
+	let merged = merge_fields(merged_fields(conn, kept)?, merged_fields(conn, duplicate)?);
+	let (duplicate_id, duplicate_source): (String, Option<String>) = conn.query_row(
+		"SELECT cve_id, source FROM vulnerabilities WHERE vulnerability_id = ?1",
+		[duplicate],
+		|row| Ok((row.get(0)?, row.get(1)?)),
+	)?;
+	for (table, column) in VULNERABILITY_CHILDREN {
+		let condition = if table == "notes" { " AND entity_type = 'vulnerability'" } else { "" };
+		conn.execute(
+			&format!("UPDATE OR IGNORE {table} SET {column} = ?2 WHERE {column} = ?1{condition}"),
+			params![duplicate, kept],
+		).with_context(|| format!("Failed to move {} of the merged vulnerability", table))?;
+	}
+
+	// After the locks moved, so their triggers keep the edited values
+	conn.execute(
+		"UPDATE vulnerabilities SET description = ?2, severity = ?3, impact = ?4, mitigation = ?5,
+			published_date = ?6, cvss_score = ?7, cvss_version = ?8, kev_date_added = ?9, epss_score = ?10
+		 WHERE vulnerability_id = ?1",
+		params![
+			kept,
+			merged.description,
+			merged.severity,
+			merged.impact,
+			merged.mitigation,
+			merged.published_date,
+			merged.cvss_score,
+			merged.cvss_version,
+			merged.kev_date_added,
+			merged.epss_score,
+		],
+	).context("Failed to merge vulnerability fields")?;
+	// Rows left behind duplicate rows of `kept`; affected_software does not cascade
+	conn.execute("DELETE FROM affected_software WHERE vulnerability_id = ?1", [duplicate])?;
+	conn.execute("DELETE FROM vulnerabilities WHERE vulnerability_id = ?1", [duplicate])?;
+	conn.execute(
+		"INSERT OR REPLACE INTO vulnerability_aliases (alias, vulnerability_id, source) VALUES (?1, ?2, ?3)",
+		params![duplicate_id, kept, duplicate_source.as_deref().unwrap_or(NVD_SOURCE)],
+	)?;
+	Ok(())
+}
+
+/// Merges every entry stored under an ID that is an alias of another entry, such as a
+/// GHSA entry imported before its CVE was assigned. Returns the number merged.
+pub(crate) fn collapse_aliases(conn: &Connection) -> Result<usize> {
+	let pairs = conn
+		.prepare(
+			"SELECT a.vulnerability_id, v.vulnerability_id FROM vulnerability_aliases a
+			 JOIN vulnerabilities v ON v.cve_id = a.alias
+			 WHERE v.vulnerability_id != a.vulnerability_id",
+		)?
+		.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+		.collect::<rusqlite::Result<Vec<(i64, i64)>>>()?;
+	for (kept, duplicate) in &pairs {
+		merge_into(conn, *kept, *duplicate)?;
+	}
+	Ok(pairs.len())
+}
+
+pub struct AliasRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl AliasRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Records `alias` as another ID of the vulnerability known as `id`, merging the
+	/// entries and rescoring the fleet if both are stored. Returns whether entries were
+	/// merged.
+	pub async fn link(&self, id: String, alias: String, source: String) -> Result<bool> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let kept = resolve(&tx, &id)?;
+			let before = kept.map(|kept| audit_repo::snapshot(&tx, AuditEntity::Vulnerability, kept)).transpose()?.flatten();
+			let merged = link_alias(&tx, &id, &alias, &source)?;
+			if let Some(kept) = kept {
+				audit_repo::record_change(&tx, AuditEntity::Vulnerability, kept, AuditAction::Update, before)?;
+			}
+			if merged {
+				refresh_risk_scores(&tx)?;
+			}
+			tx.commit()?;
+			Ok(merged)
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Merges all entries stored under an alias of another entry and rescores the fleet
+	pub async fn collapse(&self) -> Result<usize> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let merged = collapse_aliases(&tx)?;
+			if merged > 0 {
+				audit_repo::record(&tx, AuditEntity::Vulnerability, None, "Entries stored under an alias", AuditAction::Delete, &[
+					FieldChange::new("merged", None, Some(merged.to_string())),
+				])?;
+				refresh_risk_scores(&tx)?;
+			}
+			tx.commit()?;
+			Ok(merged)
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_merge_fields_prefers_richest() {
+		let fields = |description: &str, severity: &str, score: Option<f64>, version: Option<&str>, published: Option<&str>| MergedFields {
+			description: Some(description.to_string()),
+			severity: severity.to_string(),
+			impact: None,
+			mitigation: None,
+			published_date: published.map(str::to_string),
+			cvss_score: score,
+			cvss_version: version.map(str::to_string),
+			kev_date_added: None,
+			epss_score: None,
+		};
+		let nvd = fields("Short", "High", Some(7.5), Some("3.1"), Some("2024-03-02"));
+		let ghsa = fields("A much longer description", "Critical", Some(9.3), Some("4.0"), Some("2024-03-01"));
+		let merged = merge_fields(nvd.clone(), ghsa);
+		assert_eq!(merged.description.as_deref(), Some("A much longer description"));
+		assert_eq!((merged.severity.as_str(), merged.cvss_score), ("Critical", Some(9.3)));
+		assert_eq!(merged.published_date.as_deref(), Some("2024-03-01"));
+
+		// A score beats a rating without one, but an unknown severity is filled in
+		let rvd = fields("", "Unknown", None, None, None);
+		let merged = merge_fields(rvd, fields("Text", "Medium", None, None, None));
+		assert_eq!(merged.severity, "Medium");
+		assert_eq!(merge_fields(nvd, fields("", "Low", None, None, None)).cvss_score, Some(7.5));
+	}
+
+	#[test]
+	fn test_link_alias_merges_entries() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = connection::establish_pool_with_path(dir.path().join("test.db"))?;
+		let conn = pool.get()?;
+		conn.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, description, severity, source) VALUES
+				(1, 'CVE-2024-1111', 'NVD text', 'High', NULL),
+				(2, 'RVD#42', 'The longer text from the RVD ticket', 'Critical', 'Alias Robotics RVD');
+			 INSERT INTO vulnerability_status (vulnerability_id, status) VALUES (2, 'In Progress');
+			 INSERT INTO notes (entity_type, entity_id, body) VALUES ('vulnerability', 2, 'Vendor contacted');",
+		)?;
+
+		assert!(link_alias(&conn, "CVE-2024-1111", "RVD#42", "Alias Robotics RVD")?);
+		assert!(!link_alias(&conn, "RVD#42", "GHSA-abcd-1234-wxyz", "GitHub Advisory Database")?);
+
+		let (count, description, status): (i64, String, String) = conn.query_row(
+			"SELECT (SELECT COUNT(*) FROM vulnerabilities), v.description, s.status
+			 FROM vulnerabilities v JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id
+			 WHERE v.cve_id = 'CVE-2024-1111'",
+			[],
+			|row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+		)?;
+		assert_eq!((count, description.as_str(), status.as_str()), (1, "The longer text from the RVD ticket", "In Progress"));
+		assert_eq!(conn.query_row("SELECT entity_id FROM notes", [], |row| row.get::<_, i64>(0))?, 1);
+		assert_eq!(resolve(&conn, "GHSA-abcd-1234-wxyz")?, Some(1));
+		assert_eq!(canonical_id(&conn, "RVD#42")?, "CVE-2024-1111");
+		assert_eq!(canonical_id(&conn, "RVD#43")?, "RVD#43");
+
+		// An entry imported again under an alias is collapsed into the CVE
+		conn.execute("INSERT INTO vulnerabilities (cve_id, severity) VALUES ('GHSA-abcd-1234-wxyz', 'Unknown')", [])?;
+		assert_eq!(collapse_aliases(&conn)?, 1);
+		assert_eq!(conn.query_row("SELECT COUNT(*) FROM vulnerabilities", [], |row| row.get::<_, i64>(0))?, 1);
+		Ok(())
+	}
+}
@@ -0,0 +1,441 @@
+// src/repositories/audit_repo.rs
+
+//! Audit log of changes, written by the mutating repository calls in the same
+//! transaction as the change. Field diffs come from snapshots of the record taken
+//! before and after the change, so callers only say which record they touched.
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::audit::{AuditAction, AuditEntity, AuditEntry, FieldChange};
+use crate::repositories::access;
+use crate::utils::time;
+use anyhow::{bail, Context, Result};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use std::sync::Arc;
+use tokio::task;
+
+/// Audited fields of a record with its label, as of one point in time
+pub struct Snapshot {
+	label: String,
+	fields: Vec<(&'static str, Option<String>)>,
+}
+
+/// Audited field names with the SQL expression reading each
+type SnapshotFields = &'static [(&'static str, &'static str)];
+
+/// Tables joined to read a record of `entity`, bound to its ID, with the expression
+/// for its label and the audited fields. Computed columns such as risk scores are
+/// left out, as they change on their own.
+fn snapshot_source(entity: AuditEntity) -> Option<(&'static str, &'static str, SnapshotFields)> {
+	match entity {
+		AuditEntity::Vulnerability => Some((
+			"vulnerabilities v LEFT JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id
+			 WHERE v.vulnerability_id = ?1",
+			"v.cve_id",
+			&[
+				("cve_id", "v.cve_id"),
+				("description", "v.description"),
+				("severity", "v.severity"),
+				("impact", "v.impact"),
+				("mitigation", "v.mitigation"),
+				("published_date", "v.published_date"),
+				("cvss_score", "v.cvss_score"),
+				("cvss_version", "v.cvss_version"),
+				("status", "COALESCE(s.status, 'Open')"),
+				("assigned_to", "s.assigned_to"),
+				("justification", "s.justification"),
+				("approved_by", "s.approved_by"),
+				("expires_on", "s.expires_on"),
+				("ticket", "(SELECT ticket_key FROM vulnerability_tickets WHERE vulnerability_id = v.vulnerability_id)"),
+				("locked_fields", "(SELECT group_concat(field, ', ') FROM (
+					SELECT field FROM field_locks WHERE vulnerability_id = v.vulnerability_id ORDER BY field))"),
+				("aliases", "(SELECT group_concat(alias, ', ') FROM (
+					SELECT alias FROM vulnerability_aliases WHERE vulnerability_id = v.vulnerability_id ORDER BY alias))"),
+				("deleted_at", "v.deleted_at"),
+			],
+		)),
+		AuditEntity::Robot => Some((
+			"robots r WHERE r.robot_id = ?1",
+			"r.name",
+			&[
+				("name", "r.name"),
+				("manufacturer", "r.manufacturer"),
+				("model", "r.model"),
+				("firmware_version", "r.firmware_version"),
+				("os", "r.os"),
+				("ros_distro", "r.ros_distro"),
+				("specifications", "r.specifications"),
+				("operational_note", "r.operational_note"),
+				("criticality", "r.criticality"),
+				("software", "(SELECT group_concat(software, ', ') FROM (
+					SELECT sp.product_name || ' ' || sv.version_number AS software
+					FROM robot_software rs
+					JOIN software_versions sv ON sv.version_id = rs.version_id
+					JOIN software_products sp ON sp.product_id = sv.product_id
+					WHERE rs.robot_id = r.robot_id ORDER BY software))"),
+				("deleted_at", "r.deleted_at"),
+			],
+		)),
+		AuditEntity::SoftwareProduct => Some((
+			"software_products sp WHERE sp.product_id = ?1",
+			"sp.product_name || ' (' || sp.vendor || ')'",
+			&[
+				("product_name", "sp.product_name"),
+				("vendor", "sp.vendor"),
+				("description", "sp.description"),
+				("license", "sp.license"),
+			],
+		)),
+		AuditEntity::SoftwareVersion => Some((
+			"software_versions sv JOIN software_products sp ON sp.product_id = sv.product_id WHERE sv.version_id = ?1",
+			"sp.product_name || ' ' || sv.version_number",
+			&[
+				("version_number", "sv.version_number"),
+				("release_date", "sv.release_date"),
+				("eol_date", "sv.eol_date"),
+				("notes", "sv.notes"),
+			],
+		)),
+		AuditEntity::Note => Some((
+			"notes n WHERE n.note_id = ?1",
+			"'Note on ' || COALESCE(
+				CASE n.entity_type
+					WHEN 'vulnerability' THEN (SELECT cve_id FROM vulnerabilities WHERE vulnerability_id = n.entity_id)
+					WHEN 'robot' THEN (SELECT name FROM robots WHERE robot_id = n.entity_id)
+				END,
+				n.entity_type || ' ' || n.entity_id)",
+			&[("body", "n.body")],
+		)),
+		AuditEntity::Setting | AuditEntity::Import => None,
+	}
+}
+
+/// Reads the audited fields of a record; `None` when it does not exist or is not a
+/// kind of record with snapshots
+pub fn snapshot(conn: &Connection, entity: AuditEntity, id: i64) -> Result<Option<Snapshot>> {
+	let Some((source, label, fields)) = snapshot_source(entity) else {
+		return Ok(None);
+	};
+	let columns = fields
+		.iter()
+		.map(|(_, expression)| format!("CAST({} AS TEXT)", expression))
+		.collect::<Vec<_>>()
+		.join(", ");
+	conn.query_row(&format!("SELECT {}, {} FROM {}", label, columns, source), [id], |row| {
+		Ok(Snapshot {
+			label: row.get(0)?,
+			fields: fields
+				.iter()
+				.enumerate()
+				.map(|(idx, (field, _))| Ok((*field, row.get(idx + 1)?)))
+				.collect::<rusqlite::Result<_>>()?,
+		})
+	})
+		.optional()
+		.with_context(|| format!("Failed to read {} {} for the audit log", entity, id))
+}
+
+/// Records a change to one record, diffing its fields against the snapshot taken
+/// before the change. Updates that changed no audited field are not recorded.
+pub fn record_change(
+	conn: &Connection,
+	entity: AuditEntity,
+	id: i64,
+	action: AuditAction,
+	before: Option<Snapshot>,
+) -> Result<()> {
+	let after = snapshot(conn, entity, id)?;
+	let fields = |snapshot: &Option<Snapshot>| snapshot.as_ref().map(|s| s.fields.clone()).unwrap_or_default();
+	let changes = FieldChange::diff(&fields(&before), &fields(&after));
+	if changes.is_empty() && action == AuditAction::Update {
+		return Ok(());
+	}
+	let label = after.or(before).map(|snapshot| snapshot.label).unwrap_or_else(|| id.to_string());
+	record(conn, entity, Some(id), &label, action, &changes)
+}
+
+/// ID of the latest audit entry, to pass to `close_batch` once a bulk operation
+/// recorded its changes
+pub fn open_batch(conn: &Connection) -> Result<i64> {
+	conn.query_row("SELECT COALESCE(MAX(audit_id), 0) FROM audit_log", [], |row| row.get(0))
+		.context("Failed to read the audit log")
+}
+
+/// Groups the entries recorded since `open_batch` returned `opened` into one batch,
+/// identified by the ID of its first entry, so they can be reverted together
+pub fn close_batch(conn: &Connection, opened: i64) -> Result<()> {
+	conn.execute(
+		"UPDATE audit_log SET batch_id = (SELECT MIN(audit_id) FROM audit_log WHERE audit_id > ?1) WHERE audit_id > ?1",
+		[opened],
+	).context("Failed to group the audit entries into a batch")?;
+	Ok(())
+}
+
+/// Table, key column and column a field is written back to when reverting a batch;
+/// `None` for fields no bulk operation changes
+fn revertible_column(entity: AuditEntity, field: &str) -> Option<(&'static str, &'static str, &'static str)> {
+	match (entity, field) {
+		(AuditEntity::SoftwareVersion, "release_date") => Some(("software_versions", "version_id", "release_date")),
+		(AuditEntity::SoftwareVersion, "eol_date") => Some(("software_versions", "version_id", "eol_date")),
+		(AuditEntity::SoftwareVersion, "notes") => Some(("software_versions", "version_id", "notes")),
+		_ => None,
+	}
+}
+
+/// Sets the fields changed by a batch back to the values recorded before it, newest
+/// entry first, and records that as a batch of its own. Nothing is changed when a
+/// record was edited again since, as reverting would lose that edit.
+fn revert_batch(conn: &mut Connection, batch_id: i64) -> Result<usize> {
+	let tx = conn.transaction()?;
+	let entries = tx
+		.prepare(
+			"SELECT audit_id, recorded_at, actor, entity, entity_id, label, action, changes, batch_id
+			 FROM audit_log WHERE batch_id = ?1 ORDER BY audit_id DESC",
+		)?
+		.query_map([batch_id], audit_entry_from_row)?
+		.collect::<rusqlite::Result<Vec<_>>>()
+		.context("Failed to read the audit batch")?;
+	if entries.is_empty() {
+		bail!("Audit batch {} not found", batch_id);
+	}
+
+	let opened = open_batch(&tx)?;
+	for entry in &entries {
+		let (Some(id), AuditAction::Update) = (entry.entity_id, entry.action) else {
+			bail!("{} {} was not an update and cannot be reverted", entry.entity, entry.label);
+		};
+		let before = snapshot(&tx, entry.entity, id)?
+			.with_context(|| format!("{} {} no longer exists", entry.entity, entry.label))?;
+		for change in &entry.changes {
+			let (table, key, column) = revertible_column(entry.entity, &change.field)
+				.with_context(|| format!("The {} of {} cannot be reverted", change.field, entry.label))?;
+			let current = before.fields.iter().find(|(field, _)| *field == change.field).and_then(|(_, value)| value.clone());
+			if current != change.new {
+				bail!("{} was changed again after the batch, so the batch is left as it is", entry.label);
+			}
+			tx.execute(&format!("UPDATE {} SET {} = ?1 WHERE {} = ?2", table, column, key), params![change.old, id])?;
+		}
+		record_change(&tx, entry.entity, id, AuditAction::Update, Some(before))?;
+	}
+	close_batch(&tx, opened)?;
+	tx.commit()?;
+	Ok(entries.len())
+}
+
+/// Records a change by the current user
+pub(crate) fn record(
+	conn: &Connection,
+	entity: AuditEntity,
+	entity_id: Option<i64>,
+	label: &str,
+	action: AuditAction,
+	changes: &[FieldChange],
+) -> Result<()> {
+	let changes = serde_json::to_string(changes).context("Failed to serialize audited changes")?;
+	conn.execute(
+		"INSERT INTO audit_log (actor, entity, entity_id, label, action, changes) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+		params![access::current_user(), entity.as_str(), entity_id, label, action.as_str(), changes],
+	).context("Failed to write the audit log")?;
+	Ok(())
+}
+
+/// Records an import run, summarized in one entry
+pub(crate) fn record_import(conn: &Connection, source: &str, summary: &str) -> Result<()> {
+	record(
+		conn,
+		AuditEntity::Import,
+		None,
+		source,
+		AuditAction::Import,
+		&[FieldChange::new("summary", None, Some(summary.to_string()))],
+	)
+}
+
+/// Which audit entries to list
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+	pub entity: Option<AuditEntity>,
+	/// Matched against labels, actors and changed values; empty matches all
+	pub search: String,
+}
+
+fn audit_entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<AuditEntry> {
+	let recorded_at: String = row.get(1)?;
+	let entity: String = row.get(3)?;
+	let action: String = row.get(6)?;
+	let changes: String = row.get(7)?;
+	let invalid = |column: usize, what: &str, value: &str| {
+		rusqlite::Error::FromSqlConversionFailure(
+			column,
+			rusqlite::types::Type::Text,
+			format!("Invalid audit {}: {}", what, value).into(),
+		)
+	};
+	Ok(AuditEntry {
+		audit_id: row.get(0)?,
+		recorded_at: time::parse_utc(&recorded_at).ok_or_else(|| invalid(1, "time", &recorded_at))?,
+		actor: row.get(2)?,
+		entity: AuditEntity::from_db(&entity).ok_or_else(|| invalid(3, "entity", &entity))?,
+		entity_id: row.get(4)?,
+		label: row.get(5)?,
+		action: AuditAction::from_db(&action).ok_or_else(|| invalid(6, "action", &action))?,
+		changes: serde_json::from_str(&changes).map_err(|e| invalid(7, "changes", &e.to_string()))?,
+		batch_id: row.get(8)?,
+	})
+}
+
+pub struct AuditRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl AuditRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Entries matching `filter`, newest first; all of them when `limit` is `None`
+	pub async fn get_entries(&self, filter: AuditFilter, limit: Option<usize>) -> Result<Vec<AuditEntry>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut conditions = Vec::new();
+			let mut values = Vec::new();
+			if let Some(entity) = filter.entity {
+				conditions.push("entity = ?");
+				values.push(Value::Text(entity.as_str().to_string()));
+			}
+			let search = filter.search.trim();
+			if !search.is_empty() {
+				conditions.push("(label LIKE ? OR actor LIKE ? OR changes LIKE ?)");
+				values.extend(std::iter::repeat_n(Value::Text(format!("%{}%", search)), 3));
+			}
+			let where_sql = if conditions.is_empty() {
+				String::new()
+			} else {
+				format!("WHERE {}", conditions.join(" AND "))
+			};
+			values.push(Value::Integer(limit.map_or(-1, |limit| limit as i64)));
+
+			let mut stmt = conn.prepare(&format!(
+				"SELECT audit_id, recorded_at, actor, entity, entity_id, label, action, changes, batch_id
+				 FROM audit_log {} ORDER BY audit_id DESC LIMIT ?",
+				where_sql
+			))?;
+			let entries = stmt
+				.query_map(params_from_iter(values.iter()), audit_entry_from_row)?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to read the audit log")?;
+			Ok(entries)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Reverts the bulk operation recorded as `batch_id`, returning how many records
+	/// were set back
+	pub async fn revert_batch(&self, batch_id: i64) -> Result<usize> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| revert_batch(conn, batch_id)))
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use crate::models::vulnerability::{LockedField, RiskAcceptance, TriageStatus, Vulnerability};
+	use crate::repositories::note_repo::NoteRepository;
+	use crate::repositories::trash_repo::TrashRepository;
+	use crate::repositories::vulnerability_repo::VulnerabilityRepository;
+	use crate::models::note::{Note, NoteEntity};
+	use crate::models::software::VersionMetadataChange;
+	use crate::models::trash::DeletedKind;
+	use crate::repositories::software_repo::SoftwareRepository;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_mutations_are_audited() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let vulnerabilities = VulnerabilityRepository::new(pool.clone());
+		let id = vulnerabilities.add_vulnerability(Vulnerability::new("CVE-2024-0001".to_string(), "Low".to_string())).await?;
+		vulnerabilities.edit_fields(id, vec![(LockedField::Severity, Some("High".to_string()))], "ana".to_string()).await?;
+		vulnerabilities.update_triage(id, TriageStatus::InProgress, Some("ana".to_string()), RiskAcceptance::default()).await?;
+		// Setting the same status again changes nothing
+		vulnerabilities.update_triage(id, TriageStatus::InProgress, Some("ana".to_string()), RiskAcceptance::default()).await?;
+		NoteRepository::new(pool.clone()).add_note(Note::new(NoteEntity::Vulnerability, id, "Vendor contacted".to_string())).await?;
+		TrashRepository::new(pool.clone()).delete(DeletedKind::Vulnerability, id).await?;
+		TrashRepository::new(pool.clone()).purge(DeletedKind::Vulnerability, id).await?;
+
+		let repo = AuditRepository::new(pool);
+		let entries = repo.get_entries(AuditFilter::default(), None).await?;
+		let actions: Vec<(AuditEntity, AuditAction, &str)> = entries
+			.iter()
+			.rev()
+			.map(|entry| (entry.entity, entry.action, entry.label.as_str()))
+			.collect();
+		assert_eq!(actions, [
+			(AuditEntity::Vulnerability, AuditAction::Insert, "CVE-2024-0001"),
+			(AuditEntity::Vulnerability, AuditAction::Update, "CVE-2024-0001"),
+			(AuditEntity::Vulnerability, AuditAction::Update, "CVE-2024-0001"),
+			(AuditEntity::Note, AuditAction::Insert, "Note on CVE-2024-0001"),
+			(AuditEntity::Vulnerability, AuditAction::Delete, "CVE-2024-0001"),
+			(AuditEntity::Vulnerability, AuditAction::Purge, "CVE-2024-0001"),
+		]);
+		assert_eq!(entries[4].summary(), "severity: Low → High; locked_fields: severity");
+		assert_eq!(entries[3].summary(), "status: Open → In Progress; assigned_to: ana");
+		assert!(entries.iter().all(|entry| entry.actor == access::current_user()));
+
+		let filter = AuditFilter { entity: Some(AuditEntity::Note), search: String::new() };
+		assert_eq!(repo.get_entries(filter, None).await?.len(), 1);
+		let filter = AuditFilter { entity: None, search: "vendor contacted".to_string() };
+		assert_eq!(repo.get_entries(filter, Some(10)).await?.len(), 1);
+		assert_eq!(repo.get_entries(AuditFilter::default(), Some(2)).await?.len(), 2);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_revert_batch() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ros', 'OSRF');
+			 INSERT INTO software_versions (version_id, product_id, version_number, notes) VALUES
+				(1, 1, 'humble', NULL), (2, 1, 'iron', 'Short-term');",
+		)?;
+		let software = SoftwareRepository::new(pool.clone());
+		let notes = |notes: &str| VersionMetadataChange { notes: Some(Some(notes.to_string())), ..Default::default() };
+		let current_notes = || -> Result<Vec<Option<String>>> {
+			Ok(pool.get()?
+				.prepare("SELECT notes FROM software_versions ORDER BY version_id")?
+				.query_map([], |row| row.get(0))?
+				.collect::<rusqlite::Result<_>>()?)
+		};
+
+		software.update_version_metadata(vec![1, 2], notes("LTS")).await?;
+		let repo = AuditRepository::new(pool.clone());
+		let entries = repo.get_entries(AuditFilter::default(), None).await?;
+		let batch_id = entries[0].batch_id.unwrap();
+		assert!(entries.iter().all(|entry| entry.batch_id == Some(batch_id)));
+
+		assert_eq!(repo.revert_batch(batch_id).await?, 2);
+		assert_eq!(current_notes()?, [None, Some("Short-term".to_string())]);
+		// The revert is a batch of its own
+		let entries = repo.get_entries(AuditFilter::default(), None).await?;
+		assert_eq!(entries.len(), 4);
+		assert_ne!(entries[0].batch_id, Some(batch_id));
+		assert_eq!(entries[0].batch_id, entries[1].batch_id);
+
+		// A later edit of one of the versions keeps the whole batch from being reverted
+		software.update_version_metadata(vec![1, 2], notes("LTS")).await?;
+		let batch_id = repo.get_entries(AuditFilter::default(), Some(1)).await?[0].batch_id.unwrap();
+		software.update_version_metadata(vec![2], notes("EOL soon")).await?;
+		assert_eq!(repo.get_entries(AuditFilter::default(), Some(1)).await?[0].batch_id, None);
+		assert!(repo.revert_batch(batch_id).await.is_err());
+		assert_eq!(current_notes()?, [Some("LTS".to_string()), Some("EOL soon".to_string())]);
+		Ok(())
+	}
+}
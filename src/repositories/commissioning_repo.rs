@@ -0,0 +1,227 @@
+// src/repositories/commissioning_repo.rs
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::audit::{AuditAction, AuditEntity, FieldChange};
+use crate::models::commissioning::{ChecklistEntry, ChecklistItem};
+use crate::repositories::{access, audit_repo};
+use crate::repositories::robot_repo::refresh_risk_scores;
+use crate::utils::time;
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, OptionalExtension};
+use std::sync::Arc;
+use tokio::task;
+
+/// Label of the checklist's entries in the audit log
+const CHECKLIST_LABEL: &str = "Commissioning checklist";
+
+fn status(done: bool) -> Option<String> {
+	Some(if done { "done" } else { "open" }.to_string())
+}
+
+pub struct CommissioningRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl CommissioningRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// The configured checklist items in the order they were added
+	pub async fn get_items(&self) -> Result<Vec<ChecklistItem>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let items = conn
+				.prepare("SELECT item_id, label FROM commissioning_items ORDER BY position, item_id")?
+				.query_map([], |row| Ok(ChecklistItem { item_id: row.get(0)?, label: row.get(1)? }))?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to read the commissioning checklist")?;
+			Ok(items)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Adds an item to the end of the checklist; it starts open on every robot
+	pub async fn add_item(&self, label: &str) -> Result<()> {
+		access::require_write_access()?;
+		let label = label.trim().to_string();
+		if label.is_empty() {
+			bail!("A checklist item needs a label");
+		}
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let added = tx.execute(
+				"INSERT INTO commissioning_items (label, position)
+				 SELECT ?1, COALESCE(MAX(position), 0) + 1 FROM commissioning_items WHERE true
+				 ON CONFLICT(label) DO NOTHING",
+				[&label],
+			)?;
+			if added == 0 {
+				bail!("{} is already on the commissioning checklist", label);
+			}
+			audit_repo::record(
+				&tx,
+				AuditEntity::Setting,
+				None,
+				CHECKLIST_LABEL,
+				AuditAction::Insert,
+				&[FieldChange::new("item", None, Some(label.clone()))],
+			)?;
+			refresh_risk_scores(&tx)?;
+			tx.commit()?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Removes an item, given by label, and its completion on every robot. Returns false
+	/// when there is no such item.
+	pub async fn remove_item(&self, label: &str) -> Result<bool> {
+		access::require_write_access()?;
+		let label = label.trim().to_string();
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let removed: Option<String> = tx
+				.query_row("DELETE FROM commissioning_items WHERE label = ?1 RETURNING label", [&label], |row| row.get(0))
+				.optional()?;
+			let Some(removed) = removed else {
+				return Ok(false);
+			};
+			audit_repo::record(
+				&tx,
+				AuditEntity::Setting,
+				None,
+				CHECKLIST_LABEL,
+				AuditAction::Delete,
+				&[FieldChange::new("item", Some(removed), None)],
+			)?;
+			refresh_risk_scores(&tx)?;
+			tx.commit()?;
+			Ok(true)
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Every checklist item with whether the robot has done it
+	pub async fn get_checklist(&self, robot_id: i64) -> Result<Vec<ChecklistEntry>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let entries = conn
+				.prepare(
+					"SELECT i.item_id, i.label, rc.completed_by, rc.completed_at
+					 FROM commissioning_items i
+					 LEFT JOIN robot_commissioning rc ON rc.item_id = i.item_id AND rc.robot_id = ?1
+					 ORDER BY i.position, i.item_id",
+				)?
+				.query_map([robot_id], |row| {
+					let completed_by: Option<String> = row.get(2)?;
+					let completed_at: Option<String> = row.get(3)?;
+					Ok(ChecklistEntry {
+						item: ChecklistItem { item_id: row.get(0)?, label: row.get(1)? },
+						completed: completed_by.zip(completed_at.as_deref().and_then(time::parse_utc)),
+					})
+				})?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to read the robot's commissioning checklist")?;
+			Ok(entries)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Ticks an item off for a robot, or opens it again, and rescores the fleet
+	pub async fn set_done(&self, robot_id: i64, item_id: i64, done: bool) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let (robot, item): (String, String) = tx
+				.query_row(
+					"SELECT r.name, i.label FROM robots r, commissioning_items i
+					 WHERE r.robot_id = ?1 AND r.deleted_at IS NULL AND i.item_id = ?2",
+					params![robot_id, item_id],
+					|row| Ok((row.get(0)?, row.get(1)?)),
+				)
+				.optional()?
+				.context("Robot or checklist item not found")?;
+			let changed = if done {
+				tx.execute(
+					"INSERT INTO robot_commissioning (robot_id, item_id, completed_by) VALUES (?1, ?2, ?3)
+					 ON CONFLICT DO NOTHING",
+					params![robot_id, item_id, access::current_user()],
+				)?
+			} else {
+				tx.execute(
+					"DELETE FROM robot_commissioning WHERE robot_id = ?1 AND item_id = ?2",
+					params![robot_id, item_id],
+				)?
+			};
+			if changed > 0 {
+				audit_repo::record(
+					&tx,
+					AuditEntity::Robot,
+					Some(robot_id),
+					&robot,
+					AuditAction::Update,
+					&[FieldChange::new(&format!("checklist: {}", item), status(!done), status(done))],
+				)?;
+				refresh_risk_scores(&tx)?;
+			}
+			tx.commit()?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::repositories::robot_repo::RobotRepository;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_checklist() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute("INSERT INTO robots (robot_id, name) VALUES (1, 'arm-01')", [])?;
+		let repo = CommissioningRepository::new(pool.clone());
+		let score = || async {
+			let robots = RobotRepository::new(pool.clone()).get_all_robots().await.unwrap();
+			robots[0].risk_score.unwrap_or_default()
+		};
+
+		repo.add_item("Default passwords changed").await?;
+		repo.add_item("Network segmented").await?;
+		assert!(repo.add_item(" network SEGMENTED ").await.is_err());
+		assert_eq!(score().await, 9.8);
+
+		let checklist = repo.get_checklist(1).await?;
+		assert_eq!(
+			checklist.iter().map(|entry| entry.item.label.as_str()).collect::<Vec<_>>(),
+			["Default passwords changed", "Network segmented"]
+		);
+		assert!(!checklist[0].is_done());
+
+		repo.set_done(1, checklist[0].item.item_id, true).await?;
+		let checklist = repo.get_checklist(1).await?;
+		assert!(checklist[0].is_done() && !checklist[1].is_done());
+		assert_eq!(score().await, 5.0);
+
+		assert!(repo.remove_item("network segmented").await?);
+		assert!(!repo.remove_item("network segmented").await?);
+		assert_eq!(score().await, 0.0);
+
+		repo.set_done(1, checklist[0].item.item_id, false).await?;
+		assert_eq!(score().await, 5.0);
+		Ok(())
+	}
+}
@@ -0,0 +1,239 @@
+// src/repositories/compliance_repo.rs
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::audit::{AuditAction, AuditEntity, FieldChange};
+use crate::models::compliance::{ComplianceControl, ControlCoverage};
+use crate::repositories::{access, audit_repo};
+use crate::repositories::vulnerability_repo::{unresolved_status_sql, STATUS_JOIN};
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, OptionalExtension, Row};
+use std::sync::Arc;
+use tokio::task;
+
+/// Label of the control catalog's entries in the audit log
+const CONTROLS_LABEL: &str = "Compliance controls";
+
+fn control_from_row(row: &Row) -> rusqlite::Result<ComplianceControl> {
+	Ok(ComplianceControl {
+		control_id: row.get(0)?,
+		standard: row.get(1)?,
+		code: row.get(2)?,
+		title: row.get(3)?,
+	})
+}
+
+fn sorted(mut controls: Vec<ComplianceControl>) -> Vec<ComplianceControl> {
+	controls.sort_by_key(ComplianceControl::sort_key);
+	controls
+}
+
+pub struct ComplianceRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl ComplianceRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Every known control, by standard and code
+	pub async fn get_controls(&self) -> Result<Vec<ComplianceControl>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let controls = conn
+				.prepare("SELECT control_id, standard, code, title FROM compliance_controls")?
+				.query_map([], control_from_row)?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to read compliance controls")?;
+			Ok(sorted(controls))
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Adds a control to the catalog, e.g. a requirement of a standard not seeded
+	pub async fn add_control(&self, standard: &str, code: &str, title: &str) -> Result<()> {
+		access::require_write_access()?;
+		let (standard, code, title) = (standard.trim().to_string(), code.trim().to_string(), title.trim().to_string());
+		if standard.is_empty() || code.is_empty() || title.is_empty() {
+			bail!("A compliance control needs a standard, a code and a title");
+		}
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let added = tx.execute(
+				"INSERT INTO compliance_controls (standard, code, title) VALUES (?1, ?2, ?3)",
+				params![standard, code, title],
+			)?;
+			if added == 0 {
+				bail!("{} {} is already a compliance control", standard, code);
+			}
+			audit_repo::record(
+				&tx,
+				AuditEntity::Setting,
+				None,
+				CONTROLS_LABEL,
+				AuditAction::Insert,
+				&[FieldChange::new("control", None, Some(format!("{} {} {}", standard, code, title)))],
+			)?;
+			tx.commit()?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Controls a vulnerability is linked to
+	pub async fn get_linked_controls(&self, vulnerability_id: i64) -> Result<Vec<ComplianceControl>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let controls = conn
+				.prepare(
+					"SELECT c.control_id, c.standard, c.code, c.title FROM compliance_controls c
+					 JOIN vulnerability_controls vc ON vc.control_id = c.control_id
+					 WHERE vc.vulnerability_id = ?1",
+				)?
+				.query_map([vulnerability_id], control_from_row)?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to read the vulnerability's compliance controls")?;
+			Ok(sorted(controls))
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Links a vulnerability to a control, or removes the link
+	pub async fn set_linked(&self, vulnerability_id: i64, control_id: i64, linked: bool) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let (cve_id, control): (String, String) = tx
+				.query_row(
+					"SELECT v.cve_id, c.standard || ' ' || c.code FROM vulnerabilities v, compliance_controls c
+					 WHERE v.vulnerability_id = ?1 AND c.control_id = ?2",
+					params![vulnerability_id, control_id],
+					|row| Ok((row.get(0)?, row.get(1)?)),
+				)
+				.optional()?
+				.context("Vulnerability or compliance control not found")?;
+			let changed = if linked {
+				tx.execute(
+					"INSERT INTO vulnerability_controls (vulnerability_id, control_id, linked_by) VALUES (?1, ?2, ?3)
+					 ON CONFLICT DO NOTHING",
+					params![vulnerability_id, control_id, access::current_user()],
+				)?
+			} else {
+				tx.execute(
+					"DELETE FROM vulnerability_controls WHERE vulnerability_id = ?1 AND control_id = ?2",
+					params![vulnerability_id, control_id],
+				)?
+			};
+			if changed > 0 {
+				let (old, new) = if linked { (None, Some(control)) } else { (Some(control), None) };
+				audit_repo::record(
+					&tx,
+					AuditEntity::Vulnerability,
+					Some(vulnerability_id),
+					&cve_id,
+					AuditAction::Update,
+					&[FieldChange::new("compliance control", old, new)],
+				)?;
+			}
+			tx.commit()?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Every control with the findings on the fleet linked to it
+	pub async fn get_coverage(&self) -> Result<Vec<ControlCoverage>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(&format!(
+				"SELECT c.control_id, c.standard, c.code, c.title,
+					COUNT(DISTINCT f.vulnerability_id),
+					COUNT(DISTINCT CASE WHEN f.open THEN f.vulnerability_id END),
+					COUNT(DISTINCT f.robot_id)
+				 FROM compliance_controls c
+				 LEFT JOIN (
+					SELECT vc.control_id, v.vulnerability_id, rs.robot_id, {} AS open
+					FROM vulnerability_controls vc
+					JOIN vulnerabilities v ON v.vulnerability_id = vc.vulnerability_id
+					JOIN affected_software af ON af.vulnerability_id = v.vulnerability_id
+					JOIN robot_software rs ON rs.version_id = af.version_id
+					JOIN robots r ON r.robot_id = rs.robot_id
+					{}
+					WHERE v.deleted_at IS NULL AND r.deleted_at IS NULL
+				 ) f ON f.control_id = c.control_id
+				 GROUP BY c.control_id",
+				unresolved_status_sql(), STATUS_JOIN
+			))?;
+			let mut coverage = stmt
+				.query_map([], |row| {
+					Ok(ControlCoverage {
+						control: control_from_row(row)?,
+						findings: row.get(4)?,
+						open: row.get(5)?,
+						robots: row.get(6)?,
+					})
+				})?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to compute compliance coverage")?;
+			coverage.sort_by_key(|entry| entry.control.sort_key());
+			Ok(coverage)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_coverage() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO robots (robot_id, name) VALUES (1, 'arm-01'), (2, 'arm-02');
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ros', 'OSRF');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, 'humble');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 1), (2, 1);
+			 INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity) VALUES
+				(1, 'CVE-2024-0001', 'High'), (2, 'CVE-2024-0002', 'Low'), (3, 'CVE-2024-0003', 'Medium');
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES
+				(1, 1, 'humble'), (2, 1, 'humble');
+			 INSERT INTO vulnerability_status (vulnerability_id, status) VALUES (2, 'Mitigated');",
+		)?;
+		let repo = ComplianceRepository::new(pool);
+		repo.add_control("IEC 62443-3-3", "SR 1.10", "Authenticator feedback").await?;
+		assert!(repo.add_control("iec 62443-3-3", "sr 1.10", "Duplicate").await.is_err());
+
+		let controls = repo.get_controls().await?;
+		let code = |code: &str| controls.iter().find(|c| c.code == code).unwrap().control_id;
+		let codes: Vec<&str> = controls.iter().take(3).map(|c| c.code.as_str()).collect();
+		assert_eq!(codes, ["SR 1.1", "SR 1.7", "SR 1.10"]);
+
+		repo.set_linked(1, code("SR 5.1"), true).await?;
+		repo.set_linked(2, code("SR 5.1"), true).await?;
+		repo.set_linked(2, code("A.8.8"), true).await?;
+		// Not affecting any robot, so no finding on the fleet
+		repo.set_linked(3, code("A.8.8"), true).await?;
+		repo.set_linked(2, code("A.8.8"), false).await?;
+		assert_eq!(repo.get_linked_controls(2).await?.len(), 1);
+
+		let coverage = repo.get_coverage().await?;
+		let of = |code: &str| coverage.iter().find(|entry| entry.control.code == code).unwrap();
+		assert_eq!((of("SR 5.1").findings, of("SR 5.1").open, of("SR 5.1").robots), (2, 1, 2));
+		assert_eq!(of("SR 5.1").status(), "1 open");
+		assert_eq!(of("A.8.8").status(), "No findings linked");
+		Ok(())
+	}
+}
@@ -0,0 +1,266 @@
+// src/repositories/data_source_repo.rs
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::audit::{AuditAction, AuditEntity, FieldChange};
+use crate::models::data_source::{DataSource, RunStatus, SourceKind, MIN_SYNC_INTERVAL_MINUTES};
+use crate::repositories::{access, audit_repo};
+use crate::utils::time;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, OptionalExtension, Row};
+use std::sync::Arc;
+use tokio::task;
+
+const SOURCE_COLUMNS: &str =
+	"source_id, name, kind, url, enabled, interval_minutes, last_run_at, last_status, last_message";
+
+fn source_from_row(row: &Row) -> rusqlite::Result<DataSource> {
+	let kind: String = row.get(2)?;
+	Ok(DataSource {
+		source_id: row.get(0)?,
+		name: row.get(1)?,
+		kind: SourceKind::from_db(&kind).ok_or_else(|| {
+			rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, format!("Unknown data source kind {}", kind).into())
+		})?,
+		url: row.get(3)?,
+		enabled: row.get(4)?,
+		interval_minutes: row.get(5)?,
+		last_run_at: row.get::<_, Option<String>>(6)?.as_deref().and_then(time::parse_utc),
+		last_status: row.get::<_, Option<String>>(7)?.as_deref().and_then(RunStatus::from_db),
+		last_message: row.get(8)?,
+	})
+}
+
+fn audit_label(name: &str) -> String {
+	format!("Data source {}", name)
+}
+
+fn validate(interval_minutes: u32, url: Option<&str>, kind: SourceKind) -> Result<()> {
+	if interval_minutes < MIN_SYNC_INTERVAL_MINUTES {
+		bail!("The sync interval must be at least {} minutes", MIN_SYNC_INTERVAL_MINUTES);
+	}
+	if kind.uses_url() && !url.is_some_and(|url| url.starts_with("http://") || url.starts_with("https://")) {
+		bail!("The {} needs an http:// or https:// URL", kind);
+	}
+	Ok(())
+}
+
+/// Feeds the background update syncs with
+pub struct DataSourceRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl DataSourceRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Every data source, the built-in ones first
+	pub async fn get_sources(&self) -> Result<Vec<DataSource>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let sources = conn
+				.prepare(&format!("SELECT {} FROM data_sources ORDER BY source_id", SOURCE_COLUMNS))?
+				.query_map([], source_from_row)?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to read data sources")?;
+			Ok(sources)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// The data source named `name`, ignoring case
+	pub async fn get_source(&self, name: &str) -> Result<Option<DataSource>> {
+		let pool = self.pool.clone();
+		let name = name.trim().to_string();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			conn.query_row(
+				&format!("SELECT {} FROM data_sources WHERE name = ?1", SOURCE_COLUMNS),
+				[name],
+				source_from_row,
+			)
+				.optional()
+				.context("Failed to read data source")
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Enable or disable a source and change its schedule and URL
+	pub async fn update_source(&self, source_id: i64, enabled: bool, interval_minutes: u32, url: Option<String>) -> Result<()> {
+		access::require_write_access()?;
+		let url = url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let before = tx
+				.query_row(&format!("SELECT {} FROM data_sources WHERE source_id = ?1", SOURCE_COLUMNS), [source_id], source_from_row)
+				.optional()?
+				.context("Data source not found")?;
+			validate(interval_minutes, url.as_deref(), before.kind)?;
+			tx.execute(
+				"UPDATE data_sources SET enabled = ?2, interval_minutes = ?3, url = ?4 WHERE source_id = ?1",
+				params![source_id, enabled, interval_minutes, url],
+			)?;
+			let fields = |source_enabled: bool, interval: u32, url: Option<String>| {
+				[
+					("enabled", Some(source_enabled.to_string())),
+					("interval minutes", Some(interval.to_string())),
+					("url", url),
+				]
+			};
+			let changes = FieldChange::diff(
+				&fields(before.enabled, before.interval_minutes, before.url.clone()),
+				&fields(enabled, interval_minutes, url.clone()),
+			);
+			if !changes.is_empty() {
+				audit_repo::record(&tx, AuditEntity::Setting, None, &audit_label(&before.name), AuditAction::Update, &changes)?;
+			}
+			tx.commit()?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Add an NVD JSON feed at `url`, e.g. a mirror on the internal network. The feed
+	/// is enabled right away.
+	pub async fn add_feed(&self, name: &str, url: &str, interval_minutes: u32) -> Result<()> {
+		access::require_write_access()?;
+		let (name, url) = (name.trim().to_string(), url.trim().to_string());
+		if name.is_empty() {
+			bail!("A data source needs a name");
+		}
+		validate(interval_minutes, Some(&url), SourceKind::NvdFeed)?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let added = tx.execute(
+				"INSERT INTO data_sources (name, kind, url, enabled, interval_minutes) VALUES (?1, ?2, ?3, 1, ?4)
+				 ON CONFLICT(name) DO NOTHING",
+				params![name, SourceKind::NvdFeed.as_str(), url, interval_minutes],
+			)?;
+			if added == 0 {
+				bail!("A data source named {} already exists", name);
+			}
+			audit_repo::record(
+				&tx,
+				AuditEntity::Setting,
+				None,
+				&audit_label(&name),
+				AuditAction::Insert,
+				&[FieldChange::new("url", None, Some(url.clone()))],
+			)?;
+			tx.commit()?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Remove a feed added by users; the built-in sources can only be disabled
+	pub async fn remove_source(&self, source_id: i64) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let source = tx
+				.query_row(&format!("SELECT {} FROM data_sources WHERE source_id = ?1", SOURCE_COLUMNS), [source_id], source_from_row)
+				.optional()?
+				.context("Data source not found")?;
+			if !source.is_custom() {
+				bail!("{} is built in; disable it instead", source.name);
+			}
+			tx.execute("DELETE FROM data_sources WHERE source_id = ?1", [source_id])?;
+			audit_repo::record(
+				&tx,
+				AuditEntity::Setting,
+				None,
+				&audit_label(&source.name),
+				AuditAction::Delete,
+				&[FieldChange::new("url", source.url, None)],
+			)?;
+			tx.commit()?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Record the start of a run, from which the next one is scheduled. Recorded up
+	/// front, so a failing source is not retried on every poll.
+	pub async fn record_start(&self, source_id: i64, at: DateTime<Utc>) -> Result<()> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			conn.execute(
+				"UPDATE data_sources SET last_run_at = ?2, last_status = NULL, last_message = NULL WHERE source_id = ?1",
+				params![source_id, at.to_rfc3339_opts(SecondsFormat::Secs, true)],
+			)?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Record how the run of a source ended
+	pub async fn record_result(&self, source_id: i64, status: RunStatus, message: &str) -> Result<()> {
+		let message = message.to_string();
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			conn.execute(
+				"UPDATE data_sources SET last_status = ?2, last_message = ?3 WHERE source_id = ?1",
+				params![source_id, status.as_str(), message],
+			)?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_data_sources() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let repo = DataSourceRepository::new(pool.clone());
+
+		let sources = repo.get_sources().await?;
+		assert_eq!(sources[0].kind, SourceKind::Nvd);
+		assert!(sources[0].enabled);
+		assert!(sources.iter().skip(1).all(|source| !source.enabled));
+
+		let kev = repo.get_source("cisa kev").await?.expect("KEV seeded");
+		repo.update_source(kev.source_id, true, 720, kev.url.clone()).await?;
+		assert!(repo.update_source(kev.source_id, true, 1, kev.url.clone()).await.is_err());
+		assert!(repo.update_source(kev.source_id, true, 720, None).await.is_err());
+		let kev = repo.get_source("CISA KEV").await?.expect("KEV seeded");
+		assert!(kev.enabled);
+		assert_eq!(kev.interval_minutes, 720);
+		assert!(repo.remove_source(kev.source_id).await.is_err());
+
+		repo.add_feed("Mirror", "https://mirror.example/nvdcve-2.0-modified.json.gz", 60).await?;
+		assert!(repo.add_feed("mirror", "https://other.example/feed.json", 60).await.is_err());
+		let mirror = repo.get_source("Mirror").await?.expect("feed added");
+		assert!(mirror.is_custom() && mirror.enabled);
+
+		let at = time::parse_utc("2024-05-01T10:00:00Z").unwrap();
+		repo.record_start(mirror.source_id, at).await?;
+		repo.record_result(mirror.source_id, RunStatus::Failed, "Feed unreachable").await?;
+		let mirror = repo.get_source("Mirror").await?.expect("feed added");
+		assert_eq!(mirror.last_run_at, Some(at));
+		assert_eq!(mirror.last_status, Some(RunStatus::Failed));
+		assert_eq!(mirror.last_message.as_deref(), Some("Feed unreachable"));
+
+		repo.remove_source(mirror.source_id).await?;
+		assert!(repo.get_source("Mirror").await?.is_none());
+		Ok(())
+	}
+}
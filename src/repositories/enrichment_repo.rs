@@ -0,0 +1,252 @@
+// src/repositories/enrichment_repo.rs
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::enrichment::{EnrichmentOutcome, EnrichmentProgress, EnrichmentRun};
+use crate::models::vulnerability::Vulnerability;
+use crate::repositories::vulnerability_repo::{vulnerability_from_row, STATUS_JOIN, VULNERABILITY_COLUMNS};
+use rusqlite::{params, OptionalExtension};
+use std::sync::Arc;
+use anyhow::{Result, Context};
+use crate::utils::time;
+use tokio::task;
+
+/// CVEs over alias `v` still missing a field the NVD can provide; advisories from
+/// other databases, such as `RVD#` entries, are never looked up, and neither are fields
+/// an analyst set by hand, even to an empty value
+pub(crate) const INCOMPLETE_SQL: &str = "(v.cve_id LIKE 'CVE-%' AND (
+	(COALESCE(v.description, '') = '' AND 'description' NOT IN (SELECT p.field FROM field_provenance p
+		WHERE p.vulnerability_id = v.vulnerability_id AND p.source = 'manual'))
+	OR (UPPER(v.severity) = 'UNKNOWN' AND 'severity' NOT IN (SELECT p.field FROM field_provenance p
+		WHERE p.vulnerability_id = v.vulnerability_id AND p.source = 'manual'))
+	OR (v.cvss_score IS NULL AND 'cvss_score' NOT IN (SELECT p.field FROM field_provenance p
+		WHERE p.vulnerability_id = v.vulnerability_id AND p.source = 'manual'))
+	OR (v.published_date IS NULL AND 'published_date' NOT IN (SELECT p.field FROM field_provenance p
+		WHERE p.vulnerability_id = v.vulnerability_id AND p.source = 'manual'))))";
+
+/// Entries that failed this many times in a row are skipped for `FAILURE_COOLDOWN`
+pub const FAILURE_COOLDOWN_THRESHOLD: i64 = 3;
+/// SQLite datetime modifier for how long repeatedly failing entries are skipped
+const FAILURE_COOLDOWN: &str = "-24 hours";
+
+pub struct EnrichmentRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl EnrichmentRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Open a new run and return its ID
+	pub async fn start_run(&self, batch_size: usize) -> Result<i64> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			conn.execute(
+				"INSERT INTO enrichment_runs (batch_size) VALUES (?1)",
+				[batch_size as i64],
+			).context("Failed to start enrichment run")?;
+			Ok(conn.last_insert_rowid())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Remember the latest outcome for one vulnerability, counting consecutive failures
+	pub async fn record_attempt(&self, run_id: i64, vulnerability_id: i64, outcome: EnrichmentOutcome) -> Result<()> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			conn.execute(
+				"INSERT INTO enrichment_attempts (vulnerability_id, run_id, outcome, consecutive_failures)
+				 VALUES (?1, ?2, ?3, CASE WHEN ?3 = 'failed' THEN 1 ELSE 0 END)
+				 ON CONFLICT(vulnerability_id) DO UPDATE SET
+					run_id = excluded.run_id,
+					outcome = excluded.outcome,
+					attempted_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'),
+					consecutive_failures = CASE WHEN excluded.outcome = 'failed'
+						THEN consecutive_failures + 1 ELSE 0 END",
+				params![vulnerability_id, run_id, outcome.as_str()],
+			).context("Failed to record enrichment attempt")?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Store the final tallies of a run
+	pub async fn finish_run(&self, run_id: i64, run: EnrichmentRun) -> Result<()> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			conn.execute(
+				"UPDATE enrichment_runs
+				 SET finished_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), updated = ?1, unchanged = ?2, failed = ?3, rate_limited = ?4
+				 WHERE run_id = ?5",
+				params![run.updated, run.unchanged, run.failed, run.rate_limited, run_id],
+			).context("Failed to finish enrichment run")?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Next incomplete vulnerabilities to enrich: never attempted or rate-limited ones first,
+	/// then the ones attempted longest ago, so restarts pick up where the last run stopped.
+	/// Entries that keep failing are left out until their cooldown has passed.
+	pub async fn get_pending(&self, batch_size: usize) -> Result<Vec<Vulnerability>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(&format!(
+				"SELECT {} FROM vulnerabilities v {}
+				 LEFT JOIN enrichment_attempts a ON a.vulnerability_id = v.vulnerability_id
+				 WHERE {} AND v.deleted_at IS NULL
+					AND NOT (
+						COALESCE(a.consecutive_failures, 0) >= ?2
+						AND julianday(a.attempted_at) > julianday('now', ?3)
+					)
+				 ORDER BY
+					CASE WHEN a.vulnerability_id IS NULL OR a.outcome = 'rate_limited' THEN 0 ELSE 1 END,
+					a.attempted_at,
+					v.vulnerability_id
+				 LIMIT ?1",
+				VULNERABILITY_COLUMNS, STATUS_JOIN, INCOMPLETE_SQL
+			))?;
+
+			let vulnerabilities = stmt
+				.query_map(
+					params![batch_size as i64, FAILURE_COOLDOWN_THRESHOLD, FAILURE_COOLDOWN],
+					vulnerability_from_row,
+				)?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to collect pending vulnerabilities")?;
+
+			Ok(vulnerabilities)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	pub async fn get_progress(&self) -> Result<EnrichmentProgress> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+
+			let (remaining_unknown, never_attempted) = conn.query_row(
+				&format!(
+					"SELECT COUNT(*), COUNT(*) - COUNT(a.vulnerability_id)
+					 FROM vulnerabilities v
+					 LEFT JOIN enrichment_attempts a ON a.vulnerability_id = v.vulnerability_id
+					 WHERE {} AND v.deleted_at IS NULL",
+					INCOMPLETE_SQL
+				),
+				[],
+				|row| Ok((row.get(0)?, row.get(1)?)),
+			).context("Failed to count incomplete vulnerabilities")?;
+
+			let last_run = conn.query_row(
+				"SELECT run_id, started_at, finished_at, batch_size, updated, unchanged, failed, rate_limited
+				 FROM enrichment_runs
+				 WHERE finished_at IS NOT NULL
+				 ORDER BY run_id DESC
+				 LIMIT 1",
+				[],
+				|row| {
+					Ok(EnrichmentRun {
+						run_id: row.get(0)?,
+						started_at: row.get::<_, Option<String>>(1)?
+							.as_deref().and_then(time::parse_utc),
+						finished_at: row.get::<_, Option<String>>(2)?
+							.as_deref().and_then(time::parse_utc),
+						batch_size: row.get(3)?,
+						updated: row.get(4)?,
+						unchanged: row.get(5)?,
+						failed: row.get(6)?,
+						rate_limited: row.get(7)?,
+					})
+				},
+			).optional().context("Failed to load last enrichment run")?;
+
+			Ok(EnrichmentProgress { remaining_unknown, never_attempted, last_run })
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_enrichment_progress_survives_runs() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, description, severity, published_date, cvss_score) VALUES
+				(1, 'CVE-2024-0001', NULL, 'UNKNOWN', NULL, NULL),
+				(2, 'CVE-2024-0002', NULL, 'UNKNOWN', NULL, NULL),
+				(3, 'CVE-2024-0003', 'Complete', 'High', '2024-01-01', 7.5);"
+		)?;
+		let repo = EnrichmentRepository::new(pool);
+
+		let progress = repo.get_progress().await?;
+		assert_eq!(progress.remaining_unknown, 2);
+		assert_eq!(progress.never_attempted, 2);
+		assert!(progress.last_run.is_none());
+
+		// A failed attempt moves the entry behind the untried one
+		let run_id = repo.start_run(1).await?;
+		let pending = repo.get_pending(1).await?;
+		assert_eq!(pending[0].cve_id, "CVE-2024-0001");
+		repo.record_attempt(run_id, 1, EnrichmentOutcome::Failed).await?;
+		let mut run = EnrichmentRun::default();
+		run.record(EnrichmentOutcome::Failed);
+		repo.finish_run(run_id, run).await?;
+
+		let pending = repo.get_pending(1).await?;
+		assert_eq!(pending[0].cve_id, "CVE-2024-0002");
+
+		let progress = repo.get_progress().await?;
+		assert_eq!(progress.remaining_unknown, 2);
+		assert_eq!(progress.never_attempted, 1);
+		assert_eq!(progress.last_run.map(|r| r.failed), Some(1));
+
+		Ok(())
+	}
+	#[tokio::test]
+	async fn test_repeated_failures_cool_down() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity) VALUES (1, 'CVE-2024-0001', 'UNKNOWN')",
+			[],
+		)?;
+		let repo = EnrichmentRepository::new(pool.clone());
+		let run_id = repo.start_run(1).await?;
+
+		for _ in 0..FAILURE_COOLDOWN_THRESHOLD {
+			assert_eq!(repo.get_pending(1).await?.len(), 1);
+			repo.record_attempt(run_id, 1, EnrichmentOutcome::Failed).await?;
+		}
+		assert!(repo.get_pending(1).await?.is_empty());
+
+		// Once the cooldown has passed the entry is retried
+		pool.get()?.execute(
+			"UPDATE enrichment_attempts SET attempted_at = datetime('now', '-2 days')",
+			[],
+		)?;
+		assert_eq!(repo.get_pending(1).await?.len(), 1);
+
+		// A success resets the failure streak
+		repo.record_attempt(run_id, 1, EnrichmentOutcome::Unchanged).await?;
+		let failures: i64 = pool.get()?.query_row(
+			"SELECT consecutive_failures FROM enrichment_attempts WHERE vulnerability_id = 1",
+			[],
+			|row| row.get(0),
+		)?;
+		assert_eq!(failures, 0);
+
+		Ok(())
+	}
+}
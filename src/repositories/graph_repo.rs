@@ -0,0 +1,259 @@
+// src/repositories/graph_repo.rs
+
+use crate::db::connection::SqlitePool;
+use crate::models::graph::{GraphCenter, NodeKind, RelationshipGraph};
+use crate::repositories::vulnerability_repo::{unresolved_status_sql, EFFECTIVE_CVSS_SQL};
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashSet;
+use std::sync::Arc;
+use anyhow::{anyhow, Context, Result};
+use tokio::task;
+
+/// Limits keeping a graph readable; the rest is counted as omitted
+const MAX_ROBOTS: usize = 20;
+const MAX_VULNERABILITIES: usize = 15;
+
+pub struct GraphRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl GraphRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// The blast radius of a CVE or robot: software versions, the robots running
+	/// them and the open CVEs affecting them
+	pub async fn get_graph(&self, center: GraphCenter) -> Result<RelationshipGraph> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			match center {
+				GraphCenter::Vulnerability(id) => vulnerability_graph(&conn, id),
+				GraphCenter::Robot(id) => robot_graph(&conn, id),
+			}
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+/// A CVE, the versions it affects and the robots running them. CVEs sharing an
+/// affected version link to it; other open CVEs of the same products link to the center.
+fn vulnerability_graph(conn: &Connection, vulnerability_id: i64) -> Result<RelationshipGraph> {
+	let (cve_id, severity): (String, String) = conn
+		.query_row(
+			"SELECT cve_id, severity FROM vulnerabilities WHERE vulnerability_id = ?1",
+			[vulnerability_id],
+			|row| Ok((row.get(0)?, row.get(1)?)),
+		)
+		.optional()?
+		.ok_or_else(|| anyhow!("Vulnerability {} not found", vulnerability_id))?;
+
+	let mut graph = RelationshipGraph::default();
+	let center = graph.add_node(NodeKind::Vulnerability, vulnerability_id, cve_id, Some(severity));
+
+	let versions = query_versions(
+		conn,
+		"SELECT sv.version_id, sp.product_name || ' ' || sv.version_number
+		 FROM affected_software af
+		 JOIN software_versions sv ON sv.version_id = af.version_id
+		 JOIN software_products sp ON sp.product_id = sv.product_id
+		 WHERE af.vulnerability_id = ?1
+		 ORDER BY sp.product_name, sv.version_number",
+		vulnerability_id,
+	)?;
+	for (version_id, label) in &versions {
+		let idx = graph.add_node(NodeKind::SoftwareVersion, *version_id, label.clone(), None);
+		graph.add_edge(center, idx, false);
+	}
+	add_robots(conn, &mut graph, &versions)?;
+
+	let related = query_vulnerabilities(conn, &format!(
+		"SELECT v.vulnerability_id, v.cve_id, v.severity, af.version_id
+		 FROM affected_software af
+		 JOIN software_versions sv ON sv.version_id = af.version_id
+		 JOIN vulnerabilities v ON v.vulnerability_id = af.vulnerability_id
+		 LEFT JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id
+		 WHERE sv.product_id IN (
+			SELECT sv2.product_id
+			FROM affected_software af2
+			JOIN software_versions sv2 ON sv2.version_id = af2.version_id
+			WHERE af2.vulnerability_id = ?1
+		 )
+		 AND v.vulnerability_id != ?1 AND v.deleted_at IS NULL AND {}
+		 ORDER BY {} DESC, v.cve_id",
+		unresolved_status_sql(), EFFECTIVE_CVSS_SQL
+	), vulnerability_id)?;
+	add_vulnerabilities(&mut graph, related, Some(center));
+
+	Ok(graph)
+}
+
+/// A robot, its installed versions, the open CVEs affecting them and the other
+/// robots running the same versions
+fn robot_graph(conn: &Connection, robot_id: i64) -> Result<RelationshipGraph> {
+	let name: String = conn
+		.query_row("SELECT name FROM robots WHERE robot_id = ?1", [robot_id], |row| row.get(0))
+		.optional()?
+		.ok_or_else(|| anyhow!("Robot {} not found", robot_id))?;
+
+	let mut graph = RelationshipGraph::default();
+	let center = graph.add_node(NodeKind::Robot, robot_id, name, None);
+
+	let versions = query_versions(
+		conn,
+		"SELECT sv.version_id, sp.product_name || ' ' || sv.version_number
+		 FROM robot_software rs
+		 JOIN software_versions sv ON sv.version_id = rs.version_id
+		 JOIN software_products sp ON sp.product_id = sv.product_id
+		 WHERE rs.robot_id = ?1
+		 ORDER BY sp.product_name, sv.version_number",
+		robot_id,
+	)?;
+	for (version_id, label) in &versions {
+		let idx = graph.add_node(NodeKind::SoftwareVersion, *version_id, label.clone(), None);
+		graph.add_edge(center, idx, false);
+	}
+
+	let vulnerabilities = query_vulnerabilities(conn, &format!(
+		"SELECT v.vulnerability_id, v.cve_id, v.severity, af.version_id
+		 FROM affected_software af
+		 JOIN vulnerabilities v ON v.vulnerability_id = af.vulnerability_id
+		 LEFT JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id
+		 WHERE af.version_id IN (SELECT version_id FROM robot_software WHERE robot_id = ?1)
+		 AND v.deleted_at IS NULL AND {}
+		 ORDER BY {} DESC, v.cve_id",
+		unresolved_status_sql(), EFFECTIVE_CVSS_SQL
+	), robot_id)?;
+	add_vulnerabilities(&mut graph, vulnerabilities, None);
+	add_robots(conn, &mut graph, &versions)?;
+
+	Ok(graph)
+}
+
+fn query_versions(conn: &Connection, sql: &str, id: i64) -> Result<Vec<(i64, String)>> {
+	let mut stmt = conn.prepare(sql).context("Failed to prepare statement")?;
+	let rows = stmt.query_map([id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+	rows.collect::<rusqlite::Result<Vec<_>>>().context("Failed to load software versions")
+}
+
+/// Rows of (vulnerability ID, CVE ID, severity, affected version ID)
+fn query_vulnerabilities(conn: &Connection, sql: &str, id: i64) -> Result<Vec<(i64, String, String, i64)>> {
+	let mut stmt = conn.prepare(sql).context("Failed to prepare statement")?;
+	let rows = stmt.query_map([id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?;
+	rows.collect::<rusqlite::Result<Vec<_>>>().context("Failed to load related vulnerabilities")
+}
+
+/// Link each CVE to the affected versions already in the graph, else to `fallback`
+/// as related by product. Rows come ordered by risk, so the riskiest are kept.
+fn add_vulnerabilities(
+	graph: &mut RelationshipGraph,
+	rows: Vec<(i64, String, String, i64)>,
+	fallback: Option<usize>,
+) {
+	let mut kept = Vec::new();
+	let mut omitted = HashSet::new();
+	for (vulnerability_id, cve_id, severity, version_id) in &rows {
+		if !kept.contains(vulnerability_id) {
+			if kept.len() == MAX_VULNERABILITIES {
+				omitted.insert(*vulnerability_id);
+				continue;
+			}
+			kept.push(*vulnerability_id);
+		}
+		let idx = graph.add_node(NodeKind::Vulnerability, *vulnerability_id, cve_id.clone(), Some(severity.clone()));
+		if let Some(version) = graph.find(NodeKind::SoftwareVersion, *version_id) {
+			graph.add_edge(version, idx, false);
+		}
+	}
+
+	if let Some(fallback) = fallback {
+		let nodes: Vec<usize> = kept.iter().filter_map(|&id| graph.find(NodeKind::Vulnerability, id)).collect();
+		for idx in nodes {
+			if !graph.edges.iter().any(|e| e.to == idx) {
+				graph.add_edge(fallback, idx, true);
+			}
+		}
+	}
+	graph.omitted += omitted.len();
+}
+
+/// Link the robots running each version, up to `MAX_ROBOTS` in the graph
+fn add_robots(conn: &Connection, graph: &mut RelationshipGraph, versions: &[(i64, String)]) -> Result<()> {
+	let mut stmt = conn.prepare(
+		"SELECT r.robot_id, r.name
+		 FROM robot_software rs
+		 JOIN robots r ON r.robot_id = rs.robot_id
+		 WHERE rs.version_id = ?1
+		 ORDER BY r.name"
+	).context("Failed to prepare statement")?;
+
+	let mut omitted = HashSet::new();
+	for (version_id, _) in versions {
+		let Some(version) = graph.find(NodeKind::SoftwareVersion, *version_id) else {
+			continue;
+		};
+		let robots = stmt
+			.query_map([version_id], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+			.collect::<rusqlite::Result<Vec<_>>>()
+			.context("Failed to load robots")?;
+
+		for (robot_id, name) in robots {
+			let robot_count = graph.nodes.iter().filter(|n| n.kind == NodeKind::Robot).count();
+			if graph.find(NodeKind::Robot, robot_id).is_none() && robot_count >= MAX_ROBOTS {
+				omitted.insert(robot_id);
+				continue;
+			}
+			let idx = graph.add_node(NodeKind::Robot, robot_id, name, None);
+			graph.add_edge(version, idx, false);
+		}
+	}
+	graph.omitted += omitted.len();
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_graphs() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO robots (robot_id, name) VALUES (1, 'arm-01'), (2, 'arm-02'), (3, 'cart-01');
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ros-core', 'OSRF'), (2, 'firmware', 'ACME');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (10, 1, '1.0'), (11, 1, '1.1'), (20, 2, '2.0');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 10), (2, 10), (3, 11), (3, 20);
+			 INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity, cvss_score) VALUES
+				(1, 'CVE-2024-0001', 'High', 7.0),
+				(2, 'CVE-2024-0002', 'Critical', 9.8),
+				(3, 'CVE-2024-0003', 'Low', 2.0),
+				(4, 'CVE-2024-0004', 'Medium', 5.0);
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES
+				(1, 10, '1.0'), (2, 10, '1.0'), (3, 11, '1.1'), (4, 20, '2.0');
+			 INSERT INTO vulnerability_status (vulnerability_id, status) VALUES (2, 'Mitigated');",
+		)?;
+		let repo = GraphRepository::new(pool);
+
+		// CVE-1 reaches both robots running ros-core 1.0; CVE-3 only shares the product,
+		// and the mitigated CVE-2 is left out
+		let graph = repo.get_graph(GraphCenter::Vulnerability(1)).await?;
+		let labels: Vec<&str> = graph.nodes.iter().map(|n| n.label.as_str()).collect();
+		assert_eq!(labels, ["CVE-2024-0001", "ros-core 1.0", "arm-01", "arm-02", "CVE-2024-0003"]);
+		let related = graph.find(NodeKind::Vulnerability, 3).unwrap();
+		assert!(graph.edges.iter().any(|e| e.from == 0 && e.to == related && e.related));
+
+		let graph = repo.get_graph(GraphCenter::Robot(3)).await?;
+		let labels: Vec<&str> = graph.nodes.iter().map(|n| n.label.as_str()).collect();
+		assert_eq!(labels, ["cart-01", "firmware 2.0", "ros-core 1.1", "CVE-2024-0004", "CVE-2024-0003"]);
+		assert_eq!(graph.depths(), [0, 1, 1, 2, 2]);
+		assert!(graph.edges.iter().all(|e| !e.related));
+
+		assert!(repo.get_graph(GraphCenter::Robot(99)).await.is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,527 @@
+// src/repositories/import_run_repo.rs
+
+//! Revertible runs of the vulnerability list and robot inventory imports. The importer
+//! opens a run, asks before each upsert whether the vulnerability is new, and tags new
+//! ones with the run while keeping the previous values of those it overwrites; robots
+//! are tagged and kept the same way. Reverting a run deletes what it created and puts
+//! the previous values back.
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::audit::{AuditAction, AuditEntity, FieldChange};
+use crate::models::import_run::{ImportReport, ImportRun, ImportRunStatus, RevertSummary, SkippedRow};
+use crate::repositories::{access, audit_repo};
+use crate::repositories::robot_repo::refresh_risk_scores;
+use crate::repositories::software_repo::refresh_robot_correlations;
+use crate::repositories::trash_repo::{delete_robot_rows, delete_vulnerability_rows};
+use crate::utils::time;
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::task;
+
+/// Fields of a vulnerability an import overwrites, as they were before the run
+#[derive(Debug, Serialize, Deserialize)]
+struct PreviousValues {
+	description: Option<String>,
+	severity: String,
+	impact: Option<String>,
+	mitigation: Option<String>,
+	published_date: Option<String>,
+	/// URLs of the references it had; the run may add more
+	references: Vec<String>,
+}
+
+/// Fields and software of a robot an inventory import overwrites, as they were before the run
+#[derive(Debug, Serialize, Deserialize)]
+struct PreviousRobot {
+	model: Option<String>,
+	specifications: Option<String>,
+	operational_note: Option<String>,
+	criticality: String,
+	firmware_version: Option<String>,
+	os: Option<String>,
+	ros_distro: Option<String>,
+	inventory_refreshed_at: Option<String>,
+	inventory_source: Option<String>,
+	/// Installed versions with their install dates
+	software: Vec<(i64, String)>,
+}
+
+/// What an upsert in a run does to the vulnerability
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RunChange {
+	Create,
+	/// Overwrites a vulnerability from before the run
+	Overwrite,
+	/// Upserts a vulnerability the run already created or overwrote
+	Repeat,
+}
+
+/// Opens a run for an import of `source` by the current user
+pub(crate) fn start(conn: &Connection, source: &str) -> Result<i64> {
+	conn.execute(
+		"INSERT INTO import_runs (source, actor) VALUES (?1, ?2)",
+		params![source, access::current_user()],
+	).context("Failed to record the import run")?;
+	Ok(conn.last_insert_rowid())
+}
+
+/// Call before upserting `cve_id` in run `run_id`. The first time the run overwrites an
+/// existing vulnerability its previous values are kept.
+pub(crate) fn prepare_upsert(conn: &Connection, run_id: i64, cve_id: &str) -> Result<RunChange> {
+	let existing: Option<(i64, Option<i64>)> = conn
+		.query_row(
+			"SELECT vulnerability_id, import_run_id FROM vulnerabilities WHERE cve_id = ?1",
+			[cve_id],
+			|row| Ok((row.get(0)?, row.get(1)?)),
+		)
+		.optional()?;
+	let Some((vulnerability_id, created_by)) = existing else {
+		return Ok(RunChange::Create);
+	};
+	if created_by == Some(run_id) {
+		return Ok(RunChange::Repeat);
+	}
+
+	let previous = conn.query_row(
+		"SELECT description, severity, impact, mitigation, published_date,
+			(SELECT json_group_array(url) FROM vulnerability_references WHERE vulnerability_id = v.vulnerability_id)
+		 FROM vulnerabilities v WHERE vulnerability_id = ?1",
+		[vulnerability_id],
+		|row| {
+			let references: String = row.get(5)?;
+			Ok(PreviousValues {
+				description: row.get(0)?,
+				severity: row.get(1)?,
+				impact: row.get(2)?,
+				mitigation: row.get(3)?,
+				published_date: row.get(4)?,
+				references: serde_json::from_str(&references).unwrap_or_default(),
+			})
+		},
+	)?;
+	let kept = conn.execute(
+		"INSERT OR IGNORE INTO import_run_previous (run_id, vulnerability_id, previous) VALUES (?1, ?2, ?3)",
+		params![run_id, vulnerability_id, serde_json::to_string(&previous)?],
+	)?;
+	Ok(if kept == 1 { RunChange::Overwrite } else { RunChange::Repeat })
+}
+
+/// Tags a vulnerability the run has just inserted
+pub(crate) fn tag_created(conn: &Connection, run_id: i64, cve_id: &str) -> Result<()> {
+	conn.execute("UPDATE vulnerabilities SET import_run_id = ?1 WHERE cve_id = ?2", params![run_id, cve_id])?;
+	Ok(())
+}
+
+/// Call before a robot inventory run updates an existing robot, keeping its fields and
+/// installed software for a revert
+pub(crate) fn keep_previous_robot(conn: &Connection, run_id: i64, robot_id: i64) -> Result<()> {
+	let software = conn
+		.prepare("SELECT version_id, installed_date FROM robot_software WHERE robot_id = ?1 ORDER BY version_id")?
+		.query_map([robot_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+		.collect::<rusqlite::Result<Vec<(i64, String)>>>()?;
+	let previous = conn.query_row(
+		"SELECT model, specifications, operational_note, criticality, firmware_version, os, ros_distro,
+			inventory_refreshed_at, inventory_source
+		 FROM robots WHERE robot_id = ?1",
+		[robot_id],
+		|row| {
+			Ok(PreviousRobot {
+				model: row.get(0)?,
+				specifications: row.get(1)?,
+				operational_note: row.get(2)?,
+				criticality: row.get(3)?,
+				firmware_version: row.get(4)?,
+				os: row.get(5)?,
+				ros_distro: row.get(6)?,
+				inventory_refreshed_at: row.get(7)?,
+				inventory_source: row.get(8)?,
+				software,
+			})
+		},
+	)?;
+	conn.execute(
+		"INSERT OR IGNORE INTO import_run_robot_previous (run_id, robot_id, previous) VALUES (?1, ?2, ?3)",
+		params![run_id, robot_id, serde_json::to_string(&previous)?],
+	)?;
+	Ok(())
+}
+
+/// Sets a robot back to its fields and installed software from before a run
+fn restore_robot(conn: &Connection, robot_id: i64, previous: &PreviousRobot) -> Result<()> {
+	conn.execute(
+		"UPDATE robots SET model = ?2, specifications = ?3, operational_note = ?4, criticality = ?5,
+			firmware_version = ?6, os = ?7, ros_distro = ?8, inventory_refreshed_at = ?9, inventory_source = ?10,
+			updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+		 WHERE robot_id = ?1",
+		params![
+			robot_id,
+			previous.model,
+			previous.specifications,
+			previous.operational_note,
+			previous.criticality,
+			previous.firmware_version,
+			previous.os,
+			previous.ros_distro,
+			previous.inventory_refreshed_at,
+			previous.inventory_source,
+		],
+	)?;
+	conn.execute("DELETE FROM robot_software WHERE robot_id = ?1", [robot_id])?;
+	for (version_id, installed_date) in &previous.software {
+		conn.execute(
+			"INSERT INTO robot_software (robot_id, version_id, installed_date) VALUES (?1, ?2, ?3)",
+			params![robot_id, version_id, installed_date],
+		)?;
+	}
+	refresh_robot_correlations(conn, robot_id)?;
+	Ok(())
+}
+
+/// Adds the vulnerabilities a batch created and overwrote to the run's counts
+pub(crate) fn add_counts(conn: &Connection, run_id: i64, created: usize, updated: usize) -> Result<()> {
+	conn.execute(
+		"UPDATE import_runs SET created = created + ?2, updated = updated + ?3 WHERE run_id = ?1",
+		params![run_id, created as i64, updated as i64],
+	)?;
+	Ok(())
+}
+
+/// Keeps the rows the run left out, as far as the report holds them
+pub(crate) fn record_skipped(conn: &mut Connection, run_id: i64, report: &ImportReport) -> Result<()> {
+	let tx = conn.transaction()?;
+	tx.execute("UPDATE import_runs SET skipped = ?2 WHERE run_id = ?1", params![run_id, report.skipped as i64])?;
+	{
+		let mut stmt = tx.prepare(
+			"INSERT OR REPLACE INTO import_run_skipped (run_id, line, cve_id, reason) VALUES (?1, ?2, ?3, ?4)",
+		)?;
+		for row in &report.skipped_rows {
+			stmt.execute(params![run_id, row.line as i64, row.cve_id, row.reason])?;
+		}
+	}
+	tx.commit().context("Failed to record the skipped rows")?;
+	Ok(())
+}
+
+/// Closes a run with the status it ended in
+pub(crate) fn finish(conn: &Connection, run_id: i64, status: ImportRunStatus) -> Result<()> {
+	conn.execute(
+		"UPDATE import_runs SET status = ?2, finished_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE run_id = ?1",
+		params![run_id, status.as_str()],
+	).context("Failed to close the import run")?;
+	Ok(())
+}
+
+fn import_run_from_row(row: &rusqlite::Row) -> rusqlite::Result<ImportRun> {
+	let started_at: String = row.get(3)?;
+	let finished_at: Option<String> = row.get(4)?;
+	let status: String = row.get(5)?;
+	Ok(ImportRun {
+		run_id: row.get(0)?,
+		source: row.get(1)?,
+		actor: row.get(2)?,
+		started_at: time::parse_utc(&started_at).ok_or_else(|| {
+			rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, format!("Invalid time: {}", started_at).into())
+		})?,
+		finished_at: finished_at.as_deref().and_then(time::parse_utc),
+		status: ImportRunStatus::from_db(&status),
+		created: row.get(6)?,
+		updated: row.get(7)?,
+		skipped: row.get(8)?,
+	})
+}
+
+const RUN_COLUMNS: &str = "run_id, source, actor, started_at, finished_at, status, created, updated, skipped";
+
+/// Reverts a run: deletes the vulnerabilities it created, with their triage and notes,
+/// and sets those it overwrote back to their previous values and references. Robots
+/// are deleted and set back alike, with their installed software.
+pub(crate) fn revert(conn: &mut Connection, run_id: i64) -> Result<RevertSummary> {
+	let tx = conn.transaction()?;
+	let run = tx
+		.query_row(&format!("SELECT {} FROM import_runs WHERE run_id = ?1", RUN_COLUMNS), [run_id], import_run_from_row)
+		.optional()?
+		.with_context(|| format!("Import run {} not found", run_id))?;
+	if !run.can_revert() {
+		bail!("Import run {} is {} and cannot be reverted", run_id, run.status.as_str());
+	}
+	// Reverting under a later run would lose its previous values, so undo in reverse order
+	let later: Option<i64> = tx.query_row(
+		"SELECT MIN(later) FROM (
+			SELECT p.run_id AS later FROM import_run_previous p JOIN import_runs r ON r.run_id = p.run_id
+			WHERE p.run_id > ?1 AND r.status != 'reverted'
+			  AND p.vulnerability_id IN (
+				SELECT vulnerability_id FROM vulnerabilities WHERE import_run_id = ?1
+				UNION SELECT vulnerability_id FROM import_run_previous WHERE run_id = ?1)
+			UNION ALL
+			SELECT p.run_id FROM import_run_robot_previous p JOIN import_runs r ON r.run_id = p.run_id
+			WHERE p.run_id > ?1 AND r.status != 'reverted'
+			  AND p.robot_id IN (
+				SELECT robot_id FROM robots WHERE import_run_id = ?1
+				UNION SELECT robot_id FROM import_run_robot_previous WHERE run_id = ?1))",
+		[run_id],
+		|row| row.get(0),
+	)?;
+	if let Some(later) = later {
+		bail!("Import run {} changed the same vulnerabilities afterwards; revert it first", later);
+	}
+
+	let overwritten = tx
+		.prepare("SELECT vulnerability_id, previous FROM import_run_previous WHERE run_id = ?1")?
+		.query_map([run_id], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+	for (vulnerability_id, previous) in &overwritten {
+		let previous: PreviousValues = serde_json::from_str(previous)
+			.with_context(|| format!("Invalid previous values of vulnerability {}", vulnerability_id))?;
+		tx.execute(
+			"UPDATE vulnerabilities SET description = ?2, severity = ?3, impact = ?4, mitigation = ?5, published_date = ?6
+			 WHERE vulnerability_id = ?1",
+			params![
+				vulnerability_id,
+				previous.description,
+				previous.severity,
+				previous.impact,
+				previous.mitigation,
+				previous.published_date,
+			],
+		)?;
+		tx.execute(
+			"DELETE FROM vulnerability_references
+			 WHERE vulnerability_id = ?1 AND url NOT IN (SELECT value FROM json_each(?2))",
+			params![vulnerability_id, serde_json::to_string(&previous.references)?],
+		)?;
+	}
+
+	let created = tx
+		.prepare("SELECT vulnerability_id FROM vulnerabilities WHERE import_run_id = ?1")?
+		.query_map([run_id], |row| row.get::<_, i64>(0))?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+	for vulnerability_id in &created {
+		delete_vulnerability_rows(&tx, *vulnerability_id)?;
+	}
+
+	let overwritten_robots = tx
+		.prepare("SELECT robot_id, previous FROM import_run_robot_previous WHERE run_id = ?1")?
+		.query_map([run_id], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+	for (robot_id, previous) in &overwritten_robots {
+		let previous: PreviousRobot = serde_json::from_str(previous)
+			.with_context(|| format!("Invalid previous values of robot {}", robot_id))?;
+		restore_robot(&tx, *robot_id, &previous)?;
+	}
+
+	let created_robots = tx
+		.prepare("SELECT robot_id FROM robots WHERE import_run_id = ?1")?
+		.query_map([run_id], |row| row.get::<_, i64>(0))?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+	for robot_id in &created_robots {
+		delete_robot_rows(&tx, *robot_id)?;
+	}
+
+	tx.execute("DELETE FROM import_run_previous WHERE run_id = ?1", [run_id])?;
+	tx.execute("DELETE FROM import_run_robot_previous WHERE run_id = ?1", [run_id])?;
+	tx.execute("UPDATE import_runs SET status = ?2 WHERE run_id = ?1", params![run_id, ImportRunStatus::Reverted.as_str()])?;
+	let summary = RevertSummary {
+		removed: created.len(),
+		restored: overwritten.len(),
+		robots_removed: created_robots.len(),
+		robots_restored: overwritten_robots.len(),
+	};
+	audit_repo::record(
+		&tx,
+		AuditEntity::Import,
+		Some(run_id),
+		&run.source,
+		AuditAction::Delete,
+		&[
+			FieldChange::new("status", Some(run.status.as_str().to_string()), Some(ImportRunStatus::Reverted.as_str().to_string())),
+			FieldChange::new("summary", None, Some(summary.to_string())),
+		],
+	)?;
+	refresh_risk_scores(&tx)?;
+	tx.commit()?;
+	Ok(summary)
+}
+
+pub struct ImportRunRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl ImportRunRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Import runs, newest first
+	pub async fn get_runs(&self) -> Result<Vec<ImportRun>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let runs = conn
+				.prepare(&format!("SELECT {} FROM import_runs ORDER BY run_id DESC", RUN_COLUMNS))?
+				.query_map([], import_run_from_row)?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to read import runs")?;
+			Ok(runs)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Rows the run left out, in file order
+	pub async fn get_skipped_rows(&self, run_id: i64) -> Result<Vec<SkippedRow>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let rows = conn
+				.prepare("SELECT line, cve_id, reason FROM import_run_skipped WHERE run_id = ?1 ORDER BY line")?
+				.query_map([run_id], |row| {
+					Ok(SkippedRow { line: row.get::<_, i64>(0)? as usize, cve_id: row.get(1)?, reason: row.get(2)? })
+				})?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to read skipped rows")?;
+			Ok(rows)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	pub async fn revert(&self, run_id: i64) -> Result<RevertSummary> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| revert(conn, run_id)))
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::csv_mapping::CsvMapping;
+	use crate::utils::csv_importer::import_vulnerabilities_from_csv;
+	use crate::utils::progress::ProgressReporter;
+	use crate::utils::robot_import::import_robots;
+	use tempfile::tempdir;
+
+	/// A robot's fields, optionally with `updated_at`, and its installed software
+	type RobotState = (Vec<Option<String>>, Vec<(i64, String)>);
+
+	fn robot_state(conn: &Connection, robot_id: i64, with_updated_at: bool) -> Result<RobotState> {
+		let columns = "name, manufacturer, model, specifications, operational_note, criticality,
+			firmware_version, os, ros_distro, inventory_refreshed_at, inventory_source";
+		let columns = if with_updated_at { format!("{}, updated_at", columns) } else { columns.to_string() };
+		let count = columns.split(',').count();
+		let fields = conn.query_row(
+			&format!("SELECT {} FROM robots WHERE robot_id = ?1", columns),
+			[robot_id],
+			|row| (0..count).map(|i| row.get(i)).collect::<rusqlite::Result<Vec<Option<String>>>>(),
+		)?;
+		let software = conn
+			.prepare("SELECT version_id, installed_date FROM robot_software WHERE robot_id = ?1 ORDER BY version_id")?
+			.query_map([robot_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+			.collect::<rusqlite::Result<Vec<_>>>()?;
+		Ok((fields, software))
+	}
+
+	#[tokio::test]
+	async fn test_revert() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, description, severity) VALUES
+				(1, 'CVE-2024-0001', 'Buffer overflow', 'High');
+			 INSERT INTO vulnerability_references (vulnerability_id, url) VALUES (1, 'https://a.example');"
+		)?;
+		let import = |rows: &str| {
+			let path = dir.path().join("import.csv");
+			std::fs::write(&path, format!("Name,Status,Description,References,Phase,Votes,Comments\n{}", rows)).unwrap();
+			let path = path.to_string_lossy().into_owned();
+			import_vulnerabilities_from_csv(path, pool.clone(), CsvMapping::default(), ProgressReporter::disabled())
+		};
+		let description = |cve_id: &'static str| {
+			let conn = pool.get().unwrap();
+			conn.query_row("SELECT description FROM vulnerabilities WHERE cve_id = ?1", [cve_id], |row| row.get::<_, String>(0))
+				.optional()
+				.unwrap()
+		};
+		import(
+			"CVE-2024-0001,Entry,Wrong column,URL:https://b.example,,,\n\
+			 CVE-2024-0002,Entry,Use after free,,,,\n",
+		).await?;
+		import("CVE-2024-0002,Entry,Second import,,,,\n").await?;
+
+		let repo = ImportRunRepository::new(pool.clone());
+		let runs = repo.get_runs().await?;
+		assert_eq!(runs.iter().map(|run| (run.run_id, run.created, run.updated)).collect::<Vec<_>>(), [(2, 0, 1), (1, 1, 1)]);
+		assert_eq!(runs[1].status, ImportRunStatus::Completed);
+
+		let err = repo.revert(1).await.unwrap_err();
+		assert!(err.to_string().contains("Import run 2 changed"), "{}", err);
+		assert_eq!(repo.revert(2).await?, RevertSummary { removed: 0, restored: 1, ..Default::default() });
+		assert_eq!(description("CVE-2024-0002").as_deref(), Some("Use after free"));
+		assert_eq!(repo.revert(1).await?, RevertSummary { removed: 1, restored: 1, ..Default::default() });
+		assert_eq!(description("CVE-2024-0001").as_deref(), Some("Buffer overflow"));
+		assert_eq!(description("CVE-2024-0002"), None);
+		let urls: Vec<String> = pool.get()?
+			.prepare("SELECT url FROM vulnerability_references")?
+			.query_map([], |row| row.get(0))?
+			.collect::<rusqlite::Result<_>>()?;
+		assert_eq!(urls, ["https://a.example"]);
+
+		assert_eq!(repo.get_runs().await?[1].status, ImportRunStatus::Reverted);
+		assert!(repo.revert(1).await.is_err());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_revert_robot_import() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO robots (robot_id, name, manufacturer, model, specifications, operational_note, criticality,
+				firmware_version, os) VALUES
+				(1, 'Arm-01', 'KUKA', 'KR 5', '6 axis', 'Cell 4 line', 'Low', '1.0', 'VxWorks'),
+				(2, 'Cobot-1', 'UR', 'UR5e', NULL, NULL, 'High', NULL, NULL);
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'firmware', 'KUKA'), (2, 'polyscope', 'UR');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, '1.0'), (2, 2, '5.11');
+			 INSERT INTO robot_software (robot_id, version_id, installed_date) VALUES
+				(1, 1, '2024-01-02T00:00:00Z'), (2, 2, '2024-03-04T00:00:00Z');",
+		)?;
+		let conn = pool.get()?;
+		let before_updated = robot_state(&conn, 1, false)?;
+		let before_untouched = robot_state(&conn, 2, true)?;
+
+		let path = dir.path().join("fleet.csv");
+		std::fs::write(
+			&path,
+			"Name,Manufacturer,Model,Software,Criticality,Operational_note\n\
+			 Arm-01,KUKA,KR 6,firmware 2.0;OSRF/ros-core 1.0,Critical,Cell 5 line\n\
+			 AGV-07,MiR,MiR250,firmware 3.1,,\n",
+		)?;
+		let summary = import_robots(path, pool.clone()).await?;
+		assert_eq!((summary.created, summary.updated), (1, 1));
+		let created: i64 = conn.query_row("SELECT robot_id FROM robots WHERE name = 'AGV-07'", [], |row| row.get(0))?;
+		let installed = |robot_id: i64| -> Result<i64> {
+			Ok(conn.query_row("SELECT COUNT(*) FROM robot_software WHERE robot_id = ?1", [robot_id], |row| row.get(0))?)
+		};
+		assert_eq!(installed(created)?, 1);
+		assert_ne!(robot_state(&conn, 1, false)?, before_updated);
+
+		let repo = ImportRunRepository::new(pool.clone());
+		let run_id = repo.get_runs().await?[0].run_id;
+		let reverted = repo.revert(run_id).await?;
+		assert_eq!((reverted.robots_removed, reverted.robots_restored), (1, 1));
+
+		// The created robot is gone with its software
+		let exists: bool = conn.query_row("SELECT EXISTS (SELECT 1 FROM robots WHERE robot_id = ?1)", [created], |row| row.get(0))?;
+		assert!(!exists);
+		assert_eq!(installed(created)?, 0);
+		// The updated robot has its fields and installed software from before the run
+		assert_eq!(robot_state(&conn, 1, false)?, before_updated);
+		// The robot the run did not list is left as it was
+		assert_eq!(robot_state(&conn, 2, true)?, before_untouched);
+		Ok(())
+	}
+}
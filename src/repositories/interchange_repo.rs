@@ -0,0 +1,511 @@
+// src/repositories/interchange_repo.rs
+
+use crate::db::connection::{self, SqlitePool};
+use crate::repositories::{access, audit_repo};
+use crate::models::interchange::{
+	InterchangeAssessment, InterchangeCorrelation, InterchangeDocument, InterchangeImportSummary,
+	InterchangeProduct, InterchangeRobot, InterchangeVersion, NewProduct, SoftwareRef,
+};
+use crate::models::note::NoteEntity;
+use crate::models::robot::InventorySource;
+use crate::models::vulnerability::TriageStatus;
+use crate::repositories::robot_repo::mark_inventory_refreshed;
+use crate::repositories::software_repo::refresh_robot_correlations;
+use crate::utils::product_match::{self, KnownProduct};
+use crate::utils::progress::ProgressReporter;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::Arc;
+use anyhow::{Result, Context};
+use tokio::task;
+
+pub struct InterchangeRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl InterchangeRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Export the fleet inventory, correlations and assessments. Cancelling `progress`
+	/// abandons the export between sections.
+	pub async fn export(&self, progress: ProgressReporter) -> Result<InterchangeDocument> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let tracker = progress.start("Fleet export");
+
+			let mut document = InterchangeDocument::new(chrono::Utc::now().to_rfc3339());
+			document.software = export_software(&conn)?;
+			let mut exported = document.software.len();
+			tracker.check_cancelled()?;
+			tracker.update(exported, 0.25);
+
+			document.robots = export_robots(&conn)?;
+			exported += document.robots.len();
+			tracker.check_cancelled()?;
+			tracker.update(exported, 0.5);
+
+			document.correlations = export_correlations(&conn)?;
+			exported += document.correlations.len();
+			tracker.check_cancelled()?;
+			tracker.update(exported, 0.75);
+
+			document.assessments = export_assessments(&conn)?;
+			tracker.finish(exported + document.assessments.len());
+			Ok(document)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Merge a document into this database in a single transaction.
+	///
+	/// Existing records are matched by natural key and updated; CVEs that are not known
+	/// yet are created as placeholders for NVD enrichment to fill in. Products without an
+	/// exact match are created too and listed in the summary with similar existing products.
+	pub async fn import(&self, document: InterchangeDocument) -> Result<InterchangeImportSummary> {
+		access::require_write_access()?;
+		document.validate()?;
+
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction().context("Failed to start database transaction")?;
+			let mut summary = InterchangeImportSummary::default();
+			let known = known_products(&tx)?;
+			let mut changed_robots = Vec::new();
+
+			for product in &document.software {
+				for version in &product.versions {
+					let software = SoftwareRef {
+						product_name: product.product_name.clone(),
+						vendor: product.vendor.clone(),
+						version_number: version.version_number.clone(),
+					};
+					let version_id = ensure_version(&tx, &software, &known, &mut summary.new_products)?;
+					if version.release_date.is_some() {
+						tx.execute(
+							"UPDATE software_versions SET release_date = ?1 WHERE version_id = ?2 AND release_date IS NULL",
+							params![version.release_date, version_id],
+						)?;
+					}
+					summary.software_versions += 1;
+				}
+				if product.description.is_some() {
+					tx.execute(
+						"UPDATE software_products SET description = COALESCE(description, ?1)
+						 WHERE product_name = ?2 AND vendor = ?3",
+						params![product.description, product.product_name, product.vendor],
+					)?;
+				}
+				// The license of the latest import wins, as products get relicensed
+				if product.license.is_some() {
+					tx.execute(
+						"UPDATE software_products SET license = ?1 WHERE product_name = ?2 AND vendor = ?3",
+						params![product.license, product.product_name, product.vendor],
+					)?;
+				}
+			}
+
+			for robot in &document.robots {
+				let existing: Option<i64> = tx.query_row(
+					"SELECT robot_id FROM robots WHERE name = ?1 AND manufacturer IS ?2 AND deleted_at IS NULL",
+					params![robot.name, robot.manufacturer],
+					|row| row.get(0),
+				).optional()?;
+
+				let robot_id = match existing {
+					Some(id) => {
+						tx.execute(
+							"UPDATE robots SET specifications = COALESCE(?1, specifications),
+							 operational_note = COALESCE(?2, operational_note), updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+							 WHERE robot_id = ?3",
+							params![robot.specifications, robot.operational_note, id],
+						)?;
+						summary.robots_updated += 1;
+						id
+					}
+					None => {
+						tx.execute(
+							"INSERT INTO robots (name, manufacturer, specifications, operational_note) VALUES (?1, ?2, ?3, ?4)",
+							params![robot.name, robot.manufacturer, robot.specifications, robot.operational_note],
+						)?;
+						summary.robots_created += 1;
+						tx.last_insert_rowid()
+					}
+				};
+
+				for software in &robot.installed_software {
+					let version_id = ensure_version(&tx, software, &known, &mut summary.new_products)?;
+					let installed = tx.execute(
+						"INSERT OR IGNORE INTO robot_software (robot_id, version_id) VALUES (?1, ?2)",
+						params![robot_id, version_id],
+					)?;
+					if installed > 0 && !changed_robots.contains(&robot_id) {
+						changed_robots.push(robot_id);
+					}
+				}
+				if !robot.installed_software.is_empty() {
+					mark_inventory_refreshed(&tx, robot_id, InventorySource::FleetImport)?;
+				}
+				for body in &robot.notes {
+					summary.notes += add_note_once(&tx, NoteEntity::Robot, robot_id, body)? as usize;
+				}
+			}
+
+			for correlation in &document.correlations {
+				let vulnerability_id = ensure_vulnerability(&tx, &correlation.cve_id)?;
+				let version_id = ensure_version(&tx, &correlation.software, &known, &mut summary.new_products)?;
+				tx.execute(
+					"INSERT OR REPLACE INTO affected_software
+						(vulnerability_id, version_id, affected_version_pattern, fixed_in_version, detection_confidence)
+					 VALUES (?1, ?2, ?3, ?4, ?5)",
+					params![
+						vulnerability_id,
+						version_id,
+						correlation.affected_version_pattern,
+						correlation.fixed_in_version,
+						correlation.detection_confidence,
+					],
+				)?;
+				summary.correlations += 1;
+			}
+
+			// Correlations of the document are in place, so new installs can match them too
+			for robot_id in changed_robots {
+				summary.derived_correlations += refresh_robot_correlations(&tx, robot_id)?;
+			}
+
+			for assessment in &document.assessments {
+				let vulnerability_id = ensure_vulnerability(&tx, &assessment.cve_id)?;
+				tx.execute(
+					"INSERT INTO vulnerability_status (vulnerability_id, status, assigned_to) VALUES (?1, ?2, ?3)
+					 ON CONFLICT(vulnerability_id) DO UPDATE SET
+						status = excluded.status,
+						assigned_to = excluded.assigned_to,
+						updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+					params![
+						vulnerability_id,
+						TriageStatus::from_db(&assessment.status).as_str(),
+						assessment.assigned_to,
+					],
+				)?;
+				for body in &assessment.notes {
+					summary.notes += add_note_once(&tx, NoteEntity::Vulnerability, vulnerability_id, body)? as usize;
+				}
+				summary.assessments += 1;
+			}
+
+			audit_repo::record_import(&tx, "Interchange document", &format!(
+				"{} software versions, {} robots created, {} updated, {} correlations, {} assessments, {} notes",
+				summary.software_versions,
+				summary.robots_created,
+				summary.robots_updated,
+				summary.correlations,
+				summary.assessments,
+				summary.notes,
+			))?;
+			tx.commit().context("Failed to commit interchange import")?;
+			Ok(summary)
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+fn export_software(conn: &Connection) -> Result<Vec<InterchangeProduct>> {
+	let mut products_stmt = conn.prepare(
+		"SELECT product_id, product_name, vendor, description, license FROM software_products ORDER BY vendor, product_name"
+	)?;
+	let mut versions_stmt = conn.prepare(
+		"SELECT version_number, release_date FROM software_versions WHERE product_id = ?1 ORDER BY version_number"
+	)?;
+
+	let products = products_stmt
+		.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))?
+		.collect::<rusqlite::Result<Vec<(i64, String, String, Option<String>, Option<String>)>>>()?;
+
+	products
+		.into_iter()
+		.map(|(product_id, product_name, vendor, description, license)| {
+			let versions = versions_stmt
+				.query_map([product_id], |row| {
+					Ok(InterchangeVersion { version_number: row.get(0)?, release_date: row.get(1)? })
+				})?
+				.collect::<rusqlite::Result<Vec<_>>>()?;
+			Ok(InterchangeProduct { product_name, vendor, description, license, versions })
+		})
+		.collect()
+}
+
+fn export_robots(conn: &Connection) -> Result<Vec<InterchangeRobot>> {
+	let mut robots_stmt = conn.prepare(
+		"SELECT robot_id, name, manufacturer, specifications, operational_note FROM robots
+		 WHERE deleted_at IS NULL ORDER BY name, robot_id"
+	)?;
+	let mut software_stmt = conn.prepare(
+		"SELECT p.product_name, p.vendor, sv.version_number
+		 FROM robot_software rs
+		 JOIN software_versions sv ON sv.version_id = rs.version_id
+		 JOIN software_products p ON p.product_id = sv.product_id
+		 WHERE rs.robot_id = ?1
+		 ORDER BY p.vendor, p.product_name, sv.version_number"
+	)?;
+
+	let robots = robots_stmt
+		.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))?
+		.collect::<rusqlite::Result<Vec<(i64, String, Option<String>, Option<String>, Option<String>)>>>()?;
+
+	robots
+		.into_iter()
+		.map(|(robot_id, name, manufacturer, specifications, operational_note)| {
+			let installed_software = software_stmt
+				.query_map([robot_id], software_ref_from_row)?
+				.collect::<rusqlite::Result<Vec<_>>>()?;
+			Ok(InterchangeRobot {
+				name,
+				manufacturer,
+				specifications,
+				operational_note,
+				installed_software,
+				notes: note_bodies(conn, NoteEntity::Robot, robot_id)?,
+			})
+		})
+		.collect()
+}
+
+fn export_correlations(conn: &Connection) -> Result<Vec<InterchangeCorrelation>> {
+	let mut stmt = conn.prepare(
+		"SELECT p.product_name, p.vendor, sv.version_number, v.cve_id,
+			af.affected_version_pattern, af.fixed_in_version, af.detection_confidence
+		 FROM affected_software af
+		 JOIN vulnerabilities v ON v.vulnerability_id = af.vulnerability_id
+		 JOIN software_versions sv ON sv.version_id = af.version_id
+		 JOIN software_products p ON p.product_id = sv.product_id
+		 WHERE v.deleted_at IS NULL
+		 ORDER BY v.cve_id, p.vendor, p.product_name, sv.version_number"
+	)?;
+
+	let correlations = stmt
+		.query_map([], |row| {
+			Ok(InterchangeCorrelation {
+				software: software_ref_from_row(row)?,
+				cve_id: row.get(3)?,
+				affected_version_pattern: row.get(4)?,
+				fixed_in_version: row.get(5)?,
+				detection_confidence: row.get(6)?,
+			})
+		})?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+	Ok(correlations)
+}
+
+/// Only CVEs that were actually triaged or commented on are exported as assessments
+fn export_assessments(conn: &Connection) -> Result<Vec<InterchangeAssessment>> {
+	let mut stmt = conn.prepare(
+		"SELECT v.vulnerability_id, v.cve_id, COALESCE(s.status, 'Open'), s.assigned_to
+		 FROM vulnerabilities v
+		 LEFT JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id
+		 WHERE v.deleted_at IS NULL AND (
+			s.vulnerability_id IS NOT NULL
+			OR EXISTS (
+				SELECT 1 FROM notes n
+				WHERE n.entity_type = 'vulnerability' AND n.entity_id = v.vulnerability_id
+			)
+		 )
+		 ORDER BY v.cve_id"
+	)?;
+
+	let rows = stmt
+		.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+		.collect::<rusqlite::Result<Vec<(i64, String, String, Option<String>)>>>()?;
+
+	rows
+		.into_iter()
+		.map(|(vulnerability_id, cve_id, status, assigned_to)| {
+			Ok(InterchangeAssessment {
+				cve_id,
+				status,
+				assigned_to,
+				notes: note_bodies(conn, NoteEntity::Vulnerability, vulnerability_id)?,
+			})
+		})
+		.collect()
+}
+
+fn software_ref_from_row(row: &rusqlite::Row) -> rusqlite::Result<SoftwareRef> {
+	Ok(SoftwareRef {
+		product_name: row.get(0)?,
+		vendor: row.get(1)?,
+		version_number: row.get(2)?,
+	})
+}
+
+pub(crate) fn note_bodies(conn: &Connection, entity: NoteEntity, entity_id: i64) -> Result<Vec<String>> {
+	let mut stmt = conn.prepare_cached(
+		"SELECT body FROM notes WHERE entity_type = ?1 AND entity_id = ?2 ORDER BY created_at, note_id"
+	)?;
+	let bodies = stmt
+		.query_map(params![entity.as_str(), entity_id], |row| row.get(0))?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+	Ok(bodies)
+}
+
+/// Look up a software version by natural key, creating product and version when missing
+/// Number of similar existing products listed for each product an import creates
+const MAX_SUGGESTIONS: usize = 3;
+
+fn known_products(conn: &Connection) -> Result<Vec<KnownProduct>> {
+	let mut stmt = conn.prepare("SELECT product_id, product_name, vendor FROM software_products")?;
+	let products = stmt
+		.query_map([], |row| Ok(KnownProduct {
+			product_id: row.get(0)?,
+			product_name: row.get(1)?,
+			vendor: row.get(2)?,
+		}))?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+	Ok(products)
+}
+
+/// Resolve a software version, creating its product and version when missing. A newly
+/// created product is recorded in `new_products` with its closest `known` products.
+fn ensure_version(
+	conn: &Connection,
+	software: &SoftwareRef,
+	known: &[KnownProduct],
+	new_products: &mut Vec<NewProduct>,
+) -> Result<i64> {
+	let created = conn.execute(
+		"INSERT OR IGNORE INTO software_products (product_name, vendor) VALUES (?1, ?2)",
+		params![software.product_name, software.vendor],
+	)?;
+	if created > 0 {
+		new_products.push(NewProduct {
+			product_name: software.product_name.clone(),
+			vendor: software.vendor.clone(),
+			suggestions: product_match::suggest(&software.product_name, &software.vendor, known, MAX_SUGGESTIONS),
+		});
+	}
+	let product_id: i64 = conn.query_row(
+		"SELECT product_id FROM software_products WHERE product_name = ?1 AND vendor = ?2",
+		params![software.product_name, software.vendor],
+		|row| row.get(0),
+	)?;
+
+	conn.execute(
+		"INSERT OR IGNORE INTO software_versions (product_id, version_number) VALUES (?1, ?2)",
+		params![product_id, software.version_number],
+	)?;
+	conn.query_row(
+		"SELECT version_id FROM software_versions WHERE product_id = ?1 AND version_number = ?2",
+		params![product_id, software.version_number],
+		|row| row.get(0),
+	).with_context(|| format!("Failed to resolve software version {:?}", software))
+}
+
+/// Look up a vulnerability by CVE ID, creating an unknown-severity placeholder when missing
+fn ensure_vulnerability(conn: &Connection, cve_id: &str) -> Result<i64> {
+	conn.execute(
+		"INSERT OR IGNORE INTO vulnerabilities (cve_id, severity) VALUES (?1, 'Unknown')",
+		[cve_id],
+	)?;
+	conn.query_row(
+		"SELECT vulnerability_id FROM vulnerabilities WHERE cve_id = ?1",
+		[cve_id],
+		|row| row.get(0),
+	).with_context(|| format!("Failed to resolve vulnerability {}", cve_id))
+}
+
+/// Add a note unless the record already has one with the same text, so re-imports stay idempotent
+pub(crate) fn add_note_once(conn: &Connection, entity: NoteEntity, entity_id: i64, body: &str) -> Result<bool> {
+	let inserted = conn.execute(
+		"INSERT INTO notes (entity_type, entity_id, body)
+		 SELECT ?1, ?2, ?3
+		 WHERE NOT EXISTS (SELECT 1 FROM notes WHERE entity_type = ?1 AND entity_id = ?2 AND body = ?3)",
+		params![entity.as_str(), entity_id, body.trim()],
+	)?;
+	Ok(inserted > 0)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_export_import_round_trip() -> Result<()> {
+		let dir = tempdir()?;
+		let source = Arc::new(connection::establish_pool_with_path(dir.path().join("source.db"))?);
+		source.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity) VALUES (1, 'CVE-2024-0001', 'High');
+			 INSERT INTO vulnerability_status (vulnerability_id, status, assigned_to) VALUES (1, 'In Progress', 'alice');
+			 INSERT INTO notes (entity_type, entity_id, body) VALUES ('vulnerability', 1, 'Vendor patch pending');
+			 INSERT INTO robots (robot_id, name, manufacturer) VALUES (1, 'arm-01', 'KUKA');
+			 INSERT INTO notes (entity_type, entity_id, body) VALUES ('robot', 1, 'Cell 4');
+			 INSERT INTO software_products (product_id, product_name, vendor, license) VALUES (1, 'ros', 'OSRF', 'Apache-2.0');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, 'humble');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 1);
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES (1, 1, '<= humble');"
+		)?;
+
+		let document = InterchangeRepository::new(source).export(ProgressReporter::disabled()).await?;
+		assert_eq!(document.robots[0].installed_software[0].version_number, "humble");
+		assert_eq!(document.assessments[0].notes, vec!["Vendor patch pending".to_string()]);
+
+		// Round trip through JSON into an empty instance
+		let json = serde_json::to_string(&document)?;
+		let target = Arc::new(connection::establish_pool_with_path(dir.path().join("target.db"))?);
+		let repo = InterchangeRepository::new(target.clone());
+		let summary = repo.import(serde_json::from_str(&json)?).await?;
+		assert_eq!(summary.robots_created, 1);
+		assert_eq!(summary.correlations, 1);
+		assert_eq!(summary.notes, 2);
+		assert_eq!(summary.new_products.len(), 1);
+		assert!(summary.new_products[0].suggestions.is_empty());
+
+		let (status, severity): (String, String) = target.get()?.query_row(
+			"SELECT s.status, v.severity FROM vulnerabilities v
+			 JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id
+			 WHERE v.cve_id = 'CVE-2024-0001'",
+			[],
+			|row| Ok((row.get(0)?, row.get(1)?)),
+		)?;
+		assert_eq!(status, "In Progress");
+		assert_eq!(severity, "Unknown");
+		let license: Option<String> = target.get()?.query_row(
+			"SELECT license FROM software_products WHERE product_name = 'ros'",
+			[],
+			|row| row.get(0),
+		)?;
+		assert_eq!(license.as_deref(), Some("Apache-2.0"));
+
+		// Importing again updates in place instead of duplicating
+		let summary = repo.import(serde_json::from_str(&json)?).await?;
+		assert_eq!(summary.robots_created, 0);
+		assert_eq!(summary.robots_updated, 1);
+		assert_eq!(summary.notes, 0);
+		assert!(summary.new_products.is_empty());
+		assert_eq!(summary.derived_correlations, 0);
+
+		// A newly installed version inside the affected range is correlated right away
+		let mut upgraded: InterchangeDocument = serde_json::from_str(&json)?;
+		upgraded.robots[0].installed_software[0].version_number = "foxy".to_string();
+		upgraded.correlations.clear();
+		let summary = repo.import(upgraded).await?;
+		assert_eq!(summary.derived_correlations, 1);
+
+		// A differently spelled product is created, with the existing one suggested
+		let mut respelled: InterchangeDocument = serde_json::from_str(&json)?;
+		respelled.software[0].product_name = "ROS".to_string();
+		let summary = repo.import(respelled).await?;
+		assert_eq!(summary.new_products.len(), 1);
+		assert_eq!(summary.new_products[0].suggestions[0].product_name, "ros");
+
+		let mut newer: InterchangeDocument = serde_json::from_str(&json)?;
+		newer.version += 1;
+		assert!(repo.import(newer).await.is_err());
+
+		Ok(())
+	}
+}
@@ -0,0 +1,152 @@
+// src/repositories/metrics_repo.rs
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::robot::Criticality;
+use crate::models::trends::{open_criticals_metric, FleetTrends, MEAN_RISK_METRIC};
+use crate::repositories::access;
+use crate::repositories::vulnerability_repo::{unresolved_status_sql, STATUS_JOIN};
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::task;
+
+/// Days of history the Trends tab plots
+pub const TREND_DAYS: i64 = 365;
+
+/// Store today's open critical vulnerabilities per robot criticality and the mean
+/// robot risk score, replacing earlier values from the same day. Nothing is recorded
+/// without robots.
+pub fn record_daily_metrics(conn: &Connection) -> Result<()> {
+	let robots: i64 = conn.query_row("SELECT COUNT(*) FROM robots WHERE deleted_at IS NULL", [], |row| row.get(0))?;
+	if robots == 0 {
+		return Ok(());
+	}
+
+	let mut stmt = conn.prepare(&format!(
+		"SELECT DISTINCT r.criticality, v.vulnerability_id
+		 FROM robot_software rs
+		 JOIN robots r ON r.robot_id = rs.robot_id
+		 JOIN affected_software af ON af.version_id = rs.version_id
+		 JOIN vulnerabilities v ON v.vulnerability_id = af.vulnerability_id
+		 {}
+		 WHERE r.deleted_at IS NULL AND v.deleted_at IS NULL AND UPPER(v.severity) = 'CRITICAL' AND {}",
+		STATUS_JOIN, unresolved_status_sql()
+	))?;
+	let mut open: HashMap<Criticality, HashSet<i64>> = HashMap::new();
+	let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))?;
+	for row in rows {
+		let (group, vulnerability_id) = row?;
+		open.entry(Criticality::parse(&group)).or_default().insert(vulnerability_id);
+	}
+	let mean_risk: f64 = conn.query_row(
+		"SELECT AVG(COALESCE(risk_score, 0)) FROM robots WHERE deleted_at IS NULL",
+		[],
+		|row| row.get(0),
+	)?;
+
+	let mut record = conn.prepare(
+		"INSERT INTO metrics_history (recorded_on, metric, value) VALUES (date('now'), ?1, ?2)
+		 ON CONFLICT (recorded_on, metric) DO UPDATE SET value = excluded.value",
+	)?;
+	for group in Criticality::ALL {
+		let count = open.get(&group).map_or(0, HashSet::len);
+		record.execute(params![open_criticals_metric(group), count as f64])?;
+	}
+	record.execute(params![MEAN_RISK_METRIC, mean_risk]).context("Failed to record daily metrics")?;
+	Ok(())
+}
+
+/// Metrics recorded over the last `days` days, oldest first
+fn fleet_trends(conn: &Connection, days: i64) -> Result<FleetTrends> {
+	let rows = conn
+		.prepare(
+			"SELECT recorded_on, metric, value FROM metrics_history
+			 WHERE recorded_on >= date('now', ?1)
+			 ORDER BY recorded_on",
+		)?
+		.query_map([format!("-{} days", days)], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+		.collect::<rusqlite::Result<Vec<_>>>()
+		.context("Failed to read the metrics history")?;
+	Ok(FleetTrends::from_rows(rows))
+}
+
+pub struct MetricsRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl MetricsRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Records today's exposure metrics; run by the update scheduler
+	pub async fn record_today(&self) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			connection::with_write_retry(&pool, |conn| {
+				let tx = conn.transaction()?;
+				record_daily_metrics(&tx)?;
+				tx.commit()?;
+				Ok(())
+			})
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	pub async fn get_trends(&self, days: i64) -> Result<FleetTrends> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			fleet_trends(&conn, days)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_record_daily_metrics() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let repo = MetricsRepository::new(pool.clone());
+		let conn = pool.get()?;
+		// Nothing to record without robots
+		record_daily_metrics(&conn)?;
+		assert!(repo.get_trends(TREND_DAYS).await?.is_empty());
+
+		conn.execute_batch(
+			"INSERT INTO robots (robot_id, name, criticality, risk_score) VALUES
+				(1, 'arm-01', 'Critical', 80.0), (2, 'arm-02', 'Critical', 40.0), (3, 'cart-01', 'Low', 0.0);
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ros-core', 'OSRF');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, '1.0');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 1), (2, 1);
+			 INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity) VALUES
+				(1, 'CVE-2024-0001', 'Critical'), (2, 'CVE-2024-0002', 'High'), (3, 'CVE-2024-0003', 'CRITICAL');
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES
+				(1, 1, '1.0'), (2, 1, '1.0'), (3, 1, '1.0');
+			 INSERT INTO vulnerability_status (vulnerability_id, status) VALUES (3, 'Mitigated');
+			 INSERT INTO metrics_history (recorded_on, metric, value) VALUES ('2000-01-01', 'mean_risk_score', 90.0);",
+		)?;
+		record_daily_metrics(&conn)?;
+		// Recording again the same day replaces the values
+		record_daily_metrics(&conn)?;
+
+		let trends = repo.get_trends(TREND_DAYS).await?;
+		let counts: Vec<(&str, f64)> = trends.open_criticals
+			.iter()
+			.map(|series| (series.label.as_str(), series.points[0].value))
+			.collect();
+		assert_eq!(counts, [("Critical", 1.0), ("High", 0.0), ("Medium", 0.0), ("Low", 0.0)]);
+		assert_eq!(trends.mean_risk_score.points.len(), 1);
+		assert_eq!(trends.mean_risk_score.points[0].value, 40.0);
+		Ok(())
+	}
+}
@@ -1,6 +1,29 @@
 // src/repositories/mod.rs
 
+pub mod access;
+pub mod alias_repo;
+pub mod audit_repo;
+pub mod commissioning_repo;
+pub mod compliance_repo;
+pub mod data_source_repo;
+pub mod alert_repo;
+pub mod enrichment_repo;
+pub mod graph_repo;
+pub mod import_run_repo;
+pub mod metrics_repo;
+pub mod interchange_repo;
+pub mod note_repo;
+pub mod offline_bundle_repo;
+pub(crate) mod provenance_repo;
+pub mod reference_repo;
 pub mod robot_repo;
+pub mod settings_repo;
+pub mod snapshot_repo;
+pub mod statistics_repo;
+pub mod sync_delta_repo;
+pub mod ticket_repo;
+pub mod trash_repo;
 pub mod vulnerability_repo;
 mod software;
-mod software_repo;
+pub mod software_repo;
+pub(crate) mod weakness_repo;
@@ -0,0 +1,152 @@
+// src/repositories/note_repo.rs
+
+use crate::db::connection::{self, SqlitePool};
+use crate::repositories::{access, audit_repo};
+use crate::models::audit::{AuditAction, AuditEntity};
+use crate::models::note::{Note, NoteEntity};
+use rusqlite::params;
+use std::sync::Arc;
+use anyhow::{Result, Context};
+use crate::utils::time;
+use tokio::task;
+
+pub struct NoteRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl NoteRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Add a note and return its ID
+	pub async fn add_note(&self, note: Note) -> Result<i64> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			tx.execute(
+				"INSERT INTO notes (entity_type, entity_id, body) VALUES (?1, ?2, ?3)",
+				params![note.entity_type.as_str(), note.entity_id, note.body.trim()],
+			).context("Failed to insert note")?;
+
+			let id = tx.last_insert_rowid();
+			audit_repo::record_change(&tx, AuditEntity::Note, id, AuditAction::Insert, None)?;
+			tx.commit()?;
+			Ok(id)
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Replace the text of an existing note
+	pub async fn update_note(&self, note_id: i64, body: String) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let before = audit_repo::snapshot(&tx, AuditEntity::Note, note_id)?;
+			let result = tx.execute(
+				"UPDATE notes SET body = ?1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE note_id = ?2",
+				params![body.trim(), note_id],
+			).context("Failed to update note")?;
+
+			if result != 1 {
+				anyhow::bail!("Note not found");
+			}
+			audit_repo::record_change(&tx, AuditEntity::Note, note_id, AuditAction::Update, before)?;
+			tx.commit()?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	pub async fn delete_note(&self, note_id: i64) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let before = audit_repo::snapshot(&tx, AuditEntity::Note, note_id)?;
+			let result = tx.execute("DELETE FROM notes WHERE note_id = ?1", [note_id])
+				.context("Failed to delete note")?;
+
+			if result != 1 {
+				anyhow::bail!("Note not found");
+			}
+			audit_repo::record_change(&tx, AuditEntity::Note, note_id, AuditAction::Delete, before)?;
+			tx.commit()?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Get the notes of one vulnerability or robot, newest first
+	pub async fn get_notes(&self, entity_type: NoteEntity, entity_id: i64) -> Result<Vec<Note>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(
+				"SELECT note_id, entity_id, body, created_at, updated_at
+				 FROM notes
+				 WHERE entity_type = ?1 AND entity_id = ?2
+				 ORDER BY created_at DESC, note_id DESC"
+			)?;
+
+			let note_iter = stmt.query_map(params![entity_type.as_str(), entity_id], |row| {
+				Ok(Note {
+					note_id: row.get(0)?,
+					entity_type,
+					entity_id: row.get(1)?,
+					body: row.get(2)?,
+					created_at: row.get::<_, Option<String>>(3)?
+						.as_deref().and_then(time::parse_utc),
+					updated_at: row.get::<_, Option<String>>(4)?
+						.as_deref().and_then(time::parse_utc),
+				})
+			})?;
+
+			note_iter
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to collect notes")
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_note_lifecycle() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let repo = NoteRepository::new(pool);
+
+		let first = repo.add_note(Note::new(NoteEntity::Robot, 1, "Air-gapped cell".to_string())).await?;
+		repo.add_note(Note::new(NoteEntity::Robot, 1, "  Firmware pinned by vendor  ".to_string())).await?;
+		repo.add_note(Note::new(NoteEntity::Vulnerability, 1, "Not reachable".to_string())).await?;
+
+		// Notes are scoped to their entity and returned newest first
+		let notes = repo.get_notes(NoteEntity::Robot, 1).await?;
+		assert_eq!(notes.len(), 2);
+		assert_eq!(notes[0].body, "Firmware pinned by vendor");
+		assert!(notes[0].created_at.is_some());
+
+		repo.update_note(first, "Air-gapped cell B".to_string()).await?;
+		let notes = repo.get_notes(NoteEntity::Robot, 1).await?;
+		assert!(notes.iter().any(|n| n.body == "Air-gapped cell B"));
+
+		repo.delete_note(first).await?;
+		assert_eq!(repo.get_notes(NoteEntity::Robot, 1).await?.len(), 1);
+		assert!(repo.delete_note(first).await.is_err());
+		assert_eq!(repo.get_notes(NoteEntity::Vulnerability, 1).await?.len(), 1);
+
+		Ok(())
+	}
+}
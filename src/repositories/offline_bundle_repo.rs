@@ -0,0 +1,427 @@
+// src/repositories/offline_bundle_repo.rs
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::note::NoteEntity;
+use crate::models::offline_bundle::{
+	BundleAlias, BundleImportSummary, BundleSource, BundleTriage, BundleVulnerability, OfflineBundle,
+};
+use crate::models::reference::Reference;
+use crate::models::vulnerability::TriageStatus;
+use crate::repositories::interchange_repo::{add_note_once, note_bodies};
+use crate::repositories::reference_repo::insert_references;
+use crate::repositories::robot_repo::refresh_risk_scores;
+use crate::repositories::weakness_repo::insert_weaknesses;
+use crate::repositories::{access, alias_repo, audit_repo};
+use crate::utils::progress::ProgressReporter;
+use crate::utils::time;
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task;
+
+/// Whether a copy modified at `theirs` replaces one modified at `ours`; undated copies
+/// lose to dated ones and a tie keeps ours
+fn is_newer(theirs: Option<&str>, ours: Option<&str>) -> bool {
+	match (theirs.and_then(time::parse_utc), ours.and_then(time::parse_utc)) {
+		(Some(theirs), Some(ours)) => theirs > ours,
+		(Some(_), None) => true,
+		(None, _) => false,
+	}
+}
+
+/// `value` in the stored timestamp format, if it is a timestamp
+fn normalized(value: Option<&str>) -> Option<String> {
+	value.and_then(time::parse_utc).map(|t: DateTime<Utc>| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+pub struct OfflineBundleRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl OfflineBundleRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Export every vulnerability, including those in Recently deleted so the deletion
+	/// carries over, and the sync state of the data sources. Cancelling `progress`
+	/// abandons the export between sections.
+	pub async fn export(&self, progress: ProgressReporter) -> Result<OfflineBundle> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let tracker = progress.start("Offline bundle export");
+
+			let mut bundle = OfflineBundle::new(Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true));
+			bundle.vulnerabilities = export_vulnerabilities(&conn)?;
+			tracker.check_cancelled()?;
+			tracker.update(bundle.vulnerabilities.len(), 0.9);
+
+			bundle.sources = export_sources(&conn)?;
+			tracker.finish(bundle.vulnerabilities.len());
+			Ok(bundle)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Merge a bundle into this database in a single transaction.
+	///
+	/// Unknown vulnerabilities are added. Known ones, matched by CVE ID or alias, take the
+	/// bundle's fields only when its copy was modified later, and triage the same way.
+	/// References, weaknesses, aliases and notes are added to what is here. A data source
+	/// takes the bundle's last run when it ran later on the exporting instance.
+	pub async fn import(&self, bundle: OfflineBundle) -> Result<BundleImportSummary> {
+		access::require_write_access()?;
+		bundle.validate()?;
+
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction().context("Failed to start database transaction")?;
+			let mut summary = BundleImportSummary::default();
+
+			for vulnerability in &bundle.vulnerabilities {
+				merge_vulnerability(&tx, vulnerability, &mut summary)
+					.with_context(|| format!("Failed to merge {}", vulnerability.cve_id))?;
+			}
+			for source in &bundle.sources {
+				summary.sources_updated += merge_source(&tx, source)? as usize;
+			}
+
+			refresh_risk_scores(&tx)?;
+			audit_repo::record_import(
+				&tx,
+				&format!("Offline bundle exported {}", bundle.exported_at),
+				&summary.to_string(),
+			)?;
+			tx.commit().context("Failed to commit offline bundle")?;
+			Ok(summary.clone())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+fn export_vulnerabilities(conn: &Connection) -> Result<Vec<BundleVulnerability>> {
+	let mut references: HashMap<i64, Vec<Reference>> = HashMap::new();
+	let mut stmt = conn.prepare(
+		"SELECT vulnerability_id, url, source, advisory_id, tags FROM vulnerability_references ORDER BY reference_id"
+	)?;
+	let rows = stmt.query_map([], |row| {
+		Ok((row.get::<_, i64>(0)?, Reference {
+			url: row.get(1)?,
+			source: row.get(2)?,
+			advisory_id: row.get(3)?,
+			tags: row.get::<_, Option<String>>(4)?
+				.map(|tags| tags.split(',').map(str::to_string).collect())
+				.unwrap_or_default(),
+		}))
+	})?;
+	for row in rows {
+		let (vulnerability_id, reference) = row?;
+		references.entry(vulnerability_id).or_default().push(reference);
+	}
+
+	let mut weaknesses: HashMap<i64, Vec<String>> = HashMap::new();
+	let mut stmt = conn.prepare("SELECT vulnerability_id, cwe_id FROM vulnerability_weaknesses ORDER BY cwe_id")?;
+	for row in stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?)))? {
+		let (vulnerability_id, cwe_id) = row?;
+		weaknesses.entry(vulnerability_id).or_default().push(cwe_id);
+	}
+
+	let mut aliases: HashMap<i64, Vec<BundleAlias>> = HashMap::new();
+	let mut stmt = conn.prepare("SELECT vulnerability_id, alias, source FROM vulnerability_aliases ORDER BY alias")?;
+	for row in stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, BundleAlias { alias: row.get(1)?, source: row.get(2)? })))? {
+		let (vulnerability_id, alias) = row?;
+		aliases.entry(vulnerability_id).or_default().push(alias);
+	}
+
+	let mut triage: HashMap<i64, BundleTriage> = HashMap::new();
+	let mut stmt = conn.prepare(
+		"SELECT vulnerability_id, status, assigned_to, justification, approved_by, accepted_at, expires_on, updated_at
+		 FROM vulnerability_status"
+	)?;
+	let rows = stmt.query_map([], |row| {
+		Ok((row.get::<_, i64>(0)?, BundleTriage {
+			status: row.get(1)?,
+			assigned_to: row.get(2)?,
+			justification: row.get(3)?,
+			approved_by: row.get(4)?,
+			accepted_at: row.get(5)?,
+			expires_on: row.get(6)?,
+			updated_at: row.get(7)?,
+		}))
+	})?;
+	for row in rows {
+		let (vulnerability_id, status) = row?;
+		triage.insert(vulnerability_id, status);
+	}
+
+	let mut stmt = conn.prepare(
+		"SELECT vulnerability_id, cve_id, description, severity, impact, mitigation, published_date, cvss_score,
+			cvss_version, kev_date_added, epss_score, source, deleted_at, updated_at
+		 FROM vulnerabilities ORDER BY cve_id"
+	)?;
+	let rows = stmt
+		.query_map([], |row| {
+			Ok((row.get::<_, i64>(0)?, BundleVulnerability {
+				cve_id: row.get(1)?,
+				description: row.get(2)?,
+				severity: row.get(3)?,
+				impact: row.get(4)?,
+				mitigation: row.get(5)?,
+				published_date: row.get(6)?,
+				cvss_score: row.get(7)?,
+				cvss_version: row.get(8)?,
+				kev_date_added: row.get(9)?,
+				epss_score: row.get(10)?,
+				source: row.get(11)?,
+				deleted_at: row.get(12)?,
+				updated_at: row.get(13)?,
+				references: Vec::new(),
+				weaknesses: Vec::new(),
+				aliases: Vec::new(),
+				triage: None,
+				notes: Vec::new(),
+			}))
+		})?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+
+	rows
+		.into_iter()
+		.map(|(vulnerability_id, mut vulnerability)| {
+			vulnerability.references = references.remove(&vulnerability_id).unwrap_or_default();
+			vulnerability.weaknesses = weaknesses.remove(&vulnerability_id).unwrap_or_default();
+			vulnerability.aliases = aliases.remove(&vulnerability_id).unwrap_or_default();
+			vulnerability.triage = triage.remove(&vulnerability_id);
+			vulnerability.notes = note_bodies(conn, NoteEntity::Vulnerability, vulnerability_id)?;
+			Ok(vulnerability)
+		})
+		.collect()
+}
+
+fn export_sources(conn: &Connection) -> Result<Vec<BundleSource>> {
+	let sources = conn
+		.prepare("SELECT name, last_run_at, last_status, last_message FROM data_sources ORDER BY source_id")?
+		.query_map([], |row| {
+			Ok(BundleSource {
+				name: row.get(0)?,
+				last_run_at: row.get(1)?,
+				last_status: row.get(2)?,
+				last_message: row.get(3)?,
+			})
+		})?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+	Ok(sources)
+}
+
+fn merge_vulnerability(conn: &Connection, vulnerability: &BundleVulnerability, summary: &mut BundleImportSummary) -> Result<()> {
+	let updated_at = normalized(vulnerability.updated_at.as_deref());
+	let fields = params![
+		vulnerability.cve_id,
+		vulnerability.description,
+		vulnerability.severity,
+		vulnerability.impact,
+		vulnerability.mitigation,
+		vulnerability.published_date,
+		vulnerability.cvss_score,
+		vulnerability.cvss_version,
+		vulnerability.kev_date_added,
+		vulnerability.epss_score,
+		vulnerability.source,
+		vulnerability.deleted_at,
+		updated_at,
+	];
+
+	let vulnerability_id = match alias_repo::resolve(conn, &vulnerability.cve_id)? {
+		None => {
+			conn.execute(
+				"INSERT INTO vulnerabilities (cve_id, description, severity, impact, mitigation, published_date,
+					cvss_score, cvss_version, kev_date_added, epss_score, source, deleted_at, updated_at)
+				 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+				fields,
+			)?;
+			summary.created += 1;
+			conn.last_insert_rowid()
+		}
+		Some(vulnerability_id) => {
+			let ours: Option<String> = conn.query_row(
+				"SELECT updated_at FROM vulnerabilities WHERE vulnerability_id = ?1",
+				[vulnerability_id],
+				|row| row.get(0),
+			)?;
+			if is_newer(updated_at.as_deref(), ours.as_deref()) {
+				// The stored ID stays, in case this instance keeps the entry under another alias
+				conn.execute(
+					"UPDATE vulnerabilities SET description = ?1, severity = ?2, impact = ?3, mitigation = ?4,
+						published_date = ?5, cvss_score = ?6, cvss_version = ?7, kev_date_added = ?8,
+						epss_score = ?9, source = ?10, deleted_at = ?11, updated_at = ?12
+					 WHERE vulnerability_id = ?13",
+					params![
+						vulnerability.description,
+						vulnerability.severity,
+						vulnerability.impact,
+						vulnerability.mitigation,
+						vulnerability.published_date,
+						vulnerability.cvss_score,
+						vulnerability.cvss_version,
+						vulnerability.kev_date_added,
+						vulnerability.epss_score,
+						vulnerability.source,
+						vulnerability.deleted_at,
+						updated_at,
+						vulnerability_id,
+					],
+				)?;
+				summary.updated += 1;
+			} else {
+				summary.unchanged += 1;
+			}
+			vulnerability_id
+		}
+	};
+
+	let cve_id: String = conn.query_row(
+		"SELECT cve_id FROM vulnerabilities WHERE vulnerability_id = ?1",
+		[vulnerability_id],
+		|row| row.get(0),
+	)?;
+	insert_references(conn, &cve_id, &vulnerability.references)?;
+	insert_weaknesses(conn, &cve_id, &vulnerability.weaknesses)?;
+	for alias in &vulnerability.aliases {
+		if alias_repo::resolve(conn, &alias.alias)? != Some(vulnerability_id) {
+			alias_repo::link_alias(conn, &cve_id, &alias.alias, &alias.source)?;
+		}
+	}
+	for body in &vulnerability.notes {
+		add_note_once(conn, NoteEntity::Vulnerability, vulnerability_id, body)?;
+	}
+
+	if let Some(triage) = &vulnerability.triage {
+		let ours: Option<String> = conn
+			.query_row(
+				"SELECT updated_at FROM vulnerability_status WHERE vulnerability_id = ?1",
+				[vulnerability_id],
+				|row| row.get(0),
+			)
+			.optional()?;
+		let theirs = normalized(Some(&triage.updated_at));
+		if ours.is_none() || is_newer(theirs.as_deref(), ours.as_deref()) {
+			conn.execute(
+				"INSERT INTO vulnerability_status
+					(vulnerability_id, status, assigned_to, justification, approved_by, accepted_at, expires_on, updated_at)
+				 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, COALESCE(?8, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')))
+				 ON CONFLICT(vulnerability_id) DO UPDATE SET
+					status = excluded.status,
+					assigned_to = excluded.assigned_to,
+					justification = excluded.justification,
+					approved_by = excluded.approved_by,
+					accepted_at = excluded.accepted_at,
+					expires_on = excluded.expires_on,
+					updated_at = excluded.updated_at",
+				params![
+					vulnerability_id,
+					TriageStatus::from_db(&triage.status).as_str(),
+					triage.assigned_to,
+					triage.justification,
+					triage.approved_by,
+					triage.accepted_at,
+					triage.expires_on,
+					theirs,
+				],
+			)?;
+			summary.triage_updated += 1;
+		}
+	}
+	Ok(())
+}
+
+/// Takes the bundle's last run of a source known here when it is the later one
+fn merge_source(conn: &Connection, source: &BundleSource) -> Result<bool> {
+	let ours: Option<Option<String>> = conn
+		.query_row("SELECT last_run_at FROM data_sources WHERE name = ?1", [&source.name], |row| row.get(0))
+		.optional()?;
+	let Some(ours) = ours else {
+		return Ok(false);
+	};
+	let theirs = normalized(source.last_run_at.as_deref());
+	if !is_newer(theirs.as_deref(), ours.as_deref()) {
+		return Ok(false);
+	}
+	conn.execute(
+		"UPDATE data_sources SET last_run_at = ?2, last_status = ?3, last_message = ?4 WHERE name = ?1",
+		params![source.name, theirs, source.last_status, source.last_message],
+	)?;
+	Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_bundle_merges_by_modification_time() -> Result<()> {
+		let dir = tempdir()?;
+		let connected = Arc::new(connection::establish_pool_with_path(dir.path().join("connected.db"))?);
+		connected.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, description, severity, updated_at)
+				VALUES (1, 'CVE-2024-0001', 'Refreshed upstream', 'Critical', '2024-06-01T00:00:00Z'),
+				       (2, 'CVE-2024-0002', 'Stale upstream', 'Low', '2024-01-01T00:00:00Z'),
+				       (3, 'CVE-2024-0003', 'New upstream', 'High', '2024-06-01T00:00:00Z');
+			 INSERT INTO vulnerability_weaknesses (vulnerability_id, cwe_id) VALUES (3, 'CWE-787');
+			 INSERT INTO vulnerability_aliases (alias, vulnerability_id, source) VALUES ('GHSA-aaaa-bbbb-cccc', 3, 'GHSA');
+			 INSERT INTO vulnerability_status (vulnerability_id, status, assigned_to, updated_at)
+				VALUES (3, 'In Progress', 'alice', '2024-06-02T00:00:00Z');
+			 UPDATE data_sources SET last_run_at = '2024-06-01T00:00:00Z', last_status = 'succeeded',
+				last_message = '50 vulnerabilities updated' WHERE name = 'NVD';"
+		)?;
+		let bundle = OfflineBundleRepository::new(connected).export(ProgressReporter::disabled()).await?;
+		assert_eq!(bundle.vulnerabilities.len(), 3);
+		assert_eq!(bundle.vulnerabilities[2].weaknesses, vec!["CWE-787".to_string()]);
+
+		let air_gapped = Arc::new(connection::establish_pool_with_path(dir.path().join("air-gapped.db"))?);
+		air_gapped.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, description, severity, updated_at)
+				VALUES (1, 'CVE-2024-0001', 'Old local copy', 'Medium', '2024-03-01T00:00:00Z'),
+				       (2, 'CVE-2024-0002', 'Edited locally', 'High', '2024-03-01T00:00:00Z');"
+		)?;
+		let repo = OfflineBundleRepository::new(air_gapped.clone());
+		let json = serde_json::to_string(&bundle)?;
+		let summary = repo.import(serde_json::from_str(&json)?).await?;
+		assert_eq!((summary.created, summary.updated, summary.unchanged), (1, 1, 1));
+		assert_eq!(summary.triage_updated, 1);
+		assert_eq!(summary.sources_updated, 1);
+
+		let descriptions = air_gapped.get()?
+			.prepare("SELECT description, updated_at FROM vulnerabilities ORDER BY cve_id")?
+			.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+			.collect::<rusqlite::Result<Vec<(String, String)>>>()?;
+		assert_eq!(descriptions, vec![
+			("Refreshed upstream".to_string(), "2024-06-01T00:00:00Z".to_string()),
+			("Edited locally".to_string(), "2024-03-01T00:00:00Z".to_string()),
+			("New upstream".to_string(), "2024-06-01T00:00:00Z".to_string()),
+		]);
+		let (status, alias): (String, String) = air_gapped.get()?.query_row(
+			"SELECT s.status, a.alias FROM vulnerability_status s
+			 JOIN vulnerability_aliases a ON a.vulnerability_id = s.vulnerability_id",
+			[],
+			|row| Ok((row.get(0)?, row.get(1)?)),
+		)?;
+		assert_eq!((status.as_str(), alias.as_str()), ("In Progress", "GHSA-aaaa-bbbb-cccc"));
+		let nvd = air_gapped.get()?.query_row(
+			"SELECT last_message FROM data_sources WHERE name = 'NVD'",
+			[],
+			|row| row.get::<_, Option<String>>(0),
+		)?;
+		assert_eq!(nvd.as_deref(), Some("50 vulnerabilities updated"));
+
+		// Merging the same bundle again changes nothing
+		let summary = repo.import(serde_json::from_str(&json)?).await?;
+		assert_eq!((summary.created, summary.updated, summary.unchanged), (0, 0, 3));
+		assert_eq!((summary.triage_updated, summary.sources_updated), (0, 0));
+		Ok(())
+	}
+}
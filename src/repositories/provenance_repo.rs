@@ -0,0 +1,130 @@
+// src/repositories/provenance_repo.rs
+
+//! Where the value of each tracked field of a vulnerability came from. Writers take a
+//! snapshot of an entry before changing it and record their source for the fields that
+//! did change, so a value kept by a lock or a merge rule keeps the source it had.
+
+use crate::models::vulnerability::{ProvenanceField, ProvenanceSource};
+use anyhow::{Context, Result};
+use rusqlite::types::Value;
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Tracked fields of one vulnerability at one point, in the order of `ProvenanceField::ALL`
+pub(crate) struct FieldValues {
+	vulnerability_id: i64,
+	values: Vec<Value>,
+}
+
+/// The tracked fields of the entry stored under `cve_id`, if there is one
+pub(crate) fn snapshot(conn: &Connection, cve_id: &str) -> Result<Option<FieldValues>> {
+	let columns: Vec<&str> = ProvenanceField::ALL.iter().map(ProvenanceField::as_str).collect();
+	let mut stmt = conn.prepare_cached(&format!(
+		"SELECT vulnerability_id, {} FROM vulnerabilities WHERE cve_id = ?1",
+		columns.join(", ")
+	))?;
+	stmt.query_row([cve_id], |row| {
+		Ok(FieldValues {
+			vulnerability_id: row.get(0)?,
+			values: (1..=columns.len()).map(|i| row.get(i)).collect::<rusqlite::Result<_>>()?,
+		})
+	})
+		.optional()
+		.with_context(|| format!("Failed to read the fields of {}", cve_id))
+}
+
+/// Records `source` for the fields of `cve_id` that differ from `before`, or for every
+/// field with a value when the entry is new
+pub(crate) fn record_changes(conn: &Connection, cve_id: &str, before: Option<FieldValues>, source: ProvenanceSource) -> Result<()> {
+	let Some(after) = snapshot(conn, cve_id)? else {
+		return Ok(());
+	};
+	let changed: Vec<ProvenanceField> = ProvenanceField::ALL
+		.into_iter()
+		.enumerate()
+		.filter(|(i, _)| match &before {
+			Some(before) => before.values[*i] != after.values[*i],
+			None => match &after.values[*i] {
+				Value::Null => false,
+				Value::Text(text) => !text.is_empty(),
+				_ => true,
+			},
+		})
+		.map(|(_, field)| field)
+		.collect();
+	record(conn, after.vulnerability_id, &changed, source)
+}
+
+/// Records `source` as where the current value of `fields` came from
+pub(crate) fn record(conn: &Connection, vulnerability_id: i64, fields: &[ProvenanceField], source: ProvenanceSource) -> Result<()> {
+	let mut stmt = conn.prepare_cached(
+		"INSERT INTO field_provenance (vulnerability_id, field, source) VALUES (?1, ?2, ?3)
+		 ON CONFLICT (vulnerability_id, field) DO UPDATE SET
+			source = excluded.source, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+	)?;
+	for field in fields {
+		stmt.execute(params![vulnerability_id, field.as_str(), source.as_str()])
+			.with_context(|| format!("Failed to record the source of the {}", field))?;
+	}
+	Ok(())
+}
+
+/// Forgets that an analyst set `field`, so enrichment may fill it in again
+pub(crate) fn forget_manual(conn: &Connection, vulnerability_id: i64, field: ProvenanceField) -> Result<()> {
+	conn.execute(
+		"DELETE FROM field_provenance WHERE vulnerability_id = ?1 AND field = ?2 AND source = 'manual'",
+		params![vulnerability_id, field.as_str()],
+	).context("Failed to clear the source of the field")?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use crate::models::vulnerability::{LockedField, Vulnerability};
+	use crate::repositories::enrichment_repo::EnrichmentRepository;
+	use crate::repositories::vulnerability_repo::VulnerabilityRepository;
+	use std::sync::Arc;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_field_provenance() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		{
+			let conn = pool.get()?;
+			conn.execute(
+				"INSERT INTO vulnerabilities (vulnerability_id, cve_id, description, severity) VALUES (1, 'CVE-2024-0001', 'From a scan', 'Unknown')",
+				[],
+			)?;
+			record_changes(&conn, "CVE-2024-0001", None, ProvenanceSource::Csv)?;
+			let before = snapshot(&conn, "CVE-2024-0001")?;
+			conn.execute(
+				"UPDATE vulnerabilities SET severity = 'HIGH', cvss_score = 7.5, published_date = '2024-01-01' WHERE vulnerability_id = 1",
+				[],
+			)?;
+			record_changes(&conn, "CVE-2024-0001", before, ProvenanceSource::Nvd)?;
+		}
+		let repo = VulnerabilityRepository::new(pool.clone());
+		let vuln = repo.get_vulnerability_by_id(1).await?;
+		let source = |vuln: &Vulnerability, field| vuln.provenance(field).map(|p| p.source);
+		assert_eq!(source(&vuln, ProvenanceField::Description), Some(ProvenanceSource::Csv));
+		assert_eq!(source(&vuln, ProvenanceField::Severity), Some(ProvenanceSource::Nvd));
+		assert_eq!(source(&vuln, ProvenanceField::CvssScore), Some(ProvenanceSource::Nvd));
+		assert_eq!(source(&vuln, ProvenanceField::Impact), None);
+		assert!(vuln.provenance(ProvenanceField::Severity).and_then(|p| p.updated_at).is_some());
+
+		// An analyst rating the CVE unknown keeps enrichment from looking it up again
+		let enrichment = EnrichmentRepository::new(pool.clone());
+		repo.edit_fields(1, vec![(LockedField::Severity, Some("Unknown".to_string()))], "ana".to_string()).await?;
+		let vuln = repo.get_vulnerability_by_id(1).await?;
+		assert!(vuln.is_curated(ProvenanceField::Severity));
+		assert_eq!(enrichment.get_progress().await?.remaining_unknown, 0);
+
+		repo.unlock_field(1, LockedField::Severity).await?;
+		let vuln = repo.get_vulnerability_by_id(1).await?;
+		assert!(!vuln.is_curated(ProvenanceField::Severity));
+		assert_eq!(enrichment.get_progress().await?.remaining_unknown, 1);
+		Ok(())
+	}
+}
@@ -0,0 +1,101 @@
+// src/repositories/reference_repo.rs
+
+use crate::db::connection::SqlitePool;
+use crate::models::reference::Reference;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::sync::Arc;
+use tokio::task;
+
+/// Store the references of a CVE. URLs it already has keep their row and only take
+/// over tags, which older imports did not record. Used inside the import
+/// transactions, so it takes a connection rather than the pool.
+pub(crate) fn insert_references(conn: &Connection, cve_id: &str, references: &[Reference]) -> rusqlite::Result<usize> {
+	let mut stmt = conn.prepare_cached(
+		"INSERT INTO vulnerability_references (vulnerability_id, url, source, advisory_id, tags)
+		 SELECT vulnerability_id, ?2, ?3, ?4, ?5 FROM vulnerabilities WHERE cve_id = ?1
+		 ON CONFLICT(vulnerability_id, url) DO UPDATE SET tags = excluded.tags
+		 WHERE excluded.tags IS NOT NULL AND tags IS NOT excluded.tags",
+	)?;
+
+	let mut inserted = 0;
+	for reference in references {
+		let tags = Some(reference.tags.join(",")).filter(|tags| !tags.is_empty());
+		inserted += stmt.execute(params![cve_id, reference.url, reference.source, reference.advisory_id, tags])?;
+	}
+	Ok(inserted)
+}
+
+pub struct ReferenceRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl ReferenceRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// References of a vulnerability; patches and exploits first, then in import order
+	pub async fn get_references(&self, vulnerability_id: i64) -> Result<Vec<Reference>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(
+				"SELECT url, source, advisory_id, tags FROM vulnerability_references
+				 WHERE vulnerability_id = ?1 ORDER BY reference_id",
+			)?;
+			let mut references = stmt
+				.query_map([vulnerability_id], |row| {
+					Ok(Reference {
+						url: row.get(0)?,
+						source: row.get(1)?,
+						advisory_id: row.get(2)?,
+						tags: row.get::<_, Option<String>>(3)?
+							.map(|tags| tags.split(',').map(str::to_string).collect())
+							.unwrap_or_default(),
+					})
+				})?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to load references")?;
+			references.sort_by_key(|reference| !(reference.has_tag("Patch") || reference.has_tag("Exploit")));
+			Ok(references)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_references_round_trip() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let vulnerability_id = {
+			let conn = pool.get()?;
+			conn.execute("INSERT INTO vulnerabilities (cve_id, severity) VALUES ('CVE-2024-0001', 'High')", [])?;
+			let advisory = Reference::new("https://example.com/advisory".to_string(), Some("MISC".to_string()));
+			insert_references(&conn, "CVE-2024-0001", std::slice::from_ref(&advisory))?;
+
+			// A later NVD import tags the known URL and adds a patch
+			let tagged = vec![
+				advisory.with_tags(vec!["Vendor Advisory".to_string()]),
+				Reference::new("https://example.com/fix.patch".to_string(), None).with_tags(vec!["Patch".to_string()]),
+			];
+			assert_eq!(insert_references(&conn, "CVE-2024-0001", &tagged)?, 2);
+			assert_eq!(insert_references(&conn, "CVE-2024-0001", &tagged)?, 0);
+			conn.query_row("SELECT vulnerability_id FROM vulnerabilities", [], |row| row.get(0))?
+		};
+
+		let references = ReferenceRepository::new(pool).get_references(vulnerability_id).await?;
+		assert_eq!(references.len(), 2);
+		assert_eq!(references[0].url, "https://example.com/fix.patch");
+		assert_eq!(references[1].tags, ["Vendor Advisory"]);
+		assert_eq!(references[1].source.as_deref(), Some("MISC"));
+		Ok(())
+	}
+}
@@ -1,12 +1,105 @@
 // src/repositories/robot_repo.rs
 
-use crate::db::connection::SqlitePool;
-use crate::models::robot::Robot;
-use rusqlite::params;
+use crate::db::connection::{self, SqlitePool};
+use crate::repositories::{access, audit_repo, trash_repo};
+use crate::models::audit::{AuditAction, AuditEntity, FieldChange};
+use crate::models::robot::{Criticality, InventorySource, Robot, RobotExposure};
+use crate::models::trash::DeletedKind;
+use crate::models::trends::FLEET_RISK_METRIC;
+use crate::models::risk::{fleet_risk_index, robot_risk_score, Exposure};
+use crate::repositories::snapshot_repo::record_snapshot;
+use crate::repositories::vulnerability_repo::{
+	unresolved_status_sql, vulnerability_from_row, EFFECTIVE_CVSS_SQL, STATUS_JOIN, VULNERABILITY_COLUMNS,
+	VULNERABILITY_COLUMN_COUNT,
+};
+use crate::utils::time;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
 use std::sync::Arc;
 use anyhow::{Result, Context};
 use tokio::task;
 
+/// Recompute the risk score of every robot from the unresolved vulnerabilities of its
+/// installed software and its open commissioning checklist items, and save today's snapshot. Returns the number of robots whose
+/// score changed.
+pub fn refresh_risk_scores(conn: &Connection) -> Result<usize> {
+	let mut stmt = conn.prepare(&format!(
+		"SELECT rs.robot_id, {}, v.epss_score, v.kev_date_added IS NOT NULL
+		 FROM robot_software rs
+		 JOIN affected_software af ON af.version_id = rs.version_id
+		 JOIN vulnerabilities v ON v.vulnerability_id = af.vulnerability_id
+		 {}
+		 WHERE v.deleted_at IS NULL AND {}",
+		EFFECTIVE_CVSS_SQL, STATUS_JOIN, unresolved_status_sql()
+	))?;
+	let mut exposures: HashMap<i64, Vec<Exposure>> = HashMap::new();
+	let rows = stmt.query_map([], |row| {
+		Ok((row.get(0)?, Exposure { cvss: row.get(1)?, epss: row.get(2)?, known_exploited: row.get(3)? }))
+	})?;
+	for row in rows {
+		let (robot_id, exposure) = row?;
+		exposures.entry(robot_id).or_default().push(exposure);
+	}
+	let open_items = conn
+		.prepare(
+			"SELECT r.robot_id, COUNT(*) FROM robots r
+			 CROSS JOIN commissioning_items i
+			 LEFT JOIN robot_commissioning rc ON rc.robot_id = r.robot_id AND rc.item_id = i.item_id
+			 WHERE r.deleted_at IS NULL AND rc.robot_id IS NULL
+			 GROUP BY r.robot_id",
+		)?
+		.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, usize>(1)?)))?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+	for (robot_id, open) in open_items {
+		exposures.entry(robot_id).or_default().extend(std::iter::repeat_n(Exposure::OPEN_CHECKLIST_ITEM, open));
+	}
+
+	let robot_ids = conn
+		.prepare("SELECT robot_id FROM robots WHERE deleted_at IS NULL")?
+		.query_map([], |row| row.get(0))?
+		.collect::<rusqlite::Result<Vec<i64>>>()?;
+	let mut update = conn.prepare("UPDATE robots SET risk_score = ?2 WHERE robot_id = ?1 AND risk_score IS NOT ?2")?;
+	let mut changed = 0;
+	for robot_id in robot_ids {
+		let score = robot_risk_score(exposures.get(&robot_id).map_or(&[], Vec::as_slice));
+		changed += update.execute(params![robot_id, score])?;
+	}
+	record_fleet_risk(conn)?;
+	record_snapshot(conn)?;
+	Ok(changed)
+}
+
+/// Records that a robot's installed software was just refreshed, so it is no longer
+/// flagged as stale and is alerted about again once it goes stale
+pub fn mark_inventory_refreshed(conn: &Connection, robot_id: i64, source: InventorySource) -> Result<()> {
+	conn.execute(
+		"UPDATE robots SET inventory_refreshed_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), inventory_source = ?2,
+		 inventory_alerted_at = NULL
+		 WHERE robot_id = ?1",
+		params![robot_id, source.as_str()],
+	).context("Failed to record the inventory refresh")?;
+	Ok(())
+}
+
+/// Fleet risk index over the stored robot scores
+pub(crate) fn current_fleet_risk_index(conn: &Connection) -> Result<f64> {
+	let robots = conn
+		.prepare("SELECT COALESCE(risk_score, 0), criticality FROM robots WHERE deleted_at IS NULL")?
+		.query_map([], |row| Ok((row.get(0)?, Criticality::parse(&row.get::<_, String>(1)?))))?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+	Ok(fleet_risk_index(&robots))
+}
+
+/// Store today's fleet risk index, replacing an earlier value from the same day
+fn record_fleet_risk(conn: &Connection) -> Result<()> {
+	conn.execute(
+		"INSERT INTO metrics_history (recorded_on, metric, value) VALUES (date('now'), ?1, ?2)
+		 ON CONFLICT (recorded_on, metric) DO UPDATE SET value = excluded.value",
+		params![FLEET_RISK_METRIC, current_fleet_risk_index(conn)?],
+	).context("Failed to record fleet risk index")?;
+	Ok(())
+}
+
 pub struct RobotRepository {
 	pool: Arc<SqlitePool>,
 }
@@ -18,9 +111,9 @@ impl RobotRepository {
 
 	/// Add a new robot with its software components
 	pub async fn add_robot(&self, robot: Robot) -> Result<i64> {
+		access::require_write_access()?;
 		let pool = self.pool.clone();
-		task::spawn_blocking(move || {
-			let mut conn = pool.get().context("Failed to get database connection")?;
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
 			let tx = conn.transaction()?;
 
 			// Insert into software_products if not exists
@@ -48,45 +141,46 @@ impl RobotRepository {
 				 VALUES (?1, ?2, datetime('now'))",
 				params![
 					product_id,
-					robot.specifications.unwrap_or_else(|| "1.0.0".to_string()),
+					robot.specifications.as_deref().unwrap_or("1.0.0"),
 				],
 			)?;
+			audit_repo::record_change(&tx, AuditEntity::SoftwareVersion, tx.last_insert_rowid(), AuditAction::Insert, None)?;
 
 			tx.commit()?;
 			Ok(product_id)
-		})
+		}))
 			.await
 			.context("Failed to execute database operation")?
 	}
 
-	/// Get all robots with their associated vulnerability information
+	/// All robots by name, with the risk scores as of the last rescoring
 	pub async fn get_all_robots(&self) -> Result<Vec<Robot>> {
 		let pool = self.pool.clone();
 		task::spawn_blocking(move || {
 			let conn = pool.get().context("Failed to get database connection")?;
 			let mut stmt = conn.prepare(
-				"SELECT DISTINCT
-					sp.product_id,
-					sp.product_name,
-					sp.description,
-					sp.vendor,
-					(
-						SELECT COUNT(DISTINCT v.vulnerability_id)
-						FROM vulnerabilities v
-						JOIN affected_software af ON v.vulnerability_id = af.vulnerability_id
-						JOIN software_versions sv ON af.version_id = sv.version_id
-						WHERE sv.product_id = sp.product_id
-					) as vulnerability_count
-				 FROM software_products sp
-				 LEFT JOIN software_versions sv ON sp.product_id = sv.product_id"
+				"SELECT robot_id, name, specifications, manufacturer, model, firmware_version, os, ros_distro,
+					operational_note, risk_score, criticality, inventory_refreshed_at, inventory_source
+				 FROM robots
+				 WHERE deleted_at IS NULL
+				 ORDER BY name COLLATE NOCASE"
 			)?;
 
 			let robot_iter = stmt.query_map([], |row| {
 				Ok(Robot {
-					robot_id: Some(row.get(0)?),
+					robot_id: row.get(0)?,
 					name: row.get(1)?,
 					specifications: row.get(2)?,
 					manufacturer: row.get(3)?,
+					model: row.get(4)?,
+					firmware_version: row.get(5)?,
+					os: row.get(6)?,
+					ros_distro: row.get(7)?,
+					operational_note: row.get(8)?,
+					risk_score: row.get(9)?,
+					criticality: Criticality::parse(&row.get::<_, String>(10)?),
+					inventory_refreshed_at: row.get::<_, Option<String>>(11)?.as_deref().and_then(time::parse_utc),
+					inventory_source: row.get::<_, Option<String>>(12)?.as_deref().and_then(InventorySource::parse),
 				})
 			})?;
 
@@ -118,6 +212,15 @@ impl RobotRepository {
 						name: row.get(1)?,
 						specifications: row.get(2)?,
 						manufacturer: row.get(3)?,
+						model: None,
+						firmware_version: None,
+						os: None,
+						ros_distro: None,
+						operational_note: None,
+						risk_score: None,
+						criticality: Criticality::default(),
+						inventory_refreshed_at: None,
+						inventory_source: None,
 					})
 				},
 			)
@@ -127,70 +230,174 @@ impl RobotRepository {
 			.context("Failed to execute database operation")?
 	}
 
-	/// Delete a robot and its associated software information
+	/// Moves a robot to Recently deleted, from where it can be restored
 	pub async fn delete_robot(&self, id: i64) -> Result<()> {
+		access::require_write_access()?;
 		let pool = self.pool.clone();
-		task::spawn_blocking(move || {
-			let mut conn = pool.get().context("Failed to get database connection")?;
-			let tx = conn.transaction()?;
-
-			// Delete associated version vulnerabilities
-			tx.execute(
-				"DELETE FROM affected_software
-				 WHERE version_id IN (
-					SELECT version_id
-					FROM software_versions
-					WHERE product_id = ?1
-				 )",
-				params![id],
-			)?;
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			trash_repo::soft_delete(conn, DeletedKind::Robot, id)
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
 
-			// Delete versions
-			tx.execute(
-				"DELETE FROM software_versions WHERE product_id = ?1",
-				params![id],
+	/// Records that someone checked the robot and its recorded software is still current
+	pub async fn confirm_inventory(&self, robot_id: i64) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let (name, refreshed_at): (String, Option<String>) = tx
+				.query_row(
+					"SELECT name, inventory_refreshed_at FROM robots WHERE robot_id = ?1 AND deleted_at IS NULL",
+					[robot_id],
+					|row| Ok((row.get(0)?, row.get(1)?)),
+				)
+				.optional()?
+				.context("Robot not found")?;
+			mark_inventory_refreshed(&tx, robot_id, InventorySource::Confirmed)?;
+			let confirmed_at: Option<String> = tx.query_row(
+				"SELECT inventory_refreshed_at FROM robots WHERE robot_id = ?1",
+				[robot_id],
+				|row| row.get(0),
 			)?;
-
-			// Delete product
-			let result = tx.execute(
-				"DELETE FROM software_products WHERE product_id = ?1",
-				params![id],
+			audit_repo::record(
+				&tx,
+				AuditEntity::Robot,
+				Some(robot_id),
+				&name,
+				AuditAction::Update,
+				&[FieldChange::new("inventory_refreshed_at", refreshed_at, confirmed_at)],
 			)?;
-
-			if result == 0 {
-				anyhow::bail!("Robot not found");
-			}
-
 			tx.commit()?;
 			Ok(())
-		})
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Recompute the fleet risk scores, returning how many robots changed
+	pub async fn refresh_risk_scores(&self) -> Result<usize> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			refresh_risk_scores(conn)
+		}))
 			.await
 			.context("Failed to execute database operation")?
 	}
 
-	/// Get vulnerabilities associated with a robot
-	pub async fn get_robot_vulnerabilities(&self, robot_id: i64) -> Result<Vec<(String, String)>> {
+	/// Robots with their stored risk scores, riskiest first
+	pub async fn get_robots_by_risk(&self) -> Result<Vec<Robot>> {
 		let pool = self.pool.clone();
 		task::spawn_blocking(move || {
 			let conn = pool.get().context("Failed to get database connection")?;
 			let mut stmt = conn.prepare(
-				"SELECT DISTINCT v.cve_id, v.severity
-				 FROM vulnerabilities v
-				 JOIN affected_software af ON v.vulnerability_id = af.vulnerability_id
-				 JOIN software_versions sv ON af.version_id = sv.version_id
-				 WHERE sv.product_id = ?1
-				 ORDER BY v.severity DESC"
+				"SELECT robot_id, name, specifications, manufacturer, operational_note, risk_score, criticality, model,
+				        firmware_version, os, ros_distro, inventory_refreshed_at, inventory_source
+				 FROM robots WHERE deleted_at IS NULL ORDER BY COALESCE(risk_score, 0) DESC, name",
 			)?;
+			let robots = stmt
+				.query_map([], |row| {
+					Ok(Robot {
+						robot_id: row.get(0)?,
+						name: row.get(1)?,
+						specifications: row.get(2)?,
+						manufacturer: row.get(3)?,
+						model: row.get(7)?,
+						firmware_version: row.get(8)?,
+						os: row.get(9)?,
+						ros_distro: row.get(10)?,
+						operational_note: row.get(4)?,
+						risk_score: row.get(5)?,
+						criticality: Criticality::parse(&row.get::<_, String>(6)?),
+						inventory_refreshed_at: row.get::<_, Option<String>>(11)?.as_deref().and_then(time::parse_utc),
+						inventory_source: row.get::<_, Option<String>>(12)?.as_deref().and_then(InventorySource::parse),
+					})
+				})?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to collect robots")?;
+			Ok(robots)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
 
-			let vuln_iter = stmt.query_map([robot_id], |row| {
-				Ok((row.get(0)?, row.get(1)?))
-			})?;
+	/// Vulnerabilities affecting the software installed on a robot, highest CVSS first,
+	/// with since when the robot is exposed to each
+	pub async fn get_robot_vulnerabilities(&self, robot_id: i64) -> Result<Vec<RobotExposure>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(&format!(
+				"SELECT {}, installed.since FROM vulnerabilities v {}
+				 JOIN (
+					SELECT af.vulnerability_id, MIN(rs.installed_date) AS since
+					FROM robot_software rs
+					JOIN affected_software af ON af.version_id = rs.version_id
+					WHERE rs.robot_id = ?1
+					GROUP BY af.vulnerability_id
+				 ) installed ON installed.vulnerability_id = v.vulnerability_id
+				 WHERE v.deleted_at IS NULL
+				 ORDER BY {} DESC, v.cve_id",
+				VULNERABILITY_COLUMNS, STATUS_JOIN, EFFECTIVE_CVSS_SQL
+			))?;
 
-			vuln_iter
+			let exposures = stmt
+				.query_map([robot_id], |row| {
+					let installed: Option<String> = row.get(VULNERABILITY_COLUMN_COUNT)?;
+					Ok(RobotExposure::new(vulnerability_from_row(row)?, installed.as_deref().and_then(time::parse_utc)))
+				})?
 				.collect::<rusqlite::Result<Vec<_>>>()
-				.context("Failed to collect vulnerabilities")
+				.context("Failed to collect vulnerabilities")?;
+			Ok(exposures)
 		})
 			.await
 			.context("Failed to execute database operation")?
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_robot_vulnerabilities() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO robots (robot_id, name) VALUES (1, 'arm'), (2, 'agv');
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ros', 'OSRF');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, '1.0'), (2, 1, '2.0');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 1), (2, 2);
+			 INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity, cvss_score) VALUES
+				(1, 'CVE-2024-0001', 'Medium', 5.0), (2, 'CVE-2024-0002', 'Critical', NULL), (3, 'CVE-2024-0003', 'High', 7.5);
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES
+				(1, 1, '1.0'), (2, 1, '<2.0'), (3, 2, '2.0');",
+		)?;
+
+		let exposures = RobotRepository::new(pool.clone()).get_robot_vulnerabilities(1).await?;
+		let cve_ids: Vec<&str> = exposures.iter().map(|e| e.vulnerability.cve_id.as_str()).collect();
+		assert_eq!(cve_ids, ["CVE-2024-0002", "CVE-2024-0001"]);
+		// Exposed since the software was installed, as neither CVE has a publication date
+		let installed: String = pool.get()?.query_row("SELECT installed_date FROM robot_software WHERE robot_id = 1", [], |row| row.get(0))?;
+		assert_eq!(exposures[0].exposed_since, time::parse_utc(&installed));
+
+		// Resolved vulnerabilities no longer count towards the risk score
+		let conn = pool.get()?;
+		conn.execute_batch(
+			"UPDATE vulnerabilities SET epss_score = 0.5 WHERE vulnerability_id = 3;
+			 INSERT INTO vulnerability_status (vulnerability_id, status) VALUES (1, 'Mitigated');",
+		)?;
+		assert_eq!(refresh_risk_scores(&conn)?, 2);
+		assert_eq!(refresh_risk_scores(&conn)?, 0);
+		let scores = conn
+			.prepare("SELECT risk_score FROM robots ORDER BY robot_id")?
+			.query_map([], |row| row.get(0))?
+			.collect::<rusqlite::Result<Vec<f64>>>()?;
+		assert_eq!(scores, [9.5, 37.5]);
+		Ok(())
+	}
+}
@@ -0,0 +1,399 @@
+// src/repositories/settings_repo.rs
+
+use crate::db::backup::BackupPolicy;
+use crate::db::compaction::CompactionMode;
+use crate::db::connection::{self, SqlitePool};
+use crate::db::maintenance::MaintenancePolicy;
+use crate::models::alert::AlertSettings;
+use crate::models::audit::{AuditAction, AuditEntity, FieldChange};
+use crate::models::csv_mapping::CsvMapping;
+use crate::models::keyword_discovery::KeywordDiscovery;
+use crate::models::nvd_health::NvdHealth;
+use crate::models::robot::DEFAULT_INVENTORY_MAX_AGE_DAYS;
+use crate::models::role::Role;
+use crate::models::ticket::TicketSettings;
+use crate::repositories::{access, audit_repo};
+use crate::utils::http::NetworkSettings;
+use crate::utils::import_archive::DEFAULT_RETENTION_DAYS;
+use crate::utils::time::DisplayTimeZone;
+use crate::utils::update_check::UpdateCheckSettings;
+use rusqlite::{params, OptionalExtension};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use anyhow::{Result, Context};
+use tokio::task;
+
+const ROLE_KEY: &str = "role";
+const TIME_ZONE_KEY: &str = "time_zone";
+const COMPACTION_KEY: &str = "compaction";
+const LOG_FILTER_KEY: &str = "log_filter";
+const IMPORT_RETENTION_KEY: &str = "import_retention_days";
+const INVENTORY_MAX_AGE_KEY: &str = "inventory_max_age_days";
+const NVD_HEALTH_KEY: &str = "nvd_health";
+const KEYWORD_DISCOVERY_KEY: &str = "keyword_discovery";
+const BACKUP_POLICY_KEY: &str = "backup_policy";
+const MAINTENANCE_POLICY_KEY: &str = "maintenance_policy";
+const UPDATE_CHECK_KEY: &str = "update_check";
+const NETWORK_KEY: &str = "network";
+const TICKETING_KEY: &str = "ticketing";
+const WATCH_FOLDER_KEY: &str = "watch_folder";
+/// The alert outbox triggers in the schema only queue alerts while this key exists
+const ALERTS_KEY: &str = "alerts";
+/// Prefix of the keys holding CSV import mapping presets, followed by the preset name
+const CSV_PRESET_PREFIX: &str = "csv_preset:";
+/// Keys maintained by the application itself, whose changes are not audited
+const UNAUDITED_KEYS: &[&str] = &[NVD_HEALTH_KEY];
+
+/// Key/value store for installation-wide settings
+pub struct SettingsRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl SettingsRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	pub async fn get(&self, key: &str) -> Result<Option<String>> {
+		let pool = self.pool.clone();
+		let key = key.to_string();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			conn.query_row("SELECT value FROM settings WHERE key = ?1", [&key], |row| row.get(0))
+				.optional()
+				.with_context(|| format!("Failed to read setting {}", key))
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	pub async fn set(&self, key: &str, value: &str) -> Result<()> {
+		let pool = self.pool.clone();
+		let (key, value) = (key.to_string(), value.to_string());
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let old: Option<String> = tx
+				.query_row("SELECT value FROM settings WHERE key = ?1", [&key], |row| row.get(0))
+				.optional()?;
+			tx.execute(
+				"INSERT INTO settings (key, value) VALUES (?1, ?2)
+				 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+				params![key, value],
+			).with_context(|| format!("Failed to store setting {}", key))?;
+			if old.as_ref() != Some(&value) && !UNAUDITED_KEYS.contains(&key.as_str()) {
+				let action = if old.is_some() { AuditAction::Update } else { AuditAction::Insert };
+				audit_repo::record(&tx, AuditEntity::Setting, None, &key, action, &[
+					FieldChange::new("value", old, Some(value.clone())),
+				])?;
+			}
+			tx.commit()?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Role of this installation; admin when none is set, viewer when the stored value
+	/// is not a known role, so a damaged setting never grants write access
+	pub async fn get_role(&self) -> Result<Role> {
+		Ok(match self.get(ROLE_KEY).await? {
+			Some(value) => Role::from_db(&value).unwrap_or(Role::Viewer),
+			None => Role::default(),
+		})
+	}
+
+	pub async fn set_role(&self, role: Role) -> Result<()> {
+		self.set(ROLE_KEY, role.as_str()).await
+	}
+
+	/// Time zone timestamps are displayed in; unknown or missing values fall back to local time
+	pub async fn get_time_zone(&self) -> Result<DisplayTimeZone> {
+		Ok(self.get(TIME_ZONE_KEY).await?
+			.and_then(|value| DisplayTimeZone::from_setting(&value))
+			.unwrap_or_default())
+	}
+
+	pub async fn set_time_zone(&self, zone: DisplayTimeZone) -> Result<()> {
+		self.set(TIME_ZONE_KEY, &zone.to_string()).await
+	}
+
+	/// What to do on startup when the database is worth compacting; defaults to asking
+	pub async fn get_compaction_mode(&self) -> Result<CompactionMode> {
+		Ok(self.get(COMPACTION_KEY).await?
+			.and_then(|value| CompactionMode::from_setting(&value))
+			.unwrap_or_default())
+	}
+
+	pub async fn set_compaction_mode(&self, mode: CompactionMode) -> Result<()> {
+		self.set(COMPACTION_KEY, &mode.to_string()).await
+	}
+
+	/// Log filter directives, e.g. "info,rvd_core::utils::nvd_api=debug"
+	pub async fn get_log_filter(&self) -> Result<Option<String>> {
+		self.get(LOG_FILTER_KEY).await
+	}
+
+	pub async fn set_log_filter(&self, directives: &str) -> Result<()> {
+		self.set(LOG_FILTER_KEY, directives.trim()).await
+	}
+
+	/// Days kept import files stay uncompressed before they are archived
+	pub async fn get_import_retention_days(&self) -> Result<u32> {
+		Ok(self.get(IMPORT_RETENTION_KEY).await?
+			.and_then(|value| value.parse().ok())
+			.unwrap_or(DEFAULT_RETENTION_DAYS))
+	}
+
+	pub async fn set_import_retention_days(&self, days: u32) -> Result<()> {
+		self.set(IMPORT_RETENTION_KEY, &days.to_string()).await
+	}
+
+	/// Days after which a robot's inventory is flagged as stale and alerted about
+	pub async fn get_inventory_max_age_days(&self) -> Result<u32> {
+		Ok(self.get(INVENTORY_MAX_AGE_KEY).await?
+			.and_then(|value| value.parse().ok())
+			.unwrap_or(DEFAULT_INVENTORY_MAX_AGE_DAYS))
+	}
+
+	pub async fn set_inventory_max_age_days(&self, days: u32) -> Result<()> {
+		self.set(INVENTORY_MAX_AGE_KEY, &days.to_string()).await
+	}
+
+	/// Reachability of the NVD API as of the last enrichment run
+	pub async fn get_nvd_health(&self) -> Result<NvdHealth> {
+		Ok(self.get(NVD_HEALTH_KEY).await?
+			.and_then(|value| serde_json::from_str(&value).ok())
+			.unwrap_or_default())
+	}
+
+	pub async fn set_nvd_health(&self, health: &NvdHealth) -> Result<()> {
+		let value = serde_json::to_string(health).context("Failed to serialize NVD health")?;
+		self.set(NVD_HEALTH_KEY, &value).await
+	}
+
+	/// Robotics terms searched in the NVD and how far the search got
+	pub async fn get_keyword_discovery(&self) -> Result<KeywordDiscovery> {
+		Ok(self.get(KEYWORD_DISCOVERY_KEY).await?
+			.and_then(|value| serde_json::from_str(&value).ok())
+			.unwrap_or_default())
+	}
+
+	pub async fn set_keyword_discovery(&self, discovery: &KeywordDiscovery) -> Result<()> {
+		let value = serde_json::to_string(discovery).context("Failed to serialize keyword discovery")?;
+		self.set(KEYWORD_DISCOVERY_KEY, &value).await
+	}
+
+	pub async fn get_backup_policy(&self) -> Result<BackupPolicy> {
+		Ok(self.get(BACKUP_POLICY_KEY).await?
+			.and_then(|value| serde_json::from_str(&value).ok())
+			.unwrap_or_default())
+	}
+
+	pub async fn set_backup_policy(&self, policy: &BackupPolicy) -> Result<()> {
+		let value = serde_json::to_string(policy).context("Failed to serialize backup policy")?;
+		self.set(BACKUP_POLICY_KEY, &value).await
+	}
+
+	pub async fn get_maintenance_policy(&self) -> Result<MaintenancePolicy> {
+		Ok(self.get(MAINTENANCE_POLICY_KEY).await?
+			.and_then(|value| serde_json::from_str(&value).ok())
+			.unwrap_or_default())
+	}
+
+	pub async fn set_maintenance_policy(&self, policy: &MaintenancePolicy) -> Result<()> {
+		let value = serde_json::to_string(policy).context("Failed to serialize maintenance policy")?;
+		self.set(MAINTENANCE_POLICY_KEY, &value).await
+	}
+
+	/// Whether new releases are looked for; off unless enabled
+	pub async fn get_update_check(&self) -> Result<UpdateCheckSettings> {
+		Ok(self.get(UPDATE_CHECK_KEY).await?
+			.and_then(|value| serde_json::from_str(&value).ok())
+			.unwrap_or_default())
+	}
+
+	pub async fn set_update_check(&self, settings: &UpdateCheckSettings) -> Result<()> {
+		let value = serde_json::to_string(settings).context("Failed to serialize update check settings")?;
+		self.set(UPDATE_CHECK_KEY, &value).await
+	}
+
+	/// Proxy and extra root certificates for outbound requests; none unless configured
+	pub async fn get_network_settings(&self) -> Result<NetworkSettings> {
+		self.get(NETWORK_KEY).await?
+			.map(|value| serde_json::from_str(&value).context("Network settings are corrupt"))
+			.transpose()
+			.map(Option::unwrap_or_default)
+	}
+
+	pub async fn set_network_settings(&self, settings: &NetworkSettings) -> Result<()> {
+		access::require_write_access()?;
+		let value = serde_json::to_string(settings).context("Failed to serialize network settings")?;
+		self.set(NETWORK_KEY, &value).await
+	}
+
+	/// Email alert configuration, or None when alerting is off
+	pub async fn get_alert_settings(&self) -> Result<Option<AlertSettings>> {
+		self.get(ALERTS_KEY).await?
+			.map(|value| serde_json::from_str(&value).context("Alert settings are corrupt"))
+			.transpose()
+	}
+
+	pub async fn set_alert_settings(&self, settings: &AlertSettings) -> Result<()> {
+		access::require_write_access()?;
+		let value = serde_json::to_string(settings).context("Failed to serialize alert settings")?;
+		self.set(ALERTS_KEY, &value).await
+	}
+
+	/// Turn alerting off; alerts already queued are kept
+	pub async fn clear_alert_settings(&self) -> Result<()> {
+		self.clear(ALERTS_KEY).await
+	}
+
+	/// Where remediation tickets are raised, or None when ticketing is off
+	pub async fn get_ticket_settings(&self) -> Result<Option<TicketSettings>> {
+		self.get(TICKETING_KEY).await?
+			.map(|value| serde_json::from_str(&value).context("Ticketing settings are corrupt"))
+			.transpose()
+	}
+
+	pub async fn set_ticket_settings(&self, settings: &TicketSettings) -> Result<()> {
+		access::require_write_access()?;
+		let value = serde_json::to_string(settings).context("Failed to serialize ticketing settings")?;
+		self.set(TICKETING_KEY, &value).await
+	}
+
+	/// Turn ticketing off; tickets already raised stay linked to their vulnerabilities
+	pub async fn clear_ticket_settings(&self) -> Result<()> {
+		self.clear(TICKETING_KEY).await
+	}
+
+	/// Folder the GUI imports dropped files from, or None when no folder is watched
+	pub async fn get_watch_folder(&self) -> Result<Option<PathBuf>> {
+		Ok(self.get(WATCH_FOLDER_KEY).await?.map(PathBuf::from))
+	}
+
+	pub async fn set_watch_folder(&self, dir: &Path) -> Result<()> {
+		access::require_write_access()?;
+		self.set(WATCH_FOLDER_KEY, &dir.to_string_lossy()).await
+	}
+
+	/// Stop watching; files already imported stay in the folder's archive
+	pub async fn clear_watch_folder(&self) -> Result<()> {
+		self.clear(WATCH_FOLDER_KEY).await
+	}
+
+	/// Remove a setting, recording the removal in the audit log
+	async fn clear(&self, key: &'static str) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let old: Option<String> = tx
+				.query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| row.get(0))
+				.optional()?;
+			tx.execute("DELETE FROM settings WHERE key = ?1", [key])
+				.with_context(|| format!("Failed to clear setting {}", key))?;
+			if old.is_some() {
+				audit_repo::record(&tx, AuditEntity::Setting, None, key, AuditAction::Delete, &[
+					FieldChange::new("value", old, None),
+				])?;
+			}
+			tx.commit()?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Save a CSV column mapping under `name`, replacing an earlier preset of that name
+	pub async fn save_csv_preset(&self, name: &str, mapping: &CsvMapping) -> Result<()> {
+		access::require_write_access()?;
+		let value = serde_json::to_string(mapping).context("Failed to serialize CSV preset")?;
+		self.set(&format!("{}{}", CSV_PRESET_PREFIX, name.trim()), &value).await
+	}
+
+	pub async fn get_csv_preset(&self, name: &str) -> Result<Option<CsvMapping>> {
+		self.get(&format!("{}{}", CSV_PRESET_PREFIX, name.trim())).await?
+			.map(|value| serde_json::from_str(&value)
+				.with_context(|| format!("CSV preset {} is corrupt", name)))
+			.transpose()
+	}
+
+	/// All saved CSV presets by name, skipping any that no longer parse
+	pub async fn list_csv_presets(&self) -> Result<Vec<(String, CsvMapping)>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(
+				"SELECT substr(key, length(?1) + 1), value FROM settings
+				 WHERE substr(key, 1, length(?1)) = ?1
+				 ORDER BY key"
+			)?;
+			let rows = stmt
+				.query_map([CSV_PRESET_PREFIX], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to load CSV presets")?;
+
+			Ok(rows
+				.into_iter()
+				.filter_map(|(name, value)| serde_json::from_str(&value).ok().map(|mapping| (name, mapping)))
+				.collect())
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_settings_round_trip() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let repo = SettingsRepository::new(pool);
+
+		assert_eq!(repo.get("missing").await?, None);
+		assert_eq!(repo.get_role().await?, Role::Admin);
+
+		repo.set_role(Role::Viewer).await?;
+		assert_eq!(repo.get_role().await?, Role::Viewer);
+		repo.set_role(Role::Admin).await?;
+		repo.set("role", "superuser").await?;
+		assert_eq!(repo.get_role().await?, Role::Viewer);
+
+		assert_eq!(repo.get_time_zone().await?, DisplayTimeZone::Local);
+		let zone = DisplayTimeZone::from_setting("-04:00").unwrap();
+		repo.set_time_zone(zone).await?;
+		assert_eq!(repo.get_time_zone().await?, zone);
+
+		assert_eq!(repo.get_compaction_mode().await?, CompactionMode::Prompt);
+		repo.set_compaction_mode(CompactionMode::Auto).await?;
+		assert_eq!(repo.get_compaction_mode().await?, CompactionMode::Auto);
+
+		assert_eq!(repo.get_import_retention_days().await?, DEFAULT_RETENTION_DAYS);
+		repo.set_import_retention_days(7).await?;
+		assert_eq!(repo.get_import_retention_days().await?, 7);
+
+		assert_eq!(repo.get_inventory_max_age_days().await?, DEFAULT_INVENTORY_MAX_AGE_DAYS);
+		repo.set_inventory_max_age_days(30).await?;
+		assert_eq!(repo.get_inventory_max_age_days().await?, 30);
+
+		assert_eq!(repo.get_nvd_health().await?, NvdHealth::default());
+		let mut health = NvdHealth::default();
+		health.record_failure(chrono::Utc::now());
+		repo.set_nvd_health(&health).await?;
+		assert_eq!(repo.get_nvd_health().await?, health);
+
+		let mapping = CsvMapping { source: "Scanner".to_string(), ..CsvMapping::default() };
+		repo.save_csv_preset("weekly scan", &mapping).await?;
+		assert_eq!(repo.get_csv_preset("weekly scan").await?, Some(mapping.clone()));
+		assert_eq!(repo.get_csv_preset("missing").await?, None);
+		assert_eq!(repo.list_csv_presets().await?, vec![("weekly scan".to_string(), mapping)]);
+
+		Ok(())
+	}
+}
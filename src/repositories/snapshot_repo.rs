@@ -0,0 +1,168 @@
+// src/repositories/snapshot_repo.rs
+
+use crate::db::connection::SqlitePool;
+use crate::models::snapshot::{FleetSnapshot, OpenVulnerability, RobotSnapshot, SnapshotDiff};
+use crate::repositories::robot_repo::current_fleet_risk_index;
+use crate::repositories::vulnerability_repo::{unresolved_status_sql, STATUS_JOIN};
+use anyhow::{anyhow, Context, Result};
+use chrono::{NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::sync::Arc;
+use tokio::task;
+
+/// The fleet as it is now, from the stored robot risk scores
+pub(crate) fn current_snapshot(conn: &Connection) -> Result<FleetSnapshot> {
+	let robots = conn
+		.prepare("SELECT robot_id, name, COALESCE(risk_score, 0) FROM robots WHERE deleted_at IS NULL ORDER BY name")?
+		.query_map([], |row| Ok(RobotSnapshot { robot_id: row.get(0)?, name: row.get(1)?, risk_score: row.get(2)? }))?
+		.collect::<rusqlite::Result<Vec<_>>>()
+		.context("Failed to collect robot risk scores")?;
+
+	let mut stmt = conn.prepare(&format!(
+		"SELECT DISTINCT v.cve_id, v.severity, r.name
+		 FROM robot_software rs
+		 JOIN robots r ON r.robot_id = rs.robot_id
+		 JOIN affected_software af ON af.version_id = rs.version_id
+		 JOIN vulnerabilities v ON v.vulnerability_id = af.vulnerability_id
+		 {}
+		 WHERE v.deleted_at IS NULL AND {}
+		 ORDER BY v.cve_id, r.name",
+		STATUS_JOIN, unresolved_status_sql()
+	))?;
+	let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get(2)?)))?;
+	let mut open: Vec<OpenVulnerability> = Vec::new();
+	for row in rows {
+		let (cve_id, severity, robot) = row?;
+		match open.last_mut() {
+			Some(last) if last.cve_id == cve_id => last.robots.push(robot),
+			_ => open.push(OpenVulnerability { cve_id, severity, robots: vec![robot] }),
+		}
+	}
+
+	Ok(FleetSnapshot {
+		taken_on: Utc::now().date_naive(),
+		fleet_risk_index: current_fleet_risk_index(conn)?,
+		robots,
+		open,
+	})
+}
+
+/// Store today's snapshot, replacing an earlier one from the same day
+pub(crate) fn record_snapshot(conn: &Connection) -> Result<()> {
+	let snapshot = current_snapshot(conn)?;
+	conn.execute(
+		"INSERT INTO report_snapshots (taken_on, content) VALUES (?1, ?2)
+		 ON CONFLICT (taken_on) DO UPDATE SET content = excluded.content",
+		params![snapshot.taken_on.to_string(), serde_json::to_string(&snapshot)?],
+	).context("Failed to record report snapshot")?;
+	Ok(())
+}
+
+/// Latest snapshot taken on or before `date`
+fn snapshot_on(conn: &Connection, date: NaiveDate) -> Result<Option<FleetSnapshot>> {
+	let row: Option<(String, String)> = conn.query_row(
+		"SELECT taken_on, content FROM report_snapshots WHERE taken_on <= ?1 ORDER BY taken_on DESC LIMIT 1",
+		params![date.to_string()],
+		|row| Ok((row.get(0)?, row.get(1)?)),
+	)
+		.optional()
+		.context("Failed to read report snapshot")?;
+	row
+		.map(|(taken_on, content)| {
+			let snapshot: FleetSnapshot = serde_json::from_str(&content).context("Report snapshot is not valid JSON")?;
+			let taken_on = NaiveDate::parse_from_str(&taken_on, "%Y-%m-%d").unwrap_or(snapshot.taken_on);
+			Ok(FleetSnapshot { taken_on, ..snapshot })
+		})
+		.transpose()
+}
+
+pub struct SnapshotRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl SnapshotRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Days with a saved snapshot, oldest first
+	pub async fn get_snapshot_dates(&self) -> Result<Vec<NaiveDate>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let dates = conn
+				.prepare("SELECT taken_on FROM report_snapshots ORDER BY taken_on")?
+				.query_map([], |row| row.get::<_, String>(0))?
+				.filter_map(|date| date.ok().and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()))
+				.collect();
+			Ok(dates)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Differences between the snapshots last taken on or before `from` and `to`, or
+	/// between the `from` snapshot and the current state when `to` is `None`
+	pub async fn diff(&self, from: NaiveDate, to: Option<NaiveDate>) -> Result<SnapshotDiff> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let missing = |date: NaiveDate| anyhow!("No report snapshot on or before {}; snapshots are saved when risk scores are refreshed", date);
+			let before = snapshot_on(&conn, from)?.ok_or_else(|| missing(from))?;
+			let after = match to {
+				Some(to) => snapshot_on(&conn, to)?.ok_or_else(|| missing(to))?,
+				None => current_snapshot(&conn)?,
+			};
+			Ok(SnapshotDiff::between(&before, &after))
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use crate::repositories::robot_repo::refresh_risk_scores;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_diff_against_current_state() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let conn = pool.get()?;
+		conn.execute_batch(
+			"INSERT INTO robots (robot_id, name) VALUES (1, 'arm-01');
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ros-core', 'OSRF');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, '1.0');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 1);
+			 INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity, cvss_score) VALUES
+				(1, 'CVE-2024-0001', 'High', 8.0), (2, 'CVE-2024-0002', 'Critical', 9.8);
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES (1, 1, '1.0');",
+		)?;
+		refresh_risk_scores(&conn)?;
+		// Move the snapshot a week back, as if taken at last week's meeting
+		conn.execute("UPDATE report_snapshots SET taken_on = date('now', '-7 days')", [])?;
+
+		conn.execute_batch(
+			"INSERT INTO vulnerability_status (vulnerability_id, status) VALUES (1, 'Mitigated');
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES (2, 1, '1.0');",
+		)?;
+		refresh_risk_scores(&conn)?;
+
+		let repo = SnapshotRepository::new(pool.clone());
+		assert_eq!(repo.get_snapshot_dates().await?.len(), 2);
+
+		let last_week = Utc::now().date_naive() - chrono::Duration::days(7);
+		let diff = repo.diff(last_week, None).await?;
+		assert_eq!(diff.opened.iter().map(|v| v.cve_id.as_str()).collect::<Vec<_>>(), ["CVE-2024-0002"]);
+		assert_eq!(diff.closed.iter().map(|v| v.cve_id.as_str()).collect::<Vec<_>>(), ["CVE-2024-0001"]);
+		assert_eq!(diff.closed[0].robots, ["arm-01"]);
+		assert_eq!((diff.from, diff.to), (last_week, Utc::now().date_naive()));
+		assert!(diff.risk[0].change() > 0.0);
+
+		assert!(repo.diff(last_week - chrono::Duration::days(1), None).await.is_err());
+		Ok(())
+	}
+}
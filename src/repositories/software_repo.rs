@@ -1,14 +1,197 @@
 // src/repositories/software_repo.rs
 
-use crate::db::connection::SqlitePool;
-use crate::models::software::{SoftwareProduct, SoftwareVersion, AffectedSoftware};
-use rusqlite::{params, Error as SqliteError};
+use crate::db::connection::{self, SqlitePool};
+use crate::repositories::{access, audit_repo};
+use crate::models::audit::{AuditAction, AuditEntity};
+use crate::models::software::{
+	SoftwareProduct, SoftwareVersion, AffectedSoftware, RiskySoftware, InventoryEntry, VersionMetadata,
+	VersionMetadataChange,
+};
+use crate::models::interchange::SoftwareRef;
+use crate::models::matrix::{self, AffectedMatrix, DeployedVersion, MatrixColumns, MatrixCorrelation};
+use crate::models::vulnerability::TriageStatus;
+use crate::models::robot::{ros_codename, ros_product};
+use crate::repositories::vulnerability_repo::{unresolved_status_sql, EFFECTIVE_CVSS_SQL};
+use crate::utils::version_match;
+use rusqlite::{params, params_from_iter, types::Value, Connection, Error as SqliteError, OptionalExtension};
+use std::cmp::Ordering;
 use std::sync::Arc;
 use anyhow::{Result, Context, anyhow};
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use tokio::task;
 use log::{info, warn};
 
+/// Vendor of the ROS products created for robots' ROS distributions
+const ROS_VENDOR: &str = "OSRF";
+
+/// Install the codename of the robot's ROS distribution as a version of its generation's
+/// product, `ros1` or `ros2` however the database spells it, replacing other ROS versions
+/// on the robot, so advisories recorded against ROS versions such as `<= humble` are
+/// matched against the distribution. Robots without one are left alone.
+fn sync_ros_distro(conn: &Connection, robot_id: i64) -> Result<()> {
+	let distro: Option<String> = conn
+		.query_row("SELECT ros_distro FROM robots WHERE robot_id = ?1", [robot_id], |row| row.get(0))
+		.optional()?
+		.flatten();
+	let Some(codename) = distro.as_deref().and_then(ros_codename) else {
+		return Ok(());
+	};
+
+	let product = ros_product(&codename);
+	let existing: Option<i64> = conn.query_row(
+		"SELECT product_id FROM software_products
+		 WHERE lower(replace(product_name, ' ', '')) = ?1
+		 ORDER BY product_id LIMIT 1",
+		[product],
+		|row| row.get(0),
+	).optional()?;
+	let product_id = match existing {
+		Some(id) => id,
+		None => {
+			conn.execute(
+				"INSERT INTO software_products (product_name, vendor) VALUES (?1, ?2)",
+				params![product, ROS_VENDOR],
+			)?;
+			conn.last_insert_rowid()
+		}
+	};
+	let version_id: i64 = match conn.query_row(
+		"SELECT version_id FROM software_versions WHERE product_id = ?1 AND lower(version_number) = ?2",
+		params![product_id, codename],
+		|row| row.get(0),
+	).optional()? {
+		Some(id) => id,
+		None => {
+			conn.execute(
+				"INSERT INTO software_versions (product_id, version_number) VALUES (?1, ?2)",
+				params![product_id, codename],
+			)?;
+			conn.last_insert_rowid()
+		}
+	};
+
+	// Also of the other generation and of a product spelled plain "ROS"
+	conn.execute(
+		"DELETE FROM robot_software WHERE robot_id = ?1 AND version_id != ?2
+		   AND version_id IN (
+			   SELECT sv.version_id FROM software_versions sv
+			   JOIN software_products sp ON sp.product_id = sv.product_id
+			   WHERE lower(replace(sp.product_name, ' ', '')) IN ('ros', 'ros1', 'ros2')
+		   )",
+		params![robot_id, version_id],
+	)?;
+	conn.execute(
+		"INSERT OR IGNORE INTO robot_software (robot_id, version_id) VALUES (?1, ?2)",
+		params![robot_id, version_id],
+	)?;
+	Ok(())
+}
+
+/// Re-run version matching for everything installed on a robot after its inventory
+/// changed. A version inherits a product's known vulnerabilities when it satisfies the
+/// affected version pattern recorded for another version and is older than the fix.
+/// Returns the number of correlations added; risk scores follow from them on the next query.
+pub(crate) fn refresh_robot_correlations(conn: &Connection, robot_id: i64) -> Result<usize> {
+	sync_ros_distro(conn, robot_id)?;
+	let mut installed_stmt = conn.prepare(
+		"SELECT sv.version_id, sv.product_id, sv.version_number
+		 FROM robot_software rs
+		 JOIN software_versions sv ON sv.version_id = rs.version_id
+		 WHERE rs.robot_id = ?1"
+	)?;
+	let installed = installed_stmt
+		.query_map([robot_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+		.collect::<rusqlite::Result<Vec<(i64, i64, String)>>>()?;
+
+	let mut known_stmt = conn.prepare(
+		"SELECT af.vulnerability_id, af.affected_version_pattern, af.fixed_in_version, af.detection_confidence
+		 FROM affected_software af
+		 JOIN software_versions sv ON sv.version_id = af.version_id
+		 WHERE sv.product_id = ?1 AND af.version_id != ?2
+		   AND af.vulnerability_id NOT IN (SELECT vulnerability_id FROM affected_software WHERE version_id = ?2)"
+	)?;
+
+	let mut added = 0;
+	for (version_id, product_id, version_number) in installed {
+		let candidates = known_stmt
+			.query_map(params![product_id, version_id], |row| {
+				Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+			})?
+			.collect::<rusqlite::Result<Vec<(i64, String, Option<String>, f64)>>>()?;
+
+		for (vulnerability_id, pattern, fixed_in, confidence) in candidates {
+			let unfixed = fixed_in
+				.as_deref()
+				.is_none_or(|fixed| version_match::compare_versions(&version_number, fixed) == Ordering::Less);
+			if unfixed && version_match::matches(&pattern, &version_number) {
+				added += conn.execute(
+					"INSERT OR IGNORE INTO affected_software
+						(vulnerability_id, version_id, affected_version_pattern, fixed_in_version, detection_confidence)
+					 VALUES (?1, ?2, ?3, ?4, ?5)",
+					params![vulnerability_id, version_id, pattern, fixed_in, confidence],
+				)?;
+			}
+		}
+	}
+
+	if added > 0 {
+		info!("Added {} correlations for robot {} after an inventory change", added, robot_id);
+	}
+	Ok(added)
+}
+
+/// Replace the software installed on a robot, creating products and versions it
+/// does not know yet. Versions the robot keeps retain their install date. Returns
+/// whether the installed software changed.
+pub fn set_robot_software(conn: &Connection, robot_id: i64, software: &[SoftwareRef]) -> Result<bool> {
+	let mut version_ids = Vec::with_capacity(software.len());
+	for entry in software {
+		conn.execute(
+			"INSERT OR IGNORE INTO software_products (product_name, vendor) VALUES (?1, ?2)",
+			params![entry.product_name, entry.vendor],
+		)?;
+		conn.execute(
+			"INSERT OR IGNORE INTO software_versions (product_id, version_number)
+			 SELECT product_id, ?3 FROM software_products WHERE product_name = ?1 AND vendor = ?2",
+			params![entry.product_name, entry.vendor, entry.version_number],
+		)?;
+		let version_id: i64 = conn.query_row(
+			"SELECT sv.version_id FROM software_versions sv
+			 JOIN software_products sp ON sp.product_id = sv.product_id
+			 WHERE sp.product_name = ?1 AND sp.vendor = ?2 AND sv.version_number = ?3",
+			params![entry.product_name, entry.vendor, entry.version_number],
+			|row| row.get(0),
+		)?;
+		version_ids.push(version_id);
+	}
+
+	let mut installed_stmt = conn.prepare("SELECT version_id FROM robot_software WHERE robot_id = ?1")?;
+	let installed = installed_stmt
+		.query_map([robot_id], |row| row.get(0))?
+		.collect::<rusqlite::Result<Vec<i64>>>()?;
+	let mut changed = 0;
+	for version_id in installed.iter().filter(|id| !version_ids.contains(id)) {
+		changed += conn.execute(
+			"DELETE FROM robot_software WHERE robot_id = ?1 AND version_id = ?2",
+			params![robot_id, version_id],
+		)?;
+	}
+	for version_id in version_ids {
+		changed += conn.execute(
+			"INSERT OR IGNORE INTO robot_software (robot_id, version_id) VALUES (?1, ?2)",
+			params![robot_id, version_id],
+		)?;
+	}
+
+	refresh_robot_correlations(conn, robot_id)?;
+	Ok(changed > 0)
+}
+
+/// Date part of a stored date or timestamp; release dates come as either
+fn parse_date(value: Option<String>) -> Option<NaiveDate> {
+	value.and_then(|value| NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok())
+}
+
 /// Convert i64 to i32 safely with context
 fn to_i32(value: i64, context: &str) -> Result<i32> {
 	i32::try_from(value).with_context(|| format!("Integer overflow for {}", context))
@@ -24,19 +207,20 @@ impl SoftwareRepository {
 	}
 
 	pub async fn add_software_product(&self, product: SoftwareProduct) -> Result<i64> {
+		access::require_write_access()?;
 		let pool = self.pool.clone();
 
-		task::spawn_blocking(move || {
-			let mut conn = pool.get().context("Failed to get database connection")?;
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
 			let tx = conn.transaction()?;
 
 			let result = tx.execute(
-				"INSERT INTO software_products (product_name, vendor, description)
-				 VALUES (?1, ?2, ?3)",
+				"INSERT INTO software_products (product_name, vendor, description, license)
+				 VALUES (?1, ?2, ?3, ?4)",
 				params![
 					product.product_name,
 					product.vendor,
 					product.description,
+					product.license,
 				],
 			).context("Failed to insert software product")?;
 
@@ -45,19 +229,20 @@ impl SoftwareRepository {
 			}
 
 			let id = tx.last_insert_rowid();
+			audit_repo::record_change(&tx, AuditEntity::SoftwareProduct, id, AuditAction::Insert, None)?;
 			tx.commit().context("Failed to commit transaction")?;
 
 			Ok(id)
-		})
+		}))
 			.await
 			.context("Failed to execute database operation")?
 	}
 
 	pub async fn add_software_version(&self, version: SoftwareVersion) -> Result<i64> {
+		access::require_write_access()?;
 		let pool = self.pool.clone();
 
-		task::spawn_blocking(move || {
-			let mut conn = pool.get().context("Failed to get database connection")?;
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
 			let tx = conn.transaction()?;
 
 			let result = tx.execute(
@@ -75,10 +260,11 @@ impl SoftwareRepository {
 			}
 
 			let id = tx.last_insert_rowid();
+			audit_repo::record_change(&tx, AuditEntity::SoftwareVersion, id, AuditAction::Insert, None)?;
 			tx.commit().context("Failed to commit transaction")?;
 
 			Ok(id)
-		})
+		}))
 			.await
 			.context("Failed to execute database operation")?
 	}
@@ -101,7 +287,8 @@ impl SoftwareRepository {
 					sp.vendor,
 					sp.description,
 					sv.version_number,
-					sv.release_date
+					sv.release_date,
+					sp.license
 				FROM affected_software af
 				JOIN software_versions sv ON af.version_id = sv.version_id
 				JOIN software_products sp ON sv.product_id = sp.product_id
@@ -133,6 +320,7 @@ impl SoftwareRepository {
 						product_name: row.get(6)?,
 						vendor: row.get(7)?,
 						description: row.get(8)?,
+						license: row.get(11)?,
 					},
 					SoftwareVersion {
 						version_id: Some(i32::try_from(version_id).map_err(|_| {
@@ -156,6 +344,300 @@ impl SoftwareRepository {
 			.context("Failed to execute database operation")?
 	}
 
+	/// Ranks deployed software versions by robots deployed × summed CVSS of unresolved vulnerabilities
+	#[tracing::instrument(level = "debug", skip(self))]
+	pub async fn get_top_risky_software(&self, limit: usize) -> Result<Vec<RiskySoftware>> {
+		let pool = self.pool.clone();
+
+		task::spawn_blocking(move || -> Result<_> {
+			let conn = pool.get().context("Failed to get database connection")?;
+
+			let mut stmt = conn.prepare(&format!(
+				"SELECT * FROM (
+					SELECT
+						sp.product_id,
+						sp.product_name,
+						sp.vendor,
+						sv.version_id,
+						sv.version_number,
+						sp.license,
+						(
+							SELECT COUNT(DISTINCT rs.robot_id)
+							FROM robot_software rs
+							WHERE rs.version_id = sv.version_id
+						) AS robot_count,
+						(
+							SELECT COALESCE(SUM({}), 0.0)
+							FROM affected_software af
+							JOIN vulnerabilities v ON v.vulnerability_id = af.vulnerability_id
+							LEFT JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id
+							WHERE af.version_id = sv.version_id AND v.deleted_at IS NULL AND {}
+						) AS cvss_sum
+					FROM software_versions sv
+					JOIN software_products sp ON sv.product_id = sp.product_id
+				)
+				WHERE robot_count > 0 AND cvss_sum > 0
+				ORDER BY robot_count * cvss_sum DESC
+				LIMIT ?1",
+				EFFECTIVE_CVSS_SQL, unresolved_status_sql()
+			)).context("Failed to prepare statement")?;
+
+			let results = stmt.query_map([limit as i64], |row| {
+				Ok(RiskySoftware {
+					product_id: row.get(0)?,
+					product_name: row.get(1)?,
+					vendor: row.get(2)?,
+					version_id: row.get(3)?,
+					version_number: row.get(4)?,
+					license: row.get(5)?,
+					robot_count: row.get(6)?,
+					cvss_sum: row.get(7)?,
+				})
+			})?;
+
+			results
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to collect risky software")
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Every software version installed on each robot, with its license and open findings
+	/// Every known software version with its metadata, by vendor and product
+	pub async fn get_version_metadata(&self) -> Result<Vec<VersionMetadata>> {
+		let pool = self.pool.clone();
+
+		task::spawn_blocking(move || -> Result<_> {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(
+				"SELECT sv.version_id, sp.product_name, sp.vendor, sv.version_number,
+					sv.release_date, sv.eol_date, sv.notes,
+					(SELECT COUNT(*) FROM robot_software rs WHERE rs.version_id = sv.version_id)
+				 FROM software_versions sv
+				 JOIN software_products sp ON sp.product_id = sv.product_id
+				 ORDER BY sp.vendor, sp.product_name, sv.version_number",
+			)?;
+			let versions = stmt
+				.query_map([], |row| {
+					Ok(VersionMetadata {
+						version_id: row.get(0)?,
+						product_name: row.get(1)?,
+						vendor: row.get(2)?,
+						version_number: row.get(3)?,
+						release_date: parse_date(row.get(4)?),
+						eol_date: parse_date(row.get(5)?),
+						notes: row.get(6)?,
+						robot_count: row.get(7)?,
+					})
+				})?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to load software versions")?;
+			Ok(versions)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Apply the same metadata change to each of `version_ids`, returning how many were updated
+	pub async fn update_version_metadata(&self, version_ids: Vec<i64>, change: VersionMetadataChange) -> Result<usize> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+
+		task::spawn_blocking(move || -> Result<_> {
+			let mut sets = Vec::new();
+			let mut values = Vec::new();
+			if let Some(release_date) = change.release_date {
+				// Stored as a timestamp like the release dates of added versions
+				sets.push("release_date = ?");
+				values.push(Value::from(release_date.map(|date| {
+					date.and_time(NaiveTime::MIN).format("%Y-%m-%d %H:%M:%S").to_string()
+				})));
+			}
+			if let Some(eol_date) = change.eol_date {
+				sets.push("eol_date = ?");
+				values.push(Value::from(eol_date.map(|date| date.format("%Y-%m-%d").to_string())));
+			}
+			if let Some(notes) = change.notes {
+				sets.push("notes = ?");
+				values.push(Value::from(notes));
+			}
+			if sets.is_empty() {
+				return Ok(0);
+			}
+
+			let sql = format!("UPDATE software_versions SET {} WHERE version_id = ?", sets.join(", "));
+			connection::with_write_retry(&pool, |conn| {
+				let tx = conn.transaction()?;
+				let opened = audit_repo::open_batch(&tx)?;
+				let mut updated = 0;
+				for &version_id in &version_ids {
+					let params = values.iter().cloned().chain(std::iter::once(Value::Integer(version_id)));
+					let before = audit_repo::snapshot(&tx, AuditEntity::SoftwareVersion, version_id)?;
+					updated += tx.execute(&sql, params_from_iter(params)).context("Failed to update software version")?;
+					audit_repo::record_change(&tx, AuditEntity::SoftwareVersion, version_id, AuditAction::Update, before)?;
+				}
+				// A bulk edit can be reverted from the audit log as a whole
+				if version_ids.len() > 1 {
+					audit_repo::close_batch(&tx, opened)?;
+				}
+				tx.commit()?;
+				info!("Updated metadata of {} software versions", updated);
+				Ok(updated)
+			})
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Software installed on one robot, by vendor and product
+	pub async fn get_robot_software(&self, robot_id: i64) -> Result<Vec<SoftwareRef>> {
+		let pool = self.pool.clone();
+
+		task::spawn_blocking(move || -> Result<_> {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(
+				"SELECT sp.product_name, sp.vendor, sv.version_number
+				 FROM robot_software rs
+				 JOIN software_versions sv ON sv.version_id = rs.version_id
+				 JOIN software_products sp ON sp.product_id = sv.product_id
+				 WHERE rs.robot_id = ?1
+				 ORDER BY sp.vendor, sp.product_name, sv.version_number",
+			)?;
+			let software = stmt
+				.query_map([robot_id], |row| {
+					Ok(SoftwareRef {
+						product_name: row.get(0)?,
+						vendor: row.get(1)?,
+						version_number: row.get(2)?,
+					})
+				})?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to load robot software")?;
+			Ok(software)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	pub async fn get_inventory(&self) -> Result<Vec<InventoryEntry>> {
+		let pool = self.pool.clone();
+
+		task::spawn_blocking(move || -> Result<_> {
+			let conn = pool.get().context("Failed to get database connection")?;
+
+			let mut stmt = conn.prepare(&format!(
+				"SELECT
+					r.name,
+					sp.product_name,
+					sp.vendor,
+					sv.version_number,
+					sp.license,
+					COUNT(DISTINCT open.vulnerability_id),
+					MAX(open.cvss),
+					CAST(MAX(CASE WHEN open.vulnerability_id IS NOT NULL THEN julianday('now') - MAX(
+						julianday(rs.installed_date),
+						COALESCE(julianday(open.published_date), 0)
+					) END) AS INTEGER)
+				FROM robot_software rs
+				JOIN robots r ON r.robot_id = rs.robot_id
+				JOIN software_versions sv ON sv.version_id = rs.version_id
+				JOIN software_products sp ON sp.product_id = sv.product_id
+				LEFT JOIN affected_software af ON af.version_id = sv.version_id
+				LEFT JOIN (
+					SELECT v.vulnerability_id, v.published_date, {} AS cvss
+					FROM vulnerabilities v
+					LEFT JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id
+					WHERE v.deleted_at IS NULL AND {}
+				) open ON open.vulnerability_id = af.vulnerability_id
+				GROUP BY rs.robot_id, sv.version_id
+				ORDER BY r.name, sp.product_name, sv.version_number",
+				EFFECTIVE_CVSS_SQL, unresolved_status_sql()
+			)).context("Failed to prepare statement")?;
+
+			let results = stmt.query_map([], |row| {
+				Ok(InventoryEntry {
+					robot_name: row.get(0)?,
+					product_name: row.get(1)?,
+					vendor: row.get(2)?,
+					version_number: row.get(3)?,
+					license: row.get(4)?,
+					open_vulnerabilities: row.get(5)?,
+					max_cvss: row.get(6)?,
+					max_exposure_days: row.get(7)?,
+				})
+			})?;
+
+			results
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to collect software inventory")
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// CVEs against the deployed software versions or the robots running them
+	pub async fn get_affected_matrix(&self, columns: MatrixColumns) -> Result<AffectedMatrix> {
+		let pool = self.pool.clone();
+
+		task::spawn_blocking(move || -> Result<_> {
+			let conn = pool.get().context("Failed to get database connection")?;
+
+			let mut versions = conn
+				.prepare(
+					"SELECT DISTINCT sv.version_id, sv.product_id, sp.product_name, sp.vendor, sv.version_number
+					 FROM robot_software rs
+					 JOIN software_versions sv ON sv.version_id = rs.version_id
+					 JOIN software_products sp ON sp.product_id = sv.product_id"
+				)?
+				.query_map([], |row| Ok(DeployedVersion {
+					version_id: row.get(0)?,
+					product_id: row.get(1)?,
+					product_name: row.get(2)?,
+					vendor: row.get(3)?,
+					version_number: row.get(4)?,
+				}))?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to collect deployed software")?;
+			matrix::sort_versions(&mut versions);
+
+			let correlations = conn
+				.prepare(
+					"SELECT v.cve_id, v.severity, COALESCE(s.status, 'Open'), af.version_id, sv.product_id, af.fixed_in_version
+					 FROM affected_software af
+					 JOIN vulnerabilities v ON v.vulnerability_id = af.vulnerability_id
+					 JOIN software_versions sv ON sv.version_id = af.version_id
+					 LEFT JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id
+					 WHERE v.deleted_at IS NULL AND sv.product_id IN (
+						SELECT dv.product_id FROM robot_software rs
+						JOIN software_versions dv ON dv.version_id = rs.version_id
+					 )"
+				)?
+				.query_map([], |row| Ok(MatrixCorrelation {
+					cve_id: row.get(0)?,
+					severity: row.get(1)?,
+					status: TriageStatus::from_db(&row.get::<_, String>(2)?),
+					version_id: row.get(3)?,
+					product_id: row.get(4)?,
+					fixed_in_version: row.get(5)?,
+				}))?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to collect correlations")?;
+
+			let installs = conn
+				.prepare(
+					"SELECT r.name, rs.version_id FROM robot_software rs JOIN robots r ON r.robot_id = rs.robot_id"
+				)?
+				.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+				.collect::<rusqlite::Result<Vec<(String, i64)>>>()
+				.context("Failed to collect installed software")?;
+
+			Ok(AffectedMatrix::build(columns, &versions, &correlations, &installs))
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
 	pub async fn search_software(&self, query: &str) -> Result<Vec<(SoftwareProduct, Vec<SoftwareVersion>)>> {
 		let pool = self.pool.clone();
 		let query = query.to_string();
@@ -171,7 +653,8 @@ impl SoftwareRepository {
 				sp.description,
 				sv.version_id,
 				sv.version_number,
-				sv.release_date
+				sv.release_date,
+				sp.license
 			FROM software_products sp
 			LEFT JOIN software_versions sv ON sp.product_id = sv.product_id
 			WHERE sp.product_name LIKE ?1 OR sp.vendor LIKE ?1
@@ -190,6 +673,7 @@ impl SoftwareRepository {
 					product_name: row.get(1)?,
 					vendor: row.get(2)?,
 					description: row.get(3)?,
+					license: row.get(7)?,
 				};
 
 				let version = match row.get::<_, Option<i64>>(4)? {
@@ -267,6 +751,7 @@ mod tests {
 			product_name: "Test Software".to_string(),
 			vendor: "Test Vendor".to_string(),
 			description: Some("Test Description".to_string()),
+			license: Some("MIT".to_string()),
 		};
 
 		let product_id = repo.add_software_product(product).await?;
@@ -283,12 +768,140 @@ mod tests {
 		let version_id = repo.add_software_version(version).await?;
 		assert!(version_id > 0);
 
+		// Bulk metadata changes only touch the given fields
+		let eol = NaiveDate::from_ymd_opt(2025, 5, 31).unwrap();
+		let change = VersionMetadataChange { eol_date: Some(Some(eol)), notes: Some(Some("LTS".to_string())), ..Default::default() };
+		assert_eq!(repo.update_version_metadata(vec![version_id], change).await?, 1);
+		let versions = repo.get_version_metadata().await?;
+		assert_eq!(versions.len(), 1);
+		assert_eq!(versions[0].release_date, Some(chrono::Utc::now().date_naive()));
+		assert_eq!(versions[0].eol_date, Some(eol));
+		assert_eq!(versions[0].notes.as_deref(), Some("LTS"));
+
+		let change = VersionMetadataChange { release_date: Some(None), ..Default::default() };
+		repo.update_version_metadata(vec![version_id], change).await?;
+		let versions = repo.get_version_metadata().await?;
+		assert_eq!((versions[0].release_date, versions[0].eol_date), (None, Some(eol)));
+
 		// Test search
 		let results = repo.search_software("Test").await?;
 		assert!(!results.is_empty());
 		assert_eq!(results[0].0.product_name, "Test Software");
+		assert_eq!(results[0].0.license.as_deref(), Some("MIT"));
 		assert!(!results[0].1.is_empty());
 
 		Ok(())
 	}
-}
\ No newline at end of file
+
+	#[tokio::test]
+	async fn test_top_risky_software() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let repo = SoftwareRepository::new(pool.clone());
+
+		let ros = repo.add_software_product(SoftwareProduct::new("ros-core".to_string(), "OSRF".to_string())).await?;
+		let fw = repo.add_software_product(SoftwareProduct::new("firmware".to_string(), "ACME".to_string())).await?;
+		let ros_v = repo.add_software_version(SoftwareVersion::new(ros as i32, "1.0".to_string())).await?;
+		let fw_v = repo.add_software_version(SoftwareVersion::new(fw as i32, "2.0".to_string())).await?;
+
+		let conn = pool.get()?;
+		conn.execute_batch(&format!(
+			"INSERT INTO robots (robot_id, name) VALUES (1, 'r1'), (2, 'r2'), (3, 'r3');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, {ros_v}), (2, {ros_v}), (3, {fw_v});
+			 INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity, cvss_score) VALUES
+				(1, 'CVE-2024-0001', 'High', 7.0),
+				(2, 'CVE-2024-0002', 'Low', NULL),
+				(3, 'CVE-2024-0003', 'Critical', 9.8);
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES
+				(1, {ros_v}, '1.0'), (2, {ros_v}, '1.0'), (3, {fw_v}, '2.0');
+			 INSERT INTO vulnerability_status (vulnerability_id, status) VALUES (3, 'Mitigated');"
+		))?;
+
+		// The mitigated firmware CVE no longer counts, so only ros-core remains
+		let ranked = repo.get_top_risky_software(10).await?;
+		assert_eq!(ranked.len(), 1);
+		assert_eq!(ranked[0].product_name, "ros-core");
+		assert_eq!(ranked[0].robot_count, 2);
+		assert!((ranked[0].cvss_sum - 9.0).abs() < f64::EPSILON);
+		assert!((ranked[0].risk_score() - 18.0).abs() < f64::EPSILON);
+
+		// The inventory lists every installation, including ones without open findings
+		let inventory = repo.get_inventory().await?;
+		assert_eq!(inventory.len(), 3);
+		assert_eq!((inventory[0].robot_name.as_str(), inventory[0].open_vulnerabilities), ("r1", 2));
+		assert_eq!(inventory[0].max_cvss, Some(7.0));
+		assert_eq!((inventory[2].product_name.as_str(), inventory[2].open_vulnerabilities), ("firmware", 0));
+		assert_eq!(inventory[2].max_cvss, None);
+		assert_eq!(inventory[2].max_exposure_days, None);
+
+		// Exposed from installation to the unpublished CVE, and only from publication
+		// to the CVE published after installation
+		conn.execute_batch(
+			"UPDATE robot_software SET installed_date = strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-100 days') WHERE robot_id = 1;
+			 UPDATE vulnerabilities SET published_date = date('now', '-30 days') WHERE vulnerability_id = 1;",
+		)?;
+		let inventory = repo.get_inventory().await?;
+		assert_eq!(inventory[0].max_exposure_days, Some(100));
+		conn.execute("UPDATE vulnerabilities SET published_date = date('now', '-30 days') WHERE vulnerability_id = 2", [])?;
+		assert_eq!(repo.get_inventory().await?[0].max_exposure_days, Some(30));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_ros_distro_correlation() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = connection::establish_pool_with_path(dir.path().join("test.db"))?;
+		let conn = pool.get()?;
+		conn.execute_batch(
+			"INSERT INTO robots (robot_id, name, ros_distro) VALUES (1, 'arm-01', 'ROS 2 Foxy');
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ROS 2', 'Open Robotics');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, 'humble'), (2, 1, 'iron');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 2);
+			 INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity) VALUES (1, 'CVE-2024-0001', 'High');
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES (1, 1, '<= humble');",
+		)?;
+
+		assert_eq!(refresh_robot_correlations(&conn, 1)?, 1);
+		// The distribution replaces the ROS version installed before
+		let installed: Vec<String> = conn
+			.prepare(
+				"SELECT sv.version_number FROM robot_software rs
+				 JOIN software_versions sv ON sv.version_id = rs.version_id WHERE rs.robot_id = 1",
+			)?
+			.query_map([], |row| row.get(0))?
+			.collect::<rusqlite::Result<_>>()?;
+		assert_eq!(installed, ["foxy"]);
+		let affected: i64 = conn.query_row(
+			"SELECT COUNT(*) FROM affected_software af
+			 JOIN robot_software rs ON rs.version_id = af.version_id WHERE rs.robot_id = 1",
+			[],
+			|row| row.get(0),
+		)?;
+		assert_eq!(affected, 1);
+
+		// ROS 1 codenames are not compared with ROS 2 ones, whose names sort before them
+		conn.execute_batch(
+			"INSERT INTO robots (robot_id, name, ros_distro) VALUES (2, 'arm-02', 'ROS 2 Humble'), (3, 'agv-01', 'kinetic');
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (2, 'ROS 1', 'Open Robotics');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (10, 2, 'melodic');
+			 INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity) VALUES (2, 'CVE-2024-0002', 'High');
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES (2, 10, '<= melodic');",
+		)?;
+		let exposed_to = |robot_id: i64| -> rusqlite::Result<Vec<String>> {
+			conn.prepare(
+				"SELECT v.cve_id FROM robot_software rs
+				 JOIN affected_software af ON af.version_id = rs.version_id
+				 JOIN vulnerabilities v ON v.vulnerability_id = af.vulnerability_id
+				 WHERE rs.robot_id = ?1 ORDER BY v.cve_id",
+			)?
+				.query_map([robot_id], |row| row.get(0))?
+				.collect()
+		};
+		refresh_robot_correlations(&conn, 2)?;
+		assert_eq!(exposed_to(2)?, ["CVE-2024-0001"]);
+		refresh_robot_correlations(&conn, 3)?;
+		assert_eq!(exposed_to(3)?, ["CVE-2024-0002"]);
+		Ok(())
+	}
+}
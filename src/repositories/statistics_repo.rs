@@ -0,0 +1,328 @@
+// src/repositories/statistics_repo.rs
+
+use crate::db::connection::SqlitePool;
+use crate::models::statistics::{ExposurePoint, FleetRisk, GroupRollup, StatisticsReport, Totals, WeaknessRollup, STATISTICS_FORMAT_VERSION};
+use crate::models::weakness::WeaknessClass;
+use crate::models::trends::FLEET_RISK_METRIC;
+use crate::repositories::robot_repo::current_fleet_risk_index;
+use crate::repositories::vulnerability_repo::{unresolved_status_sql, VulnerabilityFilter, STATUS_JOIN};
+use rusqlite::{params, params_from_iter, types::Value, Connection, OptionalExtension};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+use anyhow::{Result, Context};
+use tokio::task;
+
+/// Number of daily snapshots the exposure trend covers
+const EXPOSURE_TREND_DAYS: i64 = 90;
+
+pub struct StatisticsRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl StatisticsRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Compute all dashboard aggregates in SQL over the full database
+	pub async fn get_statistics(&self) -> Result<StatisticsReport> {
+		self.get_statistics_matching(VulnerabilityFilter::default()).await
+	}
+
+	/// Compute the dashboard aggregates in SQL, with the vulnerability counts restricted
+	/// to those matching `filter`
+	#[tracing::instrument(level = "debug", skip(self))]
+	pub async fn get_statistics_matching(&self, filter: VulnerabilityFilter) -> Result<StatisticsReport> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let (where_sql, values) = filter.where_sql();
+			let grouped = |key: &str, condition: &str| {
+				grouped_counts(
+					&conn,
+					&format!(
+						"SELECT {}, COUNT(*) FROM vulnerabilities v {} {}{} GROUP BY 1",
+						key, STATUS_JOIN, where_sql, condition
+					),
+					&values,
+				)
+			};
+
+			Ok(StatisticsReport {
+				format_version: STATISTICS_FORMAT_VERSION,
+				generated_at: chrono::Utc::now().to_rfc3339(),
+				filter: (!filter.is_empty()).then(|| filter.summary()),
+				totals: totals(&conn, &where_sql, &values)?,
+				by_severity: grouped("v.severity", "")?,
+				by_status: grouped("COALESCE(s.status, 'Open')", "")?,
+				published_per_year: grouped("substr(v.published_date, 1, 4)", " AND v.published_date IS NOT NULL")?,
+				by_manufacturer: manufacturer_rollups(&conn)?,
+				mttr_days: conn.query_row(
+					"SELECT AVG(julianday(s.updated_at) - julianday(v.published_date))
+					 FROM vulnerabilities v
+					 JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id
+					 WHERE s.status = 'Mitigated' AND v.published_date IS NOT NULL AND v.deleted_at IS NULL",
+					[],
+					|row| row.get(0),
+				).context("Failed to compute MTTR")?,
+				by_weakness_class: weakness_rollups(&conn)?,
+				fleet_risk: fleet_risk(&conn)?,
+				exposure_trend: exposure_trend(&conn)?,
+			})
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+/// Totals, with the vulnerabilities restricted by the filter's WHERE clause over `v`
+/// and `s` and the values it binds
+fn totals(conn: &Connection, where_sql: &str, values: &[Value]) -> Result<Totals> {
+	let count = |sql: &str, values: &[Value]| -> Result<i64> {
+		conn.query_row(sql, params_from_iter(values.iter()), |row| row.get(0))
+			.with_context(|| format!("Failed to run count query: {}", sql))
+	};
+	let vulnerabilities = format!("SELECT COUNT(*) FROM vulnerabilities v {} {}", STATUS_JOIN, where_sql);
+
+	Ok(Totals {
+		vulnerabilities: count(&vulnerabilities, values)?,
+		unresolved_vulnerabilities: count(&format!("{} AND {}", vulnerabilities, unresolved_status_sql()), values)?,
+		robots: count("SELECT COUNT(*) FROM robots WHERE deleted_at IS NULL", &[])?,
+		software_versions: count("SELECT COUNT(*) FROM software_versions", &[])?,
+	})
+}
+
+/// The fleet risk index over the stored robot scores, compared with the value last
+/// recorded before today. `None` without robots.
+fn fleet_risk(conn: &Connection) -> Result<Option<FleetRisk>> {
+	let robots: i64 = conn.query_row("SELECT COUNT(*) FROM robots WHERE deleted_at IS NULL", [], |row| row.get(0))?;
+	if robots == 0 {
+		return Ok(None);
+	}
+	let previous: Option<(String, f64)> = conn.query_row(
+		"SELECT recorded_on, value FROM metrics_history
+		 WHERE metric = ?1 AND recorded_on < date('now')
+		 ORDER BY recorded_on DESC LIMIT 1",
+		params![FLEET_RISK_METRIC],
+		|row| Ok((row.get(0)?, row.get(1)?)),
+	)
+		.optional()
+		.context("Failed to read fleet risk history")?;
+
+	Ok(Some(FleetRisk {
+		index: current_fleet_risk_index(conn)?,
+		previous: previous.as_ref().map(|(_, value)| *value),
+		previous_on: previous.map(|(recorded_on, _)| recorded_on),
+	}))
+}
+
+/// Open exposures and fleet risk of the last `EXPOSURE_TREND_DAYS` report snapshots,
+/// oldest first
+fn exposure_trend(conn: &Connection) -> Result<Vec<ExposurePoint>> {
+	let mut trend = conn
+		.prepare(
+			"SELECT taken_on, json_array_length(content, '$.open'), json_extract(content, '$.fleet_risk_index')
+			 FROM report_snapshots ORDER BY taken_on DESC LIMIT ?1",
+		)?
+		.query_map([EXPOSURE_TREND_DAYS], |row| {
+			Ok(ExposurePoint { day: row.get(0)?, open_exposures: row.get(1)?, fleet_risk_index: row.get(2)? })
+		})?
+		.collect::<rusqlite::Result<Vec<_>>>()
+		.context("Failed to read the exposure trend")?;
+	trend.reverse();
+	Ok(trend)
+}
+
+/// Runs a `SELECT key, COUNT(*) ... GROUP BY` query into an ordered map
+fn grouped_counts(conn: &Connection, sql: &str, values: &[Value]) -> Result<BTreeMap<String, i64>> {
+	let mut stmt = conn.prepare(sql)?;
+	let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+		Ok((row.get::<_, Option<String>>(0)?.unwrap_or_else(|| "Unknown".to_string()), row.get(1)?))
+	})?;
+
+	rows.collect::<rusqlite::Result<BTreeMap<_, _>>>()
+		.context("Failed to collect grouped counts")
+}
+
+fn manufacturer_rollups(conn: &Connection) -> Result<Vec<GroupRollup>> {
+	let mut stmt = conn.prepare(&format!(
+		"SELECT
+			COALESCE(NULLIF(r.manufacturer, ''), 'Unknown') AS grp,
+			COUNT(DISTINCT r.robot_id),
+			COUNT(DISTINCT u.vulnerability_id)
+		 FROM robots r
+		 LEFT JOIN robot_software rs ON rs.robot_id = r.robot_id
+		 LEFT JOIN affected_software af ON af.version_id = rs.version_id
+		 LEFT JOIN (
+			SELECT v.vulnerability_id
+			FROM vulnerabilities v
+			LEFT JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id
+			WHERE v.deleted_at IS NULL AND {}
+		 ) u ON u.vulnerability_id = af.vulnerability_id
+		 WHERE r.deleted_at IS NULL
+		 GROUP BY grp
+		 ORDER BY grp",
+		unresolved_status_sql()
+	))?;
+
+	let rows = stmt.query_map([], |row| {
+		Ok(GroupRollup {
+			group: row.get(0)?,
+			robots: row.get(1)?,
+			unresolved_vulnerabilities: row.get(2)?,
+		})
+	})?;
+
+	rows.collect::<rusqlite::Result<Vec<_>>>()
+		.context("Failed to collect manufacturer rollups")
+}
+
+/// Rolls the CWEs of each vulnerability up to their weakness class. The taxonomy
+/// lives in code, so the grouping happens here rather than in SQL.
+fn weakness_rollups(conn: &Connection) -> Result<Vec<WeaknessRollup>> {
+	let mut stmt = conn.prepare(&format!(
+		"SELECT
+			w.vulnerability_id,
+			w.cwe_id,
+			{} AND EXISTS (
+				SELECT 1 FROM affected_software af
+				JOIN robot_software rs ON rs.version_id = af.version_id
+				WHERE af.vulnerability_id = w.vulnerability_id
+			)
+		 FROM vulnerability_weaknesses w
+		 JOIN vulnerabilities v ON v.vulnerability_id = w.vulnerability_id
+		 LEFT JOIN vulnerability_status s ON s.vulnerability_id = w.vulnerability_id
+		 WHERE v.deleted_at IS NULL",
+		unresolved_status_sql()
+	))?;
+
+	let mut classes: BTreeMap<WeaknessClass, (HashSet<i64>, HashSet<i64>)> = BTreeMap::new();
+	let mut exposed = HashSet::new();
+	let mut rows = stmt.query([])?;
+	while let Some(row) = rows.next()? {
+		let vulnerability_id: i64 = row.get(0)?;
+		let cwe_id: String = row.get(1)?;
+		let (all, fleet) = classes.entry(WeaknessClass::of(&cwe_id)).or_default();
+		all.insert(vulnerability_id);
+		if row.get(2)? {
+			fleet.insert(vulnerability_id);
+			exposed.insert(vulnerability_id);
+		}
+	}
+
+	let mut rollups: Vec<WeaknessRollup> = classes
+		.into_iter()
+		.map(|(class, (all, fleet))| WeaknessRollup {
+			class,
+			vulnerabilities: all.len() as i64,
+			fleet_exposure: fleet.len() as i64,
+			exposure_share: if exposed.is_empty() { 0.0 } else { fleet.len() as f64 / exposed.len() as f64 },
+		})
+		.collect();
+	rollups.sort_by(|a, b| {
+		b.fleet_exposure.cmp(&a.fleet_exposure).then(b.vulnerabilities.cmp(&a.vulnerabilities))
+	});
+	Ok(rollups)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use crate::repositories::robot_repo::refresh_risk_scores;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_statistics_report() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity, published_date) VALUES
+				(1, 'CVE-2023-0001', 'High', '2023-01-01'),
+				(2, 'CVE-2024-0002', 'High', '2024-01-01'),
+				(3, 'CVE-2024-0003', 'Low', NULL);
+			 INSERT INTO vulnerability_status (vulnerability_id, status, updated_at) VALUES
+				(1, 'Mitigated', '2023-01-11 00:00:00');
+			 INSERT INTO robots (robot_id, name, manufacturer) VALUES (1, 'arm', 'KUKA'), (2, 'amr', NULL);
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ros', 'OSRF');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, '1.0');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 1);
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES
+				(1, 1, '1.0'), (2, 1, '1.0');
+			 INSERT INTO vulnerability_weaknesses (vulnerability_id, cwe_id) VALUES
+				(1, 'CWE-787'), (2, 'CWE-121'), (2, 'CWE-416'), (3, 'CWE-79');"
+		)?;
+
+		let report = StatisticsRepository::new(pool.clone()).get_statistics().await?;
+		assert_eq!(report.format_version, STATISTICS_FORMAT_VERSION);
+		assert_eq!(report.totals.vulnerabilities, 3);
+		assert_eq!(report.totals.unresolved_vulnerabilities, 2);
+		assert_eq!(report.totals.robots, 2);
+		assert_eq!(report.by_severity.get("High"), Some(&2));
+		assert_eq!(report.severity_counts()[1..4], [("High", 2), ("Medium", 0), ("Low", 1)]);
+		assert_eq!(report.by_status.get("Mitigated"), Some(&1));
+		assert_eq!(report.by_status.get("Open"), Some(&2));
+		assert_eq!(report.published_per_year.get("2024"), Some(&1));
+		assert_eq!(report.mttr_days, Some(10.0));
+		assert_eq!(report.filter, None);
+
+		// Vulnerability counts follow the list's filters, the fleet rollups do not
+		let high = VulnerabilityFilter { severity: Some("high".to_string()), ..Default::default() };
+		let filtered = StatisticsRepository::new(pool.clone()).get_statistics_matching(high).await?;
+		assert_eq!(filtered.filter.as_deref(), Some("severity high"));
+		assert_eq!((filtered.totals.vulnerabilities, filtered.totals.unresolved_vulnerabilities), (2, 1));
+		assert_eq!(filtered.by_severity.len(), 1);
+		assert_eq!(filtered.published_per_year.get("2023"), Some(&1));
+		assert_eq!(filtered.by_status.get("Open"), Some(&1));
+		assert_eq!(filtered.totals.robots, 2);
+		let search = VulnerabilityFilter { search: "CVE-2024".to_string(), ..Default::default() };
+		let filtered = StatisticsRepository::new(pool.clone()).get_statistics_matching(search).await?;
+		assert_eq!(filtered.totals.vulnerabilities, 2);
+		assert_eq!(filtered.published_per_year.keys().collect::<Vec<_>>(), ["2024"]);
+
+		// The mitigated CVE no longer counts towards KUKA's exposure
+		let kuka = report.by_manufacturer.iter().find(|g| g.group == "KUKA").unwrap();
+		assert_eq!(kuka.robots, 1);
+		assert_eq!(kuka.unresolved_vulnerabilities, 1);
+		assert!(report.by_manufacturer.iter().any(|g| g.group == "Unknown"));
+
+		// CVE-2 counts once for memory safety and is the only exposed one
+		let memory = &report.by_weakness_class[0];
+		assert_eq!(memory.class, WeaknessClass::MemorySafety);
+		assert_eq!((memory.vulnerabilities, memory.fleet_exposure), (2, 1));
+		assert_eq!(report.by_weakness_class[1].fleet_exposure, 0);
+		assert_eq!(report.exposure_skew().map(|r| r.class), Some(WeaknessClass::MemorySafety));
+
+		// The critical arm outweighs the unexposed amr; today's value is recorded on rescoring
+		let conn = pool.get()?;
+		conn.execute_batch(
+			"UPDATE robots SET criticality = 'Critical' WHERE robot_id = 1;
+			 INSERT INTO metrics_history (recorded_on, metric, value) VALUES ('2024-01-01', 'fleet_risk_index', 90.0);",
+		)?;
+		refresh_risk_scores(&conn)?;
+		let fleet_risk = StatisticsRepository::new(pool.clone()).get_statistics().await?.fleet_risk.unwrap();
+		let arm: f64 = conn.query_row("SELECT risk_score FROM robots WHERE robot_id = 1", [], |row| row.get(0))?;
+		assert_eq!(fleet_risk.index, (arm * 4.0 / 5.0 * 10.0).round() / 10.0);
+		assert_eq!(fleet_risk.previous_on.as_deref(), Some("2024-01-01"));
+		assert_eq!(fleet_risk.trend_arrow(), "↓");
+		let recorded: f64 = conn.query_row(
+			"SELECT value FROM metrics_history WHERE recorded_on = date('now')",
+			[],
+			|row| row.get(0),
+		)?;
+		assert_eq!(recorded, fleet_risk.index);
+
+		// Rescoring also took today's snapshot, after the one from an earlier day
+		conn.execute(
+			"INSERT INTO report_snapshots (taken_on, content) VALUES ('2024-01-01', ?1)",
+			[r#"{"taken_on":"2024-01-01","fleet_risk_index":90.0,"robots":[],"open":[{"cve_id":"CVE-2024-0002","severity":"High","robots":["arm"]},{"cve_id":"CVE-2024-0003","severity":"Low","robots":["arm"]}]}"#],
+		)?;
+		let trend = StatisticsRepository::new(pool.clone()).get_statistics().await?.exposure_trend;
+		assert_eq!(trend.len(), 2);
+		assert_eq!((trend[0].day.as_str(), trend[0].open_exposures, trend[0].fleet_risk_index), ("2024-01-01", 2, 90.0));
+		assert_eq!((trend[1].open_exposures, trend[1].fleet_risk_index), (1, fleet_risk.index));
+
+		Ok(())
+	}
+}
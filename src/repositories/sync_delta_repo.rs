@@ -0,0 +1,170 @@
+// src/repositories/sync_delta_repo.rs
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::sync_delta::{SyncChanges, SyncDelta, SyncState};
+use crate::repositories::vulnerability_repo::{unresolved_status_sql, STATUS_JOIN};
+use crate::utils::time;
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, Row};
+use std::sync::Arc;
+use tokio::task;
+
+/// Sync deltas kept; older ones are dropped as new ones are recorded
+pub const MAX_SYNC_DELTAS: usize = 200;
+
+/// Severities and unresolved robot exposures as they are now
+pub(crate) fn current_state(conn: &Connection) -> Result<SyncState> {
+	let severities = conn
+		.prepare("SELECT cve_id, severity FROM vulnerabilities WHERE deleted_at IS NULL")?
+		.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+		.collect::<rusqlite::Result<_>>()
+		.context("Failed to read severities")?;
+	let exposures = conn
+		.prepare(&format!(
+			"SELECT DISTINCT r.name, v.cve_id
+			 FROM robot_software rs
+			 JOIN robots r ON r.robot_id = rs.robot_id
+			 JOIN affected_software af ON af.version_id = rs.version_id
+			 JOIN vulnerabilities v ON v.vulnerability_id = af.vulnerability_id
+			 {}
+			 WHERE v.deleted_at IS NULL AND {}",
+			STATUS_JOIN, unresolved_status_sql()
+		))?
+		.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+		.collect::<rusqlite::Result<_>>()
+		.context("Failed to read robot exposures")?;
+	Ok(SyncState { severities, exposures })
+}
+
+fn delta_from_row(row: &Row) -> rusqlite::Result<(i64, String, String, String, String)> {
+	Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+}
+
+pub struct SyncDeltaRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl SyncDeltaRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// The state a sync is compared against, taken before it runs
+	pub async fn capture_state(&self) -> Result<SyncState> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			current_state(&conn)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Records what changed since `before` was captured, at the start of a sync of
+	/// `sources`, unless nothing did. Returns the changes.
+	pub async fn record(&self, started_at: DateTime<Utc>, sources: Vec<String>, before: SyncState) -> Result<SyncChanges> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let changes = SyncChanges::between(&before, &current_state(conn)?);
+			if changes.is_empty() {
+				return Ok(changes);
+			}
+			let tx = conn.transaction()?;
+			tx.execute(
+				"INSERT INTO sync_deltas (started_at, finished_at, sources, content) VALUES (?1, ?2, ?3, ?4)",
+				params![
+					started_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+					Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+					serde_json::to_string(&sources)?,
+					serde_json::to_string(&changes)?,
+				],
+			)?;
+			tx.execute(
+				"DELETE FROM sync_deltas WHERE delta_id NOT IN (
+					SELECT delta_id FROM sync_deltas ORDER BY delta_id DESC LIMIT ?1
+				 )",
+				[MAX_SYNC_DELTAS],
+			)?;
+			tx.commit()?;
+			Ok(changes)
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Recorded sync deltas, newest first, only those of syncs started at or after
+	/// `since` when given
+	pub async fn get_deltas(&self, since: Option<DateTime<Utc>>) -> Result<Vec<SyncDelta>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let rows = conn
+				.prepare(
+					"SELECT delta_id, started_at, finished_at, sources, content FROM sync_deltas
+					 WHERE ?1 IS NULL OR started_at >= ?1
+					 ORDER BY delta_id DESC",
+				)?
+				.query_map([since.map(|since| since.to_rfc3339_opts(SecondsFormat::Secs, true))], delta_from_row)?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to read sync deltas")?;
+			rows
+				.into_iter()
+				.map(|(delta_id, started_at, finished_at, sources, content)| {
+					Ok(SyncDelta {
+						delta_id,
+						started_at: time::parse_utc(&started_at).context("Invalid sync delta start")?,
+						finished_at: time::parse_utc(&finished_at).context("Invalid sync delta end")?,
+						sources: serde_json::from_str(&sources).context("Sync delta sources are not valid JSON")?,
+						changes: serde_json::from_str(&content).context("Sync delta is not valid JSON")?,
+					})
+				})
+				.collect()
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_record_deltas() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO robots (robot_id, name) VALUES (1, 'arm-01');
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ros-core', 'OSRF');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, '1.0');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 1);
+			 INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity) VALUES (1, 'CVE-2024-0001', 'Medium');",
+		)?;
+		let repo = SyncDeltaRepository::new(pool.clone());
+		let started = Utc::now();
+
+		// A sync that changed nothing is not kept
+		let before = repo.capture_state().await?;
+		assert!(repo.record(started, vec!["NVD".to_string()], before).await?.is_empty());
+		assert!(repo.get_deltas(None).await?.is_empty());
+
+		let before = repo.capture_state().await?;
+		pool.get()?.execute_batch(
+			"UPDATE vulnerabilities SET severity = 'High' WHERE vulnerability_id = 1;
+			 INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity) VALUES (2, 'CVE-2024-0002', 'Critical');
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES (2, 1, '1.0');",
+		)?;
+		let changes = repo.record(started, vec!["NVD".to_string(), "OSV".to_string()], before).await?;
+		assert_eq!(changes.to_string(), "1 new vulnerabilities, 1 severity changes, 1 new robot exposures");
+
+		let deltas = repo.get_deltas(None).await?;
+		assert_eq!(deltas.len(), 1);
+		assert_eq!(deltas[0].sources, ["NVD", "OSV"]);
+		assert_eq!(deltas[0].changes, changes);
+		assert_eq!(deltas[0].changes.new_exposures[0].robot, "arm-01");
+		assert!(repo.get_deltas(Some(Utc::now() + chrono::Duration::hours(1))).await?.is_empty());
+		Ok(())
+	}
+}
@@ -0,0 +1,184 @@
+// src/repositories/ticket_repo.rs
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::audit::{AuditAction, AuditEntity};
+use crate::models::ticket::Ticket;
+use crate::models::vulnerability::TriageStatus;
+use crate::repositories::{access, audit_repo};
+use crate::utils::time;
+use anyhow::{Context, Result};
+use log::debug;
+use rusqlite::{params, OptionalExtension, Row};
+use std::sync::Arc;
+use tokio::task;
+
+const TICKET_COLUMNS: &str = "t.vulnerability_id, t.ticket_key, t.url, t.status, t.created_by, t.created_at, t.synced_at";
+
+fn ticket_from_row(row: &Row) -> rusqlite::Result<Ticket> {
+	Ok(Ticket {
+		vulnerability_id: row.get(0)?,
+		key: row.get(1)?,
+		url: row.get(2)?,
+		status: row.get(3)?,
+		created_by: row.get(4)?,
+		created_at: row.get::<_, Option<String>>(5)?.as_deref().and_then(time::parse_utc),
+		synced_at: row.get::<_, Option<String>>(6)?.as_deref().and_then(time::parse_utc),
+	})
+}
+
+pub struct TicketRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl TicketRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// The ticket raised for a vulnerability, if any
+	pub async fn get_ticket(&self, vulnerability_id: i64) -> Result<Option<Ticket>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			conn.query_row(
+				&format!("SELECT {} FROM vulnerability_tickets t WHERE t.vulnerability_id = ?1", TICKET_COLUMNS),
+				[vulnerability_id],
+				ticket_from_row,
+			)
+				.optional()
+				.context("Failed to read ticket")
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Tickets of vulnerabilities that are not deleted, to sync their status
+	pub async fn get_tickets(&self) -> Result<Vec<Ticket>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(&format!(
+				"SELECT {} FROM vulnerability_tickets t
+				 JOIN vulnerabilities v ON v.vulnerability_id = t.vulnerability_id
+				 WHERE v.deleted_at IS NULL
+				 ORDER BY t.vulnerability_id",
+				TICKET_COLUMNS
+			))?;
+			let tickets = stmt.query_map([], ticket_from_row)?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to read tickets")?;
+			Ok(tickets)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Store the ticket raised for a vulnerability, replacing an earlier one
+	pub async fn save_ticket(&self, vulnerability_id: i64, key: &str, url: Option<String>, status: Option<String>) -> Result<()> {
+		access::require_write_access()?;
+		let key = key.trim().to_string();
+		let created_by = access::current_user();
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let before = audit_repo::snapshot(&tx, AuditEntity::Vulnerability, vulnerability_id)?;
+			tx.execute(
+				"INSERT OR REPLACE INTO vulnerability_tickets (vulnerability_id, ticket_key, url, status, created_by, synced_at)
+				 VALUES (?1, ?2, ?3, ?4, ?5, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))",
+				params![vulnerability_id, key, url, status, created_by],
+			).context("Failed to save ticket")?;
+			audit_repo::record_change(&tx, AuditEntity::Vulnerability, vulnerability_id, AuditAction::Update, before)?;
+			tx.commit()?;
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Record the status a ticket has in the tracker and move the vulnerability to
+	/// `triage` when given. Risk decisions are never overridden, as they were approved
+	/// outside the ticket. Returns whether the triage status changed.
+	pub async fn apply_ticket_status(&self, vulnerability_id: i64, status: &str, triage: Option<TriageStatus>) -> Result<bool> {
+		access::require_write_access()?;
+		let status = status.trim().to_string();
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			tx.execute(
+				"UPDATE vulnerability_tickets SET status = ?1, synced_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+				 WHERE vulnerability_id = ?2",
+				params![status, vulnerability_id],
+			).context("Failed to update ticket status")?;
+
+			let current = tx
+				.query_row("SELECT status FROM vulnerability_status WHERE vulnerability_id = ?1", [vulnerability_id], |row| {
+					row.get::<_, String>(0)
+				})
+				.optional()?
+				.map(|value| TriageStatus::from_db(&value))
+				.unwrap_or_default();
+			let changed = match triage {
+				Some(triage) if triage != current && !current.is_risk_decision() && !triage.is_risk_decision() => {
+					let before = audit_repo::snapshot(&tx, AuditEntity::Vulnerability, vulnerability_id)?;
+					tx.execute(
+						"INSERT INTO vulnerability_status (vulnerability_id, status, updated_at)
+						 VALUES (?1, ?2, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+						 ON CONFLICT(vulnerability_id) DO UPDATE SET
+							status = excluded.status,
+							updated_at = excluded.updated_at",
+						params![vulnerability_id, triage.as_str()],
+					).context("Failed to update triage status")?;
+					audit_repo::record_change(&tx, AuditEntity::Vulnerability, vulnerability_id, AuditAction::Update, before)?;
+					debug!("Ticket status {} moved vulnerability {} to {}", status, vulnerability_id, triage);
+					true
+				}
+				_ => false,
+			};
+			tx.commit()?;
+			Ok(changed)
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_apply_ticket_status() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity) VALUES
+				(1, 'CVE-2024-0001', 'High'), (2, 'CVE-2024-0002', 'Low');
+			 INSERT INTO vulnerability_status (vulnerability_id, status, justification, approved_by)
+				VALUES (2, 'Accepted Risk', 'Isolated network', 'CISO');",
+		)?;
+		let repo = TicketRepository::new(pool.clone());
+		assert!(repo.get_ticket(1).await?.is_none());
+
+		repo.save_ticket(1, " SEC-1 ", Some("https://jira/browse/SEC-1".to_string()), Some("To Do".to_string())).await?;
+		repo.save_ticket(2, "SEC-2", None, None).await?;
+		let ticket = repo.get_ticket(1).await?.expect("ticket saved");
+		assert_eq!(ticket.key, "SEC-1");
+		assert!(ticket.synced_at.is_some());
+		assert_eq!(repo.get_tickets().await?.len(), 2);
+
+		assert!(repo.apply_ticket_status(1, "Done", Some(TriageStatus::Mitigated)).await?);
+		assert!(!repo.apply_ticket_status(1, "Done", Some(TriageStatus::Mitigated)).await?);
+		// The accepted risk stays as approved
+		assert!(!repo.apply_ticket_status(2, "Done", Some(TriageStatus::Mitigated)).await?);
+
+		let conn = pool.get()?;
+		let status = |id: i64| conn.query_row("SELECT status FROM vulnerability_status WHERE vulnerability_id = ?1", [id], |row| {
+			row.get::<_, String>(0)
+		});
+		assert_eq!(status(1)?, "Mitigated");
+		assert_eq!(status(2)?, "Accepted Risk");
+		assert_eq!(repo.get_ticket(2).await?.and_then(|ticket| ticket.status).as_deref(), Some("Done"));
+		Ok(())
+	}
+}
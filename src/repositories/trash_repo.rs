@@ -0,0 +1,295 @@
+// src/repositories/trash_repo.rs
+
+//! Soft deletion. A deleted robot or vulnerability keeps its row with `deleted_at` set
+//! and is left out of every list and report until it is restored from Recently deleted
+//! or purged. A deleted robot's installed software is parked in
+//! `deleted_robot_software`, so it drops out of exposures and alerts as well.
+
+use crate::db::connection::{self, SqlitePool};
+use crate::models::audit::{AuditAction, AuditEntity};
+use crate::models::trash::{DeletedItem, DeletedKind, TRASH_RETENTION_DAYS};
+use crate::repositories::{access, audit_repo};
+use crate::repositories::alias_repo::VULNERABILITY_CHILDREN;
+use crate::repositories::robot_repo::refresh_risk_scores;
+use crate::utils::time;
+use anyhow::{bail, Context, Result};
+use rusqlite::Connection;
+use std::sync::Arc;
+use tokio::task;
+
+/// Table and ID column of each kind, with the column shown as its label
+fn table(kind: DeletedKind) -> (&'static str, &'static str, &'static str) {
+	match kind {
+		DeletedKind::Robot => ("robots", "robot_id", "name"),
+		DeletedKind::Vulnerability => ("vulnerabilities", "vulnerability_id", "cve_id"),
+	}
+}
+
+fn audit_entity(kind: DeletedKind) -> AuditEntity {
+	match kind {
+		DeletedKind::Robot => AuditEntity::Robot,
+		DeletedKind::Vulnerability => AuditEntity::Vulnerability,
+	}
+}
+
+/// Marks an entry deleted and rescores the fleet without it
+pub(crate) fn soft_delete(conn: &mut Connection, kind: DeletedKind, id: i64) -> Result<()> {
+	let (table, id_column, _) = table(kind);
+	let tx = conn.transaction()?;
+	let before = audit_repo::snapshot(&tx, audit_entity(kind), id)?;
+	let marked = tx.execute(
+		&format!(
+			"UPDATE {table} SET deleted_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+			 WHERE {id_column} = ?1 AND deleted_at IS NULL"
+		),
+		[id],
+	)?;
+	if marked == 0 {
+		bail!("{} not found", kind);
+	}
+	if kind == DeletedKind::Robot {
+		tx.execute(
+			"INSERT OR REPLACE INTO deleted_robot_software (robot_id, version_id, installed_date)
+			 SELECT robot_id, version_id, installed_date FROM robot_software WHERE robot_id = ?1",
+			[id],
+		)?;
+		tx.execute("DELETE FROM robot_software WHERE robot_id = ?1", [id])?;
+	}
+	audit_repo::record_change(&tx, audit_entity(kind), id, AuditAction::Delete, before)?;
+	refresh_risk_scores(&tx)?;
+	tx.commit()?;
+	Ok(())
+}
+
+/// Takes an entry out of Recently deleted, reinstalling a robot's parked software
+pub(crate) fn restore(conn: &mut Connection, kind: DeletedKind, id: i64) -> Result<()> {
+	let (table, id_column, _) = table(kind);
+	let tx = conn.transaction()?;
+	let before = audit_repo::snapshot(&tx, audit_entity(kind), id)?;
+	let restored = tx.execute(
+		&format!("UPDATE {table} SET deleted_at = NULL WHERE {id_column} = ?1 AND deleted_at IS NOT NULL"),
+		[id],
+	)?;
+	if restored == 0 {
+		bail!("{} is not in Recently deleted", kind);
+	}
+	if kind == DeletedKind::Robot {
+		tx.execute(
+			"INSERT OR IGNORE INTO robot_software (robot_id, version_id, installed_date)
+			 SELECT robot_id, version_id, installed_date FROM deleted_robot_software WHERE robot_id = ?1",
+			[id],
+		)?;
+		tx.execute("DELETE FROM deleted_robot_software WHERE robot_id = ?1", [id])?;
+	}
+	audit_repo::record_change(&tx, audit_entity(kind), id, AuditAction::Restore, before)?;
+	refresh_risk_scores(&tx)?;
+	tx.commit()?;
+	Ok(())
+}
+
+/// Deletes an entry in Recently deleted for good, with everything attached to it
+pub(crate) fn purge(conn: &mut Connection, kind: DeletedKind, id: i64) -> Result<()> {
+	let (table, id_column, _) = table(kind);
+	let tx = conn.transaction()?;
+	let deleted: bool = tx.query_row(
+		&format!("SELECT EXISTS (SELECT 1 FROM {table} WHERE {id_column} = ?1 AND deleted_at IS NOT NULL)"),
+		[id],
+		|row| row.get(0),
+	)?;
+	if !deleted {
+		bail!("{} is not in Recently deleted", kind);
+	}
+	let before = audit_repo::snapshot(&tx, audit_entity(kind), id)?;
+	match kind {
+		DeletedKind::Robot => delete_robot_rows(&tx, id)?,
+		DeletedKind::Vulnerability => delete_vulnerability_rows(&tx, id)?,
+	}
+	audit_repo::record_change(&tx, audit_entity(kind), id, AuditAction::Purge, before)?;
+	tx.commit()?;
+	Ok(())
+}
+
+/// Deletes a robot with everything attached to it, whether or not it is in Recently
+/// deleted. The installed and parked software and the commissioning checklist cascade.
+pub(crate) fn delete_robot_rows(conn: &Connection, id: i64) -> Result<()> {
+	conn.execute("DELETE FROM notes WHERE entity_type = 'robot' AND entity_id = ?1", [id])?;
+	conn.execute("DELETE FROM alert_outbox WHERE robot_id = ?1", [id])?;
+	conn.execute("DELETE FROM robots WHERE robot_id = ?1", [id])?;
+	Ok(())
+}
+
+/// Deletes a vulnerability with everything attached to it, whether or not it is in
+/// Recently deleted
+pub(crate) fn delete_vulnerability_rows(conn: &Connection, id: i64) -> Result<()> {
+	for (child, column) in VULNERABILITY_CHILDREN {
+		let condition = if child == "notes" { " AND entity_type = 'vulnerability'" } else { "" };
+		conn.execute(&format!("DELETE FROM {child} WHERE {column} = ?1{condition}"), [id])
+			.with_context(|| format!("Failed to delete {} of the vulnerability", child))?;
+	}
+	conn.execute("DELETE FROM vulnerabilities WHERE vulnerability_id = ?1", [id])?;
+	Ok(())
+}
+
+/// Entries in Recently deleted, most recently deleted first
+pub(crate) fn deleted_items(conn: &Connection) -> Result<Vec<DeletedItem>> {
+	let mut items = Vec::new();
+	for kind in [DeletedKind::Robot, DeletedKind::Vulnerability] {
+		let (table, id_column, label_column) = table(kind);
+		let mut stmt = conn.prepare(&format!(
+			"SELECT {id_column}, {label_column}, deleted_at FROM {table} WHERE deleted_at IS NOT NULL"
+		))?;
+		let rows = stmt
+			.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+			.collect::<rusqlite::Result<Vec<_>>>()?;
+		for (id, label, deleted_at) in rows {
+			let deleted_at = time::parse_utc(&deleted_at)
+				.with_context(|| format!("Invalid deletion time of {} {}", kind, label))?;
+			items.push(DeletedItem { kind, id, label, deleted_at });
+		}
+	}
+	items.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at).then_with(|| a.label.cmp(&b.label)));
+	Ok(items)
+}
+
+/// Purges the entries deleted more than the retention period ago; returns how many
+pub(crate) fn purge_expired(conn: &mut Connection) -> Result<usize> {
+	let cutoff = format!("-{} days", TRASH_RETENTION_DAYS);
+	let mut purged = 0;
+	for kind in [DeletedKind::Robot, DeletedKind::Vulnerability] {
+		let (table, id_column, _) = table(kind);
+		let expired = conn
+			.prepare(&format!(
+				"SELECT {id_column} FROM {table}
+				 WHERE deleted_at <= strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?1)"
+			))?
+			.query_map([&cutoff], |row| row.get(0))?
+			.collect::<rusqlite::Result<Vec<i64>>>()?;
+		for id in expired {
+			purge(conn, kind, id)?;
+			purged += 1;
+		}
+	}
+	Ok(purged)
+}
+
+pub struct TrashRepository {
+	pool: Arc<SqlitePool>,
+}
+
+impl TrashRepository {
+	pub fn new(pool: Arc<SqlitePool>) -> Self {
+		Self { pool }
+	}
+
+	/// Moves a robot or vulnerability to Recently deleted
+	pub async fn delete(&self, kind: DeletedKind, id: i64) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| soft_delete(conn, kind, id)))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	pub async fn restore(&self, kind: DeletedKind, id: i64) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| restore(conn, kind, id)))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Deletes an entry in Recently deleted permanently, without waiting for the retention period
+	pub async fn purge(&self, kind: DeletedKind, id: i64) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| purge(conn, kind, id)))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	pub async fn get_deleted(&self) -> Result<Vec<DeletedItem>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			deleted_items(&conn)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Purges entries deleted more than the retention period ago
+	pub async fn purge_expired(&self) -> Result<usize> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, purge_expired))
+			.await
+			.context("Failed to execute database operation")?
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::repositories::robot_repo::RobotRepository;
+	use crate::repositories::vulnerability_repo::{SortOrder, VulnerabilityFilter, VulnerabilityRepository};
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_delete_restore_purge() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity, cvss_score) VALUES
+				(1, 'CVE-2024-0001', 'Critical', 9.8),
+				(2, 'CVE-2024-0002', 'Low', 2.0);
+			 INSERT INTO robots (robot_id, name) VALUES (1, 'arm-01'), (2, 'arm-02');
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ros-core', 'OSRF');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, '1.0');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 1), (2, 1);
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES (1, 1, '1.0');"
+		)?;
+		let trash = TrashRepository::new(pool.clone());
+		let robots = RobotRepository::new(pool.clone());
+		let vulnerabilities = VulnerabilityRepository::new(pool.clone());
+		let listed = |page: crate::repositories::vulnerability_repo::VulnerabilityPage| {
+			page.vulnerabilities.into_iter().map(|v| v.cve_id).collect::<Vec<_>>()
+		};
+
+		trash.delete(DeletedKind::Robot, 1).await?;
+		assert!(trash.delete(DeletedKind::Robot, 1).await.is_err());
+		let names: Vec<String> = robots.get_robots_by_risk().await?.into_iter().map(|r| r.name).collect();
+		assert_eq!(names, ["arm-02"]);
+		assert!(robots.get_robot_vulnerabilities(1).await?.is_empty());
+
+		trash.delete(DeletedKind::Vulnerability, 1).await?;
+		let page = vulnerabilities.search_vulnerabilities(VulnerabilityFilter::default(), SortOrder::default(), 0, 10).await?;
+		assert_eq!(listed(page), ["CVE-2024-0002"]);
+		assert!(robots.get_robot_vulnerabilities(2).await?.is_empty());
+
+		let deleted = trash.get_deleted().await?;
+		assert_eq!(deleted.len(), 2);
+		assert!(deleted.iter().any(|item| item.kind == DeletedKind::Robot && item.label == "arm-01"));
+
+		// Restoring brings back the robot's software and with it its exposure
+		trash.restore(DeletedKind::Robot, 1).await?;
+		trash.restore(DeletedKind::Vulnerability, 1).await?;
+		let exposed: Vec<String> = robots.get_robot_vulnerabilities(1).await?.into_iter().map(|e| e.vulnerability.cve_id).collect();
+		assert_eq!(exposed, ["CVE-2024-0001"]);
+		assert!(trash.get_deleted().await?.is_empty());
+
+		// Only entries deleted longer than the retention period ago are purged
+		trash.delete(DeletedKind::Robot, 2).await?;
+		trash.delete(DeletedKind::Vulnerability, 1).await?;
+		pool.get()?.execute("UPDATE vulnerabilities SET deleted_at = '2020-01-01T00:00:00Z' WHERE vulnerability_id = 1", [])?;
+		assert_eq!(trash.purge_expired().await?, 1);
+		let remaining: Vec<String> = trash.get_deleted().await?.into_iter().map(|item| item.label).collect();
+		assert_eq!(remaining, ["arm-02"]);
+		let correlations: i64 = pool.get()?.query_row("SELECT COUNT(*) FROM affected_software", [], |row| row.get(0))?;
+		assert_eq!(correlations, 0);
+
+		trash.purge(DeletedKind::Robot, 2).await?;
+		assert!(trash.get_deleted().await?.is_empty());
+		assert!(trash.restore(DeletedKind::Robot, 2).await.is_err());
+		Ok(())
+	}
+}
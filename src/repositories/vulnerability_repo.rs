@@ -1,12 +1,422 @@
-use crate::db::connection::SqlitePool;
-use crate::models::vulnerability::Vulnerability;
-use rusqlite::params;
+use crate::db::connection::{self, SqlitePool};
+use crate::repositories::{access, audit_repo, provenance_repo, trash_repo};
+use crate::models::vulnerability::{Alias, CvssVersion, FieldLock, FieldProvenance, LockedField, ProvenanceField, ProvenanceSource, RelatedVulnerability, RiskAcceptance, TriageStatus, Vulnerability};
+use crate::models::audit::{AuditAction, AuditEntity};
+use crate::models::trash::DeletedKind;
+use crate::models::weakness::WeaknessClass;
+use crate::utils::time;
+use crate::db::schema;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, OptionalExtension};
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use log::{error, debug};
 use chrono::NaiveDate;
 use anyhow::{Result, Context};
 use tokio::task;
 
+/// Columns selected for a `Vulnerability`, in the order expected by `vulnerability_from_row`
+pub(crate) const VULNERABILITY_COLUMNS: &str =
+	"v.vulnerability_id, v.cve_id, v.description, v.severity, v.impact, v.mitigation, v.published_date,
+	 v.cvss_score, COALESCE(s.status, 'Open'), s.assigned_to,
+	 s.justification, s.approved_by, s.accepted_at, s.expires_on, v.kev_date_added,
+	 (SELECT group_concat(w.cwe_id, ' ') FROM vulnerability_weaknesses w WHERE w.vulnerability_id = v.vulnerability_id),
+	 v.cvss_version, v.source,
+	 (SELECT group_concat(a.alias || ' ' || a.source, char(10)) FROM vulnerability_aliases a WHERE a.vulnerability_id = v.vulnerability_id),
+	 (SELECT group_concat(l.field || ' ' || l.locked_at || ' ' || l.locked_by, char(10)) FROM field_locks l WHERE l.vulnerability_id = v.vulnerability_id),
+	 (SELECT group_concat(p.field || ' ' || p.source || ' ' || p.updated_at, char(10)) FROM field_provenance p WHERE p.vulnerability_id = v.vulnerability_id)";
+
+/// Number of columns in `VULNERABILITY_COLUMNS`
+pub(crate) const VULNERABILITY_COLUMN_COUNT: usize = 21;
+
+/// Join bringing in the triage state; vulnerabilities without a row are implicitly `Open`
+pub(crate) const STATUS_JOIN: &str =
+	"LEFT JOIN vulnerability_status s ON s.vulnerability_id = v.vulnerability_id";
+
+/// SQL counterpart of `Vulnerability::effective_cvss` over the `v` alias
+pub(crate) const EFFECTIVE_CVSS_SQL: &str =
+	"COALESCE(v.cvss_score, CASE lower(v.severity)
+		WHEN 'critical' THEN 9.5
+		WHEN 'high' THEN 8.0
+		WHEN 'medium' THEN 5.5
+		WHEN 'low' THEN 2.0
+		ELSE 0.0 END)";
+
+/// Search condition over the `v` alias; binds the LIKE pattern four times
+const SEARCH_FILTER_SQL: &str =
+	"(v.cve_id LIKE ? OR v.description LIKE ? OR EXISTS (
+		SELECT 1 FROM vulnerability_references r
+		WHERE r.vulnerability_id = v.vulnerability_id AND (r.advisory_id LIKE ? OR r.url LIKE ?)) OR EXISTS (
+		SELECT 1 FROM vulnerability_aliases a WHERE a.vulnerability_id = v.vulnerability_id AND a.alias LIKE ?))";
+
+/// Which vulnerabilities a page of the list is drawn from
+#[derive(Debug, Clone, Default)]
+pub struct VulnerabilityFilter {
+	/// Matched against CVE IDs, aliases, descriptions and references; empty matches all
+	pub search: String,
+	pub status: Option<TriageStatus>,
+	/// Severity label such as "high", compared by rank so case does not matter
+	pub severity: Option<String>,
+	/// Only the unresolved vulnerabilities affecting this software version, i.e.
+	/// its remediation queue
+	pub version_id: Option<i64>,
+	pub quick: BTreeSet<QuickFilter>,
+	/// Only vulnerabilities with a weakness of this class
+	pub weakness_class: Option<WeaknessClass>,
+}
+
+/// One-click filters above the list; active ones combine with AND
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QuickFilter {
+	Critical,
+	KnownExploited,
+	HasFix,
+	AffectsFleet,
+	/// Unresolved and not assigned to anyone
+	Unassigned,
+}
+
+impl QuickFilter {
+	pub const ALL: [QuickFilter; 5] = [
+		QuickFilter::Critical,
+		QuickFilter::KnownExploited,
+		QuickFilter::HasFix,
+		QuickFilter::AffectsFleet,
+		QuickFilter::Unassigned,
+	];
+
+	pub fn label(&self) -> &'static str {
+		match self {
+			QuickFilter::Critical => "Critical",
+			QuickFilter::KnownExploited => "KEV",
+			QuickFilter::HasFix => "Has fix",
+			QuickFilter::AffectsFleet => "Affects my fleet",
+			QuickFilter::Unassigned => "Unassigned",
+		}
+	}
+
+	/// Condition over the `v` and `s` aliases
+	fn condition_sql(&self) -> String {
+		match self {
+			QuickFilter::Critical => format!(
+				"{} = {}",
+				schema::severity_rank_sql("v.severity"),
+				schema::severity_rank("critical")
+			),
+			QuickFilter::KnownExploited => "v.kev_date_added IS NOT NULL".to_string(),
+			QuickFilter::HasFix => "EXISTS (SELECT 1 FROM affected_software af
+				WHERE af.vulnerability_id = v.vulnerability_id AND COALESCE(af.fixed_in_version, '') != '')"
+				.to_string(),
+			QuickFilter::AffectsFleet => "EXISTS (SELECT 1 FROM affected_software af
+				JOIN robot_software rs ON rs.version_id = af.version_id
+				WHERE af.vulnerability_id = v.vulnerability_id)"
+				.to_string(),
+			QuickFilter::Unassigned => format!(
+				"{} AND COALESCE(TRIM(s.assigned_to), '') = ''",
+				unresolved_status_sql()
+			),
+		}
+	}
+}
+
+impl VulnerabilityFilter {
+	/// Whether the filter matches every vulnerability
+	pub fn is_empty(&self) -> bool {
+		self.search.trim().is_empty()
+			&& self.status.is_none()
+			&& self.severity.is_none()
+			&& self.version_id.is_none()
+			&& self.quick.is_empty()
+			&& self.weakness_class.is_none()
+	}
+
+	/// Human-readable description, e.g. for a shared page of the matching list
+	pub fn summary(&self) -> String {
+		let mut parts = Vec::new();
+		if !self.search.trim().is_empty() {
+			parts.push(format!("search \"{}\"", self.search.trim()));
+		}
+		if let Some(severity) = &self.severity {
+			parts.push(format!("severity {}", severity));
+		}
+		if let Some(status) = self.status {
+			parts.push(format!("status {}", status));
+		}
+		if let Some(version_id) = self.version_id {
+			parts.push(format!("the remediation queue of software version {}", version_id));
+		}
+		parts.extend(self.quick.iter().map(|quick| quick.label().to_string()));
+		if let Some(class) = self.weakness_class {
+			parts.push(format!("weakness {}", class));
+		}
+
+		if parts.is_empty() {
+			"no filter".to_string()
+		} else {
+			parts.join(", ")
+		}
+	}
+
+	/// WHERE clause over the `v` and `s` aliases and the values it binds
+	pub(crate) fn where_sql(&self) -> (String, Vec<Value>) {
+		let mut conditions = vec!["v.deleted_at IS NULL".to_string()];
+		let mut values = Vec::new();
+
+		let search = self.search.trim();
+		if !search.is_empty() {
+			conditions.push(SEARCH_FILTER_SQL.to_string());
+			values.extend(std::iter::repeat_n(Value::Text(format!("%{}%", search)), 5));
+		}
+		if let Some(status) = self.status {
+			conditions.push("COALESCE(s.status, 'Open') = ?".to_string());
+			values.push(Value::Text(status.as_str().to_string()));
+		}
+		if let Some(severity) = &self.severity {
+			conditions.push(format!("{} = ?", schema::severity_rank_sql("v.severity")));
+			values.push(Value::Integer(schema::severity_rank(severity)));
+		}
+		if let Some(version_id) = self.version_id {
+			conditions.push(format!(
+				"EXISTS (SELECT 1 FROM affected_software af
+				 WHERE af.vulnerability_id = v.vulnerability_id AND af.version_id = ?) AND {}",
+				unresolved_status_sql()
+			));
+			values.push(Value::Integer(version_id));
+		}
+		conditions.extend(self.quick.iter().map(QuickFilter::condition_sql));
+		if let Some(class) = self.weakness_class {
+			// Classes are resolved from the CWE taxonomy in Rust, so the condition
+			// lists the CWEs of the class, or for `Other` excludes all classified ones
+			let (operator, cwe_ids) = match class {
+				WeaknessClass::Other => ("NOT IN", WeaknessClass::classified_cwe_ids()),
+				class => ("IN", class.cwe_ids()),
+			};
+			conditions.push(format!(
+				"EXISTS (SELECT 1 FROM vulnerability_weaknesses w
+				 WHERE w.vulnerability_id = v.vulnerability_id AND w.cwe_id {} ({}))",
+				operator,
+				vec!["?"; cwe_ids.len()].join(", ")
+			));
+			values.extend(cwe_ids.into_iter().map(Value::Text));
+		}
+
+		(format!("WHERE {}", conditions.join(" AND ")), values)
+	}
+}
+
+/// Column the list is sorted by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortColumn {
+	#[default]
+	Published,
+	CveId,
+	Severity,
+	/// CVSS score, or an estimate from the severity when there is none
+	Risk,
+	/// Triage status in workflow order, open first
+	Status,
+}
+
+impl SortColumn {
+	/// Sort key over the `v` alias; published, CVE ID and severity are indexed
+	fn key_sql(&self) -> String {
+		match self {
+			SortColumn::Published => "COALESCE(v.published_date, '')".to_string(),
+			SortColumn::CveId => "v.cve_id".to_string(),
+			SortColumn::Severity => schema::severity_rank_sql("v.severity"),
+			SortColumn::Risk => EFFECTIVE_CVSS_SQL.to_string(),
+			SortColumn::Status => {
+				let cases = TriageStatus::ALL
+					.iter()
+					.enumerate()
+					.map(|(rank, status)| format!("WHEN '{}' THEN {}", status.as_str(), rank))
+					.collect::<Vec<_>>()
+					.join(" ");
+				format!("CASE COALESCE(s.status, '{}') {} ELSE 0 END", TriageStatus::Open.as_str(), cases)
+			}
+		}
+	}
+}
+
+/// Order of the list; ties are broken by ID in the same direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortOrder {
+	pub column: SortColumn,
+	pub ascending: bool,
+}
+
+impl Default for SortOrder {
+	/// Newest first
+	fn default() -> Self {
+		SortOrder { column: SortColumn::Published, ascending: false }
+	}
+}
+
+/// Position after the last row of a page, from which the next page continues.
+/// Only valid for the filter and order it was read with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageCursor {
+	pub after_key: Value,
+	pub after_id: i64,
+}
+
+/// Where a page starts
+#[derive(Debug)]
+enum PageStart {
+	Offset(usize),
+	After(Option<PageCursor>),
+}
+
+/// One page of the list
+#[derive(Debug, Clone, Default)]
+pub struct VulnerabilityPage {
+	pub vulnerabilities: Vec<Vulnerability>,
+	/// Pages of all matching vulnerabilities
+	pub total_pages: usize,
+	/// Where the following page continues; `None` after the last row
+	pub next: Option<PageCursor>,
+}
+
+fn query_page(
+	conn: &rusqlite::Connection,
+	filter: &VulnerabilityFilter,
+	order: SortOrder,
+	start: PageStart,
+	page_size: usize,
+) -> Result<VulnerabilityPage> {
+	let (where_sql, mut values) = filter.where_sql();
+	let count: i64 = conn.query_row(
+		&format!("SELECT COUNT(*) FROM vulnerabilities v {} {}", STATUS_JOIN, where_sql),
+		params_from_iter(values.iter()),
+		|row| row.get(0),
+	)?;
+
+	let key = order.column.key_sql();
+	let (direction, comparison) = if order.ascending { ("ASC", ">") } else { ("DESC", "<") };
+	let mut conditions = where_sql;
+	let offset = match start {
+		PageStart::Offset(page) => page * page_size,
+		PageStart::After(None) => 0,
+		PageStart::After(Some(cursor)) => {
+			let seek = format!("({}, v.vulnerability_id) {} (?, ?)", key, comparison);
+			conditions = format!("{} AND {}", conditions, seek);
+			values.push(cursor.after_key);
+			values.push(Value::Integer(cursor.after_id));
+			0
+		}
+	};
+	values.push(Value::Integer(page_size as i64));
+	values.push(Value::Integer(offset as i64));
+
+	let mut stmt = conn.prepare(&format!(
+		"SELECT {}, {} FROM vulnerabilities v {}
+		 {}
+		 ORDER BY {} {}, v.vulnerability_id {}
+		 LIMIT ? OFFSET ?",
+		VULNERABILITY_COLUMNS, key, STATUS_JOIN, conditions, key, direction, direction
+	))?;
+	let rows = stmt
+		.query_map(params_from_iter(values.iter()), |row| {
+			Ok((vulnerability_from_row(row)?, row.get::<_, Value>(VULNERABILITY_COLUMN_COUNT)?))
+		})?
+		.collect::<rusqlite::Result<Vec<_>>>()?;
+
+	let next = rows.last().and_then(|(vulnerability, key)| {
+		Some(PageCursor { after_key: key.clone(), after_id: vulnerability.vulnerability_id? })
+	});
+	Ok(VulnerabilityPage {
+		vulnerabilities: rows.into_iter().map(|(vulnerability, _)| vulnerability).collect(),
+		total_pages: (count as usize).div_ceil(page_size),
+		next,
+	})
+}
+
+/// SQL condition matching vulnerabilities whose triage status is unresolved
+pub(crate) fn unresolved_status_sql() -> String {
+	let statuses = TriageStatus::ALL
+		.iter()
+		.filter(|status| status.is_unresolved())
+		.map(|status| format!("'{}'", status.as_str()))
+		.collect::<Vec<_>>()
+		.join(", ");
+	format!("COALESCE(s.status, 'Open') IN ({})", statuses)
+}
+
+/// Maps a row selected with `VULNERABILITY_COLUMNS` into a `Vulnerability`
+pub(crate) fn vulnerability_from_row(row: &rusqlite::Row) -> rusqlite::Result<Vulnerability> {
+	let status = TriageStatus::from_db(&row.get::<_, String>(8)?);
+	let risk_acceptance = if status.is_risk_decision() {
+		Some(RiskAcceptance {
+			justification: row.get(10)?,
+			approved_by: row.get(11)?,
+			accepted_at: row.get::<_, Option<String>>(12)?.as_deref().and_then(time::parse_utc),
+			expires_on: row.get::<_, Option<String>>(13)?
+				.and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
+		})
+	} else {
+		None
+	};
+
+	Ok(Vulnerability {
+		vulnerability_id: row.get(0)?,
+		cve_id: row.get(1)?,
+		description: row.get(2)?,
+		severity: row.get(3)?,
+		impact: row.get(4)?,
+		mitigation: row.get(5)?,
+		published_date: row.get::<_, Option<String>>(6)?
+			.and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
+		cvss_score: row.get(7)?,
+		cvss_version: row.get::<_, Option<String>>(16)?
+			.and_then(|version| CvssVersion::from_db(&version)),
+		status,
+		assigned_to: row.get(9)?,
+		risk_acceptance,
+		kev_date_added: row.get::<_, Option<String>>(14)?
+			.and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
+		cwe_ids: row.get::<_, Option<String>>(15)?
+			.map(|ids| ids.split_whitespace().map(str::to_string).collect())
+			.unwrap_or_default(),
+		source: row.get(17)?,
+		aliases: row.get::<_, Option<String>>(18)?
+			.map(|aliases| {
+				aliases
+					.lines()
+					.filter_map(|line| line.split_once(' '))
+					.map(|(id, source)| Alias { id: id.to_string(), source: source.to_string() })
+					.collect()
+			})
+			.unwrap_or_default(),
+		locks: row.get::<_, Option<String>>(19)?
+			.map(|locks| locks.lines().filter_map(parse_lock).collect())
+			.unwrap_or_default(),
+		provenance: row.get::<_, Option<String>>(20)?
+			.map(|provenance| provenance.lines().filter_map(parse_provenance).collect())
+			.unwrap_or_default(),
+	})
+}
+
+/// Parses a `field locked_at locked_by` line of the locks column
+fn parse_lock(line: &str) -> Option<FieldLock> {
+	let (field, rest) = line.split_once(' ')?;
+	let (locked_at, locked_by) = rest.split_once(' ')?;
+	Some(FieldLock {
+		field: LockedField::from_db(field)?,
+		locked_by: locked_by.to_string(),
+		locked_at: time::parse_utc(locked_at),
+	})
+}
+
+/// Parses a `field source updated_at` line of the provenance column
+fn parse_provenance(line: &str) -> Option<FieldProvenance> {
+	let mut parts = line.split(' ');
+	Some(FieldProvenance {
+		field: ProvenanceField::from_db(parts.next()?)?,
+		source: ProvenanceSource::from_db(parts.next()?)?,
+		updated_at: parts.next().and_then(time::parse_utc),
+	})
+}
+
 pub struct VulnerabilityRepository {
 	pool: Arc<SqlitePool>,
 }
@@ -17,15 +427,15 @@ impl VulnerabilityRepository {
 	}
 
 	pub async fn add_vulnerability(&self, vulnerability: Vulnerability) -> Result<i64> {
+		access::require_write_access()?;
 		let pool = self.pool.clone();
-		task::spawn_blocking(move || {
-			let conn = pool.get().context("Failed to get database connection")?;
-
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
 			let published_date = vulnerability.published_date.map(|date| date.format("%Y-%m-%d").to_string());
 
-			let result = conn.execute(
-				"INSERT INTO vulnerabilities (cve_id, description, severity, impact, mitigation, published_date)
-				 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+			let tx = conn.transaction()?;
+			let result = tx.execute(
+				"INSERT INTO vulnerabilities (cve_id, description, severity, impact, mitigation, published_date, cvss_score, cvss_version)
+				 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
 				params![
 					vulnerability.cve_id,
 					vulnerability.description,
@@ -33,6 +443,8 @@ impl VulnerabilityRepository {
 					vulnerability.impact,
 					vulnerability.mitigation,
 					published_date,
+					vulnerability.cvss_score,
+					vulnerability.cvss_version.map(|v| v.as_str()),
 				],
 			).context("Failed to execute INSERT query")?;
 
@@ -40,35 +452,30 @@ impl VulnerabilityRepository {
 				anyhow::bail!("Unexpected number of rows affected: {}", result);
 			}
 
-			let id = conn.last_insert_rowid();
+			let id = tx.last_insert_rowid();
+			audit_repo::record_change(&tx, AuditEntity::Vulnerability, id, AuditAction::Insert, None)?;
+			tx.commit()?;
 			debug!("Inserted vulnerability with ID: {}", id);
 			Ok(id)
-		})
+		}))
 			.await
 			.context("Failed to execute database operation")?
 	}
 
+	#[tracing::instrument(level = "debug", skip(self))]
 	pub async fn get_all_vulnerabilities(&self) -> Result<Vec<Vulnerability>> {
 		let pool = self.pool.clone();
 		task::spawn_blocking(move || {
 			let conn = pool.get().context("Failed to get database connection")?;
 
 			let mut stmt = conn
-				.prepare("SELECT vulnerability_id, cve_id, description, severity, impact, mitigation, published_date FROM vulnerabilities")
+				.prepare(&format!(
+					"SELECT {} FROM vulnerabilities v {} WHERE v.deleted_at IS NULL",
+					VULNERABILITY_COLUMNS, STATUS_JOIN
+				))
 				.context("Failed to prepare SELECT query")?;
 
-			let vulnerability_iter = stmt.query_map([], |row| {
-				Ok(Vulnerability {
-					vulnerability_id: row.get(0)?,
-					cve_id: row.get(1)?,
-					description: row.get(2)?,
-					severity: row.get(3)?,
-					impact: row.get(4)?,
-					mitigation: row.get(5)?,
-					published_date: row.get::<_, Option<String>>(6)?
-						.and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
-				})
-			})
+			let vulnerability_iter = stmt.query_map([], vulnerability_from_row)
 				.context("Failed to execute SELECT query")?;
 
 			vulnerability_iter
@@ -83,40 +490,52 @@ impl VulnerabilityRepository {
 		let pool = self.pool.clone();
 		task::spawn_blocking(move || {
 			let conn = pool.get().context("Failed to get database connection")?;
-			let mut stmt = conn.prepare(
-				"SELECT vulnerability_id, cve_id, description, severity, impact, mitigation, published_date
-				 FROM vulnerabilities WHERE vulnerability_id = ?"
-			)?;
+			let mut stmt = conn.prepare(&format!(
+				"SELECT {} FROM vulnerabilities v {} WHERE v.vulnerability_id = ? AND v.deleted_at IS NULL",
+				VULNERABILITY_COLUMNS, STATUS_JOIN
+			))?;
 
-			stmt.query_row([id], |row| {
-				Ok(Vulnerability {
-					vulnerability_id: row.get(0)?,
-					cve_id: row.get(1)?,
-					description: row.get(2)?,
-					severity: row.get(3)?,
-					impact: row.get(4)?,
-					mitigation: row.get(5)?,
-					published_date: row.get::<_, Option<String>>(6)?
-						.and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
-				})
-			})
+			stmt.query_row([id], vulnerability_from_row)
 				.context("Failed to find vulnerability")
 		})
 			.await
 			.context("Failed to execute database operation")?
 	}
 
-	pub async fn update_vulnerability(&self, vulnerability: &Vulnerability) -> Result<()> {
+	/// Look up a vulnerability by CVE ID, ignoring case
+	pub async fn get_vulnerability_by_cve(&self, cve_id: &str) -> Result<Option<Vulnerability>> {
 		let pool = self.pool.clone();
-		let vulnerability = vulnerability.clone();
+		let cve_id = cve_id.to_string();
 		task::spawn_blocking(move || {
 			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(&format!(
+				"SELECT {} FROM vulnerabilities v {} WHERE v.cve_id = ?1 COLLATE NOCASE AND v.deleted_at IS NULL",
+				VULNERABILITY_COLUMNS, STATUS_JOIN
+			))?;
+
+			stmt.query_row([&cve_id], vulnerability_from_row)
+				.optional()
+				.context("Failed to find vulnerability")
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	pub async fn update_vulnerability(&self, vulnerability: &Vulnerability) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		let vulnerability = vulnerability.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
 			let published_date = vulnerability.published_date.map(|date| date.format("%Y-%m-%d").to_string());
+			let id = vulnerability.vulnerability_id.context("Vulnerability not found or multiple rows affected")?;
+			let tx = conn.transaction()?;
+			let before = audit_repo::snapshot(&tx, AuditEntity::Vulnerability, id)?;
 
-			let result = conn.execute(
+			let result = tx.execute(
 				"UPDATE vulnerabilities
-				 SET cve_id = ?1, description = ?2, severity = ?3, impact = ?4, mitigation = ?5, published_date = ?6
-				 WHERE vulnerability_id = ?7",
+				 SET cve_id = ?1, description = ?2, severity = ?3, impact = ?4, mitigation = ?5, published_date = ?6, cvss_score = ?7,
+				 cvss_version = ?8
+				 WHERE vulnerability_id = ?9",
 				params![
 					vulnerability.cve_id,
 					vulnerability.description,
@@ -124,86 +543,332 @@ impl VulnerabilityRepository {
 					vulnerability.impact,
 					vulnerability.mitigation,
 					published_date,
-					vulnerability.vulnerability_id,
+					vulnerability.cvss_score,
+					vulnerability.cvss_version.map(|v| v.as_str()),
+					id,
 				],
 			)?;
 
 			if result != 1 {
 				anyhow::bail!("Vulnerability not found or multiple rows affected");
 			}
+			audit_repo::record_change(&tx, AuditEntity::Vulnerability, id, AuditAction::Update, before)?;
+			tx.commit()?;
 			Ok(())
-		})
+		}))
 			.await
 			.context("Failed to execute database operation")?
 	}
 
+	/// Sets fields edited by hand and locks them under `editor`'s name, so that imports and
+	/// NVD refreshes leave them as edited until they are unlocked
+	pub async fn edit_fields(
+		&self,
+		vulnerability_id: i64,
+		edits: Vec<(LockedField, Option<String>)>,
+		editor: String,
+	) -> Result<()> {
+		access::require_write_access()?;
+		let edits: Vec<(LockedField, Option<String>)> = edits
+			.into_iter()
+			.map(|(field, value)| (field, value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())))
+			.collect();
+		if edits.iter().any(|(field, value)| *field == LockedField::Severity && value.is_none()) {
+			anyhow::bail!("The severity cannot be empty");
+		}
+		let editor = editor.split_whitespace().collect::<Vec<_>>().join(" ");
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let before = audit_repo::snapshot(&tx, AuditEntity::Vulnerability, vulnerability_id)?;
+			for (field, value) in &edits {
+				// The lock goes first, so its trigger keeps the new value
+				tx.execute(
+					"INSERT INTO field_locks (vulnerability_id, field, value, locked_by) VALUES (?1, ?2, ?3, ?4)
+					 ON CONFLICT (vulnerability_id, field) DO UPDATE SET
+						value = excluded.value, locked_by = excluded.locked_by,
+						locked_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+					params![vulnerability_id, field.as_str(), value, editor],
+				).context("Failed to lock field")?;
+				let updated = tx.execute(
+					&format!("UPDATE vulnerabilities SET {} = ?1 WHERE vulnerability_id = ?2", field.as_str()),
+					params![value, vulnerability_id],
+				).with_context(|| format!("Failed to update the {}", field))?;
+				if updated != 1 {
+					anyhow::bail!("Vulnerability not found");
+				}
+			}
+			let fields: Vec<ProvenanceField> = edits.iter().map(|(field, _)| ProvenanceField::from(*field)).collect();
+			provenance_repo::record(&tx, vulnerability_id, &fields, ProvenanceSource::Manual)?;
+			audit_repo::record_change(&tx, AuditEntity::Vulnerability, vulnerability_id, AuditAction::Update, before)?;
+			tx.commit()?;
+			debug!("{} edited fields of vulnerability {}", editor, vulnerability_id);
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Lets automatic refreshes update a field edited by hand again; returns whether it was locked
+	pub async fn unlock_field(&self, vulnerability_id: i64, field: LockedField) -> Result<bool> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let before = audit_repo::snapshot(&tx, AuditEntity::Vulnerability, vulnerability_id)?;
+			let removed = tx.execute(
+				"DELETE FROM field_locks WHERE vulnerability_id = ?1 AND field = ?2",
+				params![vulnerability_id, field.as_str()],
+			).context("Failed to unlock field")?;
+			provenance_repo::forget_manual(&tx, vulnerability_id, field.into())?;
+			audit_repo::record_change(&tx, AuditEntity::Vulnerability, vulnerability_id, AuditAction::Update, before)?;
+			tx.commit()?;
+			Ok(removed > 0)
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Moves a vulnerability to Recently deleted, from where it can be restored
 	pub async fn delete_vulnerability(&self, id: i64) -> Result<()> {
+		access::require_write_access()?;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			trash_repo::soft_delete(conn, DeletedKind::Vulnerability, id)
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Sets the triage status and assignee of a vulnerability. The risk acceptance is
+	/// recorded for risk decisions and cleared for every other status.
+	pub async fn update_triage(
+		&self,
+		vulnerability_id: i64,
+		status: TriageStatus,
+		assigned_to: Option<String>,
+		acceptance: RiskAcceptance,
+	) -> Result<()> {
+		access::require_write_access()?;
+		acceptance.validate(status)?;
+		let assigned_to = assigned_to.filter(|a| !a.trim().is_empty());
+		let acceptance = if status.is_risk_decision() { acceptance } else { RiskAcceptance::default() };
+		let trimmed = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+		let justification = trimmed(acceptance.justification);
+		let approved_by = trimmed(acceptance.approved_by);
+		let expires_on = acceptance.expires_on.map(|d| d.to_string());
+
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || connection::with_write_retry(&pool, |conn| {
+			let tx = conn.transaction()?;
+			let before = audit_repo::snapshot(&tx, AuditEntity::Vulnerability, vulnerability_id)?;
+			tx.execute(
+				"INSERT INTO vulnerability_status
+					(vulnerability_id, status, assigned_to, updated_at, justification, approved_by, accepted_at, expires_on)
+				 VALUES (?1, ?2, ?3, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), ?4, ?5,
+					CASE WHEN ?6 THEN strftime('%Y-%m-%dT%H:%M:%SZ', 'now') END, ?7)
+				 ON CONFLICT(vulnerability_id) DO UPDATE SET
+					status = excluded.status,
+					assigned_to = excluded.assigned_to,
+					updated_at = excluded.updated_at,
+					justification = excluded.justification,
+					approved_by = excluded.approved_by,
+					accepted_at = excluded.accepted_at,
+					expires_on = excluded.expires_on",
+				params![
+					vulnerability_id,
+					status.as_str(),
+					assigned_to,
+					justification,
+					approved_by,
+					status.is_risk_decision(),
+					expires_on,
+				],
+			).context("Failed to update triage status")?;
+			audit_repo::record_change(&tx, AuditEntity::Vulnerability, vulnerability_id, AuditAction::Update, before)?;
+			tx.commit()?;
+
+			debug!("Set triage status of vulnerability {} to {}", vulnerability_id, status);
+			Ok(())
+		}))
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// Vulnerabilities closed by accepting the risk or marking them false positive, for the
+	/// risk acceptance report; those expiring soonest come first
+	pub async fn get_risk_decisions(&self) -> Result<Vec<Vulnerability>> {
 		let pool = self.pool.clone();
 		task::spawn_blocking(move || {
 			let conn = pool.get().context("Failed to get database connection")?;
-			let result = conn.execute("DELETE FROM vulnerabilities WHERE vulnerability_id = ?", [id])?;
+			let statuses = TriageStatus::ALL
+				.iter()
+				.filter(|status| status.is_risk_decision())
+				.map(|status| format!("'{}'", status.as_str()))
+				.collect::<Vec<_>>()
+				.join(", ");
+			let mut stmt = conn.prepare(&format!(
+				"SELECT {} FROM vulnerabilities v {}
+				 WHERE s.status IN ({}) AND v.deleted_at IS NULL
+				 ORDER BY s.expires_on IS NULL, s.expires_on, v.cve_id",
+				VULNERABILITY_COLUMNS, STATUS_JOIN, statuses
+			))?;
 
-			if result != 1 {
-				anyhow::bail!("Vulnerability not found or multiple rows affected");
-			}
-			Ok(())
+			let vulnerabilities = stmt
+				.query_map([], vulnerability_from_row)?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to collect risk decisions")?;
+			Ok(vulnerabilities)
 		})
 			.await
 			.context("Failed to execute database operation")?
 	}
 
+	/// Up to `limit` other vulnerabilities sharing an affected product or CWE with the
+	/// given one. Those sharing more products come first, then those sharing more CWEs,
+	/// then the riskiest.
+	pub async fn get_related_vulnerabilities(&self, vulnerability_id: i64, limit: usize) -> Result<Vec<RelatedVulnerability>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut stmt = conn.prepare(&format!(
+				"WITH matches AS (
+					SELECT af.vulnerability_id, 'product' AS kind, sp.product_name AS shared
+					FROM affected_software af
+					JOIN software_versions sv ON sv.version_id = af.version_id
+					JOIN software_products sp ON sp.product_id = sv.product_id
+					WHERE sv.product_id IN (
+						SELECT sv2.product_id FROM affected_software af2
+						JOIN software_versions sv2 ON sv2.version_id = af2.version_id
+						WHERE af2.vulnerability_id = ?1)
+					UNION
+					SELECT w.vulnerability_id, 'cwe', w.cwe_id
+					FROM vulnerability_weaknesses w
+					WHERE w.cwe_id IN (SELECT cwe_id FROM vulnerability_weaknesses WHERE vulnerability_id = ?1)
+				)
+				SELECT v.cve_id, v.severity, COALESCE(s.status, 'Open'), {cvss},
+					group_concat(CASE WHEN m.kind = 'product' THEN m.shared END, char(10)),
+					group_concat(CASE WHEN m.kind = 'cwe' THEN m.shared END, char(10))
+				FROM matches m
+				JOIN vulnerabilities v ON v.vulnerability_id = m.vulnerability_id
+				{status_join}
+				WHERE v.vulnerability_id != ?1 AND v.deleted_at IS NULL
+				GROUP BY v.vulnerability_id
+				ORDER BY COUNT(*) FILTER (WHERE m.kind = 'product') DESC, COUNT(*) DESC, {cvss} DESC, v.cve_id
+				LIMIT ?2",
+				cvss = EFFECTIVE_CVSS_SQL,
+				status_join = STATUS_JOIN,
+			))?;
+
+			let lines = |value: Option<String>| -> Vec<String> {
+				value.map(|value| value.lines().map(str::to_string).collect()).unwrap_or_default()
+			};
+			let related = stmt
+				.query_map(params![vulnerability_id, limit as i64], |row| {
+					Ok(RelatedVulnerability {
+						cve_id: row.get(0)?,
+						severity: row.get(1)?,
+						status: TriageStatus::from_db(&row.get::<_, String>(2)?),
+						effective_cvss: row.get(3)?,
+						shared_products: lines(row.get(4)?),
+						shared_cwes: lines(row.get(5)?),
+					})
+				})?
+				.collect::<rusqlite::Result<Vec<_>>>()
+				.context("Failed to collect related vulnerabilities")?;
+			Ok(related)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
+
+	/// One page of the vulnerabilities matching `filter`, jumping to a page number.
+	/// The OFFSET scan grows with the page number, so this is for jumps;
+	/// `search_vulnerabilities_after` continues from a page.
+	#[tracing::instrument(level = "debug", skip(self))]
 	pub async fn search_vulnerabilities(
 		&self,
-		query: &str,
+		filter: VulnerabilityFilter,
+		order: SortOrder,
 		page: usize,
 		page_size: usize
-	) -> Result<(Vec<Vulnerability>, usize)> {
+	) -> Result<VulnerabilityPage> {
 		let pool = self.pool.clone();
-		let query = query.to_string();
-
 		task::spawn_blocking(move || {
 			let conn = pool.get().context("Failed to get database connection")?;
+			query_page(&conn, &filter, order, PageStart::Offset(page), page_size)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
 
-			// Get total count
-			let mut count_stmt = conn.prepare(
-				"SELECT COUNT(*) FROM vulnerabilities
-				 WHERE cve_id LIKE ?1 OR description LIKE ?1"
-			)?;
+	/// Like `search_vulnerabilities`, but returns the page following `after` (the first
+	/// page when `None`). Seeks by key instead of skipping rows, and rows inserted
+	/// while browsing do not shift later pages.
+	#[tracing::instrument(level = "debug", skip(self))]
+	pub async fn search_vulnerabilities_after(
+		&self,
+		filter: VulnerabilityFilter,
+		order: SortOrder,
+		after: Option<PageCursor>,
+		page_size: usize
+	) -> Result<VulnerabilityPage> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			query_page(&conn, &filter, order, PageStart::After(after), page_size)
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
 
-			let search_pattern = format!("%{}%", query);
-			let total_count: i64 = count_stmt.query_row([&search_pattern], |row| row.get(0))?;
-			let total_pages = (total_count as usize + page_size - 1) / page_size;
+	/// All vulnerabilities matching `filter` in list order, fetched page by page
+	pub async fn get_matching_vulnerabilities(&self, filter: VulnerabilityFilter, order: SortOrder) -> Result<Vec<Vulnerability>> {
+		const PAGE_SIZE: usize = 500;
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let mut vulnerabilities = Vec::new();
+			let mut after = None;
+			loop {
+				let page = query_page(&conn, &filter, order, PageStart::After(after), PAGE_SIZE)?;
+				vulnerabilities.extend(page.vulnerabilities);
+				match page.next {
+					Some(next) => after = Some(next),
+					None => return Ok(vulnerabilities),
+				}
+			}
+		})
+			.await
+			.context("Failed to execute database operation")?
+	}
 
-			// Get paginated results
-			let mut stmt = conn.prepare(
-				"SELECT vulnerability_id, cve_id, description, severity, impact, mitigation, published_date
-				 FROM vulnerabilities
-				 WHERE cve_id LIKE ?1 OR description LIKE ?1
-				 LIMIT ?2 OFFSET ?3"
-			)?;
+	/// How many vulnerabilities each quick filter would show on its own, within the
+	/// rest of `filter`. Its active quick filters are left out, so the counts do not
+	/// drop to zero as chips are combined.
+	pub async fn count_quick_filters(&self, filter: VulnerabilityFilter) -> Result<Vec<(QuickFilter, i64)>> {
+		let pool = self.pool.clone();
+		task::spawn_blocking(move || {
+			let conn = pool.get().context("Failed to get database connection")?;
+			let base = VulnerabilityFilter { quick: BTreeSet::new(), ..filter };
+			let (where_sql, values) = base.where_sql();
+			let counts = QuickFilter::ALL
+				.iter()
+				.map(|quick| format!("COUNT(*) FILTER (WHERE {})", quick.condition_sql()))
+				.collect::<Vec<_>>()
+				.join(", ");
 
-			let vulnerability_iter = stmt.query_map(
-				params![
-					search_pattern,
-					page_size as i64,
-					(page * page_size) as i64
-				],
+			conn.query_row(
+				&format!("SELECT {} FROM vulnerabilities v {} {}", counts, STATUS_JOIN, where_sql),
+				params_from_iter(values.iter()),
 				|row| {
-					Ok(Vulnerability {
-						vulnerability_id: row.get(0)?,
-						cve_id: row.get(1)?,
-						description: row.get(2)?,
-						severity: row.get(3)?,
-						impact: row.get(4)?,
-						mitigation: row.get(5)?,
-						published_date: row.get::<_, Option<String>>(6)?
-							.and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
-					})
+					QuickFilter::ALL
+						.iter()
+						.enumerate()
+						.map(|(idx, quick)| Ok((*quick, row.get(idx)?)))
+						.collect()
 				},
-			)?;
-
-			let vulnerabilities = vulnerability_iter.collect::<rusqlite::Result<Vec<_>>>()?;
-			Ok((vulnerabilities, total_pages))
+			).context("Failed to count quick filters")
 		})
 			.await
 			.context("Failed to execute database operation")?
@@ -214,32 +879,22 @@ impl VulnerabilityRepository {
 mod tests {
 	use super::*;
 	use crate::db::connection;
+	use crate::models::reference::Reference;
+	use tempfile::{tempdir, TempDir};
+	use rusqlite::Connection;
 
-	async fn setup_test_db() -> Result<Arc<SqlitePool>> {
-		let pool = Arc::new(connection::establish_pool_with_path(
-			std::path::PathBuf::from(":memory:")
-		)?);
+	async fn setup_test_db() -> Result<(Arc<SqlitePool>, TempDir)> {
+		// Each pooled connection to ":memory:" would see its own empty database,
+		// so tests use a temporary file shared by the whole pool
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
 
-		// Create tables
-		let conn = pool.get()?;
-		conn.execute_batch(
-			"CREATE TABLE IF NOT EXISTS vulnerabilities (
-				vulnerability_id INTEGER PRIMARY KEY AUTOINCREMENT,
-				cve_id TEXT UNIQUE NOT NULL,
-				description TEXT,
-				severity TEXT NOT NULL,
-				impact TEXT,
-				mitigation TEXT,
-				published_date TEXT
-			);"
-		)?;
-
-		Ok(pool)
+		Ok((pool, dir))
 	}
 
 	#[tokio::test]
 	async fn test_crud_operations() -> Result<()> {
-		let pool = setup_test_db().await?;
+		let (pool, _dir) = setup_test_db().await?;
 		let repo = VulnerabilityRepository::new(pool);
 
 		// Test Create
@@ -251,6 +906,17 @@ mod tests {
 			impact: Some("Test impact".to_string()),
 			mitigation: Some("Test mitigation".to_string()),
 			published_date: Some(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
+			cvss_score: None,
+			cvss_version: None,
+			status: TriageStatus::Open,
+			assigned_to: None,
+			risk_acceptance: None,
+			kev_date_added: None,
+			cwe_ids: Vec::new(),
+			source: None,
+			aliases: Vec::new(),
+			locks: Vec::new(),
+			provenance: Vec::new(),
 		};
 
 		let id = repo.add_vulnerability(vuln.clone()).await?;
@@ -259,11 +925,18 @@ mod tests {
 		// Test Read
 		let retrieved = repo.get_vulnerability_by_id(id).await?;
 		assert_eq!(retrieved.cve_id, vuln.cve_id);
+		let by_cve = repo.get_vulnerability_by_cve("cve-2024-test").await?;
+		assert_eq!(by_cve.and_then(|v| v.vulnerability_id), Some(id));
+		assert!(repo.get_vulnerability_by_cve("CVE-2024-0000").await?.is_none());
 
 		// Test Search
-		let (results, total_pages) = repo.search_vulnerabilities("TEST", 0, 10).await?;
-		assert!(!results.is_empty());
-		assert!(total_pages > 0);
+		let page = repo.search_vulnerabilities(search("TEST"), SortOrder::default(), 0, 10).await?;
+		assert!(!page.vulnerabilities.is_empty());
+		assert!(page.total_pages > 0);
+		let mitigated = VulnerabilityFilter { status: Some(TriageStatus::Mitigated), ..search("TEST") };
+		let page = repo.search_vulnerabilities(mitigated, SortOrder::default(), 0, 10).await?;
+		assert!(page.vulnerabilities.is_empty());
+		assert_eq!(page.total_pages, 0);
 
 		// Test Update
 		let mut updated = retrieved.clone();
@@ -283,7 +956,7 @@ mod tests {
 
 	#[tokio::test]
 	async fn test_concurrent_operations() -> Result<()> {
-		let pool = setup_test_db().await?;
+		let (pool, _dir) = setup_test_db().await?;
 		let repo = VulnerabilityRepository::new(pool.clone());
 
 		let handle1 = {
@@ -297,6 +970,17 @@ mod tests {
 					impact: None,
 					mitigation: None,
 					published_date: None,
+					cvss_score: None,
+					cvss_version: None,
+					status: TriageStatus::Open,
+					assigned_to: None,
+					risk_acceptance: None,
+					kev_date_added: None,
+					cwe_ids: Vec::new(),
+					source: None,
+					aliases: Vec::new(),
+					locks: Vec::new(),
+					provenance: Vec::new(),
 				};
 				repo.add_vulnerability(vuln).await
 			})
@@ -313,6 +997,17 @@ mod tests {
 					impact: None,
 					mitigation: None,
 					published_date: None,
+					cvss_score: None,
+					cvss_version: None,
+					status: TriageStatus::Open,
+					assigned_to: None,
+					risk_acceptance: None,
+					kev_date_added: None,
+					cwe_ids: Vec::new(),
+					source: None,
+					aliases: Vec::new(),
+					locks: Vec::new(),
+					provenance: Vec::new(),
 				};
 				repo.add_vulnerability(vuln).await
 			})
@@ -324,4 +1019,316 @@ mod tests {
 
 		Ok(())
 	}
-}
\ No newline at end of file
+
+	#[tokio::test]
+	async fn test_update_triage() -> Result<()> {
+		let (pool, _dir) = setup_test_db().await?;
+		let repo = VulnerabilityRepository::new(pool);
+
+		let id = repo
+			.add_vulnerability(Vulnerability::new("CVE-2024-0001".to_string(), "High".to_string()))
+			.await?;
+
+		// Vulnerabilities without a status row are open and unassigned
+		let vuln = repo.get_vulnerability_by_id(id).await?;
+		assert_eq!(vuln.status, TriageStatus::Open);
+		assert_eq!(vuln.assigned_to, None);
+
+		repo.update_triage(id, TriageStatus::InProgress, Some("alice".to_string()), RiskAcceptance::default()).await?;
+		let vuln = repo.get_vulnerability_by_id(id).await?;
+		assert_eq!(vuln.status, TriageStatus::InProgress);
+		assert_eq!(vuln.assigned_to.as_deref(), Some("alice"));
+
+		// Blank assignee clears the assignment
+		repo.update_triage(id, TriageStatus::Mitigated, Some("  ".to_string()), RiskAcceptance::default()).await?;
+		let vuln = repo.get_vulnerability_by_id(id).await?;
+		assert_eq!(vuln.status, TriageStatus::Mitigated);
+		assert_eq!(vuln.assigned_to, None);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_risk_acceptance() -> Result<()> {
+		let (pool, _dir) = setup_test_db().await?;
+		let repo = VulnerabilityRepository::new(pool);
+		let id = repo
+			.add_vulnerability(Vulnerability::new("CVE-2024-0001".to_string(), "High".to_string()))
+			.await?;
+
+		// An undocumented acceptance is refused
+		let undocumented = RiskAcceptance { justification: Some("Air-gapped".to_string()), ..Default::default() };
+		assert!(repo.update_triage(id, TriageStatus::AcceptedRisk, None, undocumented.clone()).await.is_err());
+
+		let acceptance = RiskAcceptance {
+			approved_by: Some("CISO".to_string()),
+			expires_on: NaiveDate::from_ymd_opt(2025, 6, 30),
+			..undocumented
+		};
+		repo.update_triage(id, TriageStatus::AcceptedRisk, None, acceptance).await?;
+		let decisions = repo.get_risk_decisions().await?;
+		assert_eq!(decisions.len(), 1);
+		let recorded = decisions[0].risk_acceptance.clone().unwrap();
+		assert_eq!(recorded.approved_by.as_deref(), Some("CISO"));
+		assert!(recorded.accepted_at.is_some());
+		assert!(recorded.is_expired(NaiveDate::from_ymd_opt(2025, 7, 1).unwrap()));
+
+		// Reopening clears the decision
+		repo.update_triage(id, TriageStatus::Open, None, RiskAcceptance::default()).await?;
+		assert!(repo.get_risk_decisions().await?.is_empty());
+		assert!(repo.get_vulnerability_by_id(id).await?.risk_acceptance.is_none());
+
+		Ok(())
+	}
+
+	fn search(text: &str) -> VulnerabilityFilter {
+		VulnerabilityFilter { search: text.to_string(), ..VulnerabilityFilter::default() }
+	}
+
+	fn cve_ids(page: &VulnerabilityPage) -> Vec<&str> {
+		page.vulnerabilities.iter().map(|v| v.cve_id.as_str()).collect()
+	}
+
+	async fn add_paged(repo: &VulnerabilityRepository, cve_id: &str, severity: &str, published: Option<(i32, u32, u32)>) -> Result<i64> {
+		let vuln = Vulnerability {
+			published_date: published.and_then(|(y, m, d)| NaiveDate::from_ymd_opt(y, m, d)),
+			description: Some("Paged".to_string()),
+			..Vulnerability::new(cve_id.to_string(), severity.to_string())
+		};
+		repo.add_vulnerability(vuln).await
+	}
+
+	#[tokio::test]
+	async fn test_keyset_pagination() -> Result<()> {
+		let (pool, _dir) = setup_test_db().await?;
+		let repo = VulnerabilityRepository::new(pool.clone());
+		add_paged(&repo, "CVE-2024-0001", "Low", Some((2024, 1, 1))).await?;
+		add_paged(&repo, "CVE-2024-0002", "HIGH", Some((2024, 3, 1))).await?;
+		add_paged(&repo, "CVE-2024-0003", "Medium", None).await?;
+		add_paged(&repo, "CVE-2024-0004", "high", Some((2024, 3, 1))).await?;
+
+		let newest = SortOrder::default();
+		let first = repo.search_vulnerabilities_after(search("Paged"), newest, None, 2).await?;
+		assert_eq!(first.total_pages, 2);
+		assert_eq!(cve_ids(&first), ["CVE-2024-0004", "CVE-2024-0002"]);
+
+		// A row inserted ahead of the cursor does not shift the next page
+		add_paged(&repo, "CVE-2024-0005", "Low", Some((2024, 6, 1))).await?;
+		let second = repo.search_vulnerabilities_after(search("Paged"), newest, first.next, 2).await?;
+		assert_eq!(cve_ids(&second), ["CVE-2024-0001", "CVE-2024-0003"]);
+
+		let rest = repo.search_vulnerabilities_after(search("Paged"), newest, second.next, 2).await?;
+		assert!(rest.vulnerabilities.is_empty());
+		assert!(rest.next.is_none());
+
+		// Severity sorts by rank, whatever the case of the label
+		let by_severity = SortOrder { column: SortColumn::Severity, ascending: true };
+		let first = repo.search_vulnerabilities_after(search("Paged"), by_severity, None, 3).await?;
+		assert_eq!(cve_ids(&first), ["CVE-2024-0001", "CVE-2024-0005", "CVE-2024-0003"]);
+		let second = repo.search_vulnerabilities_after(search("Paged"), by_severity, first.next, 3).await?;
+		assert_eq!(cve_ids(&second), ["CVE-2024-0002", "CVE-2024-0004"]);
+
+		// Status sorts in workflow order; entries without a triage record are open
+		pool.get()?.execute(
+			"INSERT OR REPLACE INTO vulnerability_status (vulnerability_id, status)
+			 SELECT vulnerability_id, 'Mitigated' FROM vulnerabilities WHERE cve_id = 'CVE-2024-0003'",
+			[],
+		)?;
+		let by_status = SortOrder { column: SortColumn::Status, ascending: false };
+		let first = repo.search_vulnerabilities_after(search("Paged"), by_status, None, 2).await?;
+		assert_eq!(cve_ids(&first), ["CVE-2024-0003", "CVE-2024-0005"]);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_severity_filter_fills_pages() -> Result<()> {
+		let (pool, _dir) = setup_test_db().await?;
+		let repo = VulnerabilityRepository::new(pool.clone());
+		for i in 0..10 {
+			let severity = if i % 2 == 0 { "High" } else { "LOW" };
+			add_paged(&repo, &format!("CVE-2024-{:04}", i), severity, Some((2024, 1, 1 + i as u32))).await?;
+		}
+
+		let high = VulnerabilityFilter { severity: Some("high".to_string()), ..VulnerabilityFilter::default() };
+		let by_cve = SortOrder { column: SortColumn::CveId, ascending: false };
+		let page = repo.search_vulnerabilities(high.clone(), by_cve, 0, 3).await?;
+		assert_eq!(page.total_pages, 2);
+		assert_eq!(cve_ids(&page), ["CVE-2024-0008", "CVE-2024-0006", "CVE-2024-0004"]);
+		let page = repo.search_vulnerabilities(high.clone(), by_cve, 1, 3).await?;
+		assert_eq!(cve_ids(&page), ["CVE-2024-0002", "CVE-2024-0000"]);
+		assert_eq!(high.summary(), "severity high");
+		let all = repo.get_matching_vulnerabilities(high, by_cve).await?;
+		assert_eq!(all.len(), 5);
+		assert_eq!(all[4].cve_id, "CVE-2024-0000");
+
+		// The default and severity orders are read from an index, not sorted per query
+		let conn = pool.get()?;
+		for column in [SortColumn::Published, SortColumn::Severity] {
+			let key = column.key_sql();
+			let plan = conn
+				.prepare(&format!(
+					"EXPLAIN QUERY PLAN SELECT v.vulnerability_id FROM vulnerabilities v {} WHERE v.deleted_at IS NULL
+					 ORDER BY {} DESC, v.vulnerability_id DESC LIMIT 15",
+					STATUS_JOIN, key
+				))?
+				.query_map([], |row| row.get::<_, String>(3))?
+				.collect::<rusqlite::Result<Vec<_>>>()?
+				.join("\n");
+			assert!(!plan.contains("TEMP B-TREE"), "{:?}: {}", column, plan);
+		}
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_quick_filters() -> Result<()> {
+		let (pool, _dir) = setup_test_db().await?;
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity, kev_date_added) VALUES
+				(1, 'CVE-2024-0001', 'CRITICAL', '2024-03-01'),
+				(2, 'CVE-2024-0002', 'Critical', NULL),
+				(3, 'CVE-2024-0003', 'Low', NULL);
+			 INSERT INTO vulnerability_status (vulnerability_id, status, assigned_to) VALUES
+				(1, 'In Progress', 'alice'), (3, 'Mitigated', NULL);
+			 INSERT INTO robots (robot_id, name) VALUES (1, 'arm');
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ros', 'OSRF');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, '1.0'), (2, 1, '2.0');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 1);
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern, fixed_in_version) VALUES
+				(1, 1, '1.0', '1.1'), (2, 2, '2.0', NULL);",
+		)?;
+		let repo = VulnerabilityRepository::new(pool);
+
+		let counts = repo.count_quick_filters(VulnerabilityFilter::default()).await?;
+		assert_eq!(counts, [
+			(QuickFilter::Critical, 2),
+			(QuickFilter::KnownExploited, 1),
+			(QuickFilter::HasFix, 1),
+			(QuickFilter::AffectsFleet, 1),
+			(QuickFilter::Unassigned, 1),
+		]);
+
+		let quick = BTreeSet::from([QuickFilter::Critical, QuickFilter::Unassigned]);
+		let filter = VulnerabilityFilter { quick, ..VulnerabilityFilter::default() };
+		let page = repo.search_vulnerabilities(filter.clone(), SortOrder::default(), 0, 10).await?;
+		assert_eq!(cve_ids(&page), ["CVE-2024-0002"]);
+		assert_eq!(page.vulnerabilities[0].kev_date_added, None);
+		assert_eq!(repo.count_quick_filters(filter).await?[0], (QuickFilter::Critical, 2));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_weakness_class_filter() -> Result<()> {
+		let (pool, _dir) = setup_test_db().await?;
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity) VALUES
+				(1, 'CVE-2024-0001', 'High'), (2, 'CVE-2024-0002', 'High'), (3, 'CVE-2024-0003', 'Low');
+			 INSERT INTO vulnerability_weaknesses (vulnerability_id, cwe_id) VALUES
+				(1, 'CWE-121'), (1, 'CWE-20'), (2, 'CWE-78'), (3, 'CWE-1004');",
+		)?;
+		let repo = VulnerabilityRepository::new(pool);
+		let search = |class| {
+			let filter = VulnerabilityFilter { weakness_class: Some(class), ..VulnerabilityFilter::default() };
+			repo.search_vulnerabilities(filter, SortOrder { column: SortColumn::CveId, ascending: true }, 0, 10)
+		};
+
+		let page = search(WeaknessClass::MemorySafety).await?;
+		assert_eq!(cve_ids(&page), ["CVE-2024-0001"]);
+		assert_eq!(page.vulnerabilities[0].cwe_ids, ["CWE-121", "CWE-20"]);
+		assert_eq!(cve_ids(&search(WeaknessClass::Injection).await?), ["CVE-2024-0002"]);
+		assert_eq!(cve_ids(&search(WeaknessClass::Other).await?), ["CVE-2024-0003"]);
+		assert!(search(WeaknessClass::Concurrency).await?.vulnerabilities.is_empty());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_related_vulnerabilities() -> Result<()> {
+		let (pool, _dir) = setup_test_db().await?;
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (vulnerability_id, cve_id, severity, cvss_score, deleted_at) VALUES
+				(1, 'CVE-2024-0001', 'High', 7.5, NULL), (2, 'CVE-2024-0002', 'Low', 3.1, NULL),
+				(3, 'CVE-2024-0003', 'Critical', 9.8, NULL), (4, 'CVE-2024-0004', 'High', 8.8, NULL),
+				(5, 'CVE-2024-0005', 'High', 8.8, '2024-06-01T00:00:00Z'), (6, 'CVE-2024-0006', 'High', 8.8, NULL);
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ros-core', 'OSRF'), (2, 'firmware', 'ACME');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (10, 1, '1.0'), (11, 1, '2.0'), (20, 2, '1.0');
+			 INSERT INTO affected_software (vulnerability_id, version_id, affected_version_pattern) VALUES
+				(1, 10, '1.0'), (2, 11, '2.0'), (4, 11, '2.0'), (5, 10, '1.0'), (6, 20, '1.0');
+			 INSERT INTO vulnerability_weaknesses (vulnerability_id, cwe_id) VALUES
+				(1, 'CWE-787'), (2, 'CWE-787'), (3, 'CWE-787'), (6, 'CWE-20');",
+		)?;
+		let repo = VulnerabilityRepository::new(pool);
+
+		// Another version of the same product counts; the deleted entry and the
+		// unrelated firmware CVE do not
+		let related = repo.get_related_vulnerabilities(1, 10).await?;
+		let cve_ids: Vec<&str> = related.iter().map(|related| related.cve_id.as_str()).collect();
+		assert_eq!(cve_ids, ["CVE-2024-0002", "CVE-2024-0004", "CVE-2024-0003"]);
+		assert_eq!(related[0].reason(), "ros-core · CWE-787");
+		assert_eq!(related[2].shared_products, Vec::<String>::new());
+		assert_eq!(related[2].status, TriageStatus::Open);
+
+		assert_eq!(repo.get_related_vulnerabilities(1, 1).await?.len(), 1);
+		assert!(repo.get_related_vulnerabilities(6, 10).await?.is_empty());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_search_by_advisory_and_reference() -> Result<()> {
+		let (pool, _dir) = setup_test_db().await?;
+		let repo = VulnerabilityRepository::new(pool.clone());
+		repo.add_vulnerability(Vulnerability::new("CVE-2024-0001".to_string(), "High".to_string())).await?;
+		repo.add_vulnerability(Vulnerability::new("CVE-2024-0002".to_string(), "Low".to_string())).await?;
+
+		crate::repositories::reference_repo::insert_references(
+			&*pool.get()?,
+			"CVE-2024-0001",
+			&[Reference::new("https://access.redhat.com/errata/RHSA-2024:1234".to_string(), None)],
+		)?;
+
+		let page = repo.search_vulnerabilities(search("rhsa-2024:1234"), SortOrder::default(), 0, 10).await?;
+		assert_eq!(cve_ids(&page), ["CVE-2024-0001"]);
+
+		let page = repo.search_vulnerabilities(search("access.redhat.com/errata"), SortOrder::default(), 0, 10).await?;
+		assert_eq!(page.vulnerabilities.len(), 1);
+		assert_eq!(page.total_pages, 1);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_locked_fields_survive_refreshes() -> Result<()> {
+		let (pool, _dir) = setup_test_db().await?;
+		let repo = VulnerabilityRepository::new(pool.clone());
+		let id = repo.add_vulnerability(Vulnerability::new("CVE-2024-0001".to_string(), "Low".to_string())).await?;
+
+		repo.edit_fields(id, vec![
+			(LockedField::Severity, Some(" High ".to_string())),
+			(LockedField::Impact, Some("Arm stops mid-motion".to_string())),
+		], "Ana  Analyst".to_string()).await?;
+		let refresh = |conn: &Connection| conn.execute(
+			"UPDATE vulnerabilities SET severity = 'Medium', description = 'From the NVD', impact = 'From the NVD'
+			 WHERE vulnerability_id = ?1",
+			[id],
+		);
+		refresh(&*pool.get()?)?;
+		let vuln = repo.get_vulnerability_by_id(id).await?;
+		assert_eq!(vuln.severity, "High");
+		assert_eq!(vuln.description.as_deref(), Some("From the NVD"));
+		assert_eq!(vuln.impact.as_deref(), Some("Arm stops mid-motion"));
+		let lock = vuln.lock(LockedField::Severity).expect("severity is locked");
+		assert_eq!(lock.locked_by, "Ana Analyst");
+		assert!(lock.locked_at.is_some());
+		assert!(vuln.lock(LockedField::Description).is_none());
+
+		assert!(repo.unlock_field(id, LockedField::Severity).await?);
+		assert!(!repo.unlock_field(id, LockedField::Severity).await?);
+		assert!(repo.unlock_field(id, LockedField::Impact).await?);
+		refresh(&*pool.get()?)?;
+		let vuln = repo.get_vulnerability_by_id(id).await?;
+		assert_eq!(vuln.severity, "Medium");
+		assert_eq!(vuln.impact.as_deref(), Some("From the NVD"));
+		assert!(vuln.locks.is_empty());
+
+		assert!(repo.edit_fields(id, vec![(LockedField::Severity, Some(" ".to_string()))], "Ana".to_string()).await.is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,18 @@
+// src/repositories/weakness_repo.rs
+
+use rusqlite::{params, Connection};
+
+/// Store the CWE IDs of a CVE, skipping ones it already has. Used inside the import
+/// transactions, so it takes a connection rather than the pool.
+pub(crate) fn insert_weaknesses(conn: &Connection, cve_id: &str, cwe_ids: &[String]) -> rusqlite::Result<usize> {
+	let mut stmt = conn.prepare_cached(
+		"INSERT OR IGNORE INTO vulnerability_weaknesses (vulnerability_id, cwe_id)
+		 SELECT vulnerability_id, ?2 FROM vulnerabilities WHERE cve_id = ?1",
+	)?;
+
+	let mut inserted = 0;
+	for cwe_id in cwe_ids {
+		inserted += stmt.execute(params![cve_id, cwe_id])?;
+	}
+	Ok(inserted)
+}
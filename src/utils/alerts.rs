@@ -0,0 +1,182 @@
+// src/utils/alerts.rs
+
+//! Sends the alerts queued in the outbox by email, one message per alert or, in digest
+//! mode, one message a day listing everything queued since the last one. Robots whose
+//! inventory went stale are reported the same way, once per stale period.
+
+use crate::db::connection::SqlitePool;
+use crate::models::alert::{Alert, AlertSettings, StaleInventory};
+use crate::repositories::alert_repo::AlertRepository;
+use crate::repositories::settings_repo::SettingsRepository;
+use crate::utils::time;
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use log::info;
+use std::sync::Arc;
+
+/// Environment variable holding the SMTP password
+pub const SMTP_PASSWORD_VAR: &str = "RVD_SMTP_PASSWORD";
+
+/// Send pending alerts, and notices about robots whose inventory went stale, and return
+/// how many went out. Digests are held back until a day has passed since the last email
+/// unless `force_digest` is set, e.g. by a scheduled job.
+pub async fn dispatch(pool: Arc<SqlitePool>, force_digest: bool) -> Result<usize> {
+	let settings_repo = SettingsRepository::new(pool.clone());
+	let Some(settings) = settings_repo.get_alert_settings().await? else {
+		return Ok(0);
+	};
+	let max_age_days = settings_repo.get_inventory_max_age_days().await?;
+	let repo = AlertRepository::new(pool);
+	let pending = repo.get_pending().await?;
+	let stale = repo.get_stale_inventories(max_age_days).await?;
+	if pending.is_empty() && stale.is_empty() {
+		return Ok(0);
+	}
+
+	if settings.digest && !force_digest {
+		let last_sent = repo.last_sent_at().await?;
+		if last_sent.is_some_and(|sent| Utc::now() - sent < Duration::days(1)) {
+			return Ok(0);
+		}
+	}
+
+	let messages = if settings.digest {
+		vec![compose_digest(&pending, &stale)]
+	} else {
+		pending
+			.iter()
+			.map(|alert| (alert.summary(), compose_body(alert)))
+			.chain(stale.iter().map(|inventory| (inventory.summary(), compose_stale_body(inventory, max_age_days))))
+			.collect()
+	};
+
+	let mailer = transport(&settings)?;
+	for (subject, body) in messages {
+		mailer.send(email(&settings, &subject, body)?).await
+			.with_context(|| format!("Failed to send alert email via {}", settings.smtp_host))?;
+	}
+
+	repo.mark_sent(pending.iter().map(|alert| alert.alert_id).collect()).await?;
+	repo.mark_inventories_alerted(stale.iter().map(|inventory| inventory.robot_id).collect()).await?;
+	let sent = pending.len() + stale.len();
+	info!("Sent {} alerts to {}", sent, settings.recipients.join(", "));
+	Ok(sent)
+}
+
+fn compose_body(alert: &Alert) -> String {
+	format!(
+		"{}\n\nQueued {}.\nOpen the CVE in RVD to review affected robots and triage.\n",
+		alert.summary(),
+		alert.created_at.map(time::format_local).unwrap_or_default(),
+	)
+}
+
+fn compose_stale_body(inventory: &StaleInventory, max_age_days: u32) -> String {
+	format!(
+		"{}.\n\nInventories older than {} days may no longer match the robot, so its vulnerability \
+		 matches may be wrong. Import a fresh inventory or confirm the recorded software in RVD.\n",
+		inventory.summary(),
+		max_age_days,
+	)
+}
+
+fn compose_digest(alerts: &[Alert], stale: &[StaleInventory]) -> (String, String) {
+	let list = |summaries: Vec<String>| summaries.iter().map(|summary| format!("- {}\n", summary)).collect::<String>();
+	let mut subject = format!("RVD digest: {} exposure changes", alerts.len());
+	let mut body = String::new();
+	if !alerts.is_empty() {
+		body.push_str(&format!("Changes since the last digest:\n\n{}", list(alerts.iter().map(Alert::summary).collect())));
+	}
+	if !stale.is_empty() {
+		subject.push_str(&format!(", {} stale inventories", stale.len()));
+		if !body.is_empty() {
+			body.push('\n');
+		}
+		body.push_str(&format!(
+			"Robots whose exposure may be out of date:\n\n{}",
+			list(stale.iter().map(StaleInventory::summary).collect())
+		));
+	}
+	(subject, body)
+}
+
+fn email(settings: &AlertSettings, subject: &str, body: String) -> Result<Message> {
+	let mut builder = Message::builder()
+		.from(parse_mailbox(&settings.from)?)
+		.subject(format!("[RVD] {}", subject));
+	for recipient in &settings.recipients {
+		builder = builder.to(parse_mailbox(recipient)?);
+	}
+	builder.body(body).context("Failed to build alert email")
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox> {
+	address.parse().with_context(|| format!("'{}' is not a valid email address", address))
+}
+
+/// STARTTLS on the configured port, or implicit TLS on port 465
+fn transport(settings: &AlertSettings) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+	let builder = if settings.smtp_port == 465 {
+		AsyncSmtpTransport::<Tokio1Executor>::relay(&settings.smtp_host)
+	} else {
+		AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.smtp_host)
+	}
+		.with_context(|| format!("Invalid SMTP server {}", settings.smtp_host))?
+		.port(settings.smtp_port);
+
+	let builder = match &settings.smtp_username {
+		Some(username) => {
+			let password = std::env::var(SMTP_PASSWORD_VAR).unwrap_or_default();
+			builder.credentials(Credentials::new(username.clone(), password))
+		}
+		None => builder,
+	};
+	Ok(builder.build())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::models::alert::AlertKind;
+
+	#[test]
+	fn test_compose() -> Result<()> {
+		let alert = Alert {
+			alert_id: 1,
+			kind: AlertKind::Exposure,
+			cve_id: "CVE-2024-0001".to_string(),
+			severity: "High".to_string(),
+			robot_name: Some("arm-01".to_string()),
+			robot_note: Some("Do not reboot\nduring a shift".to_string()),
+			detail: None,
+			created_at: None,
+		};
+		let (subject, body) = compose_digest(&[alert], &[]);
+		assert_eq!(subject, "RVD digest: 1 exposure changes");
+		assert!(body.contains("- arm-01 (note: Do not reboot during a shift) is exposed to CVE-2024-0001 (High)"));
+
+		let stale = StaleInventory {
+			robot_id: 2,
+			robot_name: "agv-01".to_string(),
+			operational_note: Some("Docks at bay 3".to_string()),
+			refreshed_at: None,
+		};
+		let (with_stale, stale_body) = compose_digest(&[], &[stale]);
+		assert_eq!(with_stale, "RVD digest: 0 exposure changes, 1 stale inventories");
+		assert_eq!(stale_body, "Robots whose exposure may be out of date:\n\n- Inventory of agv-01 (note: Docks at bay 3) was never recorded\n");
+
+		let settings = AlertSettings {
+			smtp_host: "smtp.example.com".to_string(),
+			smtp_port: 587,
+			smtp_username: None,
+			from: "RVD <rvd@example.com>".to_string(),
+			recipients: vec!["ops@example.com".to_string(), "not an address".to_string()],
+			digest: true,
+		};
+		assert!(email(&settings, &subject, body).is_err());
+		Ok(())
+	}
+}
@@ -1,14 +1,22 @@
-// src/db/importer.rs
+// src/utils/csv_importer.rs
 
 use std::fs::File;
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use serde::Deserialize;
-use csv::ReaderBuilder;
+use std::path::Path;
+use csv::{ReaderBuilder, StringRecord};
 use tokio::task;
 use anyhow::{Result, Context, Error};
-use log::{info, warn};
-use crate::models::vulnerability::Vulnerability;
+use log::{debug, info};
+use crate::models::csv_mapping::{CsvMapping, MergePolicy};
+use crate::models::reference::{self, Reference};
+use crate::models::vulnerability::{ProvenanceSource, TriageStatus, Vulnerability};
+use crate::models::import_run::{ImportReport, ImportRunStatus};
+use crate::repositories::{audit_repo, import_run_repo, provenance_repo};
+use crate::repositories::import_run_repo::RunChange;
+use crate::repositories::reference_repo::insert_references;
 use crate::db::connection::SqlitePool;
+use crate::utils::progress::{Cancelled, ProgressReporter};
+use crate::utils::xlsx;
 use std::sync::Arc;
 use chrono::NaiveDate;
 use rusqlite::Transaction;
@@ -18,115 +26,352 @@ const BATCH_SIZE: usize = 1000;
 
 /// Represents a record in the CSV file.
 ///
-/// The struct fields are mapped to the actual CSV column headers using `serde`'s `rename` attribute.
-#[derive(Debug, Deserialize)]
+/// The struct fields are read from the CSV columns named by the import's [`CsvMapping`].
+#[derive(Debug)]
 pub struct VulnerabilityCsvRecord {
-	#[serde(rename = "Name")]
 	pub cve_id: String,
-
-	#[serde(rename = "Status")]
 	pub severity: String,
-
-	#[serde(rename = "Description")]
 	pub description: String,
-
-	#[serde(rename = "References")]
 	pub references: Option<String>,
-
-	#[serde(rename = "Phase")]
 	pub published_date: Option<String>,
-
-	#[serde(rename = "Votes")]
 	pub impact: Option<String>,
-
-	#[serde(rename = "Comments")]
 	pub mitigation: Option<String>,
 }
 
+/// Positions of the mapped columns in the header row
+#[derive(Debug, PartialEq)]
+struct ColumnIndices {
+	cve_id: usize,
+	severity: usize,
+	description: usize,
+	references: Option<usize>,
+	published_date: Option<usize>,
+	impact: Option<usize>,
+	mitigation: Option<usize>,
+}
+
+impl ColumnIndices {
+	/// Locates every mapped column, failing with the names of the ones that are missing
+	fn resolve(headers: &StringRecord, mapping: &CsvMapping) -> Result<Self> {
+		let missing: Vec<&str> = mapping.headers()
+			.into_iter()
+			.filter(|name| find_column(headers, name).is_none())
+			.collect();
+		if !missing.is_empty() {
+			return Err(anyhow::anyhow!(
+				"CSV is missing the columns mapped by the {} preset: {}",
+				mapping.source,
+				missing.join(", ")
+			));
+		}
+
+		let column = |name: &String| find_column(headers, name).unwrap_or_default();
+		Ok(Self {
+			cve_id: column(&mapping.cve_id),
+			severity: column(&mapping.severity),
+			description: column(&mapping.description),
+			references: mapping.references.as_ref().map(column),
+			published_date: mapping.published_date.as_ref().map(column),
+			impact: mapping.impact.as_ref().map(column),
+			mitigation: mapping.mitigation.as_ref().map(column),
+		})
+	}
+
+	fn record(&self, row: &StringRecord) -> VulnerabilityCsvRecord {
+		let field = |index: usize| row.get(index).unwrap_or_default().to_string();
+		VulnerabilityCsvRecord {
+			cve_id: field(self.cve_id),
+			severity: field(self.severity),
+			description: field(self.description),
+			references: self.references.map(field),
+			published_date: self.published_date.map(field),
+			impact: self.impact.map(field),
+			mitigation: self.mitigation.map(field),
+		}
+	}
+}
+
+/// Index of the header matching `name`, ignoring case
+fn find_column(headers: &StringRecord, name: &str) -> Option<usize> {
+	headers.iter().position(|header| header.trim().eq_ignore_ascii_case(name.trim()))
+}
+
 /// Imports vulnerabilities from a CSV file into the database.
 ///
+/// The file is streamed one record at a time, so its size is not limited by memory.
+/// Quoted fields may span several lines, and rows with missing or extra columns are
+/// read as far as they go.
+///
 /// # Arguments
 ///
 /// * `file_path` - The path to the CSV file.
 /// * `pool` - An `Arc`-wrapped `SqlitePool` for database connections.
+/// * `mapping` - The columns holding each vulnerability field and how rows for CVEs
+///   already recorded are merged into them, e.g. a saved preset.
+/// * `progress` - Receives an update after every inserted batch. Cancelling it stops the
+///   import with a `Cancelled` error after the current batch; earlier batches stay imported.
+///
+/// The import is recorded as an import run, which can be reverted as a whole, together
+/// with the rows it skipped.
 ///
 /// # Returns
 ///
-/// * `Result<usize>` - The number of successfully imported vulnerabilities.
+/// * `Result<ImportReport>` - The number of imported vulnerabilities and the rows
+///   skipped, with the reason for each.
 pub async fn import_vulnerabilities_from_csv(
 	file_path: String,
 	pool: Arc<SqlitePool>,
-) -> Result<usize> {
-	task::spawn_blocking(move || -> Result<usize, Error> {
+	mapping: CsvMapping,
+	progress: ProgressReporter,
+) -> Result<ImportReport> {
+	task::spawn_blocking(move || -> Result<ImportReport, Error> {
 		let file = File::open(&file_path).context("Failed to open CSV file")?;
+		let file_size = file.metadata().map(|m| m.len()).unwrap_or(0);
+		let tracker = progress.start("CSV import");
+		let source = format!("CSV file {}", file_path);
 		let mut reader = BufReader::new(file);
 
 		// Find the header line
-		let header_line = find_header_line(&mut reader)?;
+		let header_line = find_header_line(&mut reader, &mapping)?;
 		info!("Header found at line {}", header_line + 1);
 
 		// Seek back to the beginning after finding the header
 		reader.seek(SeekFrom::Start(0))?;
 
-		let mut rdr = ReaderBuilder::new()
-			.trim(csv::Trim::All)
-			.from_reader(reader);
-
-		// Skip lines until the header is reached
+		// Skip lines until the header is reached, so the CSV reader takes it as its header row
 		for _ in 0..header_line {
-			let mut record = csv::StringRecord::new();
-			if rdr.read_record(&mut record)? {
-				info!("Skipping metadata line: {:?}", record);
-			} else {
+			let mut line = String::new();
+			if reader.read_line(&mut line)? == 0 {
 				break; // Reached EOF before finding header
 			}
+			info!("Skipping metadata line: {}", line.trim_end());
 		}
 
-		validate_csv_headers(&mut rdr)?;
-
-		let mut successful_imports = 0;
-		let mut batch = Vec::with_capacity(BATCH_SIZE);
+		let mut rdr = ReaderBuilder::new()
+			.trim(csv::Trim::All)
+			.flexible(true)
+			.from_reader(reader);
 
-		for (line_number, result) in rdr.deserialize::<VulnerabilityCsvRecord>().enumerate() {
-			match process_csv_record(result, line_number + header_line + 2) {
-				Ok(vuln) => {
-					if !is_metadata_record(&vuln) {
-						batch.push(vuln);
+		let headers = rdr.headers().context("Failed to read CSV headers")?;
+		let columns = ColumnIndices::resolve(headers, &mapping)?;
+
+		let report = run_import(&pool, &source, |run_id, report| {
+			let mut batch = Vec::with_capacity(BATCH_SIZE);
+			// One buffer for every record, so memory stays flat however long the file is
+			let mut row = StringRecord::new();
+
+			loop {
+				let read = rdr.read_record(&mut row);
+				// Positions count lines from the header, which is on line `header_line + 1`
+				let line_number = |position: Option<&csv::Position>| {
+					header_line + position.map_or(0, |position| position.line() as usize)
+				};
+				let (line, record) = match read {
+					Ok(false) => break,
+					Ok(true) => (line_number(row.position()), Ok(columns.record(&row))),
+					Err(e) => (line_number(e.position()), Err(e)),
+				};
+				let cve_id = record.as_ref().map(|record| record.cve_id.clone()).unwrap_or_default();
+				match process_csv_record(record) {
+					Ok((vuln, _)) if is_metadata_record(&vuln) => {
+						report.skip(line, &cve_id, "No description, impact or mitigation".to_string());
+					}
+					Ok(entry) => {
+						batch.push(entry);
 						if batch.len() >= BATCH_SIZE {
-							successful_imports += insert_batch(&pool, &batch)?;
+							report.imported += insert_batch(&pool, run_id, &batch, &source, mapping.merge_policy)?;
 							batch.clear();
+							if tracker.is_cancelled() {
+								info!("CSV import cancelled after {} vulnerabilities", report.imported);
+								return Err(Cancelled.into());
+							}
+							if file_size > 0 {
+								tracker.update(report.imported, rdr.position().byte() as f32 / file_size as f32);
+							}
 						}
 					}
+					Err(e) => {
+						debug!("Skipping invalid record at line {}: {:#}", line, e);
+						report.skip(line, &cve_id, format!("{:#}", e));
+					}
 				}
-				Err(e) => warn!("Skipping invalid record at line {}: {}", line_number + header_line + 2, e),
 			}
-		}
 
-		if !batch.is_empty() {
-			successful_imports += insert_batch(&pool, &batch)?;
-		}
+			if !batch.is_empty() {
+				report.imported += insert_batch(&pool, run_id, &batch, &source, mapping.merge_policy)?;
+			}
+			Ok(())
+		})?;
+
+		tracker.finish(report.imported);
+		info!("Import completed: {}", report);
+		Ok(report)
+	})
+		.await
+		.context("Failed to run import task")?
+}
+
+/// Imports vulnerabilities from a sheet of an Excel workbook (.xlsx), with the same
+/// column mapping and presets as a CSV import.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the workbook.
+/// * `sheet` - The sheet to read, the first one when `None`.
+/// * `pool` - An `Arc`-wrapped `SqlitePool` for database connections.
+/// * `mapping` - The columns holding each vulnerability field, e.g. a saved preset. The
+///   header row is the first row naming every mapped column, as in a CSV.
+/// * `progress` - Receives an update after every inserted batch; cancelling it stops the
+///   import after the current batch.
+///
+/// Like a CSV import, it is recorded as a revertible import run.
+///
+/// # Returns
+///
+/// * `Result<ImportReport>` - The number of imported vulnerabilities and the rows
+///   skipped, numbered as in the sheet.
+pub async fn import_vulnerabilities_from_xlsx(
+	file_path: String,
+	sheet: Option<String>,
+	pool: Arc<SqlitePool>,
+	mapping: CsvMapping,
+	progress: ProgressReporter,
+) -> Result<ImportReport> {
+	task::spawn_blocking(move || -> Result<ImportReport, Error> {
+		let rows = xlsx::read_sheet(Path::new(&file_path), sheet.as_deref())?;
+		let tracker = progress.start("Spreadsheet import");
+		let source = format!("Spreadsheet {}", file_path);
+
+		let header_row = rows
+			.iter()
+			.position(|row| {
+				let headers = StringRecord::from(row.cells.clone());
+				mapping.headers().iter().all(|name| find_column(&headers, name).is_some())
+			})
+			.context("Header row not found in the spreadsheet")?;
+		info!("Header found at row {}", rows[header_row].number);
+		let columns = ColumnIndices::resolve(&StringRecord::from(rows[header_row].cells.clone()), &mapping)?;
+
+		let report = run_import(&pool, &source, |run_id, report| {
+			let mut vulnerabilities = Vec::new();
+			for row in &rows[header_row + 1..] {
+				let mut record = columns.record(&StringRecord::from(row.cells.clone()));
+				// Cells formatted as dates hold serial day numbers
+				if let Some(date) = record.published_date.as_deref().and_then(xlsx::serial_date) {
+					record.published_date = Some(date.to_string());
+				}
+				let cve_id = record.cve_id.clone();
+				match process_csv_record(Ok(record)) {
+					Ok((vuln, _)) if is_metadata_record(&vuln) => {
+						report.skip(row.number, &cve_id, "No description, impact or mitigation".to_string());
+					}
+					Ok(entry) => vulnerabilities.push(entry),
+					Err(e) => {
+						debug!("Skipping invalid record at row {}: {:#}", row.number, e);
+						report.skip(row.number, &cve_id, format!("{:#}", e));
+					}
+				}
+			}
+
+			// Rows the merge policy leaves alone count as processed but not as imported
+			let mut processed = 0;
+			for batch in vulnerabilities.chunks(BATCH_SIZE) {
+				report.imported += insert_batch(&pool, run_id, batch, &source, mapping.merge_policy)?;
+				processed += batch.len();
+				if tracker.is_cancelled() && processed < vulnerabilities.len() {
+					info!("Spreadsheet import cancelled after {} vulnerabilities", report.imported);
+					return Err(Cancelled.into());
+				}
+				tracker.update(report.imported, processed as f32 / vulnerabilities.len() as f32);
+			}
+			Ok(())
+		})?;
 
-		info!(
-			"Import completed. Successfully imported {} vulnerabilities.",
-			successful_imports
-		);
-		Ok(successful_imports)
+		tracker.finish(report.imported);
+		info!("Import completed: {}", report);
+		Ok(report)
 	})
 		.await
 		.context("Failed to run import task")?
 }
 
+/// Runs `import` as an import run of `source`, closing the run with the status the
+/// import ended in and keeping the rows it skipped, even when it stopped early.
+/// `import` receives the run id to pass to [`insert_batch`] and the report to fill in.
+fn run_import(
+	pool: &Arc<SqlitePool>,
+	source: &str,
+	import: impl FnOnce(i64, &mut ImportReport) -> Result<()>,
+) -> Result<ImportReport> {
+	let run_id = import_run_repo::start(&*pool.get().context("Failed to get a connection from the pool")?, source)?;
+	let mut report = ImportReport::default();
+	let result = import(run_id, &mut report);
+	let status = match &result {
+		Ok(_) => ImportRunStatus::Completed,
+		Err(e) if e.is::<Cancelled>() => ImportRunStatus::Cancelled,
+		Err(_) => ImportRunStatus::Failed,
+	};
+	let finished = pool
+		.get()
+		.context("Failed to get a connection from the pool")
+		.and_then(|mut conn| {
+			import_run_repo::record_skipped(&mut conn, run_id, &report)?;
+			import_run_repo::finish(&conn, run_id, status)
+		});
+	// The import's own error is the one worth reporting
+	match (result, finished) {
+		(Ok(_), Err(e)) => Err(e),
+		(result, _) => result.map(|_| report),
+	}
+}
+
+/// Rows searched for the header by [`read_headers`]
+const HEADER_SCAN_ROWS: usize = 50;
+
+/// Reads the column headers of a CSV file or, for an .xlsx file, of its first sheet,
+/// so a mapping can be chosen before importing. The header is the first row with at
+/// least three non-empty cells, which passes over title and metadata lines such as
+/// those at the top of the MITRE CVE list.
+pub fn read_headers(path: &Path) -> Result<Vec<String>> {
+	let is_header = |cells: &[String]| cells.iter().filter(|cell| !cell.trim().is_empty()).count() >= 3;
+	let trimmed = |cells: Vec<String>| cells.into_iter().map(|cell| cell.trim().to_string()).collect();
+
+	if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("xlsx")) {
+		return xlsx::read_sheet(path, None)?
+			.into_iter()
+			.take(HEADER_SCAN_ROWS)
+			.map(|row| row.cells)
+			.find(|cells| is_header(cells))
+			.map(trimmed)
+			.context("Header row not found in the spreadsheet");
+	}
+
+	let file = File::open(path).context("Failed to open CSV file")?;
+	let mut rdr = ReaderBuilder::new()
+		.has_headers(false)
+		.flexible(true)
+		.from_reader(BufReader::new(file));
+	for record in rdr.records().take(HEADER_SCAN_ROWS) {
+		let cells: Vec<String> = record.context("Failed to read CSV record")?.iter().map(str::to_string).collect();
+		if is_header(&cells) {
+			return Ok(trimmed(cells));
+		}
+	}
+	Err(anyhow::anyhow!("Header row not found in CSV file"))
+}
+
 /// Finds the line number where the CSV header starts.
 ///
 /// # Arguments
 ///
 /// * `reader` - A mutable reference to a `BufReader<File>`.
+/// * `mapping` - The header is the first line naming every mapped column, in any order.
 ///
 /// # Returns
 ///
 /// * `Result<usize>` - The zero-based line number where the header is found.
-fn find_header_line(reader: &mut BufReader<File>) -> Result<usize, Error> {
-	let expected_headers = ["Name", "Status", "Description", "References", "Phase", "Votes", "Comments"];
+fn find_header_line(reader: &mut BufReader<File>, mapping: &CsvMapping) -> Result<usize, Error> {
+	let expected_headers = mapping.headers();
 	let mut line_number = 0;
 
 	for line in reader.lines() {
@@ -139,8 +384,8 @@ fn find_header_line(reader: &mut BufReader<File>) -> Result<usize, Error> {
 			.map(|s| s.trim_matches('"').trim())
 			.collect();
 
-		// Check if the current line matches the expected headers (case-insensitive)
-		if fields.len() >= expected_headers.len() && expected_headers.iter().zip(fields.iter()).all(|(e, f)| e.eq_ignore_ascii_case(f)) {
+		// Check if the current line contains the expected headers (case-insensitive)
+		if expected_headers.iter().all(|e| fields.iter().any(|f| e.trim().eq_ignore_ascii_case(f))) {
 			return Ok(line_number);
 		}
 
@@ -150,53 +395,35 @@ fn find_header_line(reader: &mut BufReader<File>) -> Result<usize, Error> {
 	Err(anyhow::anyhow!("Header row not found in CSV file"))
 }
 
-/// Validates that the CSV headers match the expected headers.
-///
-/// # Arguments
-///
-/// * `rdr` - A mutable reference to a `csv::Reader`.
-///
-/// # Returns
-///
-/// * `Result<()>` - Ok if headers are valid, Err otherwise.
-fn validate_csv_headers(rdr: &mut csv::Reader<BufReader<File>>) -> Result<()> {
-	let headers = rdr.headers().context("Failed to read CSV headers")?;
-	let expected_headers = ["Name", "Status", "Description", "References", "Phase", "Votes", "Comments"];
-
-	for (expected, actual) in expected_headers.iter().zip(headers.iter()) {
-		if !expected.eq_ignore_ascii_case(actual) {
-			return Err(anyhow::anyhow!(
-				"Unexpected header. Expected '{}', found '{}'",
-				expected,
-				actual
-			));
-		}
-	}
-	Ok(())
-}
-
-/// Processes a single CSV record and converts it into a `Vulnerability` struct.
+/// Processes a single CSV record and converts it into a `Vulnerability` struct
+/// and the references listed in its References column.
 ///
 /// # Arguments
 ///
-/// * `record_result` - The result of deserializing a CSV record.
-/// * `line_number` - The line number in the CSV file.
+/// * `record_result` - The result of reading a CSV record.
 ///
 /// # Returns
 ///
-/// * `Result<Vulnerability>` - The processed vulnerability or an error.
-fn process_csv_record(record_result: csv::Result<VulnerabilityCsvRecord>, line_number: usize) -> Result<Vulnerability, Error> {
-	let record = record_result.context("Failed to deserialize CSV record")?;
+/// * `Result<(Vulnerability, Vec<Reference>)>` - The processed vulnerability or an error.
+fn process_csv_record(
+	record_result: csv::Result<VulnerabilityCsvRecord>,
+) -> Result<(Vulnerability, Vec<Reference>), Error> {
+	let record = record_result.context("Failed to read CSV record")?;
 
 	if !is_valid_cve_id(&record.cve_id) {
-		return Err(anyhow::anyhow!("Invalid CVE ID format at line {}", line_number));
+		return Err(anyhow::anyhow!("Invalid CVE ID format: {:?}", record.cve_id));
 	}
 
 	let published_date = record.published_date
 		.as_ref()
 		.and_then(|date_str| parse_date(date_str).ok());
 
-	Ok(Vulnerability {
+	let references = record.references
+		.as_deref()
+		.map(reference::parse_csv_references)
+		.unwrap_or_default();
+
+	Ok((Vulnerability {
 		vulnerability_id: None,
 		cve_id: record.cve_id,
 		description: non_empty_string(record.description),
@@ -204,7 +431,18 @@ fn process_csv_record(record_result: csv::Result<VulnerabilityCsvRecord>, line_n
 		impact: record.impact,
 		mitigation: record.mitigation,
 		published_date,
-	})
+		cvss_score: None,
+		cvss_version: None,
+		status: TriageStatus::Open,
+		assigned_to: None,
+		risk_acceptance: None,
+		kev_date_added: None,
+		cwe_ids: Vec::new(),
+		source: None,
+		aliases: Vec::new(),
+		locks: Vec::new(),
+		provenance: Vec::new(),
+	}, references))
 }
 
 /// Determines if a `Vulnerability` record is metadata.
@@ -243,12 +481,14 @@ fn non_empty_string(s: String) -> Option<String> {
 /// # Returns
 ///
 /// * `bool` - `true` if valid, `false` otherwise.
-fn is_valid_cve_id(cve_id: &str) -> bool {
+pub(crate) fn is_valid_cve_id(cve_id: &str) -> bool {
 	let parts: Vec<&str> = cve_id.split('-').collect();
 	parts.len() == 3
 		&& parts[0].eq_ignore_ascii_case("CVE")
 		&& parts[1].len() == 4 && parts[1].chars().all(|c| c.is_digit(10))
 		&& parts[2].len() >= 4 && parts[2].chars().all(|c| c.is_digit(10))
+		// Sequence numbers longer than four digits have no leading zeros
+		&& (parts[2].len() == 4 || !parts[2].starts_with('0'))
 }
 
 /// Parses the severity field into a standardized format.
@@ -294,16 +534,31 @@ fn parse_date(date_str: &str) -> Result<NaiveDate, Error> {
 /// # Arguments
 ///
 /// * `pool` - An `Arc`-wrapped `SqlitePool`.
-/// * `batch` - A slice of `Vulnerability` structs.
+/// * `run_id` - The import run the batch belongs to.
+/// * `batch` - Vulnerabilities with their references.
+/// * `source` - The imported file, named in the audit log entry of the batch.
+/// * `merge_policy` - How rows for CVEs already recorded are merged into them.
 ///
 /// # Returns
 ///
 /// * `Result<usize>` - The number of records inserted.
-fn insert_batch(pool: &Arc<SqlitePool>, batch: &[Vulnerability]) -> Result<usize> {
+fn insert_batch(
+	pool: &Arc<SqlitePool>,
+	run_id: i64,
+	batch: &[(Vulnerability, Vec<Reference>)],
+	source: &str,
+	merge_policy: MergePolicy,
+) -> Result<usize> {
 	let mut connection = pool.get().context("Failed to get a connection from the pool")?;
 	let transaction = connection.transaction().context("Failed to start database transaction")?;
 
-	let inserted = insert_vulnerabilities(&transaction, batch).context("Failed to insert vulnerabilities")?;
+	let inserted = insert_vulnerabilities(&transaction, run_id, batch, merge_policy)
+		.context("Failed to insert vulnerabilities")?;
+	audit_repo::record_import(
+		&transaction,
+		source,
+		&format!("{} rows, {} vulnerabilities inserted", batch.len(), inserted),
+	)?;
 
 	transaction.commit().context("Failed to commit transaction")?;
 	Ok(inserted)
@@ -314,19 +569,51 @@ fn insert_batch(pool: &Arc<SqlitePool>, batch: &[Vulnerability]) -> Result<usize
 /// # Arguments
 ///
 /// * `transaction` - A reference to a `rusqlite::Transaction`.
-/// * `vulnerabilities` - A slice of `Vulnerability` structs.
+/// * `run_id` - The import run, which new vulnerabilities are tagged with and which keeps
+///   the previous values of the ones overwritten.
+/// * `vulnerabilities` - Vulnerabilities with their references.
+/// * `merge_policy` - How rows for CVEs already recorded are merged into them.
 ///
 /// # Returns
 ///
-/// * `Result<usize, rusqlite::Error>` - The number of records inserted or a database error.
-fn insert_vulnerabilities(transaction: &Transaction, vulnerabilities: &[Vulnerability]) -> Result<usize, rusqlite::Error> {
-	let mut stmt = transaction.prepare(
-		"INSERT OR REPLACE INTO vulnerabilities (cve_id, description, severity, impact, mitigation, published_date)
-		 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-	)?;
-
-	let mut inserted = 0;
-	for vuln in vulnerabilities {
+/// * `Result<usize>` - The number of records inserted or updated, or a database error.
+fn insert_vulnerabilities(
+	transaction: &Transaction,
+	run_id: i64,
+	vulnerabilities: &[(Vulnerability, Vec<Reference>)],
+	merge_policy: MergePolicy,
+) -> Result<usize> {
+	// An upsert rather than a replace, which would delete the entry's locks and triage
+	let on_conflict = match merge_policy {
+		MergePolicy::Overwrite => {
+			"DO UPDATE SET description = excluded.description, severity = excluded.severity,
+				impact = excluded.impact, mitigation = excluded.mitigation, published_date = excluded.published_date"
+		}
+		// An empty cell, or a severity the file does not rate, keeps the recorded value
+		MergePolicy::NewestNonEmpty => {
+			"DO UPDATE SET description = COALESCE(NULLIF(excluded.description, ''), description),
+				severity = COALESCE(NULLIF(excluded.severity, 'Unknown'), severity),
+				impact = COALESCE(NULLIF(excluded.impact, ''), impact),
+				mitigation = COALESCE(NULLIF(excluded.mitigation, ''), mitigation),
+				published_date = COALESCE(excluded.published_date, published_date)"
+		}
+		MergePolicy::KeepExisting => "DO NOTHING",
+	};
+	let mut stmt = transaction.prepare(&format!(
+		"INSERT INTO vulnerabilities (cve_id, description, severity, impact, mitigation, published_date)
+		 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+		 ON CONFLICT(cve_id) {}",
+		on_conflict
+	))?;
+	let mut exists_stmt = transaction.prepare("SELECT EXISTS (SELECT 1 FROM vulnerabilities WHERE cve_id = ?1)")?;
+
+	let (mut inserted, mut created, mut updated) = (0, 0, 0);
+	for (vuln, references) in vulnerabilities {
+		if merge_policy == MergePolicy::KeepExisting && exists_stmt.query_row([&vuln.cve_id], |row| row.get(0))? {
+			continue;
+		}
+		let change = import_run_repo::prepare_upsert(transaction, run_id, &vuln.cve_id)?;
+		let before = provenance_repo::snapshot(transaction, &vuln.cve_id)?;
 		stmt.execute(rusqlite::params![
 			vuln.cve_id,
 			vuln.description,
@@ -335,8 +622,19 @@ fn insert_vulnerabilities(transaction: &Transaction, vulnerabilities: &[Vulnerab
 			vuln.mitigation,
 			vuln.published_date.map(|d| d.to_string()),
 		])?;
+		provenance_repo::record_changes(transaction, &vuln.cve_id, before, ProvenanceSource::Csv)?;
+		insert_references(transaction, &vuln.cve_id, references)?;
+		match change {
+			RunChange::Create => {
+				import_run_repo::tag_created(transaction, run_id, &vuln.cve_id)?;
+				created += 1;
+			}
+			RunChange::Overwrite => updated += 1,
+			RunChange::Repeat => {}
+		}
 		inserted += 1;
 	}
+	import_run_repo::add_counts(transaction, run_id, created, updated)?;
 
 	Ok(inserted)
 }
@@ -381,6 +679,17 @@ mod tests {
 			impact: None,
 			mitigation: None,
 			published_date: None,
+			cvss_score: None,
+			cvss_version: None,
+			status: TriageStatus::Open,
+			assigned_to: None,
+			risk_acceptance: None,
+			kev_date_added: None,
+			cwe_ids: Vec::new(),
+			source: None,
+			aliases: Vec::new(),
+			locks: Vec::new(),
+			provenance: Vec::new(),
 		};
 		assert!(is_metadata_record(&metadata_vuln));
 
@@ -392,6 +701,17 @@ mod tests {
 			impact: Some("Severe impact".to_string()),
 			mitigation: Some("Apply patch".to_string()),
 			published_date: Some(NaiveDate::from_ymd(2023, 1, 1)),
+			cvss_score: None,
+			cvss_version: None,
+			status: TriageStatus::Open,
+			assigned_to: None,
+			risk_acceptance: None,
+			kev_date_added: None,
+			cwe_ids: Vec::new(),
+			source: None,
+			aliases: Vec::new(),
+			locks: Vec::new(),
+			provenance: Vec::new(),
 		};
 		assert!(!is_metadata_record(&real_vuln));
 	}
@@ -415,10 +735,11 @@ mod tests {
 			mitigation: Some("Apply patch".to_string()),
 		};
 
-		let result = process_csv_record(Ok(valid_record), 1);
+		let result = process_csv_record(Ok(valid_record));
 		assert!(result.is_ok());
-		let vuln = result.unwrap();
+		let (vuln, references) = result.unwrap();
 		assert_eq!(vuln.cve_id, "CVE-2023-0001");
+		assert_eq!(references, vec![Reference::new("https://example.com".to_string(), None)]);
 		assert_eq!(vuln.severity, "High");
 		assert_eq!(vuln.description, Some("A test vulnerability".to_string()));
 		assert_eq!(vuln.impact, Some("Severe impact".to_string()));
@@ -435,8 +756,236 @@ mod tests {
 			mitigation: None,
 		};
 
-		let result = process_csv_record(Ok(invalid_record), 2);
+		let result = process_csv_record(Ok(invalid_record));
 		assert!(result.is_err());
 	}
 
+	#[test]
+	fn test_column_mapping() {
+		let mapping = CsvMapping {
+			source: "Scanner".to_string(),
+			cve_id: "cve".to_string(),
+			severity: "Risk".to_string(),
+			description: "Summary".to_string(),
+			references: None,
+			published_date: Some("First Seen".to_string()),
+			impact: None,
+			mitigation: Some("Solution".to_string()),
+			merge_policy: MergePolicy::default(),
+		};
+		let headers = StringRecord::from(vec!["Host", "Solution", "CVE", "Risk", "Summary", "First Seen"]);
+		let columns = ColumnIndices::resolve(&headers, &mapping).unwrap();
+
+		let row = StringRecord::from(vec!["arm-01", "Upgrade", "CVE-2024-0001", "High", "Overflow", "2024-02-03"]);
+		let record = columns.record(&row);
+		assert_eq!(record.cve_id, "CVE-2024-0001");
+		assert_eq!(record.severity, "High");
+		assert_eq!(record.mitigation.as_deref(), Some("Upgrade"));
+		assert_eq!(record.published_date.as_deref(), Some("2024-02-03"));
+		assert_eq!(record.impact, None);
+
+		let err = ColumnIndices::resolve(&headers, &CsvMapping::default()).unwrap_err();
+		assert!(err.to_string().contains("Name, Status, Description"), "{}", err);
+	}
+
+	#[tokio::test]
+	async fn test_import_xlsx() -> Result<()> {
+		use crate::db::connection;
+		use crate::repositories::vulnerability_repo::VulnerabilityRepository;
+		use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
+
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().join("findings.xlsx");
+		let mut workbook = Workbook::new();
+		let sheet = workbook.add_worksheet();
+		sheet.write_string(0, 0, "Exported from Scanner")?;
+		for (column, header) in ["CVE", "Risk", "Summary", "First Seen"].into_iter().enumerate() {
+			sheet.write_string(1, column as u16, header)?;
+		}
+		sheet.write_string(2, 0, "CVE-2024-0001")?;
+		sheet.write_string(2, 1, "High")?;
+		sheet.write_string(2, 2, "Overflow")?;
+		let date = ExcelDateTime::from_ymd(2024, 2, 3)?;
+		sheet.write_datetime_with_format(2, 3, &date, &Format::new().set_num_format("dd/mm/yyyy"))?;
+		sheet.write_string(3, 0, "not a CVE")?;
+		workbook.save(&path)?;
+		assert_eq!(read_headers(&path)?, ["CVE", "Risk", "Summary", "First Seen"]);
+
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let mapping = CsvMapping {
+			source: "Scanner".to_string(),
+			cve_id: "cve".to_string(),
+			severity: "Risk".to_string(),
+			description: "Summary".to_string(),
+			references: None,
+			published_date: Some("First Seen".to_string()),
+			impact: None,
+			mitigation: None,
+			merge_policy: MergePolicy::default(),
+		};
+		let path = path.to_string_lossy().into_owned();
+		let report = import_vulnerabilities_from_xlsx(path, None, pool.clone(), mapping, ProgressReporter::disabled()).await?;
+		assert_eq!(report.imported, 1);
+		assert_eq!(report.skipped_rows[0].line, 4);
+		assert_eq!(report.skipped_rows[0].cve_id, "not a CVE");
+
+		let vulnerability = VulnerabilityRepository::new(pool)
+			.get_vulnerability_by_cve("CVE-2024-0001")
+			.await?
+			.unwrap();
+		assert_eq!(vulnerability.severity, "High");
+		assert_eq!(vulnerability.description.as_deref(), Some("Overflow"));
+		assert_eq!(vulnerability.published_date, NaiveDate::from_ymd_opt(2024, 2, 3));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_xlsx_cancelled_after_last_batch() -> Result<()> {
+		use crate::db::connection;
+		use crate::repositories::import_run_repo::ImportRunRepository;
+		use rust_xlsxwriter::Workbook;
+
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().join("scan.xlsx");
+		let mut workbook = Workbook::new();
+		let sheet = workbook.add_worksheet();
+		for (row, cells) in [["CVE", "Risk", "Summary"], ["CVE-2024-0001", "Low", "Heap overflow"], ["CVE-2024-0002", "Medium", "Use after free"]]
+			.into_iter()
+			.enumerate()
+		{
+			for (column, cell) in cells.into_iter().enumerate() {
+				sheet.write_string(row as u32, column as u16, cell)?;
+			}
+		}
+		workbook.save(&path)?;
+
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (cve_id, description, severity) VALUES ('CVE-2024-0001', 'Overflow', 'High');",
+		)?;
+		let mapping = CsvMapping {
+			source: "Scanner".to_string(),
+			cve_id: "CVE".to_string(),
+			severity: "Risk".to_string(),
+			description: "Summary".to_string(),
+			references: None,
+			published_date: None,
+			impact: None,
+			mitigation: None,
+			merge_policy: MergePolicy::KeepExisting,
+		};
+		// A cancel arriving once every row is processed leaves the import completed,
+		// although the row of the recorded CVE was not imported
+		let progress = ProgressReporter::disabled();
+		progress.cancellation_token().cancel();
+		let path = path.to_string_lossy().into_owned();
+		let report = import_vulnerabilities_from_xlsx(path, None, pool.clone(), mapping, progress).await?;
+		assert_eq!(report.imported, 1);
+		assert_eq!(ImportRunRepository::new(pool).get_runs().await?[0].status, ImportRunStatus::Completed);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_import_report() -> Result<()> {
+		use crate::db::connection;
+		use crate::repositories::import_run_repo::ImportRunRepository;
+		use crate::repositories::vulnerability_repo::VulnerabilityRepository;
+
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().join("allitems.csv");
+		std::fs::write(
+			&path,
+			"CVE Version 20061101\n\
+			 Name,Status,Description,References,Phase,Votes,Comments\n\
+			 CVE-2024-0001,Entry,\"Overflow in the parser,\nwhich spans\nthree lines\",,,,\n\
+			 CVE-24-1,Entry,Bad ID,,,,\n\
+			 CVE-2024-0002,Entry,Short row\n\
+			 ,Entry,No ID,,,,\n",
+		)?;
+		assert_eq!(read_headers(&path)?, ["Name", "Status", "Description", "References", "Phase", "Votes", "Comments"]);
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let path = path.to_string_lossy().into_owned();
+		let report = import_vulnerabilities_from_csv(path, pool.clone(), CsvMapping::default(), ProgressReporter::disabled()).await?;
+
+		assert_eq!(report.imported, 2);
+		assert_eq!(report.skipped, 2);
+		assert_eq!(report.skipped_rows.iter().map(|row| (row.line, row.cve_id.as_str())).collect::<Vec<_>>(), [
+			(6, "CVE-24-1"),
+			(8, ""),
+		]);
+		assert!(report.skipped_rows[0].reason.contains("Invalid CVE ID"), "{}", report.skipped_rows[0].reason);
+
+		let vulnerability = VulnerabilityRepository::new(pool.clone()).get_vulnerability_by_cve("CVE-2024-0001").await?.unwrap();
+		assert_eq!(vulnerability.description.as_deref(), Some("Overflow in the parser,\nwhich spans\nthree lines"));
+		let repo = ImportRunRepository::new(pool);
+		assert_eq!(repo.get_runs().await?[0].skipped, 2);
+		assert_eq!(repo.get_skipped_rows(1).await?, report.skipped_rows);
+		Ok(())
+	}
+
+	/// Imports a scanner export updating CVE-2024-0001, which was enriched by hand, and
+	/// adding CVE-2024-0002, returning the report and the merged CVE-2024-0001
+	async fn import_with_policy(merge_policy: MergePolicy) -> Result<(ImportReport, Vulnerability)> {
+		use crate::db::connection;
+		use crate::repositories::vulnerability_repo::VulnerabilityRepository;
+
+		let dir = tempfile::tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (cve_id, description, severity, impact, mitigation, published_date)
+			 VALUES ('CVE-2024-0001', 'Overflow', 'High', 'Arm stops', 'Vendor patch 2.1', '2024-01-01');",
+		)?;
+		let path = dir.path().join("scan.csv");
+		std::fs::write(
+			&path,
+			"CVE,Risk,Summary,Fix\n\
+			 CVE-2024-0001,Low,Heap overflow in the parser,\n\
+			 CVE-2024-0002,Medium,Use after free,Upgrade\n",
+		)?;
+		let mapping = CsvMapping {
+			source: "Scanner".to_string(),
+			cve_id: "CVE".to_string(),
+			severity: "Risk".to_string(),
+			description: "Summary".to_string(),
+			references: None,
+			published_date: None,
+			impact: None,
+			mitigation: Some("Fix".to_string()),
+			merge_policy,
+		};
+		let path = path.to_string_lossy().into_owned();
+		let report = import_vulnerabilities_from_csv(path, pool.clone(), mapping, ProgressReporter::disabled()).await?;
+		let merged = VulnerabilityRepository::new(pool)
+			.get_vulnerability_by_cve("CVE-2024-0001")
+			.await?
+			.unwrap();
+		Ok((report, merged))
+	}
+
+	#[tokio::test]
+	async fn test_merge_policy() -> Result<()> {
+		// Fields the file has replace the recorded ones; empty and unmapped ones keep them
+		let (report, merged) = import_with_policy(MergePolicy::NewestNonEmpty).await?;
+		assert_eq!(report.imported, 2);
+		assert_eq!(merged.description.as_deref(), Some("Heap overflow in the parser"));
+		assert_eq!(merged.severity, "Low");
+		assert_eq!(merged.impact.as_deref(), Some("Arm stops"));
+		assert_eq!(merged.mitigation.as_deref(), Some("Vendor patch 2.1"));
+		assert_eq!(merged.published_date, NaiveDate::from_ymd_opt(2024, 1, 1));
+
+		let (report, merged) = import_with_policy(MergePolicy::Overwrite).await?;
+		assert_eq!(report.imported, 2);
+		assert_eq!(merged.description.as_deref(), Some("Heap overflow in the parser"));
+		assert_eq!(merged.severity, "Low");
+		assert_eq!(merged.impact, None);
+		assert_eq!(merged.mitigation.as_deref(), Some(""));
+		assert_eq!(merged.published_date, None);
+
+		let (report, merged) = import_with_policy(MergePolicy::KeepExisting).await?;
+		assert_eq!(report.imported, 1);
+		assert_eq!(merged.description.as_deref(), Some("Overflow"));
+		assert_eq!(merged.severity, "High");
+		assert_eq!(merged.mitigation.as_deref(), Some("Vendor patch 2.1"));
+		Ok(())
+	}
 }
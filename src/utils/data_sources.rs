@@ -0,0 +1,183 @@
+// src/utils/data_sources.rs
+
+//! Runs the configured data sources: the NVD API enrichment, the KEV catalog, the EPSS
+//! scores, OSV, the GitHub Advisory Database and NVD JSON feeds added by users. Each
+//! run is recorded on its source, which schedules the next one, and what a sync
+//! changed is kept as a sync delta.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use log::{error, info};
+use reqwest::header::USER_AGENT;
+use tempfile::TempPath;
+use crate::db::connection::SqlitePool;
+use crate::models::data_source::{DataSource, RunStatus, SourceKind, SYNC_BATCH_SIZE};
+use crate::models::sync_delta::SyncState;
+use crate::repositories::access;
+use crate::repositories::data_source_repo::DataSourceRepository;
+use crate::repositories::sync_delta_repo::SyncDeltaRepository;
+use crate::utils::nvd_api::NvdApiClient;
+use crate::utils::progress::ProgressReporter;
+use crate::utils::{epss, ghsa, http, kev, nvd_feed, osv};
+
+/// Downloads `url` into a temporary file named like the URL's last segment, as the
+/// importers tell compressed files by their extension
+async fn download(url: &str) -> Result<TempPath> {
+	let response = http::client_builder()?
+		.build()
+		.context("Failed to create HTTP client")?
+		.get(url)
+		.header(USER_AGENT, "Vulnerability-Management-System/1.0")
+		.send()
+		.await
+		.with_context(|| format!("{} unreachable", url))?;
+	let status = response.status();
+	if !status.is_success() {
+		bail!("{} answered {}", url, status);
+	}
+	let body = response.bytes().await.with_context(|| format!("Failed to download {}", url))?;
+	let name = url.split(['?', '#']).next().unwrap_or(url).rsplit('/').next().unwrap_or_default();
+	let mut file = tempfile::Builder::new()
+		.prefix("rvd-source-")
+		.suffix(&format!("-{}", name))
+		.tempfile()
+		.context("Failed to create a download file")?;
+	file.write_all(&body).context("Failed to write the download")?;
+	Ok(file.into_temp_path())
+}
+
+fn source_url(source: &DataSource) -> Result<&str> {
+	source.url.as_deref().with_context(|| format!("{} has no URL", source.name))
+}
+
+/// Runs a source once and summarizes what it imported
+async fn run_source(
+	pool: Arc<SqlitePool>,
+	nvd_client: &NvdApiClient,
+	source: &DataSource,
+	progress: ProgressReporter,
+) -> Result<String> {
+	match source.kind {
+		SourceKind::Nvd => {
+			let updated = nvd_client.batch_update_vulnerabilities(SYNC_BATCH_SIZE, progress).await?;
+			Ok(format!("{} vulnerabilities updated", updated))
+		}
+		SourceKind::Kev => {
+			let path = download(source_url(source)?).await?;
+			let summary = kev::import_kev_catalog(PathBuf::from(&*path), pool).await?;
+			Ok(format!("{} CVEs listed, {} tracked", summary.listed, summary.matched))
+		}
+		SourceKind::Epss => {
+			let path = download(source_url(source)?).await?;
+			let summary = epss::import_epss_scores(PathBuf::from(&*path), pool).await?;
+			Ok(format!(
+				"{} CVEs scored, {} tracked, {} robots rescored",
+				summary.listed, summary.matched, summary.robots_rescored,
+			))
+		}
+		SourceKind::Osv => {
+			let summary = osv::import_osv_vulnerabilities(pool, source_url(source)?, progress).await?;
+			Ok(summary.to_string())
+		}
+		SourceKind::Ghsa => {
+			let ecosystems = ghsa::DEFAULT_ECOSYSTEMS.iter().map(|e| e.to_string()).collect();
+			let summary = ghsa::import_ghsa_advisories(pool, ecosystems, progress).await?;
+			Ok(summary.to_string())
+		}
+		SourceKind::NvdFeed => {
+			access::require_write_access()?;
+			let path = download(source_url(source)?).await?;
+			let summary = nvd_feed::import_nvd_feeds(vec![PathBuf::from(&*path)], pool, progress).await?;
+			if summary.files == 0 {
+				bail!("{} is not an NVD JSON feed", source_url(source)?);
+			}
+			Ok(format!("{} records, {} new", summary.records, summary.inserted))
+		}
+	}
+}
+
+/// Runs a source and records the outcome on it
+async fn run_and_record(
+	pool: Arc<SqlitePool>,
+	nvd_client: &NvdApiClient,
+	source: &DataSource,
+	progress: ProgressReporter,
+) -> Result<String> {
+	let repo = DataSourceRepository::new(pool.clone());
+	repo.record_start(source.source_id, Utc::now()).await?;
+	let result = run_source(pool, nvd_client, source, progress).await;
+	match &result {
+		Ok(message) => {
+			info!("{} synced: {}", source.name, message);
+			repo.record_result(source.source_id, RunStatus::Succeeded, message).await?;
+		}
+		Err(e) => {
+			error!("{} failed to sync: {:#}", source.name, e);
+			repo.record_result(source.source_id, RunStatus::Failed, &format!("{:#}", e)).await?;
+		}
+	}
+	result
+}
+
+/// Records what the sync of `sources` started at `started_at` changed since `before`.
+/// A failure is logged only, as the sync itself went through.
+async fn record_changes(pool: Arc<SqlitePool>, started_at: DateTime<Utc>, sources: Vec<String>, before: SyncState) {
+	match SyncDeltaRepository::new(pool).record(started_at, sources, before).await {
+		Ok(changes) if !changes.is_empty() => info!("Sync brought {}", changes),
+		Ok(_) => {}
+		Err(e) => error!("Failed to record what the sync changed: {:#}", e),
+	}
+}
+
+/// Runs a source now and records the outcome on it and what it changed; the next
+/// scheduled run then waits a full interval
+pub async fn sync_source(
+	pool: Arc<SqlitePool>,
+	nvd_client: &NvdApiClient,
+	source: &DataSource,
+	progress: ProgressReporter,
+) -> Result<String> {
+	let started_at = Utc::now();
+	let before = SyncDeltaRepository::new(pool.clone()).capture_state().await?;
+	let result = run_and_record(pool.clone(), nvd_client, source, progress).await;
+	record_changes(pool, started_at, vec![source.name.clone()], before).await;
+	result
+}
+
+/// Runs every enabled source, or only those due when `due_since` gives the time the
+/// scheduler started, and records what they changed together. Sources that import
+/// rather than enrich need write access and are skipped for read-only roles. Returns
+/// how many sources ran; their failures are recorded on them.
+pub async fn sync_sources(
+	pool: Arc<SqlitePool>,
+	nvd_client: &NvdApiClient,
+	due_since: Option<DateTime<Utc>>,
+	progress: ProgressReporter,
+) -> Result<usize> {
+	let can_edit = access::current_role().can_edit();
+	let now = Utc::now();
+	let due: Vec<DataSource> = DataSourceRepository::new(pool.clone())
+		.get_sources()
+		.await?
+		.into_iter()
+		.filter(|source| match due_since {
+			Some(started) => source.is_due(now, started),
+			None => source.enabled,
+		})
+		.filter(|source| source.kind == SourceKind::Nvd || can_edit)
+		.collect();
+	if due.is_empty() {
+		return Ok(0);
+	}
+
+	let before = SyncDeltaRepository::new(pool.clone()).capture_state().await?;
+	for source in &due {
+		// Failures are logged and recorded on the source
+		let _ = run_and_record(pool.clone(), nvd_client, source, progress.clone()).await;
+	}
+	record_changes(pool, now, due.iter().map(|source| source.name.clone()).collect(), before).await;
+	Ok(due.len())
+}
@@ -0,0 +1,137 @@
+// src/utils/deep_link.rs
+
+//! Links into the GUI from chat or tickets: `rvd://cve/CVE-2024-1234`, or the
+//! `--open CVE-2024-1234` flag, open that vulnerability's detail view on startup.
+//! For clicked links to reach RVD, the scheme is registered with the desktop by
+//! `register_handler`.
+
+use crate::utils::csv_importer::is_valid_cve_id;
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+pub const SCHEME: &str = "rvd://";
+/// Desktop entry registered as the handler of the scheme on Linux
+const DESKTOP_FILE: &str = "rvd-link.desktop";
+
+/// The CVE ID a link or `--open` argument points to, in upper case
+pub fn parse_target(target: &str) -> Result<String> {
+	let trimmed = target.trim();
+	let cve_id = match trimmed.get(..SCHEME.len()) {
+		Some(scheme) if scheme.eq_ignore_ascii_case(SCHEME) => {
+			let path = trimmed[SCHEME.len()..].trim_end_matches('/');
+			match path.split_once('/') {
+				Some((kind, id)) if kind.eq_ignore_ascii_case("cve") => id,
+				_ => bail!("Unsupported link '{}', expected {}cve/CVE-YYYY-NNNN", target, SCHEME),
+			}
+		}
+		_ => trimmed,
+	};
+
+	if !is_valid_cve_id(cve_id) {
+		bail!("'{}' is not a CVE ID", cve_id);
+	}
+	Ok(cve_id.to_ascii_uppercase())
+}
+
+/// Desktop entry starting `exe` in `dir` with the clicked link
+fn desktop_entry(exe: &Path, dir: &Path) -> String {
+	format!(
+		"[Desktop Entry]\n\
+		 Type=Application\n\
+		 Name=Robot Vulnerability Database\n\
+		 Exec=\"{}\" %u\n\
+		 Path={}\n\
+		 MimeType=x-scheme-handler/rvd;\n\
+		 NoDisplay=true\n\
+		 Terminal=false\n",
+		exe.display(),
+		dir.display()
+	)
+}
+
+/// Registry command starting `exe` on the clicked link with `dir` as its data directory.
+/// The link goes straight to RVD as an argument, never through a shell.
+fn windows_command(exe: &Path, dir: &Path) -> String {
+	format!("{} --data-dir {} \"%1\"", quote_windows_arg(exe), quote_windows_arg(dir))
+}
+
+/// Quotes a path for a Windows command line. Windows paths cannot contain quotes, but a
+/// trailing backslash (as in `C:\`) would escape the closing quote, so it is doubled.
+fn quote_windows_arg(path: &Path) -> String {
+	let path = path.display().to_string();
+	let trailing = path.len() - path.trim_end_matches('\\').len();
+	format!("\"{}{}\"", path, "\\".repeat(trailing))
+}
+
+fn run(program: &str, args: &[&str]) -> Result<()> {
+	let status = Command::new(program)
+		.args(args)
+		.status()
+		.with_context(|| format!("Failed to run {}", program))?;
+	if !status.success() {
+		bail!("{} failed with {}", program, status);
+	}
+	Ok(())
+}
+
+/// Makes the desktop open rvd:// links with the running executable, for the current
+/// user. Links open RVD on the current directory, since the `database` directory
+/// holding the workspaces is found relative to it: the desktop entry starts RVD in it,
+/// and on Windows, where the registry has no working directory, it is passed as
+/// `--data-dir`. Returns where the handler was registered.
+pub fn register_handler() -> Result<String> {
+	let exe = std::env::current_exe().context("Failed to find the RVD executable")?;
+	let working_dir = std::env::current_dir().context("Failed to find the current directory")?;
+	if cfg!(target_os = "linux") {
+		let dir = dirs::data_dir()
+			.map(|dir| dir.join("applications"))
+			.context("No data directory to register the link handler in")?;
+		std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+		let path = dir.join(DESKTOP_FILE);
+		std::fs::write(&path, desktop_entry(&exe, &working_dir)).with_context(|| format!("Failed to write {:?}", path))?;
+		run("xdg-mime", &["default", DESKTOP_FILE, "x-scheme-handler/rvd"])?;
+		Ok(path.display().to_string())
+	} else if cfg!(target_os = "windows") {
+		let key = r"HKCU\Software\Classes\rvd";
+		let command = windows_command(&exe, &working_dir);
+		run("reg", &["add", key, "/ve", "/d", "URL:RVD link", "/f"])?;
+		run("reg", &["add", key, "/v", "URL Protocol", "/d", "", "/f"])?;
+		run("reg", &["add", &format!(r"{}\shell\open\command", key), "/ve", "/d", &command, "/f"])?;
+		Ok(key.to_string())
+	} else {
+		bail!("Link handlers are declared by the application bundle on this system; register rvd:// when packaging")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_target() {
+		assert_eq!(parse_target("rvd://cve/CVE-2024-1234").unwrap(), "CVE-2024-1234");
+		assert_eq!(parse_target("RVD://CVE/cve-2024-1234/").unwrap(), "CVE-2024-1234");
+		assert_eq!(parse_target(" cve-2024-12345 ").unwrap(), "CVE-2024-12345");
+		assert!(parse_target("rvd://robot/12").is_err());
+		assert!(parse_target("rvd://cve/2024-1234").is_err());
+		assert!(parse_target("CVE-24-1").is_err());
+	}
+
+	#[test]
+	fn test_desktop_entry() {
+		let entry = desktop_entry(Path::new("/opt/rvd/bin/rvd"), Path::new("/srv/rvd"));
+		assert!(entry.contains("Exec=\"/opt/rvd/bin/rvd\" %u\n"));
+		assert!(entry.contains("Path=/srv/rvd\n"));
+		assert!(entry.contains("MimeType=x-scheme-handler/rvd;\n"));
+	}
+
+	#[test]
+	fn test_windows_command() {
+		assert_eq!(
+			windows_command(Path::new(r"C:\Program Files\RVD\rvd.exe"), Path::new(r"D:\Data\RVD")),
+			r#""C:\Program Files\RVD\rvd.exe" --data-dir "D:\Data\RVD" "%1""#
+		);
+		assert_eq!(quote_windows_arg(Path::new(r"C:\")), r#""C:\\""#);
+	}
+}
@@ -0,0 +1,120 @@
+// src/utils/epss.rs
+
+//! Import of the FIRST Exploit Prediction Scoring System daily scores
+//! (`epss_scores-YYYY-MM-DD.csv`, optionally gzipped). Scored CVEs already in the
+//! database get their probability of exploitation; the fleet risk scores that
+//! depend on it are recomputed in the same transaction.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use log::info;
+use serde::Deserialize;
+use tokio::task;
+use crate::db::connection::SqlitePool;
+use crate::repositories::{access, audit_repo};
+use crate::repositories::robot_repo::refresh_risk_scores;
+
+#[derive(Debug, Deserialize)]
+struct EpssRow {
+	cve: String,
+	epss: f64,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EpssImportSummary {
+	/// CVEs in the file
+	pub listed: usize,
+	/// Listed CVEs found in the database
+	pub matched: usize,
+	/// Robots whose risk score changed
+	pub robots_rescored: usize,
+}
+
+pub async fn import_epss_scores(path: PathBuf, pool: Arc<SqlitePool>) -> Result<EpssImportSummary> {
+	access::require_write_access()?;
+	task::spawn_blocking(move || -> Result<EpssImportSummary> {
+		let file = File::open(&path).with_context(|| format!("Failed to open {:?}", path))?;
+		let reader: Box<dyn Read> = if path.extension().is_some_and(|ext| ext == "gz") {
+			Box::new(GzDecoder::new(file))
+		} else {
+			Box::new(file)
+		};
+		// The file starts with a `#model_version:...` comment before the header
+		let content = BufReader::new(reader)
+			.lines()
+			.filter(|line| !line.as_ref().is_ok_and(|line| line.starts_with('#')))
+			.collect::<std::io::Result<Vec<_>>>()
+			.with_context(|| format!("Failed to read {:?}", path))?
+			.join("\n");
+
+		let mut connection = pool.get().context("Failed to get database connection")?;
+		let transaction = connection.transaction().context("Failed to start database transaction")?;
+		let mut summary = EpssImportSummary::default();
+		{
+			let mut stmt = transaction.prepare("UPDATE vulnerabilities SET epss_score = ?2 WHERE cve_id = ?1")?;
+			for row in csv::Reader::from_reader(content.as_bytes()).deserialize::<EpssRow>() {
+				let row = row.with_context(|| format!("{:?} is not an EPSS score file", path))?;
+				summary.listed += 1;
+				summary.matched += stmt.execute(rusqlite::params![row.cve.trim().to_ascii_uppercase(), row.epss])?;
+			}
+		}
+		summary.robots_rescored = refresh_risk_scores(&transaction)?;
+		audit_repo::record_import(
+			&transaction,
+			&format!("EPSS scores {}", path.display()),
+			&format!("{} listed, {} tracked, {} robots rescored", summary.listed, summary.matched, summary.robots_rescored),
+		)?;
+		transaction.commit().context("Failed to commit transaction")?;
+
+		info!(
+			"Imported EPSS scores {:?}: {} listed, {} tracked, {} robots rescored",
+			path, summary.listed, summary.matched, summary.robots_rescored
+		);
+		Ok(summary)
+	})
+		.await
+		.context("Failed to run EPSS import task")?
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use flate2::{write::GzEncoder, Compression};
+	use std::io::Write;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_import_epss_scores() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (cve_id, severity) VALUES ('CVE-2021-44228', 'Critical'), ('CVE-2024-0001', 'Low');",
+		)?;
+
+		let path = dir.path().join("epss_scores-2024-06-01.csv.gz");
+		let mut encoder = GzEncoder::new(File::create(&path)?, Compression::default());
+		encoder.write_all(
+			b"#model_version:v2023.03.01,score_date:2024-06-01T00:00:00+0000\n\
+			cve,epss,percentile\n\
+			CVE-2021-44228,0.97565,0.99996\n\
+			CVE-1999-0001,0.01,0.5\n",
+		)?;
+		encoder.finish()?;
+
+		let summary = import_epss_scores(path, pool.clone()).await?;
+		assert_eq!((summary.listed, summary.matched), (2, 1));
+
+		let epss: Option<f64> = pool.get()?.query_row(
+			"SELECT epss_score FROM vulnerabilities WHERE cve_id = 'CVE-2021-44228'",
+			[],
+			|row| row.get(0),
+		)?;
+		assert_eq!(epss, Some(0.97565));
+		Ok(())
+	}
+}
@@ -0,0 +1,614 @@
+// src/utils/ghsa.rs
+
+//! Import from the GitHub Advisory Database through the GitHub GraphQL API. Advisories
+//! are looked up for the packages installed on robots, so that libraries from package
+//! ecosystems such as PyPI, crates.io, Go modules and npm are covered even where the
+//! NVD has no usable CPE data.
+//!
+//! Advisories with a CVE alias are stored under the CVE, all others under their GHSA
+//! ID. The GHSA ID is kept as the advisory reference of the entry, so an advisory
+//! first stored under its GHSA ID is merged into the CVE once GitHub lists the alias.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::fmt;
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use log::{debug, info};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT};
+use rusqlite::{params, Connection};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::task;
+use crate::db::connection::SqlitePool;
+use crate::models::reference::Reference;
+use crate::models::vulnerability::{CvssVersion, ProvenanceSource};
+use crate::models::weakness::normalize_cwe_id;
+use crate::repositories::{access, audit_repo, provenance_repo};
+use crate::repositories::alias_repo::{self, canonical_id, link_alias};
+use crate::repositories::reference_repo::insert_references;
+use crate::repositories::robot_repo::refresh_risk_scores;
+use crate::repositories::weakness_repo::insert_weaknesses;
+use crate::utils::http;
+use crate::utils::nvd_feed::title_case;
+use crate::utils::osv::OSV_SOURCE;
+use crate::utils::progress::ProgressReporter;
+use crate::utils::version_match;
+
+const GITHUB_GRAPHQL_URL: &str = "https://api.github.com/graphql";
+/// Environment variable holding the GitHub token; the GraphQL API rejects anonymous requests
+pub const GITHUB_TOKEN_ENV: &str = "GITHUB_TOKEN";
+/// Value of `vulnerabilities.source` for imported advisories
+pub const GHSA_SOURCE: &str = "GitHub Advisory Database";
+/// Ecosystems queried unless others are given: ROS Python packages and the Rust, Go
+/// and Node.js components common on robot controllers
+pub const DEFAULT_ECOSYSTEMS: [&str; 4] = ["PIP", "RUST", "GO", "NPM"];
+/// Products are matched to packages by name alone, which another project may share
+const PACKAGE_MATCH_CONFIDENCE: f64 = 0.8;
+
+const VULNERABILITIES_QUERY: &str = "
+	query($package: String!, $after: String) {
+		securityVulnerabilities(package: $package, first: 100, after: $after) {
+			nodes {
+				package { name ecosystem }
+				vulnerableVersionRange
+				firstPatchedVersion { identifier }
+				advisory {
+					ghsaId summary description severity publishedAt permalink withdrawnAt
+					identifiers { type value }
+					cvss { score vectorString }
+					cwes(first: 20) { nodes { cweId } }
+					references { url }
+				}
+			}
+			pageInfo { hasNextPage endCursor }
+		}
+	}
+";
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse {
+	data: Option<GraphQlData>,
+	#[serde(default)]
+	errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+	message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlData {
+	security_vulnerabilities: VulnerabilityPage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VulnerabilityPage {
+	nodes: Vec<GhsaVulnerability>,
+	page_info: PageInfo,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+	has_next_page: bool,
+	end_cursor: Option<String>,
+}
+
+/// One vulnerable package of an advisory
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GhsaVulnerability {
+	package: GhsaPackage,
+	/// Such as `>= 1.2.0, < 1.4.3`, the syntax of `version_match`
+	vulnerable_version_range: String,
+	first_patched_version: Option<GhsaVersion>,
+	advisory: GhsaAdvisory,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GhsaPackage {
+	name: String,
+	ecosystem: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GhsaVersion {
+	identifier: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhsaAdvisory {
+	ghsa_id: String,
+	summary: String,
+	description: Option<String>,
+	/// LOW, MODERATE, HIGH or CRITICAL
+	severity: String,
+	published_at: String,
+	permalink: String,
+	withdrawn_at: Option<String>,
+	#[serde(default)]
+	identifiers: Vec<GhsaIdentifier>,
+	cvss: Option<GhsaCvss>,
+	cwes: Option<GhsaCwes>,
+	#[serde(default)]
+	references: Vec<GhsaReference>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GhsaIdentifier {
+	#[serde(rename = "type")]
+	kind: String,
+	value: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhsaCvss {
+	score: f64,
+	vector_string: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GhsaCwes {
+	nodes: Vec<GhsaCwe>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhsaCwe {
+	cwe_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GhsaReference {
+	url: String,
+}
+
+/// A package version range an advisory applies to
+#[derive(Debug, Clone, PartialEq)]
+pub struct AffectedPackage {
+	pub name: String,
+	pub range: String,
+	pub fixed_in: Option<String>,
+}
+
+/// An advisory reduced to the fields RVD stores
+#[derive(Debug, Clone, PartialEq)]
+pub struct GhsaRecord {
+	pub ghsa_id: String,
+	/// CVE alias, if one was assigned
+	pub cve_id: Option<String>,
+	pub description: Option<String>,
+	pub severity: String,
+	pub cvss_score: Option<f64>,
+	pub cvss_version: Option<CvssVersion>,
+	pub published_date: Option<NaiveDate>,
+	/// The advisory page first, carrying the GHSA ID
+	pub references: Vec<Reference>,
+	pub weaknesses: Vec<String>,
+	pub packages: Vec<AffectedPackage>,
+}
+
+impl GhsaRecord {
+	/// ID the entry is stored under
+	pub fn id(&self) -> &str {
+		self.cve_id.as_deref().unwrap_or(&self.ghsa_id)
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GhsaImportSummary {
+	/// Installed packages looked up
+	pub packages: usize,
+	pub advisories: usize,
+	pub inserted: usize,
+	/// Entries stored under a GHSA ID merged into their CVE
+	pub merged: usize,
+	/// New correlations with installed software versions
+	pub correlations: usize,
+}
+
+impl fmt::Display for GhsaImportSummary {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} advisories for {} packages, {} new, {} merged into their CVE, {} new correlations",
+			self.advisories, self.packages, self.inserted, self.merged, self.correlations,
+		)
+	}
+}
+
+/// Package names compared the way PyPI does, ignoring case and `-` / `_` / `.`
+fn package_key(name: &str) -> String {
+	name.trim().to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Groups the vulnerable packages by advisory, keeping those of the given ecosystems
+/// and dropping withdrawn advisories
+pub fn records_from(vulnerabilities: &[GhsaVulnerability], ecosystems: &[String]) -> Vec<GhsaRecord> {
+	let mut records: BTreeMap<String, GhsaRecord> = BTreeMap::new();
+	for vulnerability in vulnerabilities {
+		let advisory = &vulnerability.advisory;
+		if advisory.withdrawn_at.is_some()
+			|| !ecosystems.iter().any(|e| e.eq_ignore_ascii_case(&vulnerability.package.ecosystem))
+		{
+			continue;
+		}
+		let package = AffectedPackage {
+			name: vulnerability.package.name.clone(),
+			range: vulnerability.vulnerable_version_range.clone(),
+			fixed_in: vulnerability.first_patched_version.as_ref().map(|v| v.identifier.clone()),
+		};
+		let record = records.entry(advisory.ghsa_id.clone()).or_insert_with(|| record_from(advisory));
+		if !record.packages.contains(&package) {
+			record.packages.push(package);
+		}
+	}
+	records.into_values().collect()
+}
+
+fn record_from(advisory: &GhsaAdvisory) -> GhsaRecord {
+	let cvss = advisory.cvss.as_ref().filter(|cvss| cvss.score > 0.0);
+	let severity = match advisory.severity.to_uppercase().as_str() {
+		"MODERATE" => "Medium".to_string(),
+		other => title_case(other),
+	};
+
+	let mut references = vec![Reference::new(advisory.permalink.clone(), Some(GHSA_SOURCE.to_string()))];
+	references.extend(
+		advisory.references
+			.iter()
+			.filter(|r| r.url != advisory.permalink)
+			.map(|r| Reference::new(r.url.clone(), None)),
+	);
+
+	GhsaRecord {
+		ghsa_id: advisory.ghsa_id.clone(),
+		cve_id: advisory.identifiers
+			.iter()
+			.find(|identifier| identifier.kind.eq_ignore_ascii_case("CVE"))
+			.map(|identifier| identifier.value.trim().to_uppercase()),
+		description: advisory.description
+			.as_deref()
+			.map(str::trim)
+			.filter(|d| !d.is_empty())
+			.or(Some(advisory.summary.trim()))
+			.map(str::to_string),
+		severity,
+		cvss_score: cvss.map(|cvss| cvss.score),
+		cvss_version: cvss
+			.and_then(|cvss| cvss.vector_string.as_deref())
+			.and_then(|vector| vector.strip_prefix("CVSS:"))
+			.and_then(|vector| vector.split('/').next())
+			.and_then(CvssVersion::from_db),
+		published_date: advisory.published_at
+			.get(..10)
+			.and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()),
+		references,
+		weaknesses: advisory.cwes
+			.iter()
+			.flat_map(|cwes| &cwes.nodes)
+			.filter_map(|cwe| normalize_cwe_id(&cwe.cwe_id))
+			.collect(),
+		packages: Vec::new(),
+	}
+}
+
+pub struct GhsaClient {
+	client: reqwest::Client,
+}
+
+impl GhsaClient {
+	/// Client authenticated with the token in `GITHUB_TOKEN`
+	pub fn from_env() -> Result<Self> {
+		let token = std::env::var(GITHUB_TOKEN_ENV)
+			.ok()
+			.filter(|token| !token.trim().is_empty())
+			.with_context(|| format!("Set {} to a GitHub token to query the GitHub Advisory Database", GITHUB_TOKEN_ENV))?;
+
+		let mut headers = HeaderMap::new();
+		headers.insert(USER_AGENT, HeaderValue::from_static("Vulnerability-Management-System/1.0"));
+		headers.insert(
+			AUTHORIZATION,
+			HeaderValue::from_str(&format!("Bearer {}", token.trim())).context("Invalid GitHub token")?,
+		);
+		let client = http::client_builder()?
+			.default_headers(headers)
+			.build()
+			.context("Failed to create HTTP client")?;
+		Ok(Self { client })
+	}
+
+	/// All vulnerable version ranges GitHub lists for a package name, in any ecosystem
+	pub async fn fetch_package(&self, package: &str) -> Result<Vec<GhsaVulnerability>> {
+		let mut vulnerabilities = Vec::new();
+		let mut after: Option<String> = None;
+		loop {
+			debug!("Fetching GitHub advisories for {} after {:?}", package, after);
+			let response = self.client
+				.post(GITHUB_GRAPHQL_URL)
+				.json(&json!({ "query": VULNERABILITIES_QUERY, "variables": { "package": package, "after": after } }))
+				.send()
+				.await
+				.context("GitHub GraphQL API unreachable")?;
+			let status = response.status();
+			if !status.is_success() {
+				bail!("GitHub GraphQL API request failed with status: {}", status);
+			}
+			let response: GraphQlResponse = response.json().await.context("Failed to parse GitHub GraphQL response")?;
+			if let Some(error) = response.errors.first() {
+				bail!("GitHub GraphQL API error: {}", error.message);
+			}
+			let page = response.data.context("GitHub GraphQL response without data")?.security_vulnerabilities;
+
+			vulnerabilities.extend(page.nodes);
+			match page.page_info.end_cursor {
+				Some(cursor) if page.page_info.has_next_page => after = Some(cursor),
+				_ => return Ok(vulnerabilities),
+			}
+		}
+	}
+}
+
+/// Names of the software products installed on at least one robot
+pub(crate) fn installed_packages(conn: &Connection) -> Result<Vec<String>> {
+	let names = conn
+		.prepare(
+			"SELECT DISTINCT sp.product_name FROM software_products sp
+			 JOIN software_versions sv ON sv.product_id = sp.product_id
+			 JOIN robot_software rs ON rs.version_id = sv.version_id
+			 ORDER BY sp.product_name",
+		)?
+		.query_map([], |row| row.get(0))?
+		.collect::<rusqlite::Result<Vec<String>>>()
+		.context("Failed to collect installed software")?;
+	Ok(names)
+}
+
+/// Looks up the advisories of the given ecosystems for every installed package and
+/// stores them, correlating the installed versions in the vulnerable ranges.
+pub async fn import_ghsa_advisories(
+	pool: Arc<SqlitePool>,
+	ecosystems: Vec<String>,
+	progress: ProgressReporter,
+) -> Result<GhsaImportSummary> {
+	access::require_write_access()?;
+	let client = GhsaClient::from_env()?;
+	let packages = task::spawn_blocking({
+		let pool = pool.clone();
+		move || -> Result<Vec<String>> {
+			let conn = pool.get().context("Failed to get database connection")?;
+			installed_packages(&conn)
+		}
+	})
+		.await
+		.context("Failed to run GitHub advisory import task")??;
+
+	let tracker = progress.start("GitHub advisory import");
+	let mut vulnerabilities = Vec::new();
+	for (index, package) in packages.iter().enumerate() {
+		tracker.check_cancelled()?;
+		tracker.update(index, index as f32 / packages.len() as f32);
+		vulnerabilities.extend(client.fetch_package(package).await?);
+	}
+	tracker.finish(packages.len());
+
+	let records = records_from(&vulnerabilities, &ecosystems);
+	let package_count = packages.len();
+	let summary = task::spawn_blocking(move || -> Result<GhsaImportSummary> {
+		let mut connection = pool.get().context("Failed to get database connection")?;
+		let transaction = connection.transaction().context("Failed to start database transaction")?;
+		let summary = GhsaImportSummary { packages: package_count, ..store_records(&transaction, &records, GHSA_SOURCE)? };
+		audit_repo::record_import(&transaction, "GitHub Advisory Database", &summary.to_string())?;
+		transaction.commit().context("Failed to commit transaction")?;
+		Ok(summary)
+	})
+		.await
+		.context("Failed to run GitHub advisory import task")??;
+
+	info!("Imported GitHub advisories: {}", summary);
+	Ok(summary)
+}
+
+/// Writes the advisories under their CVE, keeping the advisory ID as an alias, and
+/// rescores the fleet. Known entries keep their metrics and gain a longer description
+/// or fill in empty fields. `source` is the database the advisories came from.
+pub(crate) fn store_records(conn: &Connection, records: &[GhsaRecord], source: &str) -> Result<GhsaImportSummary> {
+	let mut summary = GhsaImportSummary { advisories: records.len(), ..Default::default() };
+	let mut upsert = conn.prepare(
+		"INSERT INTO vulnerabilities (cve_id, description, severity, published_date, cvss_score, cvss_version, source)
+		 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+		 ON CONFLICT(cve_id) DO UPDATE SET
+			description = CASE WHEN length(COALESCE(excluded.description, '')) > length(COALESCE(vulnerabilities.description, ''))
+				THEN excluded.description ELSE vulnerabilities.description END,
+			severity = CASE WHEN UPPER(vulnerabilities.severity) = 'UNKNOWN'
+				THEN excluded.severity ELSE vulnerabilities.severity END,
+			published_date = COALESCE(vulnerabilities.published_date, excluded.published_date),
+			cvss_score = COALESCE(vulnerabilities.cvss_score, excluded.cvss_score),
+			cvss_version = CASE WHEN vulnerabilities.cvss_score IS NULL
+				THEN excluded.cvss_version ELSE vulnerabilities.cvss_version END",
+	)?;
+	let origin = if source == OSV_SOURCE { ProvenanceSource::Osv } else { ProvenanceSource::Ghsa };
+	for record in records {
+		// Stored under the CVE, or whichever entry already knows the GHSA ID
+		let id = canonical_id(conn, record.id())?;
+		let known = alias_repo::resolve(conn, &id)?.is_some() || alias_repo::resolve(conn, &record.ghsa_id)?.is_some();
+		summary.inserted += usize::from(!known);
+		let before = provenance_repo::snapshot(conn, &id)?;
+		upsert.execute(params![
+			id,
+			record.description,
+			record.severity,
+			record.published_date.map(|d| d.to_string()),
+			record.cvss_score,
+			record.cvss_version.map(|v| v.as_str()),
+			source,
+		]).with_context(|| format!("Failed to import {}", id))?;
+		provenance_repo::record_changes(conn, &id, before, origin)?;
+		if id != record.ghsa_id {
+			summary.merged += usize::from(
+				link_alias(conn, &id, &record.ghsa_id, source)
+					.with_context(|| format!("Failed to merge {} into {}", record.ghsa_id, id))?,
+			);
+		}
+		insert_references(conn, &id, &record.references)
+			.with_context(|| format!("Failed to import references of {}", id))?;
+		insert_weaknesses(conn, &id, &record.weaknesses)
+			.with_context(|| format!("Failed to import weaknesses of {}", id))?;
+		summary.correlations += correlate(conn, &id, record)
+			.with_context(|| format!("Failed to correlate {}", id))?;
+	}
+
+	refresh_risk_scores(conn)?;
+	Ok(summary)
+}
+
+/// Correlates the installed versions of products named like an affected package that
+/// fall in its vulnerable range. Returns the number of correlations added.
+fn correlate(conn: &Connection, id: &str, record: &GhsaRecord) -> Result<usize> {
+	let mut installed = conn.prepare_cached(
+		"SELECT DISTINCT sv.version_id, sp.product_name, sv.version_number
+		 FROM software_versions sv
+		 JOIN software_products sp ON sp.product_id = sv.product_id
+		 JOIN robot_software rs ON rs.version_id = sv.version_id",
+	)?;
+	let installed = installed
+		.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+		.collect::<rusqlite::Result<Vec<(i64, String, String)>>>()?;
+
+	let mut added = 0;
+	for package in &record.packages {
+		for (version_id, product_name, version_number) in &installed {
+			let unfixed = package.fixed_in
+				.as_deref()
+				.is_none_or(|fixed| version_match::compare_versions(version_number, fixed) == std::cmp::Ordering::Less);
+			if package_key(product_name) == package_key(&package.name)
+				&& unfixed
+				&& version_match::matches(&package.range, version_number)
+			{
+				added += conn.execute(
+					"INSERT OR IGNORE INTO affected_software
+						(vulnerability_id, version_id, affected_version_pattern, fixed_in_version, detection_confidence)
+					 SELECT vulnerability_id, ?2, ?3, ?4, ?5 FROM vulnerabilities WHERE cve_id = ?1",
+					params![id, version_id, package.range, package.fixed_in, PACKAGE_MATCH_CONFIDENCE],
+				)?;
+			}
+		}
+	}
+	Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	const PAGE: &str = r#"{
+		"data": { "securityVulnerabilities": {
+			"nodes": [
+				{
+					"package": { "name": "ros_bridge", "ecosystem": "PIP" },
+					"vulnerableVersionRange": ">= 1.0, < 1.4.3",
+					"firstPatchedVersion": { "identifier": "1.4.3" },
+					"advisory": {
+						"ghsaId": "GHSA-abcd-1234-wxyz", "summary": "Command injection in ros_bridge",
+						"description": "", "severity": "MODERATE", "publishedAt": "2024-03-01T12:00:00Z",
+						"permalink": "https://github.com/advisories/GHSA-abcd-1234-wxyz", "withdrawnAt": null,
+						"identifiers": [{ "type": "GHSA", "value": "GHSA-abcd-1234-wxyz" }, { "type": "CVE", "value": "CVE-2024-1111" }],
+						"cvss": { "score": 6.5, "vectorString": "CVSS:3.1/AV:N/AC:L/PR:L/UI:N/S:U/C:H/I:N/A:N" },
+						"cwes": { "nodes": [{ "cweId": "CWE-78" }] },
+						"references": [{ "url": "https://github.com/advisories/GHSA-abcd-1234-wxyz" }, { "url": "https://example.com/fix" }]
+					}
+				},
+				{
+					"package": { "name": "ros-bridge", "ecosystem": "NPM" },
+					"vulnerableVersionRange": "< 2.0",
+					"firstPatchedVersion": null,
+					"advisory": {
+						"ghsaId": "GHSA-9999-8888-7777", "summary": "Prototype pollution",
+						"description": null, "severity": "HIGH", "publishedAt": "2024-04-01T00:00:00Z",
+						"permalink": "https://github.com/advisories/GHSA-9999-8888-7777", "withdrawnAt": null,
+						"identifiers": [], "cvss": { "score": 0.0, "vectorString": null }, "cwes": { "nodes": [] }, "references": []
+					}
+				}
+			],
+			"pageInfo": { "hasNextPage": false, "endCursor": null }
+		} }
+	}"#;
+
+	fn vulnerabilities() -> Vec<GhsaVulnerability> {
+		let response: GraphQlResponse = serde_json::from_str(PAGE).unwrap();
+		response.data.unwrap().security_vulnerabilities.nodes
+	}
+
+	#[test]
+	fn test_records_from() {
+		let records = records_from(&vulnerabilities(), &["pip".to_string()]);
+		assert_eq!(records.len(), 1);
+		let record = &records[0];
+		assert_eq!((record.id(), record.ghsa_id.as_str()), ("CVE-2024-1111", "GHSA-abcd-1234-wxyz"));
+		assert_eq!(record.description.as_deref(), Some("Command injection in ros_bridge"));
+		assert_eq!((record.severity.as_str(), record.cvss_score, record.cvss_version), ("Medium", Some(6.5), Some(CvssVersion::V31)));
+		assert_eq!(record.references.len(), 2);
+		assert_eq!(record.references[0].advisory_id.as_deref(), Some("GHSA-abcd-1234-wxyz"));
+		assert_eq!(record.weaknesses, ["CWE-78"]);
+
+		let records = records_from(&vulnerabilities(), &DEFAULT_ECOSYSTEMS.map(String::from));
+		// Ordered by GHSA ID
+		assert_eq!(records[0].id(), "GHSA-9999-8888-7777");
+		assert_eq!((records[0].severity.as_str(), records[0].cvss_score), ("High", None));
+	}
+
+	#[test]
+	fn test_store_merges_ghsa_entry_and_correlates() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = connection::establish_pool_with_path(dir.path().join("test.db"))?;
+		let conn = pool.get()?;
+		conn.execute_batch(
+			"INSERT INTO robots (robot_id, name) VALUES (1, 'arm-01');
+			 INSERT INTO software_products (product_id, product_name, vendor) VALUES (1, 'ROS-Bridge', 'OSRF');
+			 INSERT INTO software_versions (version_id, product_id, version_number) VALUES (1, 1, '1.4.2'), (2, 1, '1.4.3');
+			 INSERT INTO robot_software (robot_id, version_id) VALUES (1, 1), (1, 2);
+			 INSERT INTO vulnerabilities (vulnerability_id, cve_id, description, severity) VALUES
+				(1, 'CVE-2024-1111', 'From the NVD', 'High'),
+				(2, 'GHSA-abcd-1234-wxyz', 'Stored before the CVE was assigned', 'Unknown');
+			 INSERT INTO notes (entity_type, entity_id, body) VALUES ('vulnerability', 2, 'Vendor contacted');",
+		)?;
+
+		let records = records_from(&vulnerabilities(), &DEFAULT_ECOSYSTEMS.map(String::from));
+		let summary = store_records(&conn, &records, GHSA_SOURCE)?;
+		assert_eq!((summary.advisories, summary.inserted, summary.merged), (2, 1, 1));
+		// Only 1.4.2 is in the PyPI range; the npm advisory has the same normalized name
+		// and no fix, so it covers both
+		assert_eq!(summary.correlations, 3);
+
+		let ids: Vec<String> = conn
+			.prepare("SELECT cve_id FROM vulnerabilities ORDER BY cve_id")?
+			.query_map([], |row| row.get(0))?
+			.collect::<rusqlite::Result<_>>()?;
+		assert_eq!(ids, ["CVE-2024-1111", "GHSA-9999-8888-7777"]);
+		let (description, severity): (String, String) = conn.query_row(
+			"SELECT description, severity FROM vulnerabilities WHERE cve_id = 'CVE-2024-1111'",
+			[],
+			|row| Ok((row.get(0)?, row.get(1)?)),
+		)?;
+		// The longest description of the three wins; the NVD rating is kept
+		assert_eq!((description.as_str(), severity.as_str()), ("Stored before the CVE was assigned", "High"));
+		assert_eq!(canonical_id(&conn, "GHSA-abcd-1234-wxyz")?, "CVE-2024-1111");
+		let note_owner: i64 = conn.query_row("SELECT entity_id FROM notes", [], |row| row.get(0))?;
+		assert_eq!(note_owner, 1);
+		let advisory: String = conn.query_row(
+			"SELECT advisory_id FROM vulnerability_references WHERE vulnerability_id = 1 AND advisory_id IS NOT NULL",
+			[],
+			|row| row.get(0),
+		)?;
+		assert_eq!(advisory, "GHSA-abcd-1234-wxyz");
+		Ok(())
+	}
+}
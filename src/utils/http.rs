@@ -0,0 +1,109 @@
+// src/utils/http.rs
+
+//! HTTP clients for every outbound request: the NVD, advisory databases, data source
+//! downloads, ticketing and the update check. Most industrial networks only allow
+//! egress through a TLS-inspecting proxy, so the clients go through the configured
+//! proxy and trust the configured root certificates on top of the system's.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// Password of the proxy account, kept out of the database
+pub const PROXY_PASSWORD_ENV: &str = "RVD_PROXY_PASSWORD";
+
+/// How outbound requests leave the network. The proxy password is read from the
+/// `RVD_PROXY_PASSWORD` environment variable so it never lands in the database.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkSettings {
+	/// Proxy for HTTP and HTTPS requests, e.g. `http://proxy.plant.local:3128`; without
+	/// one the `HTTP_PROXY` and `HTTPS_PROXY` environment variables apply
+	#[serde(default)]
+	pub proxy_url: Option<String>,
+	/// Account to authenticate to the proxy with
+	#[serde(default)]
+	pub proxy_username: Option<String>,
+	/// PEM file of extra root certificates, such as the one a TLS-inspecting proxy
+	/// signs its certificates with
+	#[serde(default)]
+	pub ca_bundle: Option<PathBuf>,
+}
+
+static NETWORK_SETTINGS: RwLock<NetworkSettings> = RwLock::new(NetworkSettings {
+	proxy_url: None,
+	proxy_username: None,
+	ca_bundle: None,
+});
+
+pub fn set_network_settings(settings: NetworkSettings) {
+	*NETWORK_SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = settings;
+}
+
+pub fn network_settings() -> NetworkSettings {
+	NETWORK_SETTINGS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Builder of an HTTP client using the configured proxy and root certificates
+pub fn client_builder() -> Result<reqwest::ClientBuilder> {
+	builder_for(&network_settings(), std::env::var(PROXY_PASSWORD_ENV).ok())
+}
+
+/// Fails with the reason when clients cannot be built with `settings`, e.g. for a
+/// malformed proxy URL or an unreadable certificate file
+pub fn check(settings: &NetworkSettings) -> Result<()> {
+	builder_for(settings, std::env::var(PROXY_PASSWORD_ENV).ok())?
+		.build()
+		.context("Failed to create HTTP client")?;
+	Ok(())
+}
+
+fn builder_for(settings: &NetworkSettings, password: Option<String>) -> Result<reqwest::ClientBuilder> {
+	let mut builder = reqwest::Client::builder();
+	if let Some(url) = settings.proxy_url.as_deref().map(str::trim).filter(|url| !url.is_empty()) {
+		let mut proxy = reqwest::Proxy::all(url).with_context(|| format!("Invalid proxy URL {}", url))?;
+		if let Some(username) = settings.proxy_username.as_deref().map(str::trim).filter(|name| !name.is_empty()) {
+			proxy = proxy.basic_auth(username, password.as_deref().unwrap_or_default());
+		}
+		builder = builder.proxy(proxy);
+	}
+	if let Some(path) = &settings.ca_bundle {
+		let pem = std::fs::read(path).with_context(|| format!("Failed to read the CA bundle {:?}", path))?;
+		let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+			.with_context(|| format!("The CA bundle {:?} is not valid PEM", path))?;
+		if certificates.is_empty() {
+			bail!("The CA bundle {:?} holds no certificate", path);
+		}
+		for certificate in certificates {
+			builder = builder.add_root_certificate(certificate);
+		}
+	}
+	Ok(builder)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_network_settings() -> Result<()> {
+		check(&NetworkSettings::default())?;
+		check(&NetworkSettings {
+			proxy_url: Some("http://proxy.plant.local:3128".to_string()),
+			proxy_username: Some("svc-rvd".to_string()),
+			ca_bundle: None,
+		})?;
+		let invalid_proxy = NetworkSettings { proxy_url: Some("not a url".to_string()), ..Default::default() };
+		assert!(check(&invalid_proxy).is_err());
+
+		let dir = tempdir()?;
+		let missing = NetworkSettings { ca_bundle: Some(dir.path().join("missing.pem")), ..Default::default() };
+		assert!(check(&missing).is_err());
+		let not_pem = dir.path().join("proxy.pem");
+		std::fs::write(&not_pem, "not a certificate")?;
+		let empty = NetworkSettings { ca_bundle: Some(not_pem), ..Default::default() };
+		assert!(check(&empty).is_err());
+		Ok(())
+	}
+}
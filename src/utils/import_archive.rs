@@ -0,0 +1,167 @@
+// src/utils/import_archive.rs
+
+//! Copies of imported files kept for provenance. Each import stores its input
+//! under `database/imports/<workspace>`; copies older than the retention period are
+//! gzipped into `archive/` and listed in `archive/index.csv`, so automated
+//! pipelines that import daily do not fill the disk.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use flate2::{write::GzEncoder, Compression};
+use log::info;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+
+/// Days a copy stays uncompressed when no retention is configured
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+const ARCHIVE_DIR: &str = "archive";
+const INDEX_FILE: &str = "index.csv";
+const INDEX_HEADERS: [&str; 5] = ["archived_at", "imported_at", "file", "bytes", "compressed_bytes"];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveSummary {
+	pub files: usize,
+	/// Size of the archived copies before compression
+	pub bytes: u64,
+	pub compressed_bytes: u64,
+}
+
+/// The kept import files of one workspace
+#[derive(Debug, Clone)]
+pub struct ImportArchive {
+	root: PathBuf,
+}
+
+impl ImportArchive {
+	pub fn new(root: PathBuf) -> Self {
+		Self { root }
+	}
+
+	pub fn for_workspace(workspace: &str) -> Self {
+		Self::new(PathBuf::from("database").join("imports").join(workspace))
+	}
+
+	/// Keeps a copy of an imported file, prefixed with the time of the import
+	pub fn retain(&self, source: &Path, now: DateTime<Utc>) -> Result<PathBuf> {
+		let name = source.file_name()
+			.with_context(|| format!("{:?} is not a file", source))?
+			.to_string_lossy();
+		fs::create_dir_all(&self.root).with_context(|| format!("Failed to create {:?}", self.root))?;
+		let target = self.root.join(format!("{}-{}", now.format("%Y%m%dT%H%M%SZ"), name));
+		fs::copy(source, &target).with_context(|| format!("Failed to copy {:?} to {:?}", source, target))?;
+		Ok(target)
+	}
+
+	/// Compresses the copies not modified for `retention_days` into the archive
+	pub fn archive_older_than(&self, retention_days: u32, now: DateTime<Utc>) -> Result<ArchiveSummary> {
+		let mut summary = ArchiveSummary::default();
+		if !self.root.is_dir() {
+			return Ok(summary);
+		}
+		let cutoff = now - Duration::days(retention_days.into());
+		let archive_dir = self.root.join(ARCHIVE_DIR);
+
+		let mut expired = Vec::new();
+		for entry in fs::read_dir(&self.root).with_context(|| format!("Failed to list {:?}", self.root))? {
+			let entry = entry?;
+			let metadata = entry.metadata()?;
+			if !metadata.is_file() {
+				continue;
+			}
+			let modified: DateTime<Utc> = metadata.modified()?.into();
+			if modified < cutoff {
+				expired.push((entry.path(), modified, metadata.len()));
+			}
+		}
+		if expired.is_empty() {
+			return Ok(summary);
+		}
+		expired.sort();
+
+		fs::create_dir_all(&archive_dir).with_context(|| format!("Failed to create {:?}", archive_dir))?;
+		let index_path = archive_dir.join(INDEX_FILE);
+		let new_index = !index_path.exists();
+		let index = OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(&index_path)
+			.with_context(|| format!("Failed to open {:?}", index_path))?;
+		let mut index = csv::Writer::from_writer(index);
+		if new_index {
+			index.write_record(INDEX_HEADERS)?;
+		}
+
+		for (path, modified, bytes) in expired {
+			let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+			let target = archive_dir.join(format!("{}.gz", name));
+			let mut encoder = GzEncoder::new(
+				File::create(&target).with_context(|| format!("Failed to create {:?}", target))?,
+				Compression::default(),
+			);
+			io::copy(&mut BufReader::new(File::open(&path)?), &mut encoder)
+				.with_context(|| format!("Failed to compress {:?}", path))?;
+			encoder.finish()?;
+			let compressed_bytes = fs::metadata(&target)?.len();
+
+			index.write_record([
+				now.to_rfc3339(),
+				modified.to_rfc3339(),
+				format!("{}.gz", name),
+				bytes.to_string(),
+				compressed_bytes.to_string(),
+			])?;
+			// Only drop the copy once its archive entry is written
+			index.flush()?;
+			fs::remove_file(&path).with_context(|| format!("Failed to remove {:?}", path))?;
+
+			summary.files += 1;
+			summary.bytes += bytes;
+			summary.compressed_bytes += compressed_bytes;
+		}
+
+		info!(
+			"Archived {} import files in {:?} ({} bytes to {})",
+			summary.files, archive_dir, summary.bytes, summary.compressed_bytes
+		);
+		Ok(summary)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use flate2::read::GzDecoder;
+	use std::io::Read;
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_retain_and_archive() -> Result<()> {
+		let dir = tempdir()?;
+		let source = dir.path().join("scan.csv");
+		let csv = "cve_id,severity\nCVE-2024-0001,High\n";
+		fs::write(&source, csv)?;
+		let archive = ImportArchive::new(dir.path().join("imports"));
+
+		let now = Utc::now();
+		let kept = archive.retain(&source, now)?;
+		assert!(kept.file_name().unwrap().to_string_lossy().ends_with("-scan.csv"));
+
+		// Nothing is old enough yet
+		assert_eq!(archive.archive_older_than(1, now)?, ArchiveSummary::default());
+
+		let summary = archive.archive_older_than(1, now + Duration::days(2))?;
+		assert_eq!((summary.files, summary.bytes), (1, csv.len() as u64));
+		assert!(!kept.exists());
+
+		let archived = dir.path().join("imports/archive").join(format!("{}.gz", kept.file_name().unwrap().to_string_lossy()));
+		let mut content = String::new();
+		GzDecoder::new(File::open(archived)?).read_to_string(&mut content)?;
+		assert_eq!(content, csv);
+
+		let index = fs::read_to_string(dir.path().join("imports/archive/index.csv"))?;
+		assert_eq!(index.lines().count(), 2);
+		assert!(index.starts_with("archived_at,imported_at,file,bytes,compressed_bytes"));
+		Ok(())
+	}
+}
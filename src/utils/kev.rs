@@ -0,0 +1,114 @@
+// src/utils/kev.rs
+
+//! Import of the CISA Known Exploited Vulnerabilities catalog
+//! (`known_exploited_vulnerabilities.json`). Listed CVEs already in the database
+//! get the date CISA added them; CVEs RVD does not track are skipped.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use log::info;
+use serde::Deserialize;
+use tokio::task;
+use crate::db::connection::SqlitePool;
+use crate::repositories::{access, audit_repo};
+use crate::repositories::robot_repo::refresh_risk_scores;
+
+#[derive(Debug, Deserialize)]
+struct KevCatalog {
+	vulnerabilities: Vec<KevEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KevEntry {
+	#[serde(rename = "cveID")]
+	cve_id: String,
+	date_added: String,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct KevImportSummary {
+	/// CVEs in the catalog
+	pub listed: usize,
+	/// Listed CVEs found in the database
+	pub matched: usize,
+}
+
+pub async fn import_kev_catalog(path: PathBuf, pool: Arc<SqlitePool>) -> Result<KevImportSummary> {
+	access::require_write_access()?;
+	task::spawn_blocking(move || -> Result<KevImportSummary> {
+		let json = std::fs::read_to_string(&path)
+			.with_context(|| format!("Failed to read {:?}", path))?;
+		let catalog: KevCatalog = serde_json::from_str(&json)
+			.with_context(|| format!("{:?} is not a KEV catalog", path))?;
+
+		let mut connection = pool.get().context("Failed to get database connection")?;
+		let transaction = connection.transaction().context("Failed to start database transaction")?;
+		let mut matched = 0;
+		{
+			let mut stmt = transaction.prepare(
+				"UPDATE vulnerabilities SET kev_date_added = ?2 WHERE cve_id = ?1",
+			)?;
+			for entry in &catalog.vulnerabilities {
+				let date_added = NaiveDate::parse_from_str(&entry.date_added, "%Y-%m-%d")
+					.with_context(|| format!("Invalid dateAdded of {}", entry.cve_id))?;
+				matched += stmt.execute(rusqlite::params![
+					entry.cve_id.trim().to_ascii_uppercase(),
+					date_added.to_string(),
+				])?;
+			}
+		}
+		// Known exploitation weighs into the fleet risk scores
+		refresh_risk_scores(&transaction)?;
+		audit_repo::record_import(
+			&transaction,
+			&format!("KEV catalog {}", path.display()),
+			&format!("{} listed, {} tracked", catalog.vulnerabilities.len(), matched),
+		)?;
+		transaction.commit().context("Failed to commit transaction")?;
+
+		info!("Imported KEV catalog {:?}: {} listed, {} tracked", path, catalog.vulnerabilities.len(), matched);
+		Ok(KevImportSummary { listed: catalog.vulnerabilities.len(), matched })
+	})
+		.await
+		.context("Failed to run KEV import task")?
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_import_kev_catalog() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO vulnerabilities (cve_id, severity) VALUES ('CVE-2021-44228', 'Critical'), ('CVE-2024-0001', 'Low');",
+		)?;
+
+		let path = dir.path().join("known_exploited_vulnerabilities.json");
+		std::fs::write(&path, r#"{
+			"title": "CISA Catalog of Known Exploited Vulnerabilities",
+			"count": 2,
+			"vulnerabilities": [
+				{ "cveID": "CVE-2021-44228", "vendorProject": "Apache", "dateAdded": "2021-12-10", "dueDate": "2021-12-24" },
+				{ "cveID": "CVE-2019-0708", "vendorProject": "Microsoft", "dateAdded": "2021-11-03", "dueDate": "2022-05-03" }
+			]
+		}"#)?;
+
+		let summary = import_kev_catalog(path, pool.clone()).await?;
+		assert_eq!((summary.listed, summary.matched), (2, 1));
+
+		let added: Option<String> = pool.get()?.query_row(
+			"SELECT kev_date_added FROM vulnerabilities WHERE cve_id = 'CVE-2021-44228'",
+			[],
+			|row| row.get(0),
+		)?;
+		assert_eq!(added.as_deref(), Some("2021-12-10"));
+		Ok(())
+	}
+}
@@ -1,8 +1,59 @@
-use env_logger::{Builder, Env};
+// src/utils/logger.rs
+
+//! Logging through `tracing`. The existing `log` macros are forwarded into it, spans
+//! report their duration when they close, and the output is text or one JSON object
+//! per line (`RVD_LOG_FORMAT=json`).
+//!
+//! Filter directives such as `info,rvd_core::utils::nvd_api=debug`
+//! come from `RUST_LOG`, else from the `log_filter` setting once the database is open.
+
+use anyhow::{Context, Result};
+use std::sync::OnceLock;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+const DEFAULT_FILTER: &str = "info";
+const FORMAT_VAR: &str = "RVD_LOG_FORMAT";
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
 
 pub fn init() {
-	Builder::from_env(Env::default().default_filter_or("info"))
-		.format_timestamp_millis()
-		.format_module_path(true)
-		.init();
-}
\ No newline at end of file
+	let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+	let (filter, handle) = reload::Layer::new(filter);
+
+	let json = std::env::var(FORMAT_VAR).is_ok_and(|format| format.eq_ignore_ascii_case("json"));
+	// stderr like env_logger, so command output on stdout stays clean
+	let fmt = tracing_subscriber::fmt::layer()
+		.with_writer(std::io::stderr)
+		.with_span_events(FmtSpan::CLOSE);
+	let registry = tracing_subscriber::registry().with(filter);
+	let result = if json {
+		registry.with(fmt.json().with_current_span(true)).try_init()
+	} else {
+		registry.with(fmt).try_init()
+	};
+
+	// A second init, e.g. from tests, keeps the first subscriber
+	if result.is_ok() {
+		let _ = FILTER_HANDLE.set(handle);
+	}
+}
+
+/// Check that `directives` parse as a filter, e.g. before saving them
+pub fn parse_filter(directives: &str) -> Result<EnvFilter> {
+	EnvFilter::try_new(directives).with_context(|| format!("Invalid log filter '{}'", directives))
+}
+
+/// Apply the configured filter directives, unless `RUST_LOG` overrides them
+pub fn apply_configured_filter(directives: &str) -> Result<()> {
+	if std::env::var_os(EnvFilter::DEFAULT_ENV).is_some() {
+		return Ok(());
+	}
+	let filter = parse_filter(directives)?;
+	if let Some(handle) = FILTER_HANDLE.get() {
+		handle.reload(filter).context("Failed to apply log filter")?;
+	}
+	Ok(())
+}
@@ -1,5 +1,29 @@
 // src/utils/mod.rs
 
 pub mod logger;
+pub mod alerts;
 pub mod csv_importer;
-pub(crate) mod nvd_api;
+pub mod data_sources;
+pub mod deep_link;
+pub mod epss;
+pub mod ghsa;
+pub mod http;
+pub mod import_archive;
+pub mod kev;
+pub mod nvd_api;
+pub mod nvd_cache;
+pub mod nvd_feed;
+pub mod offline_bundle;
+pub mod osv;
+pub(crate) mod nvd_metrics;
+pub(crate) mod nvd_rate_limit;
+pub mod product_match;
+pub mod robot_import;
+pub mod rvd_import;
+pub mod ticketing;
+pub mod progress;
+pub mod time;
+pub mod update_check;
+pub mod version_match;
+pub mod watch_folder;
+pub(crate) mod xlsx;
@@ -1,15 +1,132 @@
 use anyhow::{Context, Result};
-use chrono::NaiveDate;
-use log::{debug, error, info};
-use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use chrono::{NaiveDate, Utc};
+use log::{debug, error, info, warn};
+use rand::Rng;
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER, USER_AGENT};
+use reqwest::StatusCode;
 use serde::Deserialize;
-use std::sync::Arc;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use tokio::time::{sleep, Duration};
-use crate::db::connection::SqlitePool;
-use crate::models::vulnerability::Vulnerability;
+use crate::db::connection::{self, SqlitePool};
+use crate::models::enrichment::{EnrichmentOutcome, EnrichmentRun};
+use crate::models::keyword_discovery::KeywordDiscovery;
+use crate::models::nvd_health::NvdHealth;
+use crate::models::vulnerability::{ProvenanceField, ProvenanceSource, Vulnerability};
+use crate::models::reference::Reference;
+use crate::repositories::{access, provenance_repo};
+use crate::repositories::enrichment_repo::EnrichmentRepository;
+use crate::models::weakness::normalize_cwe_id;
+use crate::repositories::reference_repo::insert_references;
+use crate::repositories::settings_repo::SettingsRepository;
+use crate::repositories::weakness_repo::insert_weaknesses;
+use crate::utils::http;
+use crate::utils::nvd_cache::NvdCache;
+use crate::utils::nvd_feed::{insert_new_records, parse_api_page, FeedRecord};
+use crate::utils::nvd_metrics::NvdMetrics;
+use crate::utils::nvd_rate_limit::{NvdRateLimiter, RequestPriority};
+use crate::utils::progress::ProgressReporter;
+use crate::utils::time;
 
 const NVD_API_BASE_URL: &str = "https://services.nvd.nist.gov/rest/json/cves/2.0";
-const REQUEST_DELAY: Duration = Duration::from_millis(2000);
+/// Environment variable holding an NVD API key, which raises the request quota
+pub const API_KEY_ENV: &str = "NVD_API_KEY";
+/// Requests in flight at once during batch enrichment; the shared rate limiter
+/// decides when they are sent
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+const RETRY_ATTEMPTS_ENV: &str = "RVD_NVD_RETRY_ATTEMPTS";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const KEYWORD_RESULTS_PER_PAGE: usize = 2000;
+
+/// The NVD API key from `NVD_API_KEY`, if one is set
+pub fn api_key() -> Option<String> {
+	std::env::var(API_KEY_ENV)
+		.ok()
+		.map(|key| key.trim().to_string())
+		.filter(|key| !key.is_empty())
+}
+
+/// How failed NVD requests are retried
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+	/// Total number of tries per request, including the first one
+	pub max_attempts: u32,
+	/// Delay before the first retry; doubled for every further retry
+	pub base_delay: Duration,
+	pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self {
+			max_attempts: 4,
+			// The NVD asks unauthenticated clients to wait 6 seconds between requests
+			base_delay: Duration::from_secs(6),
+			max_delay: Duration::from_secs(120),
+		}
+	}
+}
+
+impl RetryPolicy {
+	/// Default policy, with the attempt count overridable through `RVD_NVD_RETRY_ATTEMPTS`
+	pub fn from_env() -> Self {
+		let mut policy = Self::default();
+		if let Some(attempts) = std::env::var(RETRY_ATTEMPTS_ENV).ok().and_then(|v| v.parse::<u32>().ok()) {
+			policy.max_attempts = attempts.max(1);
+		}
+		policy
+	}
+
+	/// Exponential backoff for the given retry (1-based), jittered into the upper half
+	/// of the interval so that parallel clients do not retry in lockstep
+	fn backoff(&self, retry: u32) -> Duration {
+		let exponential = self.base_delay
+			.saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+			.min(self.max_delay);
+		exponential.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+	}
+}
+
+/// Parses a `Retry-After` header given in seconds
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+	headers
+		.get(RETRY_AFTER)?
+		.to_str()
+		.ok()?
+		.trim()
+		.parse::<u64>()
+		.ok()
+		.map(Duration::from_secs)
+}
+
+/// The NVD answers 403 or 429 once the unauthenticated request quota is used up
+#[derive(Debug, thiserror::Error)]
+#[error("NVD API rate limit reached (status {0})")]
+struct RateLimited(StatusCode);
+
+/// No answer, or only server errors, after the last attempt
+#[derive(Debug, thiserror::Error)]
+#[error("NVD API unreachable: {0}")]
+struct Unreachable(String);
+
+/// Outcome of a keyword discovery run
+#[derive(Debug, Default, Clone)]
+pub struct KeywordDiscoverySummary {
+	/// Term and date range combinations searched
+	pub searches: usize,
+	pub hits: usize,
+	/// CVE IDs imported by this run
+	pub inserted: Vec<String>,
+}
+
+impl fmt::Display for KeywordDiscoverySummary {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} searches, {} matching CVEs, {} new", self.searches, self.hits, self.inserted.len())
+	}
+}
 
 #[derive(Debug, Deserialize)]
 struct NvdApiResponse {
@@ -28,30 +145,45 @@ struct NvdCve {
 	metrics: Option<NvdMetrics>,
 	published: String,
 	lastModified: String,
+	#[serde(default)]
+	references: Vec<NvdReference>,
+	#[serde(default)]
+	weaknesses: Vec<NvdWeakness>,
 }
 
 #[derive(Debug, Deserialize)]
-struct NvdDescription {
-	lang: String,
-	value: String,
+struct NvdWeakness {
+	#[serde(default)]
+	description: Vec<NvdDescription>,
 }
 
 #[derive(Debug, Deserialize)]
-struct NvdMetrics {
-	cvssMetrics: Vec<NvdCvssMetric>,
+struct NvdReference {
+	url: String,
+	source: Option<String>,
+	#[serde(default)]
+	tags: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
-struct NvdCvssMetric {
-	source: String,
-	score: Option<f64>,
-	severity: Option<String>,
+struct NvdDescription {
+	lang: String,
+	value: String,
 }
 
 #[derive(Clone)]
 pub struct NvdApiClient {
 	client: reqwest::Client,
 	pool: Arc<SqlitePool>,
+	base_url: String,
+	retry_policy: RetryPolicy,
+	limiter: &'static NvdRateLimiter,
+	/// Shared by the concurrent requests of a batch and persisted when it ends
+	health: Arc<Mutex<NvdHealth>>,
+	cache: NvdCache,
+	/// Fetch every CVE from the NVD even if the cache has a recent answer; the cache
+	/// still stores the new answers
+	bypass_cache: bool,
 }
 
 impl NvdApiClient {
@@ -61,39 +193,249 @@ impl NvdApiClient {
 			USER_AGENT,
 			HeaderValue::from_static("Vulnerability-Management-System/1.0"),
 		);
+		if let Some(key) = api_key() {
+			let mut value = HeaderValue::from_str(&key).context("Invalid NVD API key")?;
+			value.set_sensitive(true);
+			headers.insert("apiKey", value);
+		}
 
-		let client = reqwest::Client::builder()
+		let client = http::client_builder()?
 			.default_headers(headers)
+			.timeout(REQUEST_TIMEOUT)
 			.build()
 			.context("Failed to create HTTP client")?;
 
-		Ok(Self { client, pool })
+		Ok(Self {
+			client,
+			pool,
+			base_url: NVD_API_BASE_URL.to_string(),
+			retry_policy: RetryPolicy::from_env(),
+			limiter: NvdRateLimiter::shared(),
+			health: Arc::new(Mutex::new(NvdHealth::default())),
+			cache: NvdCache::from_env(),
+			bypass_cache: false,
+		})
+	}
+
+	/// Ignores cached answers, e.g. when an analyst asks for the NVD's current record
+	pub fn bypassing_cache(mut self) -> Self {
+		self.bypass_cache = true;
+		self
 	}
 
-	async fn fetch_nvd_data(&self, cve_id: &str) -> Result<NvdApiResponse> {
-		let url = format!("{}?cveId={}", NVD_API_BASE_URL, cve_id);
-		debug!("Fetching NVD data for {}", cve_id);
+	fn health(&self) -> NvdHealth {
+		self.health.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+	}
 
-		let response = self.client
-			.get(&url)
-			.send()
-			.await
-			.context("Failed to send request to NVD API")?;
+	/// Fetch one CVE, from the cache when it has a recent answer, and track whether the
+	/// NVD answered
+	async fn fetch_nvd_data(&self, cve_id: &str, priority: RequestPriority) -> Result<NvdApiResponse> {
+		if !self.bypass_cache {
+			// An unreadable entry, e.g. from an older version, is fetched again
+			let cached = self.cache.get(cve_id, Utc::now()).and_then(|body| serde_json::from_str(&body).ok());
+			if let Some(response) = cached {
+				debug!("Using the cached NVD answer for {}", cve_id);
+				return Ok(response);
+			}
+		}
+		let result = self.request_nvd_data(cve_id, priority).await;
+		self.track_health(&result);
+		result
+	}
 
-		if !response.status().is_success() {
-			return Err(anyhow::anyhow!(
-				"NVD API request failed with status: {}",
-				response.status()
-			));
+	/// Records whether the NVD answered a request
+	fn track_health<T>(&self, result: &Result<T>) {
+		let reached = match result {
+			Ok(_) => Some(true),
+			Err(e) if e.is::<Unreachable>() => Some(false),
+			// The NVD answered, e.g. with a rate limit or an unparsable document
+			Err(_) => None,
+		};
+		if let Some(reached) = reached {
+			let mut health = self.health.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+			if reached {
+				health.record_success(Utc::now());
+			} else {
+				health.record_failure(Utc::now());
+			}
 		}
+	}
 
-		let data = response
-			.json::<NvdApiResponse>()
+	/// Fetch one CVE, see `send_with_retry`
+	#[tracing::instrument(level = "debug", skip(self))]
+	async fn request_nvd_data(&self, cve_id: &str, priority: RequestPriority) -> Result<NvdApiResponse> {
+		let url = format!("{}?cveId={}", self.base_url, cve_id);
+		let body = self.send_with_retry(&url, cve_id, priority)
+			.await?
+			.text()
 			.await
-			.context("Failed to parse NVD API response")?;
+			.context("Failed to read NVD API response")?;
+		let response: NvdApiResponse = serde_json::from_str(&body).context("Failed to parse NVD API response")?;
+		// Unknown CVEs are not cached, so they are found once the NVD publishes them
+		if let Some(vuln) = response.vulnerabilities.first() {
+			if let Err(e) = self.cache.put(cve_id, &vuln.cve.lastModified, &body) {
+				warn!("Failed to cache the NVD answer for {}: {:#}", cve_id, e);
+			}
+		}
+		Ok(response)
+	}
 
-		sleep(REQUEST_DELAY).await;
-		Ok(data)
+	/// Send a request once the shared rate limiter lets it through, retrying network errors,
+	/// server errors and rate limiting with backoff. A rate limit holds back the requests of
+	/// every job. Gives up with a `RateLimited` error if the NVD is still throttling after the
+	/// last attempt, or with `Unreachable` if it did not answer or only with server errors.
+	async fn send_with_retry(&self, url: &str, what: &str, priority: RequestPriority) -> Result<reqwest::Response> {
+		let mut attempt = 1;
+
+		loop {
+			self.limiter.acquire(priority).await;
+			debug!("Fetching NVD data for {} (attempt {})", what, attempt);
+			let last_attempt = attempt >= self.retry_policy.max_attempts;
+
+			let response = match self.client.get(url).send().await {
+				Ok(response) => response,
+				Err(e) if !last_attempt => {
+					let delay = self.retry_policy.backoff(attempt);
+					warn!("NVD request for {} failed ({}), retrying in {:?}", what, e, delay);
+					sleep(delay).await;
+					attempt += 1;
+					continue;
+				}
+				Err(e) => return Err(Unreachable(e.to_string()).into()),
+			};
+
+			let status = response.status();
+			let rate_limited = matches!(status, StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS);
+
+			if rate_limited || status.is_server_error() {
+				if last_attempt {
+					if rate_limited {
+						return Err(RateLimited(status).into());
+					}
+					return Err(Unreachable(format!("status {}", status)).into());
+				}
+
+				let delay = retry_after(response.headers())
+					.unwrap_or_else(|| self.retry_policy.backoff(attempt));
+				warn!("NVD API answered {} for {}, retrying in {:?}", status, what, delay);
+				if rate_limited {
+					self.limiter.pause(delay);
+				} else {
+					sleep(delay).await;
+				}
+				attempt += 1;
+				continue;
+			}
+
+			if !status.is_success() {
+				anyhow::bail!("NVD API request failed with status: {}", status);
+			}
+			return Ok(response);
+		}
+	}
+
+	/// Fetch one page of CVEs published in `window` that mention `term`. Terms of several
+	/// words are matched as a phrase.
+	async fn search_keyword(
+		&self,
+		term: &str,
+		window: (NaiveDate, NaiveDate),
+		start_index: usize,
+	) -> Result<(Vec<FeedRecord>, usize)> {
+		let mut query = vec![
+			("keywordSearch", term.to_string()),
+			("pubStartDate", format!("{}T00:00:00.000", window.0)),
+			("pubEndDate", format!("{}T23:59:59.999", window.1)),
+			("resultsPerPage", KEYWORD_RESULTS_PER_PAGE.to_string()),
+			("startIndex", start_index.to_string()),
+		];
+		if term.contains(char::is_whitespace) {
+			query.push(("keywordExactMatch", String::new()));
+		}
+		let url = reqwest::Url::parse_with_params(&self.base_url, &query).context("Invalid NVD keyword query")?;
+
+		let result = async {
+			let body = self.send_with_retry(url.as_str(), &format!("\"{}\"", term), RequestPriority::Discovery)
+				.await?
+				.text()
+				.await
+				.context("Failed to read NVD API response")?;
+			parse_api_page(&body)
+		}.await;
+		self.track_health(&result);
+		result
+	}
+
+	/// Search the NVD for CVEs mentioning the configured robotics terms and import those
+	/// not stored yet, so that relevant CVEs show up without a software match.
+	///
+	/// Only searches publication dates not covered by earlier runs; progress is saved
+	/// after every date range, so a cancelled or rate limited run resumes there. Unless
+	/// `force` is set, runs at most once a day and not while the NVD is down.
+	pub async fn discover_keyword_matches(&self, force: bool, progress: ProgressReporter) -> Result<KeywordDiscoverySummary> {
+		access::require_write_access()?;
+		let settings = SettingsRepository::new(self.pool.clone());
+		let mut discovery = settings.get_keyword_discovery().await?;
+		let mut summary = KeywordDiscoverySummary::default();
+		if discovery.terms.is_empty() || !(force || discovery.is_due(Utc::now())) {
+			return Ok(summary);
+		}
+		let health = settings.get_nvd_health().await?;
+		if !force && health.is_outage() {
+			info!("NVD unreachable, keyword discovery postponed");
+			return Ok(summary);
+		}
+		*self.health.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = health;
+
+		let result = self.search_keywords(&settings, &mut discovery, &mut summary, progress).await;
+		settings.set_nvd_health(&self.health()).await?;
+		result?;
+		info!("Keyword discovery finished: {}", summary);
+		Ok(summary)
+	}
+
+	async fn search_keywords(
+		&self,
+		settings: &SettingsRepository,
+		discovery: &mut KeywordDiscovery,
+		summary: &mut KeywordDiscoverySummary,
+		progress: ProgressReporter,
+	) -> Result<()> {
+		let windows = discovery.windows(Utc::now().date_naive());
+		let searches = windows.len() * discovery.terms.len();
+		let tracker = progress.start("NVD keyword discovery");
+		for window in windows {
+			for term in &discovery.terms {
+				tracker.check_cancelled()?;
+				tracker.update(summary.searches, summary.searches as f32 / searches as f32);
+				let mut start_index = 0;
+				loop {
+					let (records, total) = self.search_keyword(term, window, start_index)
+						.await
+						.with_context(|| format!("NVD keyword search for \"{}\" failed", term))?;
+					let fetched = records.len();
+					start_index += fetched;
+					summary.hits += fetched;
+					let pool = self.pool.clone();
+					let inserted = tokio::task::spawn_blocking(move || insert_new_records(&pool, records)).await??;
+					for cve_id in inserted {
+						info!("Discovered {} through \"{}\"", cve_id, term);
+						summary.inserted.push(cve_id);
+					}
+					if fetched == 0 || start_index >= total {
+						break;
+					}
+				}
+				summary.searches += 1;
+			}
+			discovery.searched_until = Some(window.1);
+			settings.set_keyword_discovery(discovery).await?;
+		}
+
+		discovery.last_run = Some(Utc::now());
+		settings.set_keyword_discovery(discovery).await?;
+		tracker.finish(summary.searches);
+		Ok(())
 	}
 
 	fn get_english_description(&self, descriptions: &[NvdDescription]) -> Option<String> {
@@ -103,56 +445,74 @@ impl NvdApiClient {
 			.map(|desc| desc.value.clone())
 	}
 
-	fn get_severity(&self, metrics: &Option<NvdMetrics>) -> Option<String> {
-		metrics.as_ref().and_then(|m| {
-			m.cvssMetrics.iter()
-				.find(|metric| metric.severity.is_some())
-				.and_then(|metric| metric.severity.clone())
-				.map(|s| s.to_uppercase())
-		})
-	}
 
-	async fn update_fields_if_unknown(&self, vuln: &Vulnerability) -> Result<bool> {
-		// Check if any fields need updating
-		let needs_update = vuln.description.as_ref().map_or(true, |d| d.trim().is_empty())
-			|| vuln.severity.to_uppercase() == "UNKNOWN"
-			|| vuln.published_date.is_none()
-			|| vuln.impact.as_ref().map_or(true, |i| i.trim().is_empty())
-			|| vuln.mitigation.as_ref().map_or(true, |m| m.trim().is_empty());
+	/// Fills in the fields that are empty or unknown, except those an analyst set by hand
+	async fn update_fields_if_unknown(&self, vuln: &Vulnerability, priority: RequestPriority) -> Result<bool> {
+		let missing_description = !vuln.is_curated(ProvenanceField::Description)
+			&& vuln.description.as_ref().is_none_or(|d| d.trim().is_empty());
+		let unknown_severity = !vuln.is_curated(ProvenanceField::Severity) && vuln.severity.to_uppercase() == "UNKNOWN";
+		let missing_score = !vuln.is_curated(ProvenanceField::CvssScore) && vuln.cvss_score.is_none();
+		let missing_date = !vuln.is_curated(ProvenanceField::PublishedDate) && vuln.published_date.is_none();
+		let needs_update = missing_description || unknown_severity || missing_score || missing_date;
 
 		if !needs_update {
 			return Ok(false);
 		}
 
-		let nvd_data = self.fetch_nvd_data(&vuln.cve_id).await?;
+		let nvd_data = self.fetch_nvd_data(&vuln.cve_id, priority).await?;
 
 		if let Some(vuln_data) = nvd_data.vulnerabilities.first() {
 			// Only update fields that are unknown or empty
-			let description = if vuln.description.as_ref().map_or(true, |d| d.trim().is_empty()) {
+			let description = if missing_description {
 				self.get_english_description(&vuln_data.cve.descriptions)
 			} else {
 				vuln.description.clone()
 			};
 
-			let severity = if vuln.severity.to_uppercase() == "UNKNOWN" {
-				self.get_severity(&vuln_data.cve.metrics)
+			let cvss = vuln_data.cve.metrics.as_ref().and_then(NvdMetrics::preferred);
+
+			let severity = if unknown_severity {
+				cvss.as_ref()
+					.and_then(|c| c.severity.as_ref())
+					.map(|s| s.to_uppercase())
 					.unwrap_or_else(|| vuln.severity.clone())
 			} else {
 				vuln.severity.clone()
 			};
 
-			let published_date = if vuln.published_date.is_none() {
+			// The version is only stored together with a score taken from the NVD
+			let (cvss_score, cvss_version) = match (missing_score, &cvss) {
+				(true, Some(cvss)) => (Some(cvss.score), Some(cvss.version)),
+				_ => (vuln.cvss_score, None),
+			};
+
+			let published_date = if missing_date {
 				NaiveDate::parse_from_str(&vuln_data.cve.published[..10], "%Y-%m-%d").ok()
 			} else {
 				vuln.published_date
 			};
 
+			let references: Vec<Reference> = vuln_data.cve.references
+				.iter()
+				.map(|r| Reference::new(r.url.clone(), r.source.clone()).with_tags(r.tags.clone()))
+				.collect();
+			let weaknesses: Vec<String> = vuln_data.cve.weaknesses
+				.iter()
+				.flat_map(|w| &w.description)
+				.filter_map(|d| normalize_cwe_id(&d.value))
+				.collect();
+
 			// Use spawn_blocking for SQLite operations
 			tokio::task::spawn_blocking({
 				let pool = self.pool.clone();
 				let cve_id = vuln.cve_id.clone();
-				move || -> Result<()> {
-					let conn = pool.get().context("Failed to get database connection")?;
+				move || connection::with_write_retry(&pool, |conn| {
+					let tx = conn.transaction()?;
+					let before = provenance_repo::snapshot(&tx, &cve_id)?;
+					insert_references(&tx, &cve_id, &references)
+						.context("Failed to store references")?;
+					insert_weaknesses(&tx, &cve_id, &weaknesses)
+						.context("Failed to store weaknesses")?;
 
 					// Build dynamic update query based on which fields need updating
 					let mut update_parts = Vec::new();
@@ -168,28 +528,38 @@ impl NvdApiClient {
 						params.push(Box::new(severity.clone()));
 					}
 
+					if cvss_score.is_some() {
+						update_parts.push("cvss_score = ?");
+						params.push(Box::new(cvss_score));
+					}
+
+					if let Some(version) = cvss_version {
+						update_parts.push("cvss_version = ?");
+						params.push(Box::new(version.as_str()));
+					}
+
 					if published_date.is_some() {
 						update_parts.push("published_date = ?");
 						params.push(Box::new(published_date.map(|d| d.to_string())));
 					}
 
-					if update_parts.is_empty() {
-						return Ok(());
+					if !update_parts.is_empty() {
+						let query = format!(
+							"UPDATE vulnerabilities SET {} WHERE cve_id = ?",
+							update_parts.join(", ")
+						);
+						params.push(Box::new(cve_id.clone()));
+
+						tx.execute(
+							&query,
+							rusqlite::params_from_iter(params.iter()),
+						).context("Failed to update vulnerability record")?;
 					}
 
-					let query = format!(
-						"UPDATE vulnerabilities SET {} WHERE cve_id = ?",
-						update_parts.join(", ")
-					);
-					params.push(Box::new(cve_id));
-
-					conn.execute(
-						&query,
-						rusqlite::params_from_iter(params.iter()),
-					).context("Failed to update vulnerability record")?;
-
+					provenance_repo::record_changes(&tx, &cve_id, before, ProvenanceSource::Nvd)?;
+					tx.commit()?;
 					Ok(())
-				}
+				})
 			})
 				.await??;
 
@@ -199,61 +569,305 @@ impl NvdApiClient {
 		}
 	}
 
-	pub async fn batch_update_vulnerabilities(&self, batch_size: usize) -> Result<usize> {
-		let vulnerabilities = tokio::task::spawn_blocking({
-			let pool = self.pool.clone();
-			move || -> Result<Vec<Vulnerability>> {
-				let conn = pool.get().context("Failed to get database connection")?;
-				let mut stmt = conn.prepare(
-					"SELECT vulnerability_id, cve_id, description, severity, impact, mitigation, published_date
-					 FROM vulnerabilities
-					 WHERE description IS NULL
-						OR description = ''
-						OR severity = 'UNKNOWN'
-						OR published_date IS NULL
-						OR impact IS NULL
-						OR impact = ''
-						OR mitigation IS NULL
-						OR mitigation = ''
-					 LIMIT ?"
-				)?;
-
-				let vulnerabilities = stmt.query_map([batch_size], |row| {
-					Ok(Vulnerability {
-						vulnerability_id: row.get(0)?,
-						cve_id: row.get(1)?,
-						description: row.get(2)?,
-						severity: row.get(3)?,
-						impact: row.get(4)?,
-						mitigation: row.get(5)?,
-						published_date: row.get::<_, Option<String>>(6)?
-							.and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()),
-					})
-				})?
-					.collect::<Result<Vec<_>, _>>()?;
-
-				Ok(vulnerabilities)
+	/// Fill in the missing fields of one vulnerability right away, ahead of the requests of
+	/// background jobs. Returns false when nothing was missing or the NVD has no record.
+	pub async fn fetch_now(&self, vuln: &Vulnerability) -> Result<bool> {
+		access::require_write_access()?;
+		self.update_fields_if_unknown(vuln, RequestPriority::Interactive).await
+	}
+
+	/// Enrich one vulnerability and classify the result
+	async fn enrich(&self, vuln: &Vulnerability) -> EnrichmentOutcome {
+		match self.update_fields_if_unknown(vuln, RequestPriority::Enrichment).await {
+			Ok(true) => {
+				info!("Updated unknown fields for vulnerability: {}", vuln.cve_id);
+				EnrichmentOutcome::Updated
 			}
-		})
-			.await??;
+			Ok(false) => {
+				debug!("No unknown fields to update for: {}", vuln.cve_id);
+				EnrichmentOutcome::Unchanged
+			}
+			Err(e) if e.is::<RateLimited>() => {
+				warn!("{}, stopping batch at {}", e, vuln.cve_id);
+				EnrichmentOutcome::RateLimited
+			}
+			Err(e) if e.is::<Unreachable>() => {
+				warn!("{} while fetching {}", e, vuln.cve_id);
+				EnrichmentOutcome::Failed
+			}
+			Err(e) => {
+				error!("Failed to update unknown fields for {}: {}", vuln.cve_id, e);
+				EnrichmentOutcome::Failed
+			}
+		}
+	}
 
-		let mut updated_count = 0;
+	/// Enrich the next batch of incomplete vulnerabilities, persisting per-entry outcomes
+	/// and the run tallies so that a restart continues with the entries not yet tried.
+	///
+	/// Up to `MAX_CONCURRENT_REQUESTS` CVEs are fetched at once, each request starting
+	/// only once the shared rate limiter lets it through, so the NVD quota holds however
+	/// many are in flight. Once the NVD keeps
+	/// rate limiting or stops answering, or the run is cancelled through `progress`,
+	/// requests that have not started yet are left for the next run. While the NVD is
+	/// down, a run only sends one request to find out whether it is back.
+	#[tracing::instrument(level = "debug", skip(self, progress))]
+	pub async fn batch_update_vulnerabilities(
+		&self,
+		batch_size: usize,
+		progress: ProgressReporter,
+	) -> Result<usize> {
+		let enrichment_repo = EnrichmentRepository::new(self.pool.clone());
+		let settings = SettingsRepository::new(self.pool.clone());
+		let health = settings.get_nvd_health().await?;
+		let probing = health.is_outage();
+		*self.health.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = health.clone();
+		let batch_size = if probing {
+			info!(
+				"NVD unreachable since {}, checking with a single request",
+				health.failing_since.map(time::format_local).unwrap_or_default()
+			);
+			1
+		} else {
+			batch_size
+		};
+
+		let vulnerabilities = enrichment_repo.get_pending(batch_size).await?;
+		let run_id = enrichment_repo.start_run(batch_size).await?;
+		let mut run = EnrichmentRun::default();
+		let total = vulnerabilities.len();
+		let tracker = progress.start("NVD enrichment");
+		let cancel = progress.cancellation_token();
+		let mut processed = 0;
+
+		let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+		let rate_limited = Arc::new(AtomicBool::new(false));
+		let mut tasks = JoinSet::new();
 
 		for vuln in vulnerabilities {
-			match self.update_fields_if_unknown(&vuln).await {
-				Ok(true) => {
-					updated_count += 1;
-					info!("Updated unknown fields for vulnerability: {}", vuln.cve_id);
+			let client = self.clone();
+			let semaphore = semaphore.clone();
+			let rate_limited = rate_limited.clone();
+			let cancel = cancel.clone();
+
+			tasks.spawn(async move {
+				let _permit = semaphore.acquire_owned().await.ok()?;
+				let unreachable = !probing && client.health().is_outage();
+				if rate_limited.load(Ordering::Relaxed) || unreachable || cancel.is_cancelled() {
+					return None;
 				}
-				Ok(false) => {
-					debug!("No unknown fields to update for: {}", vuln.cve_id);
+
+				let outcome = client.enrich(&vuln).await;
+				if outcome == EnrichmentOutcome::RateLimited {
+					rate_limited.store(true, Ordering::Relaxed);
 				}
-				Err(e) => {
-					error!("Failed to update unknown fields for {}: {}", vuln.cve_id, e);
+				Some((vuln, outcome))
+			});
+		}
+
+		while let Some(result) = tasks.join_next().await {
+			processed += 1;
+			tracker.update(processed, processed as f32 / total as f32);
+			match result {
+				Ok(Some((vuln, outcome))) => {
+					run.record(outcome);
+					if let Some(id) = vuln.vulnerability_id {
+						enrichment_repo.record_attempt(run_id, id, outcome).await?;
+					}
 				}
+				Ok(None) => {}
+				Err(e) => error!("Enrichment task failed: {}", e),
 			}
 		}
 
+		let health = self.health();
+		match (probing, health.is_outage()) {
+			(false, true) => warn!(
+				"NVD unreachable since {}, pausing enrichment until it answers again",
+				health.failing_since.map(time::format_local).unwrap_or_default()
+			),
+			(true, false) if health.consecutive_failures == 0 => info!("NVD reachable again"),
+			_ => {}
+		}
+		settings.set_nvd_health(&health).await?;
+
+		info!("Enrichment run {} finished: {}", run_id, run.summary());
+		let updated_count = run.updated as usize;
+		enrichment_repo.finish_run(run_id, run).await?;
+		tracker.finish(processed);
+
 		Ok(updated_count)
 	}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::atomic::AtomicUsize;
+	use std::time::Instant;
+	use tempfile::{tempdir, TempDir};
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+	use tokio::net::{TcpListener, TcpStream};
+
+	/// Local stand-in for the NVD, answering every request with `status` after `delay`
+	#[derive(Clone)]
+	struct MockNvd {
+		url: String,
+		status: &'static str,
+		delay: Duration,
+		/// When each request arrived, in order
+		arrivals: Arc<Mutex<Vec<Instant>>>,
+		in_flight: Arc<AtomicUsize>,
+		max_in_flight: Arc<AtomicUsize>,
+	}
+
+	impl MockNvd {
+		async fn start(status: &'static str, delay: Duration) -> Result<Self> {
+			let listener = TcpListener::bind("127.0.0.1:0").await?;
+			let mock = Self {
+				url: format!("http://{}/rest/json/cves/2.0", listener.local_addr()?),
+				status,
+				delay,
+				arrivals: Arc::default(),
+				in_flight: Arc::default(),
+				max_in_flight: Arc::default(),
+			};
+			let server = mock.clone();
+			tokio::spawn(async move {
+				while let Ok((stream, _)) = listener.accept().await {
+					tokio::spawn(server.clone().answer(stream));
+				}
+			});
+			Ok(mock)
+		}
+
+		async fn answer(self, mut stream: TcpStream) -> std::io::Result<()> {
+			let mut head = Vec::new();
+			let mut buffer = [0; 1024];
+			while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+				let read = stream.read(&mut buffer).await?;
+				if read == 0 {
+					return Ok(());
+				}
+				head.extend_from_slice(&buffer[..read]);
+			}
+			self.arrivals.lock().unwrap().push(Instant::now());
+			let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+			self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+			sleep(self.delay).await;
+			self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+			let body = r#"{"vulnerabilities":[]}"#;
+			let response = format!(
+				"HTTP/1.1 {}\r\nRetry-After: 1\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+				self.status,
+				body.len(),
+				body
+			);
+			stream.write_all(response.as_bytes()).await
+		}
+
+		fn arrivals(&self) -> Vec<Instant> {
+			self.arrivals.lock().unwrap().clone()
+		}
+	}
+
+	/// A client of `mock` with its own rate limiter and cache, and a database holding
+	/// `pending` vulnerabilities to enrich
+	async fn client_for(
+		mock: &MockNvd,
+		requests_per_window: usize,
+		window: Duration,
+		max_attempts: u32,
+		pending: usize,
+	) -> Result<(NvdApiClient, TempDir)> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		for n in 1..=pending {
+			pool.get()?.execute(
+				"INSERT INTO vulnerabilities (cve_id, severity) VALUES (?1, 'UNKNOWN')",
+				[format!("CVE-2024-{:04}", n)],
+			)?;
+		}
+		let client = NvdApiClient {
+			base_url: mock.url.clone(),
+			retry_policy: RetryPolicy { max_attempts, ..RetryPolicy::default() },
+			limiter: Box::leak(Box::new(NvdRateLimiter::new(requests_per_window, window))),
+			cache: NvdCache::new(dir.path().join("nvd_cache"), chrono::Duration::hours(1)),
+			..NvdApiClient::new(pool)?
+		};
+		Ok((client, dir))
+	}
+
+	#[tokio::test]
+	async fn test_batch_concurrency_is_bounded() -> Result<()> {
+		let mock = MockNvd::start("200 OK", Duration::from_millis(200)).await?;
+		let (client, _dir) = client_for(&mock, 100, Duration::from_secs(30), 1, 10).await?;
+		client.batch_update_vulnerabilities(10, ProgressReporter::disabled()).await?;
+		assert_eq!(mock.arrivals().len(), 10);
+		assert_eq!(mock.max_in_flight.load(Ordering::SeqCst), MAX_CONCURRENT_REQUESTS);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_batch_keeps_to_the_request_quota() -> Result<()> {
+		let window = Duration::from_millis(400);
+		let mock = MockNvd::start("200 OK", Duration::from_millis(20)).await?;
+		let (client, _dir) = client_for(&mock, 2, window, 1, 6).await?;
+		client.batch_update_vulnerabilities(6, ProgressReporter::disabled()).await?;
+
+		// Although 4 requests may be in flight, no 3 start within one window
+		let arrivals = mock.arrivals();
+		assert_eq!(arrivals.len(), 6);
+		for pair in arrivals.windows(3) {
+			assert!(pair[2] - pair[0] >= window - Duration::from_millis(50), "{:?}", pair[2] - pair[0]);
+		}
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_batch_rate_limited() -> Result<()> {
+		let mock = MockNvd::start("429 Too Many Requests", Duration::from_millis(20)).await?;
+		let (client, _dir) = client_for(&mock, 100, Duration::from_secs(30), 2, 6).await?;
+		client.batch_update_vulnerabilities(6, ProgressReporter::disabled()).await?;
+
+		// The first requests are retried once the Retry-After pause has passed; the
+		// requests that had not started are left for the next run
+		let arrivals = mock.arrivals();
+		assert_eq!(arrivals.len(), 2 * MAX_CONCURRENT_REQUESTS);
+		let (first, retries) = arrivals.split_at(MAX_CONCURRENT_REQUESTS);
+		assert!(retries[0] - first[MAX_CONCURRENT_REQUESTS - 1] >= Duration::from_millis(900));
+		let rate_limited: i64 = client.pool.get()?.query_row(
+			"SELECT COUNT(*) FROM enrichment_attempts WHERE outcome = 'rate_limited'",
+			[],
+			|row| row.get(0),
+		)?;
+		assert_eq!(rate_limited, MAX_CONCURRENT_REQUESTS as i64);
+		Ok(())
+	}
+
+	#[test]
+	fn test_backoff_grows_and_is_capped() {
+		let policy = RetryPolicy {
+			max_attempts: 5,
+			base_delay: Duration::from_secs(2),
+			max_delay: Duration::from_secs(5),
+		};
+
+		let first = policy.backoff(1);
+		assert!(first >= Duration::from_secs(1) && first <= Duration::from_secs(2));
+		let second = policy.backoff(2);
+		assert!(second >= Duration::from_secs(2) && second <= Duration::from_secs(4));
+		assert!(policy.backoff(10) <= Duration::from_secs(5));
+	}
+
+	#[test]
+	fn test_retry_after_header() {
+		let mut headers = HeaderMap::new();
+		assert_eq!(retry_after(&headers), None);
+		headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+		assert_eq!(retry_after(&headers), Some(Duration::from_secs(30)));
+		headers.insert(RETRY_AFTER, HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+		assert_eq!(retry_after(&headers), None);
+	}
+}
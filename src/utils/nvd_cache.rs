@@ -0,0 +1,116 @@
+// src/utils/nvd_cache.rs
+
+//! On-disk cache of the NVD API's answers for single CVEs, so that enrichment run again
+//! after a crash does not fetch thousands of unchanged records a second time. An answer
+//! is stored as `database/nvd_cache/<CVE>/<lastModified>.json`; a newer revision of a
+//! CVE replaces the older one, and answers older than the TTL are fetched again.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use log::debug;
+use std::fs;
+use std::path::PathBuf;
+
+/// Hours an answer is reused when no TTL is configured
+pub const DEFAULT_TTL_HOURS: u32 = 24;
+const TTL_ENV: &str = "RVD_NVD_CACHE_TTL_HOURS";
+
+#[derive(Debug, Clone)]
+pub struct NvdCache {
+	root: PathBuf,
+	ttl: Duration,
+}
+
+impl NvdCache {
+	pub fn new(root: PathBuf, ttl: Duration) -> Self {
+		Self { root, ttl }
+	}
+
+	/// Cache under `database/nvd_cache`, keeping answers for `RVD_NVD_CACHE_TTL_HOURS`,
+	/// else `DEFAULT_TTL_HOURS`; a TTL of 0 turns the cache off
+	pub fn from_env() -> Self {
+		let hours = std::env::var(TTL_ENV).ok().and_then(|v| v.trim().parse::<u32>().ok()).unwrap_or(DEFAULT_TTL_HOURS);
+		Self::new(PathBuf::from("database").join("nvd_cache"), Duration::hours(hours.into()))
+	}
+
+	/// The stored answer for `cve_id`, if one was stored less than the TTL before `now`
+	pub fn get(&self, cve_id: &str, now: DateTime<Utc>) -> Option<String> {
+		if self.ttl <= Duration::zero() {
+			return None;
+		}
+		let dir = self.root.join(file_name(cve_id));
+		let (path, stored) = fs::read_dir(&dir).ok()?
+			.filter_map(|entry| {
+				let entry = entry.ok()?;
+				let stored: DateTime<Utc> = entry.metadata().ok()?.modified().ok()?.into();
+				Some((entry.path(), stored))
+			})
+			.filter(|(path, _)| path.extension().is_some_and(|ext| ext == "json"))
+			.max_by_key(|(_, stored)| *stored)?;
+		if now - stored >= self.ttl {
+			debug!("Cached NVD answer for {} has expired", cve_id);
+			return None;
+		}
+		fs::read_to_string(path).ok()
+	}
+
+	/// Stores the answer for revision `last_modified` of `cve_id`, replacing the answers
+	/// stored for other revisions
+	pub fn put(&self, cve_id: &str, last_modified: &str, body: &str) -> Result<()> {
+		let dir = self.root.join(file_name(cve_id));
+		fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+		let path = dir.join(format!("{}.json", file_name(last_modified)));
+		// Written aside and renamed, so a crash never leaves half an answer behind
+		let partial = path.with_extension("part");
+		fs::write(&partial, body).with_context(|| format!("Failed to write {:?}", partial))?;
+		fs::rename(&partial, &path).with_context(|| format!("Failed to write {:?}", path))?;
+		for entry in fs::read_dir(&dir).with_context(|| format!("Failed to list {:?}", dir))? {
+			let stale = entry?.path();
+			if stale != path {
+				fs::remove_file(&stale).with_context(|| format!("Failed to remove {:?}", stale))?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// `value` with everything but letters, digits, dashes and dots replaced, e.g. the
+/// colons of a timestamp, which some file systems do not allow
+fn file_name(value: &str) -> String {
+	let name: String = value
+		.chars()
+		.map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+		.collect();
+	// Never `.` or `..`, which would leave the cache directory
+	name.trim_start_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_cache() -> Result<()> {
+		let dir = tempdir()?;
+		let cache = NvdCache::new(dir.path().to_path_buf(), Duration::hours(1));
+		let now = Utc::now();
+		assert_eq!(cache.get("CVE-2024-0001", now), None);
+
+		cache.put("CVE-2024-0001", "2024-01-02T03:04:05.123", "first")?;
+		assert_eq!(cache.get("CVE-2024-0001", now).as_deref(), Some("first"));
+		assert!(dir.path().join("CVE-2024-0001").join("2024-01-02T03_04_05.123.json").is_file());
+
+		// A newer revision replaces the older one
+		cache.put("CVE-2024-0001", "2024-02-01T00:00:00.000", "second")?;
+		assert_eq!(cache.get("CVE-2024-0001", now).as_deref(), Some("second"));
+		assert_eq!(fs::read_dir(dir.path().join("CVE-2024-0001"))?.count(), 1);
+
+		assert_eq!(cache.get("CVE-2024-0001", now + Duration::hours(2)), None);
+		assert_eq!(cache.get("CVE-2024-0002", now), None);
+
+		let disabled = NvdCache::new(dir.path().to_path_buf(), Duration::zero());
+		assert_eq!(disabled.get("CVE-2024-0001", now), None);
+		Ok(())
+	}
+}
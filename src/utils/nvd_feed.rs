@@ -0,0 +1,422 @@
+// src/utils/nvd_feed.rs
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use flate2::read::GzDecoder;
+use log::{info, warn};
+use serde::Deserialize;
+use tokio::task;
+use crate::db::connection::SqlitePool;
+use crate::models::reference::Reference;
+use crate::models::vulnerability::{CvssVersion, ProvenanceSource};
+use crate::models::weakness::normalize_cwe_id;
+use crate::repositories::{alias_repo, audit_repo, provenance_repo};
+use crate::repositories::reference_repo::insert_references;
+use crate::repositories::weakness_repo::insert_weaknesses;
+use crate::utils::nvd_metrics::NvdMetrics;
+use crate::utils::progress::ProgressReporter;
+
+/// Top-level shape shared by the nvdcve-2.0 year feeds and saved CVE API 2.0 response pages
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FeedDocument {
+	/// Matches of an API query across all its pages
+	#[serde(default)]
+	total_results: usize,
+	vulnerabilities: Vec<FeedItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedItem {
+	cve: FeedCve,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FeedCve {
+	id: String,
+	published: Option<String>,
+	#[serde(default)]
+	descriptions: Vec<FeedDescription>,
+	#[serde(default)]
+	metrics: NvdMetrics,
+	#[serde(default)]
+	references: Vec<FeedReference>,
+	#[serde(default)]
+	weaknesses: Vec<FeedWeakness>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedWeakness {
+	#[serde(default)]
+	description: Vec<FeedDescription>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedReference {
+	url: String,
+	source: Option<String>,
+	#[serde(default)]
+	tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeedDescription {
+	lang: String,
+	value: String,
+}
+
+/// A feed entry reduced to the fields RVD stores
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedRecord {
+	pub cve_id: String,
+	pub description: Option<String>,
+	pub severity: String,
+	pub cvss_score: Option<f64>,
+	pub cvss_version: Option<CvssVersion>,
+	pub published_date: Option<NaiveDate>,
+	pub references: Vec<Reference>,
+	/// CWE IDs such as `CWE-787`
+	pub weaknesses: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FeedImportSummary {
+	pub files: usize,
+	pub records: usize,
+	pub inserted: usize,
+}
+
+impl From<FeedCve> for FeedRecord {
+	fn from(cve: FeedCve) -> Self {
+		let metric = cve.metrics.preferred();
+		let severity = metric
+			.as_ref()
+			.and_then(|m| m.severity.as_deref())
+			.map(title_case)
+			.unwrap_or_else(|| "Unknown".to_string());
+
+		FeedRecord {
+			description: cve.descriptions
+				.into_iter()
+				.find(|d| d.lang == "en")
+				.map(|d| d.value.trim().to_string())
+				.filter(|d| !d.is_empty()),
+			severity,
+			cvss_score: metric.as_ref().map(|m| m.score),
+			cvss_version: metric.map(|m| m.version),
+			published_date: cve.published
+				.as_deref()
+				.and_then(|p| p.get(..10))
+				.and_then(|p| NaiveDate::parse_from_str(p, "%Y-%m-%d").ok()),
+			references: cve.references
+				.into_iter()
+				.map(|r| Reference::new(r.url, r.source).with_tags(r.tags))
+				.collect(),
+			weaknesses: cve.weaknesses
+				.iter()
+				.flat_map(|w| &w.description)
+				.filter_map(|d| normalize_cwe_id(&d.value))
+				.collect(),
+			cve_id: cve.id,
+		}
+	}
+}
+
+pub(crate) fn title_case(s: &str) -> String {
+	let lower = s.to_lowercase();
+	let mut chars = lower.chars();
+	match chars.next() {
+		Some(first) => first.to_uppercase().chain(chars).collect(),
+		None => String::new(),
+	}
+}
+
+/// Parses one feed file; `.gz` files are decompressed on the fly
+pub fn read_feed_file(path: &Path) -> Result<Vec<FeedRecord>> {
+	let file = File::open(path).with_context(|| format!("Failed to open feed {:?}", path))?;
+	let reader: Box<dyn Read> = if path.extension().is_some_and(|ext| ext == "gz") {
+		Box::new(GzDecoder::new(BufReader::new(file)))
+	} else {
+		Box::new(BufReader::new(file))
+	};
+
+	let document: FeedDocument = serde_json::from_reader(reader)
+		.with_context(|| format!("Failed to parse NVD JSON feed {:?}", path))?;
+
+	Ok(document.vulnerabilities.into_iter().map(|item| item.cve.into()).collect())
+}
+
+/// Parses one CVE API 2.0 response page into its records and the total number of
+/// matches of the query
+pub(crate) fn parse_api_page(body: &str) -> Result<(Vec<FeedRecord>, usize)> {
+	let document: FeedDocument = serde_json::from_str(body).context("Failed to parse NVD API response")?;
+	let total = document.total_results;
+	Ok((document.vulnerabilities.into_iter().map(|item| item.cve.into()).collect(), total))
+}
+
+/// Inserts the records not stored yet, under their CVE ID or an alias, leaving known
+/// entries untouched. Returns the IDs inserted.
+pub(crate) fn insert_new_records(pool: &Arc<SqlitePool>, records: Vec<FeedRecord>) -> Result<Vec<String>> {
+	let new_records: Vec<FeedRecord> = {
+		let conn = pool.get().context("Failed to get a connection from the pool")?;
+		let mut new_records = Vec::new();
+		for record in records {
+			if alias_repo::resolve(&conn, &record.cve_id)?.is_none() {
+				new_records.push(record);
+			}
+		}
+		new_records
+	};
+	upsert_records(pool, &new_records, "NVD keyword discovery")?;
+	Ok(new_records.into_iter().map(|record| record.cve_id).collect())
+}
+
+/// Expands directories into the `.json` / `.json.gz` files they contain, in name order
+fn collect_feed_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+	let mut files = Vec::new();
+	for path in paths {
+		if path.is_dir() {
+			let mut entries = std::fs::read_dir(path)
+				.with_context(|| format!("Failed to read directory {:?}", path))?
+				.filter_map(|entry| entry.ok().map(|e| e.path()))
+				.filter(|p| {
+					let name = p.to_string_lossy();
+					name.ends_with(".json") || name.ends_with(".json.gz")
+				})
+				.collect::<Vec<_>>();
+			entries.sort();
+			files.extend(entries);
+		} else {
+			files.push(path.clone());
+		}
+	}
+	Ok(files)
+}
+
+/// Imports NVD JSON feeds from local files or directories.
+///
+/// New CVEs are inserted; for known ones only empty or unknown fields are filled in,
+/// so curated descriptions and severities are never overwritten. Each file is committed
+/// on its own, and cancelling `progress` stops the import before the next file.
+pub async fn import_nvd_feeds(
+	paths: Vec<PathBuf>,
+	pool: Arc<SqlitePool>,
+	progress: ProgressReporter,
+) -> Result<FeedImportSummary> {
+	task::spawn_blocking(move || -> Result<FeedImportSummary> {
+		let mut summary = FeedImportSummary::default();
+		let files = collect_feed_files(&paths)?;
+		let tracker = progress.start("NVD feed import");
+
+		for (index, path) in files.iter().enumerate() {
+			tracker.check_cancelled()?;
+			tracker.update(summary.records, index as f32 / files.len() as f32);
+
+			let records = match read_feed_file(path) {
+				Ok(records) => records,
+				Err(e) => {
+					warn!("Skipping feed {:?}: {:#}", path, e);
+					continue;
+				}
+			};
+
+			let inserted = upsert_records(&pool, &records, &format!("NVD feed {}", path.display()))?;
+			info!("Imported {:?}: {} records, {} new", path, records.len(), inserted);
+
+			summary.files += 1;
+			summary.records += records.len();
+			summary.inserted += inserted;
+		}
+
+		tracker.finish(summary.records);
+		Ok(summary)
+	})
+		.await
+		.context("Failed to run feed import task")?
+}
+
+/// Writes one feed in a single transaction, recorded in the audit log as an import
+/// from `source`, and returns how many CVEs were new
+fn upsert_records(pool: &Arc<SqlitePool>, records: &[FeedRecord], source: &str) -> Result<usize> {
+	let mut connection = pool.get().context("Failed to get a connection from the pool")?;
+	let transaction = connection.transaction().context("Failed to start database transaction")?;
+
+	let count = |tx: &rusqlite::Transaction| -> rusqlite::Result<i64> {
+		tx.query_row("SELECT COUNT(*) FROM vulnerabilities", [], |row| row.get(0))
+	};
+	let before = count(&transaction)?;
+
+	{
+		let mut stmt = transaction.prepare(
+			"INSERT INTO vulnerabilities (cve_id, description, severity, published_date, cvss_score, cvss_version)
+			 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+			 ON CONFLICT(cve_id) DO UPDATE SET
+				description = COALESCE(NULLIF(vulnerabilities.description, ''), excluded.description),
+				severity = CASE WHEN UPPER(vulnerabilities.severity) = 'UNKNOWN'
+					THEN excluded.severity ELSE vulnerabilities.severity END,
+				published_date = COALESCE(vulnerabilities.published_date, excluded.published_date),
+				cvss_score = COALESCE(vulnerabilities.cvss_score, excluded.cvss_score),
+				cvss_version = CASE WHEN vulnerabilities.cvss_score IS NULL
+					THEN excluded.cvss_version ELSE vulnerabilities.cvss_version END",
+		)?;
+
+		for record in records {
+			let before = provenance_repo::snapshot(&transaction, &record.cve_id)?;
+			stmt.execute(rusqlite::params![
+				record.cve_id,
+				record.description,
+				record.severity,
+				record.published_date.map(|d| d.to_string()),
+				record.cvss_score,
+				record.cvss_version.map(|v| v.as_str()),
+			]).with_context(|| format!("Failed to import {}", record.cve_id))?;
+			provenance_repo::record_changes(&transaction, &record.cve_id, before, ProvenanceSource::Nvd)?;
+			insert_references(&transaction, &record.cve_id, &record.references)
+				.with_context(|| format!("Failed to import references of {}", record.cve_id))?;
+			insert_weaknesses(&transaction, &record.cve_id, &record.weaknesses)
+				.with_context(|| format!("Failed to import weaknesses of {}", record.cve_id))?;
+		}
+	}
+
+	let inserted = (count(&transaction)? - before) as usize;
+	if !records.is_empty() {
+		audit_repo::record_import(&transaction, source, &format!("{} records, {} new", records.len(), inserted))?;
+	}
+	transaction.commit().context("Failed to commit transaction")?;
+	Ok(inserted)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use flate2::{write::GzEncoder, Compression};
+	use std::io::Write;
+	use tempfile::tempdir;
+
+	const FEED: &str = r#"{
+		"resultsPerPage": 2,
+		"totalResults": 2,
+		"format": "NVD_CVE",
+		"version": "2.0",
+		"vulnerabilities": [
+			{
+				"cve": {
+					"id": "CVE-2024-0001",
+					"published": "2024-01-02T10:15:08.000",
+					"descriptions": [
+						{ "lang": "es", "value": "Desbordamiento" },
+						{ "lang": "en", "value": "Buffer overflow in ROS bridge." }
+					],
+					"metrics": {
+						"cvssMetricV31": [{ "cvssData": { "baseScore": 9.8, "baseSeverity": "CRITICAL" } }],
+						"cvssMetricV2": [{ "cvssData": { "baseScore": 7.5 }, "baseSeverity": "HIGH" }]
+					},
+					"references": [
+						{ "url": "https://github.com/advisories/GHSA-abcd-1234-wxyz", "source": "security-advisories@github.com", "tags": ["Patch", "Third Party Advisory"] }
+					],
+					"weaknesses": [
+						{ "source": "nvd@nist.gov", "type": "Primary", "description": [{ "lang": "en", "value": "CWE-787" }] },
+						{ "source": "cna@example.com", "type": "Secondary", "description": [{ "lang": "en", "value": "NVD-CWE-Other" }] }
+					]
+				}
+			},
+			{
+				"cve": {
+					"id": "CVE-2024-0002",
+					"published": "2024-02-03T00:00:00.000",
+					"descriptions": [{ "lang": "en", "value": "Old advisory." }],
+					"metrics": {
+						"cvssMetricV2": [{ "cvssData": { "baseScore": 4.3 }, "baseSeverity": "MEDIUM" }]
+					}
+				}
+			}
+		]
+	}"#;
+
+	#[test]
+	fn test_feed_record_mapping() -> Result<()> {
+		let document: FeedDocument = serde_json::from_str(FEED)?;
+		let records: Vec<FeedRecord> = document.vulnerabilities.into_iter().map(|i| i.cve.into()).collect();
+
+		assert_eq!(records[0].description.as_deref(), Some("Buffer overflow in ROS bridge."));
+		assert_eq!(records[0].severity, "Critical");
+		assert_eq!(records[0].cvss_score, Some(9.8));
+		assert_eq!(records[0].cvss_version, Some(CvssVersion::V31));
+		assert_eq!(records[0].published_date, NaiveDate::from_ymd_opt(2024, 1, 2));
+		assert_eq!(records[0].references[0].advisory_id.as_deref(), Some("GHSA-abcd-1234-wxyz"));
+		assert_eq!(records[0].references[0].tags, ["Patch", "Third Party Advisory"]);
+		assert!(records[1].references.is_empty());
+		assert_eq!(records[0].weaknesses, ["CWE-787"]);
+		assert_eq!(records[1].severity, "Medium");
+		assert_eq!(records[1].cvss_score, Some(4.3));
+		assert_eq!(records[1].cvss_version, Some(CvssVersion::V2));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_import_gzipped_feed_keeps_curated_fields() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute(
+			"INSERT INTO vulnerabilities (cve_id, description, severity) VALUES ('CVE-2024-0002', 'Curated text', 'UNKNOWN')",
+			[],
+		)?;
+
+		let feeds = dir.path().join("feeds");
+		std::fs::create_dir(&feeds)?;
+		let mut encoder = GzEncoder::new(File::create(feeds.join("nvdcve-2.0-2024.json.gz"))?, Compression::default());
+		encoder.write_all(FEED.as_bytes())?;
+		encoder.finish()?;
+
+		let summary = import_nvd_feeds(vec![feeds], pool.clone(), ProgressReporter::disabled()).await?;
+		assert_eq!(summary.files, 1);
+		assert_eq!(summary.records, 2);
+		assert_eq!(summary.inserted, 1);
+
+		let (description, severity, score, version): (String, String, f64, String) = pool.get()?.query_row(
+			"SELECT description, severity, cvss_score, cvss_version FROM vulnerabilities WHERE cve_id = 'CVE-2024-0002'",
+			[],
+			|row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+		)?;
+		assert_eq!(description, "Curated text");
+		assert_eq!(severity, "Medium");
+		assert_eq!((score, version.as_str()), (4.3, "2.0"));
+
+		let cwe_id: String = pool.get()?.query_row(
+			"SELECT w.cwe_id FROM vulnerability_weaknesses w
+			 JOIN vulnerabilities v ON v.vulnerability_id = w.vulnerability_id
+			 WHERE v.cve_id = 'CVE-2024-0001'",
+			[],
+			|row| row.get(0),
+		)?;
+		assert_eq!(cwe_id, "CWE-787");
+		Ok(())
+	}
+
+	#[test]
+	fn test_insert_new_records_skips_known() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute(
+			"INSERT INTO vulnerabilities (cve_id, description, severity) VALUES ('CVE-2024-0002', 'Curated text', 'Unknown')",
+			[],
+		)?;
+
+		let (records, total) = parse_api_page(FEED)?;
+		assert_eq!((records.len(), total), (2, 2));
+		assert_eq!(insert_new_records(&pool, records)?, ["CVE-2024-0001"]);
+		let severity: String = pool.get()?.query_row(
+			"SELECT severity FROM vulnerabilities WHERE cve_id = 'CVE-2024-0002'",
+			[],
+			|row| row.get(0),
+		)?;
+		assert_eq!(severity, "Unknown");
+		Ok(())
+	}
+}
@@ -0,0 +1,101 @@
+// src/utils/nvd_metrics.rs
+
+//! The `metrics` object of a CVE in the NVD CVE API 2.0 and its JSON feeds. Scores are
+//! grouped by CVSS version, each entry keeping its numbers in a nested `cvssData`.
+
+use serde::Deserialize;
+use crate::models::vulnerability::CvssVersion;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NvdMetrics {
+	#[serde(default)]
+	cvss_metric_v40: Vec<NvdCvssMetric>,
+	#[serde(default)]
+	cvss_metric_v31: Vec<NvdCvssMetric>,
+	#[serde(default)]
+	cvss_metric_v30: Vec<NvdCvssMetric>,
+	#[serde(default)]
+	cvss_metric_v2: Vec<NvdCvssMetric>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NvdCvssMetric {
+	/// `Primary` for the NVD's own assessment, `Secondary` for a CNA's
+	#[serde(rename = "type")]
+	kind: Option<String>,
+	cvss_data: NvdCvssData,
+	/// CVSS v2 keeps the severity next to the data instead of inside it
+	base_severity: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NvdCvssData {
+	base_score: f64,
+	base_severity: Option<String>,
+}
+
+/// The base score RVD keeps for a CVE
+#[derive(Debug, Clone, PartialEq)]
+pub struct CvssScore {
+	pub version: CvssVersion,
+	pub score: f64,
+	/// Qualitative rating as the NVD spells it, e.g. `CRITICAL`
+	pub severity: Option<String>,
+}
+
+impl NvdMetrics {
+	/// Score of the newest CVSS version present, the NVD's own assessment before a CNA's
+	pub fn preferred(&self) -> Option<CvssScore> {
+		[
+			(CvssVersion::V40, &self.cvss_metric_v40),
+			(CvssVersion::V31, &self.cvss_metric_v31),
+			(CvssVersion::V30, &self.cvss_metric_v30),
+			(CvssVersion::V2, &self.cvss_metric_v2),
+		]
+			.into_iter()
+			.find_map(|(version, metrics)| {
+				let metric = metrics
+					.iter()
+					.find(|m| m.kind.as_deref() == Some("Primary"))
+					.or_else(|| metrics.first())?;
+				Some(CvssScore {
+					version,
+					score: metric.cvss_data.base_score,
+					severity: metric.cvss_data.base_severity.clone().or_else(|| metric.base_severity.clone()),
+				})
+			})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_preferred_score() {
+		let metrics: NvdMetrics = serde_json::from_str(r#"{
+			"cvssMetricV2": [{ "type": "Primary", "cvssData": { "baseScore": 7.5 }, "baseSeverity": "HIGH" }],
+			"cvssMetricV31": [
+				{ "type": "Secondary", "cvssData": { "baseScore": 8.1, "baseSeverity": "HIGH" } },
+				{ "type": "Primary", "cvssData": { "baseScore": 9.8, "baseSeverity": "CRITICAL" } }
+			]
+		}"#).unwrap();
+		let score = metrics.preferred().unwrap();
+		assert_eq!((score.version, score.score, score.severity.as_deref()), (CvssVersion::V31, 9.8, Some("CRITICAL")));
+
+		let metrics: NvdMetrics = serde_json::from_str(r#"{
+			"cvssMetricV40": [{ "type": "Secondary", "cvssData": { "baseScore": 9.3, "baseSeverity": "CRITICAL" } }],
+			"cvssMetricV31": [{ "type": "Primary", "cvssData": { "baseScore": 9.8, "baseSeverity": "CRITICAL" } }]
+		}"#).unwrap();
+		assert_eq!(metrics.preferred().map(|s| (s.version, s.score)), Some((CvssVersion::V40, 9.3)));
+
+		let metrics: NvdMetrics = serde_json::from_str(r#"{
+			"cvssMetricV2": [{ "cvssData": { "baseScore": 4.3 }, "baseSeverity": "MEDIUM" }]
+		}"#).unwrap();
+		assert_eq!(metrics.preferred().and_then(|s| s.severity), Some("MEDIUM".to_string()));
+		assert_eq!(NvdMetrics::default().preferred(), None);
+	}
+}
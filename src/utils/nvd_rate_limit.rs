@@ -0,0 +1,190 @@
+// src/utils/nvd_rate_limit.rs
+
+//! One request quota for every NVD API job in the process.
+//!
+//! The NVD allows clients without an API key 5 requests in any 30 seconds, and those
+//! with a key 50, and bans clients that keep exceeding it. Enrichment runs, keyword discovery and requests made
+//! from the GUI each go through [`NvdRateLimiter::acquire`] with a [`RequestPriority`];
+//! a waiting request is only sent once no request of a higher priority is waiting, so
+//! a background job cannot starve a "fetch now" from the GUI.
+
+use crate::utils::nvd_api::api_key;
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::{sleep_until, Instant};
+
+/// Overrides the number of requests allowed per window
+const RATE_LIMIT_ENV: &str = "RVD_NVD_REQUESTS_PER_30S";
+const DEFAULT_REQUESTS_PER_WINDOW: usize = 5;
+/// Requests per window allowed with an NVD API key
+const KEYED_REQUESTS_PER_WINDOW: usize = 50;
+const WINDOW: Duration = Duration::from_secs(30);
+
+static SHARED: OnceLock<NvdRateLimiter> = OnceLock::new();
+
+/// Who is waiting for a request, most urgent first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RequestPriority {
+	/// Asked for by someone looking at the GUI
+	Interactive,
+	/// Filling in missing fields of stored vulnerabilities
+	Enrichment,
+	/// Searching for new CVEs mentioning robotics terms
+	Discovery,
+}
+
+impl RequestPriority {
+	const ALL: [Self; 3] = [Self::Interactive, Self::Enrichment, Self::Discovery];
+
+	fn index(self) -> usize {
+		self as usize
+	}
+}
+
+#[derive(Debug, Default)]
+struct Quota {
+	/// When the requests of the current window were sent, oldest first
+	sent: VecDeque<Instant>,
+	/// Requests waiting per priority
+	waiting: [usize; RequestPriority::ALL.len()],
+	/// Set once the NVD answered with a rate limit; nothing is sent before then
+	paused_until: Option<Instant>,
+}
+
+pub struct NvdRateLimiter {
+	requests_per_window: usize,
+	window: Duration,
+	quota: Mutex<Quota>,
+	changed: Notify,
+}
+
+impl NvdRateLimiter {
+	pub fn new(requests_per_window: usize, window: Duration) -> Self {
+		Self {
+			requests_per_window: requests_per_window.max(1),
+			window,
+			quota: Mutex::new(Quota::default()),
+			changed: Notify::new(),
+		}
+	}
+
+	/// The limiter shared by all NVD clients of the process, allowing
+	/// `RVD_NVD_REQUESTS_PER_30S` requests per 30 seconds; by default 5, or 50 when an
+	/// API key is set in `NVD_API_KEY`
+	pub fn shared() -> &'static Self {
+		SHARED.get_or_init(|| {
+			let default = if api_key().is_some() { KEYED_REQUESTS_PER_WINDOW } else { DEFAULT_REQUESTS_PER_WINDOW };
+			let requests = std::env::var(RATE_LIMIT_ENV)
+				.ok()
+				.and_then(|v| v.parse::<usize>().ok())
+				.unwrap_or(default);
+			Self::new(requests, WINDOW)
+		})
+	}
+
+	fn quota(&self) -> MutexGuard<'_, Quota> {
+		self.quota.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	/// Waits until a request of the given priority may be sent and counts it against
+	/// the quota
+	pub async fn acquire(&self, priority: RequestPriority) {
+		let _waiting = Waiting::register(self, priority);
+		loop {
+			// Registered before checking, so a change made right after is not missed
+			let changed = self.changed.notified();
+			tokio::pin!(changed);
+			changed.as_mut().enable();
+
+			let wake_at = {
+				let mut quota = self.quota();
+				let now = Instant::now();
+				while quota.sent.front().is_some_and(|&sent| now.duration_since(sent) >= self.window) {
+					quota.sent.pop_front();
+				}
+				let paused = quota.paused_until.filter(|&until| until > now);
+				let full = quota.sent.len() >= self.requests_per_window;
+				let outranked = quota.waiting[..priority.index()].iter().any(|&waiting| waiting > 0);
+				if paused.is_none() && !full && !outranked {
+					quota.sent.push_back(now);
+					return;
+				}
+				let slot_free_at = quota.sent.front().filter(|_| full).map(|&sent| sent + self.window);
+				paused.max(slot_free_at)
+			};
+
+			match wake_at {
+				Some(wake_at) => tokio::select! {
+					_ = sleep_until(wake_at) => {}
+					_ = changed => {}
+				},
+				None => changed.await,
+			}
+		}
+	}
+
+	/// Holds back every request for `delay`, e.g. after the NVD answered with a rate
+	/// limit
+	pub fn pause(&self, delay: Duration) {
+		let until = Instant::now() + delay;
+		let mut quota = self.quota();
+		quota.paused_until = quota.paused_until.max(Some(until));
+	}
+}
+
+/// Counts a request as waiting until it is sent or given up
+struct Waiting<'a> {
+	limiter: &'a NvdRateLimiter,
+	priority: RequestPriority,
+}
+
+impl<'a> Waiting<'a> {
+	fn register(limiter: &'a NvdRateLimiter, priority: RequestPriority) -> Self {
+		limiter.quota().waiting[priority.index()] += 1;
+		Self { limiter, priority }
+	}
+}
+
+impl Drop for Waiting<'_> {
+	fn drop(&mut self) {
+		self.limiter.quota().waiting[self.priority.index()] -= 1;
+		// Lower priorities may go now
+		self.limiter.changed.notify_waiters();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Arc;
+
+	#[tokio::test]
+	async fn test_quota_and_priority() {
+		let window = Duration::from_millis(300);
+		let limiter = Arc::new(NvdRateLimiter::new(2, window));
+		let start = Instant::now();
+		limiter.acquire(RequestPriority::Enrichment).await;
+		limiter.acquire(RequestPriority::Enrichment).await;
+		assert!(start.elapsed() < window);
+
+		// The quota is used up: both wait for the window, the interactive request goes first
+		let background = tokio::spawn({
+			let limiter = limiter.clone();
+			async move {
+				limiter.acquire(RequestPriority::Discovery).await;
+				Instant::now()
+			}
+		});
+		tokio::task::yield_now().await;
+		limiter.acquire(RequestPriority::Interactive).await;
+		let interactive_at = Instant::now();
+		assert!(interactive_at - start >= window);
+		assert!(background.await.unwrap() >= interactive_at);
+
+		limiter.pause(window * 2);
+		limiter.acquire(RequestPriority::Interactive).await;
+		assert!(interactive_at.elapsed() >= window * 2);
+	}
+}
@@ -0,0 +1,134 @@
+// src/utils/offline_bundle.rs
+
+//! Signed offline bundle files for air-gapped deployments: a zip archive holding the
+//! bundle as JSON, deflated, and its HMAC-SHA256 under a key shared by the connected
+//! instance and the air-gapped ones. A bundle whose signature does not match is
+//! rejected before anything is read from it.
+
+use crate::db::connection::SqlitePool;
+use crate::models::offline_bundle::{BundleImportSummary, OfflineBundle};
+use crate::repositories::offline_bundle_repo::OfflineBundleRepository;
+use crate::utils::progress::ProgressReporter;
+use anyhow::{bail, Context, Result};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Environment variable holding the key bundles are signed and verified with
+pub const BUNDLE_KEY_ENV: &str = "RVD_BUNDLE_KEY";
+
+const BUNDLE_ENTRY: &str = "bundle.json";
+const SIGNATURE_ENTRY: &str = "bundle.sig";
+
+/// The signing key, which every instance exchanging bundles must share
+pub fn bundle_key() -> Result<Vec<u8>> {
+	std::env::var(BUNDLE_KEY_ENV)
+		.ok()
+		.map(|key| key.trim().as_bytes().to_vec())
+		.filter(|key| !key.is_empty())
+		.with_context(|| format!("Set {} to the key offline bundles are signed with", BUNDLE_KEY_ENV))
+}
+
+fn mac(key: &[u8], content: &[u8]) -> Hmac<Sha256> {
+	let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+	mac.update(content);
+	mac
+}
+
+/// Writes `bundle` signed with `key` to `path`
+pub fn write_bundle(bundle: &OfflineBundle, path: &Path, key: &[u8]) -> Result<()> {
+	let json = serde_json::to_vec(bundle).context("Failed to serialize offline bundle")?;
+	let signature = hex::encode(mac(key, &json).finalize().into_bytes());
+
+	let file = File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+	let mut zip = ZipWriter::new(file);
+	let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+	zip.start_file(BUNDLE_ENTRY, options)?;
+	zip.write_all(&json)?;
+	zip.start_file(SIGNATURE_ENTRY, options)?;
+	zip.write_all(signature.as_bytes())?;
+	zip.finish().with_context(|| format!("Failed to write {:?}", path))?;
+	Ok(())
+}
+
+/// Reads the bundle at `path` after checking it was signed with `key`
+pub fn read_bundle(path: &Path, key: &[u8]) -> Result<OfflineBundle> {
+	let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+	let mut archive = ZipArchive::new(file).with_context(|| format!("{:?} is not an offline bundle", path))?;
+	let mut entry = |name: &str| -> Result<Vec<u8>> {
+		let mut content = Vec::new();
+		archive
+			.by_name(name)
+			.with_context(|| format!("{:?} has no {}", path, name))?
+			.read_to_end(&mut content)
+			.with_context(|| format!("Failed to read {} from {:?}", name, path))?;
+		Ok(content)
+	};
+	let json = entry(BUNDLE_ENTRY)?;
+	let signature = entry(SIGNATURE_ENTRY)?;
+
+	let signature = hex::decode(signature.trim_ascii()).context("The bundle signature is malformed")?;
+	if mac(key, &json).verify_slice(&signature).is_err() {
+		bail!("The signature of {:?} does not match; it was altered or signed with another key", path);
+	}
+	let bundle: OfflineBundle = serde_json::from_slice(&json)
+		.with_context(|| format!("{:?} is not a valid offline bundle", path))?;
+	bundle.validate()?;
+	Ok(bundle)
+}
+
+/// Exports the database to a bundle at `path` signed with the key in `RVD_BUNDLE_KEY`.
+/// Returns how many vulnerabilities it holds.
+pub async fn export_bundle(pool: Arc<SqlitePool>, path: &Path, progress: ProgressReporter) -> Result<usize> {
+	let key = bundle_key()?;
+	let bundle = OfflineBundleRepository::new(pool).export(progress).await?;
+	write_bundle(&bundle, path, &key)?;
+	Ok(bundle.vulnerabilities.len())
+}
+
+/// Verifies the bundle at `path` with the key in `RVD_BUNDLE_KEY` and merges it
+pub async fn import_bundle(pool: Arc<SqlitePool>, path: &Path) -> Result<BundleImportSummary> {
+	let bundle = read_bundle(path, &bundle_key()?)?;
+	OfflineBundleRepository::new(pool).import(bundle).await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_signature_is_checked() -> Result<()> {
+		let dir = tempdir()?;
+		let path = dir.path().join("rvd.bundle");
+		let bundle = OfflineBundle::new("2024-06-01T00:00:00Z".to_string());
+		write_bundle(&bundle, &path, b"site key")?;
+
+		assert_eq!(read_bundle(&path, b"site key")?.exported_at, "2024-06-01T00:00:00Z");
+		assert!(read_bundle(&path, b"other key").is_err());
+
+		// Swap the content for another bundle's, keeping the signature
+		let tampered = dir.path().join("tampered.bundle");
+		let mut forged = bundle.clone();
+		forged.exported_at = "2030-01-01T00:00:00Z".to_string();
+		let signature = {
+			let mut archive = ZipArchive::new(File::open(&path)?)?;
+			let mut signature = Vec::new();
+			archive.by_name(SIGNATURE_ENTRY)?.read_to_end(&mut signature)?;
+			signature
+		};
+		let mut zip = ZipWriter::new(File::create(&tampered)?);
+		zip.start_file(BUNDLE_ENTRY, SimpleFileOptions::default())?;
+		zip.write_all(&serde_json::to_vec(&forged)?)?;
+		zip.start_file(SIGNATURE_ENTRY, SimpleFileOptions::default())?;
+		zip.write_all(&signature)?;
+		zip.finish()?;
+		assert!(read_bundle(&tampered, b"site key").is_err());
+		Ok(())
+	}
+}
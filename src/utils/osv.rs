@@ -0,0 +1,352 @@
+// src/utils/osv.rs
+
+//! Import from the OSV database (https://osv.dev). Like the GitHub advisory import,
+//! vulnerabilities are looked up for the packages installed on robots, in the PyPI,
+//! crates.io, Go and npm ecosystems, and stored under their CVE alias when they have one.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use log::{debug, info, warn};
+use reqwest::header::USER_AGENT;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::task;
+use crate::db::connection::SqlitePool;
+use crate::models::reference::Reference;
+use crate::models::weakness::normalize_cwe_id;
+use crate::repositories::{access, audit_repo};
+use crate::utils::ghsa::{installed_packages, store_records, AffectedPackage, GhsaImportSummary, GhsaRecord};
+use crate::utils::http;
+use crate::utils::nvd_feed::title_case;
+use crate::utils::progress::ProgressReporter;
+
+/// Value of `vulnerabilities.source` for imported entries
+pub const OSV_SOURCE: &str = "OSV";
+/// Ecosystems queried, named the way OSV does
+const ECOSYSTEMS: [&str; 4] = ["PyPI", "crates.io", "Go", "npm"];
+/// Queries per `querybatch` request, well below the API's limit of 1000
+const BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct BatchResponse {
+	#[serde(default)]
+	results: Vec<BatchResult>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct BatchResult {
+	#[serde(default)]
+	vulns: Vec<VulnId>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VulnId {
+	id: String,
+}
+
+/// An OSV entry, reduced to the fields RVD stores
+#[derive(Debug, Deserialize)]
+pub struct OsvVulnerability {
+	id: String,
+	#[serde(default)]
+	summary: Option<String>,
+	#[serde(default)]
+	details: Option<String>,
+	#[serde(default)]
+	aliases: Vec<String>,
+	#[serde(default)]
+	published: Option<String>,
+	#[serde(default)]
+	withdrawn: Option<String>,
+	#[serde(default)]
+	affected: Vec<OsvAffected>,
+	#[serde(default)]
+	references: Vec<OsvReference>,
+	#[serde(default)]
+	database_specific: Option<OsvDatabaseSpecific>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvAffected {
+	package: OsvPackage,
+	#[serde(default)]
+	ranges: Vec<OsvRange>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvPackage {
+	name: String,
+	ecosystem: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvRange {
+	#[serde(rename = "type")]
+	kind: String,
+	#[serde(default)]
+	events: Vec<OsvEvent>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct OsvEvent {
+	introduced: Option<String>,
+	fixed: Option<String>,
+	last_affected: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvReference {
+	url: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct OsvDatabaseSpecific {
+	severity: Option<String>,
+	#[serde(default)]
+	cwe_ids: Vec<String>,
+}
+
+/// Version ranges of an affected package in the `>= a, < b` form of `version_match`.
+/// Git commit ranges cannot be compared with installed versions and are skipped.
+fn package_ranges(affected: &OsvAffected) -> Vec<AffectedPackage> {
+	let mut packages = Vec::new();
+	for range in affected.ranges.iter().filter(|r| r.kind != "GIT") {
+		let mut introduced: Option<&str> = None;
+		let mut open = false;
+		for event in &range.events {
+			if let Some(version) = &event.introduced {
+				introduced = Some(version).filter(|v| *v != "0").map(String::as_str);
+				open = true;
+				continue;
+			}
+			let upper = match (&event.fixed, &event.last_affected) {
+				(Some(fixed), _) => format!("< {}", fixed),
+				(None, Some(last)) => format!("<= {}", last),
+				(None, None) => continue,
+			};
+			packages.push(AffectedPackage {
+				name: affected.package.name.clone(),
+				range: match introduced {
+					Some(lower) => format!(">= {}, {}", lower, upper),
+					None => upper,
+				},
+				fixed_in: event.fixed.clone(),
+			});
+			open = false;
+		}
+		if open {
+			packages.push(AffectedPackage {
+				name: affected.package.name.clone(),
+				range: introduced.map(|lower| format!(">= {}", lower)).unwrap_or_else(|| "*".to_string()),
+				fixed_in: None,
+			});
+		}
+	}
+	packages
+}
+
+/// The entry in the form shared with the GitHub advisory import, `None` when withdrawn
+pub fn record_from(vulnerability: &OsvVulnerability) -> Option<GhsaRecord> {
+	if vulnerability.withdrawn.is_some() {
+		return None;
+	}
+	let specific = vulnerability.database_specific.as_ref();
+	let severity = match specific.and_then(|s| s.severity.as_deref()).map(str::to_uppercase).as_deref() {
+		Some("MODERATE") => "Medium".to_string(),
+		Some(other) => title_case(other),
+		None => "Unknown".to_string(),
+	};
+
+	let page = format!("https://osv.dev/vulnerability/{}", vulnerability.id);
+	let mut references = vec![Reference::new(page.clone(), Some(OSV_SOURCE.to_string()))];
+	references.extend(
+		vulnerability.references
+			.iter()
+			.filter(|r| r.url != page)
+			.map(|r| Reference::new(r.url.clone(), None)),
+	);
+
+	let mut packages: Vec<AffectedPackage> = Vec::new();
+	for affected in vulnerability.affected
+		.iter()
+		.filter(|a| ECOSYSTEMS.iter().any(|e| e.eq_ignore_ascii_case(&a.package.ecosystem)))
+	{
+		for package in package_ranges(affected) {
+			if !packages.contains(&package) {
+				packages.push(package);
+			}
+		}
+	}
+
+	Some(GhsaRecord {
+		ghsa_id: vulnerability.id.clone(),
+		cve_id: std::iter::once(&vulnerability.id)
+			.chain(&vulnerability.aliases)
+			.find(|alias| alias.to_uppercase().starts_with("CVE-"))
+			.map(|alias| alias.trim().to_uppercase()),
+		description: [&vulnerability.details, &vulnerability.summary]
+			.into_iter()
+			.filter_map(|text| text.as_deref().map(str::trim))
+			.find(|text| !text.is_empty())
+			.map(str::to_string),
+		severity,
+		cvss_score: None,
+		cvss_version: None,
+		published_date: vulnerability.published
+			.as_deref()
+			.and_then(|date| date.get(..10))
+			.and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()),
+		references,
+		weaknesses: specific
+			.iter()
+			.flat_map(|s| &s.cwe_ids)
+			.filter_map(|cwe| normalize_cwe_id(cwe))
+			.collect(),
+		packages,
+	})
+}
+
+struct OsvClient {
+	client: reqwest::Client,
+	base_url: String,
+}
+
+impl OsvClient {
+	fn new(base_url: &str) -> Result<Self> {
+		let client = http::client_builder()?.build().context("Failed to create HTTP client")?;
+		Ok(Self { client, base_url: base_url.trim_end_matches('/').to_string() })
+	}
+
+	async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+		let response = request
+			.header(USER_AGENT, "Vulnerability-Management-System/1.0")
+			.send()
+			.await
+			.context("OSV API unreachable")?;
+		let status = response.status();
+		if !status.is_success() {
+			bail!("OSV API request failed with status: {}", status);
+		}
+		Ok(response)
+	}
+
+	/// IDs of the entries affecting any of the packages, in any queried ecosystem
+	async fn query(&self, packages: &[String]) -> Result<BTreeSet<String>> {
+		let queries: Vec<_> = packages
+			.iter()
+			.flat_map(|name| ECOSYSTEMS.iter().map(move |ecosystem| json!({ "package": { "name": name, "ecosystem": ecosystem } })))
+			.collect();
+		let mut ids = BTreeSet::new();
+		for chunk in queries.chunks(BATCH_SIZE) {
+			let response: BatchResponse = self
+				.send(self.client.post(format!("{}/v1/querybatch", self.base_url)).json(&json!({ "queries": chunk })))
+				.await?
+				.json()
+				.await
+				.context("Failed to parse OSV query response")?;
+			ids.extend(response.results.into_iter().flat_map(|r| r.vulns).map(|v| v.id));
+		}
+		Ok(ids)
+	}
+
+	async fn fetch(&self, id: &str) -> Result<OsvVulnerability> {
+		debug!("Fetching OSV entry {}", id);
+		self.send(self.client.get(format!("{}/v1/vulns/{}", self.base_url, id)))
+			.await?
+			.json()
+			.await
+			.with_context(|| format!("Failed to parse OSV entry {}", id))
+	}
+}
+
+/// Looks up the OSV entries of every installed package at `base_url`, e.g.
+/// https://api.osv.dev, and stores them, correlating the installed versions in the
+/// affected ranges. Entries that cannot be fetched are skipped and logged.
+pub async fn import_osv_vulnerabilities(
+	pool: Arc<SqlitePool>,
+	base_url: &str,
+	progress: ProgressReporter,
+) -> Result<GhsaImportSummary> {
+	access::require_write_access()?;
+	let client = OsvClient::new(base_url)?;
+	let packages = task::spawn_blocking({
+		let pool = pool.clone();
+		move || -> Result<Vec<String>> {
+			let conn = pool.get().context("Failed to get database connection")?;
+			installed_packages(&conn)
+		}
+	})
+		.await
+		.context("Failed to run OSV import task")??;
+
+	let tracker = progress.start("OSV import");
+	let ids = client.query(&packages).await?;
+	let mut records = Vec::new();
+	for (index, id) in ids.iter().enumerate() {
+		tracker.check_cancelled()?;
+		tracker.update(index, index as f32 / ids.len() as f32);
+		match client.fetch(id).await {
+			Ok(vulnerability) => records.extend(record_from(&vulnerability)),
+			Err(e) => warn!("Skipping {}: {:#}", id, e),
+		}
+	}
+	tracker.finish(ids.len());
+
+	let package_count = packages.len();
+	let summary = task::spawn_blocking(move || -> Result<GhsaImportSummary> {
+		let mut connection = pool.get().context("Failed to get database connection")?;
+		let transaction = connection.transaction().context("Failed to start database transaction")?;
+		let summary = GhsaImportSummary { packages: package_count, ..store_records(&transaction, &records, OSV_SOURCE)? };
+		audit_repo::record_import(&transaction, OSV_SOURCE, &summary.to_string())?;
+		transaction.commit().context("Failed to commit transaction")?;
+		Ok(summary)
+	})
+		.await
+		.context("Failed to run OSV import task")??;
+
+	info!("Imported OSV entries: {}", summary);
+	Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const ENTRY: &str = r#"{
+		"id": "GHSA-abcd-1234-wxyz",
+		"summary": "Command injection in ros_bridge",
+		"details": "",
+		"aliases": ["CVE-2024-1111"],
+		"published": "2024-03-01T12:00:00Z",
+		"affected": [
+			{
+				"package": { "name": "ros_bridge", "ecosystem": "PyPI" },
+				"ranges": [
+					{ "type": "ECOSYSTEM", "events": [{ "introduced": "1.0" }, { "fixed": "1.4.3" }, { "introduced": "2.0" }] },
+					{ "type": "GIT", "events": [{ "introduced": "0" }, { "fixed": "abc123" }] }
+				]
+			},
+			{
+				"package": { "name": "ros-bridge", "ecosystem": "Debian" },
+				"ranges": [{ "type": "ECOSYSTEM", "events": [{ "introduced": "0" }, { "fixed": "1.4.3-1" }] }]
+			}
+		],
+		"references": [{ "type": "WEB", "url": "https://github.com/ros/bridge/pull/7" }],
+		"database_specific": { "severity": "MODERATE", "cwe_ids": ["CWE-78"] }
+	}"#;
+
+	#[test]
+	fn test_record_from() {
+		let vulnerability: OsvVulnerability = serde_json::from_str(ENTRY).unwrap();
+		let record = record_from(&vulnerability).expect("not withdrawn");
+		assert_eq!(record.id(), "CVE-2024-1111");
+		assert_eq!(record.severity, "Medium");
+		assert_eq!(record.description.as_deref(), Some("Command injection in ros_bridge"));
+		assert_eq!(record.weaknesses, vec!["CWE-78".to_string()]);
+		assert_eq!(record.references[0].url, "https://osv.dev/vulnerability/GHSA-abcd-1234-wxyz");
+		let ranges: Vec<_> = record.packages.iter().map(|p| (p.range.as_str(), p.fixed_in.as_deref())).collect();
+		assert_eq!(ranges, vec![(">= 1.0, < 1.4.3", Some("1.4.3")), (">= 2.0", None)]);
+	}
+}
@@ -0,0 +1,121 @@
+// src/utils/product_match.rs
+
+//! Approximate matching of software names against known products, so a component
+//! that matches nothing exactly comes with "did you mean" suggestions for an analyst
+//! to confirm instead of quietly becoming another product.
+
+use serde::Serialize;
+
+/// Lowest combined similarity worth suggesting
+const MIN_SIMILARITY: f64 = 0.85;
+/// Share of the score taken by the product name; the vendor makes up the rest
+const NAME_WEIGHT: f64 = 0.8;
+
+/// A product already in the database
+#[derive(Debug, Clone)]
+pub struct KnownProduct {
+	pub product_id: i64,
+	pub product_name: String,
+	pub vendor: String,
+}
+
+/// An existing product that is probably what an unmatched name meant
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProductSuggestion {
+	pub product_id: i64,
+	pub product_name: String,
+	pub vendor: String,
+	/// Between 0.0 and 1.0
+	pub similarity: f64,
+}
+
+/// Similarity of two names, ignoring case and punctuation ("ros_humble" equals "ROS Humble")
+pub fn similarity(a: &str, b: &str) -> f64 {
+	strsim::jaro_winkler(&normalize(a), &normalize(b))
+}
+
+/// The `limit` known products closest to `product_name` by `vendor`, best first
+pub fn suggest(product_name: &str, vendor: &str, known: &[KnownProduct], limit: usize) -> Vec<ProductSuggestion> {
+	let mut suggestions: Vec<ProductSuggestion> = known
+		.iter()
+		.map(|product| ProductSuggestion {
+			product_id: product.product_id,
+			product_name: product.product_name.clone(),
+			vendor: product.vendor.clone(),
+			similarity: NAME_WEIGHT * similarity(product_name, &product.product_name)
+				+ (1.0 - NAME_WEIGHT) * similarity(vendor, &product.vendor),
+		})
+		.filter(|suggestion| suggestion.similarity >= MIN_SIMILARITY)
+		.collect();
+
+	suggestions.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+	suggestions.truncate(limit);
+	suggestions
+}
+
+/// Up to `limit` known names that `input` is probably the start or a respelling of,
+/// best first, so an existing spelling ("KUKA") can be picked instead of a variant
+/// ("kuka", "Kuka Robotics"). Nothing is suggested once `input` is a known name.
+pub fn complete(input: &str, known: &[String], limit: usize) -> Vec<String> {
+	let typed = normalize(input);
+	if typed.is_empty() || known.iter().any(|name| name == input.trim()) {
+		return Vec::new();
+	}
+
+	let mut matches: Vec<(bool, f64, &String)> = known
+		.iter()
+		.filter_map(|name| {
+			let normalized = normalize(name);
+			let prefix = normalized.starts_with(&typed) || typed.starts_with(&normalized);
+			let score = strsim::jaro_winkler(&typed, &normalized);
+			(prefix || score >= MIN_SIMILARITY).then_some((prefix, score, name))
+		})
+		.collect();
+	matches.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.total_cmp(&a.1)));
+	matches.into_iter().take(limit).map(|(_, _, name)| name.clone()).collect()
+}
+
+fn normalize(name: &str) -> String {
+	name.split(|c: char| !c.is_alphanumeric())
+		.filter(|word| !word.is_empty())
+		.map(str::to_lowercase)
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn known(id: i64, name: &str, vendor: &str) -> KnownProduct {
+		KnownProduct { product_id: id, product_name: name.to_string(), vendor: vendor.to_string() }
+	}
+
+	#[test]
+	fn test_suggest() {
+		let products = vec![
+			known(1, "ROS Humble", "Open Robotics"),
+			known(2, "ROS Noetic", "Open Robotics"),
+			known(3, "nginx", "F5"),
+		];
+
+		let suggestions = suggest("ros_humble", "open-robotics", &products, 3);
+		assert_eq!(suggestions[0].product_id, 1);
+		assert!((suggestions[0].similarity - 1.0).abs() < 1e-9);
+		assert!(suggestions.iter().all(|s| s.product_id != 3));
+
+		assert!(suggest("OpenSSL", "OpenSSL Project", &products, 3).is_empty());
+		assert_eq!(suggest("ROS", "Open Robotics", &products, 1).len(), 1);
+	}
+
+	#[test]
+	fn test_complete() {
+		let known = vec!["KUKA".to_string(), "Open Robotics".to_string(), "OSRF".to_string()];
+		assert_eq!(complete("ku", &known, 3), ["KUKA"]);
+		assert_eq!(complete("Kuka Robotics", &known, 3), ["KUKA"]);
+		assert_eq!(complete("open", &known, 3), ["Open Robotics"]);
+		assert!(complete("KUKA", &known, 3).is_empty());
+		assert!(complete("Fanuc", &known, 3).is_empty());
+		assert!(complete(" ", &known, 3).is_empty());
+	}
+}
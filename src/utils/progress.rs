@@ -0,0 +1,255 @@
+// src/utils/progress.rs
+
+//! Progress events for long-running imports and syncs.
+//!
+//! Workers publish through a [`ProgressReporter`]; the GUI watches the receiving end
+//! and draws a progress bar. A reporter without a receiver is a no-op, so headless
+//! callers can pass [`ProgressReporter::disabled`].
+//!
+//! The reporter also carries a [`CancellationToken`]; workers check it at batch
+//! boundaries, so a cancelled operation keeps everything committed up to then.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Snapshot of a running (or just finished) operation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+	pub operation: String,
+	pub processed: usize,
+	/// Completed share of the work, between 0.0 and 1.0
+	pub fraction: f32,
+	/// Estimated time left, once enough work is done to extrapolate
+	pub eta: Option<Duration>,
+	pub finished: bool,
+	/// Set on the final event of an operation that stopped on request
+	pub cancelled: bool,
+}
+
+impl Progress {
+	fn idle() -> Self {
+		Self {
+			operation: String::new(),
+			processed: 0,
+			fraction: 0.0,
+			eta: None,
+			finished: true,
+			cancelled: false,
+		}
+	}
+
+	/// One-line description such as "CSV import: 12000 records (40%, about 2m 10s left)"
+	pub fn summary(&self) -> String {
+		let mut text = format!(
+			"{}: {} records ({:.0}%",
+			self.operation,
+			self.processed,
+			self.fraction * 100.0
+		);
+		if let Some(eta) = self.eta {
+			let secs = eta.as_secs();
+			if secs >= 60 {
+				text.push_str(&format!(", about {}m {}s left", secs / 60, secs % 60));
+			} else {
+				text.push_str(&format!(", about {}s left", secs));
+			}
+		}
+		text.push(')');
+		text
+	}
+}
+
+pub type ProgressReceiver = watch::Receiver<Progress>;
+
+/// Create a reporter and the receiver that observes it
+pub fn channel() -> (ProgressReporter, ProgressReceiver) {
+	let (tx, rx) = watch::channel(Progress::idle());
+	let reporter = ProgressReporter {
+		sender: Some(Arc::new(tx)),
+		cancel: CancellationToken::default(),
+	};
+	(reporter, rx)
+}
+
+/// The operation was stopped on request before it completed
+#[derive(Debug, thiserror::Error)]
+#[error("Operation cancelled")]
+pub struct Cancelled;
+
+/// Shared flag asking the running operation to stop at its next batch boundary
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+	pub fn cancel(&self) {
+		self.0.store(true, Ordering::Relaxed);
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.0.load(Ordering::Relaxed)
+	}
+
+	fn reset(&self) {
+		self.0.store(false, Ordering::Relaxed);
+	}
+}
+
+/// Cheap-to-clone handle used by workers to publish progress
+#[derive(Debug, Clone, Default)]
+pub struct ProgressReporter {
+	sender: Option<Arc<watch::Sender<Progress>>>,
+	cancel: CancellationToken,
+}
+
+impl ProgressReporter {
+	/// A reporter that drops every event
+	pub fn disabled() -> Self {
+		Self::default()
+	}
+
+	/// Token that cancels whichever operation is running on this reporter
+	pub fn cancellation_token(&self) -> CancellationToken {
+		self.cancel.clone()
+	}
+
+	/// Begin tracking `operation`; the returned tracker publishes updates for it
+	pub fn start(&self, operation: &str) -> ProgressTracker {
+		let tracker = ProgressTracker {
+			reporter: self.clone(),
+			operation: operation.to_string(),
+			started: Instant::now(),
+			processed: Cell::new(0),
+			fraction: Cell::new(0.0),
+			completed: false,
+		};
+		tracker.update(0, 0.0);
+		tracker
+	}
+
+	fn publish(&self, progress: Progress) {
+		if let Some(sender) = &self.sender {
+			// send_replace succeeds even while no GUI is watching
+			sender.send_replace(progress);
+		}
+	}
+}
+
+/// Progress of one operation, with the start time needed for the ETA.
+///
+/// Dropping the tracker publishes the final event, also when the operation
+/// bailed out early or was cancelled, and clears a pending cancellation.
+#[derive(Debug)]
+pub struct ProgressTracker {
+	reporter: ProgressReporter,
+	operation: String,
+	started: Instant,
+	processed: Cell<usize>,
+	fraction: Cell<f32>,
+	completed: bool,
+}
+
+impl ProgressTracker {
+	pub fn update(&self, processed: usize, fraction: f32) {
+		let fraction = fraction.clamp(0.0, 1.0);
+		self.processed.set(processed);
+		self.fraction.set(fraction);
+		self.reporter.publish(Progress {
+			operation: self.operation.clone(),
+			processed,
+			fraction,
+			eta: estimate_remaining(self.started.elapsed(), fraction),
+			finished: false,
+			cancelled: false,
+		});
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.reporter.cancel.is_cancelled()
+	}
+
+	/// Fails with [`Cancelled`] once cancellation was requested
+	pub fn check_cancelled(&self) -> anyhow::Result<()> {
+		if self.is_cancelled() {
+			return Err(Cancelled.into());
+		}
+		Ok(())
+	}
+
+	pub fn finish(mut self, processed: usize) {
+		self.processed.set(processed);
+		self.fraction.set(1.0);
+		self.completed = true;
+	}
+}
+
+impl Drop for ProgressTracker {
+	fn drop(&mut self) {
+		self.reporter.publish(Progress {
+			operation: self.operation.clone(),
+			processed: self.processed.get(),
+			fraction: self.fraction.get(),
+			eta: None,
+			finished: true,
+			cancelled: !self.completed && self.is_cancelled(),
+		});
+		self.reporter.cancel.reset();
+	}
+}
+
+/// Linear extrapolation of the remaining time from the time spent so far
+fn estimate_remaining(elapsed: Duration, fraction: f32) -> Option<Duration> {
+	if fraction <= 0.01 || fraction >= 1.0 {
+		return None;
+	}
+	Some(elapsed.mul_f32((1.0 - fraction) / fraction))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_estimate_remaining() {
+		assert_eq!(estimate_remaining(Duration::from_secs(10), 0.0), None);
+		assert_eq!(estimate_remaining(Duration::from_secs(10), 1.0), None);
+		let eta = estimate_remaining(Duration::from_secs(10), 0.25).unwrap();
+		assert_eq!(eta.as_secs(), 30);
+	}
+
+	#[test]
+	fn test_reporter_publishes_to_receiver() {
+		let (reporter, rx) = channel();
+		let tracker = reporter.start("CSV import");
+		tracker.update(50, 0.5);
+		assert_eq!(rx.borrow().processed, 50);
+		assert!(!rx.borrow().finished);
+
+		tracker.finish(100);
+		let last = rx.borrow().clone();
+		assert!(last.finished);
+		assert_eq!(last.fraction, 1.0);
+
+		// A disabled reporter must not panic without a receiver
+		ProgressReporter::disabled().start("noop").finish(0);
+	}
+
+	#[test]
+	fn test_cancellation_ends_operation() {
+		let (reporter, rx) = channel();
+		let token = reporter.cancellation_token();
+
+		let tracker = reporter.start("NVD feed import");
+		assert!(tracker.check_cancelled().is_ok());
+		token.cancel();
+		assert!(tracker.check_cancelled().unwrap_err().is::<Cancelled>());
+		drop(tracker);
+
+		assert!(rx.borrow().finished);
+		assert!(rx.borrow().cancelled);
+		// The next operation starts uncancelled
+		assert!(!reporter.start("CSV import").is_cancelled());
+	}
+}
@@ -0,0 +1,394 @@
+// src/utils/robot_import.rs
+
+//! Bulk import of a robot inventory from CSV or JSON, so a fleet does not have to be
+//! entered robot by robot. A robot matching an existing one by name and manufacturer
+//! updates it instead of creating a duplicate. Rows that fail validation are reported
+//! with their row number and skipped; the valid rows are imported. Each import is an
+//! import run that can be reverted, deleting the robots it created and setting those
+//! it updated back.
+//!
+//! CSV files have a header row with the columns `name`, `manufacturer`, `model`,
+//! `software` (entries separated by `;`) and optionally `specifications`,
+//! `operational_note`, `criticality`, `firmware_version`, `os` and `ros_distro`. JSON files hold an array of objects with the
+//! same fields, `software` being an array of strings. Software entries are written as
+//! in the robot form, `vendor/product version` or `product version` for software by
+//! the robot's manufacturer.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use anyhow::{Context, Result};
+use csv::StringRecord;
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Deserialize;
+use tokio::task;
+use crate::db::connection::SqlitePool;
+use crate::models::import_run::{ImportReport, ImportRunStatus};
+use crate::models::interchange::SoftwareRef;
+use crate::models::robot::{Criticality, InventorySource};
+use crate::repositories::{access, audit_repo, import_run_repo};
+use crate::repositories::robot_repo::{mark_inventory_refreshed, refresh_risk_scores};
+use crate::repositories::software_repo::{refresh_robot_correlations, set_robot_software};
+
+/// One robot as listed in the file
+#[derive(Debug, Default, Deserialize)]
+struct RobotRecord {
+	#[serde(default)]
+	name: String,
+	#[serde(default)]
+	manufacturer: Option<String>,
+	#[serde(default)]
+	model: Option<String>,
+	#[serde(default)]
+	specifications: Option<String>,
+	#[serde(default)]
+	operational_note: Option<String>,
+	#[serde(default)]
+	criticality: Option<String>,
+	#[serde(default)]
+	firmware_version: Option<String>,
+	#[serde(default)]
+	os: Option<String>,
+	#[serde(default)]
+	ros_distro: Option<String>,
+	#[serde(default)]
+	software: Vec<String>,
+}
+
+/// A record that passed validation
+struct ValidRobot {
+	name: String,
+	manufacturer: Option<String>,
+	model: Option<String>,
+	specifications: Option<String>,
+	operational_note: Option<String>,
+	criticality: Option<Criticality>,
+	firmware_version: Option<String>,
+	os: Option<String>,
+	ros_distro: Option<String>,
+	software: Vec<String>,
+}
+
+/// A row that was skipped and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowError {
+	/// Line of a CSV file, counting the header, or position in a JSON array, from 1
+	pub row: usize,
+	pub message: String,
+}
+
+impl fmt::Display for RowError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Row {}: {}", self.row, self.message)
+	}
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RobotImportSummary {
+	pub created: usize,
+	/// Rows matching a robot already in the database
+	pub updated: usize,
+	pub errors: Vec<RowError>,
+}
+
+impl fmt::Display for RobotImportSummary {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} robots created, {} updated, {} rows skipped",
+			self.created,
+			self.updated,
+			self.errors.len()
+		)
+	}
+}
+
+/// Imports the robots listed in a CSV or JSON file. The import is recorded as an
+/// import run, so it can be reverted as a whole from the import history.
+pub async fn import_robots(path: PathBuf, pool: Arc<SqlitePool>) -> Result<RobotImportSummary> {
+	access::require_write_access()?;
+	task::spawn_blocking(move || -> Result<RobotImportSummary> {
+		let content = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+		let records = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+			serde_json::from_str::<Vec<RobotRecord>>(&content)
+				.with_context(|| format!("{:?} is not a JSON array of robots", path))?
+				.into_iter()
+				.enumerate()
+				.map(|(index, record)| (index + 1, record))
+				.collect()
+		} else {
+			read_csv(&content).with_context(|| format!("Failed to read robot CSV {:?}", path))?
+		};
+
+		let source = format!("Robot inventory {}", path.display());
+		let mut connection = pool.get().context("Failed to get database connection")?;
+		let run_id = import_run_repo::start(&connection, &source)?;
+		let mut summary = RobotImportSummary::default();
+		let result = import_records(&mut connection, run_id, &source, records, &mut summary);
+
+		// The rows left out are kept with the run, as for a vulnerability list
+		let mut report = ImportReport { imported: summary.created + summary.updated, ..Default::default() };
+		for error in &summary.errors {
+			report.skip(error.row, "", error.message.clone());
+		}
+		let status = if result.is_ok() { ImportRunStatus::Completed } else { ImportRunStatus::Failed };
+		let finished = import_run_repo::record_skipped(&mut connection, run_id, &report)
+			.and_then(|_| import_run_repo::finish(&connection, run_id, status));
+		result.and(finished)?;
+		info!("Imported robots from {:?}: {}", path, summary);
+		Ok(summary)
+	})
+		.await
+		.context("Failed to run robot import task")?
+}
+
+/// Creates or updates the robots of run `run_id` in one transaction, so a failure
+/// leaves the inventory as it was
+fn import_records(
+	connection: &mut Connection,
+	run_id: i64,
+	source: &str,
+	records: Vec<(usize, RobotRecord)>,
+	summary: &mut RobotImportSummary,
+) -> Result<()> {
+	let mut seen: HashMap<(String, String), usize> = HashMap::new();
+	let tx = connection.transaction().context("Failed to start database transaction")?;
+
+	for (row, record) in records {
+		let robot = match validate(record) {
+			Ok(robot) => robot,
+			Err(message) => {
+				summary.errors.push(RowError { row, message });
+				continue;
+			}
+		};
+		let key = (robot.name.to_lowercase(), robot.manufacturer.clone().unwrap_or_default().to_lowercase());
+		if let Some(first) = seen.insert(key, row) {
+			summary.errors.push(RowError { row, message: format!("{} is already listed in row {}", robot.name, first) });
+			continue;
+		}
+
+		let existing: Option<(i64, Option<String>)> = tx.query_row(
+			"SELECT robot_id, manufacturer FROM robots
+			 WHERE lower(trim(name)) = lower(?1) AND lower(COALESCE(manufacturer, '')) = lower(?2)
+				AND deleted_at IS NULL
+			 ORDER BY robot_id LIMIT 1",
+			params![robot.name, robot.manufacturer.as_deref().unwrap_or_default()],
+			|row| Ok((row.get(0)?, row.get(1)?)),
+		).optional()?;
+		// Software without a vendor is by the manufacturer as already spelled in the database
+		let manufacturer = existing
+			.as_ref()
+			.and_then(|(_, manufacturer)| manufacturer.clone())
+			.or_else(|| robot.manufacturer.clone())
+			.unwrap_or_default();
+		let software = match parse_software(&robot.software, &manufacturer) {
+			Ok(software) => software,
+			Err(message) => {
+				summary.errors.push(RowError { row, message });
+				continue;
+			}
+		};
+		let criticality = robot.criticality.map(|c| c.as_str());
+
+		let robot_id = match existing {
+			Some((id, _)) => {
+				import_run_repo::keep_previous_robot(&tx, run_id, id)?;
+				tx.execute(
+					"UPDATE robots SET model = COALESCE(?1, model), specifications = COALESCE(?2, specifications),
+					 operational_note = COALESCE(?3, operational_note), criticality = COALESCE(?4, criticality),
+					 firmware_version = COALESCE(?5, firmware_version), os = COALESCE(?6, os),
+					 ros_distro = COALESCE(?7, ros_distro), updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+					 WHERE robot_id = ?8",
+					params![
+						robot.model, robot.specifications, robot.operational_note, criticality,
+						robot.firmware_version, robot.os, robot.ros_distro, id,
+					],
+				)?;
+				summary.updated += 1;
+				id
+			}
+			None => {
+				tx.execute(
+					"INSERT INTO robots (name, manufacturer, model, specifications, operational_note, criticality,
+					 firmware_version, os, ros_distro, import_run_id)
+					 VALUES (?1, ?2, ?3, ?4, ?5, COALESCE(?6, 'Medium'), ?7, ?8, ?9, ?10)",
+					params![
+						robot.name, robot.manufacturer, robot.model, robot.specifications, robot.operational_note,
+						criticality, robot.firmware_version, robot.os, robot.ros_distro, run_id,
+					],
+				)?;
+				summary.created += 1;
+				tx.last_insert_rowid()
+			}
+		};
+		// A row without software leaves what is recorded for an existing robot alone
+		if !software.is_empty() {
+			set_robot_software(&tx, robot_id, &software)
+				.with_context(|| format!("Failed to save the software of {}", robot.name))?;
+			mark_inventory_refreshed(&tx, robot_id, InventorySource::Import)?;
+		} else if robot.ros_distro.is_some() {
+			refresh_robot_correlations(&tx, robot_id)?;
+		}
+	}
+
+	refresh_risk_scores(&tx)?;
+	import_run_repo::add_counts(&tx, run_id, summary.created, summary.updated)?;
+	audit_repo::record_import(&tx, source, &summary.to_string())?;
+	tx.commit().context("Failed to commit transaction")?;
+	Ok(())
+}
+
+/// Records of a CSV file with their line numbers
+fn read_csv(content: &str) -> Result<Vec<(usize, RobotRecord)>> {
+	let mut reader = csv::ReaderBuilder::new()
+		.trim(csv::Trim::All)
+		.flexible(true)
+		.from_reader(content.as_bytes());
+	let headers = reader.headers().context("Failed to read CSV headers")?.clone();
+	let column = |name: &str| headers.iter().position(|header| header.eq_ignore_ascii_case(name));
+	if column("name").is_none() {
+		anyhow::bail!("CSV has no name column");
+	}
+	let columns = [
+		"name", "manufacturer", "model", "specifications", "operational_note", "criticality", "software",
+		"firmware_version", "os", "ros_distro",
+	]
+		.map(column);
+
+	let mut records = Vec::new();
+	for (index, row) in reader.records().enumerate() {
+		let row: StringRecord = row.with_context(|| format!("Failed to read line {}", index + 2))?;
+		let field = |position: usize| {
+			columns[position]
+				.and_then(|column| row.get(column))
+				.map(str::to_string)
+				.filter(|value| !value.is_empty())
+		};
+		records.push((index + 2, RobotRecord {
+			name: field(0).unwrap_or_default(),
+			manufacturer: field(1),
+			model: field(2),
+			specifications: field(3),
+			operational_note: field(4),
+			criticality: field(5),
+			firmware_version: field(7),
+			os: field(8),
+			ros_distro: field(9),
+			software: field(6)
+				.map(|software| software.split(';').map(str::to_string).collect())
+				.unwrap_or_default(),
+		}));
+	}
+	Ok(records)
+}
+
+fn validate(record: RobotRecord) -> Result<ValidRobot, String> {
+	let text = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+	let name = record.name.trim().to_string();
+	if name.is_empty() {
+		return Err("name is missing".to_string());
+	}
+	let manufacturer = text(record.manufacturer);
+	let criticality = match text(record.criticality) {
+		Some(value) => Some(Criticality::from_label(&value).ok_or_else(|| {
+			format!("unknown criticality '{}', expected low, medium, high or critical", value)
+		})?),
+		None => None,
+	};
+	Ok(ValidRobot {
+		name,
+		manufacturer,
+		model: text(record.model),
+		specifications: text(record.specifications),
+		operational_note: text(record.operational_note),
+		criticality,
+		firmware_version: text(record.firmware_version),
+		os: text(record.os),
+		ros_distro: text(record.ros_distro),
+		software: record.software.into_iter().filter(|entry| !entry.trim().is_empty()).collect(),
+	})
+}
+
+fn parse_software(entries: &[String], manufacturer: &str) -> Result<Vec<SoftwareRef>, String> {
+	entries
+		.iter()
+		.map(|entry| {
+			SoftwareRef::parse(entry, manufacturer)
+				.ok_or_else(|| format!("software '{}' is not written as 'vendor/product version'", entry.trim()))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use crate::repositories::import_run_repo::ImportRunRepository;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_import_robots() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute_batch(
+			"INSERT INTO robots (robot_id, name, manufacturer, specifications) VALUES (1, 'Arm-01', 'KUKA', '6 axis');",
+		)?;
+
+		let path = dir.path().join("fleet.csv");
+		std::fs::write(
+			&path,
+			"Name,Manufacturer,Model,Software,Criticality\n\
+			 arm-01,kuka,KR 6,firmware 2.0;OSRF/ros-core 1.0,High\n\
+			 AGV-07,MiR,MiR250,firmware 3.1,\n\
+			 ,MiR,MiR250,,\n\
+			 AGV-08,MiR,,ros-core,\n\
+			 AGV-09,MiR,,,urgent\n\
+			 agv-07,MIR,,,\n",
+		)?;
+
+		let summary = import_robots(path, pool.clone()).await?;
+		assert_eq!((summary.created, summary.updated), (1, 1));
+		let rows: Vec<usize> = summary.errors.iter().map(|error| error.row).collect();
+		assert_eq!(rows, [4, 5, 6, 7]);
+		assert_eq!(summary.errors[3].message, "agv-07 is already listed in row 3");
+
+		let conn = pool.get()?;
+		let (model, specifications, criticality): (String, String, String) = conn.query_row(
+			"SELECT model, specifications, criticality FROM robots WHERE robot_id = 1",
+			[],
+			|row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+		)?;
+		assert_eq!((model.as_str(), specifications.as_str(), criticality.as_str()), ("KR 6", "6 axis", "High"));
+		let installed: i64 = conn.query_row("SELECT COUNT(*) FROM robot_software WHERE robot_id = 1", [], |row| row.get(0))?;
+		assert_eq!(installed, 2);
+
+		let path = dir.path().join("fleet.json");
+		std::fs::write(&path, r#"[{"name": "Cobot-1", "manufacturer": "UR", "software": ["polyscope 5.11"]}]"#)?;
+		let summary = import_robots(path, pool.clone()).await?;
+		assert_eq!((summary.created, summary.errors.len()), (1, 0));
+
+		// Reverting the CSV import deletes AGV-07 and sets Arm-01 back
+		let repo = ImportRunRepository::new(pool.clone());
+		let runs = repo.get_runs().await?;
+		assert_eq!(runs.iter().map(|run| (run.created, run.updated, run.skipped)).collect::<Vec<_>>(), [(1, 0, 0), (1, 1, 4)]);
+		let reverted = repo.revert(runs[1].run_id).await?;
+		assert_eq!((reverted.robots_removed, reverted.robots_restored), (1, 1));
+		let (model, criticality): (Option<String>, String) = conn.query_row(
+			"SELECT model, criticality FROM robots WHERE robot_id = 1",
+			[],
+			|row| Ok((row.get(0)?, row.get(1)?)),
+		)?;
+		assert_eq!((model, criticality.as_str()), (None, "Medium"));
+		let installed: i64 = conn.query_row("SELECT COUNT(*) FROM robot_software WHERE robot_id = 1", [], |row| row.get(0))?;
+		assert_eq!(installed, 0);
+		let names: Vec<String> = conn
+			.prepare("SELECT name FROM robots ORDER BY robot_id")?
+			.query_map([], |row| row.get(0))?
+			.collect::<rusqlite::Result<_>>()?;
+		assert_eq!(names, ["Arm-01", "Cobot-1"]);
+		Ok(())
+	}
+}
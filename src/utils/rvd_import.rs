@@ -0,0 +1,651 @@
+// src/utils/rvd_import.rs
+
+//! Importer for the advisories of the Alias Robotics Robot Vulnerability Database
+//! (github.com/aliasrobotics/RVD). Its tickets describe robot-specific flaws, many of
+//! which never reach the NVD, in a YAML schema (also exported as JSON):
+//!
+//! ```yaml
+//! id: 2105
+//! title: Unauthenticated access to the robot controller
+//! type: vulnerability
+//! description: ...
+//! cwe: CWE-306
+//! cve: None
+//! severity:
+//!   severity-description: critical
+//!   cvss-score: 9.8
+//!   cvss-vector: CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H
+//! links:
+//!   - https://...
+//! flaw:
+//!   date-reported: 2020-06-30
+//! exploitation:
+//!   description: ...
+//! mitigation:
+//!   description: ...
+//! ```
+//!
+//! Advisories with a CVE are stored under their CVE ID with `RVD#<id>` as an alias, all
+//! others as `RVD#<id>`.
+//! Plain bug reports carry no security impact and are skipped.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::fmt;
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use log::{info, warn};
+use serde_json::{Map, Number, Value};
+use tokio::task;
+use crate::db::connection::SqlitePool;
+use crate::models::reference::Reference;
+use crate::models::vulnerability::CvssVersion;
+use crate::models::weakness::normalize_cwe_id;
+use crate::repositories::{access, audit_repo};
+use crate::repositories::alias_repo::{self, canonical_id, link_alias};
+use crate::repositories::reference_repo::insert_references;
+use crate::repositories::weakness_repo::insert_weaknesses;
+use crate::utils::nvd_feed::title_case;
+
+/// Value of `vulnerabilities.source` for imported advisories
+pub const RVD_SOURCE: &str = "Alias Robotics RVD";
+
+const TICKET_URL: &str = "https://github.com/aliasrobotics/RVD/issues/";
+
+/// An RVD advisory reduced to the fields RVD stores
+#[derive(Debug, Clone, PartialEq)]
+pub struct RvdRecord {
+	/// CVE ID when the advisory has one, otherwise `RVD#<id>`
+	pub cve_id: String,
+	/// `RVD#<id>`, kept as an alias of the CVE
+	pub rvd_id: String,
+	pub description: Option<String>,
+	pub severity: String,
+	pub cvss_score: Option<f64>,
+	pub cvss_version: Option<CvssVersion>,
+	pub impact: Option<String>,
+	pub mitigation: Option<String>,
+	pub published_date: Option<NaiveDate>,
+	pub references: Vec<Reference>,
+	pub weaknesses: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RvdImportSummary {
+	pub files: usize,
+	pub advisories: usize,
+	pub inserted: usize,
+	/// Bug reports and entries without an ID
+	pub skipped: usize,
+}
+
+impl fmt::Display for RvdImportSummary {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} advisories from {} files, {} new, {} skipped",
+			self.advisories, self.files, self.inserted, self.skipped,
+		)
+	}
+}
+
+/// Maps one advisory; `None` for bug reports and entries without an ID
+pub fn record_from_advisory(advisory: &Value) -> Option<RvdRecord> {
+	if text(advisory.get("type")).is_some_and(|kind| kind.eq_ignore_ascii_case("bug")) {
+		return None;
+	}
+	let id = text(advisory.get("id"))?;
+	let rvd_id = format!("RVD#{}", id);
+	let cve_id = text(advisory.get("cve"))
+		.map(|cve| cve.to_uppercase())
+		.filter(|cve| cve.starts_with("CVE-"))
+		.unwrap_or_else(|| rvd_id.clone());
+
+	let severity = advisory.get("severity");
+	let cvss_score = number(severity.and_then(|s| s.get("cvss-score")));
+	let rating = text(severity.and_then(|s| s.get("severity-description")))
+		.map(|s| title_case(&s))
+		.or_else(|| cvss_score.or_else(|| number(severity.and_then(|s| s.get("rvss-score")))).map(score_band))
+		.unwrap_or_else(|| "Unknown".to_string());
+
+	let mut references: Vec<Reference> = list(advisory.get("links"))
+		.into_iter()
+		.filter(|link| link.starts_with("http"))
+		.map(|link| Reference::new(link, Some(RVD_SOURCE.to_string())))
+		.collect();
+	let ticket = text(advisory.pointer("/flaw/issue"))
+		.filter(|issue| issue.starts_with("http"))
+		.unwrap_or_else(|| format!("{}{}", TICKET_URL, id));
+	if !references.iter().any(|r| r.url == ticket) {
+		references.push(Reference::new(ticket, Some(RVD_SOURCE.to_string())));
+	}
+
+	Some(RvdRecord {
+		description: text(advisory.get("description")).or_else(|| text(advisory.get("title"))),
+		severity: rating,
+		cvss_version: cvss_score.and(
+			text(severity.and_then(|s| s.get("cvss-vector")))
+				.and_then(|vector| vector.strip_prefix("CVSS:").and_then(|v| v.split('/').next()).and_then(CvssVersion::from_db)),
+		),
+		cvss_score,
+		impact: text(advisory.pointer("/exploitation/description")),
+		mitigation: text(advisory.pointer("/mitigation/description")),
+		published_date: [advisory.pointer("/flaw/date-reported"), advisory.pointer("/flaw/date-detected")]
+			.into_iter()
+			.filter_map(text)
+			.find_map(|date| date.get(..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())),
+		references,
+		weaknesses: list(advisory.get("cwe")).iter().filter_map(|cwe| normalize_cwe_id(cwe)).collect(),
+		cve_id,
+		rvd_id,
+	})
+}
+
+/// Qualitative rating of a CVSS-style score
+fn score_band(score: f64) -> String {
+	match score {
+		s if s >= 9.0 => "Critical",
+		s if s >= 7.0 => "High",
+		s if s >= 4.0 => "Medium",
+		s if s > 0.0 => "Low",
+		_ => "Unknown",
+	}
+		.to_string()
+}
+
+/// A scalar as text, with RVD's `None` / `N/A` placeholders treated as missing
+fn text(value: Option<&Value>) -> Option<String> {
+	let text = match value? {
+		Value::String(s) => s.trim().to_string(),
+		Value::Number(n) => n.to_string(),
+		_ => return None,
+	};
+	(!text.is_empty() && !["none", "n/a", "null"].contains(&text.to_lowercase().as_str())).then_some(text)
+}
+
+fn number(value: Option<&Value>) -> Option<f64> {
+	match value? {
+		Value::Number(n) => n.as_f64(),
+		Value::String(s) => s.trim().parse().ok(),
+		_ => None,
+	}
+}
+
+/// A field that may hold one value or a list of them
+fn list(value: Option<&Value>) -> Vec<String> {
+	match value {
+		Some(Value::Array(items)) => items.iter().filter_map(|item| text(Some(item))).collect(),
+		other => text(other).into_iter().collect(),
+	}
+}
+
+/// Reads the advisories of one `.yml`, `.yaml` or `.json` file
+pub fn read_advisory_file(path: &Path) -> Result<Vec<Value>> {
+	let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+	let documents = if path.extension().is_some_and(|ext| ext == "json") {
+		vec![serde_json::from_str(&content).with_context(|| format!("Failed to parse RVD JSON {:?}", path))?]
+	} else {
+		parse_yaml(&content).with_context(|| format!("Failed to parse RVD YAML {:?}", path))?
+	};
+
+	Ok(documents
+		.into_iter()
+		.flat_map(|document| match document {
+			Value::Array(items) => items,
+			Value::Null => Vec::new(),
+			other => vec![other],
+		})
+		.collect())
+}
+
+/// Expands directories into the advisory files they contain, in name order
+fn collect_advisory_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+	let mut files = Vec::new();
+	for path in paths {
+		if path.is_dir() {
+			let mut entries = std::fs::read_dir(path)
+				.with_context(|| format!("Failed to read directory {:?}", path))?
+				.filter_map(|entry| entry.ok().map(|e| e.path()))
+				.filter(|p| p.extension().is_some_and(|ext| ext == "yml" || ext == "yaml" || ext == "json"))
+				.collect::<Vec<_>>();
+			entries.sort();
+			files.extend(entries);
+		} else {
+			files.push(path.clone());
+		}
+	}
+	Ok(files)
+}
+
+/// Imports RVD advisories from local files or directories.
+///
+/// New entries are tagged with [`RVD_SOURCE`]; known ones, such as CVEs already
+/// imported from the NVD, gain a longer description or fill in empty fields.
+pub async fn import_rvd_advisories(paths: Vec<PathBuf>, pool: Arc<SqlitePool>) -> Result<RvdImportSummary> {
+	task::spawn_blocking(move || -> Result<RvdImportSummary> {
+		access::require_write_access()?;
+		let mut summary = RvdImportSummary::default();
+		let mut records = Vec::new();
+
+		for path in collect_advisory_files(&paths)? {
+			let advisories = match read_advisory_file(&path) {
+				Ok(advisories) => advisories,
+				Err(e) => {
+					warn!("Skipping RVD file {:?}: {:#}", path, e);
+					continue;
+				}
+			};
+			summary.files += 1;
+			for advisory in &advisories {
+				match record_from_advisory(advisory) {
+					Some(record) => records.push(record),
+					None => summary.skipped += 1,
+				}
+			}
+		}
+
+		summary.advisories = records.len();
+		summary.inserted = upsert_records(&pool, &records)?;
+		info!("Imported RVD advisories: {}", summary);
+		Ok(summary)
+	})
+		.await
+		.context("Failed to run RVD import task")?
+}
+
+/// Writes all advisories in one transaction and returns how many were new
+fn upsert_records(pool: &Arc<SqlitePool>, records: &[RvdRecord]) -> Result<usize> {
+	let mut connection = pool.get().context("Failed to get a connection from the pool")?;
+	let transaction = connection.transaction().context("Failed to start database transaction")?;
+	let mut inserted = 0;
+
+	{
+		let mut stmt = transaction.prepare(
+			"INSERT INTO vulnerabilities (cve_id, description, severity, impact, mitigation, published_date, cvss_score, cvss_version, source)
+			 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+			 ON CONFLICT(cve_id) DO UPDATE SET
+				description = CASE WHEN length(COALESCE(excluded.description, '')) > length(COALESCE(vulnerabilities.description, ''))
+					THEN excluded.description ELSE vulnerabilities.description END,
+				severity = CASE WHEN UPPER(vulnerabilities.severity) = 'UNKNOWN'
+					THEN excluded.severity ELSE vulnerabilities.severity END,
+				impact = COALESCE(NULLIF(vulnerabilities.impact, ''), excluded.impact),
+				mitigation = COALESCE(NULLIF(vulnerabilities.mitigation, ''), excluded.mitigation),
+				published_date = COALESCE(vulnerabilities.published_date, excluded.published_date),
+				cvss_score = COALESCE(vulnerabilities.cvss_score, excluded.cvss_score),
+				cvss_version = CASE WHEN vulnerabilities.cvss_score IS NULL
+					THEN excluded.cvss_version ELSE vulnerabilities.cvss_version END",
+		)?;
+
+		for record in records {
+			// Stored under the CVE, or whichever entry already knows the RVD ID
+			let id = canonical_id(&transaction, &record.cve_id)?;
+			if alias_repo::resolve(&transaction, &id)?.is_none() && alias_repo::resolve(&transaction, &record.rvd_id)?.is_none() {
+				inserted += 1;
+			}
+			stmt.execute(rusqlite::params![
+				id,
+				record.description,
+				record.severity,
+				record.impact,
+				record.mitigation,
+				record.published_date.map(|d| d.to_string()),
+				record.cvss_score,
+				record.cvss_version.map(|v| v.as_str()),
+				RVD_SOURCE,
+			]).with_context(|| format!("Failed to import {}", id))?;
+			if id != record.rvd_id {
+				link_alias(&transaction, &id, &record.rvd_id, RVD_SOURCE)
+					.with_context(|| format!("Failed to merge {} into {}", record.rvd_id, id))?;
+			}
+			insert_references(&transaction, &id, &record.references)
+				.with_context(|| format!("Failed to import references of {}", id))?;
+			insert_weaknesses(&transaction, &id, &record.weaknesses)
+				.with_context(|| format!("Failed to import weaknesses of {}", id))?;
+		}
+	}
+
+	audit_repo::record_import(
+		&transaction,
+		"RVD advisories",
+		&format!("{} advisories, {} new", records.len(), inserted),
+	)?;
+	transaction.commit().context("Failed to commit transaction")?;
+	Ok(inserted)
+}
+
+/// Parses the YAML subset RVD tickets are written in: block mappings and sequences,
+/// `|` / `>` block scalars, quoted and plain scalars, flow lists and `---` separated
+/// documents. Markdown code fences around a ticket are ignored.
+pub fn parse_yaml(content: &str) -> Result<Vec<Value>> {
+	let mut documents = Vec::new();
+	let mut lines: Vec<String> = Vec::new();
+	for line in content.lines().chain(std::iter::once("---")) {
+		let trimmed = line.trim_end();
+		if trimmed.trim_start().starts_with("```") {
+			continue;
+		}
+		if trimmed == "---" || trimmed == "..." {
+			if lines.iter().any(|l| !is_blank(l)) {
+				let mut parser = YamlParser { lines: std::mem::take(&mut lines), index: 0 };
+				documents.push(parser.block(0)?);
+			}
+			lines.clear();
+		} else {
+			lines.push(trimmed.replace('\t', "  "));
+		}
+	}
+	Ok(documents)
+}
+
+fn is_blank(line: &str) -> bool {
+	let content = line.trim_start();
+	content.is_empty() || content.starts_with('#')
+}
+
+fn indent_of(line: &str) -> usize {
+	line.len() - line.trim_start().len()
+}
+
+/// `|` or `>` with optional chomping and indentation indicators
+fn is_block_indicator(value: &str) -> bool {
+	value.starts_with(['|', '>']) && value[1..].chars().all(|c| c == '-' || c == '+' || c.is_ascii_digit())
+}
+
+fn is_sequence_item(content: &str) -> bool {
+	content == "-" || content.starts_with("- ")
+}
+
+struct YamlParser {
+	lines: Vec<String>,
+	index: usize,
+}
+
+impl YamlParser {
+	/// Indent of the next non-blank line, skipping blank ones
+	fn peek(&mut self) -> Option<usize> {
+		while self.index < self.lines.len() && is_blank(&self.lines[self.index]) {
+			self.index += 1;
+		}
+		self.lines.get(self.index).map(|line| indent_of(line))
+	}
+
+	/// A node whose lines are indented at least `min_indent`
+	fn block(&mut self, min_indent: usize) -> Result<Value> {
+		match self.peek() {
+			Some(indent) if indent >= min_indent => {
+				if is_sequence_item(self.lines[self.index].trim_start()) {
+					self.sequence(indent)
+				} else {
+					self.mapping(indent)
+				}
+			}
+			_ => Ok(Value::Null),
+		}
+	}
+
+	fn sequence(&mut self, indent: usize) -> Result<Value> {
+		let mut items = Vec::new();
+		while self.peek() == Some(indent) && is_sequence_item(self.lines[self.index].trim_start()) {
+			let rest = self.lines[self.index].trim_start()[1..].trim_start().to_string();
+			if rest.is_empty() {
+				self.index += 1;
+				items.push(self.block(indent + 1)?);
+			} else if split_key(&rest).is_some() {
+				// `- key: value` opens a mapping indented past the dash
+				let item_indent = self.lines[self.index].len() - rest.len();
+				self.lines[self.index] = format!("{}{}", " ".repeat(item_indent), rest);
+				items.push(self.mapping(item_indent)?);
+			} else {
+				self.index += 1;
+				items.push(self.scalar(&rest, indent)?);
+			}
+		}
+		Ok(Value::Array(items))
+	}
+
+	fn mapping(&mut self, indent: usize) -> Result<Value> {
+		let mut map = Map::new();
+		while self.peek() == Some(indent) {
+			let line = self.lines[self.index].trim_start().to_string();
+			if is_sequence_item(&line) {
+				break;
+			}
+			let (key, value) = split_key(&line)
+				.ok_or_else(|| anyhow!("Line {}: expected `key: value`", self.index + 1))?;
+			self.index += 1;
+
+			let value = if value.is_empty() {
+				match self.peek() {
+					Some(next) if next > indent => self.block(next)?,
+					// Sequences may sit at the same indent as their key
+					Some(next) if next == indent && is_sequence_item(self.lines[self.index].trim_start()) => {
+						self.sequence(indent)?
+					}
+					_ => Value::Null,
+				}
+			} else if is_block_indicator(&value) {
+				self.block_scalar(indent, value.starts_with('>'))
+			} else {
+				self.scalar(&value, indent)?
+			};
+			map.insert(key, value);
+		}
+		Ok(Value::Object(map))
+	}
+
+	/// A `|` (literal) or `>` (folded) scalar on the lines indented past `indent`
+	fn block_scalar(&mut self, indent: usize, folded: bool) -> Value {
+		let mut block: Vec<&str> = Vec::new();
+		let mut block_indent = None;
+		while let Some(line) = self.lines.get(self.index) {
+			if line.trim().is_empty() {
+				block.push("");
+			} else if indent_of(line) > indent {
+				let cut = *block_indent.get_or_insert(indent_of(line));
+				block.push(line.get(cut..).unwrap_or(line.trim_start()));
+			} else {
+				break;
+			}
+			self.index += 1;
+		}
+		while block.last() == Some(&"") {
+			block.pop();
+		}
+
+		let text = if folded {
+			block
+				.split(|line| line.is_empty())
+				.map(|paragraph| paragraph.join(" "))
+				.collect::<Vec<_>>()
+				.join("\n")
+		} else {
+			block.join("\n")
+		};
+		Value::String(text)
+	}
+
+	/// A scalar, joining plain or open-quoted text continued on more indented lines
+	fn scalar(&mut self, value: &str, indent: usize) -> Result<Value> {
+		let mut text = value.to_string();
+		let open_quote = |t: &str| {
+			let quote = t.chars().next().filter(|c| *c == '"' || *c == '\'');
+			quote.is_some_and(|q| t.len() < 2 || !t.ends_with(q))
+		};
+		let quoted = text.starts_with(['"', '\'', '[', '{']);
+		while (open_quote(&text) || !quoted) && self.peek().is_some_and(|next| next > indent) {
+			let line = self.lines[self.index].trim();
+			if !open_quote(&text) && split_key(line).is_some() {
+				break;
+			}
+			text.push(' ');
+			text.push_str(line);
+			self.index += 1;
+		}
+		Ok(parse_scalar(&text))
+	}
+}
+
+/// Splits `key: value`, ignoring colons inside URLs and quoted keys
+fn split_key(line: &str) -> Option<(String, String)> {
+	if line.starts_with(['"', '\'']) {
+		let quote = line.chars().next()?;
+		let end = line[1..].find(quote)? + 1;
+		let rest = line[end + 1..].trim_start().strip_prefix(':')?;
+		return Some((line[1..end].to_string(), strip_comment(rest).to_string()));
+	}
+	let colon = line.find(": ").or_else(|| line.ends_with(':').then(|| line.len() - 1))?;
+	let key = &line[..colon];
+	if key.is_empty() || key.contains(' ') && key.contains(['[', '{', '"']) {
+		return None;
+	}
+	Some((key.trim().to_string(), strip_comment(&line[colon + 1..]).to_string()))
+}
+
+/// Drops a trailing ` # comment` outside quotes
+fn strip_comment(value: &str) -> &str {
+	let value = value.trim();
+	if value.starts_with(['"', '\'']) {
+		return value;
+	}
+	value.find(" #").map_or(value, |cut| value[..cut].trim_end())
+}
+
+fn parse_scalar(value: &str) -> Value {
+	let value = value.trim();
+	if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+		return Value::String(inner.replace("\\n", "\n").replace("\\\"", "\"").replace("\\\\", "\\"));
+	}
+	if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+		return Value::String(inner.replace("''", "'"));
+	}
+	if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+		return Value::Array(
+			inner.split(',').map(str::trim).filter(|item| !item.is_empty()).map(parse_scalar).collect(),
+		);
+	}
+	match value {
+		"" | "~" | "null" | "Null" | "NULL" | "{}" => return Value::Null,
+		"true" | "True" => return Value::Bool(true),
+		"false" | "False" => return Value::Bool(false),
+		_ => {}
+	}
+	if let Ok(int) = value.parse::<i64>() {
+		return Value::Number(int.into());
+	}
+	if let Some(float) = value.parse::<f64>().ok().and_then(Number::from_f64) {
+		return Value::Number(float);
+	}
+	Value::String(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	const TICKETS: &str = r#"```yaml
+id: 2105
+title: Unauthenticated access to the robot controller
+type: vulnerability
+description: >
+  The controller accepts motion commands
+  from any host on the network.
+
+  No credentials are required.
+cwe:
+- CWE-306
+- CWE-284
+cve: None
+keywords: [ROS, "robot controller"]
+severity:
+  rvss-score: 9.3
+  severity-description: critical # rated by Alias Robotics
+  cvss-score: 9.8
+  cvss-vector: CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H
+links:
+  - https://example.com/advisory
+flaw:
+  date-detected: 2020-06-01
+  date-reported: '2020-06-30 (12:00)'
+exploitation:
+  description: |
+    Send a move command
+    to port 30002.
+mitigation:
+  description: "Restrict the controller to a \"trusted\" network"
+```
+---
+id: 1
+type: bug
+title: Typo in the launch file
+---
+- id: 3
+  type: weakness
+  title: Hardcoded credentials
+  cve: cve-2019-19626
+  severity:
+    rvss-score: 7.5
+  flaw:
+    issue: https://github.com/aliasrobotics/RVD/issues/3
+"#;
+
+	#[test]
+	fn test_advisory_mapping() -> Result<()> {
+		let advisories: Vec<Value> = parse_yaml(TICKETS)?
+			.into_iter()
+			.flat_map(|d| match d { Value::Array(items) => items, other => vec![other] })
+			.collect();
+		assert_eq!(advisories.len(), 3);
+		assert_eq!(advisories[0]["keywords"], serde_json::json!(["ROS", "robot controller"]));
+
+		let record = record_from_advisory(&advisories[0]).unwrap();
+		assert_eq!(record.cve_id, "RVD#2105");
+		assert_eq!(
+			record.description.as_deref(),
+			Some("The controller accepts motion commands from any host on the network.\nNo credentials are required."),
+		);
+		assert_eq!((record.severity.as_str(), record.cvss_score, record.cvss_version), ("Critical", Some(9.8), Some(CvssVersion::V31)));
+		assert_eq!(record.impact.as_deref(), Some("Send a move command\nto port 30002."));
+		assert_eq!(record.mitigation.as_deref(), Some("Restrict the controller to a \"trusted\" network"));
+		assert_eq!(record.published_date, NaiveDate::from_ymd_opt(2020, 6, 30));
+		assert_eq!(record.weaknesses, ["CWE-306", "CWE-284"]);
+		let urls: Vec<&str> = record.references.iter().map(|r| r.url.as_str()).collect();
+		assert_eq!(urls, ["https://example.com/advisory", "https://github.com/aliasrobotics/RVD/issues/2105"]);
+
+		assert_eq!(record_from_advisory(&advisories[1]), None);
+
+		let record = record_from_advisory(&advisories[2]).unwrap();
+		assert_eq!((record.cve_id.as_str(), record.severity.as_str()), ("CVE-2019-19626", "High"));
+		assert_eq!(record.description.as_deref(), Some("Hardcoded credentials"));
+		assert_eq!(record.references.len(), 1);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_import_tags_source() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		pool.get()?.execute(
+			"INSERT INTO vulnerabilities (cve_id, description, severity) VALUES ('CVE-2019-19626', 'From the NVD', 'High')",
+			[],
+		)?;
+		let tickets = dir.path().join("rvd");
+		std::fs::create_dir(&tickets)?;
+		std::fs::write(tickets.join("tickets.yml"), TICKETS)?;
+		std::fs::write(tickets.join("README.md"), "not an advisory")?;
+
+		let summary = import_rvd_advisories(vec![tickets], pool.clone()).await?;
+		assert_eq!((summary.files, summary.advisories, summary.inserted, summary.skipped), (1, 2, 1, 1));
+
+		let sources: Vec<(String, Option<String>)> = pool.get()?
+			.prepare("SELECT cve_id, source FROM vulnerabilities ORDER BY cve_id")?
+			.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+			.collect::<rusqlite::Result<_>>()?;
+		assert_eq!(sources, [
+			("CVE-2019-19626".to_string(), None),
+			("RVD#2105".to_string(), Some(RVD_SOURCE.to_string())),
+		]);
+		Ok(())
+	}
+}
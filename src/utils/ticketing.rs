@@ -0,0 +1,228 @@
+// src/utils/ticketing.rs
+
+//! Raises remediation tickets for vulnerabilities in Jira or a generic REST tracker and
+//! syncs the tickets' statuses back into the triage status.
+
+use crate::db::connection::SqlitePool;
+use crate::models::ticket::{Ticket, TicketSettings, TicketSystem};
+use crate::models::vulnerability::Vulnerability;
+use crate::repositories::settings_repo::SettingsRepository;
+use crate::repositories::ticket_repo::TicketRepository;
+use crate::repositories::vulnerability_repo::VulnerabilityRepository;
+use crate::utils::http;
+use anyhow::{bail, Context, Result};
+use log::{info, warn};
+use reqwest::header::{ACCEPT, USER_AGENT};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Environment variable holding the tracker's API token
+pub const TICKET_TOKEN_VAR: &str = "RVD_TICKET_TOKEN";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Answer of a generic tracker to a created issue
+#[derive(Debug, Deserialize)]
+struct CreatedIssue {
+	key: String,
+	#[serde(default)]
+	url: Option<String>,
+	#[serde(default)]
+	status: Option<String>,
+}
+
+/// Answer of a generic tracker to an issue lookup
+#[derive(Debug, Deserialize)]
+struct IssueStatus {
+	status: String,
+}
+
+struct TicketClient {
+	client: reqwest::Client,
+	settings: TicketSettings,
+	token: Option<String>,
+}
+
+impl TicketClient {
+	fn new(settings: TicketSettings) -> Result<Self> {
+		let client = http::client_builder()?
+			.timeout(REQUEST_TIMEOUT)
+			.build()
+			.context("Failed to create HTTP client")?;
+		let token = std::env::var(TICKET_TOKEN_VAR).ok().filter(|token| !token.trim().is_empty());
+		Ok(Self { client, settings, token })
+	}
+
+	fn base_url(&self) -> &str {
+		self.settings.base_url.trim_end_matches('/')
+	}
+
+	fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+		let request = self.client
+			.request(method, url)
+			.header(USER_AGENT, "Vulnerability-Management-System/1.0")
+			.header(ACCEPT, "application/json");
+		match (&self.token, &self.settings.username) {
+			(Some(token), Some(username)) => request.basic_auth(username, Some(token.trim())),
+			(Some(token), None) => request.bearer_auth(token.trim()),
+			(None, _) => request,
+		}
+	}
+
+	async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+		let response = request.send().await
+			.with_context(|| format!("Ticketing system {} unreachable", self.settings.base_url))?;
+		let status = response.status();
+		if !status.is_success() {
+			let body = response.text().await.unwrap_or_default();
+			bail!("Ticketing system answered {}: {}", status, body.trim());
+		}
+		Ok(response)
+	}
+
+	/// Raise a ticket for a vulnerability and return its key, URL and initial status
+	async fn create(&self, vuln: &Vulnerability) -> Result<(String, Option<String>, Option<String>)> {
+		let summary = format!("Remediate {} ({})", vuln.cve_id, vuln.severity);
+		let description = ticket_description(vuln);
+		match self.settings.system {
+			TicketSystem::Jira => {
+				let body = json!({
+					"fields": {
+						"project": { "key": self.settings.project },
+						"summary": summary,
+						"description": description,
+						"issuetype": { "name": self.settings.issue_type },
+						"labels": ["rvd", vuln.cve_id.replace(' ', "-")],
+					}
+				});
+				let created: Value = self.send(self.request(reqwest::Method::POST, &format!("{}/rest/api/2/issue", self.base_url())).json(&body))
+					.await?
+					.json()
+					.await
+					.context("Jira did not answer with the created issue")?;
+				let key = created["key"].as_str().context("Jira did not return the issue key")?.to_string();
+				let url = format!("{}/browse/{}", self.base_url(), key);
+				let status = self.status(&key).await.ok();
+				Ok((key, Some(url), status))
+			}
+			TicketSystem::Generic => {
+				let body = json!({
+					"project": self.settings.project,
+					"type": self.settings.issue_type,
+					"title": summary,
+					"description": description,
+					"cve_id": vuln.cve_id,
+					"severity": vuln.severity,
+				});
+				let created: CreatedIssue = self.send(self.request(reqwest::Method::POST, &format!("{}/issues", self.base_url())).json(&body))
+					.await?
+					.json()
+					.await
+					.context("The ticketing system did not answer with the created issue")?;
+				Ok((created.key, created.url, created.status))
+			}
+		}
+	}
+
+	/// Current status of a ticket in the tracker
+	async fn status(&self, key: &str) -> Result<String> {
+		match self.settings.system {
+			TicketSystem::Jira => {
+				let issue: Value = self.send(self.request(reqwest::Method::GET, &format!("{}/rest/api/2/issue/{}?fields=status", self.base_url(), key)))
+					.await?
+					.json()
+					.await
+					.with_context(|| format!("Jira did not answer with issue {}", key))?;
+				issue["fields"]["status"]["name"]
+					.as_str()
+					.map(str::to_string)
+					.with_context(|| format!("Jira issue {} has no status", key))
+			}
+			TicketSystem::Generic => {
+				let issue: IssueStatus = self.send(self.request(reqwest::Method::GET, &format!("{}/issues/{}", self.base_url(), key)))
+					.await?
+					.json()
+					.await
+					.with_context(|| format!("The ticketing system did not answer with issue {}", key))?;
+				Ok(issue.status)
+			}
+		}
+	}
+}
+
+/// Body of a raised ticket: what the vulnerability is and where to read more
+fn ticket_description(vuln: &Vulnerability) -> String {
+	let mut lines = vec![format!("{} was found on the robot fleet and needs remediation.", vuln.cve_id)];
+	lines.push(format!(
+		"Severity: {}{}",
+		vuln.severity,
+		vuln.cvss_score.map(|score| format!(" (CVSS {:.1})", score)).unwrap_or_default()
+	));
+	if let Some(description) = vuln.description.as_deref().filter(|d| !d.trim().is_empty()) {
+		lines.push(String::new());
+		lines.push(description.trim().to_string());
+	}
+	if let Some(mitigation) = vuln.mitigation.as_deref().filter(|m| !m.trim().is_empty()) {
+		lines.push(String::new());
+		lines.push(format!("Mitigation: {}", mitigation.trim()));
+	}
+	if let Some(url) = vuln.nvd_url() {
+		lines.push(String::new());
+		lines.push(url);
+	}
+	lines.join("\n")
+}
+
+/// Whether tickets can be raised from the detail view
+pub async fn is_enabled(pool: Arc<SqlitePool>) -> Result<bool> {
+	Ok(SettingsRepository::new(pool).get_ticket_settings().await?.is_some())
+}
+
+/// Raise a ticket for a vulnerability in the configured tracker and link it to the
+/// vulnerability. A vulnerability that already has a ticket keeps it.
+pub async fn create_ticket(pool: Arc<SqlitePool>, vulnerability_id: i64) -> Result<Ticket> {
+	let settings = SettingsRepository::new(pool.clone())
+		.get_ticket_settings()
+		.await?
+		.context("Ticketing is not configured")?;
+	let repo = TicketRepository::new(pool.clone());
+	if let Some(ticket) = repo.get_ticket(vulnerability_id).await? {
+		bail!("{} was already raised for this vulnerability", ticket.key);
+	}
+	let vuln = VulnerabilityRepository::new(pool).get_vulnerability_by_id(vulnerability_id).await?;
+	let (key, url, status) = TicketClient::new(settings)?.create(&vuln).await?;
+	repo.save_ticket(vulnerability_id, &key, url, status).await?;
+	info!("Raised {} for {}", key, vuln.cve_id);
+	repo.get_ticket(vulnerability_id).await?.context("Failed to read the saved ticket")
+}
+
+/// Fetch the status of every linked ticket and update the triage status of the
+/// vulnerabilities whose ticket moved, returning how many changed. Tickets the tracker
+/// cannot answer for are skipped and logged.
+pub async fn sync(pool: Arc<SqlitePool>) -> Result<usize> {
+	let Some(settings) = SettingsRepository::new(pool.clone()).get_ticket_settings().await? else {
+		return Ok(0);
+	};
+	let repo = TicketRepository::new(pool);
+	let tickets = repo.get_tickets().await?;
+	if tickets.is_empty() {
+		return Ok(0);
+	}
+	let client = TicketClient::new(settings)?;
+	let mut changed = 0;
+	for ticket in tickets {
+		let status = match client.status(&ticket.key).await {
+			Ok(status) => status,
+			Err(e) => {
+				warn!("Failed to sync {}: {:#}", ticket.key, e);
+				continue;
+			}
+		};
+		let triage = client.settings.triage_status(&status);
+		if repo.apply_ticket_status(ticket.vulnerability_id, &status, triage).await? {
+			changed += 1;
+		}
+	}
+	Ok(changed)
+}
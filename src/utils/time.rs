@@ -0,0 +1,106 @@
+// src/utils/time.rs
+
+//! Timestamps are stored as UTC RFC 3339 strings (`2024-05-01T12:30:00Z`) and shown
+//! in the display time zone, which defaults to the system's local zone and can be
+//! overridden in the settings.
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, Utc};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+/// Format written by SQLite's `CURRENT_TIMESTAMP`, used before timestamps were RFC 3339
+const LEGACY_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Format used when showing a timestamp to the user
+const DISPLAY_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+static DISPLAY_TIME_ZONE: RwLock<DisplayTimeZone> = RwLock::new(DisplayTimeZone::Local);
+
+/// Time zone timestamps are displayed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplayTimeZone {
+	/// The system's local time zone
+	#[default]
+	Local,
+	Utc,
+	/// A fixed offset from UTC, e.g. `+02:00`
+	Fixed(FixedOffset),
+}
+
+impl DisplayTimeZone {
+	/// Parses a settings value: `local`, `utc` or an offset such as `+05:30`
+	pub fn from_setting(value: &str) -> Option<Self> {
+		match value.trim().to_ascii_lowercase().as_str() {
+			"local" => Some(DisplayTimeZone::Local),
+			"utc" | "z" => Some(DisplayTimeZone::Utc),
+			offset => FixedOffset::from_str(offset).ok().map(DisplayTimeZone::Fixed),
+		}
+	}
+}
+
+impl fmt::Display for DisplayTimeZone {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			DisplayTimeZone::Local => write!(f, "local"),
+			DisplayTimeZone::Utc => write!(f, "utc"),
+			DisplayTimeZone::Fixed(offset) => write!(f, "{}", offset),
+		}
+	}
+}
+
+pub fn set_display_time_zone(zone: DisplayTimeZone) {
+	*DISPLAY_TIME_ZONE.write().unwrap_or_else(|e| e.into_inner()) = zone;
+}
+
+pub fn display_time_zone() -> DisplayTimeZone {
+	*DISPLAY_TIME_ZONE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Reads a stored timestamp. Values without a zone predate RFC 3339 storage and
+/// came from SQLite's `CURRENT_TIMESTAMP`, which is UTC.
+pub fn parse_utc(value: &str) -> Option<DateTime<Utc>> {
+	DateTime::parse_from_rfc3339(value)
+		.map(|t| t.with_timezone(&Utc))
+		.or_else(|_| NaiveDateTime::parse_from_str(value, LEGACY_FORMAT).map(|t| t.and_utc()))
+		.ok()
+}
+
+/// Formats a timestamp in the configured display time zone
+pub fn format_local(timestamp: DateTime<Utc>) -> String {
+	format_in(timestamp, display_time_zone())
+}
+
+fn format_in(timestamp: DateTime<Utc>, zone: DisplayTimeZone) -> String {
+	match zone {
+		DisplayTimeZone::Local => timestamp.with_timezone(&Local).format(DISPLAY_FORMAT).to_string(),
+		DisplayTimeZone::Utc => format!("{} UTC", timestamp.format(DISPLAY_FORMAT)),
+		DisplayTimeZone::Fixed(offset) => timestamp.with_timezone(&offset).format(DISPLAY_FORMAT).to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::TimeZone;
+
+	#[test]
+	fn test_parse_utc() {
+		let expected = Utc.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap();
+		assert_eq!(parse_utc("2024-05-01T12:30:00Z"), Some(expected));
+		assert_eq!(parse_utc("2024-05-01T14:30:00+02:00"), Some(expected));
+		assert_eq!(parse_utc("2024-05-01 12:30:00"), Some(expected));
+		assert_eq!(parse_utc("yesterday"), None);
+	}
+
+	#[test]
+	fn test_display_time_zone() {
+		let timestamp = Utc.with_ymd_and_hms(2024, 5, 1, 23, 30, 0).unwrap();
+		let zone = DisplayTimeZone::from_setting("+05:30").unwrap();
+		assert_eq!(format_in(timestamp, zone), "2024-05-02 05:00");
+		assert_eq!(zone.to_string(), "+05:30");
+		assert_eq!(format_in(timestamp, DisplayTimeZone::Utc), "2024-05-01 23:30 UTC");
+		assert_eq!(DisplayTimeZone::from_setting("UTC"), Some(DisplayTimeZone::Utc));
+		assert_eq!(DisplayTimeZone::from_setting("Mars/Olympus"), None);
+	}
+}
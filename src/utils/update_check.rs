@@ -0,0 +1,121 @@
+// src/utils/update_check.rs
+
+//! Optional check for newer releases of RVD. It asks a release endpoint, by default the
+//! GitHub releases of the project, for the latest release and compares its version with
+//! the running one. Off by default, as many sites run air-gapped.
+
+use anyhow::{Context, Result};
+use reqwest::header::{ACCEPT, USER_AGENT};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::time::Duration;
+use crate::utils::http;
+
+/// Latest release of the project on GitHub
+pub const DEFAULT_RELEASE_ENDPOINT: &str = "https://api.github.com/repos/zenbuns/RVD/releases/latest";
+/// Version of the running build
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Whether to look for new releases on startup, and where
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateCheckSettings {
+	pub enabled: bool,
+	/// URL answering like the GitHub "latest release" API, e.g. an internal mirror
+	pub endpoint: String,
+}
+
+impl Default for UpdateCheckSettings {
+	fn default() -> Self {
+		Self { enabled: false, endpoint: DEFAULT_RELEASE_ENDPOINT.to_string() }
+	}
+}
+
+/// Fields of a GitHub release
+#[derive(Debug, Deserialize)]
+struct Release {
+	tag_name: String,
+	#[serde(default)]
+	name: Option<String>,
+	#[serde(default)]
+	body: Option<String>,
+	#[serde(default)]
+	html_url: Option<String>,
+}
+
+/// A release newer than the running build
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvailableUpdate {
+	pub version: String,
+	pub title: String,
+	/// Release notes as written on the release, usually Markdown
+	pub notes: String,
+	pub url: Option<String>,
+}
+
+/// Numeric parts of a version such as "v1.4.0" or "1.4.0-beta.2"; pre-release and build
+/// suffixes are ignored
+fn version_parts(version: &str) -> Option<Vec<u64>> {
+	let version = version.trim().trim_start_matches(['v', 'V']);
+	let core = version.split(['-', '+']).next()?;
+	core.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Whether `candidate` is a later version than `current`; unparsable versions never are
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+	let (Some(mut candidate), Some(mut current)) = (version_parts(candidate), version_parts(current)) else {
+		return false;
+	};
+	// "1.2" and "1.2.0" are the same version
+	let len = candidate.len().max(current.len());
+	candidate.resize(len, 0);
+	current.resize(len, 0);
+	candidate.cmp(&current) == Ordering::Greater
+}
+
+/// Asks the configured endpoint for the latest release. `None` when the check is off or
+/// the running build is current.
+pub async fn check(settings: &UpdateCheckSettings) -> Result<Option<AvailableUpdate>> {
+	if !settings.enabled {
+		return Ok(None);
+	}
+	let release: Release = http::client_builder()?
+		.timeout(REQUEST_TIMEOUT)
+		.build()?
+		.get(&settings.endpoint)
+		.header(USER_AGENT, format!("rvd/{}", CURRENT_VERSION))
+		.header(ACCEPT, "application/vnd.github+json")
+		.send()
+		.await
+		.and_then(|response| response.error_for_status())
+		.with_context(|| format!("Failed to reach the release endpoint {}", settings.endpoint))?
+		.json()
+		.await
+		.context("The release endpoint did not answer with a release")?;
+
+	if !is_newer(&release.tag_name, CURRENT_VERSION) {
+		return Ok(None);
+	}
+	let version = release.tag_name.trim_start_matches(['v', 'V']).to_string();
+	Ok(Some(AvailableUpdate {
+		title: release.name.filter(|name| !name.trim().is_empty()).unwrap_or_else(|| format!("RVD {}", version)),
+		version,
+		notes: release.body.unwrap_or_default(),
+		url: release.html_url,
+	}))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_is_newer() {
+		assert!(is_newer("v0.2.0", "0.1.0"));
+		assert!(is_newer("0.1.10", "0.1.9"));
+		assert!(is_newer("1.0.0-rc.1", "0.9.3"));
+		assert!(!is_newer("v0.1", "0.1.0"));
+		assert!(!is_newer("0.1.0", "0.1.1"));
+		assert!(!is_newer("nightly", "0.1.0"));
+	}
+}
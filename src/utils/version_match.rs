@@ -0,0 +1,74 @@
+// src/utils/version_match.rs
+
+//! Evaluates the affected version patterns stored with correlations, such as
+//! `>= 1.2, < 1.4.3` or `<= humble`, against a concrete version number.
+
+use std::cmp::Ordering;
+
+/// Compare two version numbers part by part, numerically where both parts are numbers
+/// ("1.10" > "1.9") and case-insensitively otherwise. Missing parts count as zero.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+	let a = parts(a);
+	let b = parts(b);
+	for i in 0..a.len().max(b.len()) {
+		let left = a.get(i).map_or("0", String::as_str);
+		let right = b.get(i).map_or("0", String::as_str);
+		let ordering = match (left.parse::<u64>(), right.parse::<u64>()) {
+			(Ok(l), Ok(r)) => l.cmp(&r),
+			_ => left.cmp(right),
+		};
+		if ordering != Ordering::Equal {
+			return ordering;
+		}
+	}
+	Ordering::Equal
+}
+
+/// Whether `version` satisfies every comma-separated clause of `pattern`. A clause is
+/// `*`, a bare version (exact match) or a version prefixed by `=`, `<`, `<=`, `>` or `>=`.
+pub fn matches(pattern: &str, version: &str) -> bool {
+	pattern.split(',').map(str::trim).all(|clause| {
+		if clause.is_empty() || clause == "*" {
+			return true;
+		}
+		let (accepted, bound): (&[Ordering], &str) = match clause {
+			c if c.starts_with(">=") => (&[Ordering::Greater, Ordering::Equal], &c[2..]),
+			c if c.starts_with("<=") => (&[Ordering::Less, Ordering::Equal], &c[2..]),
+			c if c.starts_with("==") => (&[Ordering::Equal], &c[2..]),
+			c if c.starts_with('>') => (&[Ordering::Greater], &c[1..]),
+			c if c.starts_with('<') => (&[Ordering::Less], &c[1..]),
+			c if c.starts_with('=') => (&[Ordering::Equal], &c[1..]),
+			c => (&[Ordering::Equal], c),
+		};
+		accepted.contains(&compare_versions(version, bound.trim()))
+	})
+}
+
+fn parts(version: &str) -> Vec<String> {
+	version
+		.trim()
+		.trim_start_matches(['v', 'V'])
+		.split(['.', '-', '_', '+'])
+		.filter(|part| !part.is_empty())
+		.map(str::to_lowercase)
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_matches() {
+		assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+		assert_eq!(compare_versions("v2.0", "2.0.0"), Ordering::Equal);
+
+		assert!(matches(">= 1.2, < 1.4.3", "1.4.2"));
+		assert!(!matches(">= 1.2, < 1.4.3", "1.4.3"));
+		assert!(matches("<= humble", "foxy"));
+		assert!(!matches("<= humble", "iron"));
+		assert!(matches("2.0", "2.0.0"));
+		assert!(matches("*", "anything"));
+		assert!(!matches("= 2.0", "2.1"));
+	}
+}
@@ -0,0 +1,336 @@
+// src/utils/watch_folder.rs
+
+//! Folder watched for files to import automatically. A file dropped into it is
+//! recognised by its extension and content and imported with the matching importer:
+//! vulnerability, robot or EPSS CSV, NVD feeds, RVD advisories, the KEV catalog,
+//! robot inventories and RVD interchange documents (the software inventory or SBOM
+//! of a fleet). It is then moved to `archive/`, or to `failed/` when it could not be
+//! imported, and the outcome is appended to `ingest.log` in the folder.
+
+use crate::db::connection::SqlitePool;
+use crate::models::csv_mapping::CsvMapping;
+use crate::models::interchange::{InterchangeDocument, INTERCHANGE_FORMAT};
+use crate::repositories::access;
+use crate::repositories::interchange_repo::InterchangeRepository;
+use crate::repositories::settings_repo::SettingsRepository;
+use crate::utils::csv_importer::{import_vulnerabilities_from_csv, read_headers};
+use crate::utils::epss::import_epss_scores;
+use crate::utils::kev::import_kev_catalog;
+use crate::utils::nvd_feed::import_nvd_feeds;
+use crate::utils::progress::ProgressReporter;
+use crate::utils::robot_import::import_robots;
+use crate::utils::rvd_import::import_rvd_advisories;
+use crate::utils::alerts;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::{info, warn};
+use notify::{RecursiveMode, Watcher};
+use serde_json::Value;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+pub const ARCHIVE_DIR: &str = "archive";
+pub const FAILED_DIR: &str = "failed";
+pub const LOG_FILE: &str = "ingest.log";
+/// Quiet time after the last change in the folder before its files are imported, so
+/// files still being copied in are complete
+const SETTLE: Duration = Duration::from_secs(2);
+/// Lines of a CSV file searched for its header
+const HEADER_SCAN_LINES: usize = 20;
+
+/// What a dropped file holds, and so which importer takes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchedFile {
+	/// Vulnerability list in the MITRE CVE list layout or one saved as a CSV preset
+	VulnerabilityCsv,
+	/// Robot inventory as CSV or a JSON array of robots
+	Robots,
+	EpssScores,
+	KevCatalog,
+	NvdFeed,
+	RvdAdvisories,
+	/// RVD interchange document with the robots, software and correlations of a fleet
+	Interchange,
+}
+
+impl fmt::Display for WatchedFile {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			WatchedFile::VulnerabilityCsv => "vulnerability CSV",
+			WatchedFile::Robots => "robot inventory",
+			WatchedFile::EpssScores => "EPSS scores",
+			WatchedFile::KevCatalog => "KEV catalog",
+			WatchedFile::NvdFeed => "NVD feed",
+			WatchedFile::RvdAdvisories => "RVD advisories",
+			WatchedFile::Interchange => "interchange document",
+		})
+	}
+}
+
+/// One file taken from the folder
+#[derive(Debug, Clone)]
+pub struct IngestedFile {
+	/// Where the file was moved to
+	pub path: PathBuf,
+	/// What was imported, or why nothing was
+	pub outcome: Result<String, String>,
+}
+
+fn extension(path: &Path) -> String {
+	path.extension().map(|ext| ext.to_string_lossy().to_ascii_lowercase()).unwrap_or_default()
+}
+
+/// Whether the file has an extension some importer reads; others are left in place
+fn is_importable(path: &Path) -> bool {
+	let hidden = path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'));
+	!hidden && matches!(extension(path).as_str(), "csv" | "json" | "gz" | "yml" | "yaml")
+}
+
+fn classify_csv(path: &Path) -> Result<WatchedFile> {
+	let file = fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+	for line in BufReader::new(file).lines().take(HEADER_SCAN_LINES) {
+		let line = line.with_context(|| format!("Failed to read {:?}", path))?.to_ascii_lowercase();
+		let columns: Vec<&str> = line.split(',').map(|column| column.trim().trim_matches('"')).collect();
+		if columns.contains(&"epss") {
+			return Ok(WatchedFile::EpssScores);
+		}
+		if columns.contains(&"name") && (columns.contains(&"manufacturer") || columns.contains(&"model")) {
+			return Ok(WatchedFile::Robots);
+		}
+	}
+	Ok(WatchedFile::VulnerabilityCsv)
+}
+
+fn classify_json(path: &Path) -> Result<WatchedFile> {
+	let content = fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+	let document: Value = serde_json::from_str(&content).with_context(|| format!("{:?} is not valid JSON", path))?;
+	let first_vulnerability = document.get("vulnerabilities").and_then(|items| items.get(0));
+	Ok(match &document {
+		Value::Object(object) if object.get("format").and_then(Value::as_str) == Some(INTERCHANGE_FORMAT) => {
+			WatchedFile::Interchange
+		}
+		Value::Object(object) if object.contains_key("bomFormat") || object.contains_key("spdxVersion") => {
+			bail!("CycloneDX and SPDX documents are not imported; export the inventory as an RVD interchange document")
+		}
+		_ if first_vulnerability.is_some_and(|item| item.get("cveID").is_some()) => WatchedFile::KevCatalog,
+		_ if first_vulnerability.is_some_and(|item| item.get("cve").is_some()) => WatchedFile::NvdFeed,
+		Value::Array(items) if items.first().is_some_and(|item| item.get("name").is_some()) => WatchedFile::Robots,
+		Value::Array(_) | Value::Object(_) => WatchedFile::RvdAdvisories,
+		_ => bail!("{:?} holds no document RVD imports", path),
+	})
+}
+
+/// Recognises what the file holds from its extension and, where that is ambiguous,
+/// its content
+pub fn classify(path: &Path) -> Result<WatchedFile> {
+	let name = path.file_name().map(|name| name.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+	match extension(path).as_str() {
+		"csv" => classify_csv(path),
+		"json" => classify_json(path),
+		"yml" | "yaml" => Ok(WatchedFile::RvdAdvisories),
+		"gz" if name.ends_with(".json.gz") => Ok(WatchedFile::NvdFeed),
+		"gz" if name.ends_with(".csv.gz") => Ok(WatchedFile::EpssScores),
+		_ => bail!("No importer reads {:?}", path),
+	}
+}
+
+/// The first saved CSV preset whose columns are all in the file's header, or else
+/// the MITRE layout
+async fn csv_mapping_for(pool: &Arc<SqlitePool>, path: &Path) -> Result<CsvMapping> {
+	let headers = read_headers(path).unwrap_or_default();
+	let has_column = |name: &str| headers.iter().any(|header| header.eq_ignore_ascii_case(name.trim()));
+	Ok(SettingsRepository::new(pool.clone())
+		.list_csv_presets()
+		.await?
+		.into_iter()
+		.map(|(_, mapping)| mapping)
+		.find(|mapping| mapping.headers().into_iter().all(has_column))
+		.unwrap_or_default())
+}
+
+/// Imports one file with the importer for its content. Returns a summary of what
+/// was imported.
+pub async fn import_file(pool: Arc<SqlitePool>, path: &Path, progress: ProgressReporter) -> Result<String> {
+	let kind = classify(path)?;
+	let summary = match kind {
+		WatchedFile::VulnerabilityCsv => {
+			import_vulnerabilities_from_csv(
+				path.to_string_lossy().into_owned(),
+				pool.clone(),
+				csv_mapping_for(&pool, path).await?,
+				progress,
+			).await?.to_string()
+		}
+		WatchedFile::Robots => import_robots(path.to_path_buf(), pool).await?.to_string(),
+		WatchedFile::EpssScores => {
+			let summary = import_epss_scores(path.to_path_buf(), pool).await?;
+			format!("{} scores, {} matched", summary.listed, summary.matched)
+		}
+		WatchedFile::KevCatalog => {
+			let summary = import_kev_catalog(path.to_path_buf(), pool).await?;
+			format!("{} listed CVEs, {} matched", summary.listed, summary.matched)
+		}
+		WatchedFile::NvdFeed => {
+			let summary = import_nvd_feeds(vec![path.to_path_buf()], pool, progress).await?;
+			if summary.files == 0 {
+				bail!("{:?} is not a valid NVD feed", path);
+			}
+			format!("{} records, {} new vulnerabilities", summary.records, summary.inserted)
+		}
+		WatchedFile::RvdAdvisories => import_rvd_advisories(vec![path.to_path_buf()], pool).await?.to_string(),
+		WatchedFile::Interchange => {
+			let json = fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+			let document: InterchangeDocument = serde_json::from_str(&json)
+				.with_context(|| format!("{:?} is not a valid interchange document", path))?;
+			let summary = InterchangeRepository::new(pool).import(document).await?;
+			format!(
+				"{} robots, {} software versions, {} correlations, {} assessments",
+				summary.robots_created + summary.robots_updated,
+				summary.software_versions,
+				summary.correlations,
+				summary.assessments
+			)
+		}
+	};
+	Ok(format!("{}: {}", kind, summary))
+}
+
+/// Moves a processed file into `subdir` of the folder, prefixed with the time it was
+/// processed so files dropped again under the same name are kept apart
+fn move_into(dir: &Path, subdir: &str, path: &Path, now: DateTime<Utc>) -> Result<PathBuf> {
+	let name = path.file_name().with_context(|| format!("{:?} is not a file", path))?.to_string_lossy();
+	let target_dir = dir.join(subdir);
+	fs::create_dir_all(&target_dir).with_context(|| format!("Failed to create {:?}", target_dir))?;
+	let target = target_dir.join(format!("{}-{}", now.format("%Y%m%dT%H%M%SZ"), name));
+	fs::rename(path, &target).with_context(|| format!("Failed to move {:?} to {:?}", path, target))?;
+	Ok(target)
+}
+
+fn append_log(dir: &Path, line: &str) -> Result<()> {
+	let path = dir.join(LOG_FILE);
+	let mut log = OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(&path)
+		.with_context(|| format!("Failed to open {:?}", path))?;
+	writeln!(log, "{}", line).with_context(|| format!("Failed to write {:?}", path))
+}
+
+/// The importable files in the folder, in name order
+fn pending_files(dir: &Path) -> Result<Vec<PathBuf>> {
+	let mut files = Vec::new();
+	for entry in fs::read_dir(dir).with_context(|| format!("Failed to list {:?}", dir))? {
+		let entry = entry?;
+		if entry.file_type()?.is_file() && is_importable(&entry.path()) {
+			files.push(entry.path());
+		}
+	}
+	files.sort();
+	Ok(files)
+}
+
+/// Imports every file waiting in the folder, one at a time, moving each to the archive
+/// or to the failed files and logging the outcome
+pub async fn ingest_pending(pool: Arc<SqlitePool>, dir: &Path, progress: ProgressReporter) -> Result<Vec<IngestedFile>> {
+	// Otherwise every file would fail and be moved aside
+	access::require_write_access()?;
+	let mut ingested = Vec::new();
+	for path in pending_files(dir)? {
+		let outcome = import_file(pool.clone(), &path, progress.clone()).await.map_err(|e| format!("{:#}", e));
+		let now = Utc::now();
+		let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+		let (subdir, line) = match &outcome {
+			Ok(summary) => {
+				info!("Imported {} from the watch folder: {}", name, summary);
+				(ARCHIVE_DIR, format!("{} imported {}: {}", now.to_rfc3339_opts(SecondsFormat::Secs, true), name, summary))
+			}
+			Err(e) => {
+				warn!("Failed to import {} from the watch folder: {}", name, e);
+				(FAILED_DIR, format!("{} failed {}: {}", now.to_rfc3339_opts(SecondsFormat::Secs, true), name, e))
+			}
+		};
+		let moved = move_into(dir, subdir, &path, now)?;
+		if let Err(e) = append_log(dir, &line) {
+			warn!("Failed to log the import of {}: {:#}", name, e);
+		}
+		ingested.push(IngestedFile { path: moved, outcome });
+	}
+
+	if ingested.iter().any(|file| file.outcome.is_ok()) {
+		// A finished operation has the GUI show the imported data
+		progress.start("Watch folder import").finish(ingested.len());
+		if let Err(e) = alerts::dispatch(pool, false).await {
+			warn!("Failed to send email alerts: {}", e);
+		}
+	}
+	Ok(ingested)
+}
+
+/// Imports the files already in the folder, then each file dropped into it, until
+/// the returned future is dropped
+pub async fn watch(pool: Arc<SqlitePool>, dir: PathBuf, progress: ProgressReporter) -> Result<()> {
+	fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+	let (tx, mut rx) = mpsc::unbounded_channel();
+	let mut watcher = notify::recommended_watcher(move |event| {
+		let _ = tx.send(event);
+	})
+		.context("Failed to start the file watcher")?;
+	watcher
+		.watch(&dir, RecursiveMode::NonRecursive)
+		.with_context(|| format!("Failed to watch {:?}", dir))?;
+	info!("Watching {:?} for files to import", dir);
+
+	loop {
+		ingest_pending(pool.clone(), &dir, progress.clone()).await?;
+
+		match rx.recv().await {
+			Some(Ok(_)) => {}
+			Some(Err(e)) => warn!("Watch folder event error: {}", e),
+			None => bail!("The file watcher stopped"),
+		}
+		// Wait for the folder to settle, as a copy raises many events
+		while let Ok(event) = timeout(SETTLE, rx.recv()).await {
+			if event.is_none() {
+				bail!("The file watcher stopped");
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::connection;
+	use tempfile::tempdir;
+
+	#[tokio::test]
+	async fn test_ingest_pending() -> Result<()> {
+		let dir = tempdir()?;
+		let pool = Arc::new(connection::establish_pool_with_path(dir.path().join("test.db"))?);
+		let watched = dir.path().join("watched");
+		fs::create_dir(&watched)?;
+		fs::write(watched.join("fleet.csv"), "name,manufacturer\narm-01,ACME\n")?;
+		fs::write(watched.join("sbom.json"), r#"{"bomFormat": "CycloneDX", "components": []}"#)?;
+		fs::write(watched.join("notes.txt"), "not imported")?;
+
+		assert_eq!(classify(&watched.join("fleet.csv"))?, WatchedFile::Robots);
+		let ingested = ingest_pending(pool, &watched, ProgressReporter::disabled()).await?;
+		assert_eq!(ingested.len(), 2);
+		assert_eq!(ingested[0].outcome.as_deref(), Ok("robot inventory: 1 robots created, 0 updated, 0 rows skipped"));
+		assert!(ingested[0].path.starts_with(watched.join(ARCHIVE_DIR)));
+		assert!(ingested[1].outcome.is_err());
+		assert!(ingested[1].path.starts_with(watched.join(FAILED_DIR)));
+
+		// Files nothing imports stay where they are
+		assert_eq!(pending_files(&watched)?, Vec::<PathBuf>::new());
+		assert!(watched.join("notes.txt").exists());
+		assert_eq!(fs::read_to_string(watched.join(LOG_FILE))?.lines().count(), 2);
+		Ok(())
+	}
+}
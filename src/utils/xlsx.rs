@@ -0,0 +1,294 @@
+// src/utils/xlsx.rs
+
+//! Minimal reader for the cell values of an Excel workbook (.xlsx), enough to import
+//! vulnerability lists kept in spreadsheets. Formulas are read as their cached results
+//! and formatting is ignored, so dates come back as serial numbers; see [`serial_date`].
+
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDate};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// A non-empty row of a worksheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheetRow {
+	/// One-based row number as shown in Excel
+	pub number: usize,
+	/// Cell values from column A on, empty for blank cells
+	pub cells: Vec<String>,
+}
+
+/// Reads the rows of the sheet named `sheet`, or of the first sheet when `None`
+pub fn read_sheet(path: &Path, sheet: Option<&str>) -> Result<Vec<SheetRow>> {
+	let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+	let mut archive = ZipArchive::new(file).context("Not an .xlsx workbook")?;
+
+	let sheets = workbook_sheets(&mut archive)?;
+	let (_, part) = match sheet {
+		Some(name) => sheets
+			.iter()
+			.find(|(sheet_name, _)| sheet_name.eq_ignore_ascii_case(name))
+			.with_context(|| format!(
+				"Workbook has no sheet named '{}'; its sheets are {}",
+				name,
+				sheets.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(", ")
+			))?,
+		None => sheets.first().context("Workbook has no sheets")?,
+	};
+	let shared_strings = shared_strings(&mut archive)?;
+	let reader = xml_reader(&mut archive, part)?.context("Worksheet part is missing from the workbook")?;
+	read_rows(reader, &shared_strings)
+}
+
+/// Date of an Excel serial date number, as stored for cells formatted as dates
+pub fn serial_date(value: &str) -> Option<NaiveDate> {
+	let serial = value.trim().parse::<f64>().ok()?;
+	// 1 is 1900-01-01; 2958465 is 9999-12-31
+	if !(1.0..2958466.0).contains(&serial) {
+		return None;
+	}
+	// Counting from 1899-12-30 absorbs Excel's phantom 1900-02-29 for dates after it
+	NaiveDate::from_ymd_opt(1899, 12, 30)?.checked_add_signed(Duration::days(serial.trunc() as i64))
+}
+
+type XmlReader<'a> = Reader<BufReader<zip::read::ZipFile<'a>>>;
+
+fn xml_reader<'a, R: Read + Seek>(archive: &'a mut ZipArchive<R>, name: &str) -> Result<Option<XmlReader<'a>>> {
+	match archive.by_name(name) {
+		Ok(part) => Ok(Some(Reader::from_reader(BufReader::new(part)))),
+		Err(zip::result::ZipError::FileNotFound) => Ok(None),
+		Err(e) => Err(e).with_context(|| format!("Failed to read {} from the workbook", name)),
+	}
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>> {
+	Ok(match element.try_get_attribute(name)? {
+		Some(attribute) => Some(attribute.normalized_value(XmlVersion::Implicit1_0)?.into_owned()),
+		None => None,
+	})
+}
+
+/// Appends text content, including entity and character references, to `out`
+fn push_text(event: &Event, out: &mut String) -> Result<()> {
+	match event {
+		Event::Text(text) => out.push_str(&text.xml_content(XmlVersion::Implicit1_0)?),
+		Event::CData(data) => out.push_str(&data.decode()?),
+		Event::GeneralRef(reference) => {
+			if let Some(c) = reference.resolve_char_ref()? {
+				out.push(c);
+			} else if let Some(value) = quick_xml::escape::resolve_predefined_entity(&reference.decode()?) {
+				out.push_str(value);
+			}
+		}
+		_ => {}
+	}
+	Ok(())
+}
+
+/// Names of the sheets in workbook order with the zip entry holding each
+fn workbook_sheets<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<Vec<(String, String)>> {
+	let mut targets = Vec::new();
+	let mut buf = Vec::new();
+	if let Some(mut reader) = xml_reader(archive, "xl/_rels/workbook.xml.rels")? {
+		loop {
+			match reader.read_event_into(&mut buf)? {
+				Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Relationship" => {
+					if let (Some(id), Some(target)) = (attribute(&e, "Id")?, attribute(&e, "Target")?) {
+						let part = match target.strip_prefix('/') {
+							Some(absolute) => absolute.to_string(),
+							None => format!("xl/{}", target),
+						};
+						targets.push((id, part));
+					}
+				}
+				Event::Eof => break,
+				_ => {}
+			}
+			buf.clear();
+		}
+	}
+
+	let mut sheets = Vec::new();
+	let mut reader = xml_reader(archive, "xl/workbook.xml")?.context("Not an .xlsx workbook")?;
+	loop {
+		match reader.read_event_into(&mut buf)? {
+			Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"sheet" => {
+				let name = attribute(&e, "name")?.unwrap_or_default();
+				let id = attribute(&e, "r:id")?;
+				let part = id
+					.and_then(|id| targets.iter().find(|(target_id, _)| *target_id == id))
+					.map(|(_, part)| part.clone())
+					.unwrap_or_else(|| format!("xl/worksheets/sheet{}.xml", sheets.len() + 1));
+				sheets.push((name, part));
+			}
+			Event::Eof => break,
+			_ => {}
+		}
+		buf.clear();
+	}
+	Ok(sheets)
+}
+
+/// Strings that cells of type `s` refer to by index
+fn shared_strings<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<Vec<String>> {
+	let Some(mut reader) = xml_reader(archive, "xl/sharedStrings.xml")? else {
+		return Ok(Vec::new());
+	};
+	let mut strings = Vec::new();
+	let mut current = String::new();
+	let mut in_text = false;
+	// Phonetic hints for East Asian text repeat the string in another script
+	let mut in_phonetic = false;
+	let mut buf = Vec::new();
+	loop {
+		let event = reader.read_event_into(&mut buf)?;
+		match &event {
+			Event::Start(e) => match e.local_name().as_ref() {
+				b"si" => current.clear(),
+				b"t" => in_text = !in_phonetic,
+				b"rPh" => in_phonetic = true,
+				_ => {}
+			},
+			Event::Empty(e) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+			Event::End(e) => match e.local_name().as_ref() {
+				b"si" => strings.push(std::mem::take(&mut current)),
+				b"t" => in_text = false,
+				b"rPh" => in_phonetic = false,
+				_ => {}
+			},
+			Event::Eof => break,
+			event if in_text => push_text(event, &mut current)?,
+			_ => {}
+		}
+		buf.clear();
+	}
+	Ok(strings)
+}
+
+/// Zero-based column of a cell reference such as "AB12"
+fn column_index(reference: &str) -> Option<usize> {
+	let letters: Vec<u8> = reference.bytes().take_while(u8::is_ascii_alphabetic).collect();
+	if letters.is_empty() {
+		return None;
+	}
+	let number = letters
+		.iter()
+		.fold(0usize, |number, letter| number * 26 + usize::from(letter.to_ascii_uppercase() - b'A') + 1);
+	Some(number - 1)
+}
+
+fn read_rows<R: std::io::BufRead>(mut reader: Reader<R>, shared_strings: &[String]) -> Result<Vec<SheetRow>> {
+	let mut rows = Vec::new();
+	let mut row: Option<SheetRow> = None;
+	let mut cell_type = String::new();
+	let mut column = 0;
+	let mut value = String::new();
+	let mut in_value = false;
+	let mut buf = Vec::new();
+
+	let finish_cell = |row: &mut Option<SheetRow>, cell_type: &str, column: usize, value: &mut String| -> Result<()> {
+		let value = std::mem::take(value);
+		let value = match cell_type {
+			"s" if !value.trim().is_empty() => {
+				let index: usize = value.trim().parse().context("Invalid shared string index")?;
+				shared_strings.get(index).cloned().unwrap_or_default()
+			}
+			"b" => if value.trim() == "1" { "TRUE" } else { "FALSE" }.to_string(),
+			_ => value,
+		};
+		if let Some(row) = row {
+			if row.cells.len() <= column {
+				row.cells.resize(column + 1, String::new());
+			}
+			row.cells[column] = value;
+		}
+		Ok(())
+	};
+
+	loop {
+		let event = reader.read_event_into(&mut buf)?;
+		match &event {
+			Event::Start(e) if e.local_name().as_ref() == b"row" => {
+				let number = attribute(e, "r")?
+					.and_then(|r| r.parse().ok())
+					.unwrap_or_else(|| rows.last().map_or(1, |last: &SheetRow| last.number + 1));
+				row = Some(SheetRow { number, cells: Vec::new() });
+			}
+			Event::End(e) if e.local_name().as_ref() == b"row" => {
+				if let Some(row) = row.take().filter(|row| row.cells.iter().any(|cell| !cell.trim().is_empty())) {
+					rows.push(row);
+				}
+			}
+			Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"c" => {
+				let next = row.as_ref().map_or(0, |row| row.cells.len());
+				column = attribute(e, "r")?.as_deref().and_then(column_index).unwrap_or(next);
+				cell_type = attribute(e, "t")?.unwrap_or_default();
+				value.clear();
+			}
+			Event::End(e) if e.local_name().as_ref() == b"c" => {
+				finish_cell(&mut row, &cell_type, column, &mut value)?;
+			}
+			// <v> holds the value, <t> the text of an inline string
+			Event::Start(e) if matches!(e.local_name().as_ref(), b"v" | b"t") => in_value = true,
+			Event::End(e) if matches!(e.local_name().as_ref(), b"v" | b"t") => in_value = false,
+			Event::Eof => break,
+			event if in_value => push_text(event, &mut value)?,
+			_ => {}
+		}
+		buf.clear();
+	}
+	Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
+	use tempfile::tempdir;
+
+	#[test]
+	fn test_read_sheet() -> Result<()> {
+		let dir = tempdir()?;
+		let path = dir.path().join("findings.xlsx");
+		let mut workbook = Workbook::new();
+		workbook.add_worksheet().set_name("Notes")?.write_string(0, 0, "not this one")?;
+		let sheet = workbook.add_worksheet().set_name("Findings")?;
+		sheet.write_string(1, 0, "CVE")?;
+		sheet.write_string(1, 2, "Published")?;
+		sheet.write_string(2, 0, "CVE-2024-0001")?;
+		sheet.write_string(2, 1, "Buffer overflow in <parser> & \"loader\"")?;
+		let date = ExcelDateTime::from_ymd(2024, 3, 5)?;
+		sheet.write_datetime_with_format(2, 2, &date, &Format::new().set_num_format("yyyy-mm-dd"))?;
+		sheet.write_number(3, 3, 7.5)?;
+		sheet.write_boolean(3, 4, true)?;
+		workbook.save(&path)?;
+
+		let rows = read_sheet(&path, Some("findings"))?;
+		assert_eq!(rows, vec![
+			SheetRow { number: 2, cells: vec!["CVE".into(), String::new(), "Published".into()] },
+			SheetRow {
+				number: 3,
+				cells: vec!["CVE-2024-0001".into(), "Buffer overflow in <parser> & \"loader\"".into(), "45356".into()],
+			},
+			SheetRow { number: 4, cells: vec![String::new(), String::new(), String::new(), "7.5".into(), "TRUE".into()] },
+		]);
+		assert_eq!(serial_date(&rows[1].cells[2]), NaiveDate::from_ymd_opt(2024, 3, 5));
+		assert_eq!(read_sheet(&path, None)?[0].cells, vec!["not this one".to_string()]);
+		assert!(read_sheet(&path, Some("Missing")).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn test_column_index() {
+		assert_eq!(column_index("A1"), Some(0));
+		assert_eq!(column_index("Z9"), Some(25));
+		assert_eq!(column_index("AB12"), Some(27));
+		assert_eq!(column_index("12"), None);
+		assert_eq!(serial_date("CVE"), None);
+		assert_eq!(serial_date("60"), NaiveDate::from_ymd_opt(1900, 2, 28));
+	}
+}